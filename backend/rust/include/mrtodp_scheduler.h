@@ -0,0 +1,620 @@
+/* Generated by cbindgen from backend/rust/src/ffi*.rs; do not edit. */
+
+#ifndef MRTODP_SCHEDULER_H
+#define MRTODP_SCHEDULER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+enum FfiStatus {
+  FfiStatus_Ok = 0,
+  FfiStatus_InvalidArgument = -1,
+  FfiStatus_NotFound = -2,
+  FfiStatus_Rejected = -3,
+  FfiStatus_Unavailable = -4,
+  FfiStatus_Internal = -5,
+};
+typedef int32_t FfiStatus;
+
+enum MrtodpErrorCode {
+  MrtodpErrorCode_Ok = 0,
+  MrtodpErrorCode_InvalidArgument = 1,
+  MrtodpErrorCode_InvalidJson = 2,
+  MrtodpErrorCode_DuplicateTask = 3,
+  MrtodpErrorCode_UnknownTask = 4,
+  MrtodpErrorCode_UnknownRobot = 5,
+  MrtodpErrorCode_UnknownMission = 6,
+  MrtodpErrorCode_UnknownTemplate = 7,
+  MrtodpErrorCode_CapabilityMismatch = 8,
+  MrtodpErrorCode_Rejected = 9,
+  MrtodpErrorCode_RuntimeFailure = 10,
+  MrtodpErrorCode_Internal = 11,
+  MrtodpErrorCode_UnknownToken = 12,
+};
+typedef int32_t MrtodpErrorCode;
+
+typedef struct MrtodpScheduler MrtodpScheduler;
+
+typedef struct MrtodpResult {
+  MrtodpErrorCode code;
+  char *payload;
+} MrtodpResult;
+
+char *init_ffi(uint32_t worker_threads);
+
+char *init_with_status_ffi(uint32_t worker_threads, int32_t *status);
+
+char *init_tracing_ffi(const char *level, bool json_output);
+
+char *init_tracing_with_status_ffi(const char *level, bool json_output, int32_t *status);
+
+char *shutdown_ffi(void);
+
+char *shutdown_with_status_ffi(int32_t *status);
+
+char *shutdown_scheduler_ffi(uint64_t grace_period_ms);
+
+char *shutdown_scheduler_with_status_ffi(uint64_t grace_period_ms, int32_t *status);
+
+char *start_buffered_ffi(uint32_t capacity, const char *spill_path, uint32_t spill_capacity);
+
+char *start_buffered_with_status_ffi(uint32_t capacity,
+                                     const char *spill_path,
+                                     uint32_t spill_capacity,
+                                     int32_t *status);
+
+char *get_buffered_status_ffi(const char *token);
+
+char *get_buffered_status_with_status_ffi(const char *token, int32_t *status);
+
+char *register_robot_ffi(const char *robot_id, const char *capabilities_json);
+
+char *register_robot_with_status_ffi(const char *robot_id,
+                                     const char *capabilities_json,
+                                     int32_t *status);
+
+char *update_robot_capabilities_ffi(const char *robot_id,
+                                    const char *add_json,
+                                    const char *remove_json);
+
+char *update_robot_capabilities_with_status_ffi(const char *robot_id,
+                                                const char *add_json,
+                                                const char *remove_json,
+                                                int32_t *status);
+
+char *drain_robot_ffi(const char *robot_id);
+
+char *drain_robot_with_status_ffi(const char *robot_id, int32_t *status);
+
+char *deregister_robot_ffi(const char *robot_id);
+
+char *deregister_robot_with_status_ffi(const char *robot_id, int32_t *status);
+
+char *set_robot_slots_ffi(const char *robot_id, uint32_t slots);
+
+char *set_robot_slots_with_status_ffi(const char *robot_id, uint32_t slots, int32_t *status);
+
+char *set_robot_model_ffi(const char *robot_id, const char *model, const char *firmware);
+
+char *set_robot_model_with_status_ffi(const char *robot_id,
+                                      const char *model,
+                                      const char *firmware,
+                                      int32_t *status);
+
+char *report_emergency_stop_ffi(const char *robot_id, const char *detail);
+
+char *report_emergency_stop_with_status_ffi(const char *robot_id,
+                                            const char *detail,
+                                            int32_t *status);
+
+char *report_battery_ffi(const char *robot_id, double level);
+
+char *report_battery_with_status_ffi(const char *robot_id, double level, int32_t *status);
+
+char *set_robot_zones_ffi(const char *robot_id, const char *zones_json);
+
+char *set_robot_zones_with_status_ffi(const char *robot_id,
+                                      const char *zones_json,
+                                      int32_t *status);
+
+char *reset_breaker_ffi(const char *robot_id);
+
+char *reset_breaker_with_status_ffi(const char *robot_id, int32_t *status);
+
+char *report_robot_location_ffi(const char *robot_id, const char *location_json);
+
+char *report_robot_location_with_status_ffi(const char *robot_id,
+                                            const char *location_json,
+                                            int32_t *status);
+
+char *submit_bid_ffi(uint32_t task_id, const char *robot_id, double cost, uint64_t eta_ms);
+
+char *submit_bid_with_status_ffi(uint32_t task_id,
+                                 const char *robot_id,
+                                 double cost,
+                                 uint64_t eta_ms,
+                                 int32_t *status);
+
+char *schedule_task_ffi(const char *task_json);
+
+char *schedule_task_with_status_ffi(const char *task_json, int32_t *status);
+
+char *register_template_ffi(const char *template_json);
+
+char *register_template_with_status_ffi(const char *template_json, int32_t *status);
+
+char *register_payload_schema_ffi(const char *task_type, const char *schema_json);
+
+char *register_payload_schema_with_status_ffi(const char *task_type,
+                                              const char *schema_json,
+                                              int32_t *status);
+
+char *set_submitter_limits_ffi(const char *kind, const char *name, const char *limits_json);
+
+char *set_submitter_limits_with_status_ffi(const char *kind,
+                                           const char *name,
+                                           const char *limits_json,
+                                           int32_t *status);
+
+char *instantiate_template_ffi(const char *template_id, const char *params_json);
+
+char *instantiate_template_with_status_ffi(const char *template_id,
+                                           const char *params_json,
+                                           int32_t *status);
+
+char *get_task_status_ffi(uint32_t task_id);
+
+char *get_task_status_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *watch_open_ffi(const char *filter_json);
+
+char *watch_open_with_status_ffi(const char *filter_json, int32_t *status);
+
+char *watch_poll_ffi(uint64_t watch_id, uint32_t max_events);
+
+char *watch_poll_with_status_ffi(uint64_t watch_id, uint32_t max_events, int32_t *status);
+
+char *watch_close_ffi(uint64_t watch_id);
+
+char *watch_close_with_status_ffi(uint64_t watch_id, int32_t *status);
+
+char *hold_task_ffi(uint32_t task_id);
+
+char *hold_task_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *release_task_ffi(uint32_t task_id);
+
+char *release_task_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *cancel_task_ffi(uint32_t task_id);
+
+char *cancel_task_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *complete_task_ffi(uint32_t task_id, uint64_t expected_version);
+
+char *complete_task_with_status_ffi(uint32_t task_id, uint64_t expected_version, int32_t *status);
+
+char *create_mission_ffi(const char *mission_json);
+
+char *create_mission_with_status_ffi(const char *mission_json, int32_t *status);
+
+char *get_mission_status_ffi(const char *mission_id);
+
+char *get_mission_status_with_status_ffi(const char *mission_id, int32_t *status);
+
+char *cancel_mission_ffi(const char *mission_id);
+
+char *cancel_mission_with_status_ffi(const char *mission_id, int32_t *status);
+
+char *pin_task_ffi(uint32_t task_id, const char *robot_id);
+
+char *pin_task_with_status_ffi(uint32_t task_id, const char *robot_id, int32_t *status);
+
+char *hand_off_task_ffi(uint32_t task_id, const char *request_json);
+
+char *hand_off_task_with_status_ffi(uint32_t task_id, const char *request_json, int32_t *status);
+
+char *set_policy_ffi(const char *policy_name);
+
+char *set_policy_with_status_ffi(const char *policy_name, int32_t *status);
+
+char *shadow_policy_ffi(const char *policy_name, uint64_t duration_ms);
+
+char *shadow_policy_with_status_ffi(const char *policy_name, uint64_t duration_ms, int32_t *status);
+
+char *get_shadow_report_ffi(void);
+
+char *get_shadow_report_with_status_ffi(int32_t *status);
+
+char *get_queue_wait_stats_ffi(void);
+
+char *get_queue_wait_stats_with_status_ffi(int32_t *status);
+
+char *get_queue_saturation_ffi(void);
+
+char *get_queue_saturation_with_status_ffi(int32_t *status);
+
+char *get_phase_latency_ffi(void);
+
+char *get_phase_latency_with_status_ffi(int32_t *status);
+
+char *get_quota_usage_ffi(const char *namespace_);
+
+char *get_quota_usage_with_status_ffi(const char *namespace_, int32_t *status);
+
+char *get_compatible_robots_ffi(const char *task_type);
+
+char *get_compatible_robots_with_status_ffi(const char *task_type, int32_t *status);
+
+char *get_mutex_group_status_ffi(const char *group);
+
+char *get_mutex_group_status_with_status_ffi(const char *group, int32_t *status);
+
+char *get_resource_status_ffi(const char *resource);
+
+char *get_resource_status_with_status_ffi(const char *resource, int32_t *status);
+
+char *get_slo_status_ffi(void);
+
+char *get_slo_status_with_status_ffi(int32_t *status);
+
+char *get_stats_ffi(uint64_t window_ms);
+
+char *get_stats_with_status_ffi(uint64_t window_ms, int32_t *status);
+
+char *get_fleet_status_ffi(uint64_t window_ms);
+
+char *get_fleet_status_with_status_ffi(uint64_t window_ms, int32_t *status);
+
+char *get_robot_profiles_ffi(void);
+
+char *get_robot_profiles_with_status_ffi(int32_t *status);
+
+char *get_breaker_status_ffi(void);
+
+char *get_breaker_status_with_status_ffi(int32_t *status);
+
+char *get_dead_letters_ffi(void);
+
+char *get_dead_letters_with_status_ffi(int32_t *status);
+
+char *get_dead_letter_ffi(uint32_t task_id);
+
+char *get_dead_letter_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *redrive_dead_letter_ffi(uint32_t task_id);
+
+char *redrive_dead_letter_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *purge_dead_letter_ffi(uint32_t task_id);
+
+char *purge_dead_letter_with_status_ffi(uint32_t task_id, int32_t *status);
+
+char *get_audit_log_ffi(const char *query_json);
+
+char *get_audit_log_with_status_ffi(const char *query_json, int32_t *status);
+
+char *list_tasks_ffi(const char *filter_json);
+
+char *list_tasks_with_status_ffi(const char *filter_json, int32_t *status);
+
+char *list_task_history_ffi(const char *filter_json, uint32_t limit, uint64_t cursor);
+
+char *list_task_history_with_status_ffi(const char *filter_json,
+                                        uint32_t limit,
+                                        uint64_t cursor,
+                                        int32_t *status);
+
+void free_string_ffi(char *s);
+
+struct MrtodpResult mrtodp_init(uint32_t worker_threads);
+
+struct MrtodpResult mrtodp_init_tracing(const char *level, bool json_output);
+
+struct MrtodpResult mrtodp_shutdown(void);
+
+struct MrtodpResult mrtodp_shutdown_scheduler(uint64_t grace_period_ms);
+
+struct MrtodpResult mrtodp_start_buffered(uint32_t capacity,
+                                          const char *spill_path,
+                                          uint32_t spill_capacity);
+
+struct MrtodpResult mrtodp_get_buffered_status(const char *token);
+
+struct MrtodpResult mrtodp_register_robot(const char *robot_id, const char *capabilities_json);
+
+struct MrtodpResult mrtodp_update_robot_capabilities(const char *robot_id,
+                                                     const char *add_json,
+                                                     const char *remove_json);
+
+struct MrtodpResult mrtodp_drain_robot(const char *robot_id);
+
+struct MrtodpResult mrtodp_deregister_robot(const char *robot_id);
+
+struct MrtodpResult mrtodp_set_robot_slots(const char *robot_id, uint32_t slots);
+
+struct MrtodpResult mrtodp_set_robot_model(const char *robot_id,
+                                           const char *model,
+                                           const char *firmware);
+
+struct MrtodpResult mrtodp_report_emergency_stop(const char *robot_id, const char *detail);
+
+struct MrtodpResult mrtodp_report_battery(const char *robot_id, double level);
+
+struct MrtodpResult mrtodp_set_robot_zones(const char *robot_id, const char *zones_json);
+
+struct MrtodpResult mrtodp_reset_breaker(const char *robot_id);
+
+struct MrtodpResult mrtodp_report_robot_location(const char *robot_id, const char *location_json);
+
+struct MrtodpResult mrtodp_submit_bid(uint32_t task_id,
+                                      const char *robot_id,
+                                      double cost,
+                                      uint64_t eta_ms);
+
+struct MrtodpResult mrtodp_schedule_task(const char *task_json);
+
+struct MrtodpResult mrtodp_register_template(const char *template_json);
+
+struct MrtodpResult mrtodp_register_payload_schema(const char *task_type, const char *schema_json);
+
+struct MrtodpResult mrtodp_set_submitter_limits(const char *kind,
+                                                const char *name,
+                                                const char *limits_json);
+
+struct MrtodpResult mrtodp_instantiate_template(const char *template_id, const char *params_json);
+
+struct MrtodpResult mrtodp_get_task_status(uint32_t task_id);
+
+struct MrtodpResult mrtodp_watch_open(const char *filter_json);
+
+struct MrtodpResult mrtodp_watch_poll(uint64_t watch_id, uint32_t max_events);
+
+struct MrtodpResult mrtodp_watch_close(uint64_t watch_id);
+
+struct MrtodpResult mrtodp_hold_task(uint32_t task_id);
+
+struct MrtodpResult mrtodp_release_task(uint32_t task_id);
+
+struct MrtodpResult mrtodp_cancel_task(uint32_t task_id);
+
+struct MrtodpResult mrtodp_complete_task(uint32_t task_id, uint64_t expected_version);
+
+struct MrtodpResult mrtodp_create_mission(const char *mission_json);
+
+struct MrtodpResult mrtodp_get_mission_status(const char *mission_id);
+
+struct MrtodpResult mrtodp_cancel_mission(const char *mission_id);
+
+struct MrtodpResult mrtodp_pin_task(uint32_t task_id, const char *robot_id);
+
+struct MrtodpResult mrtodp_hand_off_task(uint32_t task_id, const char *request_json);
+
+struct MrtodpResult mrtodp_set_policy(const char *policy_name);
+
+struct MrtodpResult mrtodp_shadow_policy(const char *policy_name, uint64_t duration_ms);
+
+struct MrtodpResult mrtodp_get_shadow_report(void);
+
+struct MrtodpResult mrtodp_get_queue_saturation(void);
+
+struct MrtodpResult mrtodp_get_queue_wait_stats(void);
+
+struct MrtodpResult mrtodp_get_phase_latency(void);
+
+struct MrtodpResult mrtodp_get_quota_usage(const char *namespace_);
+
+struct MrtodpResult mrtodp_get_compatible_robots(const char *task_type);
+
+struct MrtodpResult mrtodp_get_mutex_group_status(const char *group);
+
+struct MrtodpResult mrtodp_get_resource_status(const char *resource);
+
+struct MrtodpResult mrtodp_get_slo_status(void);
+
+struct MrtodpResult mrtodp_get_stats(uint64_t window_ms);
+
+struct MrtodpResult mrtodp_get_robot_profiles(void);
+
+struct MrtodpResult mrtodp_get_breaker_status(void);
+
+struct MrtodpResult mrtodp_get_dead_letters(void);
+
+struct MrtodpResult mrtodp_get_dead_letter(uint32_t task_id);
+
+struct MrtodpResult mrtodp_redrive_dead_letter(uint32_t task_id);
+
+struct MrtodpResult mrtodp_purge_dead_letter(uint32_t task_id);
+
+struct MrtodpResult mrtodp_get_audit_log(const char *query_json);
+
+struct MrtodpResult mrtodp_get_fleet_status(uint64_t window_ms);
+
+struct MrtodpResult mrtodp_list_tasks(const char *filter_json);
+
+struct MrtodpResult mrtodp_list_task_history(const char *filter_json,
+                                             uint32_t limit,
+                                             uint64_t cursor);
+
+struct MrtodpScheduler *mrtodp_scheduler_new(uint32_t worker_threads);
+
+void mrtodp_scheduler_free(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_register_robot(struct MrtodpScheduler *scheduler,
+                                                    const char *robot_id,
+                                                    const char *capabilities_json);
+
+struct MrtodpResult mrtodp_scheduler_update_robot_capabilities(struct MrtodpScheduler *scheduler,
+                                                               const char *robot_id,
+                                                               const char *add_json,
+                                                               const char *remove_json);
+
+struct MrtodpResult mrtodp_scheduler_drain_robot(struct MrtodpScheduler *scheduler,
+                                                 const char *robot_id);
+
+struct MrtodpResult mrtodp_scheduler_deregister_robot(struct MrtodpScheduler *scheduler,
+                                                      const char *robot_id);
+
+struct MrtodpResult mrtodp_scheduler_set_robot_slots(struct MrtodpScheduler *scheduler,
+                                                     const char *robot_id,
+                                                     uint32_t slots);
+
+struct MrtodpResult mrtodp_scheduler_set_robot_model(struct MrtodpScheduler *scheduler,
+                                                     const char *robot_id,
+                                                     const char *model,
+                                                     const char *firmware);
+
+struct MrtodpResult mrtodp_scheduler_report_emergency_stop(struct MrtodpScheduler *scheduler,
+                                                           const char *robot_id,
+                                                           const char *detail);
+
+struct MrtodpResult mrtodp_scheduler_report_battery(struct MrtodpScheduler *scheduler,
+                                                    const char *robot_id,
+                                                    double level);
+
+struct MrtodpResult mrtodp_scheduler_set_robot_zones(struct MrtodpScheduler *scheduler,
+                                                     const char *robot_id,
+                                                     const char *zones_json);
+
+struct MrtodpResult mrtodp_scheduler_reset_breaker(struct MrtodpScheduler *scheduler,
+                                                   const char *robot_id);
+
+struct MrtodpResult mrtodp_scheduler_report_robot_location(struct MrtodpScheduler *scheduler,
+                                                           const char *robot_id,
+                                                           const char *location_json);
+
+struct MrtodpResult mrtodp_scheduler_submit_bid(struct MrtodpScheduler *scheduler,
+                                                uint32_t task_id,
+                                                const char *robot_id,
+                                                double cost,
+                                                uint64_t eta_ms);
+
+struct MrtodpResult mrtodp_scheduler_schedule_task(struct MrtodpScheduler *scheduler,
+                                                   const char *task_json);
+
+struct MrtodpResult mrtodp_scheduler_register_template(struct MrtodpScheduler *scheduler,
+                                                       const char *template_json);
+
+struct MrtodpResult mrtodp_scheduler_register_payload_schema(struct MrtodpScheduler *scheduler,
+                                                             const char *task_type,
+                                                             const char *schema_json);
+
+struct MrtodpResult mrtodp_scheduler_set_submitter_limits(struct MrtodpScheduler *scheduler,
+                                                          const char *kind,
+                                                          const char *name,
+                                                          const char *limits_json);
+
+struct MrtodpResult mrtodp_scheduler_instantiate_template(struct MrtodpScheduler *scheduler,
+                                                          const char *template_id,
+                                                          const char *params_json);
+
+struct MrtodpResult mrtodp_scheduler_get_task_status(struct MrtodpScheduler *scheduler,
+                                                     uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_watch_open(struct MrtodpScheduler *scheduler,
+                                                const char *filter_json);
+
+struct MrtodpResult mrtodp_scheduler_watch_poll(struct MrtodpScheduler *scheduler,
+                                                uint64_t watch_id,
+                                                uint32_t max_events);
+
+struct MrtodpResult mrtodp_scheduler_watch_close(struct MrtodpScheduler *scheduler,
+                                                 uint64_t watch_id);
+
+struct MrtodpResult mrtodp_scheduler_hold_task(struct MrtodpScheduler *scheduler, uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_release_task(struct MrtodpScheduler *scheduler,
+                                                  uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_cancel_task(struct MrtodpScheduler *scheduler,
+                                                 uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_complete_task(struct MrtodpScheduler *scheduler,
+                                                   uint32_t task_id,
+                                                   uint64_t expected_version);
+
+struct MrtodpResult mrtodp_scheduler_create_mission(struct MrtodpScheduler *scheduler,
+                                                    const char *mission_json);
+
+struct MrtodpResult mrtodp_scheduler_get_mission_status(struct MrtodpScheduler *scheduler,
+                                                        const char *mission_id);
+
+struct MrtodpResult mrtodp_scheduler_cancel_mission(struct MrtodpScheduler *scheduler,
+                                                    const char *mission_id);
+
+struct MrtodpResult mrtodp_scheduler_pin_task(struct MrtodpScheduler *scheduler,
+                                              uint32_t task_id,
+                                              const char *robot_id);
+
+struct MrtodpResult mrtodp_scheduler_hand_off_task(struct MrtodpScheduler *scheduler,
+                                                   uint32_t task_id,
+                                                   const char *request_json);
+
+struct MrtodpResult mrtodp_scheduler_set_policy(struct MrtodpScheduler *scheduler,
+                                                const char *policy_name);
+
+struct MrtodpResult mrtodp_scheduler_shadow_policy(struct MrtodpScheduler *scheduler,
+                                                   const char *policy_name,
+                                                   uint64_t duration_ms);
+
+struct MrtodpResult mrtodp_scheduler_get_shadow_report(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_queue_saturation(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_queue_wait_stats(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_phase_latency(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_quota_usage(struct MrtodpScheduler *scheduler,
+                                                     const char *namespace_);
+
+struct MrtodpResult mrtodp_scheduler_get_compatible_robots(struct MrtodpScheduler *scheduler,
+                                                           const char *task_type);
+
+struct MrtodpResult mrtodp_scheduler_get_mutex_group_status(struct MrtodpScheduler *scheduler,
+                                                            const char *group);
+
+struct MrtodpResult mrtodp_scheduler_get_resource_status(struct MrtodpScheduler *scheduler,
+                                                         const char *resource);
+
+struct MrtodpResult mrtodp_scheduler_get_slo_status(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_stats(struct MrtodpScheduler *scheduler,
+                                               uint64_t window_ms);
+
+struct MrtodpResult mrtodp_scheduler_get_robot_profiles(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_breaker_status(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_dead_letters(struct MrtodpScheduler *scheduler);
+
+struct MrtodpResult mrtodp_scheduler_get_dead_letter(struct MrtodpScheduler *scheduler,
+                                                     uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_redrive_dead_letter(struct MrtodpScheduler *scheduler,
+                                                         uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_purge_dead_letter(struct MrtodpScheduler *scheduler,
+                                                       uint32_t task_id);
+
+struct MrtodpResult mrtodp_scheduler_get_audit_log(struct MrtodpScheduler *scheduler,
+                                                   const char *query_json);
+
+struct MrtodpResult mrtodp_scheduler_get_fleet_status(struct MrtodpScheduler *scheduler,
+                                                      uint64_t window_ms);
+
+struct MrtodpResult mrtodp_scheduler_shutdown(struct MrtodpScheduler *scheduler,
+                                              uint64_t grace_period_ms);
+
+struct MrtodpResult mrtodp_scheduler_list_tasks(struct MrtodpScheduler *scheduler,
+                                                const char *filter_json);
+
+struct MrtodpResult mrtodp_scheduler_list_task_history(struct MrtodpScheduler *scheduler,
+                                                       const char *filter_json,
+                                                       uint32_t limit,
+                                                       uint64_t cursor);
+
+#endif  /* MRTODP_SCHEDULER_H */
@@ -0,0 +1,235 @@
+// backend/rust/src/adapter.rs
+// Purpose: Robot adapter SDK for MRTODP. Vendors implement `RobotAdapter` (connect,
+// on_assignment, on_cancel, on_control, heartbeat) against a stable interface and report
+// outcomes through the `ResultReporter` handed to `connect`, instead of reverse-engineering
+// dispatch messages. `AdapterTransport` plugs adapters into the scheduler as its
+// `RobotTransport`; `ChannelAdapter` is a reference implementation that forwards every
+// lifecycle call to an async channel consumed by the vendor's own control loop.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+use crate::transport::{ControlCommand, ControlEnvelope, DispatchSeq, RobotTransport};
+use crate::BoxFuture;
+
+// Handle an adapter uses to report task outcomes back to the scheduler
+#[derive(Clone)]
+pub struct ResultReporter {
+    scheduler: Scheduler,
+    robot_id: String,
+}
+
+impl ResultReporter {
+    pub fn robot_id(&self) -> &str {
+        &self.robot_id
+    }
+
+    // Report the outcome of an assigned task: Ok for success, Err with a reason for failure
+    pub async fn report_result(&self, task_id: u32, result: Result<(), String>) {
+        self.scheduler.report_result(task_id, result).await
+    }
+}
+
+// Lifecycle hooks a vendor implements for one kind of robot
+pub trait RobotAdapter: Send + Sync {
+    // Open the link to a robot; called before its first delivery and after a failed heartbeat
+    fn connect<'a>(&'a self, robot_id: &'a str, reporter: ResultReporter) -> BoxFuture<'a, Result<(), String>>;
+    // Hand a task to the robot; Ok means the robot accepted it
+    fn on_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task) -> BoxFuture<'a, Result<(), String>>;
+    // Stop a task the robot is executing
+    fn on_cancel<'a>(&'a self, robot_id: &'a str, task_id: u32) -> BoxFuture<'a, Result<(), String>>;
+    // Liveness probe; an error marks the robot for reconnection
+    fn heartbeat<'a>(&'a self, robot_id: &'a str) -> BoxFuture<'a, Result<(), String>>;
+    // Hold, resume, and call-for-bids commands; adapters for robots that can't pause or bid
+    // may keep the default
+    fn on_control<'a>(&'a self, robot_id: &'a str, command: &'a ControlCommand) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { Err(format!("Robot {} does not support {:?}", robot_id, command)) })
+    }
+}
+
+// RobotTransport that routes deliveries to per-robot adapters, connecting lazily
+#[derive(Default)]
+pub struct AdapterTransport {
+    scheduler: OnceLock<Scheduler>,
+    adapters: Mutex<HashMap<String, Arc<dyn RobotAdapter>>>,
+    connected: Mutex<HashSet<String>>,
+}
+
+impl AdapterTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Connect the transport to the scheduler that adapters report results to
+    pub fn attach(&self, scheduler: &Scheduler) {
+        let _ = self.scheduler.set(scheduler.clone());
+    }
+
+    // Route a robot's deliveries to an adapter, replacing any previous adapter
+    pub fn register(&self, robot_id: &str, adapter: Arc<dyn RobotAdapter>) {
+        self.adapters.lock().unwrap().insert(robot_id.to_string(), adapter);
+        self.connected.lock().unwrap().remove(robot_id);
+    }
+
+    fn adapter(&self, robot_id: &str) -> Result<Arc<dyn RobotAdapter>, String> {
+        self.adapters.lock().unwrap().get(robot_id).cloned().ok_or_else(|| format!("No adapter for robot {}", robot_id))
+    }
+
+    // Adapter for a robot, connected first if needed
+    async fn connected_adapter(&self, robot_id: &str) -> Result<Arc<dyn RobotAdapter>, String> {
+        let adapter = self.adapter(robot_id)?;
+        if !self.connected.lock().unwrap().contains(robot_id) {
+            let scheduler = self.scheduler.get().cloned().ok_or("Adapter transport not attached to a scheduler")?;
+            let reporter = ResultReporter { scheduler, robot_id: robot_id.to_string() };
+            adapter.connect(robot_id, reporter).await?;
+            self.connected.lock().unwrap().insert(robot_id.to_string());
+        }
+        Ok(adapter)
+    }
+
+    // Probe every connected robot; failures are returned and their robots reconnect on the
+    // next delivery
+    pub async fn heartbeat_all(&self) -> HashMap<String, String> {
+        let connected: Vec<String> = self.connected.lock().unwrap().iter().cloned().collect();
+        let mut failures = HashMap::new();
+        for robot_id in connected {
+            let result = match self.adapter(&robot_id) {
+                Ok(adapter) => adapter.heartbeat(&robot_id).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                self.connected.lock().unwrap().remove(&robot_id);
+                failures.insert(robot_id, e);
+            }
+        }
+        failures
+    }
+
+    pub fn is_connected(&self, robot_id: &str) -> bool {
+        self.connected.lock().unwrap().contains(robot_id)
+    }
+}
+
+impl RobotTransport for AdapterTransport {
+    fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, _seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.connected_adapter(robot_id).await?.on_assignment(robot_id, task).await })
+    }
+
+    fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let adapter = self.connected_adapter(&envelope.robot_id).await?;
+            match &envelope.command {
+                ControlCommand::Abort { task_id } => adapter.on_cancel(&envelope.robot_id, *task_id).await,
+                command => adapter.on_control(&envelope.robot_id, command).await,
+            }
+        })
+    }
+}
+
+// Lifecycle call forwarded by `ChannelAdapter`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AdapterMessage {
+    Assignment { task: Box<Task> },
+    Cancel { task_id: u32 },
+    Control { command: ControlCommand },
+    Heartbeat,
+}
+
+// Reference adapter: forwards calls for one robot to a channel. The vendor's loop reads
+// `AdapterMessage`s from the receiver returned by `new` and reports outcomes through the
+// reporter from `reporter()`. Deliveries fail once the receiver is dropped.
+pub struct ChannelAdapter {
+    messages: mpsc::UnboundedSender<AdapterMessage>,
+    reporter: Mutex<Option<ResultReporter>>,
+}
+
+impl ChannelAdapter {
+    pub fn new() -> (Arc<Self>, mpsc::UnboundedReceiver<AdapterMessage>) {
+        let (messages, receiver) = mpsc::unbounded_channel();
+        (Arc::new(ChannelAdapter { messages, reporter: Mutex::new(None) }), receiver)
+    }
+
+    // Reporter from the latest connect, once the transport has connected
+    pub fn reporter(&self) -> Option<ResultReporter> {
+        self.reporter.lock().unwrap().clone()
+    }
+
+    fn forward(&self, robot_id: &str, message: AdapterMessage) -> Result<(), String> {
+        self.messages.send(message).map_err(|_| format!("Robot {} adapter loop stopped", robot_id))
+    }
+}
+
+impl RobotAdapter for ChannelAdapter {
+    fn connect<'a>(&'a self, robot_id: &'a str, reporter: ResultReporter) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            if self.messages.is_closed() {
+                return Err(format!("Robot {} adapter loop stopped", robot_id));
+            }
+            *self.reporter.lock().unwrap() = Some(reporter);
+            Ok(())
+        })
+    }
+
+    fn on_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.forward(robot_id, AdapterMessage::Assignment { task: Box::new(task.clone()) }) })
+    }
+
+    fn on_cancel<'a>(&'a self, robot_id: &'a str, task_id: u32) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.forward(robot_id, AdapterMessage::Cancel { task_id }) })
+    }
+
+    fn heartbeat<'a>(&'a self, robot_id: &'a str) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.forward(robot_id, AdapterMessage::Heartbeat) })
+    }
+
+    fn on_control<'a>(&'a self, robot_id: &'a str, command: &'a ControlCommand) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move { self.forward(robot_id, AdapterMessage::Control { command: command.clone() }) })
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::TaskState;
+
+    #[tokio::test]
+    async fn test_channel_adapter_round_trip() {
+        let transport = Arc::new(AdapterTransport::new());
+        let (scheduler, workers) = Scheduler::builder().transport(transport.clone()).build().unwrap();
+        transport.attach(&scheduler);
+        let (adapter, mut messages) = ChannelAdapter::new();
+        transport.register("Ford", adapter.clone());
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 141, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+
+        // The vendor loop receives the assignment and reports completion
+        let Some(AdapterMessage::Assignment { task }) = messages.recv().await else {
+            panic!("expected an assignment");
+        };
+        assert!(transport.is_connected("Ford"));
+        adapter.reporter().unwrap().report_result(task.id, Ok(())).await;
+        while let Ok(event) = events.recv().await {
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        assert_eq!(scheduler.task_record(141).await.unwrap().state, TaskState::Completed);
+
+        scheduler.send_control("Ford", ControlCommand::Abort { task_id: 141 }).await.unwrap();
+        assert_eq!(messages.recv().await, Some(AdapterMessage::Cancel { task_id: 141 }));
+        assert!(transport.heartbeat_all().await.is_empty());
+        assert_eq!(messages.recv().await, Some(AdapterMessage::Heartbeat));
+
+        // A stopped vendor loop fails the heartbeat and drops the connection
+        drop(messages);
+        assert!(transport.heartbeat_all().await.contains_key("Ford"));
+        assert!(!transport.is_connected("Ford"));
+    }
+}
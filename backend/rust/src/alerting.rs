@@ -0,0 +1,363 @@
+// backend/rust/src/alerting.rs
+// Purpose: Alerting for MRTODP. Critical scheduler events (robot e-stops, SLO breaches,
+// persistent store failures) are raised as `Alert`s and routed to pluggable `AlertSink`s
+// by rules matching on severity, namespace, and robot, so the right on-call is paged
+// without an external alert pipeline. Slack and PagerDuty sinks post through a
+// `WebhookTransport`; the email sink speaks plain SMTP to a plant relay.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use crate::webhooks::{HttpWebhookTransport, WebhookTransport};
+use crate::BoxFuture;
+
+// How urgently an alert needs a human
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+// Alert raised by the scheduler or an embedder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Alert {
+    pub severity: Severity,
+    pub source: String, // Kind of event, e.g. "e_stop", "slo", "store"
+    pub summary: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub robot_id: Option<String>,
+    pub at: u64, // Unix timestamp (milliseconds)
+}
+
+// Destination alerts are delivered to
+pub trait AlertSink: Send + Sync {
+    // Name routes refer to the sink by
+    fn name(&self) -> &str;
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), String>>;
+}
+
+// Rule sending matching alerts to one sink. Empty namespace and robot lists match any
+// alert; non-empty lists only match alerts naming one of their entries.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AlertRoute {
+    pub sink: String,
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub robots: Vec<String>,
+}
+
+fn default_min_severity() -> Severity {
+    Severity::Critical
+}
+
+impl AlertRoute {
+    // Route critical alerts of any namespace and robot to a sink
+    pub fn new(sink: &str) -> Self {
+        AlertRoute { sink: sink.to_string(), min_severity: default_min_severity(), namespaces: Vec::new(), robots: Vec::new() }
+    }
+
+    pub fn min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    pub fn namespaces(mut self, namespaces: Vec<String>) -> Self {
+        self.namespaces = namespaces;
+        self
+    }
+
+    pub fn robots(mut self, robots: Vec<String>) -> Self {
+        self.robots = robots;
+        self
+    }
+
+    fn matches(&self, alert: &Alert) -> bool {
+        let listed = |list: &[String], value: &Option<String>| list.is_empty() || value.as_ref().is_some_and(|v| list.contains(v));
+        alert.severity >= self.min_severity && listed(&self.namespaces, &alert.namespace) && listed(&self.robots, &alert.robot_id)
+    }
+}
+
+// One-line description used by chat and email sinks
+fn headline(alert: &Alert) -> String {
+    let mut line = format!("[{:?}] {}: {}", alert.severity, alert.source, alert.summary);
+    if let Some(robot_id) = &alert.robot_id {
+        line.push_str(&format!(" (robot {})", robot_id));
+    }
+    if let Some(namespace) = &alert.namespace {
+        line.push_str(&format!(" (namespace {})", namespace));
+    }
+    line
+}
+
+// Posts alerts to a Slack incoming webhook
+pub struct SlackSink {
+    name: String,
+    url: String,
+    transport: Arc<dyn WebhookTransport>,
+}
+
+impl SlackSink {
+    // Plain HTTP transport by default; use `transport` for HTTPS or a proxy
+    pub fn new(name: &str, url: &str) -> Self {
+        SlackSink { name: name.to_string(), url: url.to_string(), transport: Arc::new(HttpWebhookTransport) }
+    }
+
+    pub fn transport(mut self, transport: Arc<dyn WebhookTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+}
+
+impl AlertSink for SlackSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), String>> {
+        let body = json!({ "text": headline(alert) }).to_string();
+        self.transport.post(&self.url, body)
+    }
+}
+
+// Triggers PagerDuty incidents through the Events API v2
+pub struct PagerDutySink {
+    name: String,
+    routing_key: String,
+    url: String,
+    transport: Arc<dyn WebhookTransport>,
+}
+
+impl PagerDutySink {
+    pub fn new(name: &str, routing_key: &str) -> Self {
+        PagerDutySink {
+            name: name.to_string(),
+            routing_key: routing_key.to_string(),
+            url: "https://events.pagerduty.com/v2/enqueue".to_string(),
+            transport: Arc::new(HttpWebhookTransport),
+        }
+    }
+
+    // Events endpoint, e.g. a plant-side relay; the public API needs an HTTPS transport
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+
+    pub fn transport(mut self, transport: Arc<dyn WebhookTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+}
+
+impl AlertSink for PagerDutySink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), String>> {
+        // Repeats of the same event on the same robot group into one incident
+        let dedup_key = format!("{}:{}:{}", alert.source, alert.namespace.as_deref().unwrap_or(""), alert.robot_id.as_deref().unwrap_or(""));
+        let body = json!({
+            "routing_key": self.routing_key,
+            "event_action": "trigger",
+            "dedup_key": dedup_key,
+            "payload": {
+                "summary": alert.summary,
+                "source": alert.robot_id.as_deref().unwrap_or("mrtodp-scheduler"),
+                "severity": match alert.severity {
+                    Severity::Info => "info",
+                    Severity::Warning => "warning",
+                    Severity::Critical => "critical",
+                },
+                "component": alert.source,
+                "group": alert.namespace,
+            },
+        })
+        .to_string();
+        self.transport.post(&self.url, body)
+    }
+}
+
+// Emails alerts through an SMTP relay that accepts unauthenticated mail from the plant network
+pub struct EmailSink {
+    name: String,
+    relay: String, // host:port
+    from: String,
+    to: Vec<String>,
+}
+
+impl EmailSink {
+    pub fn new(name: &str, relay: &str, from: &str, to: Vec<String>) -> Self {
+        EmailSink { name: name.to_string(), relay: relay.to_string(), from: from.to_string(), to }
+    }
+
+    async fn deliver(&self, alert: &Alert) -> Result<(), String> {
+        let stream = tokio::time::timeout(Duration::from_secs(5), TcpStream::connect(&self.relay))
+            .await
+            .map_err(|_| format!("SMTP connect to {} timed out", self.relay))?
+            .map_err(|e| format!("SMTP connect to {} failed: {}", self.relay, e))?;
+        let mut stream = BufReader::new(stream);
+        smtp_reply(&mut stream, "220").await?;
+        let mut commands = vec![("HELO mrtodp\r\n".to_string(), "250"), (format!("MAIL FROM:<{}>\r\n", self.from), "250")];
+        commands.extend(self.to.iter().map(|to| (format!("RCPT TO:<{}>\r\n", to), "250")));
+        commands.push(("DATA\r\n".to_string(), "354"));
+        // Dot-stuff lines of the body that start with a period
+        let body = serde_json::to_string_pretty(alert).map_err(|e| e.to_string())?.replace("\n.", "\n..");
+        let message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\n\r\n{}\r\n.\r\n",
+            self.from,
+            self.to.join(", "),
+            headline(alert).replace(['\r', '\n'], " "),
+            body.replace('\n', "\r\n")
+        );
+        commands.push((message, "250"));
+        for (command, expected) in commands {
+            stream.get_mut().write_all(command.as_bytes()).await.map_err(|e| format!("SMTP write failed: {}", e))?;
+            smtp_reply(&mut stream, expected).await?;
+        }
+        let _ = stream.get_mut().write_all(b"QUIT\r\n").await;
+        Ok(())
+    }
+}
+
+// Read one (possibly multi-line) SMTP reply and check its status code
+async fn smtp_reply(stream: &mut BufReader<TcpStream>, expected: &str) -> Result<(), String> {
+    loop {
+        let mut line = String::new();
+        let read = tokio::time::timeout(Duration::from_secs(10), stream.read_line(&mut line))
+            .await
+            .map_err(|_| "SMTP reply timed out".to_string())?
+            .map_err(|e| format!("SMTP read failed: {}", e))?;
+        if read == 0 {
+            return Err("SMTP relay closed the connection".to_string());
+        }
+        if !line.starts_with(expected) {
+            return Err(format!("SMTP relay replied {}", line.trim_end()));
+        }
+        // "250-" continues a multi-line reply; "250 " ends it
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+impl AlertSink for EmailSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(self.deliver(alert))
+    }
+}
+
+// Background worker delivering raised alerts to every sink with a matching route
+pub(crate) struct AlertRouter {
+    pub(crate) sinks: HashMap<String, Arc<dyn AlertSink>>,
+    pub(crate) routes: Vec<AlertRoute>,
+    pub(crate) alerts: mpsc::UnboundedReceiver<Alert>,
+}
+
+impl AlertRouter {
+    pub(crate) async fn run(mut self) {
+        while let Some(alert) = self.alerts.recv().await {
+            let mut targets: Vec<&str> = self.routes.iter().filter(|r| r.matches(&alert)).map(|r| r.sink.as_str()).collect();
+            targets.sort_unstable();
+            targets.dedup();
+            for name in targets {
+                let Some(sink) = self.sinks.get(name).cloned() else { continue };
+                let alert = alert.clone();
+                // Deliver off the router loop so a slow sink doesn't delay pages elsewhere.
+                // Failures are only logged: alerting on them could loop.
+                tokio::spawn(async move {
+                    if let Err(e) = sink.send(&alert).await {
+                        tracing::warn!(sink = %sink.name(), source = %alert.source, error = %e, "alert sink failed to deliver");
+                    }
+                });
+            }
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::scheduler::Scheduler;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        posts: Mutex<Vec<(String, String)>>,
+    }
+
+    impl WebhookTransport for RecordingTransport {
+        fn post<'a>(&'a self, url: &'a str, body: String) -> BoxFuture<'a, Result<(), String>> {
+            self.posts.lock().unwrap().push((url.to_string(), body));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_routes_match_severity_namespace_and_robot() {
+        let alert = Alert {
+            severity: Severity::Warning,
+            source: "slo".to_string(),
+            summary: "at risk".to_string(),
+            namespace: Some("cell-1".to_string()),
+            robot_id: None,
+            at: 0,
+        };
+        assert!(!AlertRoute::new("pager").matches(&alert));
+        assert!(AlertRoute::new("chat").min_severity(Severity::Info).matches(&alert));
+        let cell = AlertRoute::new("chat").min_severity(Severity::Warning);
+        assert!(cell.clone().namespaces(vec!["cell-1".to_string()]).matches(&alert));
+        assert!(!cell.clone().namespaces(vec!["cell-2".to_string()]).matches(&alert));
+        // A robot-scoped route ignores alerts that aren't about a robot
+        assert!(!cell.robots(vec!["Ford".to_string()]).matches(&alert));
+    }
+
+    #[tokio::test]
+    async fn test_estop_pages_on_call_for_its_robot() {
+        let transport = Arc::new(RecordingTransport::default());
+        let slack = SlackSink::new("line-chat", "http://relay/slack").transport(transport.clone());
+        let pager = PagerDutySink::new("cell-1-oncall", "key-1").url("http://relay/pd").transport(transport.clone());
+        let (scheduler, workers) = Scheduler::builder()
+            .alert_sink(Arc::new(slack))
+            .alert_sink(Arc::new(pager))
+            .alert_route(AlertRoute::new("line-chat").min_severity(Severity::Info))
+            .alert_route(AlertRoute::new("cell-1-oncall").robots(vec!["Ford".to_string()]))
+            .build()
+            .unwrap();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        scheduler.report_emergency_stop("Ford", "light curtain tripped").await.unwrap();
+        scheduler.report_emergency_stop("Scion", "operator button").await.unwrap();
+        assert!(scheduler.report_emergency_stop("Hank", "unknown").await.is_err());
+        for _ in 0..100 {
+            if transport.posts.lock().unwrap().len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        let posts = transport.posts.lock().unwrap().clone();
+        assert_eq!(posts.len(), 3);
+        let paged: Vec<&(String, String)> = posts.iter().filter(|(url, _)| url == "http://relay/pd").collect();
+        assert_eq!(paged.len(), 1);
+        assert!(paged[0].1.contains("light curtain tripped") && paged[0].1.contains("\"severity\":\"critical\""));
+        assert!(Scheduler::builder().alert_route(AlertRoute::new("nowhere")).build().is_err());
+    }
+}
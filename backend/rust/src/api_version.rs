@@ -0,0 +1,132 @@
+// backend/rust/src/api_version.rs
+// Purpose: Versions of the MRTODP network APIs (HTTP and gRPC), served side by side so
+// the Task model can evolve without breaking existing scripts. Each version decides how
+// responses carrying task data (task records and listings, dead letters, history, event
+// streams, and watches) are rendered for clients; responses name the version that
+// produced them, and responses from a superseded version carry a deprecation notice
+// pointing at its successor.
+//
+//   v1  deadlines as Unix epoch milliseconds (deprecated; also served on unversioned paths)
+//   v2  deadlines as RFC 3339 UTC timestamps
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::dead_letters::DeadLetter;
+use crate::deadlines::format_deadline;
+use crate::history::HistoryPage;
+use crate::scheduler::{TaskEvent, TaskRecord};
+use crate::task_list::TaskSummary;
+use crate::watch::WatchEvent;
+
+// Header (HTTP) and metadata key (gRPC) naming the API version of a response
+pub const API_VERSION_HEADER: &str = "api-version";
+// Header and metadata key present on responses from a deprecated version
+pub const DEPRECATION_HEADER: &str = "deprecation";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const LATEST: ApiVersion = ApiVersion::V2;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    // Version clients should move to, if this one is deprecated
+    pub fn successor(&self) -> Option<ApiVersion> {
+        match self {
+            ApiVersion::V1 => Some(ApiVersion::V2),
+            ApiVersion::V2 => None,
+        }
+    }
+
+    // Response body as this version's clients expect it
+    pub fn render<T: Versioned>(&self, body: &T) -> Result<Value, String> {
+        let mut value = serde_json::to_value(body).map_err(|e| format!("Failed to encode response: {}", e))?;
+        if *self == ApiVersion::V2 {
+            body.to_v2(&mut value);
+        }
+        Ok(value)
+    }
+}
+
+// A response body carrying task data. It serializes as v1 renders it, and rewrites the
+// fields later versions changed.
+pub trait Versioned: Serialize {
+    // Turn the v1 rendering into the v2 one
+    fn to_v2(&self, value: &mut Value);
+}
+
+// Deadline as v2 renders it; deadlines past chrono's range (year 262143) are as good as none
+fn v2_deadline(deadline: Option<u64>) -> Value {
+    deadline.and_then(format_deadline).map_or(Value::Null, Value::String)
+}
+
+impl Versioned for TaskRecord {
+    fn to_v2(&self, value: &mut Value) {
+        value["task"]["deadline"] = v2_deadline(self.task.deadline);
+    }
+}
+
+impl Versioned for TaskSummary {
+    fn to_v2(&self, value: &mut Value) {
+        value["deadline"] = v2_deadline(self.deadline);
+    }
+}
+
+impl Versioned for DeadLetter {
+    fn to_v2(&self, value: &mut Value) {
+        value["task"]["deadline"] = v2_deadline(self.task.deadline);
+    }
+}
+
+// History entries, events, and watch updates carry no deadline; they go through the
+// renderer so that a field added to them later is versioned with the rest
+impl Versioned for HistoryPage {
+    fn to_v2(&self, _value: &mut Value) {}
+}
+
+impl Versioned for TaskEvent {
+    fn to_v2(&self, _value: &mut Value) {}
+}
+
+impl Versioned for WatchEvent {
+    fn to_v2(&self, _value: &mut Value) {}
+}
+
+impl<T: Versioned> Versioned for Vec<T> {
+    fn to_v2(&self, value: &mut Value) {
+        if let Some(values) = value.as_array_mut() {
+            for (body, value) in self.iter().zip(values) {
+                body.to_v2(value);
+            }
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::Scheduler;
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_versions_render_deadlines_differently() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        scheduler.schedule_task(Task { id: 261, deadline: Some(1_767_225_600_000), ..Default::default() }).await.unwrap();
+        let record = scheduler.task_record(261).await.unwrap();
+        assert_eq!(ApiVersion::V1.render(&record).unwrap()["task"]["deadline"], 1_767_225_600_000u64);
+        assert_eq!(ApiVersion::V2.render(&record).unwrap()["task"]["deadline"], "2026-01-01T00:00:00.000Z");
+        assert_eq!(ApiVersion::V1.successor(), Some(ApiVersion::LATEST));
+        assert_eq!(ApiVersion::LATEST.successor(), None);
+    }
+}
@@ -0,0 +1,236 @@
+// backend/rust/src/assignment.rs
+// Purpose: Robot selection for MRTODP tasks submitted without a `robot_id`. At dispatch the
+// scheduler gathers every registered robot whose capabilities cover the task's required
+// capabilities and that is certified for its task type, and picks one:
+//
+//   - robots with a free slot come before robots the task would have to wait for; among
+//     robots without one, the least loaded wins
+//   - expedited and high-priority tasks take the most reliable robot (success rate from its
+//     profile; robots without history count as perfect), then the least loaded
+//   - routine tasks take the least loaded robot (active tasks per slot), then the most
+//     reliable, spreading routine work and keeping reliable robots free for urgent work
+//   - remaining ties go to the lowest robot ID, so selection is deterministic
+//
+// With `nearest` set, robots are ranked by distance to the task's location (see
+// location.rs) right after free slots, robots without a distance last, before the rules
+// above. With `earliest_finish` set, the robot where the task is projected to finish first
+// wins instead (see capacity.rs), then the most reliable, then the lowest robot ID.
+//
+// Integrators can replace the ordering above with their own scoring (e.g. battery-aware or
+// distance-aware) by giving the builder an `AssignmentScorer`: the candidate with the
+// highest score wins, ties going to the lowest robot ID. `DefaultScorer` adds up how
+// closely the robot's capabilities match the task's, how much of its capacity is free, the
+// task's deadline slack there, and how near the robot is to the task's location, each
+// weighted; custom scorers can start from it.
+//
+// The chosen robot is recorded on the task record. A task no robot can run keeps running
+// unassigned, as before.
+
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// Slack at which a deadline stops adding to DefaultScorer's score (10 minutes)
+const SLACK_HORIZON_MS: f64 = 600_000.0;
+
+// Distance at which DefaultScorer's proximity term halves (metres, or seconds of travel)
+const PROXIMITY_SCALE: f64 = 100.0;
+
+// Assignment engine settings
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssignmentConfig {
+    pub high_priority: u32, // Tasks at or above this priority are placed by reliability first
+    #[serde(default)]
+    pub earliest_finish: bool, // Place by projected finish time instead of load
+    #[serde(default)]
+    pub nearest: bool, // Prefer the robot nearest the task's location among equally free ones
+}
+
+impl Default for AssignmentConfig {
+    fn default() -> Self {
+        AssignmentConfig { high_priority: 5, earliest_finish: false, nearest: false }
+    }
+}
+
+// Robot able to run the task, with its current load and track record
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub robot_id: String,
+    pub capabilities: Vec<String>, // Everything the robot can do, not just what the task needs
+    pub active: u32,               // Tasks assigned to, running on, or queued for the robot
+    pub slots: u32,
+    pub success_rate: f64,
+    pub projected_finish: u64, // When the task would finish there (Unix milliseconds)
+    pub distance: Option<f64>, // From the robot to the task's location; None if either is unknown
+}
+
+impl Candidate {
+    pub fn is_free(&self) -> bool {
+        self.active < self.slots
+    }
+
+    // Active tasks per slot; above 1.0 once tasks queue for the robot
+    pub fn load(&self) -> f64 {
+        self.active as f64 / self.slots.max(1) as f64
+    }
+}
+
+// Scores the robots able to run a task; the highest score wins
+pub trait AssignmentScorer: Send + Sync {
+    fn score(&self, task: &Task, candidate: &Candidate) -> f64;
+}
+
+// Capability match + free capacity + deadline slack, each weighted
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct DefaultScorer {
+    pub capability_weight: f64, // Share of the robot's capabilities the task needs; specialists score higher
+    pub load_weight: f64,       // 1.0 for an idle robot, falling by 1.0 per task per slot
+    pub slack_weight: f64,      // Deadline slack at the projected finish, up to 10 minutes; negative once late
+    pub distance_weight: f64,   // 1.0 at the task's location, halving 100 units away; 0.0 without a distance
+}
+
+impl Default for DefaultScorer {
+    fn default() -> Self {
+        DefaultScorer { capability_weight: 1.0, load_weight: 1.0, slack_weight: 1.0, distance_weight: 1.0 }
+    }
+}
+
+impl AssignmentScorer for DefaultScorer {
+    fn score(&self, task: &Task, candidate: &Candidate) -> f64 {
+        // A robot with no capabilities beyond the task's is a perfect match
+        let capability_match = match candidate.capabilities.len() {
+            0 => 1.0,
+            offered => task.required_capabilities.len().min(offered) as f64 / offered as f64,
+        };
+        let slack = task.deadline.map_or(0.0, |deadline| {
+            let slack_ms = deadline as f64 - candidate.projected_finish as f64;
+            (slack_ms / SLACK_HORIZON_MS).clamp(-1.0, 1.0)
+        });
+        let proximity = candidate.distance.map_or(0.0, |distance| PROXIMITY_SCALE / (PROXIMITY_SCALE + distance.max(0.0)));
+        self.capability_weight * capability_match
+            + self.load_weight * (1.0 - candidate.load())
+            + self.slack_weight * slack
+            + self.distance_weight * proximity
+    }
+}
+
+// Highest-scoring candidate, ties going to the lowest robot ID; None if there are none
+pub(crate) fn best_scored(scorer: &dyn AssignmentScorer, task: &Task, candidates: &[Candidate]) -> Option<String> {
+    candidates
+        .iter()
+        .map(|c| (scorer.score(task, c), c))
+        .max_by(|(a_score, a), (b_score, b)| a_score.total_cmp(b_score).then_with(|| b.robot_id.cmp(&a.robot_id)))
+        .map(|(_, c)| c.robot_id.clone())
+}
+
+impl AssignmentConfig {
+    // Best candidate for the task; None if there are no candidates
+    pub(crate) fn select(&self, task: &Task, candidates: &[Candidate]) -> Option<String> {
+        let urgent = task.expedite || task.priority >= self.high_priority;
+        candidates
+            .iter()
+            .min_by(|a, b| {
+                let by_load = a.load().total_cmp(&b.load());
+                let by_success = b.success_rate.total_cmp(&a.success_rate);
+                if self.earliest_finish {
+                    return a.projected_finish.cmp(&b.projected_finish).then(by_success).then_with(|| a.robot_id.cmp(&b.robot_id));
+                }
+                let by_free = b.is_free().cmp(&a.is_free());
+                let by_distance = match (a.distance, b.distance) {
+                    _ if !self.nearest => std::cmp::Ordering::Equal,
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                };
+                let by_fit = if a.is_free() && b.is_free() && urgent { by_success.then(by_load) } else { by_load.then(by_success) };
+                by_free.then(by_distance).then(by_fit).then_with(|| a.robot_id.cmp(&b.robot_id))
+            })
+            .map(|c| c.robot_id.clone())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::scheduler::Scheduler;
+
+    #[test]
+    fn test_selection_weighs_load_against_reliability_by_priority() {
+        let candidate = |robot_id: &str, active: u32, slots: u32, success_rate: f64| Candidate {
+            robot_id: robot_id.to_string(),
+            capabilities: Vec::new(),
+            active,
+            slots,
+            success_rate,
+            projected_finish: 1_000 * active as u64 / slots as u64,
+            distance: None,
+        };
+        let candidates = vec![
+            candidate("Ford", 1, 2, 0.99),  // Reliable, half busy
+            candidate("Scion", 0, 1, 0.80), // Idle, less reliable
+            candidate("Hank", 1, 1, 1.00),  // Perfect but full
+        ];
+        let config = AssignmentConfig::default();
+        let routine = Task { priority: 1, ..Default::default() };
+        let urgent = Task { priority: 7, ..Default::default() };
+        assert_eq!(config.select(&routine, &candidates).as_deref(), Some("Scion"));
+        assert_eq!(config.select(&urgent, &candidates).as_deref(), Some("Ford"));
+        assert_eq!(config.select(&Task { expedite: true, ..routine.clone() }, &candidates).as_deref(), Some("Ford"));
+
+        // With every robot full, the task queues on the least loaded one
+        let full = vec![candidate("Ford", 2, 2, 0.9), candidate("Scion", 3, 2, 1.0)];
+        assert_eq!(config.select(&urgent, &full).as_deref(), Some("Ford"));
+        assert_eq!(config.select(&routine, &[]), None);
+
+        // By projected finish, Ford has less queued per slot, until its work runs long
+        let by_finish = AssignmentConfig { earliest_finish: true, ..config };
+        assert_eq!(by_finish.select(&routine, &full).as_deref(), Some("Ford"));
+        let later = vec![Candidate { projected_finish: 5_000, ..full[0].clone() }, full[1].clone()];
+        assert_eq!(by_finish.select(&routine, &later).as_deref(), Some("Scion"));
+    }
+    #[tokio::test]
+    async fn test_scorers_rank_candidates() {
+        let candidate = |robot_id: &str, capabilities: &[&str], active: u32, projected_finish: u64| Candidate {
+            robot_id: robot_id.to_string(),
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+            active,
+            slots: 1,
+            success_rate: 1.0,
+            projected_finish,
+            distance: None,
+        };
+        let task = Task { required_capabilities: vec!["lift".to_string()], deadline: Some(600_000), ..Default::default() };
+        let scorer = DefaultScorer::default();
+
+        // The specialist beats the generalist; a robot that would finish late loses its lead
+        let specialist = candidate("Scion", &["lift"], 0, 0);
+        let generalist = candidate("Ford", &["lift", "weld"], 0, 0);
+        assert!((scorer.score(&task, &specialist) - 3.0).abs() < 1e-9);
+        assert_eq!(best_scored(&scorer, &task, &[generalist.clone(), specialist.clone()]).as_deref(), Some("Scion"));
+        let late = Candidate { projected_finish: 1_200_000, ..specialist.clone() };
+        assert_eq!(best_scored(&scorer, &task, &[generalist.clone(), late]).as_deref(), Some("Ford"));
+
+        // A custom scorer wins over the default; ties go to the lowest robot ID
+        struct Battery;
+        impl AssignmentScorer for Battery {
+            fn score(&self, _task: &Task, candidate: &Candidate) -> f64 {
+                if candidate.robot_id == "Ford" { 0.9 } else { 0.2 }
+            }
+        }
+        assert_eq!(best_scored(&Battery, &task, &[specialist.clone(), generalist]).as_deref(), Some("Ford"));
+        let twin = Candidate { robot_id: "Hank".to_string(), ..specialist.clone() };
+        assert_eq!(best_scored(&scorer, &task, &[specialist, twin]).as_deref(), Some("Hank"));
+        assert_eq!(best_scored(&scorer, &task, &[]), None);
+
+        // Given to the builder, the scorer places tasks submitted without a robot
+        let (scheduler, workers) = Scheduler::builder().assignment_scorer(Arc::new(Battery)).build().unwrap();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string(), "weld".to_string()]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec!["lift".to_string()]).await.unwrap();
+        scheduler.schedule_task(Task { id: 387, deadline: None, ..task }).await.unwrap();
+        while scheduler.task_record(387).await.is_none_or(|r| !r.state.is_terminal()) {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.task_record(387).await.unwrap().task.robot_id.as_deref(), Some("Ford"));
+    }
+}
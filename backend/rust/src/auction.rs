@@ -0,0 +1,129 @@
+// backend/rust/src/auction.rs
+// Purpose: Market-based task allocation for MRTODP (contract net). When enabled on the
+// builder, a queued task that names no robot is put up for auction instead of being run
+// directly: every eligible robot (registered, with the required capabilities, certified for
+// the task type) is sent a `CallForBids` control command, and robots answer through
+// `Scheduler::submit_bid` with their cost and ETA. When the bidding window closes the task
+// goes to the lowest cost, ties broken by ETA and then robot ID, and is queued again bound
+// to the winner. A task that drew no bids is announced again. Expedited tasks skip the
+// auction and go straight to the robot the assignment engine picks.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// Auction settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuctionConfig {
+    pub bidding_window: Duration, // Time robots have to bid after a task is announced
+}
+
+impl Default for AuctionConfig {
+    fn default() -> Self {
+        AuctionConfig { bidding_window: Duration::from_secs(2) }
+    }
+}
+
+impl AuctionConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bidding_window.is_zero() {
+            return Err("Bidding window must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+// A robot's offer to run an auctioned task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bid {
+    pub robot_id: String,
+    pub cost: f64,   // Robot-defined cost (e.g. energy or travel distance); lower wins
+    pub eta_ms: u64, // Expected time until the robot could start the task
+}
+
+impl Bid {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.cost.is_finite() || self.cost < 0.0 {
+            return Err(format!("Bid cost must be a non-negative number, got {}", self.cost));
+        }
+        Ok(())
+    }
+}
+
+// Task under auction and the bids received so far
+struct Auction {
+    task: Task,
+    invited: Vec<String>,       // Robots the call for bids went to
+    bids: HashMap<String, Bid>, // robot_id -> latest bid
+}
+
+// Open auctions, keyed by task ID
+pub(crate) struct Auctions {
+    pub(crate) config: AuctionConfig,
+    open: HashMap<u32, Auction>,
+}
+
+impl Auctions {
+    pub(crate) fn new(config: AuctionConfig) -> Self {
+        Auctions { config, open: HashMap::new() }
+    }
+
+    pub(crate) fn open(&mut self, task: Task, invited: Vec<String>) {
+        self.open.insert(task.id, Auction { task, invited, bids: HashMap::new() });
+    }
+
+    // Record a bid; a robot's later bid replaces its earlier one
+    pub(crate) fn bid(&mut self, task_id: u32, bid: Bid) -> Result<(), String> {
+        let auction = self.open.get_mut(&task_id).ok_or_else(|| format!("No open auction for task {}", task_id))?;
+        if !auction.invited.contains(&bid.robot_id) {
+            return Err(format!("Robot {} was not invited to bid on task {}", bid.robot_id, task_id));
+        }
+        auction.bids.insert(bid.robot_id.clone(), bid);
+        Ok(())
+    }
+
+    // End an auction, returning the task and its winning bid, if any
+    pub(crate) fn close(&mut self, task_id: u32) -> Option<(Task, Option<Bid>)> {
+        let auction = self.open.remove(&task_id)?;
+        let winner = auction
+            .bids
+            .into_values()
+            .min_by(|a, b| a.cost.total_cmp(&b.cost).then(a.eta_ms.cmp(&b.eta_ms)).then_with(|| a.robot_id.cmp(&b.robot_id)));
+        Some((auction.task, winner))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{Scheduler, TaskState};
+
+    #[tokio::test]
+    async fn test_auction_awards_task_to_cheapest_bidder() {
+        let config = AuctionConfig { bidding_window: Duration::from_millis(100) };
+        let (scheduler, workers) = Scheduler::builder().auction(config).build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        for (robot_id, capabilities) in [("Ford", vec!["lift".to_string()]), ("Scion", vec!["lift".to_string()]), ("Hank", vec![])] {
+            scheduler.register_robot(robot_id.to_string(), capabilities).await.unwrap();
+        }
+        let bid = |robot_id: &str, cost: f64, eta_ms: u64| Bid { robot_id: robot_id.to_string(), cost, eta_ms };
+        assert!(scheduler.submit_bid(281, bid("Ford", 1.0, 0)).unwrap_err().contains("No open auction"));
+        let task = Task { id: 281, required_capabilities: vec!["lift".to_string()], ..Default::default() };
+        scheduler.schedule_task(task).await.unwrap();
+        while scheduler.submit_bid(281, bid("Ford", 5.0, 100)).is_err() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(scheduler.submit_bid(281, bid("Hank", 1.0, 0)).unwrap_err().contains("not invited"));
+        assert!(scheduler.submit_bid(281, bid("Scion", -1.0, 0)).is_err());
+        scheduler.submit_bid(281, bid("Scion", 3.0, 900)).unwrap();
+        scheduler.submit_bid(281, bid("Ford", 3.0, 400)).unwrap();
+
+        while !events.recv().await.unwrap().transition.to.is_terminal() {}
+        let record = scheduler.task_record(281).await.unwrap();
+        assert_eq!(record.state, TaskState::Completed);
+        assert_eq!(record.attempts[0].robot_id.as_deref(), Some("Ford"));
+    }
+}
@@ -0,0 +1,156 @@
+// backend/rust/src/audit.rs
+// Purpose: Append-only audit log of every mutating MRTODP operation, for post-incident
+// analysis and compliance: who scheduled, cancelled, held, or pinned which task, which
+// robot each task was assigned to, robots joining and leaving the fleet, and changes to
+// the scheduling policy, rules, and submitter limits. Each entry names its actor, the
+// authenticated caller a front-end acted for (see `Scheduler::acting_as`), or none when the
+// scheduler acted on its own, e.g. when assigning a task. Entries are written through to
+// the `TaskStore` and never changed; the most recent ones (see the builder's
+// `audit_capacity`) are kept in memory, reloaded at startup, and queried by time range,
+// actor, task, and action with `Scheduler::query_audit`, GET /admin/audit, or the FFI.
+
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+// Entries kept in memory by default
+pub const DEFAULT_AUDIT_CAPACITY: usize = 100_000;
+
+// Kind of action recorded in the audit log
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    ManualOverride,    // Task pinned to a robot by an operator, bypassing the cost model
+    TaskScheduled,     // Task submitted, directly or through an upload
+    TaskCancelled,     // Task withdrawn through cancel_task
+    TaskHeld,          // Pending task held back from dispatch
+    TaskReleased,      // Held task returned to the queue
+    RobotAssigned,     // Task dispatched to a robot for execution
+    RobotRegistered,   // Robot added to the fleet
+    RobotDrained,      // Robot stopped taking new tasks
+    RobotDeregistered, // Robot removed from the fleet
+    PolicyChanged,     // Scheduling policy replaced
+    RulesReloaded,     // Admission and routing rules replaced
+    LimitsChanged,     // Submitter limits replaced
+    ZonesChanged,      // Zones a robot may operate in replaced
+    TaskHandedOff,     // Task taken from its robot with its progress, for another to continue
+    TaskReassigned,    // Task queued again under the retry policy after its robot failed it
+    BreakerTripped,    // Robot's circuit breaker opened after repeated failures
+    BreakerClosed,     // Robot's circuit breaker closed by a probe task or reset by hand
+    TaskDeadLettered,  // Task moved to the dead-letter queue after failing every attempt
+    TaskRedriven,      // Dead-lettered task queued again by an operator
+    TaskPurged,        // Dead-lettered task deleted by an operator
+}
+
+// One recorded action
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub at: u64, // Unix timestamp (milliseconds)
+    pub action: AuditAction,
+    #[serde(default)]
+    pub actor: Option<String>, // Who acted; None = the scheduler itself
+    #[serde(default)]
+    pub task_id: Option<u32>,
+    pub robot_id: Option<String>,
+    pub previous_robot_id: Option<String>, // Assignment the action replaced, if any
+    pub detail: String,
+}
+
+// Which entries an audit query returns; unset fields match every entry
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub since: Option<u64>, // Entries at or after this time (Unix ms)
+    #[serde(default)]
+    pub until: Option<u64>, // Entries before this time (Unix ms)
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub task_id: Option<u32>,
+    #[serde(default)]
+    pub actions: Vec<AuditAction>, // Empty = every action
+}
+
+impl AuditQuery {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at < until)
+            && self.actor.as_ref().is_none_or(|actor| entry.actor.as_ref() == Some(actor))
+            && self.task_id.is_none_or(|task_id| entry.task_id == Some(task_id))
+            && (self.actions.is_empty() || self.actions.contains(&entry.action))
+    }
+}
+
+// The most recent audit entries, oldest first
+pub(crate) struct AuditLog {
+    entries: VecDeque<AuditEntry>,
+    capacity: usize,
+}
+
+impl AuditLog {
+    // Keep the last `capacity` of the entries recovered from the store
+    pub(crate) fn new(recovered: Vec<AuditEntry>, capacity: usize) -> Self {
+        let mut entries = VecDeque::from(recovered);
+        entries.drain(..entries.len().saturating_sub(capacity));
+        AuditLog { entries, capacity }
+    }
+
+    pub(crate) fn push(&mut self, entry: AuditEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub(crate) fn query(&self, query: &AuditQuery) -> Vec<AuditEntry> {
+        self.entries.iter().filter(|entry| query.matches(entry)).cloned().collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::Scheduler;
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_mutations_recorded_with_their_actor() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).audit_capacity(6).build().unwrap();
+        workers.spawn();
+        let (admin, operator) = (scheduler.acting_as("it-admin"), scheduler.acting_as("line-lead"));
+        admin.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        clock.advance(Duration::from_millis(1_000));
+        operator.hold_task(359).await.unwrap_err();
+        operator.schedule_task(Task { id: 359, required_capabilities: vec!["weld".to_string()], ..Default::default() }).await.unwrap();
+        operator.cancel_task(359).await.unwrap();
+        clock.advance(Duration::from_millis(1_000));
+        let mut events = scheduler.subscribe();
+        operator.schedule_task(Task { id: 360, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while events.recv().await.unwrap().transition.to != crate::scheduler::TaskState::Completed {}
+
+        let actions = |query: AuditQuery| scheduler.query_audit(&query).into_iter().map(|e| (e.action, e.actor, e.task_id)).collect::<Vec<_>>();
+        let line_lead = Some("line-lead".to_string());
+        assert_eq!(
+            actions(AuditQuery { task_id: Some(359), ..Default::default() }),
+            vec![(AuditAction::TaskScheduled, line_lead.clone(), Some(359)), (AuditAction::TaskCancelled, line_lead.clone(), Some(359))]
+        );
+        assert_eq!(
+            actions(AuditQuery { since: Some(3_000), ..Default::default() }),
+            vec![(AuditAction::TaskScheduled, line_lead.clone(), Some(360)), (AuditAction::RobotAssigned, None, Some(360))]
+        );
+        assert_eq!(actions(AuditQuery { actor: Some("it-admin".to_string()), until: Some(2_000), ..Default::default() }).len(), 1);
+        let assigned = scheduler.query_audit(&AuditQuery { actions: vec![AuditAction::RobotAssigned], ..Default::default() });
+        assert_eq!(assigned[0].robot_id.as_deref(), Some("Ford"));
+
+        // Every entry is persisted; memory keeps the most recent
+        scheduler.acting_as("it-admin").set_policy_by_name("earliest_deadline_first").unwrap();
+        let stored = scheduler.core.store.load_audit().unwrap();
+        assert_eq!(stored.len(), 6);
+        assert_eq!(AuditLog::new(stored, 2).query(&AuditQuery::default())[0].action, AuditAction::RobotAssigned);
+        assert_eq!(scheduler.query_audit(&AuditQuery::default()).len(), 6);
+    }
+}
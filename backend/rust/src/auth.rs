@@ -0,0 +1,221 @@
+// backend/rust/src/auth.rs
+// Purpose: Authentication and role-based access control for the MRTODP network
+// front-ends. Callers present a bearer token (`authorization: Bearer <token>` as an HTTP
+// header or gRPC metadata), a `TokenVerifier` turns it into a `Principal` with a role, and
+// the role decides which actions the caller may take: viewers only read task and robot
+// status, operators also schedule and cancel tasks, and admins also register robots and
+// use the admin API. Verification is pluggable; `ApiTokens` checks a fixed set of API
+// tokens, and `JwtVerifier` (`jwt` feature) validates signed JWTs whose `sub` and `role`
+// claims name the principal. `http::require_auth` and `SchedulerService::with_auth` (see
+// grpc.rs) put a verifier in front of the REST and gRPC APIs; without one they stay open.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+// Prefix of an `authorization` value carrying a token
+const BEARER_PREFIX: &str = "Bearer ";
+
+// Caller role, least privileged first
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl Role {
+    // Least privileged role allowed to take `action`
+    fn required_for(action: Action) -> Role {
+        match action {
+            Action::Read => Role::Viewer,
+            Action::Schedule | Action::Cancel => Role::Operator,
+            Action::RegisterRobot | Action::Administer => Role::Admin,
+        }
+    }
+
+    pub fn allows(self, action: Action) -> bool {
+        self >= Role::required_for(action)
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "viewer"),
+            Role::Operator => write!(f, "operator"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+// What a front-end request does, for access control
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Read,          // Task records, robots, health, event streams
+    Schedule,      // Submit tasks
+    Cancel,        // Cancel tasks
+    RegisterRobot, // Add robots to the fleet
+    Administer,    // Admin API, e.g. submitter limits
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Read => write!(f, "read status"),
+            Action::Schedule => write!(f, "schedule tasks"),
+            Action::Cancel => write!(f, "cancel tasks"),
+            Action::RegisterRobot => write!(f, "register robots"),
+            Action::Administer => write!(f, "use the admin API"),
+        }
+    }
+}
+
+// Authenticated caller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    pub subject: String, // User, service, or API key owner
+    pub role: Role,
+}
+
+// Turns a bearer token into the caller it identifies; implement it to plug in another
+// identity provider
+pub trait TokenVerifier: Send + Sync {
+    fn verify(&self, token: &str) -> Result<Principal, String>;
+}
+
+// Why a request was refused
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthError {
+    Unauthenticated(String), // No token, or one the verifier rejected
+    Forbidden(String),       // Valid token whose role doesn't allow the action
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthError::Unauthenticated(message) | AuthError::Forbidden(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+// Check the `authorization` value of a request for `action`
+pub fn authorize(verifier: &dyn TokenVerifier, authorization: Option<&str>, action: Action) -> Result<Principal, AuthError> {
+    let authorization = authorization.ok_or_else(|| AuthError::Unauthenticated("Missing bearer token".to_string()))?;
+    let token = authorization
+        .strip_prefix(BEARER_PREFIX)
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| AuthError::Unauthenticated("Authorization must be a bearer token".to_string()))?;
+    let principal = verifier.verify(token).map_err(AuthError::Unauthenticated)?;
+    if !principal.role.allows(action) {
+        return Err(AuthError::Forbidden(format!("{} {} may not {}", principal.role, principal.subject, action)));
+    }
+    Ok(principal)
+}
+
+// Fixed set of API tokens, each issued to a principal
+#[derive(Clone, Debug, Default)]
+pub struct ApiTokens {
+    tokens: HashMap<String, Principal>,
+}
+
+impl ApiTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn token(mut self, token: &str, subject: &str, role: Role) -> Self {
+        self.tokens.insert(token.to_string(), Principal { subject: subject.to_string(), role });
+        self
+    }
+}
+
+impl TokenVerifier for ApiTokens {
+    fn verify(&self, token: &str) -> Result<Principal, String> {
+        self.tokens.get(token).cloned().ok_or_else(|| "Unknown API token".to_string())
+    }
+}
+
+// Claims read from a JWT
+#[cfg(feature = "jwt")]
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    role: Role,
+}
+
+// Validates HS256-signed JWTs; tokens must carry `sub`, `role`, and an unexpired `exp`
+#[cfg(feature = "jwt")]
+#[derive(Clone)]
+pub struct JwtVerifier {
+    key: jsonwebtoken::DecodingKey,
+    validation: jsonwebtoken::Validation,
+}
+
+#[cfg(feature = "jwt")]
+impl JwtVerifier {
+    pub fn hs256(secret: &[u8]) -> Self {
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        JwtVerifier { key: jsonwebtoken::DecodingKey::from_secret(secret), validation }
+    }
+
+    // Only accept tokens issued by `issuer`
+    pub fn issuer(mut self, issuer: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+
+    // Only accept tokens issued for `audience`
+    pub fn audience(mut self, audience: &str) -> Self {
+        self.validation.set_audience(&[audience]);
+        self
+    }
+}
+
+#[cfg(feature = "jwt")]
+impl TokenVerifier for JwtVerifier {
+    fn verify(&self, token: &str) -> Result<Principal, String> {
+        let data = jsonwebtoken::decode::<Claims>(token, &self.key, &self.validation).map_err(|e| format!("Invalid JWT: {}", e))?;
+        Ok(Principal { subject: data.claims.sub, role: data.claims.role })
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_gate_actions_by_token() {
+        let tokens = ApiTokens::new().token("view-1", "dashboard", Role::Viewer).token("ops-1", "line-lead", Role::Operator);
+        let viewer = authorize(&tokens, Some("Bearer view-1"), Action::Read).unwrap();
+        assert_eq!(viewer, Principal { subject: "dashboard".to_string(), role: Role::Viewer });
+        assert_eq!(
+            authorize(&tokens, Some("Bearer view-1"), Action::Cancel),
+            Err(AuthError::Forbidden("viewer dashboard may not cancel tasks".to_string()))
+        );
+        assert!(authorize(&tokens, Some("Bearer ops-1"), Action::Schedule).is_ok());
+        assert!(matches!(authorize(&tokens, Some("Bearer ops-1"), Action::RegisterRobot), Err(AuthError::Forbidden(_))));
+        assert!(matches!(authorize(&tokens, Some("Bearer nope"), Action::Read), Err(AuthError::Unauthenticated(_))));
+        assert!(matches!(authorize(&tokens, Some("Basic ops-1"), Action::Read), Err(AuthError::Unauthenticated(_))));
+        assert!(matches!(authorize(&tokens, None, Action::Read), Err(AuthError::Unauthenticated(_))));
+        assert!(Role::Admin.allows(Action::Administer));
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_jwt_claims_name_the_principal() {
+        let header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+        let key = jsonwebtoken::EncodingKey::from_secret(b"factory-secret");
+        let exp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() + 3600;
+        let claims = serde_json::json!({ "sub": "mes", "role": "operator", "exp": exp, "iss": "plant-idp" });
+        let token = jsonwebtoken::encode(&header, &claims, &key).unwrap();
+
+        let verifier = JwtVerifier::hs256(b"factory-secret").issuer("plant-idp");
+        assert_eq!(verifier.verify(&token).unwrap(), Principal { subject: "mes".to_string(), role: Role::Operator });
+        assert!(JwtVerifier::hs256(b"other-secret").verify(&token).unwrap_err().starts_with("Invalid JWT"));
+        assert!(JwtVerifier::hs256(b"factory-secret").issuer("elsewhere").verify(&token).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+// backend/rust/src/backpressure.rs
+// Purpose: Backpressure on the MRTODP dispatch queue. Each lane (normal and urgent)
+// takes up to the builder's `task_channel_size` submitted tasks not yet taken by the
+// execution loop; a `schedule_task` call that finds its lane full is handled by the
+// scheduler's `BackpressurePolicy`: wait for room (the default), refuse at once with a
+// retriable QUEUE_FULL_ERROR, wait up to a timeout and then refuse, or drop the
+// lowest-priority queued task in the lane (it fails with DROPPED_QUEUE_FULL) when the
+// new task outranks it. Every full lane a submission ran into, and what became of it, is
+// counted and reported with the current queue fill by `Scheduler::queue_saturation`, GET
+// /health, and the FFI. Tasks re-queued by the scheduler itself (recovery, released
+// missions and mutex groups, robot queues) bypass the policy; committed uploads are
+// subject to it like any submission.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+use crate::task::Task;
+
+// Prefix of the error returned when a full dispatch queue refuses a submission; clients
+// should back off and retry
+pub const QUEUE_FULL_ERROR: &str = "Queue full";
+
+// What a submission does when its lane of the dispatch queue is full
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    #[default]
+    Block, // Wait for room however long it takes
+    Reject, // Refuse at once
+    BlockWithTimeout { timeout_ms: u64 }, // Wait up to the timeout, then refuse
+    DropLowestPriority, // Drop the lowest-priority queued task if the new one outranks it; else refuse
+}
+
+// Fill of the dispatch queue and how often submissions found it full
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueSaturation {
+    pub policy: BackpressurePolicy,
+    pub capacity: usize,      // Per lane
+    pub queued: usize,        // Submissions waiting in the normal lane
+    pub urgent_queued: usize, // Submissions waiting in the urgent lane
+    pub saturated: u64,       // Submissions that found their lane full
+    pub rejected: u64,        // Refused at once
+    pub timed_out: u64,       // Refused after waiting out the timeout
+    pub dropped: u64,         // Queued tasks dropped for higher-priority submissions
+}
+
+// A submission waiting in a lane
+struct Queued {
+    urgent: bool,
+    priority: u32,
+    seq: u64, // Order of entry, to drop the newest of equally low tasks
+}
+
+#[derive(Default)]
+struct GateState {
+    queued: HashMap<u32, Queued>, // task_id -> its place in a lane
+    next_seq: u64,
+    saturated: u64,
+    rejected: u64,
+    timed_out: u64,
+    dropped: u64,
+}
+
+// Outcome of one attempt to enter a lane
+enum Entry {
+    Entered,
+    Displaced(u32), // Entered in place of this task, which must be dropped
+    Full,
+}
+
+// Bounds the submissions in each lane of the dispatch queue
+pub(crate) struct DispatchGate {
+    policy: BackpressurePolicy,
+    capacity: usize,
+    state: std::sync::Mutex<GateState>,
+    room: Notify, // Signalled when a task leaves a lane
+}
+
+impl DispatchGate {
+    pub(crate) fn new(policy: BackpressurePolicy, capacity: usize) -> Self {
+        DispatchGate { policy, capacity, state: std::sync::Mutex::new(GateState::default()), room: Notify::new() }
+    }
+
+    fn try_enter(&self, task: &Task, first_try: bool) -> Entry {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let in_lane = state.queued.values().filter(|q| q.urgent == task.expedite).count();
+        let mut entry = Entry::Entered;
+        if in_lane >= self.capacity {
+            if first_try {
+                state.saturated += 1;
+            }
+            let lowest = state
+                .queued
+                .iter()
+                .filter(|(_, q)| q.urgent == task.expedite && q.priority < task.priority)
+                .min_by_key(|(_, q)| (q.priority, std::cmp::Reverse(q.seq)))
+                .map(|(task_id, _)| *task_id);
+            match lowest.filter(|_| self.policy == BackpressurePolicy::DropLowestPriority) {
+                Some(victim) => {
+                    state.queued.remove(&victim);
+                    state.dropped += 1;
+                    entry = Entry::Displaced(victim);
+                }
+                None => return Entry::Full,
+            }
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queued.insert(task.id, Queued { urgent: task.expedite, priority: task.priority, seq });
+        entry
+    }
+
+    // A task left its lane: taken by the execution loop, or never queued after all
+    pub(crate) fn leave(&self, task_id: u32) {
+        if self.state.lock().unwrap_or_else(|e| e.into_inner()).queued.remove(&task_id).is_some() {
+            self.room.notify_waiters();
+        }
+    }
+
+    // Wake every waiting submission, e.g. to see that shutdown began
+    pub(crate) fn wake_all(&self) {
+        self.room.notify_waiters();
+    }
+
+    fn refused(&self, timed_out: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if timed_out {
+            state.timed_out += 1;
+        } else {
+            state.rejected += 1;
+        }
+    }
+
+    fn saturation(&self) -> QueueSaturation {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let urgent_queued = state.queued.values().filter(|q| q.urgent).count();
+        QueueSaturation {
+            policy: self.policy,
+            capacity: self.capacity,
+            queued: state.queued.len() - urgent_queued,
+            urgent_queued,
+            saturated: state.saturated,
+            rejected: state.rejected,
+            timed_out: state.timed_out,
+            dropped: state.dropped,
+        }
+    }
+}
+
+impl Scheduler {
+    // Take a place for a submission in its lane, applying the backpressure policy when the
+    // lane is full. The caller must `leave` the gate if the task is not queued after all.
+    pub(crate) async fn enter_queue(&self, task: &Task) -> Result<(), String> {
+        let gate = &self.core.dispatch_gate;
+        let deadline = match gate.policy {
+            BackpressurePolicy::BlockWithTimeout { timeout_ms } => Some(tokio::time::Instant::now() + Duration::from_millis(timeout_ms)),
+            _ => None,
+        };
+        let mut first_try = true;
+        loop {
+            // Armed before checking, so room made in between isn't missed
+            let room = gate.room.notified();
+            tokio::pin!(room);
+            room.as_mut().enable();
+            self.check_accepting()?;
+            match gate.try_enter(task, first_try) {
+                Entry::Entered => return Ok(()),
+                Entry::Displaced(victim) => {
+                    let detail = format!("Dropped from a full dispatch queue for task {} (priority {})", task.id, task.priority);
+                    if let Err(e) = self.transition_task(victim, TaskState::Failed, ReasonCode::DroppedQueueFull, detail).await {
+                        tracing::warn!(task_id = victim, displaced_by = task.id, error = %e, "could not drop task from the full dispatch queue");
+                    }
+                    return Ok(());
+                }
+                Entry::Full => first_try = false,
+            }
+            match (gate.policy, deadline) {
+                (BackpressurePolicy::Block, _) => room.await,
+                (BackpressurePolicy::BlockWithTimeout { timeout_ms }, Some(deadline)) => {
+                    if tokio::time::timeout_at(deadline, room).await.is_err() {
+                        gate.refused(true);
+                        return Err(format!("{}: no room for task {} within {} ms", QUEUE_FULL_ERROR, task.id, timeout_ms));
+                    }
+                }
+                _ => {
+                    gate.refused(false);
+                    return Err(format!("{}: {} tasks already waiting for dispatch", QUEUE_FULL_ERROR, gate.capacity));
+                }
+            }
+        }
+    }
+
+    // Fill of the dispatch queue and how often submissions found it full
+    pub fn queue_saturation(&self) -> QueueSaturation {
+        self.core.dispatch_gate.saturation()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_full_queue_handled_by_policy() {
+        // Until the workers run nothing leaves the queue, so a second task finds it full
+        let task = |id: u32, priority: u32| Task { id, priority, ..Default::default() };
+        let build = |policy: BackpressurePolicy| Scheduler::builder().task_channel_size(1).backpressure(policy).build().unwrap();
+
+        let (rejecting, _workers) = build(BackpressurePolicy::Reject);
+        rejecting.schedule_task(task(379, 1)).await.unwrap();
+        assert!(rejecting.schedule_task(task(380, 9)).await.unwrap_err().starts_with(QUEUE_FULL_ERROR));
+        assert!(rejecting.task_record(380).await.is_none());
+
+        let (waiting, _workers) = build(BackpressurePolicy::BlockWithTimeout { timeout_ms: 20 });
+        waiting.schedule_task(task(381, 1)).await.unwrap();
+        assert!(waiting.schedule_task(task(382, 1)).await.unwrap_err().starts_with(QUEUE_FULL_ERROR));
+
+        // A task only displaces a lower-priority one, which fails and is never run
+        let (dropping, workers) = build(BackpressurePolicy::DropLowestPriority);
+        dropping.schedule_task(task(383, 1)).await.unwrap();
+        assert!(dropping.schedule_task(task(384, 1)).await.is_err());
+        let submit = tokio::spawn({
+            let dropping = dropping.clone();
+            async move { dropping.schedule_task(task(385, 5)).await }
+        });
+        while dropping.task_record(383).await.is_none_or(|r| r.state != TaskState::Failed) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        workers.spawn();
+        submit.await.unwrap().unwrap();
+        let dropped = dropping.task_record(383).await.unwrap();
+        assert_eq!(dropped.attempts[0].transitions.last().unwrap().reason, ReasonCode::DroppedQueueFull);
+
+        let saturation = |scheduler: &Scheduler| {
+            let s = scheduler.queue_saturation();
+            (s.saturated, s.rejected, s.timed_out, s.dropped)
+        };
+        assert_eq!((rejecting.queue_saturation().queued, saturation(&rejecting)), (1, (1, 1, 0, 0)));
+        assert_eq!(saturation(&waiting), (1, 0, 1, 0));
+        assert_eq!(saturation(&dropping), (2, 1, 0, 1));
+    }
+}
@@ -0,0 +1,290 @@
+// backend/rust/src/breakers.rs
+// Purpose: Per-robot circuit breakers for MRTODP. With `circuit_breaker` set on the
+// builder, the scheduler keeps the outcomes of each robot's last `window` finished tasks
+// (completed, or failed because of the robot; see retries.rs) and trips the robot's
+// breaker once `failures` of them failed. A tripped (open) breaker keeps the assignment
+// engine and auctions from choosing the robot; tasks already queued for it or pinned to it
+// still go there. After `open_for` the breaker is half-open: the next task assigned to the
+// robot is a probe, and no other is assigned until it finishes. A completed probe closes
+// the breaker and forgets the robot's failures; a failed one opens it again. Trips and
+// closes are recorded in the audit log. Operators read the breakers with
+// `Scheduler::breaker_status` (GET /admin/breakers, or the FFI) and close one by hand with
+// `Scheduler::reset_breaker` (POST /admin/breakers/{robot_id}/reset, or the FFI).
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::audit::AuditAction;
+use crate::scheduler::{Scheduler, CONFLICT_ERROR};
+
+// When a robot's breaker trips and how long it stays open
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    pub failures: u32,      // Failures among the last `window` tasks that trip the breaker
+    pub window: u32,        // Finished tasks per robot the failures are counted over
+    pub open_for: Duration, // Time a tripped breaker stays open before probing the robot
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        CircuitBreakerConfig { failures: 3, window: 5, open_for: Duration::from_secs(60) }
+    }
+}
+
+impl CircuitBreakerConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.failures == 0 || self.failures > self.window {
+            return Err("Circuit breaker failures must be between 1 and the window".to_string());
+        }
+        if self.open_for.is_zero() {
+            return Err("Circuit breaker open time must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Whether a robot's breaker lets tasks through
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,   // Robot is assigned tasks as usual
+    Open,     // Robot tripped and is assigned no tasks
+    HalfOpen, // Robot is assigned one probe task
+}
+
+// A robot's breaker as reported to operators
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BreakerStatus {
+    pub robot_id: String,
+    pub state: BreakerState,
+    pub recent_tasks: u32,    // Finished tasks counted, at most the window
+    pub recent_failures: u32, // Failures among them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opened_at: Option<u64>, // When the breaker last tripped (Unix ms), while not closed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_task: Option<u32>, // Task probing a half-open robot
+}
+
+#[derive(Default)]
+struct Breaker {
+    recent: VecDeque<bool>, // Outcomes of the robot's last finished tasks, true = failed
+    opened_at: Option<u64>, // Set while open or half-open
+    probe: Option<u32>,     // Task assigned to the robot while half-open
+}
+
+// Breakers of every robot with counted outcomes
+pub(crate) struct Breakers {
+    config: CircuitBreakerConfig,
+    robots: HashMap<String, Breaker>,
+}
+
+impl Breakers {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Breakers { config, robots: HashMap::new() }
+    }
+
+    fn state(&self, breaker: &Breaker, now: u64) -> BreakerState {
+        match breaker.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if now < at.saturating_add(self.config.open_for.as_millis() as u64) => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    // Whether the robot may be chosen for a task
+    pub(crate) fn allows(&self, robot_id: &str, now: u64) -> bool {
+        self.robots.get(robot_id).is_none_or(|breaker| match self.state(breaker, now) {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => breaker.probe.is_none(),
+        })
+    }
+
+    // A task was assigned to the robot; on a half-open robot it becomes the probe
+    pub(crate) fn assigned(&mut self, robot_id: &str, task_id: u32, now: u64) {
+        let half_open = self.robots.get(robot_id).is_some_and(|b| b.probe.is_none() && self.state(b, now) == BreakerState::HalfOpen);
+        if let Some(breaker) = self.robots.get_mut(robot_id).filter(|_| half_open) {
+            breaker.probe = Some(task_id);
+        }
+    }
+
+    // Count a task the robot finished: `failed` is None for outcomes that say nothing about
+    // the robot, e.g. a cancellation. Returns the breaker's new state if it changed.
+    pub(crate) fn finished(&mut self, robot_id: &str, task_id: u32, failed: Option<bool>, now: u64) -> Option<BreakerState> {
+        let config = self.config;
+        let breaker = self.robots.entry(robot_id.to_string()).or_default();
+        if breaker.probe == Some(task_id) {
+            breaker.probe = None;
+            return match failed? {
+                true => {
+                    breaker.opened_at = Some(now);
+                    Some(BreakerState::Open)
+                }
+                false => {
+                    *breaker = Breaker::default();
+                    Some(BreakerState::Closed)
+                }
+            };
+        }
+        // Tasks finishing while the breaker is tripped were sent before it tripped
+        if breaker.opened_at.is_some() {
+            return None;
+        }
+        breaker.recent.push_back(failed?);
+        if breaker.recent.len() > config.window as usize {
+            breaker.recent.pop_front();
+        }
+        if breaker.recent.iter().filter(|f| **f).count() >= config.failures as usize {
+            breaker.opened_at = Some(now);
+            return Some(BreakerState::Open);
+        }
+        None
+    }
+
+    // Close a robot's breaker by hand; false if it wasn't tripped
+    pub(crate) fn reset(&mut self, robot_id: &str) -> bool {
+        match self.robots.get_mut(robot_id) {
+            Some(breaker) if breaker.opened_at.is_some() => {
+                *breaker = Breaker::default();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn status(&self, now: u64) -> Vec<BreakerStatus> {
+        let mut status: Vec<BreakerStatus> = self
+            .robots
+            .iter()
+            .map(|(robot_id, breaker)| BreakerStatus {
+                robot_id: robot_id.clone(),
+                state: self.state(breaker, now),
+                recent_tasks: breaker.recent.len() as u32,
+                recent_failures: breaker.recent.iter().filter(|f| **f).count() as u32,
+                opened_at: breaker.opened_at,
+                probe_task: breaker.probe,
+            })
+            .collect();
+        status.sort_by(|a, b| a.robot_id.cmp(&b.robot_id));
+        status
+    }
+}
+
+impl Scheduler {
+    // Breakers of every robot that has finished a task, by robot ID; empty without
+    // `circuit_breaker`
+    pub fn breaker_status(&self) -> Vec<BreakerStatus> {
+        match &self.core.breakers {
+            Some(breakers) => breakers.lock().unwrap_or_else(|e| e.into_inner()).status(self.core.clock.now_millis()),
+            None => Vec::new(),
+        }
+    }
+
+    // Close a tripped breaker, so the robot is assigned tasks again at once. Recorded in the
+    // audit log.
+    pub fn reset_breaker(&self, robot_id: &str) -> Result<(), String> {
+        let breakers = self.core.breakers.as_ref().ok_or_else(|| "Circuit breakers are not enabled".to_string())?;
+        if !breakers.lock().unwrap_or_else(|e| e.into_inner()).reset(robot_id) {
+            return Err(format!("{}: circuit breaker of robot {} is already closed", CONFLICT_ERROR, robot_id));
+        }
+        let detail = format!("Circuit breaker of robot {} reset", robot_id);
+        self.audit(AuditAction::BreakerClosed, None, Some(robot_id.to_string()), None, detail);
+        Ok(())
+    }
+
+    // Whether the robot's breaker lets the assignment engine choose it
+    pub(crate) fn breaker_allows(&self, robot_id: &str) -> bool {
+        self.core
+            .breakers
+            .as_ref()
+            .is_none_or(|breakers| breakers.lock().unwrap_or_else(|e| e.into_inner()).allows(robot_id, self.core.clock.now_millis()))
+    }
+
+    // Count a task the robot finished against its breaker, auditing a trip or close
+    pub(crate) fn breaker_finished(&self, robot_id: &str, task_id: u32, failed: Option<bool>) {
+        let Some(breakers) = &self.core.breakers else {
+            return;
+        };
+        let changed = breakers.lock().unwrap_or_else(|e| e.into_inner()).finished(robot_id, task_id, failed, self.core.clock.now_millis());
+        let (action, detail) = match changed {
+            Some(BreakerState::Open) => (AuditAction::BreakerTripped, format!("Circuit breaker of robot {} tripped by task {}", robot_id, task_id)),
+            Some(_) => (AuditAction::BreakerClosed, format!("Circuit breaker of robot {} closed by probe task {}", robot_id, task_id)),
+            None => return,
+        };
+        self.audit(action, Some(task_id), Some(robot_id.to_string()), None, detail);
+    }
+
+    // A task was assigned to the robot, probing it if its breaker is half-open
+    pub(crate) fn breaker_assigned(&self, robot_id: &str, task_id: u32) {
+        if let Some(breakers) = &self.core.breakers {
+            breakers.lock().unwrap_or_else(|e| e.into_inner()).assigned(robot_id, task_id, self.core.clock.now_millis());
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[test]
+    fn test_breaker_trips_probes_and_closes() {
+        let mut breakers = Breakers::new(CircuitBreakerConfig { failures: 2, window: 3, open_for: Duration::from_secs(60) });
+        assert_eq!(breakers.finished("Ford", 1, Some(true), 0), None);
+        assert_eq!(breakers.finished("Ford", 2, None, 0), None);
+        assert_eq!(breakers.finished("Ford", 3, Some(false), 0), None);
+        assert_eq!(breakers.finished("Ford", 4, Some(false), 0), None);
+        // The first failure left the window, so this one doesn't trip it
+        assert_eq!(breakers.finished("Ford", 5, Some(true), 0), None);
+        assert_eq!(breakers.finished("Ford", 6, Some(true), 1_000), Some(BreakerState::Open));
+        assert!(!breakers.allows("Ford", 60_999));
+
+        // Half-open lets one probe through; its failure opens the breaker again
+        assert!(breakers.allows("Ford", 61_000));
+        breakers.assigned("Ford", 7, 61_000);
+        assert!(!breakers.allows("Ford", 61_000));
+        assert_eq!(breakers.finished("Ford", 7, Some(true), 62_000), Some(BreakerState::Open));
+        breakers.assigned("Ford", 8, 122_000);
+        assert_eq!(breakers.status(122_000)[0].probe_task, Some(8));
+        assert_eq!(breakers.finished("Ford", 8, Some(false), 123_000), Some(BreakerState::Closed));
+        assert_eq!(breakers.status(123_000)[0].recent_tasks, 0);
+        assert!(!breakers.reset("Ford"));
+    }
+
+    #[tokio::test]
+    async fn test_tripped_robot_is_skipped_until_reset() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let config = CircuitBreakerConfig { failures: 1, window: 1, open_for: Duration::from_secs(60) };
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).circuit_breaker(config).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 2]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let assigned = |count: usize| {
+            let fake = fake.clone();
+            async move {
+                while fake.assignments().len() < count {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        scheduler.schedule_task(Task { id: 403, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        assigned(1).await;
+        scheduler.report_result(403, Err("Gripper jammed".to_string())).await;
+        assert_eq!(scheduler.breaker_status()[0].state, BreakerState::Open);
+
+        // Both robots are idle, but only Scion may be chosen until Ford's breaker is reset
+        scheduler.schedule_task(Task { id: 404, ..Default::default() }).await.unwrap();
+        assigned(2).await;
+        scheduler.reset_breaker("Ford").unwrap();
+        assert!(scheduler.reset_breaker("Ford").unwrap_err().contains("already closed"));
+        scheduler.schedule_task(Task { id: 405, ..Default::default() }).await.unwrap();
+        assigned(3).await;
+        assert_eq!(fake.assignments()[1..], [("Scion".to_string(), 404), ("Ford".to_string(), 405)]);
+    }
+}
@@ -0,0 +1,929 @@
+// backend/rust/src/builder.rs
+// Purpose: Builder for configuring and constructing a Scheduler. Selects the storage
+// backend, clock, robot transport, channel sizes and backpressure on a full dispatch
+// queue, transition hooks, deadline-miss handling, webhooks, mission concurrency caps,
+// duplicate-robot policy, coordinate frames, admission rules, load shedding, scheduling
+// policy, priority aging and class dispatch quotas, fair sharing between namespaces,
+// daily submission quotas, queued-task and per-minute limits per submitter, robot ready
+// checks, the orphan reservation reconciler, the delayed-task timer, assignment latency
+// SLOs, alert sinks and routes, decay of stale expedited tasks, the task type
+// compatibility matrix, named zones and the robots permitted in them, auction-based
+// allocation, robot selection or custom scoring for unassigned tasks and the distance
+// function used to prefer nearby robots, work stealing between robot queues, task
+// preemption, the parallel validation stage, how much of the audit log is kept in
+// memory, how many finished tasks the task history keeps, when the fleet status reports
+// a robot offline, automatic charging of robots low on battery, the retry policy for
+// tasks whose robot fails or goes offline, circuit breakers for robots that fail
+// repeatedly, how long an idle chunked upload is kept, and how long finished tasks are
+// kept and how often the store is compacted, and returns the scheduler together with
+// `SchedulerWorkers`, the background loops the caller runs or spawns.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::task::JoinHandle;
+use crate::alerting::{AlertRoute, AlertRouter, AlertSink};
+use crate::assignment::{AssignmentConfig, AssignmentScorer};
+use crate::audit::{AuditLog, DEFAULT_AUDIT_CAPACITY};
+use crate::auction::{AuctionConfig, Auctions};
+use crate::backpressure::{BackpressurePolicy, DispatchGate};
+use crate::breakers::{Breakers, CircuitBreakerConfig};
+use crate::charging::{AutoCharging, Batteries};
+use crate::clock::{Clock, SystemClock};
+use crate::coalitions::Coalitions;
+use crate::compatibility::CompatibilityMatrix;
+use crate::deadline_miss::{DeadlineMissHook, DeadlinePolicy};
+use crate::decay::ExpediteDecay;
+use crate::delayed::TimerWheel;
+use crate::events::EventLog;
+use crate::fair_queuing::FairShare;
+use crate::fleet::DEFAULT_OFFLINE_AFTER;
+use crate::frames::FrameRegistry;
+use crate::geofencing::Geofence;
+use crate::history::{TaskHistory, DEFAULT_HISTORY_CAPACITY};
+use crate::load_shedding::{LoadShedder, LoadSheddingConfig};
+use crate::location::{DistanceFunction, Euclidean};
+use crate::metrics::Metrics;
+use crate::missions::MissionLimiter;
+use crate::mutex_groups::MutexGroups;
+use crate::payload_schemas::PayloadSchemaRegistry;
+use crate::policy::{PriorityFirst, SchedulingPolicy};
+use crate::preemption::{PreemptionConfig, Suspensions};
+use crate::priority_classes::{ClassQuotas, PriorityAging, QueueOrdering};
+use crate::quotas::QuotaLimiter;
+use crate::readiness::{ReadyCheck, ReadyChecks};
+use crate::resource_locks::ResourceLocks;
+use crate::retention::DEFAULT_TASK_RETENTION;
+use crate::retries::RetryPolicy;
+use crate::robot_queues::{RobotQueues, StealPolicy};
+use crate::rules::RuleEngine;
+use crate::scheduler::{DuplicateRobotPolicy, Scheduler, SchedulerCore, TaskEvent, TaskRecord};
+use crate::slo::{SloSpec, SloTracker};
+use crate::store::{MemoryStore, TaskStore};
+use crate::submitter_limits::{Submitter, SubmitterLimiter, SubmitterLimits};
+use crate::task::Task;
+use crate::transport::{ControlDelivery, Dispatcher, RobotTransport};
+use crate::uploads::{UploadRegistry, DEFAULT_UPLOAD_TTL};
+use crate::validation::{run_validators, ValidationConfig, ValidationJob};
+use crate::webhooks::{HttpWebhookTransport, WebhookConfig, WebhookDispatcher, WebhookTransport};
+
+// Callback invoked synchronously for every task state transition
+pub type TransitionHook = Arc<dyn Fn(&TaskEvent) + Send + Sync>;
+
+// Configurable construction of a Scheduler
+pub struct SchedulerBuilder {
+    store: Arc<dyn TaskStore>,
+    clock: Arc<dyn Clock>,
+    task_channel_size: usize,
+    backpressure: BackpressurePolicy,
+    event_channel_size: usize,
+    event_replay_size: usize,
+    hooks: Vec<TransitionHook>,
+    deadline_policy: DeadlinePolicy,
+    deadline_hook: Option<DeadlineMissHook>,
+    webhooks: Vec<WebhookConfig>,
+    webhook_transport: Arc<dyn WebhookTransport>,
+    mission_limits: HashMap<String, usize>,
+    default_mission_limit: Option<usize>,
+    transport: Option<Arc<dyn RobotTransport>>,
+    control_delivery: ControlDelivery,
+    assignment_lane_size: usize,
+    replay_window: u64,
+    duplicate_robot_policy: DuplicateRobotPolicy,
+    frames: FrameRegistry,
+    rules: RuleEngine,
+    load_shedding: Option<LoadSheddingConfig>,
+    policy: Arc<dyn SchedulingPolicy>,
+    priority_aging: Option<PriorityAging>,
+    class_quotas: Option<ClassQuotas>,
+    fair_share: Option<FairShare>,
+    quota_limits: HashMap<String, u64>,
+    default_quota: Option<u64>,
+    quota_flush_interval: Duration,
+    submitter_limits: HashMap<Submitter, SubmitterLimits>,
+    ready_check: Option<ReadyCheck>,
+    reconcile_interval: Option<Duration>,
+    timer_tick: Duration,
+    slos: Vec<SloSpec>,
+    alert_sinks: Vec<Arc<dyn AlertSink>>,
+    alert_routes: Vec<AlertRoute>,
+    expedite_decay: Option<ExpediteDecay>,
+    compatibility: CompatibilityMatrix,
+    geofence: Geofence,
+    auction: Option<AuctionConfig>,
+    assignment: AssignmentConfig,
+    assignment_scorer: Option<Arc<dyn AssignmentScorer>>,
+    distance: Arc<dyn DistanceFunction>,
+    steal_policy: StealPolicy,
+    preemption: Option<PreemptionConfig>,
+    validation: Option<ValidationConfig>,
+    audit_capacity: usize,
+    history_capacity: usize,
+    offline_after: Duration,
+    upload_ttl: Duration,
+    task_retention: Duration,
+    compaction_interval: Option<Duration>,
+    auto_charging: Option<AutoCharging>,
+    retry_policy: Option<RetryPolicy>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+impl Default for SchedulerBuilder {
+    fn default() -> Self {
+        SchedulerBuilder {
+            store: Arc::new(MemoryStore::new()),
+            clock: Arc::new(SystemClock),
+            task_channel_size: 100,
+            backpressure: BackpressurePolicy::default(),
+            event_channel_size: 1024,
+            event_replay_size: 1024,
+            hooks: Vec::new(),
+            deadline_policy: DeadlinePolicy::default(),
+            deadline_hook: None,
+            webhooks: Vec::new(),
+            webhook_transport: Arc::new(HttpWebhookTransport),
+            mission_limits: HashMap::new(),
+            default_mission_limit: None,
+            transport: None,
+            control_delivery: ControlDelivery::default(),
+            assignment_lane_size: 32,
+            replay_window: 64,
+            duplicate_robot_policy: DuplicateRobotPolicy::Reject,
+            frames: FrameRegistry::default(),
+            rules: RuleEngine::default(),
+            load_shedding: None,
+            policy: Arc::new(PriorityFirst),
+            priority_aging: None,
+            class_quotas: None,
+            fair_share: None,
+            quota_limits: HashMap::new(),
+            default_quota: None,
+            quota_flush_interval: Duration::from_secs(5),
+            submitter_limits: HashMap::new(),
+            ready_check: None,
+            reconcile_interval: Some(Duration::from_secs(60)),
+            timer_tick: Duration::from_millis(50),
+            slos: Vec::new(),
+            alert_sinks: Vec::new(),
+            alert_routes: Vec::new(),
+            expedite_decay: None,
+            compatibility: CompatibilityMatrix::default(),
+            geofence: Geofence::default(),
+            auction: None,
+            assignment: AssignmentConfig::default(),
+            assignment_scorer: None,
+            distance: Arc::new(Euclidean),
+            steal_policy: StealPolicy::default(),
+            preemption: None,
+            validation: None,
+            audit_capacity: DEFAULT_AUDIT_CAPACITY,
+            history_capacity: DEFAULT_HISTORY_CAPACITY,
+            offline_after: DEFAULT_OFFLINE_AFTER,
+            upload_ttl: DEFAULT_UPLOAD_TTL,
+            task_retention: DEFAULT_TASK_RETENTION,
+            compaction_interval: Some(Duration::from_secs(600)),
+            auto_charging: None,
+            retry_policy: None,
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl SchedulerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Storage backend for task records and robot registrations
+    pub fn store(mut self, store: Arc<dyn TaskStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    // Time source for deadlines and transition timestamps
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Capacity of the channel feeding the execution loop, per lane; also the number of
+    // submissions each lane holds before backpressure applies
+    pub fn task_channel_size(mut self, size: usize) -> Self {
+        self.task_channel_size = size;
+        self
+    }
+
+    // What a submission does when its lane of the dispatch queue is full (default: block)
+    pub fn backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+
+    // Number of transition events buffered per subscriber before lagging
+    pub fn event_channel_size(mut self, size: usize) -> Self {
+        self.event_channel_size = size;
+        self
+    }
+
+    // Number of recent events retained for subscribers resuming or coalescing after lag
+    pub fn event_replay_size(mut self, size: usize) -> Self {
+        self.event_replay_size = size;
+        self
+    }
+
+    // Register a hook called for every task state transition
+    pub fn on_transition<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&TaskEvent) + Send + Sync + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    // How tasks that reach dispatch after their deadline are handled when they don't name a
+    // policy themselves (default: drop)
+    pub fn deadline_policy(mut self, policy: DeadlinePolicy) -> Self {
+        self.deadline_policy = policy;
+        self
+    }
+
+    // Register the hook that decides for late tasks whose policy is notify_callback
+    pub fn on_deadline_miss<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Task, u64) -> DeadlinePolicy + Send + Sync + 'static,
+    {
+        self.deadline_hook = Some(Arc::new(hook));
+        self
+    }
+
+    // Deliver a webhook when tasks reach the configured states
+    pub fn webhook(mut self, webhook: WebhookConfig) -> Self {
+        self.webhooks.push(webhook);
+        self
+    }
+
+    // Transport used to deliver webhooks and escalations (plain HTTP by default)
+    pub fn webhook_transport(mut self, transport: Arc<dyn WebhookTransport>) -> Self {
+        self.webhook_transport = transport;
+        self
+    }
+
+    // Cap concurrently active missions in a namespace; excess missions queue
+    pub fn max_concurrent_missions(mut self, namespace: &str, limit: usize) -> Self {
+        self.mission_limits.insert(namespace.to_string(), limit);
+        self
+    }
+
+    // Cap applied to namespaces without an explicit mission limit
+    pub fn default_max_concurrent_missions(mut self, limit: usize) -> Self {
+        self.default_mission_limit = Some(limit);
+        self
+    }
+
+    // Transport delivering assignments and control commands to robots; without one,
+    // execution is simulated in-process
+    pub fn transport(mut self, transport: Arc<dyn RobotTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    // Retry settings for the priority control lane
+    pub fn control_delivery(mut self, delivery: ControlDelivery) -> Self {
+        self.control_delivery = delivery;
+        self
+    }
+
+    // Number of assignments queued per robot before dispatch waits
+    pub fn assignment_lane_size(mut self, size: usize) -> Self {
+        self.assignment_lane_size = size;
+        self
+    }
+
+    // How far behind a robot's newest sequence number a delivery may arrive and still be
+    // accepted by its replay guard (default: 64)
+    pub fn replay_window(mut self, window: u64) -> Self {
+        self.replay_window = window;
+        self
+    }
+
+    // How re-registering an already known robot ID is resolved (default: reject)
+    pub fn duplicate_robot_policy(mut self, policy: DuplicateRobotPolicy) -> Self {
+        self.duplicate_robot_policy = policy;
+        self
+    }
+
+    // Frame tree used to translate task geometry into each robot's frame at dispatch
+    pub fn frames(mut self, frames: FrameRegistry) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    // Initial admission and routing rules; replace later with Scheduler::reload_rules
+    pub fn rules(mut self, rules: RuleEngine) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    // Shed low-priority submissions while the scheduler is overloaded (default: never)
+    pub fn load_shedding(mut self, config: LoadSheddingConfig) -> Self {
+        self.load_shedding = Some(config);
+        self
+    }
+
+    // Order in which queued tasks are dispatched (default: PriorityFirst)
+    pub fn scheduling_policy(mut self, policy: Arc<dyn SchedulingPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    // Raise the effective priority of tasks that wait in a queue (default: no aging)
+    pub fn priority_aging(mut self, aging: PriorityAging) -> Self {
+        self.priority_aging = Some(aging);
+        self
+    }
+
+    // Cap the dispatches each priority class takes per round while lower classes wait
+    // (default: higher classes always go first)
+    pub fn class_quotas(mut self, quotas: ClassQuotas) -> Self {
+        self.class_quotas = Some(quotas);
+        self
+    }
+
+    // Share dispatches between namespaces in proportion to their weights (default: no
+    // balancing between namespaces)
+    pub fn fair_share(mut self, share: FairShare) -> Self {
+        self.fair_share = Some(share);
+        self
+    }
+
+    // Cap the submissions a namespace may make per UTC day
+    pub fn daily_quota(mut self, namespace: &str, limit: u64) -> Self {
+        self.quota_limits.insert(namespace.to_string(), limit);
+        self
+    }
+
+    // Daily quota applied to namespaces without an explicit one
+    pub fn default_daily_quota(mut self, limit: u64) -> Self {
+        self.default_quota = Some(limit);
+        self
+    }
+
+    // How often changed quota counters are written to the store (default: 5s); a restart
+    // loses at most this much counting
+    pub fn quota_flush_interval(mut self, interval: Duration) -> Self {
+        self.quota_flush_interval = interval;
+        self
+    }
+
+    // Cap a namespace's or source's unfinished tasks and submissions per minute; change
+    // later with Scheduler::set_submitter_limits
+    pub fn submitter_limits(mut self, submitter: Submitter, limits: SubmitterLimits) -> Self {
+        self.submitter_limits.insert(submitter, limits);
+        self
+    }
+
+    // Gate newly registered robots on a self-test delivered through the transport (default:
+    // robots are eligible on registration). Robots restored from the store are not retested.
+    pub fn ready_check(mut self, check: ReadyCheck) -> Self {
+        self.ready_check = Some(check);
+        self
+    }
+
+    // How often the workers release reservations whose tasks are gone (default: every 60s;
+    // None disables the background pass)
+    pub fn reconcile_interval(mut self, interval: Option<Duration>) -> Self {
+        self.reconcile_interval = interval;
+        self
+    }
+
+    // Resolution of the timer releasing tasks submitted with a not_before time (default:
+    // 50ms); a delayed task starts at most one tick late
+    pub fn timer_tick(mut self, tick: Duration) -> Self {
+        self.timer_tick = tick;
+        self
+    }
+
+    // Track an assignment latency objective and alert when it is at risk
+    pub fn slo(mut self, slo: SloSpec) -> Self {
+        self.slos.push(slo);
+        self
+    }
+
+    // Add a destination for alerts; routes refer to it by name
+    pub fn alert_sink(mut self, sink: Arc<dyn AlertSink>) -> Self {
+        self.alert_sinks.push(sink);
+        self
+    }
+
+    // Send alerts matching the route's severity, namespaces, and robots to its sink
+    pub fn alert_route(mut self, route: AlertRoute) -> Self {
+        self.alert_routes.push(route);
+        self
+    }
+
+    // Demote expedited tasks released after a hold longer than the decay window
+    pub fn expedite_decay(mut self, decay: ExpediteDecay) -> Self {
+        self.expedite_decay = Some(decay);
+        self
+    }
+
+    // Task types restricted to certified robot models and firmware; replace later with
+    // Scheduler::reload_compatibility
+    pub fn compatibility(mut self, matrix: CompatibilityMatrix) -> Self {
+        self.compatibility = matrix;
+        self
+    }
+
+    // Named zones and the robots permitted to operate in each (default: no zones); change
+    // permissions later with Scheduler::set_robot_zones
+    pub fn geofence(mut self, geofence: Geofence) -> Self {
+        self.geofence = geofence;
+        self
+    }
+
+    // Allocate tasks that name no robot by auction among the eligible robots (default: off)
+    pub fn auction(mut self, config: AuctionConfig) -> Self {
+        self.auction = Some(config);
+        self
+    }
+
+    // How robots are picked for tasks submitted without one
+    pub fn assignment(mut self, config: AssignmentConfig) -> Self {
+        self.assignment = config;
+        self
+    }
+
+    // Pick robots for tasks submitted without one by the highest score instead (default:
+    // the ordering configured with `assignment`)
+    pub fn assignment_scorer(mut self, scorer: Arc<dyn AssignmentScorer>) -> Self {
+        self.assignment_scorer = Some(scorer);
+        self
+    }
+
+    // How far robots are from a task's location when picking one (default: Euclidean)
+    pub fn distance_function(mut self, distance: Arc<dyn DistanceFunction>) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    // Which queued tasks robots with a free slot may take over from busier robots
+    // (default: conservative)
+    pub fn work_stealing(mut self, policy: StealPolicy) -> Self {
+        self.steal_policy = policy;
+        self
+    }
+
+    // Let urgent tasks suspend preemptible tasks on a full robot (default: never preempt)
+    pub fn preemption(mut self, config: PreemptionConfig) -> Self {
+        self.preemption = Some(config);
+        self
+    }
+
+    // Validate submissions on a pool of workers instead of inline (default: inline)
+    pub fn validation(mut self, config: ValidationConfig) -> Self {
+        self.validation = Some(config);
+        self
+    }
+
+    // Audit entries kept in memory for queries (default: 100,000); older ones remain in the
+    // store
+    pub fn audit_capacity(mut self, entries: usize) -> Self {
+        self.audit_capacity = entries;
+        self
+    }
+
+    // Finished tasks kept in the queryable history (default: 100,000); older ones are only
+    // reachable by ID
+    pub fn history_capacity(mut self, tasks: usize) -> Self {
+        self.history_capacity = tasks;
+        self
+    }
+
+    // Silence after which the fleet status reports a robot offline (default: 60s)
+    pub fn offline_after(mut self, silence: Duration) -> Self {
+        self.offline_after = silence;
+        self
+    }
+
+    // Idle time after which an open chunked upload is discarded (default: 1 hour)
+    pub fn upload_ttl(mut self, ttl: Duration) -> Self {
+        self.upload_ttl = ttl;
+        self
+    }
+
+    // Time a finished task's record is kept before compaction drops it from memory and the
+    // store (default: 7 days); dead-lettered tasks stay until purged
+    pub fn task_retention(mut self, retention: Duration) -> Self {
+        self.task_retention = retention;
+        self
+    }
+
+    // How often the workers drop finished tasks past their retention and compact the store
+    // (default: every 10 minutes; None disables the background pass)
+    pub fn compaction_interval(mut self, interval: Option<Duration>) -> Self {
+        self.compaction_interval = interval;
+        self
+    }
+
+    // Send robots that report a battery level below the threshold to charge (default: off)
+    pub fn auto_charging(mut self, config: AutoCharging) -> Self {
+        self.auto_charging = Some(config);
+        self
+    }
+
+    // Requeue tasks whose robot fails them or goes offline while it has attempts left
+    // (default: off)
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    // Stop choosing robots that failed too many of their recent tasks, probing them again
+    // after a while (default: off)
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    // Construct the scheduler, restoring robot registrations and profiles from the store
+    pub fn build(self) -> Result<(Scheduler, SchedulerWorkers), String> {
+        if self.task_channel_size == 0 || self.event_channel_size == 0 || self.assignment_lane_size == 0 {
+            return Err("Channel sizes must be greater than zero".to_string());
+        }
+        if self.backpressure == (BackpressurePolicy::BlockWithTimeout { timeout_ms: 0 }) {
+            return Err("Backpressure timeout must be greater than zero".to_string());
+        }
+        if self.replay_window == 0 {
+            return Err("Replay window must be greater than zero".to_string());
+        }
+        if self.audit_capacity == 0 {
+            return Err("Audit capacity must be greater than zero".to_string());
+        }
+        if self.history_capacity == 0 {
+            return Err("History capacity must be greater than zero".to_string());
+        }
+        if self.offline_after.is_zero() {
+            return Err("Offline threshold must be greater than zero".to_string());
+        }
+        if self.upload_ttl.is_zero() {
+            return Err("Upload time-to-live must be greater than zero".to_string());
+        }
+        if self.task_retention.is_zero() || self.compaction_interval.is_some_and(|i| i.is_zero()) {
+            return Err("Task retention and compaction interval must be greater than zero".to_string());
+        }
+        if let Some(config) = &self.auto_charging {
+            config.validate()?;
+        }
+        if let Some(policy) = &self.retry_policy {
+            policy.validate()?;
+        }
+        if let Some(config) = &self.circuit_breaker {
+            config.validate()?;
+        }
+        self.geofence.validate()?;
+        if self.ready_check.is_some() && self.transport.is_none() {
+            return Err("Ready checks need a robot transport".to_string());
+        }
+        if self.quota_flush_interval.is_zero() || self.reconcile_interval.is_some_and(|i| i.is_zero()) {
+            return Err("Quota flush and reconcile intervals must be greater than zero".to_string());
+        }
+        if self.timer_tick.as_millis() == 0 {
+            return Err("Timer tick must be at least one millisecond".to_string());
+        }
+        self.deadline_policy.validate()?;
+        for webhook in &self.webhooks {
+            webhook.validate()?;
+        }
+        if let Some(config) = &self.load_shedding {
+            config.validate()?;
+        }
+        for slo in &self.slos {
+            slo.validate()?;
+        }
+        if let Some(decay) = &self.expedite_decay {
+            decay.validate()?;
+        }
+        if let Some(aging) = &self.priority_aging {
+            aging.validate()?;
+        }
+        if let Some(quotas) = &self.class_quotas {
+            quotas.validate()?;
+        }
+        if let Some(share) = &self.fair_share {
+            share.validate()?;
+        }
+        if let Some(auction) = &self.auction {
+            auction.validate()?;
+        }
+        if let Some(preemption) = &self.preemption {
+            preemption.validate()?;
+        }
+        if let Some(validation) = &self.validation {
+            validation.validate()?;
+        }
+        let mut sinks = HashMap::new();
+        for sink in &self.alert_sinks {
+            if sinks.insert(sink.name().to_string(), sink.clone()).is_some() {
+                return Err(format!("Duplicate alert sink: {}", sink.name()));
+            }
+        }
+        if let Some(route) = self.alert_routes.iter().find(|r| !sinks.contains_key(&r.sink)) {
+            return Err(format!("Alert route targets unknown sink: {}", route.sink));
+        }
+        let robots = self.store.load_robots()?;
+        let records: HashMap<u32, _> = self.store.load_tasks()?.into_iter().map(|r| (r.task.id, r)).collect();
+        let mut recovered: Vec<&TaskRecord> = records.values().filter(|r| !r.state.is_terminal()).collect();
+        recovered.sort_by_key(|r| (r.attempts.first().and_then(|a| a.transitions.first()).map_or(0, |t| t.at), r.task.id));
+        let recovered = recovered.into_iter().map(|r| r.task.id).collect();
+        let audit = self.store.load_audit()?;
+        let history = TaskHistory::new(records.values(), self.history_capacity);
+        let robot_slots = self.store.load_robot_slots()?;
+        let robot_models = self.store.load_robot_models()?;
+        let quotas = QuotaLimiter::new(self.quota_limits, self.default_quota, self.store.load_quota_counters()?);
+        let unfinished = records.values().filter(|r| !r.state.is_terminal()).map(|r| &r.task);
+        let submitters = SubmitterLimiter::new(self.submitter_limits, unfinished);
+        let profiles = self.store.load_profiles()?.into_iter().map(|p| (p.robot_id.clone(), p)).collect();
+        let mission_registry = self.store.load_missions()?.into_iter().map(|m| (m.id.clone(), m)).collect();
+        let epoch = self.clock.now_millis();
+        let (tx, rx) = mpsc::channel(self.task_channel_size);
+        let (urgent_tx, urgent_rx) = mpsc::channel(self.task_channel_size);
+        let (events, _) = broadcast::channel(self.event_channel_size);
+        let (validation_tx, validation) = match self.validation {
+            Some(config) => {
+                let (tx, rx) = mpsc::channel(config.queue_capacity);
+                (Some(tx), Some((rx, config.workers)))
+            }
+            None => (None, None),
+        };
+        let (alerts_tx, alerts_rx) = mpsc::unbounded_channel();
+        let alerts = (!sinks.is_empty()).then(|| AlertRouter { sinks, routes: self.alert_routes, alerts: alerts_rx });
+        let queue_ordering = QueueOrdering { aging: self.priority_aging, quotas: self.class_quotas, fair_share: self.fair_share.map(Arc::new) };
+        let core = SchedulerCore {
+            policy: std::sync::RwLock::new(self.policy),
+            queue_ordering: queue_ordering.clone(),
+            ready_depth: std::sync::atomic::AtomicUsize::new(0),
+            capabilities: Mutex::new(robots),
+            records: Mutex::new(records),
+            reserved_ids: std::sync::Mutex::new(HashSet::new()),
+            events,
+            event_log: Arc::new(std::sync::Mutex::new(EventLog::new(self.event_replay_size))),
+            tx,
+            urgent_tx,
+            store: self.store,
+            clock: self.clock,
+            hooks: self.hooks,
+            deadline_policy: self.deadline_policy,
+            deadline_hook: self.deadline_hook,
+            missions: Mutex::new(MissionLimiter::new(self.mission_limits, self.default_mission_limit)),
+            mission_registry: std::sync::Mutex::new(mission_registry),
+            metrics: std::sync::Mutex::new(Metrics::default()),
+            held: Mutex::new(HashMap::new()),
+            expedite_decay: self.expedite_decay,
+            webhook_transport: self.webhook_transport.clone(),
+            duplicate_robot_policy: self.duplicate_robot_policy,
+            frames: self.frames,
+            uploads: Mutex::new(UploadRegistry::default()),
+            templates: std::sync::RwLock::new(HashMap::new()),
+            payload_schemas: PayloadSchemaRegistry::default(),
+            rules: std::sync::RwLock::new(Arc::new(self.rules)),
+            load_shedder: self.load_shedding.map(|config| std::sync::Mutex::new(LoadShedder::new(config))),
+            load_events: broadcast::channel(16).0,
+            slos: std::sync::Mutex::new(SloTracker::new(self.slos)),
+            slo_events: broadcast::channel(16).0,
+            alerts: alerts.is_some().then_some(alerts_tx),
+            profiles: std::sync::Mutex::new(profiles),
+            checkpoints: Mutex::new(HashMap::new()),
+            audit: std::sync::Mutex::new(AuditLog::new(audit, self.audit_capacity)),
+            history: std::sync::Mutex::new(history),
+            robot_seen: std::sync::Mutex::new(HashMap::new()),
+            offline_after: self.offline_after,
+            upload_ttl: self.upload_ttl,
+            task_retention: self.task_retention,
+            auto_charging: self.auto_charging,
+            retry_policy: self.retry_policy,
+            breakers: self.circuit_breaker.map(|config| std::sync::Mutex::new(Breakers::new(config))),
+            batteries: std::sync::Mutex::new(Batteries::default()),
+            robot_slots: std::sync::Mutex::new(robot_slots),
+            robot_models: std::sync::Mutex::new(robot_models),
+            compatibility: std::sync::RwLock::new(Arc::new(self.compatibility)),
+            geofence: std::sync::RwLock::new(Arc::new(self.geofence)),
+            robot_queues: std::sync::Mutex::new(RobotQueues::new(queue_ordering.clone())),
+            delayed: std::sync::Mutex::new(TimerWheel::new(self.timer_tick.as_millis() as u64)),
+            steal_policy: self.steal_policy,
+            draining: std::sync::Mutex::new(Default::default()),
+            preemption: self.preemption,
+            suspensions: std::sync::Mutex::new(Suspensions::default()),
+            mutex_groups: std::sync::Mutex::new(MutexGroups::default()),
+            resource_locks: std::sync::Mutex::new(ResourceLocks::default()),
+            coalitions: std::sync::Mutex::new(Coalitions::default()),
+            quotas: std::sync::Mutex::new(quotas),
+            submitters: std::sync::Mutex::new(submitters),
+            shadow: std::sync::Mutex::new(None),
+            ready_checks: self.ready_check.map(|check| std::sync::Mutex::new(ReadyChecks::new(check))),
+            auctions: self.auction.map(|config| std::sync::Mutex::new(Auctions::new(config))),
+            assignment: self.assignment,
+            assignment_scorer: self.assignment_scorer,
+            distance: self.distance,
+            robot_locations: std::sync::Mutex::new(HashMap::new()),
+            validation: validation_tx,
+            dispatch_gate: DispatchGate::new(self.backpressure, self.task_channel_size),
+            shutdown: tokio::sync::watch::Sender::new(false),
+            dispatcher: self
+                .transport
+                .map(|transport| Dispatcher::new(transport, self.control_delivery, self.assignment_lane_size, epoch, self.replay_window)),
+        };
+        let scheduler = Scheduler { core: Arc::new(core), actor: None };
+        let webhooks = (!self.webhooks.is_empty()).then(|| WebhookDispatcher {
+            scheduler: scheduler.clone(),
+            events: scheduler.subscribe(),
+            webhooks: self.webhooks,
+            transport: self.webhook_transport,
+        });
+        let quota_flush_interval = self.quota_flush_interval;
+        let workers = SchedulerWorkers {
+            scheduler: scheduler.clone(),
+            rx,
+            urgent_rx,
+            webhooks,
+            quota_flush_interval,
+            reconcile_interval: self.reconcile_interval,
+            compaction_interval: self.compaction_interval,
+            timer_tick: self.timer_tick,
+            recovered,
+            alerts,
+            validation,
+        };
+        Ok((scheduler, workers))
+    }
+}
+
+// Background workers of a Scheduler; run them on a Tokio runtime to process tasks
+pub struct SchedulerWorkers {
+    scheduler: Scheduler,
+    pub(crate) rx: mpsc::Receiver<Task>,
+    urgent_rx: mpsc::Receiver<Task>,
+    webhooks: Option<WebhookDispatcher>,
+    quota_flush_interval: Duration,
+    reconcile_interval: Option<Duration>,
+    compaction_interval: Option<Duration>,
+    timer_tick: Duration,
+    recovered: Vec<u32>, // Unfinished tasks reloaded from the store, oldest submission first
+    alerts: Option<AlertRouter>,
+    validation: Option<(mpsc::Receiver<ValidationJob>, usize)>, // Validation queue and worker count
+}
+
+impl SchedulerWorkers {
+    // Run the execution loop; it runs until `Scheduler::shutdown`, which also stops the
+    // periodic loops started here
+    pub async fn run(self) {
+        // Queue unfinished tasks reloaded from the store alongside new submissions
+        tokio::spawn(recover(self.scheduler.clone(), self.recovered));
+        if let Some(webhooks) = self.webhooks {
+            tokio::spawn(webhooks.run());
+        }
+        if let Some(alerts) = self.alerts {
+            tokio::spawn(alerts.run());
+        }
+        let mut loops = Vec::new();
+        if self.scheduler.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).is_enabled() {
+            loops.push(tokio::spawn(flush_quotas(self.scheduler.clone(), self.quota_flush_interval)));
+        }
+        if let Some(interval) = self.reconcile_interval {
+            loops.push(tokio::spawn(reconcile(self.scheduler.clone(), interval)));
+        }
+        if let Some(interval) = self.compaction_interval {
+            loops.push(tokio::spawn(compact_store(self.scheduler.clone(), interval)));
+        }
+        if let Some((rx, workers)) = self.validation {
+            loops.push(tokio::spawn(run_validators(self.scheduler.clone(), rx, workers)));
+        }
+        if self.scheduler.core.retry_policy.is_some_and(|p| p.reassign_offline) {
+            loops.push(tokio::spawn(reassign_offline(self.scheduler.clone(), self.scheduler.core.offline_after / 2)));
+        }
+        loops.push(tokio::spawn(expire_uploads(self.scheduler.clone(), self.scheduler.core.upload_ttl / 4)));
+        loops.push(tokio::spawn(release_delayed(self.scheduler.clone(), self.timer_tick)));
+        self.scheduler.process_tasks(self.rx, self.urgent_rx).await;
+        for handle in loops {
+            handle.abort();
+        }
+    }
+
+    // Spawn the execution loop on the current Tokio runtime
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+}
+
+// Periodically persist changed quota counters
+async fn flush_quotas(scheduler: Scheduler, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        if let Err(e) = scheduler.flush_quotas() {
+            tracing::warn!(error = %e, "could not flush quota counters");
+        }
+    }
+}
+
+// Periodically fail the tasks of robots gone offline so the retry policy reassigns them
+async fn reassign_offline(scheduler: Scheduler, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        scheduler.reassign_offline_tasks().await;
+    }
+}
+
+// Periodically discard uploads abandoned before their commit
+async fn expire_uploads(scheduler: Scheduler, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        scheduler.expire_uploads().await;
+    }
+}
+
+// Put unfinished tasks reloaded from the store back in the queue
+async fn recover(scheduler: Scheduler, task_ids: Vec<u32>) {
+    scheduler.recover_tasks(task_ids).await;
+}
+
+// Turn the timer wheel, sending delayed tasks whose start time has come to dispatch
+async fn release_delayed(scheduler: Scheduler, tick: Duration) {
+    let mut ticker = tokio::time::interval(tick);
+    loop {
+        ticker.tick().await;
+        scheduler.release_delayed();
+    }
+}
+
+// Periodically release reservations left behind by vanished tasks
+async fn reconcile(scheduler: Scheduler, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        scheduler.reconcile_reservations().await;
+    }
+}
+
+// Periodically drop finished tasks past their retention and compact the store
+async fn compact_store(scheduler: Scheduler, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        match scheduler.compact_store().await {
+            Ok(dropped) if !dropped.is_empty() => tracing::info!(dropped = dropped.len(), "dropped finished tasks past their retention"),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "could not compact the store"),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_builder_store_and_hooks() {
+        let store = Arc::new(MemoryStore::new());
+        store.save_robot("Ford", &["heavy_lifting".to_string()]).unwrap();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = seen.clone();
+
+        let (scheduler, _workers) = Scheduler::builder()
+            .store(store.clone())
+            .task_channel_size(4)
+            .on_transition(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .build()
+            .unwrap();
+
+        // Robot restored from the store, so the bound task passes validation
+        let task = Task {
+            id: 11,
+            task_type: "heavy_lifting".to_string(),
+            priority: 1,
+            deadline: None,
+            robot_id: Some("Ford".to_string()),
+            required_capabilities: vec!["heavy_lifting".to_string()],
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+        assert!(store.load_task(11).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_builder_rejects_zero_channel() {
+        assert!(SchedulerBuilder::new().task_channel_size(0).build().is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// backend/rust/src/capacity.rs
+// Purpose: Capacity planning for MRTODP. Projects when each robot's slots free up from the
+// estimated durations of the tasks it is running and the tasks queued for it, and so when a
+// new task placed on the robot would finish. A task's duration is its own
+// `estimated_duration_ms`, else the robot's average for the task type from its profile,
+// else nothing. Running tasks are projected from their dispatch time and count as ending
+// now once overdue; queued tasks take the earliest free slot in turn. With
+// `earliest_finish` set in the assignment config, tasks submitted without a robot go to the
+// one with the earliest projected finish instead of the least loaded one, and
+// `Scheduler::capacity_plan` shows the projections to operators.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::profiles::RobotProfiles;
+use crate::scheduler::{TaskRecord, TaskState};
+use crate::task::Task;
+
+// Projected workload of one robot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RobotCapacity {
+    pub robot_id: String,
+    pub slots: u32,
+    pub running: usize,
+    pub queued: usize,
+    pub next_free_at: u64, // When a slot is projected to be free for new work (Unix milliseconds)
+    pub idle_at: u64,      // When all running and queued work is projected to be done
+}
+
+// Expected execution time of a task on a robot
+pub(crate) fn estimate(task: &Task, robot_id: Option<&str>, profiles: &RobotProfiles) -> u64 {
+    if let Some(estimate) = task.estimated_duration_ms {
+        return estimate;
+    }
+    robot_id
+        .and_then(|id| profiles.get(id))
+        .and_then(|p| p.durations.get(&task.task_type))
+        .map_or(0, |stats| stats.average_ms())
+}
+
+// Projected end of every assigned or running task, per robot
+pub(crate) fn running_ends<'a>(records: &'a HashMap<u32, TaskRecord>, profiles: &RobotProfiles, now: u64) -> HashMap<&'a str, Vec<u64>> {
+    let mut ends: HashMap<&str, Vec<u64>> = HashMap::new();
+    for record in records.values().filter(|r| r.state.is_active()) {
+        let Some(attempt) = record.attempts.last() else {
+            continue;
+        };
+        let Some(robot_id) = attempt.robot_id.as_deref() else {
+            continue;
+        };
+        let started = attempt.transitions.iter().rev().find(|t| t.from == Some(TaskState::Pending)).map_or(now, |t| t.at);
+        let end = started.saturating_add(estimate(&record.task, Some(robot_id), profiles));
+        ends.entry(robot_id).or_default().push(end.max(now));
+    }
+    ends
+}
+
+// When each of a robot's slots frees up, earliest first, once its running tasks end and its
+// queued tasks (durations in dispatch order) have run
+pub(crate) fn project(now: u64, slots: u32, running_ends: &[u64], queued: &[u64]) -> Vec<u64> {
+    let slots = slots.max(1) as usize;
+    let mut free: Vec<u64> = running_ends.iter().map(|&end| end.max(now)).collect();
+    free.sort_unstable();
+    // Over capacity (e.g. slots lowered while busy): the earliest ends free no slot
+    if free.len() > slots {
+        free.drain(..free.len() - slots);
+    }
+    free.resize(slots, now);
+    for &duration in queued {
+        free.sort_unstable();
+        free[0] = free[0].saturating_add(duration);
+    }
+    free.sort_unstable();
+    free
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::assignment::AssignmentConfig;
+    use crate::scheduler::Scheduler;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter, MockClock};
+
+    #[tokio::test]
+    async fn test_assignment_picks_earliest_projected_finish() {
+        assert_eq!(project(100, 2, &[50, 400], &[200, 100]), vec![400, 400]);
+        assert_eq!(project(100, 1, &[300, 250], &[]), vec![300]);
+
+        let start = 1_767_225_600_000;
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let config = AssignmentConfig { earliest_finish: true, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder()
+            .clock(Arc::new(MockClock::new(start)))
+            .transport(fake.clone())
+            .assignment(config)
+            .build()
+            .unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let task = |id: u32, robot_id: Option<&str>, estimate: u64| Task {
+            id,
+            robot_id: robot_id.map(str::to_string),
+            estimated_duration_ms: Some(estimate),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task(326, Some("Ford"), 60_000)).await.unwrap();
+        scheduler.schedule_task(task(327, Some("Scion"), 1_000)).await.unwrap();
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Both robots are equally loaded; Scion finishes its current task first
+        scheduler.schedule_task(task(328, None, 500)).await.unwrap();
+        while scheduler.robot_queue_depths().is_empty() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.task_record(328).await.unwrap().task.robot_id.as_deref(), Some("Scion"));
+        let plan = scheduler.capacity_plan().await;
+        assert_eq!(plan.iter().map(|c| (c.robot_id.as_str(), c.next_free_at)).collect::<Vec<_>>(), vec![("Ford", start + 60_000), ("Scion", start + 1_500)]);
+        assert_eq!((plan[1].running, plan[1].queued, plan[1].idle_at), (1, 1, start + 1_500));
+    }
+}
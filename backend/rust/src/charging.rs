@@ -0,0 +1,205 @@
+// backend/rust/src/charging.rs
+// Purpose: Battery tracking and automatic charging for MRTODP robots. Robots (or their
+// transports) report their battery level with `Scheduler::report_battery`; the latest
+// level of each robot is shown in the fleet status. With `auto_charging` set on the
+// builder, a report below the threshold injects a high-priority `charge` task bound to the
+// robot, unless it already has one. The charge task takes the robot's next free slot ahead
+// of its queued work, and every other task routed to the robot is deferred in its queue
+// until the charge task finishes (completed, failed, or cancelled); the deferred tasks are
+// then dispatched again. Charge task IDs count down from 0xFFF0_0000, below the self-test
+// IDs (see readiness.rs), skipping IDs already in use.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::audit::AuditAction;
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+
+// Task type of the charge tasks injected for robots low on battery
+pub const CHARGE_TASK_TYPE: &str = "charge";
+
+// First ID handed to an injected charge task
+const FIRST_CHARGE_TASK_ID: u32 = 0xFFF0_0000;
+
+// Automatic charging settings
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct AutoCharging {
+    pub threshold: f64, // Battery percentage below which a robot is sent to charge
+    pub priority: u32,  // Priority of the injected charge task
+}
+
+impl Default for AutoCharging {
+    fn default() -> Self {
+        AutoCharging { threshold: 20.0, priority: 100 }
+    }
+}
+
+impl AutoCharging {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(self.threshold > 0.0 && self.threshold <= 100.0) {
+            return Err("Charging threshold must be above 0 and at most 100 percent".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Latest battery level of each robot and the robots sent to charge
+pub(crate) struct Batteries {
+    levels: HashMap<String, f64>,    // robot_id -> battery percentage
+    charging: HashMap<String, u32>,  // robot_id -> its unfinished charge task
+    next_task_id: u32,
+}
+
+impl Default for Batteries {
+    fn default() -> Self {
+        Batteries { levels: HashMap::new(), charging: HashMap::new(), next_task_id: FIRST_CHARGE_TASK_ID }
+    }
+}
+
+impl Batteries {
+    pub(crate) fn level(&self, robot_id: &str) -> Option<f64> {
+        self.levels.get(robot_id).copied()
+    }
+
+    pub(crate) fn charging_task(&self, robot_id: &str) -> Option<u32> {
+        self.charging.get(robot_id).copied()
+    }
+
+    // Next free charge task ID; `taken` reports IDs already in use
+    fn next_id(&mut self, taken: impl Fn(u32) -> bool) -> u32 {
+        let mut id = self.next_task_id;
+        while taken(id) {
+            id = id.wrapping_sub(1);
+        }
+        self.next_task_id = id.wrapping_sub(1);
+        id
+    }
+}
+
+impl Scheduler {
+    // Record a robot's battery level (0 to 100 percent). With automatic charging enabled, a
+    // level below the threshold sends the robot to charge; returns the injected charge
+    // task's ID, or None if none was needed or one is already under way.
+    pub async fn report_battery(&self, robot_id: &str, level: f64) -> Result<Option<u32>, String> {
+        if !(0.0..=100.0).contains(&level) {
+            return Err(format!("Battery level must be between 0 and 100 percent, not {}", level));
+        }
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.robot_heartbeat(robot_id);
+        self.core.batteries.lock().unwrap_or_else(|e| e.into_inner()).levels.insert(robot_id.to_string(), level);
+        match self.core.auto_charging {
+            Some(config) if level < config.threshold && !self.is_shutting_down() => self.inject_charge(robot_id, level, config).await,
+            _ => Ok(None),
+        }
+    }
+
+    // Latest battery level a robot reported
+    pub fn battery_level(&self, robot_id: &str) -> Option<f64> {
+        self.core.batteries.lock().unwrap_or_else(|e| e.into_inner()).level(robot_id)
+    }
+
+    // Unfinished charge task of a robot sent to charge
+    pub(crate) fn charging_task(&self, robot_id: &str) -> Option<u32> {
+        self.core.batteries.lock().unwrap_or_else(|e| e.into_inner()).charging_task(robot_id)
+    }
+
+    async fn inject_charge(&self, robot_id: &str, level: f64, config: AutoCharging) -> Result<Option<u32>, String> {
+        let (task, event) = {
+            let mut records = self.core.records.lock().await;
+            let mut batteries = self.core.batteries.lock().unwrap_or_else(|e| e.into_inner());
+            if batteries.charging.contains_key(robot_id) {
+                return Ok(None);
+            }
+            let task = Task {
+                id: batteries.next_id(|id| self.id_taken(&records, id)),
+                task_type: CHARGE_TASK_TYPE.to_string(),
+                priority: config.priority,
+                robot_id: Some(robot_id.to_string()),
+                expedite: true,
+                ..Default::default()
+            };
+            let (mut record, event) = self.submitted_record(&task);
+            record.pinned = true;
+            self.persist(&mut record)?;
+            batteries.charging.insert(robot_id.to_string(), task.id);
+            drop(batteries);
+            records.insert(task.id, record);
+            (task, event)
+        };
+        self.publish(event);
+        let detail = format!("Charge task {} injected for robot {} at {}% battery", task.id, robot_id, level);
+        self.audit(AuditAction::TaskScheduled, Some(task.id), Some(robot_id.to_string()), None, detail);
+        let task_id = task.id;
+        self.admit(task).await?;
+        Ok(Some(task_id))
+    }
+
+    // A robot's charge task finished; dispatch the work deferred while it charged. Called
+    // under the records lock.
+    pub(crate) fn charge_finished(&self, task: &Task) {
+        let Some(robot_id) = task.robot_id.as_deref().filter(|_| task.task_type == CHARGE_TASK_TYPE) else {
+            return;
+        };
+        {
+            let mut batteries = self.core.batteries.lock().unwrap_or_else(|e| e.into_inner());
+            if batteries.charging.get(robot_id) != Some(&task.id) {
+                return;
+            }
+            batteries.charging.remove(robot_id);
+        }
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let deferred = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).release(robot_id, policy.as_ref(), |_| true);
+        tracing::info!(robot_id = %robot_id, deferred = deferred.len(), "robot finished charging; dispatching deferred tasks");
+        self.dispatch_released(deferred);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::TaskState;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[tokio::test]
+    async fn test_low_battery_injects_charge_and_defers_work() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).auto_charging(AutoCharging::default()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 3]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        let pinned = |id: u32| Task { id, robot_id: Some("Ford".to_string()), ..Default::default() };
+        scheduler.schedule_task(pinned(388)).await.unwrap();
+        wait_for_state(388, TaskState::Running).await;
+        scheduler.schedule_task(pinned(389)).await.unwrap();
+
+        // Only the first low report sends Ford to charge; a healthy one never does
+        assert_eq!(scheduler.report_battery("Ford", 55.0).await.unwrap(), None);
+        let charge = scheduler.report_battery("Ford", 15.0).await.unwrap().unwrap();
+        assert_eq!(charge, FIRST_CHARGE_TASK_ID);
+        assert_eq!(scheduler.report_battery("Ford", 14.0).await.unwrap(), None);
+        assert!(scheduler.report_battery("Ford", 140.0).await.is_err());
+        assert_eq!(scheduler.battery_level("Ford"), Some(14.0));
+
+        // The charge task goes ahead of the queued task, which waits until charging ends
+        scheduler.report_result(388, Ok(())).await;
+        wait_for_state(charge, TaskState::Running).await;
+        assert_eq!(scheduler.task_record(389).await.unwrap().state, TaskState::Pending);
+        scheduler.report_result(charge, Ok(())).await;
+        wait_for_state(389, TaskState::Running).await;
+        assert_eq!(scheduler.charging_task("Ford"), None);
+    }
+}
@@ -0,0 +1,47 @@
+// backend/rust/src/checkpoints.rs
+// Purpose: Named save points of fleet state for MRTODP. A checkpoint captures the robot
+// registry and every pending task (including hold flags) so operators can snapshot before
+// a risky bulk operation, such as a mass cancellation or policy change, and roll back.
+// Tasks that ran or finished since the checkpoint are left alone on restore.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::scheduler::TaskRecord;
+
+// Snapshot of the registry and pending queue
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Checkpoint {
+    pub name: String,
+    pub created_at: u64, // Unix timestamp (milliseconds)
+    pub robots: HashMap<String, Vec<String>>, // robot_id -> capabilities
+    pub pending: Vec<TaskRecord>, // Sorted by task ID
+}
+
+// Summary of a checkpoint without its contents
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointInfo {
+    pub name: String,
+    pub created_at: u64,
+    pub robots: usize,
+    pub pending: usize,
+}
+
+impl Checkpoint {
+    pub fn info(&self) -> CheckpointInfo {
+        CheckpointInfo {
+            name: self.name.clone(),
+            created_at: self.created_at,
+            robots: self.robots.len(),
+            pending: self.pending.len(),
+        }
+    }
+}
+
+// What a restore changed
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestoreReport {
+    pub requeued: Vec<u32>, // Failed or expired since the checkpoint; back to Pending
+    pub hold_reset: Vec<u32>, // Still pending; hold flag set back to its checkpointed value
+    pub skipped: Vec<u32>, // Running or completed since the checkpoint; left as is
+    pub robots: usize, // Registrations restored
+}
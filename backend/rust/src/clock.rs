@@ -0,0 +1,84 @@
+// backend/rust/src/clock.rs
+// Purpose: Time source abstraction for the MRTODP scheduler. Deadlines and transition
+// timestamps are Unix milliseconds, so the scheduler reads wall-clock time through the
+// `Clock` trait, letting embedders supply their own time source via the builder. Every
+// deadline check, expiry, and recorded timestamp goes through it; durations between two
+// readings saturate at zero, so a clock stepping backward yields empty phases and queue
+// waits rather than wrapping around. Relative delays (auction windows, ready-check timeouts,
+// escalation budgets, background intervals) run on Tokio's monotonic timer instead and are
+// unaffected by wall-clock steps. Tests drive time with `test_utils::MockClock`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Source of wall-clock time in Unix milliseconds
+pub trait Clock: Send + Sync {
+    fn now_millis(&self) -> u64;
+}
+
+// Clock backed by the operating system's wall-clock time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::{Scheduler, TaskState};
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_deadlines_and_timestamps_follow_clock_under_skew() {
+        let start = 1_767_225_600_000;
+        assert!(SystemClock.now_millis() > start);
+        let clock = Arc::new(MockClock::new(start));
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).build().unwrap();
+        let task = |id: u32, deadline: u64| Task { id, deadline: Some(deadline), ..Default::default() };
+        scheduler.schedule_task(task(314, start + 1_000)).await.unwrap();
+        scheduler.schedule_task(task(315, start + 60_000)).await.unwrap();
+
+        // The clock jumps forward past 314's deadline, then NTP steps it back ten minutes
+        clock.advance(Duration::from_secs(5));
+        clock.rewind(Duration::from_secs(600));
+        scheduler.schedule_task(task(316, start - 1)).await.unwrap();
+        workers.spawn();
+        let finished = |task_id: u32| {
+            let scheduler = scheduler.clone();
+            async move {
+                loop {
+                    match scheduler.task_record(task_id).await {
+                        Some(record) if record.state.is_terminal() => return record,
+                        _ => tokio::time::sleep(Duration::from_millis(1)).await,
+                    }
+                }
+            }
+        };
+
+        // Deadlines are compared with the clock as it reads at dispatch, so 314 still runs,
+        // and its queue wait across the backward step is zero rather than wrapping around
+        let early = finished(314).await;
+        assert_eq!(early.state, TaskState::Completed);
+        assert_eq!(early.attempts[0].transitions[0].at, start);
+        assert_eq!(early.attempts[0].transitions[1].at, start - 595_000);
+        let phases = early.phases.unwrap();
+        assert_eq!((phases.queue_wait_ms, phases.total_ms), (Some(0), 0));
+        assert_eq!(finished(315).await.state, TaskState::Completed);
+        assert_eq!(finished(316).await.state, TaskState::Completed);
+
+        // Once the clock passes 317's deadline, the task expires
+        clock.set(start + 60_001);
+        scheduler.schedule_task(task(317, start + 60_000)).await.unwrap();
+        assert_eq!(finished(317).await.state, TaskState::Expired);
+    }
+}
@@ -0,0 +1,300 @@
+// backend/rust/src/coalitions.rs
+// Purpose: Multi-robot coalition tasks for MRTODP. Some work needs several robots acting
+// together, e.g. a cooperative lift. A task with a `coalition` states how many robots it
+// needs and, optionally, the capabilities each of their slots requires on top of the
+// task's own. The executor dispatches such a task only once a full set of eligible robots
+// is free at the same time (a pinned robot must be one of them); until then the task
+// waits, and waiting coalitions are tried again whenever a robot frees a slot or
+// registers. Every member holds a slot while the task is assigned or running; the pinned
+// robot, or else the first member, leads the coalition and is the task's `robot_id`. With
+// a transport, each member is sent the assignment and the task stays Assigned until all
+// of them accepted; they are then sent `ControlCommand::Start` together. The first report
+// from any member ends the task, and a failure or cancellation aborts every member.
+
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{active_per_robot, ReasonCode, Scheduler, TaskRecord, TaskState};
+use crate::task::Task;
+use crate::transport::ControlCommand;
+
+// Robots a task needs at once and what each of them must be able to do
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coalition {
+    pub required_robots: u32, // Robots acting together; at least 2
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slot_capabilities: Vec<Vec<String>>, // Capabilities per slot beyond the task's; empty or one entry per robot
+}
+
+impl Coalition {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.required_robots < 2 {
+            return Err(format!("A coalition needs at least 2 robots, not {}", self.required_robots));
+        }
+        if !self.slot_capabilities.is_empty() && self.slot_capabilities.len() != self.required_robots as usize {
+            return Err(format!(
+                "A coalition of {} robots needs capabilities for every slot or none, not {}",
+                self.required_robots,
+                self.slot_capabilities.len()
+            ));
+        }
+        Ok(())
+    }
+
+    // A distinct robot for every slot among `free` (robot_id and capabilities, most
+    // preferred first), including `pinned` if given; None if no full set is free
+    pub(crate) fn select(&self, free: &[(&str, &[String])], pinned: Option<&str>) -> Option<Vec<String>> {
+        let slots: Vec<&[String]> = (0..self.required_robots as usize).map(|i| self.slot_capabilities.get(i).map_or(&[][..], Vec::as_slice)).collect();
+        let mut members = Vec::new();
+        fill(&slots, free, pinned, &mut members).then_some(members)
+    }
+}
+
+// Backtrack over the slots in order, trying free robots in preference order
+fn fill(slots: &[&[String]], free: &[(&str, &[String])], pinned: Option<&str>, members: &mut Vec<String>) -> bool {
+    let Some((slot, rest)) = slots.split_first() else {
+        return pinned.is_none_or(|p| members.iter().any(|m| m == p));
+    };
+    for (robot_id, caps) in free {
+        if members.iter().any(|m| m == robot_id) || !slot.iter().all(|c| caps.contains(c)) {
+            continue;
+        }
+        members.push(robot_id.to_string());
+        if fill(rest, free, pinned, members) {
+            return true;
+        }
+        members.pop();
+    }
+    false
+}
+
+// Coalition tasks waiting for robots and the acceptances of those being assigned
+#[derive(Default)]
+pub(crate) struct Coalitions {
+    waiting: Vec<Task>,                      // Tasks without a full set of free robots, oldest first
+    accepted: HashMap<u32, HashSet<String>>, // task_id -> members that accepted their assignment
+}
+
+impl Coalitions {
+    // Drop a waiting task, e.g. because it was cancelled
+    pub(crate) fn withdraw(&mut self, task_id: u32) {
+        self.waiting.retain(|t| t.id != task_id);
+    }
+}
+
+impl Scheduler {
+    // Coalition tasks waiting for a full set of free robots, oldest first
+    pub fn waiting_coalitions(&self) -> Vec<u32> {
+        self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).waiting.iter().map(|t| t.id).collect()
+    }
+
+    // Hand a coalition task to a full set of free robots, or park it until one frees up.
+    // Runs once the task holds its mutex group and resources.
+    pub(crate) async fn dispatch_coalition(&self, task: Task) {
+        let Some(coalition) = &task.coalition else {
+            return;
+        };
+        let members = {
+            // Check and park under the records lock so a freed slot can't miss the task
+            let caps = self.core.capabilities.lock().await;
+            let mut records = self.core.records.lock().await;
+            let active = active_per_robot(&records);
+            let mut free: Vec<(&str, &[String])> = caps
+                .iter()
+                .filter(|(id, robot_caps)| {
+                    task.required_capabilities.iter().all(|c| robot_caps.contains(c))
+                        && active.get(id.as_str()).copied().unwrap_or(0) < self.robot_slots(id)
+                        && !self.is_draining(id)
+                        && self.charging_task(id).is_none()
+                        && self.check_compatibility(&task.task_type, id).is_ok()
+                        && self.check_zone(&task, id).is_ok()
+                })
+                .map(|(id, robot_caps)| (id.as_str(), robot_caps.as_slice()))
+                .collect();
+            // The pinned robot first, then by ID
+            free.sort_by_key(|(id, _)| (Some(*id) != task.robot_id.as_deref(), *id));
+            let Some(members) = coalition.select(&free, task.robot_id.as_deref()) else {
+                tracing::debug!(free = free.len(), "waiting for a full coalition of free robots");
+                self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).waiting.push(task);
+                return;
+            };
+            let Some(record) = records.get_mut(&task.id).filter(|r| !r.state.is_terminal()) else {
+                return;
+            };
+            let leader = task.robot_id.clone().unwrap_or_else(|| members[0].clone());
+            let selected = self.update(record, |next| {
+                next.coalition_members = members.clone();
+                next.task.robot_id = Some(leader.clone());
+                if let Some(attempt) = next.attempts.last_mut() {
+                    attempt.robot_id = Some(leader);
+                }
+            });
+            if selected.is_err() {
+                // Tried again with the next coalition that frees up
+                self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).waiting.push(task);
+                return;
+            }
+            members
+        };
+        tracing::Span::current().record("robot_id", members.join(",").as_str());
+        let Some(dispatcher) = &self.core.dispatcher else {
+            // Simulated: the members start and finish together at once
+            let picked_up = format!("Picked up by executor with coalition of robots {}", members.join(", "));
+            if let Err(e) = self.transition_task(task.id, TaskState::Running, ReasonCode::Dispatched, picked_up).await {
+                tracing::warn!(error = %e, "could not start task");
+                return;
+            }
+            tracing::info!("processing task");
+            self.transition(task.id, TaskState::Completed, ReasonCode::CompletedOk, "Execution finished".to_string()).await;
+            return;
+        };
+        let detail = format!("Assigned to coalition of robots {}", members.join(", "));
+        if let Err(e) = self.transition_task(task.id, TaskState::Assigned, ReasonCode::Dispatched, detail).await {
+            tracing::warn!(error = %e, "could not assign task");
+            return;
+        }
+        // Members accept through the delivery loop; the last acceptance starts them all
+        for robot_id in &members {
+            let member_task = Task { robot_id: Some(robot_id.clone()), ..task.clone() };
+            let assigned = match self.core.frames.localize(&member_task, robot_id) {
+                Ok(local) => dispatcher.assign(self, robot_id, local).await,
+                Err(e) => Err(format!("Cannot localize task for robot {}: {}", robot_id, e)),
+            };
+            if let Err(e) = assigned {
+                tracing::warn!(error = %e, "hand-off to coalition member failed");
+                self.transition(task.id, TaskState::Failed, ReasonCode::FailedRobotError, e).await;
+                return;
+            }
+        }
+        tracing::info!("handed off to coalition");
+        let handed_off_at = self.core.clock.now_millis();
+        if let Some(record) = self.core.records.lock().await.get_mut(&task.id) {
+            record.marks.handed_off_at.get_or_insert(handed_off_at);
+        }
+    }
+
+    // A member accepted its assignment; once every member has, the task runs and all of
+    // them are told to start
+    pub(crate) async fn coalition_member_accepted(&self, task_id: u32, robot_id: &str, members: Vec<String>) {
+        {
+            let mut coalitions = self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner());
+            let accepted = coalitions.accepted.entry(task_id).or_default();
+            accepted.insert(robot_id.to_string());
+            if !members.iter().all(|m| accepted.contains(m)) {
+                return;
+            }
+            coalitions.accepted.remove(&task_id);
+        }
+        let detail = format!("All {} coalition robots accepted assignment", members.len());
+        if let Err(e) = self.transition_task(task_id, TaskState::Running, ReasonCode::Accepted, detail).await {
+            tracing::warn!(task_id, error = %e, "could not start coalition task after every member accepted");
+            return;
+        }
+        for robot_id in &members {
+            if let Err(e) = self.send_control(robot_id, ControlCommand::Start { task_id }).await {
+                tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send coalition start");
+            }
+        }
+    }
+
+    // A coalition task stopped holding its robots: free every member's slot (the caller
+    // frees the slot of `freed_leader`), abort the members of a failed or requeued task, and
+    // try the waiting coalitions again. Called under the records lock.
+    pub(crate) fn coalition_finished(&self, record: &mut TaskRecord, to: TaskState, freed_leader: Option<&str>) {
+        let task_id = record.task.id;
+        let members = std::mem::take(&mut record.coalition_members);
+        self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).accepted.remove(&task_id);
+        if matches!(to, TaskState::Failed | TaskState::Pending) && self.core.dispatcher.is_some() {
+            let (scheduler, members) = (self.clone(), members.clone());
+            tokio::spawn(async move {
+                for robot_id in members {
+                    if let Err(e) = scheduler.send_control(&robot_id, ControlCommand::Abort { task_id }).await {
+                        tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send abort to coalition member");
+                    }
+                }
+            });
+        }
+        if self.is_shutting_down() {
+            return;
+        }
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let now = self.core.clock.now_millis();
+        let released: Vec<Task> = {
+            let mut queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+            members.iter().filter(|m| Some(m.as_str()) != freed_leader).filter_map(|m| queues.pop(m, policy.as_ref(), now)).collect()
+        };
+        self.dispatch_released(released);
+        self.retry_coalitions();
+    }
+
+    // Send the waiting coalition tasks through dispatch again, e.g. after a slot freed up
+    pub(crate) fn retry_coalitions(&self) {
+        let waiting = std::mem::take(&mut self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).waiting);
+        self.dispatch_released(waiting);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[test]
+    fn test_slots_get_distinct_capable_robots() {
+        let lift = vec!["lift".to_string()];
+        let coalition = Coalition { required_robots: 2, slot_capabilities: vec![lift.clone(), vec![]] };
+        let free: Vec<(&str, &[String])> = vec![("Ford", &[]), ("Scion", &lift), ("Hank", &lift)];
+        assert_eq!(coalition.select(&free, None), Some(vec!["Scion".to_string(), "Ford".to_string()]));
+        assert_eq!(coalition.select(&free, Some("Hank")), Some(vec!["Scion".to_string(), "Hank".to_string()]));
+        assert_eq!(coalition.select(&free[..1], None), None);
+        assert!(Coalition { required_robots: 3, slot_capabilities: vec![lift] }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_coalition_waits_for_a_full_set_and_starts_together() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion", "Hank"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 2]);
+            let caps = if robot_id == "Hank" { vec![] } else { vec!["lift".to_string()] };
+            scheduler.register_robot(robot_id.to_string(), caps).await.unwrap();
+        }
+        let lift = |id: u32, required_robots: u32| Task {
+            id,
+            coalition: Some(Coalition { required_robots, slot_capabilities: vec![vec!["lift".to_string()]; required_robots as usize] }),
+            ..Default::default()
+        };
+        assert!(scheduler.schedule_task(lift(396, 1)).await.is_err());
+        scheduler.schedule_task(Task { id: 397, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while scheduler.task_record(397).await.is_none_or(|r| r.state != TaskState::Running) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Hank can't lift and Ford is busy, so the lift waits for Ford
+        scheduler.schedule_task(lift(398, 2)).await.unwrap();
+        while scheduler.waiting_coalitions().is_empty() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 397)]);
+        scheduler.report_result(397, Ok(())).await;
+        while scheduler.task_record(398).await.is_none_or(|r| r.state != TaskState::Running) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let record = scheduler.task_record(398).await.unwrap();
+        assert_eq!(record.coalition_members, vec!["Ford".to_string(), "Scion".to_string()]);
+        while fake.controls().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let mut started: Vec<(String, ControlCommand)> = fake.controls().into_iter().map(|c| (c.robot_id, c.command)).collect();
+        started.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(started, vec![("Ford".to_string(), ControlCommand::Start { task_id: 398 }), ("Scion".to_string(), ControlCommand::Start { task_id: 398 })]);
+
+        // Finishing frees both members
+        scheduler.report_result(398, Ok(())).await;
+        assert!(scheduler.task_record(398).await.unwrap().coalition_members.is_empty());
+    }
+}
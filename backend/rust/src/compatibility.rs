@@ -0,0 +1,168 @@
+// backend/rust/src/compatibility.rs
+// Purpose: Task type × robot model/firmware compatibility matrix for MRTODP. Robots declare
+// their hardware model and firmware version; the matrix, loaded from site configuration,
+// lists which models (and which firmware range of each) are certified to run a task type.
+// Task types the matrix doesn't mention are unrestricted. A restricted type only goes to
+// robots with a certified model and firmware, checked at submission, when pinning, when
+// picking a robot for an unassigned task, and again at dispatch in case a robot's firmware
+// changed while the task was queued.
+//
+// Example: {"entries": [{"task_type": "welding", "model": "UR10", "min_firmware": "5.2"}]}
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+// Hardware model and firmware version a robot reports
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RobotModel {
+    pub model: String,
+    pub firmware: String, // Dotted numeric version, e.g. "5.2.1"
+}
+
+impl RobotModel {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.is_empty() {
+            return Err("Robot model must not be empty".to_string());
+        }
+        parse_firmware(&self.firmware).map(|_| ())
+    }
+}
+
+// One certified (task type, model) pair with an optional inclusive firmware range
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompatibilityEntry {
+    pub task_type: String,
+    pub model: String,
+    #[serde(default)]
+    pub min_firmware: Option<String>,
+    #[serde(default)]
+    pub max_firmware: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MatrixSpec {
+    #[serde(default)]
+    entries: Vec<CompatibilityEntry>,
+}
+
+// Compare dotted numeric firmware versions; missing trailing components count as zero,
+// so "5.2" and "5.2.0" are the same release
+pub fn compare_firmware(a: &str, b: &str) -> Result<Ordering, String> {
+    let (a, b) = (parse_firmware(a)?, parse_firmware(b)?);
+    let len = a.len().max(b.len());
+    let component = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    Ok((0..len).map(|i| component(&a, i).cmp(&component(&b, i))).find(|o| o.is_ne()).unwrap_or(Ordering::Equal))
+}
+
+fn parse_firmware(version: &str) -> Result<Vec<u64>, String> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().map_err(|_| format!("Invalid firmware version: {:?}", version)))
+        .collect()
+}
+
+// Certified models and firmware ranges per task type; empty = every robot runs everything
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompatibilityMatrix {
+    entries: Vec<CompatibilityEntry>,
+}
+
+impl CompatibilityMatrix {
+    pub fn new(entries: Vec<CompatibilityEntry>) -> Result<Self, String> {
+        for entry in &entries {
+            if entry.task_type.is_empty() || entry.model.is_empty() {
+                return Err("Compatibility entries need a task type and a model".to_string());
+            }
+            for version in [&entry.min_firmware, &entry.max_firmware].into_iter().flatten() {
+                parse_firmware(version)?;
+            }
+            if let (Some(min), Some(max)) = (&entry.min_firmware, &entry.max_firmware) {
+                if compare_firmware(min, max)? == Ordering::Greater {
+                    return Err(format!("Compatibility entry {}/{}: min firmware {} above max {}", entry.task_type, entry.model, min, max));
+                }
+            }
+        }
+        Ok(CompatibilityMatrix { entries })
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, String> {
+        let spec: MatrixSpec = serde_json::from_str(raw).map_err(|e| format!("Invalid compatibility matrix: {}", e))?;
+        Self::new(spec.entries)
+    }
+
+    pub fn entries(&self) -> &[CompatibilityEntry] {
+        &self.entries
+    }
+
+    // Whether a robot may run a task type, and why not if it can't
+    pub fn check(&self, task_type: &str, robot: Option<&RobotModel>) -> Result<(), String> {
+        let certified: Vec<&CompatibilityEntry> = self.entries.iter().filter(|e| e.task_type == task_type).collect();
+        if certified.is_empty() {
+            return Ok(());
+        }
+        let Some(robot) = robot else {
+            return Err(format!("Task type {} is restricted and the robot hasn't declared its model and firmware", task_type));
+        };
+        let ranges: Vec<&CompatibilityEntry> = certified.into_iter().filter(|e| e.model == robot.model).collect();
+        if ranges.is_empty() {
+            return Err(format!("Model {} is not certified for task type {}", robot.model, task_type));
+        }
+        for entry in &ranges {
+            let above_min = entry.min_firmware.as_deref().map_or(Ok(true), |min| compare_firmware(&robot.firmware, min).map(Ordering::is_ge));
+            let below_max = entry.max_firmware.as_deref().map_or(Ok(true), |max| compare_firmware(&robot.firmware, max).map(Ordering::is_le));
+            if above_min? && below_max? {
+                return Ok(());
+            }
+        }
+        let certified: Vec<String> = ranges
+            .iter()
+            .map(|e| format!("{} to {}", e.min_firmware.as_deref().unwrap_or("any"), e.max_firmware.as_deref().unwrap_or("any")))
+            .collect();
+        Err(format!("Firmware {} of model {} is not certified for task type {} (certified: {})", robot.firmware, robot.model, task_type, certified.join(", ")))
+    }
+}
+
+// Which registered robots can run a task type right now, and why the others can't
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    pub task_type: String,
+    pub compatible: Vec<String>,                // Robot IDs, sorted
+    pub incompatible: BTreeMap<String, String>, // robot_id -> reason
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn robot(model: &str, firmware: &str) -> RobotModel {
+        RobotModel { model: model.to_string(), firmware: firmware.to_string() }
+    }
+
+    #[test]
+    fn test_matrix_restricts_listed_task_types() {
+        let matrix = CompatibilityMatrix::from_json(
+            r#"{"entries": [
+                {"task_type": "welding", "model": "UR10", "min_firmware": "5.2", "max_firmware": "6"},
+                {"task_type": "welding", "model": "KR6", "min_firmware": "1.10"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(compare_firmware("5.2", "5.2.0"), Ok(Ordering::Equal));
+        assert_eq!(compare_firmware("1.10", "1.9"), Ok(Ordering::Greater));
+
+        assert!(matrix.check("navigation", None).is_ok());
+        assert!(matrix.check("welding", Some(&robot("UR10", "5.2.0"))).is_ok());
+        assert!(matrix.check("welding", Some(&robot("UR10", "6.0"))).is_ok());
+        assert!(matrix.check("welding", Some(&robot("KR6", "1.10.3"))).is_ok());
+        let err = matrix.check("welding", Some(&robot("UR10", "6.0.1"))).unwrap_err();
+        assert!(err.contains("certified: 5.2 to 6"), "{}", err);
+        assert!(matrix.check("welding", Some(&robot("Spot", "3.0"))).unwrap_err().contains("not certified"));
+        assert!(matrix.check("welding", None).unwrap_err().contains("hasn't declared"));
+
+        assert!(CompatibilityMatrix::from_json(r#"{"entries": [{"task_type": "welding", "model": "UR10", "min_firmware": "5.x"}]}"#).is_err());
+        assert!(CompatibilityMatrix::from_json(r#"{"entries": [{"task_type": "welding", "model": "UR10", "min_firmware": "6", "max_firmware": "5"}]}"#).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+// backend/rust/src/compression.rs
+// Purpose: Content-encoding negotiation and incremental compression for the MRTODP HTTP
+// event stream (`http` feature). Verbose JSON events compress well, which matters on the
+// constrained uplink between a site and its cloud dashboard. Each event is compressed as it
+// is sent and the encoder flushed, so the client decodes every event on arrival instead of
+// waiting for a full compression block; the flush costs a few bytes per event but keeps the
+// stream live.
+
+use std::io::{self, Write};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+// zstd level for event streams; low levels keep per-event latency down
+const ZSTD_LEVEL: i32 = 3;
+
+// Content encoding of a streamed response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl StreamEncoding {
+    // Choose from an Accept-Encoding header: the highest q-value wins and zstd beats gzip on
+    // a tie; identity if the client accepts neither
+    pub fn negotiate(accept_encoding: &str) -> Self {
+        let (mut gzip, mut zstd, mut any) = (None, None, None);
+        for offer in accept_encoding.split(',') {
+            let mut parts = offer.split(';').map(str::trim);
+            let coding = parts.next().unwrap_or_default().to_ascii_lowercase();
+            let q = parts
+                .filter_map(|p| p.strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            match coding.as_str() {
+                "gzip" | "x-gzip" => gzip = Some(q),
+                "zstd" => zstd = Some(q),
+                "*" => any = Some(q),
+                _ => {}
+            }
+        }
+        let (gzip, zstd) = (gzip.or(any).unwrap_or(0.0), zstd.or(any).unwrap_or(0.0));
+        if zstd > 0.0 && zstd >= gzip {
+            StreamEncoding::Zstd
+        } else if gzip > 0.0 {
+            StreamEncoding::Gzip
+        } else {
+            StreamEncoding::Identity
+        }
+    }
+
+    // Value of the Content-Encoding header; None for identity
+    pub fn content_encoding(&self) -> Option<&'static str> {
+        match self {
+            StreamEncoding::Identity => None,
+            StreamEncoding::Gzip => Some("gzip"),
+            StreamEncoding::Zstd => Some("zstd"),
+        }
+    }
+}
+
+enum Encoder {
+    Identity,
+    Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+// Compresses a response body chunk by chunk, flushing after each
+pub struct StreamEncoder {
+    encoder: Encoder,
+}
+
+impl StreamEncoder {
+    pub fn new(encoding: StreamEncoding) -> io::Result<Self> {
+        let encoder = match encoding {
+            StreamEncoding::Identity => Encoder::Identity,
+            StreamEncoding::Gzip => Encoder::Gzip(GzEncoder::new(Vec::new(), Compression::default())),
+            StreamEncoding::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(Vec::new(), ZSTD_LEVEL)?),
+        };
+        Ok(StreamEncoder { encoder })
+    }
+
+    // Compress one chunk and return the bytes to send, decodable without later chunks
+    pub fn encode(&mut self, chunk: &[u8]) -> io::Result<Vec<u8>> {
+        match &mut self.encoder {
+            Encoder::Identity => Ok(chunk.to_vec()),
+            Encoder::Gzip(gzip) => {
+                gzip.write_all(chunk)?;
+                gzip.flush()?;
+                Ok(std::mem::take(gzip.get_mut()))
+            }
+            Encoder::Zstd(zstd) => {
+                zstd.write_all(chunk)?;
+                zstd.flush()?;
+                Ok(std::mem::take(zstd.get_mut()))
+            }
+        }
+    }
+
+    // End the stream, returning its trailing bytes (gzip footer, end of zstd frame)
+    pub fn finish(self) -> io::Result<Vec<u8>> {
+        match self.encoder {
+            Encoder::Identity => Ok(Vec::new()),
+            Encoder::Gzip(gzip) => gzip.finish(),
+            Encoder::Zstd(zstd) => zstd.finish(),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiation_and_per_chunk_decoding() {
+        assert_eq!(StreamEncoding::negotiate("gzip, deflate, br, zstd"), StreamEncoding::Zstd);
+        assert_eq!(StreamEncoding::negotiate("zstd;q=0.5, gzip"), StreamEncoding::Gzip);
+        assert_eq!(StreamEncoding::negotiate("gzip;q=0, zstd;q=0"), StreamEncoding::Identity);
+        assert_eq!(StreamEncoding::negotiate("*;q=0.1"), StreamEncoding::Zstd);
+        assert_eq!(StreamEncoding::negotiate("br"), StreamEncoding::Identity);
+
+        // Each encoded chunk decodes on its own, before the stream is finished
+        let mut gzip = StreamEncoder::new(StreamEncoding::Gzip).unwrap();
+        let mut gunzip = flate2::write::GzDecoder::new(Vec::new());
+        let mut zstd = StreamEncoder::new(StreamEncoding::Zstd).unwrap();
+        let mut unzstd = zstd::stream::write::Decoder::new(Vec::new()).unwrap();
+        for line in ["{\"task_id\": 1}\n", "{\"task_id\": 2}\n"] {
+            gunzip.write_all(&gzip.encode(line.as_bytes()).unwrap()).unwrap();
+            gunzip.flush().unwrap();
+            assert!(gunzip.get_ref().ends_with(line.as_bytes()));
+            unzstd.write_all(&zstd.encode(line.as_bytes()).unwrap()).unwrap();
+            unzstd.flush().unwrap();
+            assert!(unzstd.get_ref().ends_with(line.as_bytes()));
+        }
+        gunzip.write_all(&gzip.finish().unwrap()).unwrap();
+        assert_eq!(gunzip.finish().unwrap(), b"{\"task_id\": 1}\n{\"task_id\": 2}\n");
+    }
+}
@@ -0,0 +1,153 @@
+// backend/rust/src/dead_letters.rs
+// Purpose: Dead-letter queue for MRTODP poison tasks. Under a retry policy (see
+// retries.rs), a task that fails because of its robot on the last attempt the policy
+// allows is not just left failed: its record is marked dead-lettered (persisted with it,
+// so the queue survives restarts) and the move is recorded in the audit log. Operators
+// list the queue oldest first with `Scheduler::dead_letters`, inspect one task's full
+// attempt history with `Scheduler::dead_letter`, queue a task again as a new attempt
+// with a fresh set of retries with `Scheduler::redrive_dead_letter` (e.g. once the fault
+// is fixed), or delete it for good with `Scheduler::purge_dead_letter`. Served by GET
+// /admin/dead-letters, GET and DELETE /admin/dead-letters/{id}, POST
+// /admin/dead-letters/{id}/redrive, and the FFI.
+
+use serde::{Deserialize, Serialize};
+use crate::audit::AuditAction;
+use crate::retries::{attempts_in_run, robot_failure};
+use crate::scheduler::{ReasonCode, Scheduler, TaskRecord, TaskState};
+use crate::task::Task;
+
+// A dead-lettered task as listed to operators
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DeadLetter {
+    pub task: Task,
+    pub attempts: u32,       // Attempts made before the task was dead-lettered
+    pub robots: Vec<String>, // Robots that failed those attempts, first to last
+    pub reason: ReasonCode,  // Reason of the final failure
+    pub detail: String,
+    pub dead_lettered_at: u64, // Unix timestamp (milliseconds)
+}
+
+impl DeadLetter {
+    fn of(record: &TaskRecord) -> Option<DeadLetter> {
+        let dead_lettered_at = record.dead_lettered_at?;
+        let attempts = attempts_in_run(record);
+        let run = &record.attempts[record.attempts.len().saturating_sub(attempts as usize)..];
+        let failure = record.attempts.last()?.transitions.last()?;
+        Some(DeadLetter {
+            task: record.task.clone(),
+            attempts,
+            robots: run.iter().filter_map(|a| a.robot_id.clone()).collect(),
+            reason: failure.reason,
+            detail: failure.detail.clone(),
+            dead_lettered_at,
+        })
+    }
+}
+
+impl Scheduler {
+    // Whether a task failing with `reason` used up the retry policy's attempts
+    pub(crate) fn is_dead_letter(&self, record: &TaskRecord, to: TaskState, reason: ReasonCode) -> bool {
+        self.core.retry_policy.is_some_and(|policy| {
+            to == TaskState::Failed && robot_failure(reason) && !record.cancel_requested && attempts_in_run(record) >= policy.max_attempts
+        })
+    }
+
+    // Tasks in the dead-letter queue, oldest first
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        let mut letters: Vec<DeadLetter> = self.core.records.lock().await.values().filter_map(DeadLetter::of).collect();
+        letters.sort_by_key(|letter| (letter.dead_lettered_at, letter.task.id));
+        letters
+    }
+
+    // Full record of a task in the dead-letter queue, with every attempt
+    pub async fn dead_letter(&self, task_id: u32) -> Option<TaskRecord> {
+        self.core.records.lock().await.get(&task_id).filter(|r| r.dead_lettered_at.is_some()).cloned()
+    }
+
+    // Take a task out of the dead-letter queue and queue it again as a new attempt, with
+    // the retry policy's attempts and no robots excluded. Recorded in the audit log.
+    pub async fn redrive_dead_letter(&self, task_id: u32) -> Result<(), String> {
+        let saved = {
+            let mut records = self.core.records.lock().await;
+            let record = records.get_mut(&task_id).filter(|r| r.dead_lettered_at.is_some()).ok_or_else(|| format!("Unknown dead letter: {}", task_id))?;
+            record.dead_lettered_at = None;
+            record.task.excluded_robots.clear();
+            if !record.pinned {
+                record.task.robot_id = None;
+            }
+            record.clone()
+        };
+        let detail = format!("Task {} re-driven from the dead-letter queue", task_id);
+        self.audit(AuditAction::TaskRedriven, Some(task_id), None, None, detail.clone());
+        self.requeue(saved, ReasonCode::Redriven, detail).await
+    }
+
+    // Delete a task in the dead-letter queue, its record and history included. Recorded in
+    // the audit log.
+    pub async fn purge_dead_letter(&self, task_id: u32) -> Result<(), String> {
+        {
+            let mut records = self.core.records.lock().await;
+            if records.get(&task_id).is_none_or(|r| r.dead_lettered_at.is_none()) {
+                return Err(format!("Unknown dead letter: {}", task_id));
+            }
+            self.core.store.remove_task(task_id)?;
+            records.remove(&task_id);
+        }
+        self.core.history.lock().unwrap_or_else(|e| e.into_inner()).forget(task_id);
+        let detail = format!("Task {} purged from the dead-letter queue", task_id);
+        self.audit(AuditAction::TaskPurged, Some(task_id), None, None, detail);
+        Ok(())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::retries::RetryPolicy;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[tokio::test]
+    async fn test_poison_task_is_dead_lettered_then_redriven_and_purged() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let policy = RetryPolicy { max_attempts: 2, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).retry_policy(policy).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 2]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let fail_attempt = |number: usize| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(406).await.is_none_or(|r| r.state != TaskState::Running || r.attempts.len() < number) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+                scheduler.report_result(406, Err("Payload too heavy".to_string())).await;
+            }
+        };
+        scheduler.schedule_task(Task { id: 406, ..Default::default() }).await.unwrap();
+        fail_attempt(1).await;
+        assert!(scheduler.dead_letters().await.is_empty());
+        fail_attempt(2).await;
+        let letters = scheduler.dead_letters().await;
+        assert_eq!(letters.len(), 1);
+        assert_eq!((letters[0].attempts, letters[0].robots.len(), letters[0].reason), (2, 2, ReasonCode::FailedRobotError));
+        assert_eq!(scheduler.dead_letter(406).await.unwrap().state, TaskState::Failed);
+
+        // Re-driven, it gets a fresh set of attempts before it is dead-lettered again
+        scheduler.redrive_dead_letter(406).await.unwrap();
+        assert!(scheduler.dead_letter(406).await.is_none());
+        fail_attempt(3).await;
+        assert!(scheduler.dead_letters().await.is_empty());
+        fail_attempt(4).await;
+        assert_eq!(scheduler.dead_letters().await[0].attempts, 2);
+
+        scheduler.purge_dead_letter(406).await.unwrap();
+        assert!(scheduler.task_record(406).await.is_none());
+        assert!(scheduler.purge_dead_letter(406).await.unwrap_err().starts_with("Unknown dead letter"));
+    }
+}
@@ -0,0 +1,124 @@
+// backend/rust/src/deadline_miss.rs
+// Purpose: Deadline-miss handling for MRTODP. A task that reaches dispatch after its deadline
+// is handled by its own `deadline_policy` if it names one, otherwise by the scheduler-wide
+// policy set on the builder:
+//
+//   - drop (default): the task expires with reason EXPIRED_DEADLINE, as before
+//   - execute_anyway: the task is dispatched late
+//   - reschedule: the deadline moves `extend_ms` past the miss and the task queues again
+//   - escalate_priority: the task's priority rises by `boost` and it queues again, late
+//   - notify_callback: the hook registered with `on_deadline_miss` picks one of the above;
+//     without a hook the task expires
+//
+// The outcome is recorded as `deadline_miss` on the task record, so status queries show how
+// the miss was handled. A late task is judged once per deadline; only a rescheduled one can
+// miss again.
+
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// What to do with a task that missed its deadline
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeadlinePolicy {
+    #[default]
+    Drop,
+    ExecuteAnyway,
+    Reschedule { extend_ms: u64 },
+    EscalatePriority { boost: u32 },
+    NotifyCallback,
+}
+
+// Callback deciding how a late task is handled, given the task and the current time (Unix
+// milliseconds); answering notify_callback again drops the task
+pub type DeadlineMissHook = Arc<dyn Fn(&Task, u64) -> DeadlinePolicy + Send + Sync>;
+
+// How a missed deadline was handled
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeadlineMiss {
+    pub deadline: u64,  // The deadline that was missed (Unix milliseconds)
+    pub missed_at: u64, // When dispatch found it missed
+    pub outcome: DeadlinePolicy,
+}
+
+impl DeadlinePolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            DeadlinePolicy::Reschedule { extend_ms: 0 } => Err("Deadline reschedule extension must be greater than zero".to_string()),
+            DeadlinePolicy::EscalatePriority { boost: 0 } => Err("Deadline priority boost must be greater than zero".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    // The policy actually applied: notify_callback defers to the hook, and drops the task
+    // when there is none or the hook defers back
+    pub(crate) fn resolve(self, hook: Option<&DeadlineMissHook>, task: &Task, now: u64) -> DeadlinePolicy {
+        if self != DeadlinePolicy::NotifyCallback {
+            return self;
+        }
+        match hook.map(|hook| hook(task, now)) {
+            Some(DeadlinePolicy::NotifyCallback) | None => DeadlinePolicy::Drop,
+            Some(outcome) if outcome.validate().is_err() => DeadlinePolicy::Drop,
+            Some(outcome) => outcome,
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+
+    #[tokio::test]
+    async fn test_missed_deadlines_follow_task_or_global_policy() {
+        assert!(DeadlinePolicy::Reschedule { extend_ms: 0 }.validate().is_err());
+        let policy: DeadlinePolicy = serde_json::from_str(r#"{"action": "escalate_priority", "boost": 3}"#).unwrap();
+        assert_eq!(policy, DeadlinePolicy::EscalatePriority { boost: 3 });
+
+        let (scheduler, workers) = Scheduler::builder()
+            .deadline_policy(DeadlinePolicy::Reschedule { extend_ms: 3_600_000 })
+            .on_deadline_miss(|task, _| if task.id == 313 { DeadlinePolicy::ExecuteAnyway } else { DeadlinePolicy::NotifyCallback })
+            .build()
+            .unwrap();
+        workers.spawn();
+        let late = |id: u32, deadline_policy: Option<DeadlinePolicy>| Task { id, priority: 1, deadline: Some(1), deadline_policy, ..Default::default() };
+        scheduler.schedule_task(late(309, None)).await.unwrap();
+        scheduler.schedule_task(late(310, Some(DeadlinePolicy::Drop))).await.unwrap();
+        scheduler.schedule_task(late(311, Some(DeadlinePolicy::EscalatePriority { boost: 4 }))).await.unwrap();
+        scheduler.schedule_task(late(312, Some(DeadlinePolicy::NotifyCallback))).await.unwrap();
+        scheduler.schedule_task(late(313, Some(DeadlinePolicy::NotifyCallback))).await.unwrap();
+        let finished = |task_id: u32| {
+            let scheduler = scheduler.clone();
+            async move {
+                loop {
+                    match scheduler.task_record(task_id).await {
+                        Some(record) if record.state.is_terminal() => return record,
+                        _ => tokio::time::sleep(Duration::from_millis(1)).await,
+                    }
+                }
+            }
+        };
+
+        // The global policy moves the deadline out and the task runs against the new one
+        let rescheduled = finished(309).await;
+        assert_eq!(rescheduled.state, TaskState::Completed);
+        let miss = rescheduled.deadline_miss.unwrap();
+        assert_eq!((miss.deadline, miss.outcome), (1, DeadlinePolicy::Reschedule { extend_ms: 3_600_000 }));
+        assert_eq!(rescheduled.task.deadline, Some(miss.missed_at + 3_600_000));
+
+        let dropped = finished(310).await;
+        assert_eq!(dropped.state, TaskState::Expired);
+        assert_eq!(dropped.attempts[0].transitions.last().unwrap().reason, ReasonCode::ExpiredDeadline);
+        let escalated = finished(311).await;
+        assert_eq!((escalated.state, escalated.task.priority), (TaskState::Completed, 5));
+        assert_eq!(escalated.task.deadline, Some(1));
+
+        // The hook drops 312 by deferring back, and lets 313 run late
+        assert_eq!(finished(312).await.deadline_miss.unwrap().outcome, DeadlinePolicy::Drop);
+        let executed = finished(313).await;
+        assert_eq!((executed.state, executed.deadline_miss.unwrap().outcome), (TaskState::Completed, DeadlinePolicy::ExecuteAnyway));
+    }
+}
@@ -0,0 +1,89 @@
+// backend/rust/src/deadlines.rs
+// Purpose: Submission-time deadline parsing for MRTODP. A task's deadline may be submitted
+// as Unix epoch milliseconds or as an RFC 3339 timestamp with an explicit offset ("Z" or
+// "+02:00"); either way it is normalized to UTC epoch milliseconds, which is what the
+// scheduler stores and serializes. Timestamps without an offset are ambiguous (the
+// submitter's local time is unknown here), so they are rejected instead of guessed at.
+
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Deserializer};
+
+// Deadline as submitted: epoch milliseconds or an RFC 3339 string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDeadline {
+    Millis(u64),
+    Text(String),
+}
+
+// Parse an RFC 3339 timestamp with an offset into UTC epoch milliseconds
+pub fn parse_deadline(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let parsed = match DateTime::parse_from_rfc3339(text) {
+        Ok(parsed) => parsed,
+        // Valid once an offset is added, so the planner sent local time
+        Err(_) if DateTime::parse_from_rfc3339(&format!("{}Z", text)).is_ok() => {
+            return Err(format!("Deadline {:?} has no UTC offset; add \"Z\" or \"+hh:mm\"", text));
+        }
+        Err(e) => return Err(format!("Deadline {:?} is not an RFC 3339 timestamp: {}", text, e)),
+    };
+    u64::try_from(parsed.timestamp_millis()).map_err(|_| format!("Deadline {:?} is before the Unix epoch", text))
+}
+
+// Render epoch milliseconds as an RFC 3339 UTC timestamp; None if out of chrono's range
+pub fn format_deadline(millis: u64) -> Option<String> {
+    let millis = i64::try_from(millis).ok()?;
+    DateTime::from_timestamp_millis(millis).map(|at| at.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+// `deserialize_with` for `Task::deadline`
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let raw = Option::<RawDeadline>::deserialize(deserializer)?;
+    match raw {
+        None => Ok(None),
+        Some(RawDeadline::Millis(millis)) => Ok(Some(millis)),
+        Some(RawDeadline::Text(text)) => parse_deadline(&text).map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+// `deserialize_with` for timestamps that must be present, e.g. the bounds of a time window
+pub(crate) fn deserialize_required<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match RawDeadline::deserialize(deserializer)? {
+        RawDeadline::Millis(millis) => Ok(millis),
+        RawDeadline::Text(text) => parse_deadline(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Task;
+
+    fn deadline_of(json: &str) -> Result<Option<u64>, String> {
+        serde_json::from_str::<Task>(&format!(r#"{{"id": 1, "task_type": "lift", "priority": 1, "robot_id": null, "required_capabilities": [], "deadline": {}}}"#, json))
+            .map(|task| task.deadline)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_deadlines_normalize_to_utc_millis() {
+        assert_eq!(deadline_of("null"), Ok(None));
+        assert_eq!(deadline_of("1767225600000"), Ok(Some(1_767_225_600_000)));
+        assert_eq!(deadline_of(r#""2026-01-01T00:00:00Z""#), Ok(Some(1_767_225_600_000)));
+        // Six hours behind UTC is the same instant as 06:00Z
+        assert_eq!(deadline_of(r#""2026-01-01T00:00:00.250-06:00""#), Ok(Some(1_767_247_200_250)));
+
+        let naive = deadline_of(r#""2026-01-01T00:00:00""#).unwrap_err();
+        assert!(naive.contains("has no UTC offset"), "{}", naive);
+        assert!(deadline_of(r#""next tuesday""#).unwrap_err().contains("not an RFC 3339 timestamp"));
+        assert!(deadline_of(r#""1969-12-31T23:59:59Z""#).unwrap_err().contains("before the Unix epoch"));
+        assert_eq!(parse_deadline(" 2026-01-01T00:00:00Z "), Ok(1_767_225_600_000));
+        assert_eq!(format_deadline(1_767_247_200_250).as_deref(), Some("2026-01-01T06:00:00.250Z"));
+        assert_eq!(format_deadline(u64::MAX), None);
+
+        // Stored and serialized as plain epoch milliseconds
+        let task: Task = serde_json::from_str(r#"{"id": 1, "task_type": "lift", "priority": 1, "deadline": "2026-01-01T01:00:00+01:00", "robot_id": null, "required_capabilities": []}"#).unwrap();
+        assert_eq!(serde_json::to_value(&task).unwrap()["deadline"], 1_767_225_600_000u64);
+    }
+}
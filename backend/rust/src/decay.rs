@@ -0,0 +1,64 @@
+// backend/rust/src/decay.rs
+// Purpose: Priority decay for stale expedited tasks in MRTODP. An expedited task that sat
+// under an operator hold for longer than the configured window is probably no longer
+// urgent, so when it is released it loses its urgent-lane pass and some priority for every
+// window it was held, and goes back into the ordinary queue instead of jumping ahead.
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// How expedited tasks decay while held
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpediteDecay {
+    pub window_ms: u64,     // Hold time after which an expedited task counts as stale
+    pub priority_step: u32, // Priority lost for every full window held
+}
+
+impl ExpediteDecay {
+    pub fn new(window: Duration, priority_step: u32) -> Self {
+        ExpediteDecay { window_ms: window.as_millis() as u64, priority_step }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_ms == 0 {
+            return Err("Expedite decay window must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+
+    // Decay a task released after `held_ms` on hold; returns whether it was stale
+    pub(crate) fn apply(&self, task: &mut Task, held_ms: u64) -> bool {
+        if !task.expedite || held_ms < self.window_ms {
+            return false;
+        }
+        let windows = u32::try_from(held_ms / self.window_ms).unwrap_or(u32::MAX);
+        task.priority = task.priority.saturating_sub(self.priority_step.saturating_mul(windows));
+        task.expedite = false;
+        true
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_expedited_task_loses_urgency_per_window() {
+        let decay = ExpediteDecay::new(Duration::from_secs(3600), 2);
+        let mut task = Task { priority: 9, expedite: true, ..Default::default() };
+        assert!(!decay.apply(&mut task, 3_599_999));
+        assert_eq!((task.priority, task.expedite), (9, true));
+
+        // Held for three and a half windows
+        assert!(decay.apply(&mut task, 12_600_000));
+        assert_eq!((task.priority, task.expedite), (3, false));
+
+        // Ordinary tasks keep their priority however long they were held
+        let mut task = Task { priority: 9, ..Default::default() };
+        assert!(!decay.apply(&mut task, u64::MAX));
+        assert_eq!(task.priority, 9);
+        assert!(ExpediteDecay::new(Duration::ZERO, 1).validate().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// backend/rust/src/delayed.rs
+// Purpose: Delayed task scheduling for MRTODP. A task submitted with `not_before` (Unix
+// milliseconds, or RFC 3339 like deadlines), or with a time window opening later, is
+// accepted and recorded right away but held out of dispatch until that time. When the execution loop pops a task whose start time is
+// still ahead, it goes on a hashed timer wheel instead; a worker turns the wheel every tick
+// (see `SchedulerBuilder::timer_tick`) and sends tasks whose time has come back to the
+// dispatch queue. Start times are read against the scheduler's clock; a task whose time has
+// already passed at submission is dispatched as usual.
+
+use crate::task::Task;
+
+// Slots on the wheel; tasks further out than one rotation wait for later rounds
+const SLOTS: u64 = 512;
+
+// Delayed tasks bucketed by the tick their start time falls in
+pub(crate) struct TimerWheel {
+    tick_ms: u64,
+    slots: Vec<Vec<Task>>,
+    current: Option<u64>, // Last tick turned to; None until the first turn
+    len: usize,
+}
+
+impl TimerWheel {
+    pub(crate) fn new(tick_ms: u64) -> Self {
+        TimerWheel { tick_ms: tick_ms.max(1), slots: vec![Vec::new(); SLOTS as usize], current: None, len: 0 }
+    }
+
+    // Hold a task until its start time
+    pub(crate) fn insert(&mut self, task: Task) {
+        let due_tick = task.start_after().unwrap_or(0) / self.tick_ms;
+        // A start time within the tick already turned past goes out on the next one
+        let tick = self.current.map_or(due_tick, |current| due_tick.max(current + 1));
+        self.slots[(tick % SLOTS) as usize].push(task);
+        self.len += 1;
+    }
+
+    // Turn the wheel to `now`, taking every task whose start time has come, earliest first.
+    // A clock stepping backward turns nothing; tasks still wait for their own start time.
+    pub(crate) fn advance(&mut self, now: u64) -> Vec<Task> {
+        let now_tick = now / self.tick_ms;
+        let from = match self.current {
+            Some(current) if current >= now_tick => {
+                self.current = Some(now_tick);
+                return Vec::new();
+            }
+            Some(current) => current + 1,
+            None => 0,
+        };
+        self.current = Some(now_tick);
+        let mut due = Vec::new();
+        // On the first turn, or after a long gap, every slot is visited once
+        for tick in from.max(now_tick.saturating_sub(SLOTS - 1))..=now_tick {
+            let slot = &mut self.slots[(tick % SLOTS) as usize];
+            let (ready, waiting) = std::mem::take(slot).into_iter().partition(|t: &Task| t.start_after().unwrap_or(0) <= now);
+            *slot = waiting;
+            due.extend(ready);
+        }
+        self.len -= due.len();
+        due.sort_by_key(|t| (t.start_after(), t.id));
+        due
+    }
+
+    // Drop a task from the wheel, e.g. when it is cancelled
+    pub(crate) fn withdraw(&mut self, task_id: u32) -> Option<Task> {
+        for slot in &mut self.slots {
+            if let Some(index) = slot.iter().position(|t| t.id == task_id) {
+                self.len -= 1;
+                return Some(slot.remove(index));
+            }
+        }
+        None
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::{Scheduler, TaskState};
+    use crate::test_utils::MockClock;
+
+    #[test]
+    fn test_wheel_releases_tasks_when_due() {
+        let task = |id: u32, not_before: u64| Task { id, not_before: Some(not_before), ..Default::default() };
+        let mut wheel = TimerWheel::new(10);
+        assert!(wheel.advance(1_000).is_empty());
+        wheel.insert(task(1, 1_025));
+        wheel.insert(task(2, 1_005)); // Within the tick already turned past
+        wheel.insert(task(3, 1_000 + 10 * SLOTS + 25)); // A full rotation later
+        wheel.insert(task(4, 1_500));
+        assert_eq!(wheel.advance(1_010).iter().map(|t| t.id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(wheel.advance(1_030).iter().map(|t| t.id).collect::<Vec<_>>(), vec![1]);
+        assert!(wheel.advance(900).is_empty());
+        assert_eq!(wheel.withdraw(4).map(|t| t.id), Some(4));
+        assert_eq!(wheel.len(), 1);
+
+        // Same slot as task 1, one rotation on; a jump past it releases it once
+        assert!(wheel.advance(1_000 + 10 * SLOTS).is_empty());
+        assert_eq!(wheel.advance(1_000_000).iter().map(|t| t.id).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(wheel.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_wait_for_their_start_time() {
+        let start = 1_767_225_600_000;
+        let clock = Arc::new(MockClock::new(start));
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).timer_tick(Duration::from_millis(1)).build().unwrap();
+        workers.spawn();
+        let task = |id: u32, not_before: u64| Task { id, not_before: Some(not_before), ..Default::default() };
+        let bad = Task { deadline: Some(start + 1_000), ..task(318, start + 2_000) };
+        assert!(scheduler.schedule_task(bad).await.unwrap_err().contains("after its deadline"));
+
+        scheduler.schedule_task(task(319, start + 60_000)).await.unwrap();
+        scheduler.schedule_task(task(320, start + 60_000)).await.unwrap();
+        scheduler.schedule_task(task(321, start - 1)).await.unwrap();
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        // A start time already passed doesn't hold the task
+        wait_for_state(321, TaskState::Completed).await;
+        while scheduler.delayed_count() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.task_record(319).await.unwrap().state, TaskState::Pending);
+        assert_eq!(scheduler.cancel_task(320).await, Ok(TaskState::Cancelled));
+        assert_eq!(scheduler.delayed_count(), 1);
+
+        clock.set(start + 60_000);
+        wait_for_state(319, TaskState::Completed).await;
+        let submitted_at = scheduler.task_record(319).await.unwrap().attempts[0].transitions[0].at;
+        assert_eq!(submitted_at, start);
+        assert_eq!(scheduler.delayed_count(), 0);
+    }
+}
@@ -0,0 +1,157 @@
+// backend/rust/src/escalation.rs
+// Purpose: Per-task soft-timeout escalation for MRTODP. A task with a time budget can name an
+// escalation endpoint that is called when the running task crosses configured fractions of
+// its budget (50%/80%/100% by default), letting the planner respond progressively (notify,
+// slow other robots in the zone, abort). Calls go through the webhook transport.
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{Scheduler, TaskState};
+
+// Escalation endpoint and budget thresholds (percent of the task's timeout_ms)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EscalationConfig {
+    pub url: String,
+    #[serde(default = "default_thresholds")]
+    pub thresholds: Vec<u32>,
+}
+
+fn default_thresholds() -> Vec<u32> {
+    vec![50, 80, 100]
+}
+
+// Payload posted to the escalation endpoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EscalationNotice {
+    pub task_id: u32,
+    pub attempt: u32,
+    pub robot_id: Option<String>,
+    pub threshold_pct: u32,
+    pub elapsed_ms: u64,
+    pub budget_ms: u64,
+}
+
+impl EscalationConfig {
+    pub fn validate(&self, timeout_ms: Option<u64>) -> Result<(), String> {
+        if self.url.is_empty() {
+            return Err("Escalation URL must not be empty".to_string());
+        }
+        if timeout_ms.unwrap_or(0) == 0 {
+            return Err("Escalation requires a positive timeout_ms budget".to_string());
+        }
+        if self.thresholds.is_empty() || self.thresholds.iter().any(|&t| t == 0 || t > 1_000) {
+            return Err(format!("Escalation thresholds must be between 1 and 1000 percent: {:?}", self.thresholds));
+        }
+        Ok(())
+    }
+}
+
+// Watch a task that just started running and call its escalation endpoint at each
+// threshold while the same attempt is still running
+pub(crate) fn watch(scheduler: Scheduler, task_id: u32, attempt: u32) {
+    tokio::spawn(async move {
+        let Some(record) = scheduler.task_record(task_id).await else {
+            return;
+        };
+        let (Some(config), Some(budget_ms)) = (record.task.escalation.clone(), record.task.timeout_ms) else {
+            return;
+        };
+        let mut thresholds = config.thresholds.clone();
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        let started = tokio::time::Instant::now();
+        for threshold_pct in thresholds {
+            let due = Duration::from_millis(budget_ms.saturating_mul(threshold_pct as u64) / 100);
+            tokio::time::sleep_until(started + due).await;
+            let still_running = scheduler.task_record(task_id).await.is_some_and(|r| {
+                r.state == TaskState::Running && r.attempts.last().is_some_and(|a| a.number == attempt)
+            });
+            if !still_running {
+                return;
+            }
+            let notice = EscalationNotice {
+                task_id,
+                attempt,
+                robot_id: record.task.robot_id.clone(),
+                threshold_pct,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                budget_ms,
+            };
+            let body = match serde_json::to_string(&notice) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::warn!(task_id, error = %e, "could not build escalation payload");
+                    return;
+                }
+            };
+            if let Err(e) = scheduler.core.webhook_transport.post(&config.url, body).await {
+                tracing::warn!(task_id, threshold_pct, error = %e, "escalation delivery failed");
+            }
+        }
+    });
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+    use crate::webhooks::WebhookTransport;
+    use crate::BoxFuture;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        posts: Mutex<Vec<String>>,
+    }
+
+    impl WebhookTransport for RecordingTransport {
+        fn post<'a>(&'a self, _url: &'a str, body: String) -> BoxFuture<'a, Result<(), String>> {
+            self.posts.lock().unwrap().push(body);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = EscalationConfig { url: "http://planner/escalate".to_string(), thresholds: default_thresholds() };
+        assert!(config.validate(Some(1_000)).is_ok());
+        assert!(config.validate(None).is_err());
+        let bad = EscalationConfig { thresholds: vec![0], ..config };
+        assert!(bad.validate(Some(1_000)).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_thresholds_fire_while_running() {
+        let hooks = Arc::new(RecordingTransport::default());
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder()
+            .transport(fake.clone())
+            .webhook_transport(hooks.clone())
+            .build()
+            .unwrap();
+        fake.attach(&scheduler);
+        // Robot finishes at 90% of the budget, so the 100% escalation never fires
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_millis(900))]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        let task = Task {
+            id: 81,
+            robot_id: Some("Ford".to_string()),
+            timeout_ms: Some(1_000),
+            escalation: Some(EscalationConfig { url: "http://planner/escalate".to_string(), thresholds: default_thresholds() }),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(2_000)).await;
+
+        let posts = hooks.posts.lock().unwrap().clone();
+        let levels: Vec<u32> = posts
+            .iter()
+            .map(|p| serde_json::from_str::<EscalationNotice>(p).unwrap().threshold_pct)
+            .collect();
+        assert_eq!(levels, vec![50, 80]);
+        assert_eq!(scheduler.task_record(81).await.unwrap().state, TaskState::Completed);
+    }
+}
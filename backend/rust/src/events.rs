@@ -0,0 +1,344 @@
+// backend/rust/src/events.rs
+// Purpose: Server-side filtering and flow control of task transition events. Event stream
+// front-ends pass each subscriber's `EventFilter` (namespace, robot, task type, tag, state)
+// so a dashboard showing one cell only receives that cell's events instead of the whole
+// fleet's firehose. Every event carries a sequence number; a subscriber that falls more
+// than `max_pending` events behind is either disconnected with a resume point or has its
+// backlog coalesced to the latest transition per task, so a slow consumer never makes the
+// scheduler buffer without bound.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use crate::scheduler::{TaskEvent, TaskState};
+
+// Subscription filter; every non-empty field must match (fields are OR-ed internally)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub robots: Vec<String>,
+    #[serde(default)]
+    pub task_types: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>, // Matches events carrying any of these tags
+    #[serde(default)]
+    pub states: Vec<TaskState>, // Event kind: the state the task transitioned to
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &TaskEvent) -> bool {
+        let namespace = event.namespace.as_deref().unwrap_or(crate::missions::DEFAULT_NAMESPACE);
+        (self.namespaces.is_empty() || self.namespaces.iter().any(|n| n == namespace))
+            && (self.robots.is_empty() || event.robot_id.as_ref().is_some_and(|r| self.robots.contains(r)))
+            && (self.task_types.is_empty() || self.task_types.contains(&event.task_type))
+            && (self.tags.is_empty() || event.tags.iter().any(|t| self.tags.contains(t)))
+            && (self.states.is_empty() || self.states.contains(&event.transition.to))
+    }
+}
+
+// What happens to a subscriber that falls too far behind
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowConsumerPolicy {
+    // End the stream with the sequence number to resume from after reconnecting
+    #[default]
+    Disconnect,
+    // Skip ahead, delivering only the latest missed transition of each task
+    Coalesce,
+}
+
+// Per-subscriber flow control for an event stream
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamOptions {
+    #[serde(default = "default_max_pending")]
+    pub max_pending: usize, // Undelivered events allowed before the policy applies
+    #[serde(default)]
+    pub policy: SlowConsumerPolicy,
+    #[serde(default)]
+    pub resume_from: Option<u64>, // Replay retained events from this sequence number first
+}
+
+fn default_max_pending() -> usize {
+    256
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        StreamOptions { max_pending: default_max_pending(), policy: SlowConsumerPolicy::default(), resume_from: None }
+    }
+}
+
+// Why an event stream ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamError {
+    // The subscriber fell behind under `Disconnect`; reconnect with `resume_from`
+    Lagged { resume_from: u64 },
+    // The scheduler shut down
+    Closed,
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Lagged { resume_from } => write!(f, "Subscriber fell behind; resume from {}", resume_from),
+            StreamError::Closed => write!(f, "Event stream closed"),
+        }
+    }
+}
+
+// Recent events kept for resuming and coalescing, numbered from 1
+pub(crate) struct EventLog {
+    capacity: usize,
+    next_seq: u64,
+    events: VecDeque<TaskEvent>,
+}
+
+impl EventLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        EventLog { capacity, next_seq: 1, events: VecDeque::with_capacity(capacity) }
+    }
+
+    // Number an event and retain it, evicting the oldest once full
+    pub(crate) fn append(&mut self, event: &mut TaskEvent) {
+        event.seq = self.next_seq;
+        self.next_seq += 1;
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+    }
+
+    // Retained events from `seq` on; errors if some of them were already evicted
+    fn since(&self, seq: u64) -> Result<Vec<TaskEvent>, String> {
+        let oldest = self.events.front().map_or(self.next_seq, |e| e.seq);
+        if seq < oldest || seq > self.next_seq {
+            return Err(format!("Cannot resume from event {}; retained events are {}..{}", seq, oldest, self.next_seq));
+        }
+        Ok(self.events.iter().filter(|e| e.seq >= seq).cloned().collect())
+    }
+}
+
+// Broadcast subscription that only yields events matching its filter
+pub struct FilteredSubscription {
+    receiver: broadcast::Receiver<TaskEvent>,
+    filter: EventFilter,
+    options: StreamOptions,
+    log: Arc<Mutex<EventLog>>,
+    backlog: VecDeque<TaskEvent>, // Replayed or coalesced events, delivered before live ones
+    last_seq: u64,                // Latest event consumed, matching or not
+    lagged: bool,
+}
+
+impl FilteredSubscription {
+    // Subscribe to `events`, whose publisher numbers and retains events in `log`
+    pub(crate) fn open(
+        events: &broadcast::Sender<TaskEvent>,
+        log: Arc<Mutex<EventLog>>,
+        filter: EventFilter,
+        options: StreamOptions,
+    ) -> Result<Self, String> {
+        // Publishing holds the log lock while sending, so the replay and the live receiver
+        // meet without a gap
+        let guard = log.lock().unwrap_or_else(|e| e.into_inner());
+        let (backlog, last_seq) = match options.resume_from {
+            Some(seq) => (guard.since(seq)?.into(), seq.saturating_sub(1)),
+            None => (VecDeque::new(), guard.next_seq - 1),
+        };
+        let receiver = events.subscribe();
+        drop(guard);
+        Ok(FilteredSubscription { receiver, filter, options, log, backlog, last_seq, lagged: false })
+    }
+
+    pub fn filter(&self) -> &EventFilter {
+        &self.filter
+    }
+
+    // Sequence number of the latest event this subscription has consumed
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq
+    }
+
+    // Next matching event; ends with `Lagged` if the subscriber fell behind under
+    // `Disconnect`, after which every call returns the same error
+    pub async fn recv(&mut self) -> Result<TaskEvent, StreamError> {
+        loop {
+            if self.lagged {
+                return Err(StreamError::Lagged { resume_from: self.last_seq + 1 });
+            }
+            if let Some(event) = self.backlog.pop_front() {
+                if let Some(event) = self.accept(event) {
+                    return Ok(event);
+                }
+                continue;
+            }
+            if self.receiver.len() > self.options.max_pending {
+                self.fall_behind();
+                continue;
+            }
+            match self.receiver.recv().await {
+                Ok(event) => {
+                    if let Some(event) = self.accept(event) {
+                        return Ok(event);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => self.fall_behind(),
+                Err(broadcast::error::RecvError::Closed) => return Err(StreamError::Closed),
+            }
+        }
+    }
+
+    // Next matching event if one is already waiting, without blocking; Ok(None) when
+    // caught up. Ends like `recv`.
+    pub fn try_recv(&mut self) -> Result<Option<TaskEvent>, StreamError> {
+        loop {
+            if self.lagged {
+                return Err(StreamError::Lagged { resume_from: self.last_seq + 1 });
+            }
+            if let Some(event) = self.backlog.pop_front() {
+                if let Some(event) = self.accept(event) {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+            if self.receiver.len() > self.options.max_pending {
+                self.fall_behind();
+                continue;
+            }
+            match self.receiver.try_recv() {
+                Ok(event) => {
+                    if let Some(event) = self.accept(event) {
+                        return Ok(Some(event));
+                    }
+                }
+                Err(broadcast::error::TryRecvError::Empty) => return Ok(None),
+                Err(broadcast::error::TryRecvError::Lagged(_)) => self.fall_behind(),
+                Err(broadcast::error::TryRecvError::Closed) => return Err(StreamError::Closed),
+            }
+        }
+    }
+
+    // Advance past an event, returning it if it is new and matches the filter
+    fn accept(&mut self, event: TaskEvent) -> Option<TaskEvent> {
+        if event.seq <= self.last_seq {
+            return None;
+        }
+        self.last_seq = event.seq;
+        self.filter.matches(&event).then_some(event)
+    }
+
+    fn fall_behind(&mut self) {
+        match self.options.policy {
+            SlowConsumerPolicy::Disconnect => self.lagged = true,
+            SlowConsumerPolicy::Coalesce => self.coalesce(),
+        }
+    }
+
+    // Drop the pending backlog and queue the latest retained transition of each task
+    // instead, in the order those transitions happened
+    fn coalesce(&mut self) {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        self.receiver = self.receiver.resubscribe();
+        let missed: Vec<TaskEvent> =
+            log.events.iter().filter(|e| e.seq > self.last_seq && self.filter.matches(e)).cloned().collect();
+        drop(log);
+        let mut latest: HashMap<u32, TaskEvent> = HashMap::new();
+        for event in missed {
+            latest.insert(event.task_id, event);
+        }
+        let mut coalesced: Vec<TaskEvent> = latest.into_values().collect();
+        coalesced.sort_by_key(|e| e.seq);
+        self.backlog = coalesced.into();
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::Scheduler;
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_filtered_subscription_skips_other_cells() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let filter: EventFilter = serde_json::from_str(r#"{"namespaces": ["cell-3"], "tags": ["inspection"]}"#).unwrap();
+        let mut subscription = scheduler.subscribe_filtered(filter);
+
+        let other_cell = Task { id: 91, namespace: Some("cell-1".to_string()), tags: vec!["inspection".to_string()], ..Default::default() };
+        let untagged = Task { id: 92, namespace: Some("cell-3".to_string()), ..Default::default() };
+        let wanted = Task { id: 93, namespace: Some("cell-3".to_string()), tags: vec!["inspection".to_string()], ..Default::default() };
+        for task in [other_cell, untagged, wanted] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+
+        let event = subscription.recv().await.unwrap();
+        assert_eq!(event.task_id, 93);
+        assert_eq!(event.namespace.as_deref(), Some("cell-3"));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_disconnects_and_resumes() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let options = StreamOptions { max_pending: 2, ..Default::default() };
+        let mut subscription = scheduler.subscribe_stream(EventFilter::default(), options).unwrap();
+        for id in 94..98 {
+            scheduler.schedule_task(Task { id, ..Default::default() }).await.unwrap();
+        }
+
+        // Four undelivered events exceed the limit of two; nothing missed is skipped
+        let resume_from = match subscription.recv().await {
+            Err(StreamError::Lagged { resume_from }) => resume_from,
+            other => panic!("expected lag, got {:?}", other),
+        };
+        let options = StreamOptions { resume_from: Some(resume_from), ..Default::default() };
+        let mut resumed = scheduler.subscribe_stream(EventFilter::default(), options).unwrap();
+        for id in 94..98 {
+            assert_eq!(resumed.recv().await.unwrap().task_id, id);
+        }
+        let evicted = StreamOptions { resume_from: Some(0), ..Default::default() };
+        assert!(scheduler.subscribe_stream(EventFilter::default(), evicted).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_coalescing_keeps_latest_transition_per_task() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let options = StreamOptions { max_pending: 1, policy: SlowConsumerPolicy::Coalesce, resume_from: None };
+        let mut subscription = scheduler.subscribe_stream(EventFilter::default(), options).unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.schedule_task(Task { id: 98, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 99, ..Default::default() }).await.unwrap();
+        let mut finished = 0;
+        while finished < 2 {
+            finished += events.recv().await.unwrap().transition.to.is_terminal() as usize;
+        }
+
+        // Six missed transitions collapse to one completion per task
+        let first = subscription.recv().await.unwrap();
+        let second = subscription.recv().await.unwrap();
+        let mut ids = [first.task_id, second.task_id];
+        ids.sort();
+        assert_eq!(ids, [98, 99]);
+        assert!(first.seq < second.seq);
+        assert_eq!((first.transition.to, second.transition.to), (TaskState::Completed, TaskState::Completed));
+        assert_eq!(subscription.last_seq(), second.seq);
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let event: TaskEvent = serde_json::from_str(
+            r#"{"task_id": 1, "attempt": 1, "transition": {"from": null, "to": "Pending", "reason": "SUBMITTED", "detail": "", "at": 0}}"#,
+        )
+        .unwrap();
+        assert!(EventFilter::default().matches(&event));
+        let by_robot = EventFilter { robots: vec!["Ford".to_string()], ..Default::default() };
+        assert!(!by_robot.matches(&event));
+    }
+}
@@ -0,0 +1,122 @@
+// backend/rust/src/fair_queuing.rs
+// Purpose: Weighted fair queuing across namespaces (teams or tenants sharing one robot
+// fleet) for MRTODP dispatch. Without it the queue that holds the most work wins, so the
+// team that submits fastest crowds everyone else out. With fair sharing configured on the
+// builder, each queue picks the namespace to serve next by start-time fair queuing: every
+// dispatch advances its namespace's virtual finish time by the inverse of the namespace's
+// weight, and the waiting namespace with the earliest start time goes next. Over any busy
+// stretch, namespaces with work waiting get dispatches in proportion to their weights,
+// however many tasks each submits. A namespace that goes idle starts again from the
+// queue's current virtual time, so it can't bank credit while idle and then take over the
+// fleet. The scheduling policy still orders tasks within a namespace, and priority classes
+// (see priority_classes.rs) are applied before fairness.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::missions::namespace_of;
+use crate::task::Task;
+
+// Virtual time one dispatch costs a namespace of weight 1
+const DISPATCH_COST: u64 = 1_000_000;
+
+// Namespace weights for fair sharing of dispatches
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FairShare {
+    #[serde(default)]
+    pub weights: HashMap<String, u32>, // Relative share per namespace
+    pub default_weight: u32,           // Share of namespaces not listed
+}
+
+impl Default for FairShare {
+    fn default() -> Self {
+        FairShare { weights: HashMap::new(), default_weight: 1 }
+    }
+}
+
+impl FairShare {
+    // Fair sharing with every namespace weighted equally; add weights with `weight`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn weight(mut self, namespace: &str, weight: u32) -> Self {
+        self.weights.insert(namespace.to_string(), weight);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.default_weight == 0 || self.weights.values().any(|w| *w == 0) {
+            return Err("Fair share weights must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+
+    fn weight_of(&self, namespace: &str) -> u64 {
+        u64::from(self.weights.get(namespace).copied().unwrap_or(self.default_weight).max(1))
+    }
+}
+
+// Virtual times of one queue's namespaces
+#[derive(Default)]
+pub(crate) struct FairClock {
+    virtual_time: u64,            // Start time of the last dispatch
+    finish: HashMap<String, u64>, // Virtual finish time of each namespace's last dispatch
+}
+
+impl FairClock {
+    fn start(&self, namespace: &str) -> u64 {
+        self.finish.get(namespace).copied().unwrap_or(0).max(self.virtual_time)
+    }
+
+    // Namespace to serve next among the waiting tasks: earliest start, then by name
+    pub(crate) fn turn<'a>(&self, waiting: impl Iterator<Item = &'a Task>) -> Option<&'a str> {
+        waiting.map(namespace_of).min_by(|a, b| self.start(a).cmp(&self.start(b)).then_with(|| a.cmp(b)))
+    }
+
+    // Charge a dispatch to `namespace`
+    pub(crate) fn record(&mut self, namespace: &str, share: &FairShare) {
+        let start = self.start(namespace);
+        self.virtual_time = start;
+        self.finish.insert(namespace.to_string(), start + DISPATCH_COST / share.weight_of(namespace));
+        // Namespaces at or behind the virtual time have nothing banked; forget them
+        let virtual_time = self.virtual_time;
+        self.finish.retain(|_, finish| *finish > virtual_time);
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{PriorityFirst, ReadyQueue};
+    use crate::priority_classes::QueueOrdering;
+
+    fn task(id: u32, namespace: &str) -> Task {
+        Task { id, namespace: Some(namespace.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_dispatches_shared_by_namespace_weight() {
+        assert!(FairShare::new().weight("vision", 0).validate().is_err());
+        let share = FairShare::new().weight("assembly", 2);
+        let mut queue = ReadyQueue::new(QueueOrdering { fair_share: Some(share.into()), ..Default::default() });
+        // Logistics floods the queue first; assembly still gets two of every three dispatches
+        for id in 0..12 {
+            queue.push(task(100 + id, "logistics"), 0);
+        }
+        for id in 0..4 {
+            queue.push(task(200 + id, "assembly"), 0);
+        }
+        let order: Vec<u32> = (0..6).filter_map(|_| queue.pop(&PriorityFirst, 0)).map(|t| t.id).collect();
+        assert_eq!(order, vec![200, 100, 201, 202, 101, 203]);
+
+        // A namespace arriving after a busy stretch competes from now, not from zero
+        for _ in 0..4 {
+            queue.pop(&PriorityFirst, 0);
+        }
+        queue.push(task(300, "vision"), 0);
+        queue.push(task(301, "vision"), 0);
+        let order: Vec<u32> = (0..4).filter_map(|_| queue.pop(&PriorityFirst, 0)).map(|t| t.id).collect();
+        assert_eq!(order, vec![300, 106, 301, 107]);
+    }
+}
@@ -0,0 +1,1782 @@
+// backend/rust/src/ffi.rs
+// Purpose: Thin C FFI layer over a process-global Scheduler instance, used by
+// backend/python/ai_engine/delegator.py via ctypes. Compiled only with the `ffi` feature
+// (enabled by default); Rust applications can embed `Scheduler` directly instead.
+// Returned strings are heap-allocated and must be released with free_string_ffi.
+// Calls without a scheduler handle share one Tokio runtime, created on first use or
+// explicitly with init_ffi, and torn down with shutdown_ffi.
+// In buffered mode (start_buffered_ffi) submissions made before the scheduler is ready are
+// held in a bounded, disk-spilling buffer and answered with a provisional token.
+// Live task updates are followed with watch_open_ffi, drained with watch_poll_ffi, and
+// released with watch_close_ffi, instead of polling get_task_status_ffi per task.
+// Every function returning a string has a *_with_status_ffi twin taking an extra `int32_t*`
+// out-parameter that receives an FfiStatus code (0 = OK, negative = error category); the
+// original functions are thin wrappers passing null and behave exactly as before.
+// New callers should prefer the mrtodp_* functions (see ffi_result.rs), which return a
+// stable MrtodpErrorCode and a JSON payload; the string-returning functions here remain
+// for existing callers. Independent scheduler instances behind opaque handles are in
+// ffi_instance.rs. build.rs generates the C declarations for all of these into
+// include/mrtodp_scheduler.h.
+
+// FFI entry points validate their raw pointers (null checks) before dereferencing and keep
+// a safe `extern "C"` signature so existing ctypes callers are unaffected.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime};
+use crate::auction::Bid;
+use crate::audit::AuditQuery;
+use crate::compatibility::RobotModel;
+use crate::events::EventFilter;
+use crate::geometry::Location;
+use crate::handoff::HandoffRequest;
+use crate::history::HistoryFilter;
+use crate::missions::Mission;
+use crate::scheduler::{Scheduler, TaskState};
+use crate::shutdown::SHUTTING_DOWN_ERROR;
+use crate::submission_buffer::SubmissionBuffer;
+use crate::submitter_limits::{Submitter, SubmitterLimits};
+use crate::task::Task;
+use crate::task_list::TaskListFilter;
+use crate::templates::TaskTemplate;
+use crate::watch::Watch;
+
+// Shared runtime for every FFI call; None before first use and after shutdown_ffi
+static RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+// How long shutdown_ffi waits for in-flight work before abandoning it
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn build_runtime(worker_threads: usize) -> Result<Runtime, String> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    if worker_threads > 0 {
+        builder.worker_threads(worker_threads);
+    }
+    builder
+        .enable_all()
+        .thread_name("mrtodp-ffi")
+        .build()
+        .map_err(|e| format!("Tokio runtime creation failed: {}", e))
+}
+
+// Handle to the shared runtime, creating it with default settings on first use
+fn runtime_handle() -> Result<Handle, String> {
+    let mut guard = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    if SHUT_DOWN.load(Ordering::SeqCst) {
+        return Err("FFI runtime has been shut down".to_string());
+    }
+    if guard.is_none() {
+        *guard = Some(build_runtime(0)?);
+    }
+    Ok(guard.as_ref().expect("runtime initialized above").handle().clone())
+}
+
+// Scheduler instance the calls on this thread run against, set while a handle-based call
+// (see ffi_instance.rs) runs; None = the process-global scheduler on the shared runtime
+thread_local! {
+    static TARGET: RefCell<Option<(Handle, Scheduler)>> = const { RefCell::new(None) };
+}
+
+// Run `call` with the FFI functions it makes directed at `scheduler` on `runtime`
+pub(crate) fn with_target<R>(runtime: Handle, scheduler: Scheduler, call: impl FnOnce() -> R) -> R {
+    let previous = TARGET.with(|target| target.replace(Some((runtime, scheduler))));
+    let result = call();
+    TARGET.with(|target| target.replace(previous));
+    result
+}
+
+fn targets_instance() -> bool {
+    TARGET.with(|target| target.borrow().is_some())
+}
+
+// Run a scheduler call to completion on the target's runtime
+fn run<F, Fut, T>(call: F) -> Result<T, String>
+where
+    F: FnOnce(Scheduler) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let (handle, scheduler) = match TARGET.with(|target| target.borrow().clone()) {
+        Some(target) => target,
+        None => {
+            let handle = runtime_handle()?;
+            let scheduler = {
+                let _context = handle.enter();
+                scheduler().clone()
+            };
+            (handle, scheduler)
+        }
+    };
+    Ok(handle.block_on(call(scheduler)))
+}
+
+// Status codes written through the `status` out-parameter of the *_with_status_ffi
+// functions, so callers can branch without parsing messages. Stable: values are never
+// reused. The message string is returned as before, "Error: ..." on failure.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FfiStatus {
+    Ok = 0,
+    InvalidArgument = -1, // Null pointer, invalid UTF-8, malformed JSON, or unknown log level
+    NotFound = -2,        // Unknown task or robot
+    Rejected = -3,        // The scheduler refused the request (validation, quota, state)
+    Unavailable = -4,     // Runtime shut down or scheduler not running
+    Internal = -5,        // Response could not be serialized
+}
+
+impl FfiStatus {
+    pub(crate) fn from_code(code: i32) -> Option<Self> {
+        [FfiStatus::Ok, FfiStatus::InvalidArgument, FfiStatus::NotFound, FfiStatus::Rejected, FfiStatus::Unavailable, FfiStatus::Internal]
+            .into_iter()
+            .find(|status| *status as i32 == code)
+    }
+
+    // Classify a scheduler error; its "Unknown task: N" and "Unknown robot: X" messages
+    // mean the target doesn't exist, a scheduler shutting down is unavailable, and everything
+    // else is a refusal
+    fn of_scheduler_error(message: &str) -> Self {
+        let unknown = ["Unknown task:", "Unknown robot:", "Unknown mission:", "Unknown template:", "Unknown dead letter:"];
+        if unknown.iter().any(|prefix| message.starts_with(prefix)) {
+            FfiStatus::NotFound
+        } else if message.starts_with(SHUTTING_DOWN_ERROR) {
+            FfiStatus::Unavailable
+        } else {
+            FfiStatus::Rejected
+        }
+    }
+}
+
+// Run a fallible scheduler call, tagging failures with their status code
+fn run_fallible<F, Fut, T>(call: F) -> Result<T, (FfiStatus, String)>
+where
+    F: FnOnce(Scheduler) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    run(call).map_err(|e| (FfiStatus::Unavailable, e))?.map_err(|e| (FfiStatus::of_scheduler_error(&e), e))
+}
+
+// Write `code` through `status` unless the caller passed null
+fn set_status(status: *mut i32, code: FfiStatus) {
+    if !status.is_null() {
+        unsafe { *status = code as i32 };
+    }
+}
+
+fn reply(status: *mut i32, message: impl Into<Vec<u8>>) -> *mut c_char {
+    set_status(status, FfiStatus::Ok);
+    CString::new(message).unwrap().into_raw()
+}
+
+fn error(status: *mut i32, code: FfiStatus, message: impl std::fmt::Display) -> *mut c_char {
+    set_status(status, code);
+    CString::new(format!("Error: {}", message)).unwrap().into_raw()
+}
+
+// FFI function to create the shared runtime up front; `worker_threads` = 0 uses one per core.
+// Optional: the first other FFI call creates it with defaults.
+#[no_mangle]
+pub extern "C" fn init_ffi(worker_threads: u32) -> *mut c_char {
+    init_with_status_ffi(worker_threads, std::ptr::null_mut())
+}
+
+// Like init_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn init_with_status_ffi(worker_threads: u32, status: *mut i32) -> *mut c_char {
+    let mut guard = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    if SHUT_DOWN.load(Ordering::SeqCst) {
+        return error(status, FfiStatus::Unavailable, "FFI runtime has been shut down");
+    }
+    if guard.is_some() {
+        return error(status, FfiStatus::Rejected, "FFI runtime already initialized");
+    }
+    match build_runtime(worker_threads as usize) {
+        Ok(runtime) => {
+            *guard = Some(runtime);
+            reply(status, "Success")
+        }
+        Err(e) => error(status, FfiStatus::Unavailable, e),
+    }
+}
+
+// FFI function to install a process-wide log subscriber for the scheduler's tracing output.
+// `level` is one of trace, debug, info, warn, or error; `json_output` selects JSON lines
+// over human-readable text. Can be called once per process.
+#[no_mangle]
+pub extern "C" fn init_tracing_ffi(level: *const c_char, json_output: bool) -> *mut c_char {
+    init_tracing_with_status_ffi(level, json_output, std::ptr::null_mut())
+}
+
+// Like init_tracing_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn init_tracing_with_status_ffi(level: *const c_char, json_output: bool, status: *mut i32) -> *mut c_char {
+    let level = unsafe {
+        if level.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null level");
+        }
+        match CStr::from_ptr(level).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid level"),
+        }
+    };
+    let level: tracing::Level = match level.parse() {
+        Ok(level) => level,
+        Err(_) => return error(status, FfiStatus::InvalidArgument, format!("Unknown log level: {}", level)),
+    };
+    let subscriber = tracing_subscriber::fmt().with_max_level(level);
+    let installed = if json_output { subscriber.json().try_init() } else { subscriber.try_init() };
+    match installed {
+        Ok(()) => reply(status, "Success"),
+        Err(e) => error(status, FfiStatus::Rejected, format!("Tracing already initialized: {}", e)),
+    }
+}
+
+// FFI function to stop the scheduler and tear down the shared runtime. Later FFI calls
+// return an error; call it once, from a thread the runtime does not own. Tasks in flight
+// are abandoned; call shutdown_scheduler_ffi first to let them finish or checkpoint them.
+#[no_mangle]
+pub extern "C" fn shutdown_ffi() -> *mut c_char {
+    shutdown_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like shutdown_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn shutdown_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let runtime = {
+        let mut guard = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+        if SHUT_DOWN.swap(true, Ordering::SeqCst) {
+            return error(status, FfiStatus::Unavailable, "FFI runtime has been shut down");
+        }
+        guard.take()
+    };
+    if let Some(runtime) = runtime {
+        runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    }
+    reply(status, "Success")
+}
+
+// FFI function to shut the scheduler down gracefully (see shutdown.rs): new tasks are
+// refused, tasks on robots get `grace_period_ms` to finish and are suspended after it, and
+// the store is flushed. Returns {"finished", "suspended", "unfinished"}. Queries keep working
+// until shutdown_ffi tears down the runtime.
+#[no_mangle]
+pub extern "C" fn shutdown_scheduler_ffi(grace_period_ms: u64) -> *mut c_char {
+    shutdown_scheduler_with_status_ffi(grace_period_ms, std::ptr::null_mut())
+}
+
+// Like shutdown_scheduler_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn shutdown_scheduler_with_status_ffi(grace_period_ms: u64, status: *mut i32) -> *mut c_char {
+    let report = match run_fallible(|scheduler| async move { scheduler.shutdown(Duration::from_millis(grace_period_ms)).await }) {
+        Ok(report) => report,
+        Err((code, e)) => return error(status, code, e),
+    };
+    match serde_json::to_string(&report) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// Global scheduler instance for FFI, built on first use
+static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+
+// Access the global scheduler; must be called from within a Tokio runtime on first use
+fn scheduler() -> &'static Scheduler {
+    SCHEDULER.get_or_init(|| {
+        let (scheduler, workers) = Scheduler::builder()
+            .build()
+            .expect("default scheduler configuration is valid");
+        workers.spawn();
+        scheduler
+    })
+}
+
+// Buffered-mode state: the buffer (None when buffering is off), readiness of the core, and
+// each provisional token not yet looked up after its flush, with its outcome once flushed
+static BUFFER: Mutex<Option<SubmissionBuffer>> = Mutex::new(None);
+static READY: AtomicBool = AtomicBool::new(false);
+static BUFFERED_OUTCOMES: OnceLock<Mutex<HashMap<String, Option<BufferedOutcome>>>> = OnceLock::new();
+
+// Status and reply of a flushed submission
+type BufferedOutcome = (FfiStatus, String);
+
+fn buffered_outcomes() -> &'static Mutex<HashMap<String, Option<BufferedOutcome>>> {
+    BUFFERED_OUTCOMES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Submit buffered tasks until the buffer is empty, then mark the core ready. Readiness is set
+// under the buffer lock so no submission can slip into the buffer after the last flush.
+async fn flush_buffer() {
+    loop {
+        let submissions = {
+            let mut guard = BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+            let drained = match guard.as_mut() {
+                Some(buffer) if !buffer.is_empty() => buffer.drain(),
+                _ => Ok(Vec::new()),
+            };
+            match drained {
+                Ok(submissions) if submissions.is_empty() => {
+                    READY.store(true, Ordering::SeqCst);
+                    return;
+                }
+                Ok(submissions) => submissions,
+                Err(e) => {
+                    tracing::error!(error = %e, "submission buffer flush failed");
+                    READY.store(true, Ordering::SeqCst);
+                    return;
+                }
+            }
+        };
+        for submission in submissions {
+            let outcome = match scheduler().schedule_task(submission.task).await {
+                Ok(()) => (FfiStatus::Ok, "Success".to_string()),
+                Err(e) => (FfiStatus::of_scheduler_error(&e), format!("Error: {}", e)),
+            };
+            buffered_outcomes().lock().unwrap_or_else(|e| e.into_inner()).insert(submission.token, Some(outcome));
+        }
+    }
+}
+
+// FFI function to enable buffered mode and initialize the scheduler in the background.
+// `capacity` bounds in-memory buffering; with a non-null `spill_path`, up to `spill_capacity`
+// further submissions are spilled to that file.
+#[no_mangle]
+pub extern "C" fn start_buffered_ffi(capacity: u32, spill_path: *const c_char, spill_capacity: u32) -> *mut c_char {
+    start_buffered_with_status_ffi(capacity, spill_path, spill_capacity, std::ptr::null_mut())
+}
+
+// Like start_buffered_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn start_buffered_with_status_ffi(capacity: u32, spill_path: *const c_char, spill_capacity: u32, status: *mut i32) -> *mut c_char {
+    let spill_path = if spill_path.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(spill_path) }.to_str() {
+            Ok(s) => Some(PathBuf::from(s)),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid spill path"),
+        }
+    };
+    {
+        let mut guard = BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_some() || SCHEDULER.get().is_some() {
+            return error(status, FfiStatus::Rejected, "Scheduler already started");
+        }
+        match SubmissionBuffer::new(capacity as usize, spill_path, spill_capacity as usize) {
+            Ok(buffer) => *guard = Some(buffer),
+            Err(e) => return error(status, FfiStatus::Internal, e),
+        }
+    }
+    match runtime_handle() {
+        Ok(handle) => {
+            handle.spawn(async {
+                scheduler();
+                flush_buffer().await;
+            });
+            reply(status, "Success")
+        }
+        Err(e) => {
+            *BUFFER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            error(status, FfiStatus::Unavailable, e)
+        }
+    }
+}
+
+// FFI function to look up the outcome of a provisional token: "Pending", "Success", or an
+// error. An outcome is returned once; later lookups, like lookups of tokens never issued,
+// fail with "Unknown token".
+#[no_mangle]
+pub extern "C" fn get_buffered_status_ffi(token: *const c_char) -> *mut c_char {
+    get_buffered_status_with_status_ffi(token, std::ptr::null_mut())
+}
+
+// Like get_buffered_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_buffered_status_with_status_ffi(token: *const c_char, status: *mut i32) -> *mut c_char {
+    if token.is_null() {
+        return error(status, FfiStatus::InvalidArgument, "Null token");
+    }
+    let token = match unsafe { CStr::from_ptr(token) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid token"),
+    };
+    let mut outcomes = buffered_outcomes().lock().unwrap_or_else(|e| e.into_inner());
+    match outcomes.remove(token) {
+        Some(Some((code, outcome))) => {
+            set_status(status, code);
+            CString::new(outcome).unwrap().into_raw()
+        }
+        Some(None) => {
+            outcomes.insert(token.to_string(), None);
+            reply(status, "Pending")
+        }
+        None => error(status, FfiStatus::NotFound, format!("Unknown token: {}", token)),
+    }
+}
+
+// FFI function to register robot capabilities
+#[no_mangle]
+pub extern "C" fn register_robot_ffi(robot_id: *const c_char, capabilities_json: *const c_char) -> *mut c_char {
+    register_robot_with_status_ffi(robot_id, capabilities_json, std::ptr::null_mut())
+}
+
+// Like register_robot_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn register_robot_with_status_ffi(robot_id: *const c_char, capabilities_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+
+    let capabilities: Vec<String> = unsafe {
+        if capabilities_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null capabilities JSON");
+        }
+        match CStr::from_ptr(capabilities_json).to_str() {
+            Ok(s) => match serde_json::from_str(s) {
+                Ok(caps) => caps,
+                Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+            },
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid capabilities JSON"),
+        }
+    };
+
+    match run_fallible(|scheduler| async move { scheduler.register_robot(robot_id, capabilities).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to add and remove robot capabilities, returning the updated set as JSON
+#[no_mangle]
+pub extern "C" fn update_robot_capabilities_ffi(robot_id: *const c_char, add_json: *const c_char, remove_json: *const c_char) -> *mut c_char {
+    update_robot_capabilities_with_status_ffi(robot_id, add_json, remove_json, std::ptr::null_mut())
+}
+
+// Like update_robot_capabilities_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn update_robot_capabilities_with_status_ffi(
+    robot_id: *const c_char,
+    add_json: *const c_char,
+    remove_json: *const c_char,
+    status: *mut i32,
+) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let mut lists: [Vec<String>; 2] = Default::default();
+    for (list, json) in lists.iter_mut().zip([add_json, remove_json]) {
+        *list = unsafe {
+            if json.is_null() {
+                return error(status, FfiStatus::InvalidArgument, "Null capabilities JSON");
+            }
+            match CStr::from_ptr(json).to_str() {
+                Ok(s) => match serde_json::from_str(s) {
+                    Ok(caps) => caps,
+                    Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+                },
+                Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid capabilities JSON"),
+            }
+        };
+    }
+    let [add, remove] = lists;
+    let capabilities = match run_fallible(|scheduler| async move { scheduler.update_robot_capabilities(&robot_id, add, remove).await }) {
+        Ok(capabilities) => capabilities,
+        Err((code, e)) => return error(status, code, e),
+    };
+    match serde_json::to_string(&capabilities) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to stop assigning a robot new tasks while its in-flight tasks finish
+#[no_mangle]
+pub extern "C" fn drain_robot_ffi(robot_id: *const c_char) -> *mut c_char {
+    drain_robot_with_status_ffi(robot_id, std::ptr::null_mut())
+}
+
+// Like drain_robot_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn drain_robot_with_status_ffi(robot_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.drain_robot(&robot_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to take a robot out of service, requeueing tasks bound to it
+#[no_mangle]
+pub extern "C" fn deregister_robot_ffi(robot_id: *const c_char) -> *mut c_char {
+    deregister_robot_with_status_ffi(robot_id, std::ptr::null_mut())
+}
+
+// Like deregister_robot_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn deregister_robot_with_status_ffi(robot_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.deregister_robot(&robot_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to declare how many tasks a robot can execute in parallel
+#[no_mangle]
+pub extern "C" fn set_robot_slots_ffi(robot_id: *const c_char, slots: u32) -> *mut c_char {
+    set_robot_slots_with_status_ffi(robot_id, slots, std::ptr::null_mut())
+}
+
+// Like set_robot_slots_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn set_robot_slots_with_status_ffi(robot_id: *const c_char, slots: u32, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.set_robot_slots(&robot_id, slots).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to declare a robot's hardware model and firmware version
+#[no_mangle]
+pub extern "C" fn set_robot_model_ffi(robot_id: *const c_char, model: *const c_char, firmware: *const c_char) -> *mut c_char {
+    set_robot_model_with_status_ffi(robot_id, model, firmware, std::ptr::null_mut())
+}
+
+// Like set_robot_model_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn set_robot_model_with_status_ffi(robot_id: *const c_char, model: *const c_char, firmware: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let model = unsafe {
+        if model.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null model");
+        }
+        match CStr::from_ptr(model).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid model"),
+        }
+    };
+    let firmware = unsafe {
+        if firmware.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null firmware");
+        }
+        match CStr::from_ptr(firmware).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid firmware"),
+        }
+    };
+    let model = RobotModel { model, firmware };
+    match run_fallible(|scheduler| async move { scheduler.set_robot_model(&robot_id, model).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to report a robot's emergency stop and page its on-call
+#[no_mangle]
+pub extern "C" fn report_emergency_stop_ffi(robot_id: *const c_char, detail: *const c_char) -> *mut c_char {
+    report_emergency_stop_with_status_ffi(robot_id, detail, std::ptr::null_mut())
+}
+
+// Like report_emergency_stop_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn report_emergency_stop_with_status_ffi(robot_id: *const c_char, detail: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let detail = unsafe {
+        if detail.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null detail");
+        }
+        match CStr::from_ptr(detail).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid detail"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.report_emergency_stop(&robot_id, &detail).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to report a robot's battery level (0 to 100 percent); returns "Charging: <task
+// ID>" when the report sends the robot to charge (see charging.rs), "Success" otherwise
+#[no_mangle]
+pub extern "C" fn report_battery_ffi(robot_id: *const c_char, level: f64) -> *mut c_char {
+    report_battery_with_status_ffi(robot_id, level, std::ptr::null_mut())
+}
+
+// Like report_battery_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn report_battery_with_status_ffi(robot_id: *const c_char, level: f64, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.report_battery(&robot_id, level).await }) {
+        Ok(Some(task_id)) => reply(status, format!("Charging: {}", task_id)),
+        Ok(None) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to replace the zones a robot may operate in (see geofencing.rs);
+// `zones_json` is a JSON array of zone names, empty to bar the robot from every zone
+#[no_mangle]
+pub extern "C" fn set_robot_zones_ffi(robot_id: *const c_char, zones_json: *const c_char) -> *mut c_char {
+    set_robot_zones_with_status_ffi(robot_id, zones_json, std::ptr::null_mut())
+}
+
+// Like set_robot_zones_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn set_robot_zones_with_status_ffi(robot_id: *const c_char, zones_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let zones: Vec<String> = unsafe {
+        if zones_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null zones JSON");
+        }
+        match CStr::from_ptr(zones_json).to_str() {
+            Ok(s) => match serde_json::from_str(s) {
+                Ok(zones) => zones,
+                Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+            },
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid zones JSON"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.set_robot_zones(&robot_id, zones) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to close a robot's tripped circuit breaker (see breakers.rs)
+#[no_mangle]
+pub extern "C" fn reset_breaker_ffi(robot_id: *const c_char) -> *mut c_char {
+    reset_breaker_with_status_ffi(robot_id, std::ptr::null_mut())
+}
+
+// Like reset_breaker_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn reset_breaker_with_status_ffi(robot_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.reset_breaker(&robot_id) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to report where a robot is; `location_json` is a pose ({"frame_id", "x",
+// "y", ...}) or a lat/lon point ({"lat", "lon", "alt"}) (see location.rs)
+#[no_mangle]
+pub extern "C" fn report_robot_location_ffi(robot_id: *const c_char, location_json: *const c_char) -> *mut c_char {
+    report_robot_location_with_status_ffi(robot_id, location_json, std::ptr::null_mut())
+}
+
+// Like report_robot_location_ffi, also writing a status code (see FfiStatus) to `status`
+// unless null
+#[no_mangle]
+pub extern "C" fn report_robot_location_with_status_ffi(robot_id: *const c_char, location_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let location: Location = unsafe {
+        if location_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null location JSON");
+        }
+        match CStr::from_ptr(location_json).to_str() {
+            Ok(s) => match serde_json::from_str(s) {
+                Ok(location) => location,
+                Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+            },
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid location JSON"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.report_robot_location(&robot_id, location).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to bid on behalf of a robot for a task under auction
+#[no_mangle]
+pub extern "C" fn submit_bid_ffi(task_id: u32, robot_id: *const c_char, cost: f64, eta_ms: u64) -> *mut c_char {
+    submit_bid_with_status_ffi(task_id, robot_id, cost, eta_ms, std::ptr::null_mut())
+}
+
+// Like submit_bid_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn submit_bid_with_status_ffi(task_id: u32, robot_id: *const c_char, cost: f64, eta_ms: u64, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    let bid = Bid { robot_id, cost, eta_ms };
+    match run_fallible(|scheduler| async move { scheduler.submit_bid(task_id, bid) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to schedule a task
+#[no_mangle]
+pub extern "C" fn schedule_task_ffi(task_json: *const c_char) -> *mut c_char {
+    schedule_task_with_status_ffi(task_json, std::ptr::null_mut())
+}
+
+// Like schedule_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn schedule_task_with_status_ffi(task_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let task_json = unsafe {
+        if task_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null task JSON");
+        }
+        match CStr::from_ptr(task_json).to_str() {
+            Ok(s) => s,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid task JSON"),
+        }
+    };
+
+    let task: Task = match serde_json::from_str(task_json) {
+        Ok(task) => task,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+
+    // While the core is starting in buffered mode, hold the task and return a provisional token
+    if !READY.load(Ordering::SeqCst) && !targets_instance() {
+        let mut guard = BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        if let (Some(buffer), false) = (guard.as_mut(), READY.load(Ordering::SeqCst)) {
+            return match buffer.push(task) {
+                Ok(token) => {
+                    buffered_outcomes().lock().unwrap_or_else(|e| e.into_inner()).insert(token.clone(), None);
+                    reply(status, format!("Buffered: {}", token))
+                }
+                Err(e) => error(status, FfiStatus::Rejected, e),
+            };
+        }
+    }
+
+    match run_fallible(|scheduler| async move { scheduler.schedule_task(task).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to register (or replace) a task template after validating it
+#[no_mangle]
+pub extern "C" fn register_template_ffi(template_json: *const c_char) -> *mut c_char {
+    register_template_with_status_ffi(template_json, std::ptr::null_mut())
+}
+
+// Like register_template_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn register_template_with_status_ffi(template_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let template_json = unsafe {
+        if template_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null template JSON");
+        }
+        match CStr::from_ptr(template_json).to_str() {
+            Ok(s) => s,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid template JSON"),
+        }
+    };
+    let template: TaskTemplate = match serde_json::from_str(template_json) {
+        Ok(template) => template,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    match run_fallible(|scheduler| async move { scheduler.register_template(template) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to register (or replace) the JSON Schema that payloads of `task_type` must match
+#[no_mangle]
+pub extern "C" fn register_payload_schema_ffi(task_type: *const c_char, schema_json: *const c_char) -> *mut c_char {
+    register_payload_schema_with_status_ffi(task_type, schema_json, std::ptr::null_mut())
+}
+
+// Like register_payload_schema_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn register_payload_schema_with_status_ffi(task_type: *const c_char, schema_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let (task_type, schema_json) = unsafe {
+        if task_type.is_null() || schema_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null task type or schema JSON");
+        }
+        match (CStr::from_ptr(task_type).to_str(), CStr::from_ptr(schema_json).to_str()) {
+            (Ok(task_type), Ok(schema_json)) => (task_type.to_string(), schema_json),
+            _ => return error(status, FfiStatus::InvalidArgument, "Invalid UTF-8 in task type or schema JSON"),
+        }
+    };
+    let schema: serde_json::Value = match serde_json::from_str(schema_json) {
+        Ok(schema) => schema,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    match run_fallible(|scheduler| async move { scheduler.register_payload_schema(&task_type, schema) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to replace the limits of a submitter: `kind` is "namespace" or "source" and
+// `limits_json` is {"max_queued": n, "max_per_minute": n}, either optional; {} removes them
+#[no_mangle]
+pub extern "C" fn set_submitter_limits_ffi(kind: *const c_char, name: *const c_char, limits_json: *const c_char) -> *mut c_char {
+    set_submitter_limits_with_status_ffi(kind, name, limits_json, std::ptr::null_mut())
+}
+
+// Like set_submitter_limits_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn set_submitter_limits_with_status_ffi(kind: *const c_char, name: *const c_char, limits_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let (kind, name, limits_json) = unsafe {
+        if kind.is_null() || name.is_null() || limits_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null submitter or limits JSON");
+        }
+        match (CStr::from_ptr(kind).to_str(), CStr::from_ptr(name).to_str(), CStr::from_ptr(limits_json).to_str()) {
+            (Ok(kind), Ok(name), Ok(limits_json)) => (kind, name, limits_json),
+            _ => return error(status, FfiStatus::InvalidArgument, "Invalid UTF-8 in submitter or limits JSON"),
+        }
+    };
+    let submitter = match Submitter::parse(kind, name) {
+        Ok(submitter) => submitter,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, e),
+    };
+    let limits: SubmitterLimits = match serde_json::from_str(limits_json) {
+        Ok(limits) => limits,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    match run(|scheduler| async move { scheduler.set_submitter_limits(submitter, limits) }) {
+        Ok(()) => reply(status, "Success"),
+        Err(e) => error(status, FfiStatus::Unavailable, e),
+    }
+}
+
+// FFI function to build a task from a template and submit it; returns the task as JSON
+#[no_mangle]
+pub extern "C" fn instantiate_template_ffi(template_id: *const c_char, params_json: *const c_char) -> *mut c_char {
+    instantiate_template_with_status_ffi(template_id, params_json, std::ptr::null_mut())
+}
+
+// Like instantiate_template_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn instantiate_template_with_status_ffi(template_id: *const c_char, params_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let (template_id, params_json) = unsafe {
+        if template_id.is_null() || params_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null template ID or parameters");
+        }
+        match (CStr::from_ptr(template_id).to_str(), CStr::from_ptr(params_json).to_str()) {
+            (Ok(id), Ok(params)) => (id.to_string(), params.to_string()),
+            _ => return error(status, FfiStatus::InvalidArgument, "Invalid template ID or parameters"),
+        }
+    };
+    let task = match run_fallible(|scheduler| async move { scheduler.instantiate_template(&template_id, &params_json).await }) {
+        Ok(task) => task,
+        Err((code, e)) => return error(status, code, e),
+    };
+    match serde_json::to_string(&task) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to query task state, attempts, and transition reasons as JSON
+#[no_mangle]
+pub extern "C" fn get_task_status_ffi(task_id: u32) -> *mut c_char {
+    get_task_status_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like get_task_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_task_status_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run(|scheduler| async move { scheduler.task_record(task_id).await }) {
+        Ok(Some(record)) => match serde_json::to_string(&record) {
+            Ok(json) => reply(status, json),
+            Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+        },
+        Ok(None) => error(status, FfiStatus::NotFound, format!("Unknown task: {}", task_id)),
+        Err(e) => error(status, FfiStatus::Unavailable, e),
+    }
+}
+
+// Open watches by ID, polled from the host language
+static WATCHES: OnceLock<Mutex<HashMap<u64, Watch>>> = OnceLock::new();
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+fn watches() -> &'static Mutex<HashMap<u64, Watch>> {
+    WATCHES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// FFI function to start watching task updates matching an EventFilter given as JSON (null
+// for every task); returns the watch ID to poll
+#[no_mangle]
+pub extern "C" fn watch_open_ffi(filter_json: *const c_char) -> *mut c_char {
+    watch_open_with_status_ffi(filter_json, std::ptr::null_mut())
+}
+
+// Like watch_open_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn watch_open_with_status_ffi(filter_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let filter = if filter_json.is_null() {
+        EventFilter::default()
+    } else {
+        let parsed = unsafe { CStr::from_ptr(filter_json) }.to_str().map_err(|e| e.to_string()).and_then(|s| serde_json::from_str(s).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(filter) => filter,
+            Err(e) => return error(status, FfiStatus::InvalidArgument, format!("Invalid filter JSON: {}", e)),
+        }
+    };
+    let watch = match run(|scheduler| async move { scheduler.watch(filter) }) {
+        Ok(watch) => watch,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    let watch_id = NEXT_WATCH_ID.fetch_add(1, Ordering::SeqCst);
+    watches().lock().unwrap_or_else(|e| e.into_inner()).insert(watch_id, watch);
+    reply(status, watch_id.to_string())
+}
+
+// FFI function to take up to `max_events` task updates that arrived since the last poll,
+// without waiting; returns a JSON array, empty when there are none
+#[no_mangle]
+pub extern "C" fn watch_poll_ffi(watch_id: u64, max_events: u32) -> *mut c_char {
+    watch_poll_with_status_ffi(watch_id, max_events, std::ptr::null_mut())
+}
+
+// Like watch_poll_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn watch_poll_with_status_ffi(watch_id: u64, max_events: u32, status: *mut i32) -> *mut c_char {
+    let mut watches = watches().lock().unwrap_or_else(|e| e.into_inner());
+    let Some(watch) = watches.get_mut(&watch_id) else {
+        return error(status, FfiStatus::NotFound, format!("Unknown watch: {}", watch_id));
+    };
+    let updates = match watch.poll(max_events as usize) {
+        Ok(updates) => updates,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&updates) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to stop a watch and release it
+#[no_mangle]
+pub extern "C" fn watch_close_ffi(watch_id: u64) -> *mut c_char {
+    watch_close_with_status_ffi(watch_id, std::ptr::null_mut())
+}
+
+// Like watch_close_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn watch_close_with_status_ffi(watch_id: u64, status: *mut i32) -> *mut c_char {
+    match watches().lock().unwrap_or_else(|e| e.into_inner()).remove(&watch_id) {
+        Some(_) => reply(status, "Success"),
+        None => error(status, FfiStatus::NotFound, format!("Unknown watch: {}", watch_id)),
+    }
+}
+
+// FFI function to hold a pending task for manual operator intervention
+#[no_mangle]
+pub extern "C" fn hold_task_ffi(task_id: u32) -> *mut c_char {
+    hold_task_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like hold_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn hold_task_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.hold_task(task_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to release a held task back to dispatch
+#[no_mangle]
+pub extern "C" fn release_task_ffi(task_id: u32) -> *mut c_char {
+    release_task_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like release_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn release_task_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.release_task(task_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to cancel a task; returns "Cancelled", or "Cancelling" for a running task
+// that stops cooperatively once its robot reports
+#[no_mangle]
+pub extern "C" fn cancel_task_ffi(task_id: u32) -> *mut c_char {
+    cancel_task_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like cancel_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn cancel_task_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.cancel_task(task_id).await }) {
+        Ok(TaskState::Cancelled) => reply(status, "Cancelled"),
+        Ok(_) => reply(status, "Cancelling"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to complete an assigned or running task on behalf of a controller, only if
+// its record is still at `expected_version`; returns "Completed: <new version>", or a
+// "Version conflict" error if the task changed since (see versioning.rs)
+#[no_mangle]
+pub extern "C" fn complete_task_ffi(task_id: u32, expected_version: u64) -> *mut c_char {
+    complete_task_with_status_ffi(task_id, expected_version, std::ptr::null_mut())
+}
+
+// Like complete_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn complete_task_with_status_ffi(task_id: u32, expected_version: u64, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.complete_task(task_id, expected_version).await }) {
+        Ok(version) => reply(status, format!("Completed: {}", version)),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to register a mission with its name and metadata
+#[no_mangle]
+pub extern "C" fn create_mission_ffi(mission_json: *const c_char) -> *mut c_char {
+    create_mission_with_status_ffi(mission_json, std::ptr::null_mut())
+}
+
+// Like create_mission_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn create_mission_with_status_ffi(mission_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let mission_json = unsafe {
+        if mission_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null mission JSON");
+        }
+        match CStr::from_ptr(mission_json).to_str() {
+            Ok(s) => s,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid mission JSON"),
+        }
+    };
+    let mission: Mission = match serde_json::from_str(mission_json) {
+        Ok(mission) => mission,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    match run_fallible(|scheduler| async move { scheduler.create_mission(mission) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to query a mission's task counts, percentage complete, and state as JSON
+#[no_mangle]
+pub extern "C" fn get_mission_status_ffi(mission_id: *const c_char) -> *mut c_char {
+    get_mission_status_with_status_ffi(mission_id, std::ptr::null_mut())
+}
+
+// Like get_mission_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_mission_status_with_status_ffi(mission_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let mission_id = unsafe {
+        if mission_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null mission ID");
+        }
+        match CStr::from_ptr(mission_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid mission ID"),
+        }
+    };
+    let lookup = mission_id.clone();
+    match run(|scheduler| async move { scheduler.get_mission_status(&lookup).await }) {
+        Ok(Some(mission_status)) => match serde_json::to_string(&mission_status) {
+            Ok(json) => reply(status, json),
+            Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+        },
+        Ok(None) => error(status, FfiStatus::NotFound, format!("Unknown mission: {}", mission_id)),
+        Err(e) => error(status, FfiStatus::Unavailable, e),
+    }
+}
+
+// FFI function to cancel every unfinished task of a mission; returns the mission status as
+// JSON
+#[no_mangle]
+pub extern "C" fn cancel_mission_ffi(mission_id: *const c_char) -> *mut c_char {
+    cancel_mission_with_status_ffi(mission_id, std::ptr::null_mut())
+}
+
+// Like cancel_mission_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn cancel_mission_with_status_ffi(mission_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let mission_id = unsafe {
+        if mission_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null mission ID");
+        }
+        match CStr::from_ptr(mission_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid mission ID"),
+        }
+    };
+    let mission_status = match run_fallible(|scheduler| async move { scheduler.cancel_mission(&mission_id).await }) {
+        Ok(mission_status) => mission_status,
+        Err((code, e)) => return error(status, code, e),
+    };
+    match serde_json::to_string(&mission_status) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to pin a pending task to a robot as a manual override
+#[no_mangle]
+pub extern "C" fn pin_task_ffi(task_id: u32, robot_id: *const c_char) -> *mut c_char {
+    pin_task_with_status_ffi(task_id, robot_id, std::ptr::null_mut())
+}
+
+// Like pin_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn pin_task_with_status_ffi(task_id: u32, robot_id: *const c_char, status: *mut i32) -> *mut c_char {
+    let robot_id = unsafe {
+        if robot_id.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null robot ID");
+        }
+        match CStr::from_ptr(robot_id).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid robot ID"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.pin_task(task_id, &robot_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to hand a task off from its robot to another (see handoff.rs);
+// `request_json` is {"to_robot": optional robot ID, "progress": any JSON}
+#[no_mangle]
+pub extern "C" fn hand_off_task_ffi(task_id: u32, request_json: *const c_char) -> *mut c_char {
+    hand_off_task_with_status_ffi(task_id, request_json, std::ptr::null_mut())
+}
+
+// Like hand_off_task_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn hand_off_task_with_status_ffi(task_id: u32, request_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let request: HandoffRequest = unsafe {
+        if request_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null hand-off JSON");
+        }
+        match CStr::from_ptr(request_json).to_str() {
+            Ok(s) => match serde_json::from_str(s) {
+                Ok(request) => request,
+                Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+            },
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid hand-off JSON"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.hand_off_task(task_id, request).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to switch the dispatch order ("priority_first" or "earliest_deadline_first")
+#[no_mangle]
+pub extern "C" fn set_policy_ffi(policy_name: *const c_char) -> *mut c_char {
+    set_policy_with_status_ffi(policy_name, std::ptr::null_mut())
+}
+
+// Like set_policy_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn set_policy_with_status_ffi(policy_name: *const c_char, status: *mut i32) -> *mut c_char {
+    let name = unsafe {
+        if policy_name.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null policy name");
+        }
+        match CStr::from_ptr(policy_name).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid policy name"),
+        }
+    };
+    match run_fallible(|scheduler| async move { scheduler.set_policy_by_name(&name) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to evaluate a built-in policy in shadow for `duration_ms` without applying it
+#[no_mangle]
+pub extern "C" fn shadow_policy_ffi(policy_name: *const c_char, duration_ms: u64) -> *mut c_char {
+    shadow_policy_with_status_ffi(policy_name, duration_ms, std::ptr::null_mut())
+}
+
+// Like shadow_policy_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn shadow_policy_with_status_ffi(policy_name: *const c_char, duration_ms: u64, status: *mut i32) -> *mut c_char {
+    let name = unsafe {
+        if policy_name.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null policy name");
+        }
+        match CStr::from_ptr(policy_name).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid policy name"),
+        }
+    };
+    let duration = Duration::from_millis(duration_ms);
+    match run_fallible(|scheduler| async move { scheduler.shadow_policy_by_name(&name, duration) }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to get the current shadow trial report as JSON ("null" when none ran)
+#[no_mangle]
+pub extern "C" fn get_shadow_report_ffi() -> *mut c_char {
+    get_shadow_report_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_shadow_report_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_shadow_report_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let report = match run(|scheduler| async move { scheduler.shadow_report() }) {
+        Ok(report) => report,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&report) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get queue wait histograms by capability as JSON
+#[no_mangle]
+pub extern "C" fn get_queue_wait_stats_ffi() -> *mut c_char {
+    get_queue_wait_stats_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_queue_wait_stats_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_queue_wait_stats_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let stats = match run(|scheduler| async move { scheduler.queue_wait_by_capability() }) {
+        Ok(stats) => stats,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&stats) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the dispatch queue's fill and saturation counters as JSON (see
+// backpressure.rs)
+#[no_mangle]
+pub extern "C" fn get_queue_saturation_ffi() -> *mut c_char {
+    get_queue_saturation_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_queue_saturation_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_queue_saturation_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let saturation = match run(|scheduler| async move { scheduler.queue_saturation() }) {
+        Ok(saturation) => saturation,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&saturation) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get per-phase latency histograms of finished tasks as JSON
+#[no_mangle]
+pub extern "C" fn get_phase_latency_ffi() -> *mut c_char {
+    get_phase_latency_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_phase_latency_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_phase_latency_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let stats = match run(|scheduler| async move { scheduler.phase_latency() }) {
+        Ok(stats) => stats,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&stats) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get a namespace's daily submission count and quota as JSON
+#[no_mangle]
+pub extern "C" fn get_quota_usage_ffi(namespace: *const c_char) -> *mut c_char {
+    get_quota_usage_with_status_ffi(namespace, std::ptr::null_mut())
+}
+
+// Like get_quota_usage_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_quota_usage_with_status_ffi(namespace: *const c_char, status: *mut i32) -> *mut c_char {
+    let namespace = unsafe {
+        if namespace.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null namespace");
+        }
+        match CStr::from_ptr(namespace).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid namespace"),
+        }
+    };
+    let usage = match run(|scheduler| async move { scheduler.quota_usage(&namespace) }) {
+        Ok(usage) => usage,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&usage) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to list which robots can run a task type, and why the others can't, as JSON
+#[no_mangle]
+pub extern "C" fn get_compatible_robots_ffi(task_type: *const c_char) -> *mut c_char {
+    get_compatible_robots_with_status_ffi(task_type, std::ptr::null_mut())
+}
+
+// Like get_compatible_robots_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_compatible_robots_with_status_ffi(task_type: *const c_char, status: *mut i32) -> *mut c_char {
+    let task_type = unsafe {
+        if task_type.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null task type");
+        }
+        match CStr::from_ptr(task_type).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid task type"),
+        }
+    };
+    let report = match run(|scheduler| async move { scheduler.compatible_robots(&task_type).await }) {
+        Ok(report) => report,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&report) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the holder and waiters of a task mutex group as JSON
+#[no_mangle]
+pub extern "C" fn get_mutex_group_status_ffi(group: *const c_char) -> *mut c_char {
+    get_mutex_group_status_with_status_ffi(group, std::ptr::null_mut())
+}
+
+// Like get_mutex_group_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_mutex_group_status_with_status_ffi(group: *const c_char, status: *mut i32) -> *mut c_char {
+    let group = unsafe {
+        if group.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null group");
+        }
+        match CStr::from_ptr(group).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid group"),
+        }
+    };
+    let group_status = match run(|scheduler| async move { scheduler.mutex_group_status(&group) }) {
+        Ok(group_status) => group_status,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&group_status) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the holder and waiters of an exclusive resource as JSON
+#[no_mangle]
+pub extern "C" fn get_resource_status_ffi(resource: *const c_char) -> *mut c_char {
+    get_resource_status_with_status_ffi(resource, std::ptr::null_mut())
+}
+
+// Like get_resource_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_resource_status_with_status_ffi(resource: *const c_char, status: *mut i32) -> *mut c_char {
+    let resource = unsafe {
+        if resource.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null resource");
+        }
+        match CStr::from_ptr(resource).to_str() {
+            Ok(s) => s.to_string(),
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid resource"),
+        }
+    };
+    let resource_status = match run(|scheduler| async move { scheduler.resource_status(&resource) }) {
+        Ok(resource_status) => resource_status,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&resource_status) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get attainment and burn rate of every configured SLO as JSON
+#[no_mangle]
+pub extern "C" fn get_slo_status_ffi() -> *mut c_char {
+    get_slo_status_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_slo_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_slo_status_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let slos = match run(|scheduler| async move { scheduler.slo_status() }) {
+        Ok(slos) => slos,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&slos) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get dashboard statistics over the trailing window as JSON
+#[no_mangle]
+pub extern "C" fn get_stats_ffi(window_ms: u64) -> *mut c_char {
+    get_stats_with_status_ffi(window_ms, std::ptr::null_mut())
+}
+
+// Like get_stats_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_stats_with_status_ffi(window_ms: u64, status: *mut i32) -> *mut c_char {
+    let stats = match run(|scheduler| async move { scheduler.get_stats(Duration::from_millis(window_ms)) }) {
+        Ok(stats) => stats,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&stats) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the fleet status as JSON: each robot's liveness, current tasks, queue
+// depth, and utilization and deadline misses over the trailing window
+#[no_mangle]
+pub extern "C" fn get_fleet_status_ffi(window_ms: u64) -> *mut c_char {
+    get_fleet_status_with_status_ffi(window_ms, std::ptr::null_mut())
+}
+
+// Like get_fleet_status_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_fleet_status_with_status_ffi(window_ms: u64, status: *mut i32) -> *mut c_char {
+    let fleet = match run(|scheduler| async move { scheduler.fleet_status(Duration::from_millis(window_ms)).await }) {
+        Ok(fleet) => fleet,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&fleet) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get robot performance profiles as a JSON array
+#[no_mangle]
+pub extern "C" fn get_robot_profiles_ffi() -> *mut c_char {
+    get_robot_profiles_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_robot_profiles_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_robot_profiles_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let profiles = match run(|scheduler| async move { scheduler.robot_profiles() }) {
+        Ok(profiles) => profiles,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&profiles) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the circuit breaker of every robot that has finished a task as a
+// JSON array
+#[no_mangle]
+pub extern "C" fn get_breaker_status_ffi() -> *mut c_char {
+    get_breaker_status_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_breaker_status_ffi, also writing a status code (see FfiStatus) to `status` unless
+// null
+#[no_mangle]
+pub extern "C" fn get_breaker_status_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let breakers = match run(|scheduler| async move { scheduler.breaker_status() }) {
+        Ok(breakers) => breakers,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&breakers) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to list the tasks in the dead-letter queue as a JSON array, oldest first
+#[no_mangle]
+pub extern "C" fn get_dead_letters_ffi() -> *mut c_char {
+    get_dead_letters_with_status_ffi(std::ptr::null_mut())
+}
+
+// Like get_dead_letters_ffi, also writing a status code (see FfiStatus) to `status` unless
+// null
+#[no_mangle]
+pub extern "C" fn get_dead_letters_with_status_ffi(status: *mut i32) -> *mut c_char {
+    let letters = match run(|scheduler| async move { scheduler.dead_letters().await }) {
+        Ok(letters) => letters,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&letters) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to get the full record of a task in the dead-letter queue as JSON
+#[no_mangle]
+pub extern "C" fn get_dead_letter_ffi(task_id: u32) -> *mut c_char {
+    get_dead_letter_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like get_dead_letter_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_dead_letter_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run(|scheduler| async move { scheduler.dead_letter(task_id).await }) {
+        Ok(Some(record)) => match serde_json::to_string(&record) {
+            Ok(json) => reply(status, json),
+            Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+        },
+        Ok(None) => error(status, FfiStatus::NotFound, format!("Unknown dead letter: {}", task_id)),
+        Err(e) => error(status, FfiStatus::Unavailable, e),
+    }
+}
+
+// FFI function to queue a task in the dead-letter queue again with a fresh set of retries
+#[no_mangle]
+pub extern "C" fn redrive_dead_letter_ffi(task_id: u32) -> *mut c_char {
+    redrive_dead_letter_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like redrive_dead_letter_ffi, also writing a status code (see FfiStatus) to `status`
+// unless null
+#[no_mangle]
+pub extern "C" fn redrive_dead_letter_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.redrive_dead_letter(task_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to delete a task in the dead-letter queue for good
+#[no_mangle]
+pub extern "C" fn purge_dead_letter_ffi(task_id: u32) -> *mut c_char {
+    purge_dead_letter_with_status_ffi(task_id, std::ptr::null_mut())
+}
+
+// Like purge_dead_letter_ffi, also writing a status code (see FfiStatus) to `status` unless
+// null
+#[no_mangle]
+pub extern "C" fn purge_dead_letter_with_status_ffi(task_id: u32, status: *mut i32) -> *mut c_char {
+    match run_fallible(|scheduler| async move { scheduler.purge_dead_letter(task_id).await }) {
+        Ok(()) => reply(status, "Success"),
+        Err((code, e)) => error(status, code, e),
+    }
+}
+
+// FFI function to query the audit log: `query_json` is {"since", "until", "actor", "task_id",
+// "actions"}, every field optional; returns the matching entries as a JSON array, oldest first
+#[no_mangle]
+pub extern "C" fn get_audit_log_ffi(query_json: *const c_char) -> *mut c_char {
+    get_audit_log_with_status_ffi(query_json, std::ptr::null_mut())
+}
+
+// Like get_audit_log_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn get_audit_log_with_status_ffi(query_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let query_json = unsafe {
+        if query_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null audit query JSON");
+        }
+        match CStr::from_ptr(query_json).to_str() {
+            Ok(query_json) => query_json,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid UTF-8 in audit query JSON"),
+        }
+    };
+    let query: AuditQuery = match serde_json::from_str(query_json) {
+        Ok(query) => query,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    let entries = match run(|scheduler| async move { scheduler.query_audit(&query) }) {
+        Ok(entries) => entries,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&entries) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to list unfinished tasks, soonest deadline first: `filter_json` is
+// {"states", "robot_id", "min_priority", "max_priority", "capability", "due_within_ms"},
+// every field optional; returns the matching task summaries as a JSON array
+#[no_mangle]
+pub extern "C" fn list_tasks_ffi(filter_json: *const c_char) -> *mut c_char {
+    list_tasks_with_status_ffi(filter_json, std::ptr::null_mut())
+}
+
+// Like list_tasks_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn list_tasks_with_status_ffi(filter_json: *const c_char, status: *mut i32) -> *mut c_char {
+    let filter_json = unsafe {
+        if filter_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null task filter JSON");
+        }
+        match CStr::from_ptr(filter_json).to_str() {
+            Ok(filter_json) => filter_json,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid UTF-8 in task filter JSON"),
+        }
+    };
+    let filter: TaskListFilter = match serde_json::from_str(filter_json) {
+        Ok(filter) => filter,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    let tasks = match run(|scheduler| async move { scheduler.list_tasks(&filter).await }) {
+        Ok(tasks) => tasks,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&tasks) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to list finished tasks, newest first: `filter_json` is {"robot_id",
+// "task_type", "states", "since", "until"}, every field optional; returns a page of up to
+// `limit` entries as {"entries": [...], "next_cursor": n | null}. Pass `next_cursor` back as
+// `cursor` for the following page, or 0 to start from the newest.
+#[no_mangle]
+pub extern "C" fn list_task_history_ffi(filter_json: *const c_char, limit: u32, cursor: u64) -> *mut c_char {
+    list_task_history_with_status_ffi(filter_json, limit, cursor, std::ptr::null_mut())
+}
+
+// Like list_task_history_ffi, also writing a status code (see FfiStatus) to `status` unless null
+#[no_mangle]
+pub extern "C" fn list_task_history_with_status_ffi(filter_json: *const c_char, limit: u32, cursor: u64, status: *mut i32) -> *mut c_char {
+    let filter_json = unsafe {
+        if filter_json.is_null() {
+            return error(status, FfiStatus::InvalidArgument, "Null history filter JSON");
+        }
+        match CStr::from_ptr(filter_json).to_str() {
+            Ok(filter_json) => filter_json,
+            Err(_) => return error(status, FfiStatus::InvalidArgument, "Invalid UTF-8 in history filter JSON"),
+        }
+    };
+    let filter: HistoryFilter = match serde_json::from_str(filter_json) {
+        Ok(filter) => filter,
+        Err(e) => return error(status, FfiStatus::InvalidArgument, format!("JSON parsing failed: {}", e)),
+    };
+    let cursor = (cursor != 0).then_some(cursor);
+    let page = match run(|scheduler| async move { scheduler.list_task_history(&filter, limit as usize, cursor) }) {
+        Ok(page) => page,
+        Err(e) => return error(status, FfiStatus::Unavailable, e),
+    };
+    match serde_json::to_string(&page) {
+        Ok(json) => reply(status, json),
+        Err(e) => error(status, FfiStatus::Internal, format!("JSON serialization failed: {}", e)),
+    }
+}
+
+// FFI function to free C string memory
+#[no_mangle]
+pub extern "C" fn free_string_ffi(s: *mut c_char) {
+    if !s.is_null() {
+        unsafe {
+            let _ = CString::from_raw(s);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+// backend/rust/src/ffi_instance.rs
+// Purpose: Handle-based C FFI. mrtodp_scheduler_new creates an independent scheduler with its
+// own runtime and returns an opaque `MrtodpScheduler*`; every mrtodp_* call that works on a
+// scheduler has an mrtodp_scheduler_* twin taking that handle first, so one process can run
+// several schedulers side by side (one per fleet, or one per test). Results are structured
+// as in ffi_result.rs. Handles are looked up in a registry of live instances rather than
+// dereferenced, so a call with a freed or foreign handle fails with InvalidArgument instead
+// of crashing, and a call still running when its instance is freed finishes first. The
+// functions without a handle keep working on the process-global scheduler; process-wide
+// setup (init_tracing_ffi) and buffered start-up stay global-only.
+//
+//   MrtodpScheduler *fleet = mrtodp_scheduler_new(0);
+//   MrtodpResult result = mrtodp_scheduler_schedule_task(fleet, task_json);
+//   if (result.code != MrtodpErrorCode_Ok) { ... }
+//   free_string_ffi(result.payload);
+//   mrtodp_scheduler_free(fleet);
+
+use std::collections::HashMap;
+use std::ffi::c_char;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use crate::ffi;
+use crate::ffi_result::{failure, structured, MrtodpErrorCode, MrtodpResult};
+use crate::scheduler::Scheduler;
+
+// How long mrtodp_scheduler_free waits for in-flight work before abandoning it
+const FREE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Independent scheduler instance; opaque to C callers
+pub struct MrtodpScheduler {
+    runtime: Runtime,
+    scheduler: Scheduler,
+}
+
+// Live instances keyed by the address handed out as their handle
+static INSTANCES: OnceLock<Mutex<HashMap<usize, Arc<MrtodpScheduler>>>> = OnceLock::new();
+
+fn instances() -> &'static Mutex<HashMap<usize, Arc<MrtodpScheduler>>> {
+    INSTANCES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Create a scheduler instance with default settings; `worker_threads` = 0 uses one per core.
+// Returns null if its runtime can't be started.
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_new(worker_threads: u32) -> *mut MrtodpScheduler {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    if worker_threads > 0 {
+        builder.worker_threads(worker_threads as usize);
+    }
+    let runtime = match builder.enable_all().thread_name("mrtodp-instance").build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            tracing::error!(error = %e, "Tokio runtime creation failed");
+            return std::ptr::null_mut();
+        }
+    };
+    let scheduler = {
+        let _context = runtime.enter();
+        let (scheduler, workers) = Scheduler::builder().build().expect("default scheduler configuration is valid");
+        workers.spawn();
+        scheduler
+    };
+    let instance = Arc::new(MrtodpScheduler { runtime, scheduler });
+    let handle = Arc::as_ptr(&instance).cast_mut();
+    instances().lock().unwrap_or_else(|e| e.into_inner()).insert(handle as usize, instance);
+    handle
+}
+
+// Stop a scheduler instance and release it; null and unknown handles are ignored
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_free(scheduler: *mut MrtodpScheduler) {
+    let instance = instances().lock().unwrap_or_else(|e| e.into_inner()).remove(&(scheduler as usize));
+    // With calls still running, the last of them releases the instance
+    if let Some(Ok(instance)) = instance.map(Arc::try_unwrap) {
+        instance.runtime.shutdown_timeout(FREE_TIMEOUT);
+    }
+}
+
+// Run a *_with_status_ffi call against one instance and structure its result
+fn on_instance(scheduler: *mut MrtodpScheduler, call: impl FnOnce(*mut i32) -> *mut c_char) -> MrtodpResult {
+    if scheduler.is_null() {
+        return failure(MrtodpErrorCode::InvalidArgument, "Null scheduler");
+    }
+    let Some(instance) = instances().lock().unwrap_or_else(|e| e.into_inner()).get(&(scheduler as usize)).cloned() else {
+        return failure(MrtodpErrorCode::InvalidArgument, "Unknown scheduler handle");
+    };
+    ffi::with_target(instance.runtime.handle().clone(), instance.scheduler.clone(), || structured(call))
+}
+
+// mrtodp_register_robot on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_register_robot(
+    scheduler: *mut MrtodpScheduler,
+    robot_id: *const c_char,
+    capabilities_json: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::register_robot_with_status_ffi(robot_id, capabilities_json, status))
+}
+
+// mrtodp_update_robot_capabilities on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_update_robot_capabilities(
+    scheduler: *mut MrtodpScheduler,
+    robot_id: *const c_char,
+    add_json: *const c_char,
+    remove_json: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::update_robot_capabilities_with_status_ffi(robot_id, add_json, remove_json, status))
+}
+
+// mrtodp_drain_robot on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_drain_robot(scheduler: *mut MrtodpScheduler, robot_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::drain_robot_with_status_ffi(robot_id, status))
+}
+
+// mrtodp_deregister_robot on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_deregister_robot(scheduler: *mut MrtodpScheduler, robot_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::deregister_robot_with_status_ffi(robot_id, status))
+}
+
+// mrtodp_set_robot_slots on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_set_robot_slots(scheduler: *mut MrtodpScheduler, robot_id: *const c_char, slots: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::set_robot_slots_with_status_ffi(robot_id, slots, status))
+}
+
+// mrtodp_set_robot_model on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_set_robot_model(
+    scheduler: *mut MrtodpScheduler,
+    robot_id: *const c_char,
+    model: *const c_char,
+    firmware: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::set_robot_model_with_status_ffi(robot_id, model, firmware, status))
+}
+
+// mrtodp_report_emergency_stop on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_report_emergency_stop(
+    scheduler: *mut MrtodpScheduler,
+    robot_id: *const c_char,
+    detail: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::report_emergency_stop_with_status_ffi(robot_id, detail, status))
+}
+
+// mrtodp_report_battery on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_report_battery(scheduler: *mut MrtodpScheduler, robot_id: *const c_char, level: f64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::report_battery_with_status_ffi(robot_id, level, status))
+}
+
+// mrtodp_set_robot_zones on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_set_robot_zones(scheduler: *mut MrtodpScheduler, robot_id: *const c_char, zones_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::set_robot_zones_with_status_ffi(robot_id, zones_json, status))
+}
+
+// mrtodp_reset_breaker on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_reset_breaker(scheduler: *mut MrtodpScheduler, robot_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::reset_breaker_with_status_ffi(robot_id, status))
+}
+
+// mrtodp_report_robot_location on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_report_robot_location(
+    scheduler: *mut MrtodpScheduler,
+    robot_id: *const c_char,
+    location_json: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::report_robot_location_with_status_ffi(robot_id, location_json, status))
+}
+
+// mrtodp_submit_bid on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_submit_bid(
+    scheduler: *mut MrtodpScheduler,
+    task_id: u32,
+    robot_id: *const c_char,
+    cost: f64,
+    eta_ms: u64,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::submit_bid_with_status_ffi(task_id, robot_id, cost, eta_ms, status))
+}
+
+// mrtodp_schedule_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_schedule_task(scheduler: *mut MrtodpScheduler, task_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::schedule_task_with_status_ffi(task_json, status))
+}
+
+// mrtodp_register_template on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_register_template(scheduler: *mut MrtodpScheduler, template_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::register_template_with_status_ffi(template_json, status))
+}
+
+// mrtodp_register_payload_schema on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_register_payload_schema(scheduler: *mut MrtodpScheduler, task_type: *const c_char, schema_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::register_payload_schema_with_status_ffi(task_type, schema_json, status))
+}
+
+// mrtodp_set_submitter_limits on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_set_submitter_limits(
+    scheduler: *mut MrtodpScheduler,
+    kind: *const c_char,
+    name: *const c_char,
+    limits_json: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::set_submitter_limits_with_status_ffi(kind, name, limits_json, status))
+}
+
+// mrtodp_instantiate_template on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_instantiate_template(
+    scheduler: *mut MrtodpScheduler,
+    template_id: *const c_char,
+    params_json: *const c_char,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::instantiate_template_with_status_ffi(template_id, params_json, status))
+}
+
+// mrtodp_get_task_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_task_status(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_task_status_with_status_ffi(task_id, status))
+}
+
+// mrtodp_watch_open on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_watch_open(scheduler: *mut MrtodpScheduler, filter_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::watch_open_with_status_ffi(filter_json, status))
+}
+
+// mrtodp_watch_poll on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_watch_poll(scheduler: *mut MrtodpScheduler, watch_id: u64, max_events: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::watch_poll_with_status_ffi(watch_id, max_events, status))
+}
+
+// mrtodp_watch_close on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_watch_close(scheduler: *mut MrtodpScheduler, watch_id: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::watch_close_with_status_ffi(watch_id, status))
+}
+
+// mrtodp_hold_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_hold_task(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::hold_task_with_status_ffi(task_id, status))
+}
+
+// mrtodp_release_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_release_task(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::release_task_with_status_ffi(task_id, status))
+}
+
+// mrtodp_cancel_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_cancel_task(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::cancel_task_with_status_ffi(task_id, status))
+}
+
+// mrtodp_complete_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_complete_task(scheduler: *mut MrtodpScheduler, task_id: u32, expected_version: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::complete_task_with_status_ffi(task_id, expected_version, status))
+}
+
+// mrtodp_create_mission on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_create_mission(scheduler: *mut MrtodpScheduler, mission_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::create_mission_with_status_ffi(mission_json, status))
+}
+
+// mrtodp_get_mission_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_mission_status(scheduler: *mut MrtodpScheduler, mission_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_mission_status_with_status_ffi(mission_id, status))
+}
+
+// mrtodp_cancel_mission on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_cancel_mission(scheduler: *mut MrtodpScheduler, mission_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::cancel_mission_with_status_ffi(mission_id, status))
+}
+
+// mrtodp_pin_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_pin_task(scheduler: *mut MrtodpScheduler, task_id: u32, robot_id: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::pin_task_with_status_ffi(task_id, robot_id, status))
+}
+
+// mrtodp_hand_off_task on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_hand_off_task(scheduler: *mut MrtodpScheduler, task_id: u32, request_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::hand_off_task_with_status_ffi(task_id, request_json, status))
+}
+
+// mrtodp_set_policy on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_set_policy(scheduler: *mut MrtodpScheduler, policy_name: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::set_policy_with_status_ffi(policy_name, status))
+}
+
+// mrtodp_shadow_policy on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_shadow_policy(scheduler: *mut MrtodpScheduler, policy_name: *const c_char, duration_ms: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::shadow_policy_with_status_ffi(policy_name, duration_ms, status))
+}
+
+// mrtodp_get_shadow_report on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_shadow_report(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_shadow_report_with_status_ffi(status))
+}
+
+// mrtodp_get_queue_saturation on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_queue_saturation(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_queue_saturation_with_status_ffi(status))
+}
+
+// mrtodp_get_queue_wait_stats on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_queue_wait_stats(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_queue_wait_stats_with_status_ffi(status))
+}
+
+// mrtodp_get_phase_latency on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_phase_latency(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_phase_latency_with_status_ffi(status))
+}
+
+// mrtodp_get_quota_usage on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_quota_usage(scheduler: *mut MrtodpScheduler, namespace: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_quota_usage_with_status_ffi(namespace, status))
+}
+
+// mrtodp_get_compatible_robots on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_compatible_robots(scheduler: *mut MrtodpScheduler, task_type: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_compatible_robots_with_status_ffi(task_type, status))
+}
+
+// mrtodp_get_mutex_group_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_mutex_group_status(scheduler: *mut MrtodpScheduler, group: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_mutex_group_status_with_status_ffi(group, status))
+}
+
+// mrtodp_get_resource_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_resource_status(scheduler: *mut MrtodpScheduler, resource: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_resource_status_with_status_ffi(resource, status))
+}
+
+// mrtodp_get_slo_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_slo_status(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_slo_status_with_status_ffi(status))
+}
+
+// mrtodp_get_stats on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_stats(scheduler: *mut MrtodpScheduler, window_ms: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_stats_with_status_ffi(window_ms, status))
+}
+
+// mrtodp_get_robot_profiles on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_robot_profiles(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_robot_profiles_with_status_ffi(status))
+}
+
+// mrtodp_get_breaker_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_breaker_status(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_breaker_status_with_status_ffi(status))
+}
+
+// mrtodp_get_dead_letters on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_dead_letters(scheduler: *mut MrtodpScheduler) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_dead_letters_with_status_ffi(status))
+}
+
+// mrtodp_get_dead_letter on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_dead_letter(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_dead_letter_with_status_ffi(task_id, status))
+}
+
+// mrtodp_redrive_dead_letter on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_redrive_dead_letter(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::redrive_dead_letter_with_status_ffi(task_id, status))
+}
+
+// mrtodp_purge_dead_letter on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_purge_dead_letter(scheduler: *mut MrtodpScheduler, task_id: u32) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::purge_dead_letter_with_status_ffi(task_id, status))
+}
+
+// mrtodp_get_audit_log on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_audit_log(scheduler: *mut MrtodpScheduler, query_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_audit_log_with_status_ffi(query_json, status))
+}
+
+// mrtodp_get_fleet_status on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_get_fleet_status(scheduler: *mut MrtodpScheduler, window_ms: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::get_fleet_status_with_status_ffi(window_ms, status))
+}
+
+// mrtodp_shutdown_scheduler on one scheduler instance; release it with
+// mrtodp_scheduler_free afterwards
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_shutdown(scheduler: *mut MrtodpScheduler, grace_period_ms: u64) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::shutdown_scheduler_with_status_ffi(grace_period_ms, status))
+}
+
+// mrtodp_list_tasks on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_list_tasks(scheduler: *mut MrtodpScheduler, filter_json: *const c_char) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::list_tasks_with_status_ffi(filter_json, status))
+}
+
+// mrtodp_list_task_history on one scheduler instance
+#[no_mangle]
+pub extern "C" fn mrtodp_scheduler_list_task_history(
+    scheduler: *mut MrtodpScheduler,
+    filter_json: *const c_char,
+    limit: u32,
+    cursor: u64,
+) -> MrtodpResult {
+    on_instance(scheduler, |status| ffi::list_task_history_with_status_ffi(filter_json, limit, cursor, status))
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    // Payload of a result as text, releasing it
+    fn payload(result: MrtodpResult) -> (MrtodpErrorCode, String) {
+        let text = unsafe { CStr::from_ptr(result.payload) }.to_str().unwrap().to_string();
+        ffi::free_string_ffi(result.payload);
+        (result.code, text)
+    }
+
+    #[test]
+    fn test_instances_are_independent() {
+        let (north, south) = (mrtodp_scheduler_new(1), mrtodp_scheduler_new(1));
+        let robot = CString::new("Ford").unwrap();
+        let capabilities = CString::new(r#"["lift"]"#).unwrap();
+        for fleet in [north, south] {
+            assert_eq!(payload(mrtodp_scheduler_register_robot(fleet, robot.as_ptr(), capabilities.as_ptr())).0, MrtodpErrorCode::Ok);
+        }
+        let task = CString::new(r#"{"id": 340, "task_type": "lift", "priority": 1, "robot_id": "Ford", "required_capabilities": ["lift"]}"#).unwrap();
+        assert_eq!(payload(mrtodp_scheduler_schedule_task(north, task.as_ptr())).0, MrtodpErrorCode::Ok);
+        assert_eq!(payload(mrtodp_scheduler_schedule_task(north, task.as_ptr())).0, MrtodpErrorCode::DuplicateTask);
+        assert_eq!(payload(mrtodp_scheduler_get_task_status(south, 340)).0, MrtodpErrorCode::UnknownTask);
+        let (code, status) = payload(mrtodp_scheduler_get_task_status(north, 340));
+        assert_eq!(code, MrtodpErrorCode::Ok);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&status).unwrap()["task"]["id"], 340);
+
+        assert_eq!(payload(mrtodp_scheduler_get_task_status(std::ptr::null_mut(), 340)), (MrtodpErrorCode::InvalidArgument, r#"{"code":"InvalidArgument","message":"Null scheduler"}"#.to_string()));
+        mrtodp_scheduler_free(north);
+        assert_eq!(payload(mrtodp_scheduler_get_task_status(north, 340)).1, r#"{"code":"InvalidArgument","message":"Unknown scheduler handle"}"#);
+        mrtodp_scheduler_free(south);
+    }
+}
@@ -0,0 +1,457 @@
+// backend/rust/src/ffi_result.rs
+// Purpose: Structured results for the C FFI. Every *_with_status_ffi function has an
+// `mrtodp_*` counterpart (schedule_task_with_status_ffi -> mrtodp_schedule_task) returning
+// an `MrtodpResult`: a stable `MrtodpErrorCode` plus a JSON payload, so callers branch on
+// the code instead of parsing "Error: ..." strings. On success the payload is the response
+// as JSON (plain-text replies such as "Success" become JSON strings); on failure it is
+// `{"code": "<MrtodpErrorCode name>", "message": "..."}`. The payload must be released with
+// free_string_ffi. The string-returning functions in ffi.rs stay as they are for existing
+// callers; the two APIs share one scheduler and may be mixed.
+
+use std::ffi::{c_char, CStr, CString};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::ffi::{self, FfiStatus};
+use crate::scheduler::CONFLICT_ERROR;
+
+// Error codes of the structured FFI. Stable: values are never reused or renumbered, and
+// new codes are only added at the end. Unlike FfiStatus, which groups failures into broad
+// categories, each code names one cause.
+#[repr(i32)]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MrtodpErrorCode {
+    Ok = 0,
+    InvalidArgument = 1,    // Null pointer, invalid UTF-8, or an out-of-range value
+    InvalidJson = 2,        // Malformed JSON, or JSON that doesn't describe the expected type
+    DuplicateTask = 3,      // A task with the same ID already exists
+    UnknownTask = 4,
+    UnknownRobot = 5,
+    UnknownMission = 6,
+    UnknownTemplate = 7,
+    CapabilityMismatch = 8, // The robot lacks the task's capabilities or is incompatible with it
+    Rejected = 9,           // Any other refusal (validation, quota, robot or task state)
+    RuntimeFailure = 10,    // Runtime shut down or scheduler not running
+    Internal = 11,          // Response could not be serialized
+    UnknownToken = 12,      // Provisional token never issued, or its outcome already read
+}
+
+impl MrtodpErrorCode {
+    // Refine the status and message of a *_with_status_ffi call into a specific code
+    pub fn classify(status: FfiStatus, message: &str) -> Self {
+        match status {
+            FfiStatus::Ok => MrtodpErrorCode::Ok,
+            FfiStatus::InvalidArgument if message.starts_with("JSON parsing failed:") || message.starts_with("Invalid filter JSON:") => {
+                MrtodpErrorCode::InvalidJson
+            }
+            FfiStatus::InvalidArgument => MrtodpErrorCode::InvalidArgument,
+            FfiStatus::NotFound | FfiStatus::Rejected => Self::of_scheduler_error(message),
+            FfiStatus::Unavailable => MrtodpErrorCode::RuntimeFailure,
+            FfiStatus::Internal => MrtodpErrorCode::Internal,
+        }
+    }
+
+    fn of_scheduler_error(message: &str) -> Self {
+        // Submission checks may prefix their cause with "Task N: "
+        let cause = message
+            .strip_prefix("Task ")
+            .and_then(|rest| rest.split_once(": "))
+            .filter(|(task_id, _)| task_id.parse::<u32>().is_ok())
+            .map_or(message, |(_, cause)| cause);
+        let unknown = [
+            ("Unknown task:", MrtodpErrorCode::UnknownTask),
+            ("Unknown robot:", MrtodpErrorCode::UnknownRobot),
+            ("Unknown mission:", MrtodpErrorCode::UnknownMission),
+            ("Unknown template:", MrtodpErrorCode::UnknownTemplate),
+            ("Unknown dead letter:", MrtodpErrorCode::UnknownTask),
+            ("Unknown token:", MrtodpErrorCode::UnknownToken),
+            ("Invalid template parameters:", MrtodpErrorCode::InvalidJson),
+        ];
+        if let Some((_, code)) = unknown.iter().find(|(prefix, _)| cause.starts_with(prefix)) {
+            *code
+        } else if cause.strip_prefix(CONFLICT_ERROR).is_some_and(|rest| rest.starts_with(": task ") && rest.contains(" already exists")) {
+            MrtodpErrorCode::DuplicateTask
+        } else if cause.contains(" lacks required capabilities") || cause.contains(" is incompatible: ") {
+            MrtodpErrorCode::CapabilityMismatch
+        } else {
+            MrtodpErrorCode::Rejected
+        }
+    }
+}
+
+// Outcome of an `mrtodp_*` call, returned by value
+#[repr(C)]
+#[derive(Debug)]
+pub struct MrtodpResult {
+    pub code: MrtodpErrorCode,
+    pub payload: *mut c_char, // JSON; release with free_string_ffi
+}
+
+// Failed result with `code` and `message`, for failures detected before any call is made
+pub(crate) fn failure(code: MrtodpErrorCode, message: &str) -> MrtodpResult {
+    let payload = json!({"code": code, "message": message});
+    MrtodpResult { code, payload: CString::new(payload.to_string()).unwrap().into_raw() }
+}
+
+// Run a *_with_status_ffi call and repackage its status and message as a structured result
+pub(crate) fn structured(call: impl FnOnce(*mut i32) -> *mut c_char) -> MrtodpResult {
+    let mut status = FfiStatus::Internal as i32;
+    let raw = call(&mut status);
+    let message = unsafe { CStr::from_ptr(raw) }.to_string_lossy().into_owned();
+    ffi::free_string_ffi(raw);
+    let status = FfiStatus::from_code(status).unwrap_or(FfiStatus::Internal);
+    let message = message.strip_prefix("Error: ").unwrap_or(&message);
+    let code = MrtodpErrorCode::classify(status, message);
+    if code != MrtodpErrorCode::Ok {
+        return failure(code, message);
+    }
+    let payload = serde_json::from_str(message).unwrap_or_else(|_| Value::from(message));
+    MrtodpResult { code, payload: CString::new(payload.to_string()).unwrap().into_raw() }
+}
+
+// init_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_init(worker_threads: u32) -> MrtodpResult {
+    structured(|status| ffi::init_with_status_ffi(worker_threads, status))
+}
+
+// init_tracing_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_init_tracing(level: *const c_char, json_output: bool) -> MrtodpResult {
+    structured(|status| ffi::init_tracing_with_status_ffi(level, json_output, status))
+}
+
+// shutdown_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_shutdown() -> MrtodpResult {
+    structured(|status| ffi::shutdown_with_status_ffi(status))
+}
+
+// shutdown_scheduler_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_shutdown_scheduler(grace_period_ms: u64) -> MrtodpResult {
+    structured(|status| ffi::shutdown_scheduler_with_status_ffi(grace_period_ms, status))
+}
+
+// start_buffered_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_start_buffered(capacity: u32, spill_path: *const c_char, spill_capacity: u32) -> MrtodpResult {
+    structured(|status| ffi::start_buffered_with_status_ffi(capacity, spill_path, spill_capacity, status))
+}
+
+// get_buffered_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_buffered_status(token: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_buffered_status_with_status_ffi(token, status))
+}
+
+// register_robot_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_register_robot(robot_id: *const c_char, capabilities_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::register_robot_with_status_ffi(robot_id, capabilities_json, status))
+}
+
+// update_robot_capabilities_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_update_robot_capabilities(robot_id: *const c_char, add_json: *const c_char, remove_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::update_robot_capabilities_with_status_ffi(robot_id, add_json, remove_json, status))
+}
+
+// drain_robot_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_drain_robot(robot_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::drain_robot_with_status_ffi(robot_id, status))
+}
+
+// deregister_robot_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_deregister_robot(robot_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::deregister_robot_with_status_ffi(robot_id, status))
+}
+
+// set_robot_slots_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_set_robot_slots(robot_id: *const c_char, slots: u32) -> MrtodpResult {
+    structured(|status| ffi::set_robot_slots_with_status_ffi(robot_id, slots, status))
+}
+
+// set_robot_model_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_set_robot_model(robot_id: *const c_char, model: *const c_char, firmware: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::set_robot_model_with_status_ffi(robot_id, model, firmware, status))
+}
+
+// report_emergency_stop_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_report_emergency_stop(robot_id: *const c_char, detail: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::report_emergency_stop_with_status_ffi(robot_id, detail, status))
+}
+
+// report_battery_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_report_battery(robot_id: *const c_char, level: f64) -> MrtodpResult {
+    structured(|status| ffi::report_battery_with_status_ffi(robot_id, level, status))
+}
+
+// set_robot_zones_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_set_robot_zones(robot_id: *const c_char, zones_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::set_robot_zones_with_status_ffi(robot_id, zones_json, status))
+}
+
+// reset_breaker_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_reset_breaker(robot_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::reset_breaker_with_status_ffi(robot_id, status))
+}
+
+// report_robot_location_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_report_robot_location(robot_id: *const c_char, location_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::report_robot_location_with_status_ffi(robot_id, location_json, status))
+}
+
+// submit_bid_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_submit_bid(task_id: u32, robot_id: *const c_char, cost: f64, eta_ms: u64) -> MrtodpResult {
+    structured(|status| ffi::submit_bid_with_status_ffi(task_id, robot_id, cost, eta_ms, status))
+}
+
+// schedule_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_schedule_task(task_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::schedule_task_with_status_ffi(task_json, status))
+}
+
+// register_template_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_register_template(template_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::register_template_with_status_ffi(template_json, status))
+}
+
+// register_payload_schema_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_register_payload_schema(task_type: *const c_char, schema_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::register_payload_schema_with_status_ffi(task_type, schema_json, status))
+}
+
+// set_submitter_limits_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_set_submitter_limits(kind: *const c_char, name: *const c_char, limits_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::set_submitter_limits_with_status_ffi(kind, name, limits_json, status))
+}
+
+// instantiate_template_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_instantiate_template(template_id: *const c_char, params_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::instantiate_template_with_status_ffi(template_id, params_json, status))
+}
+
+// get_task_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_task_status(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::get_task_status_with_status_ffi(task_id, status))
+}
+
+// watch_open_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_watch_open(filter_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::watch_open_with_status_ffi(filter_json, status))
+}
+
+// watch_poll_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_watch_poll(watch_id: u64, max_events: u32) -> MrtodpResult {
+    structured(|status| ffi::watch_poll_with_status_ffi(watch_id, max_events, status))
+}
+
+// watch_close_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_watch_close(watch_id: u64) -> MrtodpResult {
+    structured(|status| ffi::watch_close_with_status_ffi(watch_id, status))
+}
+
+// hold_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_hold_task(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::hold_task_with_status_ffi(task_id, status))
+}
+
+// release_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_release_task(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::release_task_with_status_ffi(task_id, status))
+}
+
+// cancel_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_cancel_task(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::cancel_task_with_status_ffi(task_id, status))
+}
+
+// complete_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_complete_task(task_id: u32, expected_version: u64) -> MrtodpResult {
+    structured(|status| ffi::complete_task_with_status_ffi(task_id, expected_version, status))
+}
+
+// create_mission_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_create_mission(mission_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::create_mission_with_status_ffi(mission_json, status))
+}
+
+// get_mission_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_mission_status(mission_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_mission_status_with_status_ffi(mission_id, status))
+}
+
+// cancel_mission_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_cancel_mission(mission_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::cancel_mission_with_status_ffi(mission_id, status))
+}
+
+// pin_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_pin_task(task_id: u32, robot_id: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::pin_task_with_status_ffi(task_id, robot_id, status))
+}
+
+// hand_off_task_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_hand_off_task(task_id: u32, request_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::hand_off_task_with_status_ffi(task_id, request_json, status))
+}
+
+// set_policy_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_set_policy(policy_name: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::set_policy_with_status_ffi(policy_name, status))
+}
+
+// shadow_policy_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_shadow_policy(policy_name: *const c_char, duration_ms: u64) -> MrtodpResult {
+    structured(|status| ffi::shadow_policy_with_status_ffi(policy_name, duration_ms, status))
+}
+
+// get_shadow_report_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_shadow_report() -> MrtodpResult {
+    structured(|status| ffi::get_shadow_report_with_status_ffi(status))
+}
+
+// get_queue_saturation_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_queue_saturation() -> MrtodpResult {
+    structured(|status| ffi::get_queue_saturation_with_status_ffi(status))
+}
+
+// get_queue_wait_stats_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_queue_wait_stats() -> MrtodpResult {
+    structured(|status| ffi::get_queue_wait_stats_with_status_ffi(status))
+}
+
+// get_phase_latency_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_phase_latency() -> MrtodpResult {
+    structured(|status| ffi::get_phase_latency_with_status_ffi(status))
+}
+
+// get_quota_usage_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_quota_usage(namespace: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_quota_usage_with_status_ffi(namespace, status))
+}
+
+// get_compatible_robots_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_compatible_robots(task_type: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_compatible_robots_with_status_ffi(task_type, status))
+}
+
+// get_mutex_group_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_mutex_group_status(group: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_mutex_group_status_with_status_ffi(group, status))
+}
+
+// get_resource_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_resource_status(resource: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_resource_status_with_status_ffi(resource, status))
+}
+
+// get_slo_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_slo_status() -> MrtodpResult {
+    structured(|status| ffi::get_slo_status_with_status_ffi(status))
+}
+
+// get_stats_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_stats(window_ms: u64) -> MrtodpResult {
+    structured(|status| ffi::get_stats_with_status_ffi(window_ms, status))
+}
+
+// get_robot_profiles_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_robot_profiles() -> MrtodpResult {
+    structured(|status| ffi::get_robot_profiles_with_status_ffi(status))
+}
+
+// get_breaker_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_breaker_status() -> MrtodpResult {
+    structured(|status| ffi::get_breaker_status_with_status_ffi(status))
+}
+
+// get_dead_letters_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_dead_letters() -> MrtodpResult {
+    structured(|status| ffi::get_dead_letters_with_status_ffi(status))
+}
+
+// get_dead_letter_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_dead_letter(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::get_dead_letter_with_status_ffi(task_id, status))
+}
+
+// redrive_dead_letter_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_redrive_dead_letter(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::redrive_dead_letter_with_status_ffi(task_id, status))
+}
+
+// purge_dead_letter_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_purge_dead_letter(task_id: u32) -> MrtodpResult {
+    structured(|status| ffi::purge_dead_letter_with_status_ffi(task_id, status))
+}
+
+// get_audit_log_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_audit_log(query_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::get_audit_log_with_status_ffi(query_json, status))
+}
+
+// get_fleet_status_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_get_fleet_status(window_ms: u64) -> MrtodpResult {
+    structured(|status| ffi::get_fleet_status_with_status_ffi(window_ms, status))
+}
+
+// list_tasks_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_list_tasks(filter_json: *const c_char) -> MrtodpResult {
+    structured(|status| ffi::list_tasks_with_status_ffi(filter_json, status))
+}
+
+// list_task_history_ffi with a structured result
+#[no_mangle]
+pub extern "C" fn mrtodp_list_task_history(filter_json: *const c_char, limit: u32, cursor: u64) -> MrtodpResult {
+    structured(|status| ffi::list_task_history_with_status_ffi(filter_json, limit, cursor, status))
+}
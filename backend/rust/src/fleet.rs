@@ -0,0 +1,165 @@
+// backend/rust/src/fleet.rs
+// Purpose: Fleet status for MRTODP dashboards: one summary per registered robot with
+// whether it is online, the tasks it currently holds, the tasks queued for its slots,
+// how busy it was over a recent window, and how many tasks it finished past their
+// deadline in that window, with its last reported battery level and location and whether
+// it was sent to charge. A robot is online while the scheduler has heard from it within
+// the builder's `offline_after` (registration, accepted assignments, result reports,
+// battery and location reports, emergency stops, or `Scheduler::robot_heartbeat` from a
+// transport's own liveness checks); without a robot transport, execution is simulated
+// and every registered robot is online. Utilization is the share of the robot's slot
+// time spent holding tasks (assigned or running) over the window. Served by
+// `Scheduler::fleet_status`, GET /fleet, and the FFI.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::geometry::Location;
+use crate::metrics::MAX_STATS_WINDOW_MS;
+use crate::scheduler::{Attempt, Scheduler, TaskState};
+
+// Silence after which a robot is reported offline by default
+pub const DEFAULT_OFFLINE_AFTER: Duration = Duration::from_secs(60);
+
+// Status of one robot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RobotStatus {
+    pub robot_id: String,
+    pub online: bool,
+    pub draining: bool,
+    pub last_seen: Option<u64>, // Last contact (Unix ms); None before any
+    pub current_tasks: Vec<u32>, // Assigned or running on the robot
+    pub queue_depth: usize,      // Tasks waiting for one of its slots
+    pub slots: u32,
+    pub utilization: f64, // 0.0 to 1.0 over the window
+    pub deadline_misses: u64, // Tasks finished past their deadline in the window
+    pub battery: Option<f64>, // Last reported battery percentage; None before any
+    pub charging: bool,       // Sent to charge (see charging.rs); other work waits
+    pub location: Option<Location>, // Last reported location (see location.rs); None before any
+}
+
+// Status of every registered robot, by robot ID
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FleetStatus {
+    pub window_ms: u64,
+    pub robots: Vec<RobotStatus>,
+}
+
+// When the robot of an attempt last started holding it, i.e. entered Assigned or Running
+// from a state where no robot held it
+pub(crate) fn held_since(attempt: &Attempt) -> Option<u64> {
+    attempt.transitions.iter().rev().find(|t| t.to.is_active() && !t.from.is_some_and(TaskState::is_active)).map(|t| t.at)
+}
+
+impl Scheduler {
+    // Record contact with a robot, e.g. from a transport's heartbeat
+    pub fn robot_heartbeat(&self, robot_id: &str) {
+        let now = self.core.clock.now_millis();
+        self.core.robot_seen.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string(), now);
+    }
+
+    // Status of every registered robot, with utilization and deadline misses over the last
+    // `window` (capped at a day)
+    pub async fn fleet_status(&self, window: Duration) -> FleetStatus {
+        let now = self.core.clock.now_millis();
+        let window_ms = (window.as_millis() as u64).clamp(1, MAX_STATS_WINDOW_MS);
+        let cutoff = now.saturating_sub(window_ms);
+        let mut robot_ids: Vec<String> = self.core.capabilities.lock().await.keys().cloned().collect();
+        robot_ids.sort();
+        let mut activity = self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).robot_activity(window_ms, now);
+        let mut current: HashMap<String, Vec<u32>> = HashMap::new();
+        for record in self.core.records.lock().await.values().filter(|r| r.state.is_active()) {
+            let Some(attempt) = record.attempts.last() else {
+                continue;
+            };
+            if let Some(robot_id) = &attempt.robot_id {
+                current.entry(robot_id.clone()).or_default().push(record.task.id);
+                // Tasks still held count up to now
+                let start = held_since(attempt).unwrap_or(now).max(cutoff);
+                activity.entry(robot_id.clone()).or_default().busy_ms += now.saturating_sub(start);
+            }
+        }
+        let seen = self.core.robot_seen.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let draining = self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).depths();
+        let offline_after = self.core.offline_after.as_millis() as u64;
+        let batteries = self.core.batteries.lock().unwrap_or_else(|e| e.into_inner());
+        let robots = robot_ids
+            .into_iter()
+            .map(|robot_id| {
+                let last_seen = seen.get(&robot_id).copied();
+                let online = self.core.dispatcher.is_none() || last_seen.is_some_and(|at| now.saturating_sub(at) < offline_after);
+                let slots = self.robot_slots(&robot_id);
+                let activity = activity.get(&robot_id).copied().unwrap_or_default();
+                let mut current_tasks = current.remove(&robot_id).unwrap_or_default();
+                current_tasks.sort_unstable();
+                RobotStatus {
+                    online,
+                    draining: draining.contains(&robot_id),
+                    last_seen,
+                    current_tasks,
+                    queue_depth: queues.get(&robot_id).copied().unwrap_or(0),
+                    slots,
+                    utilization: (activity.busy_ms as f64 / (window_ms as f64 * f64::from(slots.max(1)))).min(1.0),
+                    deadline_misses: activity.deadline_misses,
+                    battery: batteries.level(&robot_id),
+                    charging: batteries.charging_task(&robot_id).is_some(),
+                    location: self.robot_location(&robot_id),
+                    robot_id,
+                }
+            })
+            .collect();
+        drop(batteries);
+        FleetStatus { window_ms, robots }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter, MockClock};
+
+    #[tokio::test]
+    async fn test_fleet_status_reports_load_and_liveness() {
+        let clock = Arc::new(MockClock::new(1_000_000));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).transport(fake.clone()).offline_after(Duration::from_secs(30)).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        fake.script("Scion", vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+
+        // Scion reports a task late after holding it 30s; Ford is still holding its own and
+        // hasn't been heard from since it accepted it
+        let task = |id: u32, robot: &str| Task { id, deadline: Some(1_010_000), robot_id: Some(robot.to_string()), ..Default::default() };
+        scheduler.schedule_task(task(373, "Scion")).await.unwrap();
+        wait_for_state(373, TaskState::Running).await;
+        scheduler.schedule_task(task(374, "Ford")).await.unwrap();
+        wait_for_state(374, TaskState::Running).await;
+        clock.advance(Duration::from_secs(30));
+        scheduler.report_result(373, Ok(())).await;
+
+        let fleet = scheduler.fleet_status(Duration::from_secs(60)).await;
+        let (ford, scion) = (&fleet.robots[0], &fleet.robots[1]);
+        assert_eq!((ford.robot_id.as_str(), ford.current_tasks.clone(), ford.online), ("Ford", vec![374], false));
+        assert!((ford.utilization - 0.5).abs() < 1e-9);
+        assert_eq!((scion.online, scion.current_tasks.len(), scion.deadline_misses), (true, 0, 1));
+        assert!((scion.utilization - 0.5).abs() < 1e-9);
+
+        scheduler.robot_heartbeat("Ford");
+        assert!(scheduler.fleet_status(Duration::from_secs(60)).await.robots[0].online);
+    }
+}
@@ -0,0 +1,259 @@
+// backend/rust/src/frames.rs
+// Purpose: Coordinate frame registry for MRTODP. Frames form a tree (site -> zone -> robot)
+// linked by static planar transforms loaded from the `frames` section of the fleet
+// manifest. Waypoints, zones, and locations submitted in site coordinates are translated
+// into the assigned robot's frame at dispatch, so robots never have to know the site
+// layout.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use serde::{Deserialize, Serialize};
+use crate::geometry::{validate_frame_id, Location, Point, Pose, Zone};
+use crate::task::Task;
+
+// Pose of a frame's origin expressed in its parent frame
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StaticTransform {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub z: f64,
+    #[serde(default)]
+    pub yaw: f64, // Radians
+}
+
+impl StaticTransform {
+    // Child-frame coordinates to parent-frame coordinates
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let (sin, cos) = self.yaw.sin_cos();
+        (cos * x - sin * y + self.x, sin * x + cos * y + self.y)
+    }
+
+    // Parent-frame coordinates to child-frame coordinates
+    fn invert(&self, x: f64, y: f64) -> (f64, f64) {
+        let (sin, cos) = self.yaw.sin_cos();
+        let (dx, dy) = (x - self.x, y - self.y);
+        (cos * dx + sin * dy, -sin * dx + cos * dy)
+    }
+}
+
+// One frame entry of the fleet manifest; a robot's frame uses the robot ID as its ID
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FrameSpec {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>, // None for a root frame such as the site
+    #[serde(default)]
+    pub transform: StaticTransform,
+}
+
+// Validated frame tree with lookups by frame ID
+#[derive(Clone, Debug, Default)]
+pub struct FrameRegistry {
+    frames: HashMap<String, FrameSpec>,
+}
+
+// The `frames` section of a fleet manifest
+#[derive(Deserialize)]
+struct FramesManifest {
+    #[serde(default)]
+    frames: Vec<FrameSpec>,
+}
+
+fn normalize_angle(angle: f64) -> f64 {
+    let wrapped = (angle + PI).rem_euclid(2.0 * PI) - PI;
+    if wrapped == -PI { PI } else { wrapped }
+}
+
+impl FrameRegistry {
+    // Build a registry, rejecting duplicate IDs, unknown parents, cycles, and bad numbers
+    pub fn new(specs: Vec<FrameSpec>) -> Result<Self, String> {
+        let mut frames = HashMap::new();
+        for spec in specs {
+            validate_frame_id(&spec.id)?;
+            let t = spec.transform;
+            if ![t.x, t.y, t.z, t.yaw].iter().all(|v| v.is_finite()) {
+                return Err(format!("Non-finite transform for frame {}", spec.id));
+            }
+            if frames.contains_key(&spec.id) {
+                return Err(format!("Duplicate frame: {}", spec.id));
+            }
+            frames.insert(spec.id.clone(), spec);
+        }
+        let registry = FrameRegistry { frames };
+        for id in registry.frames.keys() {
+            registry.chain(id)?;
+        }
+        Ok(registry)
+    }
+
+    // Parse the `frames` section of a fleet manifest (other sections are ignored)
+    pub fn from_manifest_json(raw: &str) -> Result<Self, String> {
+        let manifest: FramesManifest = serde_json::from_str(raw).map_err(|e| format!("Invalid fleet manifest: {}", e))?;
+        Self::new(manifest.frames)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn contains(&self, frame_id: &str) -> bool {
+        self.frames.contains_key(frame_id)
+    }
+
+    // Frames from `frame_id` up to its root, inclusive
+    fn chain(&self, frame_id: &str) -> Result<Vec<&FrameSpec>, String> {
+        let mut chain = Vec::new();
+        let mut current = Some(frame_id);
+        while let Some(id) = current {
+            let spec = self.frames.get(id).ok_or_else(|| format!("Unknown frame: {}", id))?;
+            if chain.len() > self.frames.len() {
+                return Err(format!("Frame cycle through {}", frame_id));
+            }
+            chain.push(spec);
+            current = spec.parent.as_deref();
+        }
+        Ok(chain)
+    }
+
+    // Re-express a pose in another frame of the same tree
+    pub fn transform_pose(&self, pose: &Pose, target_frame: &str) -> Result<Pose, String> {
+        let source = self.chain(&pose.frame_id)?;
+        let target = self.chain(target_frame)?;
+        if source.last().map(|f| &f.id) != target.last().map(|f| &f.id) {
+            return Err(format!("Frames {} and {} share no common root", pose.frame_id, target_frame));
+        }
+        let (mut x, mut y, mut z, mut yaw) = (pose.x, pose.y, pose.z, pose.yaw);
+        for frame in source.iter().filter(|f| f.parent.is_some()) {
+            (x, y) = frame.transform.apply(x, y);
+            z += frame.transform.z;
+            yaw += frame.transform.yaw;
+        }
+        for frame in target.iter().rev().filter(|f| f.parent.is_some()) {
+            (x, y) = frame.transform.invert(x, y);
+            z -= frame.transform.z;
+            yaw -= frame.transform.yaw;
+        }
+        Ok(Pose { frame_id: target_frame.to_string(), x, y, z, yaw: normalize_angle(yaw) })
+    }
+
+    fn transform_zone(&self, zone: &Zone, target_frame: &str) -> Result<Zone, String> {
+        let vertices = zone
+            .vertices
+            .iter()
+            .map(|v| {
+                let pose = Pose { frame_id: zone.frame_id.clone(), x: v.x, y: v.y, ..Default::default() };
+                self.transform_pose(&pose, target_frame).map(|p| Point { x: p.x, y: p.y })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Zone { frame_id: target_frame.to_string(), vertices })
+    }
+
+    // Check that every frame a task refers to is registered
+    pub fn validate_task(&self, task: &Task) -> Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let location = match &task.location {
+            Some(Location::Pose(pose)) => Some(&pose.frame_id),
+            _ => None,
+        };
+        let frames = task.waypoints.iter().map(|w| &w.pose.frame_id).chain(task.zone.iter().map(|z| &z.frame_id)).chain(location);
+        for frame_id in frames {
+            if !self.contains(frame_id) {
+                return Err(format!("Unknown frame: {}", frame_id));
+            }
+        }
+        Ok(())
+    }
+
+    // Copy of the task with its geometry expressed in the robot's frame; tasks for robots
+    // without a registered frame are returned unchanged
+    pub fn localize(&self, task: &Task, robot_id: &str) -> Result<Task, String> {
+        let mut local = task.clone();
+        if !self.contains(robot_id) {
+            return Ok(local);
+        }
+        for waypoint in local.waypoints.iter_mut() {
+            waypoint.pose = self.transform_pose(&waypoint.pose, robot_id)?;
+        }
+        if let Some(zone) = &local.zone {
+            local.zone = Some(self.transform_zone(zone, robot_id)?);
+        }
+        if let Some(Location::Pose(pose)) = &local.location {
+            local.location = Some(Location::Pose(self.transform_pose(pose, robot_id)?));
+        }
+        Ok(local)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+    use crate::geometry::Waypoint;
+
+    fn registry() -> FrameRegistry {
+        FrameRegistry::from_manifest_json(
+            r#"{"robots": [], "frames": [
+                {"id": "site"},
+                {"id": "cell_3", "parent": "site", "transform": {"x": 10.0, "y": 0.0}},
+                {"id": "Ford", "parent": "cell_3", "transform": {"x": 0.0, "y": 5.0, "yaw": 1.5707963267948966}}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_site_pose_into_robot_frame_and_back() {
+        let registry = registry();
+        // Robot origin sits at site (10, 5), facing site +y
+        let site = Pose { frame_id: "site".to_string(), x: 10.0, y: 7.0, yaw: FRAC_PI_2, ..Default::default() };
+        let local = registry.transform_pose(&site, "Ford").unwrap();
+        assert_close(local.x, 2.0);
+        assert_close(local.y, 0.0);
+        assert_close(local.yaw, 0.0);
+        let back = registry.transform_pose(&local, "site").unwrap();
+        assert_close(back.x, 10.0);
+        assert_close(back.y, 7.0);
+    }
+
+    #[test]
+    fn test_localize_task_waypoints() {
+        let registry = registry();
+        let task = Task {
+            id: 1,
+            waypoints: vec![Waypoint { pose: Pose { frame_id: "cell_3".to_string(), x: 0.0, y: 6.0, ..Default::default() }, tolerance_m: None }],
+            ..Default::default()
+        };
+        assert!(registry.validate_task(&task).is_ok());
+        let local = registry.localize(&task, "Ford").unwrap();
+        assert_eq!(local.waypoints[0].pose.frame_id, "Ford");
+        assert_close(local.waypoints[0].pose.x, 1.0);
+        assert_close(local.waypoints[0].pose.y, 0.0);
+        assert_eq!(registry.localize(&task, "Scion").unwrap(), task);
+    }
+
+    #[test]
+    fn test_invalid_manifests_rejected() {
+        let cycle = vec![
+            FrameSpec { id: "a".to_string(), parent: Some("b".to_string()), transform: StaticTransform::default() },
+            FrameSpec { id: "b".to_string(), parent: Some("a".to_string()), transform: StaticTransform::default() },
+        ];
+        assert!(FrameRegistry::new(cycle).unwrap_err().contains("cycle"));
+        let orphan = vec![FrameSpec { id: "a".to_string(), parent: Some("site".to_string()), transform: StaticTransform::default() }];
+        assert!(FrameRegistry::new(orphan).unwrap_err().contains("Unknown frame"));
+        let task = Task {
+            waypoints: vec![Waypoint { pose: Pose { frame_id: "dock".to_string(), ..Default::default() }, tolerance_m: None }],
+            ..Default::default()
+        };
+        assert!(registry().validate_task(&task).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+// backend/rust/src/geofencing.rs
+// Purpose: Geofencing for MRTODP. The site's `Geofence` names zones (polygons in a frame,
+// see geometry.rs) and lists the zones each robot is permitted to operate in. A task is in
+// the zone named by its `zone_name`, and in every named zone its pose location falls
+// inside; it only goes to a robot permitted in all of them. Robots without permissions
+// operate outside every zone. A submission naming an unknown zone, pinned to a robot not
+// permitted there, or in zones no robot is permitted in is rejected with
+// ZONE_VIOLATION_ERROR; unassigned tasks are placed on permitted robots only, and a task
+// whose robot lost its permission while it was queued fails at dispatch. Permissions are
+// set with the builder's `geofence` and changed at runtime with
+// `Scheduler::set_robot_zones`, PUT /robots/{id}/zones, or the FFI.
+//
+// Example: {"zones": {"cold_store": {"frame_id": "site", "vertices": [{"x": 0, "y": 0},
+// {"x": 5, "y": 0}, {"x": 5, "y": 5}]}}, "permissions": {"Ford": ["cold_store"]}}
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::audit::AuditAction;
+use crate::frames::FrameRegistry;
+use crate::geometry::{Location, Point, Zone};
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+
+// Prefix of the error returned when a task would run in a zone its robot isn't permitted in
+pub const ZONE_VIOLATION_ERROR: &str = "Zone violation";
+
+// Named zones and the zones each robot may operate in; empty = no restrictions
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Geofence {
+    #[serde(default)]
+    pub zones: BTreeMap<String, Zone>, // zone name -> area
+    #[serde(default)]
+    pub permissions: BTreeMap<String, BTreeSet<String>>, // robot_id -> zone names
+}
+
+impl Geofence {
+    pub fn from_json(raw: &str) -> Result<Self, String> {
+        let geofence: Geofence = serde_json::from_str(raw).map_err(|e| format!("Invalid geofence: {}", e))?;
+        geofence.validate()?;
+        Ok(geofence)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, zone) in &self.zones {
+            if name.is_empty() {
+                return Err("Zone name must not be empty".to_string());
+            }
+            zone.validate().map_err(|e| format!("Zone {}: {}", name, e))?;
+        }
+        for (robot_id, zones) in &self.permissions {
+            self.check_known(zones).map_err(|e| format!("Permissions of robot {}: {}", robot_id, e))?;
+        }
+        Ok(())
+    }
+
+    fn check_known<'a>(&self, zones: impl IntoIterator<Item = &'a String>) -> Result<(), String> {
+        match zones.into_iter().find(|zone| !self.zones.contains_key(*zone)) {
+            Some(zone) => Err(format!("Unknown zone: {}", zone)),
+            None => Ok(()),
+        }
+    }
+
+    // Zones a task is in: the one it names and those containing its pose location.
+    // Locations in frames that can't be related to a zone's frame are outside it.
+    pub fn zones_of(&self, task: &Task, frames: &FrameRegistry) -> BTreeSet<String> {
+        let mut zones: BTreeSet<String> = task.zone_name.iter().cloned().collect();
+        if let Some(Location::Pose(pose)) = &task.location {
+            for (name, zone) in &self.zones {
+                let local = if pose.frame_id == zone.frame_id { Ok(pose.clone()) } else { frames.transform_pose(pose, &zone.frame_id) };
+                if local.is_ok_and(|p| zone.contains(Point { x: p.x, y: p.y })) {
+                    zones.insert(name.clone());
+                }
+            }
+        }
+        zones
+    }
+
+    // Whether a robot may run a task, and which zone it is barred from if not
+    pub fn check(&self, task: &Task, robot_id: &str, frames: &FrameRegistry) -> Result<(), String> {
+        let permitted = self.permissions.get(robot_id);
+        match self.zones_of(task, frames).into_iter().find(|zone| !permitted.is_some_and(|p| p.contains(zone))) {
+            Some(zone) => Err(format!("{}: robot {} is not permitted in zone {}", ZONE_VIOLATION_ERROR, robot_id, zone)),
+            None => Ok(()),
+        }
+    }
+
+    // Reject a submission naming an unknown zone or, unless pinned (see `check`), in zones no
+    // robot may operate in
+    fn admit(&self, task: &Task, frames: &FrameRegistry) -> Result<(), String> {
+        self.check_known(&task.zone_name).map_err(|e| format!("{}: {}", ZONE_VIOLATION_ERROR, e))?;
+        let zones = self.zones_of(task, frames);
+        if task.robot_id.is_none() && !zones.is_empty() && !self.permissions.values().any(|permitted| zones.is_subset(permitted)) {
+            let zones: Vec<&str> = zones.iter().map(String::as_str).collect();
+            return Err(format!("{}: no robot is permitted in zone {}", ZONE_VIOLATION_ERROR, zones.join(" and ")));
+        }
+        Ok(())
+    }
+}
+
+impl Scheduler {
+    // Replace the zones a robot may operate in; queued tasks are checked again at dispatch
+    pub fn set_robot_zones(&self, robot_id: &str, zones: Vec<String>) -> Result<(), String> {
+        let zones: BTreeSet<String> = zones.into_iter().collect();
+        {
+            let mut geofence = self.core.geofence.write().unwrap_or_else(|e| e.into_inner());
+            geofence.check_known(&zones)?;
+            let mut updated = Geofence::clone(&geofence);
+            if zones.is_empty() {
+                updated.permissions.remove(robot_id);
+            } else {
+                updated.permissions.insert(robot_id.to_string(), zones.clone());
+            }
+            *geofence = Arc::new(updated);
+        }
+        let names: Vec<&str> = zones.iter().map(String::as_str).collect();
+        let detail = format!("Robot {} permitted in zones: [{}]", robot_id, names.join(", "));
+        self.audit(AuditAction::ZonesChanged, None, Some(robot_id.to_string()), None, detail);
+        Ok(())
+    }
+
+    // Named zones and robot permissions currently in force
+    pub fn geofence(&self) -> Geofence {
+        Geofence::clone(&self.core.geofence.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    // Whether a robot is permitted in every zone a task is in
+    pub(crate) fn check_zone(&self, task: &Task, robot_id: &str) -> Result<(), String> {
+        let geofence = self.core.geofence.read().unwrap_or_else(|e| e.into_inner()).clone();
+        geofence.check(task, robot_id, &self.core.frames)
+    }
+
+    // Submission-time geofence checks that don't depend on the robot
+    pub(crate) fn admit_zones(&self, task: &Task) -> Result<(), String> {
+        let geofence = self.core.geofence.read().unwrap_or_else(|e| e.into_inner()).clone();
+        geofence.admit(task, &self.core.frames)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Pose;
+    use crate::scheduler::TaskState;
+
+    #[tokio::test]
+    async fn test_zoned_tasks_go_to_permitted_robots() {
+        let geofence = Geofence::from_json(
+            r#"{"zones": {
+                    "cold_store": {"frame_id": "site", "vertices": [{"x": 0, "y": 0}, {"x": 5, "y": 0}, {"x": 5, "y": 5}, {"x": 0, "y": 5}]},
+                    "dock": {"frame_id": "site", "vertices": [{"x": 10, "y": 0}, {"x": 15, "y": 0}, {"x": 15, "y": 5}]}
+                },
+                "permissions": {"Scion": ["cold_store"]}}"#,
+        )
+        .unwrap();
+        assert!(Geofence::from_json(r#"{"permissions": {"Ford": ["roof"]}}"#).unwrap_err().contains("Unknown zone: roof"));
+        let (scheduler, workers) = Scheduler::builder().geofence(geofence).build().unwrap();
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let zoned = |id: u32, zone_name: Option<&str>, robot_id: Option<&str>| Task {
+            id,
+            zone_name: zone_name.map(str::to_string),
+            robot_id: robot_id.map(str::to_string),
+            ..Default::default()
+        };
+
+        // Pinned to a robot barred from the zone, in an unknown zone, or where nobody may go
+        let err = scheduler.schedule_task(zoned(391, Some("cold_store"), Some("Ford"))).await.unwrap_err();
+        assert_eq!(err, "Zone violation: robot Ford is not permitted in zone cold_store");
+        assert!(scheduler.schedule_task(zoned(391, Some("roof"), None)).await.unwrap_err().contains("Unknown zone: roof"));
+        let at_dock = Task { location: Some(Location::Pose(Pose { frame_id: "site".to_string(), x: 14.0, y: 1.0, ..Default::default() })), ..zoned(391, None, None) };
+        assert!(scheduler.schedule_task(at_dock.clone()).await.unwrap_err().starts_with(ZONE_VIOLATION_ERROR));
+
+        // An unassigned zoned task goes to the permitted robot
+        scheduler.schedule_task(zoned(392, Some("cold_store"), None)).await.unwrap();
+        while scheduler.task_record(392).await.is_none_or(|r| r.state != TaskState::Completed) {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.task_record(392).await.unwrap().task.robot_id.as_deref(), Some("Scion"));
+
+        scheduler.set_robot_zones("Ford", vec!["dock".to_string()]).unwrap();
+        assert!(scheduler.set_robot_zones("Ford", vec!["roof".to_string()]).is_err());
+        scheduler.schedule_task(at_dock).await.unwrap();
+        assert_eq!(scheduler.geofence().permissions["Ford"], BTreeSet::from(["dock".to_string()]));
+    }
+}
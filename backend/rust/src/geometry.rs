@@ -0,0 +1,200 @@
+// backend/rust/src/geometry.rs
+// Purpose: Typed geometry for MRTODP task parameters. Poses, waypoint routes, and polygon
+// zones carry the coordinate frame they are expressed in and are validated at submission
+// (finite numbers, well-formed frame IDs), so robots and the zone subsystem never receive
+// unlabelled or NaN-laden coordinates. Locations of tasks and robots, used to place tasks
+// on nearby robots (see location.rs), are either a pose in a frame or a lat/lon point.
+
+use serde::{Deserialize, Serialize};
+
+// Frame IDs are ROS-style names: letters, digits, '_', '-', '.', and '/' separators
+pub fn validate_frame_id(frame_id: &str) -> Result<(), String> {
+    let valid = !frame_id.is_empty()
+        && frame_id.len() <= 128
+        && frame_id.bytes().all(|b| b.is_ascii_alphanumeric() || b"_-./".contains(&b))
+        && frame_id.split('/').all(|part| !part.is_empty());
+    if !valid {
+        return Err(format!("Invalid frame ID: {:?}", frame_id));
+    }
+    Ok(())
+}
+
+fn validate_finite(name: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() {
+        return Err(format!("Non-finite {}: {}", name, value));
+    }
+    Ok(())
+}
+
+// A 2D point in the frame of the geometry that contains it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+// Position and heading of a robot or target in a named frame
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Pose {
+    pub frame_id: String,
+    pub x: f64, // Metres
+    pub y: f64,
+    #[serde(default)]
+    pub z: f64,
+    #[serde(default)]
+    pub yaw: f64, // Radians, counter-clockwise from the frame's x axis
+}
+
+impl Pose {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_frame_id(&self.frame_id)?;
+        for (name, value) in [("x", self.x), ("y", self.y), ("z", self.z), ("yaw", self.yaw)] {
+            validate_finite(name, value)?;
+        }
+        Ok(())
+    }
+}
+
+// One stop on a route; the robot may consider it reached within the tolerance
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Waypoint {
+    pub pose: Pose,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance_m: Option<f64>, // None = the robot's own default
+}
+
+impl Waypoint {
+    pub fn validate(&self) -> Result<(), String> {
+        self.pose.validate()?;
+        if let Some(tolerance) = self.tolerance_m {
+            validate_finite("tolerance", tolerance)?;
+            if tolerance < 0.0 {
+                return Err(format!("Negative waypoint tolerance: {}", tolerance));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Point on the Earth (WGS 84), for outdoor fleets without a site frame
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64, // Degrees, -90 to 90
+    pub lon: f64, // Degrees, -180 to 180
+    #[serde(default)]
+    pub alt: f64, // Metres
+}
+
+impl GeoPoint {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("lat", self.lat), ("lon", self.lon), ("alt", self.alt)] {
+            validate_finite(name, value)?;
+        }
+        if self.lat.abs() > 90.0 || self.lon.abs() > 180.0 {
+            return Err(format!("Latitude/longitude out of range: {}, {}", self.lat, self.lon));
+        }
+        Ok(())
+    }
+}
+
+// Where a task is carried out or a robot is: a pose in a frame, or a lat/lon point
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Location {
+    Pose(Pose),
+    Geo(GeoPoint),
+}
+
+impl Location {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Location::Pose(pose) => pose.validate(),
+            Location::Geo(point) => point.validate(),
+        }
+    }
+}
+
+// Closed polygon area (e.g. a work cell or keep-out region) in a named frame
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Zone {
+    pub frame_id: String,
+    pub vertices: Vec<Point>, // At least three; the last vertex connects back to the first
+}
+
+impl Zone {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_frame_id(&self.frame_id)?;
+        if self.vertices.len() < 3 {
+            return Err(format!("Zone needs at least 3 vertices, got {}", self.vertices.len()));
+        }
+        for vertex in &self.vertices {
+            validate_finite("vertex x", vertex.x)?;
+            validate_finite("vertex y", vertex.y)?;
+        }
+        Ok(())
+    }
+
+    // Whether a point in the zone's frame lies inside it (even-odd rule)
+    pub fn contains(&self, point: Point) -> bool {
+        let mut inside = false;
+        let mut j = self.vertices.len().wrapping_sub(1);
+        for (i, a) in self.vertices.iter().enumerate() {
+            let b = self.vertices[j];
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+            j = i;
+        }
+        inside
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pose_rejects_nan_and_bad_frame() {
+        let pose = Pose { frame_id: "site/cell_3".to_string(), x: 1.0, y: 2.0, ..Default::default() };
+        assert!(pose.validate().is_ok());
+        assert!(Pose { x: f64::NAN, ..pose.clone() }.validate().unwrap_err().contains("Non-finite x"));
+        assert!(Pose { frame_id: "site//cell".to_string(), ..pose.clone() }.validate().is_err());
+        assert!(Pose { frame_id: String::new(), ..pose }.validate().is_err());
+    }
+
+    #[test]
+    fn test_waypoint_json_round_trip() {
+        let raw = r#"{"pose": {"frame_id": "map", "x": 4.5, "y": -1.0}, "tolerance_m": 0.25}"#;
+        let waypoint: Waypoint = serde_json::from_str(raw).unwrap();
+        assert!(waypoint.validate().is_ok());
+        assert_eq!(waypoint.pose.yaw, 0.0);
+        let negative = Waypoint { tolerance_m: Some(-1.0), ..waypoint };
+        assert!(negative.validate().is_err());
+    }
+
+    #[test]
+    fn test_zone_contains() {
+        let square = Zone {
+            frame_id: "map".to_string(),
+            vertices: vec![Point { x: 0.0, y: 0.0 }, Point { x: 4.0, y: 0.0 }, Point { x: 4.0, y: 4.0 }, Point { x: 0.0, y: 4.0 }],
+        };
+        assert!(square.validate().is_ok());
+        assert!(square.contains(Point { x: 2.0, y: 2.0 }));
+        assert!(!square.contains(Point { x: 5.0, y: 2.0 }));
+        let degenerate = Zone { vertices: square.vertices[..2].to_vec(), ..square };
+        assert!(degenerate.validate().is_err());
+    }
+
+    #[test]
+    fn test_location_parses_pose_or_lat_lon() {
+        let pose: Location = serde_json::from_str(r#"{"frame_id": "site", "x": 3.0, "y": 4.0}"#).unwrap();
+        assert!(matches!(&pose, Location::Pose(p) if p.frame_id == "site" && p.z == 0.0));
+        let geo: Location = serde_json::from_str(r#"{"lat": 59.33, "lon": 18.07}"#).unwrap();
+        assert_eq!(geo, Location::Geo(GeoPoint { lat: 59.33, lon: 18.07, alt: 0.0 }));
+        assert!(geo.validate().is_ok());
+        assert!(Location::Geo(GeoPoint { lat: 91.0, lon: 0.0, alt: 0.0 }).validate().is_err());
+    }
+}
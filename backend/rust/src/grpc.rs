@@ -0,0 +1,755 @@
+// backend/rust/src/grpc.rs
+// Purpose: gRPC front-end for the MRTODP scheduler (`grpc` feature), so clients in any
+// language can submit tasks, query status, register robots, and watch transitions without
+// going through the Python FFI. The contract lives in proto/scheduler.proto; the message
+// types and service routing below are written by hand against it so the crate builds
+// without protoc. Packages mrtodp.scheduler.v1 (deprecated) and .v2 are served side by side
+// (see api_version.rs for how they differ); responses carry `api-version` metadata, plus
+// `deprecation: true` from v1. `Scheduler::serve_grpc` runs a standalone server, and
+// `SchedulerService` and `SchedulerServiceV2` can be added to an existing tonic server.
+// `RegistryService` serves every instance of a `SchedulerRegistry` (see registry.rs) instead,
+// picking each request's instance from its `mrtodp-instance` metadata. With `with_auth`,
+// any of these services requires a bearer token in `authorization` metadata (see auth.rs):
+// reads need the viewer role, ScheduleTask the operator role, and RegisterRobot the admin
+// role; refused calls get UNAUTHENTICATED or PERMISSION_DENIED, and accepted ones are
+// audited under the token's subject.
+// `SchedulerClient` is a matching hand-written client for the v2 package. With the `tls`
+// feature, `Scheduler::serve_grpc_tls` serves over TLS, optionally mutual, and
+// `SchedulerClient::connect_tls` connects that way (see tls.rs).
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::{Grpc, NamedService, ServerStreamingService, UnaryService};
+use tonic::metadata::MetadataValue;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status, Streaming};
+use crate::api_version::{ApiVersion, API_VERSION_HEADER, DEPRECATION_HEADER};
+use crate::auth::{authorize, Action, AuthError, Principal, TokenVerifier};
+use crate::backpressure::QUEUE_FULL_ERROR;
+use crate::events::{EventFilter, StreamError, StreamOptions};
+use crate::load_shedding::OVERLOADED_ERROR;
+use crate::quotas::QUOTA_EXCEEDED_ERROR;
+use crate::registry::{SchedulerRegistry, INSTANCE_METADATA_KEY};
+use crate::scheduler::{Scheduler, TaskEvent, TaskState, CONFLICT_ERROR};
+use crate::shutdown::SHUTTING_DOWN_ERROR;
+use crate::task::Task;
+#[cfg(feature = "tls")]
+use crate::tls::{ClientTls, ServerTls};
+
+// Fully qualified service names from proto/scheduler.proto and proto/scheduler_v2.proto
+pub const GRPC_SERVICE_NAME: &str = "mrtodp.scheduler.v1.Scheduler";
+pub const GRPC_SERVICE_NAME_V2: &str = "mrtodp.scheduler.v2.Scheduler";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScheduleTaskRequest {
+    #[prost(string, tag = "1")]
+    pub task_json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScheduleTaskResponse {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTaskStatusRequest {
+    #[prost(uint32, tag = "1")]
+    pub task_id: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTaskStatusResponse {
+    #[prost(uint32, tag = "1")]
+    pub task_id: u32,
+    #[prost(string, tag = "2")]
+    pub state: String,
+    #[prost(string, tag = "3")]
+    pub record_json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RegisterRobotRequest {
+    #[prost(string, tag = "1")]
+    pub robot_id: String,
+    #[prost(string, repeated, tag = "2")]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RegisterRobotResponse {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WatchTasksRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub namespaces: Vec<String>,
+    #[prost(string, repeated, tag = "2")]
+    pub robots: Vec<String>,
+    #[prost(string, repeated, tag = "3")]
+    pub task_types: Vec<String>,
+    #[prost(string, repeated, tag = "4")]
+    pub tags: Vec<String>,
+    #[prost(string, repeated, tag = "5")]
+    pub states: Vec<String>,
+    #[prost(uint64, optional, tag = "6")]
+    pub resume_from: Option<u64>,
+}
+
+// Wire form of a task transition (`TaskEvent` in the proto)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TaskEventMessage {
+    #[prost(uint64, tag = "1")]
+    pub seq: u64,
+    #[prost(uint32, tag = "2")]
+    pub task_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub attempt: u32,
+    #[prost(string, tag = "4")]
+    pub from_state: String,
+    #[prost(string, tag = "5")]
+    pub to_state: String,
+    #[prost(string, tag = "6")]
+    pub reason: String,
+    #[prost(string, tag = "7")]
+    pub detail: String,
+    #[prost(uint64, tag = "8")]
+    pub at: u64,
+    #[prost(string, tag = "9")]
+    pub task_type: String,
+    #[prost(string, tag = "10")]
+    pub namespace: String,
+    #[prost(string, tag = "11")]
+    pub robot_id: String,
+    #[prost(string, repeated, tag = "12")]
+    pub tags: Vec<String>,
+}
+
+// Name of a serde unit variant ("Running", "COMPLETED_OK", ...)
+fn variant_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+impl From<&TaskEvent> for TaskEventMessage {
+    fn from(event: &TaskEvent) -> Self {
+        TaskEventMessage {
+            seq: event.seq,
+            task_id: event.task_id,
+            attempt: event.attempt,
+            from_state: event.transition.from.as_ref().map(variant_name).unwrap_or_default(),
+            to_state: variant_name(&event.transition.to),
+            reason: variant_name(&event.transition.reason),
+            detail: event.transition.detail.clone(),
+            at: event.transition.at,
+            task_type: event.task_type.clone(),
+            namespace: event.namespace.clone().unwrap_or_default(),
+            robot_id: event.robot_id.clone().unwrap_or_default(),
+            tags: event.tags.clone(),
+        }
+    }
+}
+
+// Map a scheduler error onto the closest gRPC status code
+fn status(error: String) -> Status {
+    if error.starts_with(QUOTA_EXCEEDED_ERROR) {
+        Status::resource_exhausted(error)
+    } else if [OVERLOADED_ERROR, QUEUE_FULL_ERROR, SHUTTING_DOWN_ERROR].iter().any(|prefix| error.starts_with(prefix)) {
+        Status::unavailable(error)
+    } else if error.starts_with(CONFLICT_ERROR) {
+        Status::already_exists(error)
+    } else {
+        Status::invalid_argument(error)
+    }
+}
+
+// Action a call takes, from the method name ending its path
+fn action_of(path: &str) -> Action {
+    match path.rsplit('/').next() {
+        Some("GetTaskStatus" | "WatchTasks") => Action::Read,
+        Some("ScheduleTask") => Action::Schedule,
+        Some("RegisterRobot") => Action::RegisterRobot,
+        _ => Action::Administer,
+    }
+}
+
+// Check a call's bearer token when a verifier is configured, returning the status refusing
+// it, if any; an accepted call carries the caller's `Principal` as a request extension
+fn check_token<B>(verifier: Option<&Arc<dyn TokenVerifier>>, request: &mut http::Request<B>) -> Option<Status> {
+    let verifier = verifier?;
+    let authorization = request.headers().get(http::header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    match authorize(verifier.as_ref(), authorization, action_of(request.uri().path())) {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            None
+        }
+        Err(AuthError::Unauthenticated(message)) => Some(Status::unauthenticated(message)),
+        Err(AuthError::Forbidden(message)) => Some(Status::permission_denied(message)),
+    }
+}
+
+// gRPC service over a scheduler; add it to a tonic server or run `Scheduler::serve_grpc`
+#[derive(Clone)]
+pub struct SchedulerService {
+    scheduler: Scheduler,
+    version: ApiVersion,
+    auth: Option<Arc<dyn TokenVerifier>>, // None = open to any caller
+}
+
+impl SchedulerService {
+    // Service for the v1 package
+    pub fn new(scheduler: Scheduler) -> Self {
+        SchedulerService { scheduler, version: ApiVersion::V1, auth: None }
+    }
+
+    // Service for the v2 package
+    pub fn v2(scheduler: Scheduler) -> SchedulerServiceV2 {
+        SchedulerServiceV2(SchedulerService { scheduler, version: ApiVersion::V2, auth: None })
+    }
+
+    // Require a bearer token accepted by `verifier` on every call
+    pub fn with_auth(mut self, verifier: Arc<dyn TokenVerifier>) -> Self {
+        self.auth = Some(verifier);
+        self
+    }
+
+    fn service_name(&self) -> &'static str {
+        match self.version {
+            ApiVersion::V1 => GRPC_SERVICE_NAME,
+            ApiVersion::V2 => GRPC_SERVICE_NAME_V2,
+        }
+    }
+
+    // Wrap a reply with this version's metadata
+    fn respond<T>(&self, message: T) -> Response<T> {
+        let mut response = Response::new(message);
+        let metadata = response.metadata_mut();
+        metadata.insert(API_VERSION_HEADER, MetadataValue::from_static(self.version.as_str()));
+        if self.version.successor().is_some() {
+            metadata.insert(DEPRECATION_HEADER, MetadataValue::from_static("true"));
+        }
+        response
+    }
+
+    pub async fn schedule_task(&self, request: ScheduleTaskRequest) -> Result<ScheduleTaskResponse, Status> {
+        let task: Task = serde_json::from_str(&request.task_json)
+            .map_err(|e| Status::invalid_argument(format!("Invalid task JSON: {}", e)))?;
+        self.scheduler.schedule_task(task).await.map_err(status)?;
+        Ok(ScheduleTaskResponse {})
+    }
+
+    pub async fn get_task_status(&self, request: GetTaskStatusRequest) -> Result<GetTaskStatusResponse, Status> {
+        let record = self
+            .scheduler
+            .task_record(request.task_id)
+            .await
+            .ok_or_else(|| Status::not_found(format!("Unknown task: {}", request.task_id)))?;
+        let record_json = self.version.render(&record).map_err(Status::internal)?.to_string();
+        Ok(GetTaskStatusResponse { task_id: request.task_id, state: variant_name(&record.state), record_json })
+    }
+
+    pub async fn register_robot(&self, request: RegisterRobotRequest) -> Result<RegisterRobotResponse, Status> {
+        self.scheduler.register_robot(request.robot_id, request.capabilities).await.map_err(status)?;
+        Ok(RegisterRobotResponse {})
+    }
+
+    // Stream matching transitions until the client goes away; a client that falls behind
+    // gets ABORTED with the sequence number to resume from
+    pub async fn watch_tasks(&self, request: WatchTasksRequest) -> Result<ReceiverStream<Result<TaskEventMessage, Status>>, Status> {
+        let states = request
+            .states
+            .iter()
+            .map(|s| serde_json::from_value::<TaskState>(serde_json::Value::String(s.clone())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Status::invalid_argument(format!("Invalid state filter: {}", e)))?;
+        let filter = EventFilter {
+            namespaces: request.namespaces,
+            robots: request.robots,
+            task_types: request.task_types,
+            tags: request.tags,
+            states,
+        };
+        let options = StreamOptions { resume_from: request.resume_from, ..StreamOptions::default() };
+        let mut subscription = self.scheduler.subscribe_stream(filter, options).map_err(Status::out_of_range)?;
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            loop {
+                let item = match subscription.recv().await {
+                    Ok(event) => Ok(TaskEventMessage::from(&event)),
+                    Err(StreamError::Lagged { resume_from }) => {
+                        Err(Status::aborted(format!("Subscriber fell behind; resume from {}", resume_from)))
+                    }
+                    Err(StreamError::Closed) => return,
+                };
+                let last = item.is_err();
+                if tx.send(item).await.is_err() || last {
+                    return;
+                }
+            }
+        });
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+impl Scheduler {
+    // Serve the gRPC front-end on `addr` until the server fails
+    pub async fn serve_grpc(&self, addr: SocketAddr) -> Result<(), String> {
+        tonic::transport::Server::builder()
+            .add_service(SchedulerService::new(self.clone()))
+            .add_service(SchedulerService::v2(self.clone()))
+            .serve(addr)
+            .await
+            .map_err(|e| format!("gRPC server on {} failed: {}", addr, e))
+    }
+
+    // Serve the gRPC front-end on an already bound listener, e.g. one on an ephemeral port
+    pub async fn serve_grpc_on(&self, listener: TcpListener) -> Result<(), String> {
+        let addr = listener.local_addr().map_err(|e| format!("gRPC listener has no address: {}", e))?;
+        let incoming = TcpIncoming::from_listener(listener, true, None).map_err(|e| format!("gRPC listener on {} failed: {}", addr, e))?;
+        tonic::transport::Server::builder()
+            .add_service(SchedulerService::new(self.clone()))
+            .add_service(SchedulerService::v2(self.clone()))
+            .serve_with_incoming(incoming)
+            .await
+            .map_err(|e| format!("gRPC server on {} failed: {}", addr, e))
+    }
+
+    // Serve the gRPC front-end over TLS on `addr`
+    #[cfg(feature = "tls")]
+    pub async fn serve_grpc_tls(&self, addr: SocketAddr, tls: &ServerTls) -> Result<(), String> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+        self.serve_grpc_tls_on(listener, tls).await
+    }
+
+    // Serve the gRPC front-end over TLS on an already bound listener
+    #[cfg(feature = "tls")]
+    pub async fn serve_grpc_tls_on(&self, listener: TcpListener, tls: &ServerTls) -> Result<(), String> {
+        let addr = listener.local_addr().map_err(|e| format!("gRPC listener has no address: {}", e))?;
+        let incoming = TcpIncoming::from_listener(listener, true, None).map_err(|e| format!("gRPC listener on {} failed: {}", addr, e))?;
+        tonic::transport::Server::builder()
+            .tls_config(tls.grpc_config())
+            .map_err(|e| format!("Invalid gRPC TLS configuration: {}", e))?
+            .add_service(SchedulerService::new(self.clone()))
+            .add_service(SchedulerService::v2(self.clone()))
+            .serve_with_incoming(incoming)
+            .await
+            .map_err(|e| format!("gRPC server on {} failed: {}", addr, e))
+    }
+}
+
+// Client for the v2 package of the gRPC front-end
+#[derive(Clone)]
+pub struct SchedulerClient {
+    grpc: tonic::client::Grpc<Channel>,
+    instance: Option<MetadataValue<tonic::metadata::Ascii>>, // Sent as `mrtodp-instance` metadata
+    authorization: Option<MetadataValue<tonic::metadata::Ascii>>, // Sent as `authorization` metadata
+}
+
+impl SchedulerClient {
+    // Connect to a server, e.g. "http://127.0.0.1:50051"
+    pub async fn connect(endpoint: &str) -> Result<Self, String> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| format!("Invalid gRPC endpoint {}: {}", endpoint, e))?
+            .connect()
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", endpoint, e))?;
+        Ok(SchedulerClient { grpc: tonic::client::Grpc::new(channel), instance: None, authorization: None })
+    }
+
+    // Connect to a server over TLS, e.g. "https://scheduler.plant.local:50051"; the
+    // endpoint's host must match the server's certificate
+    #[cfg(feature = "tls")]
+    pub async fn connect_tls(endpoint: &str, tls: &ClientTls) -> Result<Self, String> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| format!("Invalid gRPC endpoint {}: {}", endpoint, e))?
+            .tls_config(tls.grpc_config())
+            .map_err(|e| format!("Invalid gRPC TLS configuration: {}", e))?
+            .connect()
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", endpoint, e))?;
+        Ok(SchedulerClient { grpc: tonic::client::Grpc::new(channel), instance: None, authorization: None })
+    }
+
+    // Address the instance registered as `name` on a server running a `RegistryService`
+    pub fn for_instance(mut self, name: &str) -> Result<Self, String> {
+        self.instance = Some(name.parse().map_err(|_| format!("Invalid instance name: {:?}", name))?);
+        Ok(self)
+    }
+
+    // Present `token` as a bearer token on every call
+    pub fn with_token(mut self, token: &str) -> Result<Self, String> {
+        self.authorization = Some(format!("Bearer {}", token).parse().map_err(|_| "Invalid bearer token".to_string())?);
+        Ok(self)
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(instance) = &self.instance {
+            request.metadata_mut().insert(INSTANCE_METADATA_KEY, instance.clone());
+        }
+        if let Some(authorization) = &self.authorization {
+            request.metadata_mut().insert("authorization", authorization.clone());
+        }
+        request
+    }
+
+    async fn unary<Req, Res>(&mut self, method: &'static str, request: Req) -> Result<Res, Status>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Res: prost::Message + Default + Send + Sync + 'static,
+    {
+        self.grpc.ready().await.map_err(|e| Status::unavailable(format!("gRPC channel not ready: {}", e)))?;
+        let path = http::uri::PathAndQuery::from_static(method);
+        let request = self.request(request);
+        let response = self.grpc.unary(request, path, ProstCodec::<Req, Res>::default()).await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn schedule_task(&mut self, request: ScheduleTaskRequest) -> Result<ScheduleTaskResponse, Status> {
+        self.unary("/mrtodp.scheduler.v2.Scheduler/ScheduleTask", request).await
+    }
+
+    pub async fn get_task_status(&mut self, request: GetTaskStatusRequest) -> Result<GetTaskStatusResponse, Status> {
+        self.unary("/mrtodp.scheduler.v2.Scheduler/GetTaskStatus", request).await
+    }
+
+    pub async fn register_robot(&mut self, request: RegisterRobotRequest) -> Result<RegisterRobotResponse, Status> {
+        self.unary("/mrtodp.scheduler.v2.Scheduler/RegisterRobot", request).await
+    }
+
+    pub async fn watch_tasks(&mut self, request: WatchTasksRequest) -> Result<Streaming<TaskEventMessage>, Status> {
+        self.grpc.ready().await.map_err(|e| Status::unavailable(format!("gRPC channel not ready: {}", e)))?;
+        let path = http::uri::PathAndQuery::from_static("/mrtodp.scheduler.v2.Scheduler/WatchTasks");
+        let codec = ProstCodec::<WatchTasksRequest, TaskEventMessage>::default();
+        let request = self.request(request);
+        let response = self.grpc.server_streaming(request, path, codec).await?;
+        Ok(response.into_inner())
+    }
+}
+
+// Adapts an async handler to tonic's unary method interface
+struct Unary<F>(F);
+
+impl<Req, Res, F> UnaryService<Req> for Unary<F>
+where
+    F: FnMut(Request<Req>) -> BoxFuture<Response<Res>, Status>,
+{
+    type Response = Res;
+    type Future = BoxFuture<Response<Res>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        (self.0)(request)
+    }
+}
+
+struct WatchTasks(SchedulerService);
+
+impl ServerStreamingService<WatchTasksRequest> for WatchTasks {
+    type Response = TaskEventMessage;
+    type ResponseStream = ReceiverStream<Result<TaskEventMessage, Status>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<WatchTasksRequest>) -> Self::Future {
+        let service = self.0.clone();
+        Box::pin(async move { service.watch_tasks(request.into_inner()).await.map(|stream| service.respond(stream)) })
+    }
+}
+
+// Decode a unary request, run the handler, and encode its response
+fn unary<B, Req, Res, F>(request: http::Request<B>, handler: F) -> BoxFuture<http::Response<BoxBody>, Infallible>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    Req: prost::Message + Default + Send + 'static,
+    Res: prost::Message + Send + 'static,
+    F: FnMut(Request<Req>) -> BoxFuture<Response<Res>, Status> + Send + 'static,
+{
+    Box::pin(async move {
+        let mut grpc = Grpc::new(ProstCodec::<Res, Req>::default());
+        Ok(grpc.unary(Unary(handler), request).await)
+    })
+}
+
+impl<B> Service<http::Request<B>> for SchedulerService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        if let Some(status) = check_token(self.auth.as_ref(), &mut request) {
+            return Box::pin(async move { Ok(status.into_http()) });
+        }
+        // Act as the caller the token named, so its changes are audited under its name
+        let mut service = self.clone();
+        if let Some(principal) = request.extensions().get::<Principal>() {
+            service.scheduler = service.scheduler.acting_as(&principal.subject);
+        }
+        let method = request.uri().path().strip_prefix('/').and_then(|p| p.strip_prefix(self.service_name())).unwrap_or_default().to_string();
+        match method.as_str() {
+            "/ScheduleTask" => unary(request, move |r: Request<ScheduleTaskRequest>| {
+                let service = service.clone();
+                Box::pin(async move { service.schedule_task(r.into_inner()).await.map(|reply| service.respond(reply)) })
+            }),
+            "/GetTaskStatus" => unary(request, move |r: Request<GetTaskStatusRequest>| {
+                let service = service.clone();
+                Box::pin(async move { service.get_task_status(r.into_inner()).await.map(|reply| service.respond(reply)) })
+            }),
+            "/RegisterRobot" => unary(request, move |r: Request<RegisterRobotRequest>| {
+                let service = service.clone();
+                Box::pin(async move { service.register_robot(r.into_inner()).await.map(|reply| service.respond(reply)) })
+            }),
+            "/WatchTasks" => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::<TaskEventMessage, WatchTasksRequest>::default());
+                Ok(grpc.server_streaming(WatchTasks(service), request).await)
+            }),
+            _ => Box::pin(async move {
+                let mut response = http::Response::new(empty_body());
+                let headers = response.headers_mut();
+                headers.insert(Status::GRPC_STATUS, (tonic::Code::Unimplemented as i32).into());
+                headers.insert(http::header::CONTENT_TYPE, tonic::metadata::GRPC_CONTENT_TYPE);
+                Ok(response)
+            }),
+        }
+    }
+}
+
+impl NamedService for SchedulerService {
+    const NAME: &'static str = GRPC_SERVICE_NAME;
+}
+
+// The v2 package of the service; tonic routes by a per-type name, hence the wrapper
+#[derive(Clone)]
+pub struct SchedulerServiceV2(SchedulerService);
+
+impl SchedulerServiceV2 {
+    // Require a bearer token accepted by `verifier` on every call
+    pub fn with_auth(self, verifier: Arc<dyn TokenVerifier>) -> Self {
+        SchedulerServiceV2(self.0.with_auth(verifier))
+    }
+}
+
+impl<B> Service<http::Request<B>> for SchedulerServiceV2
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Service::<http::Request<B>>::poll_ready(&mut self.0, cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        self.0.call(request)
+    }
+}
+
+impl NamedService for SchedulerServiceV2 {
+    const NAME: &'static str = GRPC_SERVICE_NAME_V2;
+}
+
+// gRPC service over every instance of a registry; each request names its instance in
+// `mrtodp-instance` metadata and is handled by that instance's `S` service
+#[derive(Clone)]
+pub struct RegistryService<S = SchedulerService> {
+    registry: Arc<SchedulerRegistry>,
+    service: fn(Scheduler) -> S,
+    auth: Option<Arc<dyn TokenVerifier>>, // None = open to any caller
+}
+
+impl RegistryService {
+    // Service for the v1 package
+    pub fn new(registry: Arc<SchedulerRegistry>) -> Self {
+        RegistryService { registry, service: SchedulerService::new, auth: None }
+    }
+
+    // Service for the v2 package
+    pub fn v2(registry: Arc<SchedulerRegistry>) -> RegistryService<SchedulerServiceV2> {
+        RegistryService { registry, service: SchedulerService::v2, auth: None }
+    }
+}
+
+impl<S> RegistryService<S> {
+    // Require a bearer token accepted by `verifier` on every call, whatever the instance
+    pub fn with_auth(mut self, verifier: Arc<dyn TokenVerifier>) -> Self {
+        self.auth = Some(verifier);
+        self
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for RegistryService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible, Future = BoxFuture<http::Response<BoxBody>, Infallible>>,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        if let Some(status) = check_token(self.auth.as_ref(), &mut request) {
+            return Box::pin(async move { Ok(status.into_http()) });
+        }
+        let instance = request.headers().get(INSTANCE_METADATA_KEY).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let scheduler = match instance {
+            Some(instance) => self.registry.get(&instance).ok_or_else(|| Status::not_found(format!("Unknown instance: {}", instance))),
+            None => Err(Status::invalid_argument(format!("Missing {} metadata", INSTANCE_METADATA_KEY))),
+        };
+        match scheduler {
+            Ok(scheduler) => (self.service)(scheduler).call(request),
+            Err(status) => Box::pin(async move { Ok(status.into_http()) }),
+        }
+    }
+}
+
+impl NamedService for RegistryService<SchedulerService> {
+    const NAME: &'static str = GRPC_SERVICE_NAME;
+}
+
+impl NamedService for RegistryService<SchedulerServiceV2> {
+    const NAME: &'static str = GRPC_SERVICE_NAME_V2;
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+    use crate::auth::{ApiTokens, Role};
+
+    #[tokio::test]
+    async fn test_service_schedules_and_streams_tasks() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let service = SchedulerService::new(scheduler);
+        let robot = RegisterRobotRequest { robot_id: "Ford".to_string(), capabilities: vec!["lift".to_string()] };
+        service.register_robot(robot.clone()).await.unwrap();
+        assert_eq!(service.register_robot(robot).await.unwrap_err().code(), tonic::Code::AlreadyExists);
+
+        let watch = WatchTasksRequest { states: vec!["Completed".to_string()], ..Default::default() };
+        let mut events = service.watch_tasks(watch).await.unwrap();
+        let task_json = r#"{"id": 181, "task_type": "lift", "priority": 1, "deadline": null, "robot_id": "Ford", "required_capabilities": ["lift"]}"#.to_string();
+        service.schedule_task(ScheduleTaskRequest { task_json }).await.unwrap();
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!((event.task_id, event.to_state.as_str(), event.reason.as_str()), (181, "Completed", "COMPLETED_OK"));
+        let status = service.get_task_status(GetTaskStatusRequest { task_id: 181 }).await.unwrap();
+        assert_eq!(status.state, "Completed");
+
+        assert_eq!(service.get_task_status(GetTaskStatusRequest { task_id: 999 }).await.unwrap_err().code(), tonic::Code::NotFound);
+        let bad = ScheduleTaskRequest { task_json: "{".to_string() };
+        assert_eq!(service.schedule_task(bad).await.unwrap_err().code(), tonic::Code::InvalidArgument);
+        let watch = WatchTasksRequest { states: vec!["Dancing".to_string()], ..Default::default() };
+        assert!(service.watch_tasks(watch).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_registry_service_routes_by_instance_metadata() {
+        let registry = Arc::new(SchedulerRegistry::new());
+        for name in ["north", "south"] {
+            let (scheduler, workers) = Scheduler::builder().build().unwrap();
+            workers.spawn();
+            registry.insert(name, scheduler).unwrap();
+        }
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        let server = tonic::transport::Server::builder().add_service(RegistryService::v2(registry)).serve_with_incoming(incoming);
+        tokio::spawn(server);
+
+        let client = SchedulerClient::connect(&endpoint).await.unwrap();
+        let (mut north, mut south) = (client.clone().for_instance("north").unwrap(), client.clone().for_instance("south").unwrap());
+        let task_json = r#"{"id": 343, "task_type": "lift", "priority": 1, "deadline": null, "required_capabilities": []}"#.to_string();
+        north.schedule_task(ScheduleTaskRequest { task_json }).await.unwrap();
+        assert_eq!(north.get_task_status(GetTaskStatusRequest { task_id: 343 }).await.unwrap().task_id, 343);
+        assert_eq!(south.get_task_status(GetTaskStatusRequest { task_id: 343 }).await.unwrap_err().code(), tonic::Code::NotFound);
+        let unknown = client.clone().for_instance("west").unwrap().get_task_status(GetTaskStatusRequest { task_id: 343 }).await.unwrap_err();
+        assert_eq!((unknown.code(), unknown.message()), (tonic::Code::NotFound, "Unknown instance: west"));
+        let missing = client.clone().get_task_status(GetTaskStatusRequest { task_id: 343 }).await.unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_bearer_tokens_gate_calls_by_role() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let tokens = ApiTokens::new().token("view", "dashboard", Role::Viewer).token("ops", "line-lead", Role::Operator);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        let service = SchedulerService::v2(scheduler.clone()).with_auth(Arc::new(tokens));
+        tokio::spawn(tonic::transport::Server::builder().add_service(service).serve_with_incoming(incoming));
+
+        let client = SchedulerClient::connect(&endpoint).await.unwrap();
+        let (mut viewer, mut operator) = (client.clone().with_token("view").unwrap(), client.clone().with_token("ops").unwrap());
+        let task_json = r#"{"id": 357, "task_type": "lift", "priority": 1, "required_capabilities": []}"#.to_string();
+        let missing = client.clone().get_task_status(GetTaskStatusRequest { task_id: 357 }).await.unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::Unauthenticated);
+        let denied = viewer.schedule_task(ScheduleTaskRequest { task_json: task_json.clone() }).await.unwrap_err();
+        assert_eq!((denied.code(), denied.message()), (tonic::Code::PermissionDenied, "viewer dashboard may not schedule tasks"));
+        operator.schedule_task(ScheduleTaskRequest { task_json }).await.unwrap();
+        assert_eq!(viewer.get_task_status(GetTaskStatusRequest { task_id: 357 }).await.unwrap().state, "Pending");
+        assert_eq!(scheduler.audit_log(Some(357))[0].actor.as_deref(), Some("line-lead"));
+        let robot = RegisterRobotRequest { robot_id: "Ford".to_string(), capabilities: vec![] };
+        assert_eq!(operator.register_robot(robot).await.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_client_and_server_over_mutual_tls() {
+        let pki = crate::tls::test_pki();
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("https://localhost:{}", listener.local_addr().unwrap().port());
+        let tls = pki.server.clone().client_ca(pki.ca.clone());
+        tokio::spawn(async move { scheduler.serve_grpc_tls_on(listener, &tls).await });
+
+        let (cert, key) = pki.client.clone();
+        let mut client = SchedulerClient::connect_tls(&endpoint, &ClientTls::new(pki.ca.clone()).identity(cert, key)).await.unwrap();
+        let task_json = r#"{"id": 358, "task_type": "lift", "priority": 1, "required_capabilities": []}"#.to_string();
+        client.schedule_task(ScheduleTaskRequest { task_json }).await.unwrap();
+        assert_eq!(client.get_task_status(GetTaskStatusRequest { task_id: 358 }).await.unwrap().state, "Pending");
+
+        // A client without a certificate is turned away during the handshake
+        let refused = match SchedulerClient::connect_tls(&endpoint, &ClientTls::new(pki.ca.clone())).await {
+            Ok(mut client) => client.get_task_status(GetTaskStatusRequest { task_id: 358 }).await.is_err(),
+            Err(_) => true,
+        };
+        assert!(refused);
+    }
+
+    #[tokio::test]
+    async fn test_v2_package_served_beside_deprecated_v1() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let (v1, v2) = (SchedulerService::new(scheduler.clone()), SchedulerService::v2(scheduler));
+        let task_json = r#"{"id": 191, "task_type": "lift", "priority": 1, "deadline": 1767225600000, "required_capabilities": []}"#.to_string();
+        v2.0.schedule_task(ScheduleTaskRequest { task_json }).await.unwrap();
+
+        let record = |status: GetTaskStatusResponse| serde_json::from_str::<serde_json::Value>(&status.record_json).unwrap();
+        let old = record(v1.get_task_status(GetTaskStatusRequest { task_id: 191 }).await.unwrap());
+        let new = record(v2.0.get_task_status(GetTaskStatusRequest { task_id: 191 }).await.unwrap());
+        assert_eq!(old["task"]["deadline"], 1_767_225_600_000u64);
+        assert_eq!(new["task"]["deadline"], "2026-01-01T00:00:00.000Z");
+
+        let (old, new) = (v1.respond(()), v2.0.respond(()));
+        assert_eq!(old.metadata().get(API_VERSION_HEADER).unwrap(), "v1");
+        assert_eq!(old.metadata().get(DEPRECATION_HEADER).unwrap(), "true");
+        assert_eq!(new.metadata().get(API_VERSION_HEADER).unwrap(), "v2");
+        assert!(new.metadata().get(DEPRECATION_HEADER).is_none());
+        assert_eq!((v1.service_name(), v2.0.service_name()), (GRPC_SERVICE_NAME, GRPC_SERVICE_NAME_V2));
+    }
+}
@@ -0,0 +1,455 @@
+// backend/rust/src/handles.rs
+// Purpose: Narrow, cheaply clonable views over a Scheduler's shared core. Integrators hand
+// a `SubmitHandle` to planners, a `QueryHandle` to dashboards, and an `AdminHandle` to
+// fleet management so each subsystem only sees the operations it needs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::auction::Bid;
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::backpressure::QueueSaturation;
+use crate::breakers::BreakerStatus;
+use crate::checkpoints::{CheckpointInfo, RestoreReport};
+use crate::compatibility::{CompatibilityReport, RobotModel};
+use crate::dead_letters::DeadLetter;
+use crate::events::{EventFilter, FilteredSubscription, StreamOptions};
+use crate::fleet::FleetStatus;
+use crate::geometry::Location;
+use crate::handoff::HandoffRequest;
+use crate::history::{HistoryFilter, HistoryPage};
+use crate::load_shedding::LoadModeEvent;
+use crate::metrics::{HistogramSnapshot, WindowStats};
+use crate::missions::{Mission, MissionStatus};
+use crate::mutex_groups::MutexGroupStatus;
+use crate::profiles::RobotProfile;
+use crate::quotas::QuotaUsage;
+use crate::readiness::RobotReadiness;
+use crate::reconcile::ReconcileReport;
+use crate::resource_locks::ResourceStatus;
+use crate::scheduler::{ReasonCode, Scheduler, TaskEvent, TaskRecord, TaskState};
+use crate::shadow::ShadowReport;
+use crate::shutdown::ShutdownReport;
+use crate::slo::{SloAlert, SloStatus};
+use crate::submitter_limits::{Submitter, SubmitterLimits};
+use crate::task::Task;
+use crate::task_list::{TaskListFilter, TaskSummary};
+use crate::templates::TaskTemplate;
+use crate::transport::{ControlCommand, RobotSequence};
+
+// Task submission operations
+#[derive(Clone)]
+pub struct SubmitHandle {
+    scheduler: Scheduler,
+}
+
+impl SubmitHandle {
+    pub async fn schedule_task(&self, task: Task) -> Result<(), String> {
+        self.scheduler.schedule_task(task).await
+    }
+
+    pub async fn instantiate_template(&self, template_id: &str, params_json: &str) -> Result<Task, String> {
+        self.scheduler.instantiate_template(template_id, params_json).await
+    }
+
+    pub fn create_mission(&self, mission: Mission) -> Result<(), String> {
+        self.scheduler.create_mission(mission)
+    }
+
+    pub async fn begin_upload(&self, mission_id: Option<String>) -> String {
+        self.scheduler.begin_upload(mission_id).await
+    }
+
+    pub async fn upload_chunk(&self, upload_id: &str, tasks: Vec<Task>) -> Result<usize, String> {
+        self.scheduler.upload_chunk(upload_id, tasks).await
+    }
+
+    pub async fn commit_upload(&self, upload_id: &str) -> Result<usize, String> {
+        self.scheduler.commit_upload(upload_id).await
+    }
+
+    pub async fn abort_upload(&self, upload_id: &str) -> Result<(), String> {
+        self.scheduler.abort_upload(upload_id).await
+    }
+}
+
+// Read-only queries and event subscriptions
+#[derive(Clone)]
+pub struct QueryHandle {
+    scheduler: Scheduler,
+}
+
+impl QueryHandle {
+    pub async fn task_record(&self, task_id: u32) -> Option<TaskRecord> {
+        self.scheduler.task_record(task_id).await
+    }
+
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.scheduler.dead_letters().await
+    }
+
+    pub async fn dead_letter(&self, task_id: u32) -> Option<TaskRecord> {
+        self.scheduler.dead_letter(task_id).await
+    }
+
+    pub async fn tasks_by_trace(&self, trace_id: &str) -> Vec<TaskRecord> {
+        self.scheduler.tasks_by_trace(trace_id).await
+    }
+
+    pub fn queue_wait_by_capability(&self) -> HashMap<String, HistogramSnapshot> {
+        self.scheduler.queue_wait_by_capability()
+    }
+
+    pub async fn active_missions(&self, namespace: &str) -> Vec<String> {
+        self.scheduler.active_missions(namespace).await
+    }
+
+    pub async fn queued_missions(&self, namespace: &str) -> Vec<String> {
+        self.scheduler.queued_missions(namespace).await
+    }
+
+    pub async fn get_mission_status(&self, mission_id: &str) -> Option<MissionStatus> {
+        self.scheduler.get_mission_status(mission_id).await
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.scheduler.subscribe()
+    }
+
+    pub fn subscribe_filtered(&self, filter: EventFilter) -> FilteredSubscription {
+        self.scheduler.subscribe_filtered(filter)
+    }
+
+    pub fn subscribe_stream(&self, filter: EventFilter, options: StreamOptions) -> Result<FilteredSubscription, String> {
+        self.scheduler.subscribe_stream(filter, options)
+    }
+
+    pub fn subscribe_load_mode(&self) -> broadcast::Receiver<LoadModeEvent> {
+        self.scheduler.subscribe_load_mode()
+    }
+
+    pub fn is_shedding_load(&self) -> bool {
+        self.scheduler.is_shedding_load()
+    }
+
+    pub fn robot_profile(&self, robot_id: &str) -> Option<RobotProfile> {
+        self.scheduler.robot_profile(robot_id)
+    }
+
+    pub fn robot_profiles(&self) -> Vec<RobotProfile> {
+        self.scheduler.robot_profiles()
+    }
+
+    pub fn breaker_status(&self) -> Vec<BreakerStatus> {
+        self.scheduler.breaker_status()
+    }
+
+    pub async fn registered_robots(&self) -> HashMap<String, Vec<String>> {
+        self.scheduler.registered_robots().await
+    }
+
+    pub async fn robot_readiness(&self, robot_id: &str) -> Option<RobotReadiness> {
+        self.scheduler.robot_readiness(robot_id).await
+    }
+
+    pub fn robot_model(&self, robot_id: &str) -> Option<RobotModel> {
+        self.scheduler.robot_model(robot_id)
+    }
+
+    pub async fn compatible_robots(&self, task_type: &str) -> CompatibilityReport {
+        self.scheduler.compatible_robots(task_type).await
+    }
+
+    pub fn robot_sequence(&self, robot_id: &str) -> Option<RobotSequence> {
+        self.scheduler.robot_sequence(robot_id)
+    }
+
+    pub fn phase_latency(&self) -> HashMap<String, HistogramSnapshot> {
+        self.scheduler.phase_latency()
+    }
+
+    pub fn expedite_by_source(&self) -> HashMap<String, u64> {
+        self.scheduler.expedite_by_source()
+    }
+
+    pub fn queue_saturation(&self) -> QueueSaturation {
+        self.scheduler.queue_saturation()
+    }
+
+    pub fn get_stats(&self, window: Duration) -> WindowStats {
+        self.scheduler.get_stats(window)
+    }
+
+    pub fn audit_log(&self, task_id: Option<u32>) -> Vec<AuditEntry> {
+        self.scheduler.audit_log(task_id)
+    }
+
+    pub fn query_audit(&self, query: &AuditQuery) -> Vec<AuditEntry> {
+        self.scheduler.query_audit(query)
+    }
+
+    pub async fn fleet_status(&self, window: Duration) -> FleetStatus {
+        self.scheduler.fleet_status(window).await
+    }
+
+    pub async fn list_tasks(&self, filter: &TaskListFilter) -> Vec<TaskSummary> {
+        self.scheduler.list_tasks(filter).await
+    }
+
+    pub fn list_task_history(&self, filter: &HistoryFilter, limit: usize, cursor: Option<u64>) -> HistoryPage {
+        self.scheduler.list_task_history(filter, limit, cursor)
+    }
+
+    pub fn quota_usage(&self, namespace: &str) -> QuotaUsage {
+        self.scheduler.quota_usage(namespace)
+    }
+
+    pub fn mutex_group_status(&self, group: &str) -> MutexGroupStatus {
+        self.scheduler.mutex_group_status(group)
+    }
+
+    pub fn resource_status(&self, resource: &str) -> ResourceStatus {
+        self.scheduler.resource_status(resource)
+    }
+
+    pub fn robot_queue_depths(&self) -> BTreeMap<String, usize> {
+        self.scheduler.robot_queue_depths()
+    }
+
+    pub async fn is_drained(&self, robot_id: &str) -> bool {
+        self.scheduler.is_drained(robot_id).await
+    }
+
+    pub fn slo_status(&self) -> Vec<SloStatus> {
+        self.scheduler.slo_status()
+    }
+
+    pub fn subscribe_slo_alerts(&self) -> broadcast::Receiver<SloAlert> {
+        self.scheduler.subscribe_slo_alerts()
+    }
+
+    pub fn shadow_report(&self) -> Option<ShadowReport> {
+        self.scheduler.shadow_report()
+    }
+}
+
+// Fleet and scheduler control operations
+#[derive(Clone)]
+pub struct AdminHandle {
+    scheduler: Scheduler,
+}
+
+impl AdminHandle {
+    pub async fn register_robot(&self, robot_id: String, capabilities: Vec<String>) -> Result<(), String> {
+        self.scheduler.register_robot(robot_id, capabilities).await
+    }
+
+    pub async fn update_robot_capabilities(&self, robot_id: &str, add: Vec<String>, remove: Vec<String>) -> Result<Vec<String>, String> {
+        self.scheduler.update_robot_capabilities(robot_id, add, remove).await
+    }
+
+    pub async fn drain_robot(&self, robot_id: &str) -> Result<(), String> {
+        self.scheduler.drain_robot(robot_id).await
+    }
+
+    pub async fn deregister_robot(&self, robot_id: &str) -> Result<(), String> {
+        self.scheduler.deregister_robot(robot_id).await
+    }
+
+    pub async fn set_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String> {
+        self.scheduler.set_robot_slots(robot_id, slots).await
+    }
+
+    pub async fn set_robot_model(&self, robot_id: &str, model: RobotModel) -> Result<(), String> {
+        self.scheduler.set_robot_model(robot_id, model).await
+    }
+
+    pub async fn hold_task(&self, task_id: u32) -> Result<(), String> {
+        self.scheduler.hold_task(task_id).await
+    }
+
+    pub async fn release_task(&self, task_id: u32) -> Result<(), String> {
+        self.scheduler.release_task(task_id).await
+    }
+
+    pub async fn transition_task(&self, task_id: u32, to: TaskState, reason: ReasonCode, detail: String) -> Result<(), String> {
+        self.scheduler.transition_task(task_id, to, reason, detail).await
+    }
+
+    pub async fn transition_task_if(&self, task_id: u32, expected_version: u64, to: TaskState, reason: ReasonCode, detail: String) -> Result<u64, String> {
+        self.scheduler.transition_task_if(task_id, expected_version, to, reason, detail).await
+    }
+
+    pub async fn complete_task(&self, task_id: u32, expected_version: u64) -> Result<u64, String> {
+        self.scheduler.complete_task(task_id, expected_version).await
+    }
+
+    pub async fn cancel_task(&self, task_id: u32) -> Result<TaskState, String> {
+        self.scheduler.cancel_task(task_id).await
+    }
+
+    pub async fn cancel_mission(&self, mission_id: &str) -> Result<MissionStatus, String> {
+        self.scheduler.cancel_mission(mission_id).await
+    }
+
+    pub async fn pin_task(&self, task_id: u32, robot_id: &str) -> Result<(), String> {
+        self.scheduler.pin_task(task_id, robot_id).await
+    }
+
+    pub async fn hand_off_task(&self, task_id: u32, request: HandoffRequest) -> Result<(), String> {
+        self.scheduler.hand_off_task(task_id, request).await
+    }
+
+    pub async fn send_control(&self, robot_id: &str, command: ControlCommand) -> Result<u64, String> {
+        self.scheduler.send_control(robot_id, command).await
+    }
+
+    pub async fn report_emergency_stop(&self, robot_id: &str, detail: &str) -> Result<(), String> {
+        self.scheduler.report_emergency_stop(robot_id, detail).await
+    }
+
+    pub async fn report_battery(&self, robot_id: &str, level: f64) -> Result<Option<u32>, String> {
+        self.scheduler.report_battery(robot_id, level).await
+    }
+
+    pub async fn report_robot_location(&self, robot_id: &str, location: Location) -> Result<(), String> {
+        self.scheduler.report_robot_location(robot_id, location).await
+    }
+
+    pub async fn report_result(&self, task_id: u32, result: Result<(), String>) {
+        self.scheduler.report_result(task_id, result).await
+    }
+
+    pub fn submit_bid(&self, task_id: u32, bid: Bid) -> Result<(), String> {
+        self.scheduler.submit_bid(task_id, bid)
+    }
+
+    pub async fn set_mission_limit(&self, namespace: &str, limit: Option<usize>) {
+        self.scheduler.set_mission_limit(namespace, limit).await
+    }
+
+    pub fn set_policy_by_name(&self, name: &str) -> Result<(), String> {
+        self.scheduler.set_policy_by_name(name)
+    }
+
+    pub fn shadow_policy_by_name(&self, name: &str, duration: Duration) -> Result<(), String> {
+        self.scheduler.shadow_policy_by_name(name, duration)
+    }
+
+    pub fn shadow_rules(&self, raw: &str, duration: Duration) -> Result<(), String> {
+        self.scheduler.shadow_rules(raw, duration)
+    }
+
+    pub fn end_shadow(&self) -> Option<ShadowReport> {
+        self.scheduler.end_shadow()
+    }
+
+    pub async fn reconcile_reservations(&self) -> ReconcileReport {
+        self.scheduler.reconcile_reservations().await
+    }
+
+    pub fn flush_quotas(&self) -> Result<(), String> {
+        self.scheduler.flush_quotas()
+    }
+
+    pub async fn shutdown(&self, grace_period: Duration) -> Result<ShutdownReport, String> {
+        self.scheduler.shutdown(grace_period).await
+    }
+
+    pub fn register_template(&self, template: TaskTemplate) -> Result<(), String> {
+        self.scheduler.register_template(template)
+    }
+
+    pub fn register_payload_schema(&self, task_type: &str, schema: serde_json::Value) -> Result<(), String> {
+        self.scheduler.register_payload_schema(task_type, schema)
+    }
+
+    pub fn set_submitter_limits(&self, submitter: Submitter, limits: SubmitterLimits) {
+        self.scheduler.set_submitter_limits(submitter, limits)
+    }
+
+    pub fn reload_rules(&self, raw: &str) -> Result<(), String> {
+        self.scheduler.reload_rules(raw)
+    }
+
+    pub fn reload_compatibility(&self, raw: &str) -> Result<(), String> {
+        self.scheduler.reload_compatibility(raw)
+    }
+
+    pub fn set_robot_zones(&self, robot_id: &str, zones: Vec<String>) -> Result<(), String> {
+        self.scheduler.set_robot_zones(robot_id, zones)
+    }
+
+    pub fn reset_breaker(&self, robot_id: &str) -> Result<(), String> {
+        self.scheduler.reset_breaker(robot_id)
+    }
+
+    pub async fn redrive_dead_letter(&self, task_id: u32) -> Result<(), String> {
+        self.scheduler.redrive_dead_letter(task_id).await
+    }
+
+    pub async fn purge_dead_letter(&self, task_id: u32) -> Result<(), String> {
+        self.scheduler.purge_dead_letter(task_id).await
+    }
+
+    pub async fn create_checkpoint(&self, name: &str) -> Result<CheckpointInfo, String> {
+        self.scheduler.create_checkpoint(name).await
+    }
+
+    pub async fn restore_checkpoint(&self, name: &str) -> Result<RestoreReport, String> {
+        self.scheduler.restore_checkpoint(name).await
+    }
+
+    pub async fn list_checkpoints(&self) -> Vec<CheckpointInfo> {
+        self.scheduler.list_checkpoints().await
+    }
+
+    pub async fn delete_checkpoint(&self, name: &str) -> Result<(), String> {
+        self.scheduler.delete_checkpoint(name).await
+    }
+}
+
+impl Scheduler {
+    // Handle limited to task submission
+    pub fn submit_handle(&self) -> SubmitHandle {
+        SubmitHandle { scheduler: self.clone() }
+    }
+
+    // Handle limited to queries and event subscriptions
+    pub fn query_handle(&self) -> QueryHandle {
+        QueryHandle { scheduler: self.clone() }
+    }
+
+    // Handle limited to fleet and scheduler control
+    pub fn admin_handle(&self) -> AdminHandle {
+        AdminHandle { scheduler: self.clone() }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_handles_share_core() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let admin = scheduler.admin_handle();
+        let submit = scheduler.submit_handle().clone();
+        let query = scheduler.query_handle();
+        drop(scheduler);
+
+        admin.register_robot("Ford".to_string(), vec!["navigation".to_string()]).await.unwrap();
+        let task = Task {
+            id: 21,
+            task_type: "navigation".to_string(),
+            priority: 1,
+            deadline: None,
+            robot_id: Some("Ford".to_string()),
+            required_capabilities: vec!["navigation".to_string()],
+            ..Default::default()
+        };
+        submit.schedule_task(task).await.unwrap();
+        assert_eq!(query.task_record(21).await.unwrap().state, TaskState::Pending);
+    }
+}
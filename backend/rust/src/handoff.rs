@@ -0,0 +1,143 @@
+// backend/rust/src/handoff.rs
+// Purpose: Task hand-off between MRTODP robots. A robot that is failing partway through a
+// long task (e.g. a delivery), or an operator moving it to more urgent work, hands the task
+// off instead of reporting failure: `Scheduler::hand_off_task` takes an assigned, running,
+// or suspended task back from its robot together with robot-defined progress, aborts it
+// there, and queues it again as a new attempt. The next robot is the one named in the
+// request, which must be able to run the task, or else whichever robot the assignment
+// engine picks; a task is never placed back on the robot that handed it off. The progress
+// travels in the task's `handoff` field, so the next robot's assignment carries it.
+// Coalition tasks (see coalitions.rs) can't be handed off. Served by POST
+// /tasks/{id}/handoff and the FFI.
+
+use serde::{Deserialize, Serialize};
+use crate::audit::AuditAction;
+use crate::scheduler::{ReasonCode, Requeue, Scheduler, TaskState, CONFLICT_ERROR};
+use crate::transport::ControlCommand;
+
+// What a caller asks of a hand-off
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct HandoffRequest {
+    #[serde(default)]
+    pub to_robot: Option<String>, // Robot to continue on; None = the assignment engine picks one
+    #[serde(default)]
+    pub progress: serde_json::Value, // Robot-defined progress, e.g. the stops already delivered
+}
+
+// A hand-off carried on the task to the robot that continues it
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Handoff {
+    pub from_robot: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_robot: Option<String>, // Robot the caller named, if any
+    #[serde(default)]
+    pub progress: serde_json::Value,
+    pub at: u64, // Unix timestamp (milliseconds)
+}
+
+impl Scheduler {
+    // Take a task from its robot with the progress made so far and queue it for another
+    // robot. Recorded in the audit log.
+    pub async fn hand_off_task(&self, task_id: u32, request: HandoffRequest) -> Result<(), String> {
+        let (from_robot, version) = {
+            let caps = self.core.capabilities.lock().await;
+            let records = self.core.records.lock().await;
+            let record = records.get(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+            if !(record.state.is_active() || record.state == TaskState::Suspended) {
+                return Err(format!("Task {} is {:?}; only assigned, running, or suspended tasks can be handed off", task_id, record.state));
+            }
+            if record.task.coalition.is_some() {
+                return Err(format!("Task {} is a coalition task and can't be handed off", task_id));
+            }
+            let from_robot = record.attempts.last().and_then(|a| a.robot_id.clone()).ok_or_else(|| format!("Task {} has no robot to hand off from", task_id))?;
+            if let Some(to_robot) = &request.to_robot {
+                if *to_robot == from_robot {
+                    return Err(format!("{}: task {} is already on robot {}", CONFLICT_ERROR, task_id, to_robot));
+                }
+                let robot_caps = caps.get(to_robot).ok_or_else(|| format!("Unknown robot: {}", to_robot))?;
+                if self.is_draining(to_robot) {
+                    return Err(format!("Robot {} is draining and takes no new tasks", to_robot));
+                }
+                if !record.task.required_capabilities.iter().all(|c| robot_caps.contains(c)) {
+                    return Err(format!("Robot {} lacks required capabilities: {:?}", to_robot, record.task.required_capabilities));
+                }
+                self.check_compatibility(&record.task.task_type, to_robot)?;
+                self.check_zone(&record.task, to_robot)?;
+            }
+            (from_robot, record.version)
+        };
+        let detail = match &request.to_robot {
+            Some(to_robot) => format!("Task {} handed off by robot {} to robot {}", task_id, from_robot, to_robot),
+            None => format!("Task {} handed off by robot {}", task_id, from_robot),
+        };
+        let handoff = Handoff {
+            from_robot: from_robot.clone(),
+            to_robot: request.to_robot.clone(),
+            progress: request.progress,
+            at: self.core.clock.now_millis(),
+        };
+        let requeue = Requeue { to_robot: request.to_robot.clone(), exclude: None, handoff: Some(handoff) };
+        // Fails if the task moved on since the checks above
+        self.apply_transition(task_id, Some(version), TaskState::Pending, ReasonCode::HandedOff, detail.clone(), Some(requeue)).await?;
+        if self.core.dispatcher.is_some() {
+            if let Err(e) = self.send_control(&from_robot, ControlCommand::Abort { task_id }).await {
+                tracing::warn!(task_id, robot_id = %from_robot, error = %e, "could not send abort to the robot handing off");
+            }
+        }
+        self.audit(AuditAction::TaskHandedOff, Some(task_id), request.to_robot, Some(from_robot), detail);
+        Ok(())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[tokio::test]
+    async fn test_handed_off_task_continues_elsewhere_with_progress() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let running_on = |robot_id: &'static str| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(399).await.is_none_or(|r| r.state != TaskState::Running || r.task.robot_id.as_deref() != Some(robot_id)) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        let progress = serde_json::json!({"stops_delivered": 3});
+        scheduler.schedule_task(Task { id: 399, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        running_on("Ford").await;
+
+        // Only a task a robot holds can be handed off, and not to the same robot
+        scheduler.schedule_task(Task { id: 400, not_before: Some(u64::MAX), ..Default::default() }).await.unwrap();
+        assert!(scheduler.hand_off_task(400, HandoffRequest::default()).await.unwrap_err().contains("only assigned"));
+        let to_ford = HandoffRequest { to_robot: Some("Ford".to_string()), ..Default::default() };
+        assert!(scheduler.hand_off_task(399, to_ford).await.is_err());
+
+        // The engine places it on the other robot, which gets the progress with it
+        scheduler.hand_off_task(399, HandoffRequest { to_robot: None, progress: progress.clone() }).await.unwrap();
+        running_on("Scion").await;
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 399), ("Scion".to_string(), 399)]);
+        assert!(fake.controls().iter().any(|c| c.robot_id == "Ford" && c.command == ControlCommand::Abort { task_id: 399 }));
+        let record = scheduler.task_record(399).await.unwrap();
+        assert_eq!(record.attempts.len(), 2);
+        assert_eq!(record.attempts[1].transitions[0].reason, ReasonCode::HandedOff);
+        assert_eq!(record.task.handoff.map(|h| (h.from_robot, h.progress)), Some(("Ford".to_string(), progress)));
+
+        // Only the scheduler hands a task over
+        let forged = Task { id: 401, handoff: Some(Handoff { from_robot: "Scion".to_string(), ..Default::default() }), ..Default::default() };
+        assert!(scheduler.schedule_task(forged).await.unwrap_err().contains("with a hand-off"));
+    }
+}
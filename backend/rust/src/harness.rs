@@ -0,0 +1,196 @@
+// backend/rust/src/harness.rs
+// Purpose: In-process full-stack harness for end-to-end tests against a real MRTODP
+// scheduler. Compiled with the `test-utils` and `grpc` features. `TestCluster` starts a
+// scheduler with the in-memory store and its workers, serves the gRPC front-end on an
+// ephemeral loopback port, and registers N simulated robots ("robot-1".."robot-N") backed by
+// a `FakeRobotAdapter`, so a planner under test can talk to the scheduler over the wire
+// while the test scripts robot behavior and waits on task states. Everything stops when the
+// cluster is dropped.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use crate::builder::SchedulerBuilder;
+use crate::grpc::{GetTaskStatusRequest, ScheduleTaskRequest, SchedulerClient};
+use crate::scheduler::{Scheduler, TaskRecord, TaskState};
+use crate::store::MemoryStore;
+use crate::task::Task;
+use crate::test_utils::FakeRobotAdapter;
+
+// Interval between task state checks while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+type Configure = Box<dyn FnOnce(SchedulerBuilder) -> SchedulerBuilder + Send>;
+
+// Settings for a test cluster
+pub struct TestClusterBuilder {
+    robots: usize,
+    capabilities: Vec<String>, // Capabilities every simulated robot registers with
+    configure: Option<Configure>,
+}
+
+impl TestClusterBuilder {
+    // Number of simulated robots
+    pub fn robots(mut self, count: usize) -> Self {
+        self.robots = count;
+        self
+    }
+
+    pub fn capabilities(mut self, capabilities: Vec<String>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    // Customize the scheduler before it is built; the store and transport are already set
+    pub fn configure(mut self, configure: impl FnOnce(SchedulerBuilder) -> SchedulerBuilder + Send + 'static) -> Self {
+        self.configure = Some(Box::new(configure));
+        self
+    }
+
+    // Start the scheduler, the gRPC server and the robots, and connect a client
+    pub async fn start(self) -> Result<TestCluster, String> {
+        let robots = Arc::new(FakeRobotAdapter::new());
+        let mut builder = Scheduler::builder().store(Arc::new(MemoryStore::new())).transport(robots.clone());
+        if let Some(configure) = self.configure {
+            builder = configure(builder);
+        }
+        let (scheduler, workers) = builder.build()?;
+        robots.attach(&scheduler);
+        let workers = workers.spawn();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|e| format!("Failed to bind gRPC listener: {}", e))?;
+        let addr = listener.local_addr().map_err(|e| format!("gRPC listener has no address: {}", e))?;
+        let server = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                let _ = scheduler.serve_grpc_on(listener).await;
+            })
+        };
+        let cluster_robots = (1..=self.robots).map(|n| format!("robot-{}", n)).collect::<Vec<_>>();
+        for robot_id in &cluster_robots {
+            scheduler.register_robot(robot_id.clone(), self.capabilities.clone()).await?;
+        }
+        let client = SchedulerClient::connect(&format!("http://{}", addr)).await?;
+        Ok(TestCluster { scheduler, robots, robot_ids: cluster_robots, addr, client, server, workers })
+    }
+}
+
+// A running scheduler with its gRPC server and simulated robots
+pub struct TestCluster {
+    scheduler: Scheduler,
+    robots: Arc<FakeRobotAdapter>,
+    robot_ids: Vec<String>,
+    addr: SocketAddr,
+    client: SchedulerClient,
+    server: JoinHandle<()>,
+    workers: JoinHandle<()>,
+}
+
+impl TestCluster {
+    // Defaults: two robots without capabilities, default scheduler settings
+    pub fn builder() -> TestClusterBuilder {
+        TestClusterBuilder { robots: 2, capabilities: Vec::new(), configure: None }
+    }
+
+    // Address of the gRPC server
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Endpoint for connecting further clients, e.g. the planner under test
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // The scheduler itself, for direct inspection
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    // The simulated robots; script their behavior and inspect what they received
+    pub fn robots(&self) -> &FakeRobotAdapter {
+        &self.robots
+    }
+
+    pub fn robot_ids(&self) -> &[String] {
+        &self.robot_ids
+    }
+
+    // A gRPC client connected to the cluster
+    pub fn client(&self) -> SchedulerClient {
+        self.client.clone()
+    }
+
+    // Submit a task over gRPC
+    pub async fn submit(&self, task: &Task) -> Result<(), String> {
+        let task_json = serde_json::to_string(task).map_err(|e| format!("Failed to serialize task {}: {}", task.id, e))?;
+        self.client().schedule_task(ScheduleTaskRequest { task_json }).await.map(|_| ()).map_err(|s| s.message().to_string())
+    }
+
+    // State of a task as reported over gRPC
+    pub async fn task_state(&self, task_id: u32) -> Result<String, String> {
+        let response = self.client().get_task_status(GetTaskStatusRequest { task_id }).await.map_err(|s| s.message().to_string())?;
+        Ok(response.state)
+    }
+
+    // Wait until a task's record satisfies `done`, or fail after `timeout`
+    pub async fn wait_for(&self, task_id: u32, timeout: Duration, done: impl Fn(&TaskRecord) -> bool) -> Result<TaskRecord, String> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let record = self.scheduler.task_record(task_id).await;
+            match record {
+                Some(record) if done(&record) => return Ok(record),
+                _ if tokio::time::Instant::now() >= deadline => {
+                    let state = record.map_or("unknown".to_string(), |r| format!("{:?}", r.state));
+                    return Err(format!("Task {} still {} after {:?}", task_id, state, timeout));
+                }
+                _ => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    // Wait until a task reaches `state`
+    pub async fn wait_for_state(&self, task_id: u32, state: TaskState, timeout: Duration) -> Result<TaskRecord, String> {
+        self.wait_for(task_id, timeout, |record| record.state == state).await
+    }
+
+    // Wait until a task completes, fails or is cancelled
+    pub async fn wait_until_terminal(&self, task_id: u32, timeout: Duration) -> Result<TaskRecord, String> {
+        self.wait_for(task_id, timeout, |record| record.state.is_terminal()).await
+    }
+}
+
+impl Drop for TestCluster {
+    fn drop(&mut self) {
+        self.server.abort();
+        self.workers.abort();
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::FakeBehavior;
+
+    #[tokio::test]
+    async fn test_cluster_runs_tasks_end_to_end_over_grpc() {
+        let cluster = TestCluster::builder().robots(3).capabilities(vec!["lift".to_string()]).start().await.unwrap();
+        assert_eq!(cluster.robot_ids(), ["robot-1", "robot-2", "robot-3"]);
+        cluster.robots().script("robot-2", vec![FakeBehavior::FailAfter(Duration::ZERO, "gripper jammed".to_string())]);
+
+        let task = |id: u32, robot_id: &str| Task { id, robot_id: Some(robot_id.to_string()), required_capabilities: vec!["lift".to_string()], ..Default::default() };
+        cluster.submit(&task(2001, "robot-1")).await.unwrap();
+        cluster.submit(&task(2002, "robot-2")).await.unwrap();
+        assert!(cluster.submit(&task(2003, "robot-9")).await.unwrap_err().contains("Unknown robot"));
+
+        let timeout = Duration::from_secs(2);
+        assert_eq!(cluster.wait_until_terminal(2001, timeout).await.unwrap().state, TaskState::Completed);
+        assert_eq!(cluster.wait_until_terminal(2002, timeout).await.unwrap().state, TaskState::Failed);
+        assert_eq!(cluster.task_state(2001).await.unwrap(), "Completed");
+        assert!(cluster.robots().assignments().contains(&("robot-1".to_string(), 2001)));
+        assert!(cluster.wait_for_state(2002, TaskState::Completed, Duration::from_millis(20)).await.is_err());
+    }
+}
@@ -0,0 +1,202 @@
+// backend/rust/src/history.rs
+// Purpose: Queryable archive of finished MRTODP tasks. Every task that reaches a terminal
+// state (completed, failed, cancelled, timed out) is summarized here: its type, namespace,
+// robot, outcome, attempts, and when it was submitted and finished. The archive is bounded
+// (see the builder's `history_capacity`), dropping the oldest entries first, and is rebuilt
+// from the task records in the store at startup. A retried task keeps only its latest
+// outcome. `Scheduler::list_task_history`, GET /history, and the FFI list it most recently
+// finished first, filtered by robot, task type, state, and finish time, one page at a time:
+// each page returns a cursor that resumes the listing where it ended, so paging stays
+// stable while new tasks finish.
+
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{ReasonCode, TaskRecord, TaskState};
+
+// Finished tasks kept by default
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100_000;
+
+// Largest page a history listing returns
+pub const MAX_HISTORY_PAGE: usize = 1_000;
+
+// Summary of one finished task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub seq: u64, // Position in the archive, increasing as tasks finish
+    pub task_id: u32,
+    pub task_type: String,
+    pub namespace: Option<String>,
+    pub robot_id: Option<String>, // Robot of the last attempt, if it reached one
+    pub state: TaskState,
+    pub reason: ReasonCode,
+    pub detail: String, // Of the final transition, e.g. why the task failed
+    pub attempts: u32,
+    pub submitted_at: u64, // Unix timestamp (milliseconds)
+    pub finished_at: u64,  // Unix timestamp (milliseconds)
+}
+
+impl HistoryEntry {
+    // Summary of a finished record; None while the task is unfinished
+    fn of(record: &TaskRecord) -> Option<Self> {
+        if !record.state.is_terminal() {
+            return None;
+        }
+        let attempt = record.attempts.last()?;
+        let finished = attempt.transitions.last()?;
+        let submitted_at = record.attempts.first().and_then(|a| a.transitions.first()).map_or(finished.at, |t| t.at);
+        Some(HistoryEntry {
+            seq: 0,
+            task_id: record.task.id,
+            task_type: record.task.task_type.clone(),
+            namespace: record.task.namespace.clone(),
+            robot_id: attempt.robot_id.clone().or_else(|| record.task.robot_id.clone()),
+            state: record.state,
+            reason: finished.reason,
+            detail: finished.detail.clone(),
+            attempts: attempt.number,
+            submitted_at,
+            finished_at: finished.at,
+        })
+    }
+}
+
+// Which finished tasks a history listing returns; unset fields match every task
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub robot_id: Option<String>,
+    #[serde(default)]
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub states: Vec<TaskState>, // Empty = every terminal state
+    #[serde(default)]
+    pub since: Option<u64>, // Finished at or after this time (Unix ms)
+    #[serde(default)]
+    pub until: Option<u64>, // Finished before this time (Unix ms)
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.robot_id.as_ref().is_none_or(|robot_id| entry.robot_id.as_ref() == Some(robot_id))
+            && self.task_type.as_ref().is_none_or(|task_type| entry.task_type == *task_type)
+            && (self.states.is_empty() || self.states.contains(&entry.state))
+            && self.since.is_none_or(|since| entry.finished_at >= since)
+            && self.until.is_none_or(|until| entry.finished_at < until)
+    }
+}
+
+// One page of a history listing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>, // Most recently finished first
+    pub next_cursor: Option<u64>,   // Pass back for the next page; None = this was the last
+}
+
+// The most recently finished tasks, oldest first
+pub(crate) struct TaskHistory {
+    entries: VecDeque<HistoryEntry>,
+    seqs: HashMap<u32, u64>, // task_id -> seq of its entry
+    capacity: usize,
+    next_seq: u64,
+}
+
+impl TaskHistory {
+    // Archive the finished tasks among the records recovered from the store
+    pub(crate) fn new<'a>(records: impl Iterator<Item = &'a TaskRecord>, capacity: usize) -> Self {
+        let mut finished: Vec<HistoryEntry> = records.filter_map(HistoryEntry::of).collect();
+        finished.sort_by_key(|entry| (entry.finished_at, entry.task_id));
+        let mut history = TaskHistory { entries: VecDeque::new(), seqs: HashMap::new(), capacity, next_seq: 1 };
+        for entry in finished {
+            history.push(entry);
+        }
+        history
+    }
+
+    // Archive a task that just finished
+    pub(crate) fn record(&mut self, record: &TaskRecord) {
+        if let Some(entry) = HistoryEntry::of(record) {
+            self.push(entry);
+        }
+    }
+
+    // Drop a task's entry, e.g. when the task is purged
+    pub(crate) fn forget(&mut self, task_id: u32) {
+        if let Some(seq) = self.seqs.remove(&task_id) {
+            if let Ok(index) = self.entries.binary_search_by_key(&seq, |e| e.seq) {
+                self.entries.remove(index);
+            }
+        }
+    }
+
+    fn push(&mut self, mut entry: HistoryEntry) {
+        self.forget(entry.task_id);
+        if self.entries.len() == self.capacity {
+            if let Some(oldest) = self.entries.pop_front() {
+                self.seqs.remove(&oldest.task_id);
+            }
+        }
+        entry.seq = self.next_seq;
+        self.next_seq += 1;
+        self.seqs.insert(entry.task_id, entry.seq);
+        self.entries.push_back(entry);
+    }
+
+    // Up to `limit` matching entries finished before the entry at `cursor`, newest first
+    pub(crate) fn list(&self, filter: &HistoryFilter, limit: usize, cursor: Option<u64>) -> HistoryPage {
+        let limit = limit.clamp(1, MAX_HISTORY_PAGE);
+        let end = cursor.map_or(self.entries.len(), |cursor| self.entries.partition_point(|e| e.seq < cursor));
+        let mut matching = self.entries.range(..end).rev().filter(|entry| filter.matches(entry));
+        let entries: Vec<HistoryEntry> = matching.by_ref().take(limit).cloned().collect();
+        let next_cursor = match matching.next() {
+            Some(_) => entries.last().map(|entry| entry.seq),
+            None => None,
+        };
+        HistoryPage { entries, next_cursor }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::Scheduler;
+    use crate::store::MemoryStore;
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_finished_tasks_listed_by_filter_and_page() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let store = Arc::new(MemoryStore::new());
+        let (scheduler, workers) = Scheduler::builder().store(store.clone()).clock(clock.clone()).history_capacity(4).build().unwrap();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        let mut events = scheduler.subscribe();
+        for id in 362..367 {
+            let task_type = if id % 2 == 0 { "weld" } else { "lift" };
+            scheduler.schedule_task(Task { id, task_type: task_type.to_string(), robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+            while events.recv().await.unwrap().transition.to != TaskState::Completed {}
+            clock.advance(Duration::from_millis(1_000));
+        }
+        scheduler.schedule_task(Task { id: 367, not_before: Some(60_000), ..Default::default() }).await.unwrap();
+        scheduler.cancel_task(367).await.unwrap();
+
+        // Bounded to the four most recent, newest first
+        let ids = |page: &HistoryPage| page.entries.iter().map(|e| e.task_id).collect::<Vec<_>>();
+        let first = scheduler.list_task_history(&HistoryFilter::default(), 3, None);
+        assert_eq!((ids(&first), first.entries[0].state), (vec![367, 366, 365], TaskState::Cancelled));
+        let rest = scheduler.list_task_history(&HistoryFilter::default(), 3, first.next_cursor);
+        assert_eq!((ids(&rest), rest.next_cursor), (vec![364], None));
+
+        let welds = HistoryFilter { robot_id: Some("Ford".to_string()), task_type: Some("weld".to_string()), ..Default::default() };
+        assert_eq!(ids(&scheduler.list_task_history(&welds, 10, None)), vec![366, 364]);
+        let window = HistoryFilter { states: vec![TaskState::Completed], since: Some(3_000), until: Some(5_000), ..Default::default() };
+        assert_eq!(ids(&scheduler.list_task_history(&window, 10, None)), vec![365, 364]);
+
+        // Rebuilt from the store after a restart
+        let (restarted, _workers) = Scheduler::builder().store(store).history_capacity(2).build().unwrap();
+        assert_eq!(ids(&restarted.list_task_history(&HistoryFilter::default(), 10, None)), vec![367, 366]);
+    }
+}
@@ -0,0 +1,1161 @@
+// backend/rust/src/http.rs
+// Purpose: REST API for the MRTODP scheduler (`http` feature), so web dashboards can submit
+// and inspect tasks and robots without going through the Python FFI. Routes map directly
+// onto `Scheduler` methods and exchange the same JSON documents as the FFI layer:
+//
+//   POST /tasks        submit a task                  GET /tasks/{id}  task record
+//   DELETE /tasks/{id} cancel a task; 409 if it already finished
+//   POST /tasks/{id}/complete
+//                      complete an assigned or running task if its record is still at the
+//                      body's `expected_version` (see versioning.rs); 409 if it changed
+//   POST /tasks/{id}/handoff
+//                      take a task from its robot with the body's `progress` and queue it
+//                      for `to_robot` or any other robot (see handoff.rs)
+//   POST /uploads      open a chunked upload of a large mission (see uploads.rs); the body
+//                      may name the `mission_id` given to uploaded tasks that name none
+//   POST /uploads/{id}/chunks
+//                      validate and stage a JSON array of tasks   DELETE /uploads/{id}
+//                      discards the upload
+//   POST /uploads/{id}/commit
+//                      submit every staged task of the upload at once
+//   GET  /tasks        unfinished tasks, soonest deadline first (see task_list.rs), filtered
+//                      by comma-separated `states`, `robot_id`, `min_priority`/`max_priority`,
+//                      `capability`, and `due_within_ms`
+//   POST /robots       register a robot               GET /robots      registered robots
+//   PUT  /robots/{id}/zones
+//                      replace the zones a robot may operate in (see geofencing.rs); tasks
+//                      in zones their robot isn't permitted in are refused with 403
+//   GET  /health       liveness, load-shedding state, and dispatch queue saturation (see
+//                      backpressure.rs)
+//   GET  /fleet        per-robot liveness, load, and deadline misses (see fleet.rs) over the
+//                      last `window_ms` (default 15 minutes)
+//   GET  /history      finished tasks, newest first (see history.rs), filtered by `robot_id`,
+//                      `task_type`, comma-separated `states`, and `since`/`until` (Unix ms);
+//                      `limit` entries per page (default 100), continued with `cursor`
+//   GET  /events       task transitions as newline-delimited JSON, streamed until the client
+//                      disconnects; filtered by comma-separated `namespaces`, `robots`,
+//                      `task_types`, `tags`, and `states`, resumable with `resume_from`
+//   GET  /watch        WebSocket sending each task update (see watch.rs) as a JSON text
+//                      message; takes the same filters as /events
+//   GET  /admin/limits limits and standing of every submitter with limits
+//   PUT  /admin/limits/{namespace|source}/{name}
+//                      replace a submitter's limits (see submitter_limits.rs)
+//   GET  /admin/audit  audit log entries (see audit.rs), filtered by `since` and `until`
+//                      (Unix ms), `actor`, `task_id`, and comma-separated `actions`
+//   GET  /admin/breakers
+//                      circuit breaker of every robot that has finished a task (see
+//                      breakers.rs)
+//   POST /admin/breakers/{robot_id}/reset
+//                      close a tripped breaker; 409 if it is already closed
+//   GET  /admin/dead-letters
+//                      tasks in the dead-letter queue, oldest first (see dead_letters.rs)
+//   GET  /admin/dead-letters/{id}
+//                      full record of a dead-lettered task   DELETE purges it
+//   POST /admin/dead-letters/{id}/redrive
+//                      queue a dead-lettered task again with a fresh set of retries
+//
+// The event stream is compressed with gzip or zstd when the client's Accept-Encoding allows
+// (see compression.rs). A client that falls behind gets a final {"error", "resume_from"} line.
+// Errors are returned as {"error": "..."} with a status code matching the failure; those
+// prefixed CONFLICT_ERROR or VERSION_CONFLICT_ERROR get 409.
+// Routes are served under /v1 and /v2 (see api_version.rs for how they differ), and the
+// original unversioned paths keep answering as v1. Every response names its version in an
+// `api-version` header; deprecated versions add `deprecation: true` and a `link` header to
+// the same route in the successor version.
+// `Scheduler::serve_http` runs a standalone server; `http_router` can be nested into an
+// existing axum application instead. `registry_router` serves every instance of a
+// `SchedulerRegistry` (see registry.rs) from one server: GET /instances lists them, and the
+// routes above are served per instance under /instances/{name}/. `require_auth` puts either
+// router behind bearer tokens (see auth.rs): reads need the viewer role, submitting tasks
+// (directly or by upload) and cancelling them the operator role, and registering robots
+// and /admin the admin role; refused requests get 401 or 403, and accepted ones are
+// audited under the token's subject. With the `tls` feature, `Scheduler::serve_https` serves the API over TLS,
+// optionally mutual (see tls.rs), and `serve_tls` serves any of these routers that way.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use axum::body::{Body, Bytes};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Extension, Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tower::ServiceExt;
+use crate::api_version::{ApiVersion, Versioned, API_VERSION_HEADER, DEPRECATION_HEADER};
+use crate::audit::{AuditAction, AuditEntry, AuditQuery};
+use crate::auth::{authorize, Action, AuthError, Principal, TokenVerifier};
+use crate::backpressure::QUEUE_FULL_ERROR;
+use crate::breakers::BreakerStatus;
+use crate::compression::{StreamEncoder, StreamEncoding};
+use crate::events::{EventFilter, StreamError, StreamOptions};
+use crate::fleet::FleetStatus;
+use crate::geofencing::ZONE_VIOLATION_ERROR;
+use crate::handoff::HandoffRequest;
+use crate::history::HistoryFilter;
+use crate::load_shedding::OVERLOADED_ERROR;
+use crate::quotas::QUOTA_EXCEEDED_ERROR;
+use crate::registry::SchedulerRegistry;
+use crate::scheduler::{Scheduler, TaskState, CONFLICT_ERROR};
+use crate::shutdown::SHUTTING_DOWN_ERROR;
+use crate::submitter_limits::{Submitter, SubmitterLimits, SubmitterUsage};
+use crate::task::Task;
+use crate::task_list::TaskListFilter;
+#[cfg(feature = "tls")]
+use crate::tls::ServerTls;
+use crate::versioning::VERSION_CONFLICT_ERROR;
+use crate::watch::Watch;
+
+// Body of POST /robots
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RobotRegistration {
+    pub robot_id: String,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+// Body of POST /tasks/{id}/complete
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CompleteTask {
+    pub expected_version: u64, // Version of the task record the caller last read
+}
+
+// Body of POST /uploads
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct UploadRequest {
+    #[serde(default)]
+    pub mission_id: Option<String>, // Mission of uploaded tasks that name none
+}
+
+// Entry of GET /robots
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RobotSummary {
+    pub robot_id: String,
+    pub capabilities: Vec<String>,
+    pub slots: u32,
+}
+
+// Query of GET /events; list fields are comma-separated
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct EventStreamQuery {
+    #[serde(default)]
+    pub namespaces: Option<String>,
+    #[serde(default)]
+    pub robots: Option<String>,
+    #[serde(default)]
+    pub task_types: Option<String>,
+    #[serde(default)]
+    pub tags: Option<String>,
+    #[serde(default)]
+    pub states: Option<String>, // State names, e.g. "Completed,Failed"
+    #[serde(default)]
+    pub resume_from: Option<u64>,
+}
+
+// Query of GET /tasks; `states` is comma-separated, e.g. "Pending,Running"
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TaskListQuery {
+    #[serde(default)]
+    pub states: Option<String>,
+    #[serde(default)]
+    pub robot_id: Option<String>,
+    #[serde(default)]
+    pub min_priority: Option<u32>,
+    #[serde(default)]
+    pub max_priority: Option<u32>,
+    #[serde(default)]
+    pub capability: Option<String>,
+    #[serde(default)]
+    pub due_within_ms: Option<u64>,
+}
+
+impl TaskListQuery {
+    fn filter(&self) -> Result<TaskListFilter, String> {
+        Ok(TaskListFilter {
+            states: parse_states(&self.states)?,
+            robot_id: self.robot_id.clone(),
+            min_priority: self.min_priority,
+            max_priority: self.max_priority,
+            capability: self.capability.clone(),
+            due_within_ms: self.due_within_ms,
+        })
+    }
+}
+
+// Task states from a comma-separated query parameter
+fn parse_states(states: &Option<String>) -> Result<Vec<TaskState>, String> {
+    states
+        .iter()
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|s| serde_json::from_value::<TaskState>(serde_json::Value::String(s.to_string())))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid state filter: {}", e))
+}
+
+// Query of GET /history; `states` is comma-separated, e.g. "Failed,Cancelled"
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub robot_id: Option<String>,
+    #[serde(default)]
+    pub task_type: Option<String>,
+    #[serde(default)]
+    pub states: Option<String>,
+    #[serde(default)]
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub until: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>, // Entries per page; default 100
+    #[serde(default)]
+    pub cursor: Option<u64>, // `next_cursor` of the previous page
+}
+
+impl HistoryQuery {
+    fn filter(&self) -> Result<HistoryFilter, String> {
+        let states = parse_states(&self.states)?;
+        Ok(HistoryFilter { robot_id: self.robot_id.clone(), task_type: self.task_type.clone(), states, since: self.since, until: self.until })
+    }
+}
+
+// Query of GET /fleet
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct FleetQuery {
+    #[serde(default)]
+    pub window_ms: Option<u64>, // Default 15 minutes
+}
+
+// Query of GET /admin/audit; `actions` is comma-separated, e.g. "task_cancelled,robot_drained"
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct AuditLogQuery {
+    #[serde(default)]
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub until: Option<u64>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub task_id: Option<u32>,
+    #[serde(default)]
+    pub actions: Option<String>,
+}
+
+impl AuditLogQuery {
+    fn query(&self) -> Result<AuditQuery, String> {
+        let actions = self
+            .actions
+            .iter()
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|a| serde_json::from_value::<AuditAction>(serde_json::Value::String(a.to_string())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid action filter: {}", e))?;
+        Ok(AuditQuery { since: self.since, until: self.until, actor: self.actor.clone(), task_id: self.task_id, actions })
+    }
+}
+
+impl EventStreamQuery {
+    fn filter(&self) -> Result<EventFilter, String> {
+        let list = |field: &Option<String>| -> Vec<String> {
+            field.iter().flat_map(|v| v.split(',')).map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect()
+        };
+        let states = list(&self.states)
+            .into_iter()
+            .map(|s| serde_json::from_value::<TaskState>(serde_json::Value::String(s)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid state filter: {}", e))?;
+        Ok(EventFilter {
+            namespaces: list(&self.namespaces),
+            robots: list(&self.robots),
+            task_types: list(&self.task_types),
+            tags: list(&self.tags),
+            states,
+        })
+    }
+}
+
+// Scheduler error mapped onto the closest HTTP status
+struct ApiError(StatusCode, String);
+
+impl From<String> for ApiError {
+    fn from(error: String) -> Self {
+        let status = if error.starts_with(QUOTA_EXCEEDED_ERROR) {
+            StatusCode::TOO_MANY_REQUESTS
+        } else if [OVERLOADED_ERROR, QUEUE_FULL_ERROR, SHUTTING_DOWN_ERROR].iter().any(|prefix| error.starts_with(prefix)) {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else if error.starts_with(ZONE_VIOLATION_ERROR) {
+            StatusCode::FORBIDDEN
+        } else if [CONFLICT_ERROR, VERSION_CONFLICT_ERROR].iter().any(|prefix| error.starts_with(prefix)) {
+            StatusCode::CONFLICT
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        ApiError(status, error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+// A response body rendered for the version of the API the request came in on
+fn versioned<T: Versioned>(version: ApiVersion, body: &T) -> Result<Json<serde_json::Value>, ApiError> {
+    version.render(body).map(Json).map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+// The scheduler acting as the caller `require_auth` identified, so its changes are audited
+// under the caller's name
+fn as_caller(scheduler: Scheduler, caller: Option<Extension<Principal>>) -> Scheduler {
+    match caller {
+        Some(Extension(principal)) => scheduler.acting_as(&principal.subject),
+        None => scheduler,
+    }
+}
+
+async fn submit_task(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Json(task): Json<Task>,
+) -> Result<impl IntoResponse, ApiError> {
+    let task_id = task.id;
+    as_caller(scheduler, caller).schedule_task(task).await?;
+    Ok((StatusCode::CREATED, Json(json!({ "id": task_id }))))
+}
+
+async fn begin_upload(State(scheduler): State<Scheduler>, Json(request): Json<UploadRequest>) -> impl IntoResponse {
+    let upload_id = scheduler.begin_upload(request.mission_id).await;
+    (StatusCode::CREATED, Json(json!({ "upload_id": upload_id })))
+}
+
+async fn upload_chunk(
+    State(scheduler): State<Scheduler>,
+    Path(upload_id): Path<String>,
+    Json(tasks): Json<Vec<Task>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    match scheduler.upload_chunk(&upload_id, tasks).await {
+        Ok(staged) => Ok(Json(json!({ "staged": staged }))),
+        Err(e) if e.starts_with("Unknown upload") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn commit_upload(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(upload_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).commit_upload(&upload_id).await {
+        Ok(submitted) => Ok((StatusCode::CREATED, Json(json!({ "submitted": submitted })))),
+        Err(e) if e.starts_with("Unknown upload") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn abort_upload(State(scheduler): State<Scheduler>, Path(upload_id): Path<String>) -> Result<StatusCode, ApiError> {
+    match scheduler.abort_upload(&upload_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError(StatusCode::NOT_FOUND, e)),
+    }
+}
+
+async fn get_task(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Path(task_id): Path<u32>,
+) -> Result<impl IntoResponse, ApiError> {
+    match scheduler.task_record(task_id).await {
+        Some(record) => versioned(version, &record),
+        None => Err(ApiError(StatusCode::NOT_FOUND, format!("Unknown task: {}", task_id))),
+    }
+}
+
+async fn cancel_task(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(task_id): Path<u32>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).cancel_task(task_id).await {
+        Ok(state) => Ok(Json(json!({ "id": task_id, "state": state }))),
+        Err(e) if e.starts_with("Unknown task") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn complete_task(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(task_id): Path<u32>,
+    Json(request): Json<CompleteTask>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).complete_task(task_id, request.expected_version).await {
+        Ok(version) => Ok(Json(json!({ "id": task_id, "version": version }))),
+        Err(e) if e.starts_with("Unknown task") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn hand_off_task(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(task_id): Path<u32>,
+    Json(request): Json<HandoffRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).hand_off_task(task_id, request).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) if e.starts_with("Unknown task") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn register_robot(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Json(robot): Json<RobotRegistration>,
+) -> Result<impl IntoResponse, ApiError> {
+    as_caller(scheduler, caller).register_robot(robot.robot_id, robot.capabilities).await?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn list_robots(State(scheduler): State<Scheduler>) -> Json<Vec<RobotSummary>> {
+    let mut robots: Vec<RobotSummary> = scheduler
+        .registered_robots()
+        .await
+        .into_iter()
+        .map(|(robot_id, capabilities)| RobotSummary { slots: scheduler.robot_slots(&robot_id), robot_id, capabilities })
+        .collect();
+    robots.sort_by(|a, b| a.robot_id.cmp(&b.robot_id));
+    Json(robots)
+}
+
+async fn health(State(scheduler): State<Scheduler>) -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok", "shedding_load": scheduler.is_shedding_load(), "queue": scheduler.queue_saturation() }))
+}
+
+async fn fleet_status(State(scheduler): State<Scheduler>, Query(query): Query<FleetQuery>) -> Json<FleetStatus> {
+    let window = std::time::Duration::from_millis(query.window_ms.unwrap_or(15 * 60 * 1_000));
+    Json(scheduler.fleet_status(window).await)
+}
+
+async fn list_tasks(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Query(query): Query<TaskListQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let filter = query.filter()?;
+    versioned(version, &scheduler.list_tasks(&filter).await)
+}
+
+async fn task_history(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let filter = query.filter()?;
+    versioned(version, &scheduler.list_task_history(&filter, query.limit.unwrap_or(100), query.cursor))
+}
+
+async fn set_robot_zones(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(robot_id): Path<String>,
+    Json(zones): Json<Vec<String>>,
+) -> Result<impl IntoResponse, ApiError> {
+    as_caller(scheduler, caller).set_robot_zones(&robot_id, zones)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_limits(State(scheduler): State<Scheduler>) -> Json<Vec<SubmitterUsage>> {
+    Json(scheduler.submitter_usage())
+}
+
+async fn set_limits(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path((kind, name)): Path<(String, String)>,
+    Json(limits): Json<SubmitterLimits>,
+) -> Result<impl IntoResponse, ApiError> {
+    let submitter = Submitter::parse(&kind, &name).map_err(|e| ApiError(StatusCode::NOT_FOUND, e))?;
+    as_caller(scheduler, caller).set_submitter_limits(submitter, limits);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_breakers(State(scheduler): State<Scheduler>) -> Json<Vec<BreakerStatus>> {
+    Json(scheduler.breaker_status())
+}
+
+async fn reset_breaker(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(robot_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    as_caller(scheduler, caller).reset_breaker(&robot_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_dead_letters(State(scheduler): State<Scheduler>, Extension(version): Extension<ApiVersion>) -> Result<Json<serde_json::Value>, ApiError> {
+    versioned(version, &scheduler.dead_letters().await)
+}
+
+async fn get_dead_letter(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Path(task_id): Path<u32>,
+) -> Result<impl IntoResponse, ApiError> {
+    match scheduler.dead_letter(task_id).await {
+        Some(record) => versioned(version, &record),
+        None => Err(ApiError(StatusCode::NOT_FOUND, format!("Unknown dead letter: {}", task_id))),
+    }
+}
+
+async fn redrive_dead_letter(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(task_id): Path<u32>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).redrive_dead_letter(task_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) if e.starts_with("Unknown dead letter") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn purge_dead_letter(
+    State(scheduler): State<Scheduler>,
+    caller: Option<Extension<Principal>>,
+    Path(task_id): Path<u32>,
+) -> Result<impl IntoResponse, ApiError> {
+    match as_caller(scheduler, caller).purge_dead_letter(task_id).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) if e.starts_with("Unknown dead letter") => Err(ApiError(StatusCode::NOT_FOUND, e)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn audit_log(State(scheduler): State<Scheduler>, Query(query): Query<AuditLogQuery>) -> Result<Json<Vec<AuditEntry>>, ApiError> {
+    Ok(Json(scheduler.query_audit(&query.query()?)))
+}
+
+// Stream matching transitions as newline-delimited JSON, compressed as negotiated
+async fn stream_events(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let options = StreamOptions { resume_from: query.resume_from, ..StreamOptions::default() };
+    let mut subscription = scheduler.subscribe_stream(query.filter()?, options)?;
+    let accept = headers.get(header::ACCEPT_ENCODING).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    let encoding = StreamEncoding::negotiate(accept);
+    let mut encoder = StreamEncoder::new(encoding).map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(16);
+    tokio::spawn(async move {
+        loop {
+            let (line, last) = match subscription.recv().await {
+                Ok(event) => (version.render(&event).unwrap_or_default(), false),
+                Err(StreamError::Lagged { resume_from }) => {
+                    let error = StreamError::Lagged { resume_from }.to_string();
+                    (json!({ "error": error, "resume_from": resume_from }), true)
+                }
+                Err(StreamError::Closed) => break,
+            };
+            let chunk = encoder.encode(format!("{}\n", line).as_bytes()).map(Bytes::from);
+            if tx.send(chunk).await.is_err() {
+                return;
+            }
+            if last {
+                break;
+            }
+        }
+        let _ = tx.send(encoder.finish().map(Bytes::from)).await;
+    });
+    let mut response = Body::from_stream(ReceiverStream::new(rx)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/x-ndjson"));
+    headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if let Some(coding) = encoding.content_encoding() {
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(coding));
+    }
+    Ok(response)
+}
+
+// Upgrade to a WebSocket carrying matching task updates until either side closes
+async fn watch_tasks(
+    State(scheduler): State<Scheduler>,
+    Extension(version): Extension<ApiVersion>,
+    Query(query): Query<EventStreamQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let watch = scheduler.watch(query.filter()?);
+    Ok(upgrade.on_upgrade(move |socket| send_updates(socket, watch, version)))
+}
+
+async fn send_updates(mut socket: WebSocket, mut watch: Watch, version: ApiVersion) {
+    loop {
+        tokio::select! {
+            update = watch.next() => {
+                let Ok(update) = update else {
+                    break;
+                };
+                let text = version.render(&update).unwrap_or_default().to_string();
+                if socket.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            message = socket.recv() => {
+                // Client messages other than a close are ignored
+                if matches!(message, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+                    return;
+                }
+            }
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+// Name the version of a response, and point clients of a deprecated version at the same
+// route in its successor
+async fn version_headers(State(version): State<ApiVersion>, request: Request, next: Next) -> Response {
+    // Nested routers see the path with their version prefix stripped
+    let successor = version.successor().map(|next| format!("</{}{}>; rel=\"successor-version\"", next.as_str(), request.uri().path()));
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(API_VERSION_HEADER, HeaderValue::from_static(version.as_str()));
+    if let Some(link) = successor.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+        headers.insert(axum::http::header::LINK, link);
+    }
+    response
+}
+
+// Routes of one API version
+fn versioned_routes(version: ApiVersion) -> Router<Scheduler> {
+    Router::new()
+        .route("/tasks", get(list_tasks).post(submit_task))
+        .route("/tasks/:id", get(get_task).delete(cancel_task))
+        .route("/tasks/:id/complete", axum::routing::post(complete_task))
+        .route("/tasks/:id/handoff", axum::routing::post(hand_off_task))
+        .route("/uploads", axum::routing::post(begin_upload))
+        .route("/uploads/:id", axum::routing::delete(abort_upload))
+        .route("/uploads/:id/chunks", axum::routing::post(upload_chunk))
+        .route("/uploads/:id/commit", axum::routing::post(commit_upload))
+        .route("/robots", get(list_robots).post(register_robot))
+        .route("/robots/:id/zones", axum::routing::put(set_robot_zones))
+        .route("/health", get(health))
+        .route("/fleet", get(fleet_status))
+        .route("/history", get(task_history))
+        .route("/events", get(stream_events))
+        .route("/watch", get(watch_tasks))
+        .route("/admin/limits", get(list_limits))
+        .route("/admin/limits/:kind/:name", axum::routing::put(set_limits))
+        .route("/admin/audit", get(audit_log))
+        .route("/admin/breakers", get(list_breakers))
+        .route("/admin/breakers/:id/reset", axum::routing::post(reset_breaker))
+        .route("/admin/dead-letters", get(list_dead_letters))
+        .route("/admin/dead-letters/:id", get(get_dead_letter).delete(purge_dead_letter))
+        .route("/admin/dead-letters/:id/redrive", axum::routing::post(redrive_dead_letter))
+        .layer(Extension(version))
+        .layer(middleware::from_fn_with_state(version, version_headers))
+}
+
+// Routes of the REST API over a scheduler
+pub fn http_router(scheduler: Scheduler) -> Router {
+    Router::new()
+        .merge(versioned_routes(ApiVersion::V1)) // Unversioned paths predate versioning
+        .nest("/v1", versioned_routes(ApiVersion::V1))
+        .nest("/v2", versioned_routes(ApiVersion::V2))
+        .with_state(scheduler)
+}
+
+async fn list_instances(State(registry): State<Arc<SchedulerRegistry>>) -> Json<Vec<String>> {
+    Json(registry.names())
+}
+
+// Hand a request under /instances/{name}/ to that instance's routes. A fallback rather than
+// a route with path parameters, whose captures would clash with the instance routes' own.
+async fn route_to_instance(State(registry): State<Arc<SchedulerRegistry>>, mut request: Request) -> Result<Response, ApiError> {
+    let Some((instance, path)) = request.uri().path().strip_prefix("/instances/").and_then(|rest| rest.split_once('/')) else {
+        return Err(ApiError(StatusCode::NOT_FOUND, format!("No route for {}", request.uri().path())));
+    };
+    let scheduler = registry
+        .get(instance)
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("Unknown instance: {}", instance)))?;
+    let uri = match request.uri().query() {
+        Some(query) => format!("/{}?{}", path, query),
+        None => format!("/{}", path),
+    };
+    *request.uri_mut() = uri.parse().map_err(|_| ApiError(StatusCode::BAD_REQUEST, format!("Invalid path: {}", uri)))?;
+    Ok(http_router(scheduler).oneshot(request).await.into_response())
+}
+
+// Routes of the REST API over every instance of a registry
+pub fn registry_router(registry: Arc<SchedulerRegistry>) -> Router {
+    Router::new()
+        .route("/instances", get(list_instances))
+        .fallback(route_to_instance)
+        .with_state(registry)
+}
+
+// Action a request takes, from its method and its path below any instance or version prefix
+fn action_of(method: &Method, path: &str) -> Action {
+    let mut segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    if segments.len() > 2 && segments[0] == "instances" {
+        segments.drain(..2);
+    }
+    if segments.first().is_some_and(|segment| [ApiVersion::V1, ApiVersion::V2].iter().any(|v| v.as_str() == *segment)) {
+        segments.remove(0);
+    }
+    match (method, segments.first().copied()) {
+        (_, Some("admin")) => Action::Administer,
+        (&Method::GET | &Method::HEAD, _) => Action::Read,
+        (&Method::POST, Some("tasks")) => Action::Schedule,
+        (&Method::DELETE, Some("tasks")) => Action::Cancel,
+        (&Method::POST | &Method::DELETE, Some("uploads")) => Action::Schedule,
+        (&Method::POST, Some("robots")) => Action::RegisterRobot,
+        _ => Action::Administer,
+    }
+}
+
+// Refuse requests whose bearer token doesn't allow their action; accepted ones carry the
+// caller's `Principal` as a request extension
+async fn check_token(State(verifier): State<Arc<dyn TokenVerifier>>, mut request: Request, next: Next) -> Response {
+    let action = action_of(request.method(), request.uri().path());
+    let authorization = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    match authorize(verifier.as_ref(), authorization, action) {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(AuthError::Unauthenticated(e)) => {
+            let mut response = ApiError(StatusCode::UNAUTHORIZED, e).into_response();
+            response.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        Err(AuthError::Forbidden(e)) => ApiError(StatusCode::FORBIDDEN, e).into_response(),
+    }
+}
+
+// Require a bearer token accepted by `verifier` on every route of `router`
+pub fn require_auth(router: Router, verifier: Arc<dyn TokenVerifier>) -> Router {
+    router.layer(middleware::from_fn_with_state(verifier, check_token))
+}
+
+// Serve `router` over TLS on an already bound listener, with HTTP/2 and HTTP/1.1 and
+// WebSocket upgrades as over cleartext. Runs until the task is dropped; a client that
+// fails the handshake, e.g. without an accepted certificate under mutual TLS, is dropped.
+#[cfg(feature = "tls")]
+pub async fn serve_tls(listener: tokio::net::TcpListener, router: Router, tls: &ServerTls) -> Result<(), String> {
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use hyper_util::server::conn::auto;
+    use hyper_util::service::TowerToHyperService;
+    let acceptor = tokio_rustls::TlsAcceptor::from(tls.rustls_config(&[b"h2", b"http/1.1"])?);
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                // Typically out of file descriptors; back off rather than spin
+                tracing::warn!(error = %e, "HTTPS accept failed");
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let (acceptor, service) = (acceptor.clone(), TowerToHyperService::new(router.clone()));
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => return tracing::warn!(%peer, error = %e, "TLS handshake failed"),
+            };
+            let _ = auto::Builder::new(TokioExecutor::new()).serve_connection_with_upgrades(TokioIo::new(stream), service).await;
+        });
+    }
+}
+
+impl Scheduler {
+    // Serve the REST API on `addr` until the server fails
+    pub async fn serve_http(&self, addr: SocketAddr) -> Result<(), String> {
+        let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+        axum::serve(listener, http_router(self.clone()))
+            .await
+            .map_err(|e| format!("HTTP server on {} failed: {}", addr, e))
+    }
+
+    // Serve the REST API over TLS on `addr`
+    #[cfg(feature = "tls")]
+    pub async fn serve_https(&self, addr: SocketAddr, tls: &ServerTls) -> Result<(), String> {
+        let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| format!("Failed to bind {}: {}", addr, e))?;
+        serve_tls(listener, http_router(self.clone()), tls).await
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use axum::body::to_bytes;
+    use axum::http::Request;
+    use tokio_stream::StreamExt;
+    use crate::auth::{ApiTokens, Role};
+    use crate::geofencing::Geofence;
+    use crate::watch::{WatchEvent, WatchKind};
+
+    async fn send(router: &Router, method: &str, uri: &str, body: Option<&str>) -> Response {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.unwrap_or_default().to_string()))
+            .unwrap();
+        router.clone().oneshot(request).await.unwrap()
+    }
+
+    async fn call(router: &Router, method: &str, uri: &str, body: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let response = send(router, method, uri, body).await;
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_rest_routes_map_onto_scheduler() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let router = http_router(scheduler);
+        let robot = r#"{"robot_id": "Ford", "capabilities": ["lift"]}"#;
+        assert_eq!(call(&router, "POST", "/robots", Some(robot)).await.0, StatusCode::CREATED);
+        assert_eq!(call(&router, "POST", "/robots", Some(robot)).await.0, StatusCode::CONFLICT);
+        let (status, robots) = call(&router, "GET", "/robots", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(robots[0]["robot_id"], "Ford");
+        assert_eq!(robots[0]["slots"], 1);
+
+        let task = r#"{"id": 201, "task_type": "lift", "priority": 1, "deadline": null, "required_capabilities": ["lift"]}"#;
+        assert_eq!(call(&router, "POST", "/tasks", Some(task)).await, (StatusCode::CREATED, json!({ "id": 201 })));
+        let (status, record) = call(&router, "GET", "/tasks/201", None).await;
+        assert_eq!((status, &record["state"], &record["version"]), (StatusCode::OK, &json!("Pending"), &json!(1)));
+        let stale = r#"{"expected_version": 7}"#;
+        assert_eq!(call(&router, "POST", "/tasks/201/complete", Some(stale)).await.0, StatusCode::CONFLICT);
+        let pending = r#"{"expected_version": 1}"#;
+        assert_eq!(call(&router, "POST", "/tasks/201/complete", Some(pending)).await.0, StatusCode::BAD_REQUEST);
+        let (status, error) = call(&router, "GET", "/tasks/999", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["error"], "Unknown task: 999");
+        let unknown_robot = r#"{"id": 202, "task_type": "lift", "priority": 1, "deadline": null, "robot_id": "Ghost", "required_capabilities": []}"#;
+        assert_eq!(call(&router, "POST", "/tasks", Some(unknown_robot)).await.0, StatusCode::BAD_REQUEST);
+        let (status, tasks) = call(&router, "GET", "/tasks?states=Pending&capability=lift", None).await;
+        assert_eq!((status, &tasks[0]["task_id"], tasks.as_array().unwrap().len()), (StatusCode::OK, &json!(201), 1));
+        assert_eq!(call(&router, "GET", "/history?limit=5", None).await.1, json!({ "entries": [], "next_cursor": null }));
+        assert_eq!(call(&router, "GET", "/history?states=Lost", None).await.0, StatusCode::BAD_REQUEST);
+        let (_, health) = call(&router, "GET", "/health", None).await;
+        assert_eq!((&health["status"], &health["queue"]["capacity"]), (&json!("ok"), &json!(100)));
+        let (status, fleet) = call(&router, "GET", "/fleet?window_ms=60000", None).await;
+        assert_eq!((status, &fleet["window_ms"], &fleet["robots"][0]["online"]), (StatusCode::OK, &json!(60_000), &json!(true)));
+    }
+
+    #[tokio::test]
+    async fn test_admin_limits_adjust_at_runtime() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let router = http_router(scheduler);
+        let limits = r#"{"max_queued": 1}"#;
+        assert_eq!(call(&router, "PUT", "/admin/limits/source/planner", Some(limits)).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(call(&router, "PUT", "/admin/limits/robot/Ford", Some(limits)).await.0, StatusCode::NOT_FOUND);
+        let task = |id| format!(r#"{{"id": {}, "task_type": "lift", "priority": 1, "required_capabilities": [], "source": "planner"}}"#, id);
+        assert_eq!(call(&router, "POST", "/tasks", Some(&task(354))).await.0, StatusCode::CREATED);
+        let (status, error) = call(&router, "POST", "/tasks", Some(&task(355))).await;
+        assert_eq!((status, error["error"].as_str().unwrap()), (StatusCode::TOO_MANY_REQUESTS, "QuotaExceeded: source planner has 1 of 1 queued tasks"));
+        let (_, usage) = call(&router, "GET", "/admin/limits", None).await;
+        assert_eq!(usage, json!([{"submitter": {"source": "planner"}, "queued": 1, "last_minute": 0, "limits": {"max_queued": 1}}]));
+
+        assert_eq!(call(&router, "PUT", "/admin/limits/source/planner", Some("{}")).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(call(&router, "POST", "/tasks", Some(&task(355))).await.0, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_robot_zones_set_at_runtime() {
+        let geofence = r#"{"zones": {"dock": {"frame_id": "site", "vertices": [{"x": 0, "y": 0}, {"x": 5, "y": 0}, {"x": 5, "y": 5}]}}}"#;
+        let (scheduler, _workers) = Scheduler::builder().geofence(Geofence::from_json(geofence).unwrap()).build().unwrap();
+        let router = http_router(scheduler);
+        let robot = r#"{"robot_id": "Ford", "capabilities": []}"#;
+        assert_eq!(call(&router, "POST", "/robots", Some(robot)).await.0, StatusCode::CREATED);
+        let task = r#"{"id": 393, "task_type": "unload", "priority": 1, "robot_id": "Ford", "required_capabilities": [], "zone_name": "dock"}"#;
+        let (status, error) = call(&router, "POST", "/tasks", Some(task)).await;
+        assert_eq!((status, error["error"].as_str().unwrap()), (StatusCode::FORBIDDEN, "Zone violation: robot Ford is not permitted in zone dock"));
+        assert_eq!(call(&router, "PUT", "/robots/Ford/zones", Some(r#"["roof"]"#)).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(call(&router, "PUT", "/robots/Ford/zones", Some(r#"["dock"]"#)).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(call(&router, "POST", "/tasks", Some(task)).await.0, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_handoff_needs_a_task_on_a_robot() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let router = http_router(scheduler.clone());
+        let handoff = r#"{"progress": {"stops_delivered": 3}}"#;
+        assert_eq!(call(&router, "POST", "/tasks/401/handoff", Some(handoff)).await.0, StatusCode::NOT_FOUND);
+        let task = r#"{"id": 401, "task_type": "deliver", "priority": 1, "required_capabilities": [], "not_before": 18446744073709551615}"#;
+        assert_eq!(call(&router, "POST", "/tasks", Some(task)).await.0, StatusCode::CREATED);
+        let (status, error) = call(&router, "POST", "/tasks/401/handoff", Some(handoff)).await;
+        assert_eq!((status, error["error"].as_str().unwrap()), (StatusCode::BAD_REQUEST, "Task 401 is Pending; only assigned, running, or suspended tasks can be handed off"));
+    }
+
+    #[tokio::test]
+    async fn test_mission_uploaded_in_chunks() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let router = http_router(scheduler.clone());
+        let (status, opened) = call(&router, "POST", "/uploads", Some(r#"{"mission_id": "survey"}"#)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let upload = format!("/uploads/{}", opened["upload_id"].as_str().unwrap());
+        let chunk = r#"[{"id": 413, "task_type": "scan", "priority": 1, "required_capabilities": [], "not_before": 18446744073709551615}]"#;
+        let (status, staged) = call(&router, "POST", &format!("{}/chunks", upload), Some(chunk)).await;
+        assert_eq!((status, staged["staged"].as_u64()), (StatusCode::OK, Some(1)));
+        assert_eq!(call(&router, "POST", &format!("{}/chunks", upload), Some(chunk)).await.0, StatusCode::BAD_REQUEST);
+        assert!(scheduler.task_record(413).await.is_none());
+
+        let (status, committed) = call(&router, "POST", &format!("{}/commit", upload), None).await;
+        assert_eq!((status, committed["submitted"].as_u64()), (StatusCode::CREATED, Some(1)));
+        assert_eq!(scheduler.task_record(413).await.unwrap().task.mission_id.as_deref(), Some("survey"));
+        assert_eq!(call(&router, "DELETE", &upload, None).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_resetting_a_closed_breaker_conflicts() {
+        let (scheduler, _workers) = Scheduler::builder().circuit_breaker(Default::default()).build().unwrap();
+        let router = http_router(scheduler);
+        let (status, breakers) = call(&router, "GET", "/admin/breakers", None).await;
+        assert_eq!((status, breakers), (StatusCode::OK, serde_json::json!([])));
+        assert_eq!(call(&router, "POST", "/admin/breakers/Ford/reset", None).await.0, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_dead_letters_are_not_found() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let router = http_router(scheduler);
+        let (status, letters) = call(&router, "GET", "/admin/dead-letters", None).await;
+        assert_eq!((status, letters), (StatusCode::OK, serde_json::json!([])));
+        assert_eq!(call(&router, "GET", "/admin/dead-letters/407", None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(call(&router, "POST", "/admin/dead-letters/407/redrive", None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(call(&router, "DELETE", "/admin/dead-letters/407", None).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_bearer_tokens_gate_routes_by_role() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let tokens = ApiTokens::new().token("view", "dashboard", Role::Viewer).token("ops", "line-lead", Role::Operator).token("root", "it", Role::Admin);
+        let router = require_auth(http_router(scheduler), Arc::new(tokens));
+        let call = |method: &'static str, uri: &'static str, token: Option<&'static str>, body: &'static str| {
+            let router = router.clone();
+            async move {
+                let mut request = Request::builder().method(method).uri(uri).header("content-type", "application/json");
+                if let Some(token) = token {
+                    request = request.header("authorization", format!("Bearer {}", token));
+                }
+                router.oneshot(request.body(Body::from(body)).unwrap()).await.unwrap().status()
+            }
+        };
+        let robot = r#"{"robot_id": "Ford", "capabilities": ["lift"]}"#;
+        let task = r#"{"id": 356, "task_type": "lift", "priority": 1, "required_capabilities": []}"#;
+        assert_eq!(call("GET", "/health", None, "").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(call("GET", "/v2/health", Some("view"), "").await, StatusCode::OK);
+        assert_eq!(call("POST", "/v2/tasks", Some("view"), task).await, StatusCode::FORBIDDEN);
+        assert_eq!(call("POST", "/robots", Some("ops"), robot).await, StatusCode::FORBIDDEN);
+        assert_eq!(call("POST", "/robots", Some("root"), robot).await, StatusCode::CREATED);
+        assert_eq!(call("POST", "/v1/tasks", Some("ops"), task).await, StatusCode::CREATED);
+        assert_eq!(call("DELETE", "/tasks/356", Some("view"), "").await, StatusCode::FORBIDDEN);
+        assert_eq!(call("DELETE", "/tasks/356", Some("ops"), "").await, StatusCode::OK);
+        assert_eq!(call("DELETE", "/tasks/356", Some("ops"), "").await, StatusCode::CONFLICT);
+        assert_eq!(call("DELETE", "/tasks/999", Some("ops"), "").await, StatusCode::NOT_FOUND);
+        assert_eq!(call("GET", "/admin/limits", Some("ops"), "").await, StatusCode::FORBIDDEN);
+        assert_eq!(call("GET", "/admin/limits", Some("root"), "").await, StatusCode::OK);
+        assert_eq!(action_of(&Method::POST, "/instances/north/v2/robots"), Action::RegisterRobot);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_names_the_authenticated_caller() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let tokens = ApiTokens::new().token("ops", "line-lead", Role::Operator).token("root", "it", Role::Admin);
+        let router = require_auth(http_router(scheduler.clone()), Arc::new(tokens));
+        let call = |method: &'static str, uri: &'static str, token: &'static str, body: &'static str| {
+            let router = router.clone();
+            async move {
+                let request = Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::from(body))
+                    .unwrap();
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or(serde_json::Value::Null))
+            }
+        };
+        let task = r#"{"id": 361, "task_type": "lift", "priority": 1, "required_capabilities": []}"#;
+        assert_eq!(call("POST", "/tasks", "ops", task).await.0, StatusCode::CREATED);
+        assert_eq!(call("DELETE", "/tasks/361", "ops", "").await.0, StatusCode::OK);
+        assert_eq!(call("PUT", "/admin/limits/source/planner", "root", r#"{"max_queued": 1}"#).await.0, StatusCode::NO_CONTENT);
+
+        let (status, entries) = call("GET", "/admin/audit?actor=line-lead&actions=task_cancelled,task_held", "root", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!((entries.as_array().unwrap().len(), &entries[0]["task_id"]), (1, &json!(361)));
+        let (_, entries) = call("GET", "/admin/audit?actor=it", "root", "").await;
+        assert_eq!(entries[0]["action"], "limits_changed");
+        assert_eq!(call("GET", "/admin/audit?actions=rebooted", "root", "").await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(call("GET", "/admin/audit", "ops", "").await.0, StatusCode::FORBIDDEN);
+        assert_eq!(scheduler.audit_log(Some(361)).len(), 2);
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_api_served_over_mutual_tls() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::rustls::{self, pki_types::ServerName};
+        let pki = crate::tls::test_pki();
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let tls = pki.server.clone().client_ca(pki.ca.clone());
+        tokio::spawn(async move { serve_tls(listener, http_router(scheduler), &tls).await });
+
+        let get_health = |identity: Option<(Vec<u8>, Vec<u8>)>| {
+            let ca = pki.ca.clone();
+            async move {
+                let mut roots = rustls::RootCertStore::empty();
+                roots.add_parsable_certificates(rustls_pemfile::certs(&mut &ca[..]).map(Result::unwrap));
+                let provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
+                let builder = rustls::ClientConfig::builder_with_provider(provider).with_safe_default_protocol_versions().unwrap().with_root_certificates(roots);
+                let config = match identity {
+                    Some((cert, key)) => {
+                        let chain = rustls_pemfile::certs(&mut &cert[..]).map(Result::unwrap).collect();
+                        builder.with_client_auth_cert(chain, rustls_pemfile::private_key(&mut &key[..]).unwrap().unwrap()).unwrap()
+                    }
+                    None => builder.with_no_client_auth(),
+                };
+                let connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config));
+                let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+                let mut stream = connector.connect(ServerName::try_from("localhost").unwrap(), stream).await.ok()?;
+                stream.write_all(b"GET /health HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n").await.ok()?;
+                let mut response = String::new();
+                stream.read_to_string(&mut response).await.ok()?;
+                Some(response)
+            }
+        };
+        let response = get_health(Some(pki.client.clone())).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        // Without a client certificate the server ends the handshake
+        assert!(get_health(None).await.is_none_or(|response| response.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_versions_served_side_by_side() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let router = http_router(scheduler);
+        let task = r#"{"id": 211, "task_type": "lift", "priority": 1, "deadline": "2026-01-01T00:00:00Z", "required_capabilities": []}"#;
+        assert_eq!(call(&router, "POST", "/v2/tasks", Some(task)).await.0, StatusCode::CREATED);
+
+        assert_eq!(call(&router, "GET", "/tasks/211", None).await.1["task"]["deadline"], 1_767_225_600_000u64);
+        assert_eq!(call(&router, "GET", "/v1/tasks/211", None).await.1["task"]["deadline"], 1_767_225_600_000u64);
+        assert_eq!(call(&router, "GET", "/v2/tasks/211", None).await.1["task"]["deadline"], "2026-01-01T00:00:00.000Z");
+
+        // v1 and the unversioned paths are deprecated in favor of the same route in v2
+        for uri in ["/tasks/211", "/v1/tasks/211"] {
+            let response = send(&router, "GET", uri, None).await;
+            let headers = response.headers();
+            assert_eq!(headers[API_VERSION_HEADER], "v1");
+            assert_eq!(headers[DEPRECATION_HEADER], "true");
+            assert_eq!(headers["link"], "</v2/tasks/211>; rel=\"successor-version\"");
+        }
+        let response = send(&router, "GET", "/v2/health", None).await;
+        assert_eq!(response.headers()[API_VERSION_HEADER], "v2");
+        assert!(!response.headers().contains_key(DEPRECATION_HEADER));
+        // Errors are versioned too
+        let response = send(&router, "GET", "/v1/tasks/999", None).await;
+        assert_eq!((response.status(), &response.headers()[DEPRECATION_HEADER]), (StatusCode::NOT_FOUND, &HeaderValue::from_static("true")));
+    }
+
+    #[tokio::test]
+    async fn test_v2_listings_render_deadlines() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let router = http_router(scheduler);
+        let task = r#"{"id": 426, "task_type": "lift", "priority": 1, "deadline": "2026-01-01T00:00:00Z", "required_capabilities": []}"#;
+        assert_eq!(call(&router, "POST", "/v2/tasks", Some(task)).await.0, StatusCode::CREATED);
+
+        let (status, listed) = call(&router, "GET", "/v2/tasks", None).await;
+        assert_eq!((status, &listed[0]["deadline"]), (StatusCode::OK, &json!("2026-01-01T00:00:00.000Z")));
+        assert_eq!(call(&router, "GET", "/v1/tasks", None).await.1[0]["deadline"], 1_767_225_600_000u64);
+    }
+
+    #[tokio::test]
+    async fn test_registry_routes_to_named_instances() {
+        let registry = Arc::new(SchedulerRegistry::new());
+        for name in ["north", "south"] {
+            let (scheduler, workers) = Scheduler::builder().build().unwrap();
+            workers.spawn();
+            registry.insert(name, scheduler).unwrap();
+        }
+        let router = registry_router(registry);
+        assert_eq!(call(&router, "GET", "/instances", None).await.1, json!(["north", "south"]));
+        let task = r#"{"id": 342, "task_type": "lift", "priority": 1, "deadline": null, "required_capabilities": []}"#;
+        assert_eq!(call(&router, "POST", "/instances/north/v2/tasks", Some(task)).await.0, StatusCode::CREATED);
+        assert_eq!(call(&router, "GET", "/instances/north/tasks/342", None).await.1["task"]["id"], 342);
+        assert_eq!(call(&router, "GET", "/instances/south/tasks/342", None).await.0, StatusCode::NOT_FOUND);
+        let (status, error) = call(&router, "GET", "/instances/west/health", None).await;
+        assert_eq!((status, &error["error"]), (StatusCode::NOT_FOUND, &json!("Unknown instance: west")));
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_is_compressed_as_negotiated() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let router = http_router(scheduler.clone());
+        let request = Request::builder()
+            .uri("/v2/events?states=Completed&task_types=lift")
+            .header("accept-encoding", "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+        assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+        scheduler.schedule_task(Task { id: 221, task_type: "lift".to_string(), ..Default::default() }).await.unwrap();
+
+        // The first event decodes as soon as its chunk arrives
+        let mut chunks = response.into_body().into_data_stream();
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        while !decoder.get_ref().ends_with(b"\n") {
+            decoder.write_all(&chunks.next().await.unwrap().unwrap()).unwrap();
+            decoder.flush().unwrap();
+        }
+        let event: serde_json::Value = serde_json::from_slice(decoder.get_ref()).unwrap();
+        assert_eq!((event["task_id"].as_u64(), event["transition"]["to"].as_str()), (Some(221), Some("Completed")));
+
+        assert_eq!(call(&router, "GET", "/events?states=Sleeping", None).await.0, StatusCode::BAD_REQUEST);
+        let plain = send(&router, "GET", "/events", None).await;
+        assert!(!plain.headers().contains_key("content-encoding"));
+    }
+
+    #[tokio::test]
+    async fn test_watch_streams_updates_over_websocket() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = http_router(scheduler.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/v2/watch?states=Completed", addr)).await.unwrap();
+
+        // The watch subscribes before the upgrade is answered, so nothing is missed
+        scheduler.schedule_task(Task { id: 336, ..Default::default() }).await.unwrap();
+        let update = socket.next().await.unwrap().unwrap();
+        let update: WatchEvent = serde_json::from_str(update.to_text().unwrap()).unwrap();
+        assert_eq!((update.task_id, update.kind), (336, WatchKind::Completed));
+    }
+}
@@ -0,0 +1,111 @@
+// backend/rust/src/latency.rs
+// Purpose: End-to-end latency budget tracking for MRTODP. Each task's latest attempt is
+// split into phases (validation, queue wait, assignment, transport, execution, and result
+// processing) from its transition timestamps plus a few extra marks, so operators can see
+// where the dispatch budget goes. Breakdowns ride on terminal events, are stored on the
+// task record, and feed per-phase histograms.
+
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{TaskState, Transition};
+
+// Phase names used as histogram keys, in lifecycle order
+pub const PHASES: [&str; 6] = ["validation", "queue_wait", "assignment", "transport", "execution", "result_processing"];
+
+// Timestamps (milliseconds) of phase boundaries that aren't state transitions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LatencyMarks {
+    pub(crate) received_at: Option<u64>,   // schedule_task called, before validation
+    pub(crate) handed_off_at: Option<u64>, // Assignment queued on the robot's outbox
+    pub(crate) reported_at: Option<u64>,   // Robot result received
+}
+
+// Time spent in each phase of a finished attempt; phases the task never went through
+// (e.g. transport under simulated execution) are None
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PhaseBreakdown {
+    pub validation_ms: Option<u64>,
+    pub queue_wait_ms: Option<u64>,
+    pub assignment_ms: Option<u64>, // Dequeue to hand-off: routing, localization, outbox
+    pub transport_ms: Option<u64>,  // Hand-off to the robot accepting the assignment
+    pub execution_ms: Option<u64>,
+    pub result_processing_ms: Option<u64>, // Result received to terminal state recorded
+    pub total_ms: u64,
+}
+
+impl PhaseBreakdown {
+    // Break down an attempt from its transitions (excluding the terminal one, which
+    // happened at `finished_at`) and the marks recorded along the way
+    pub(crate) fn from_attempt(marks: &LatencyMarks, transitions: &[Transition], finished_at: u64) -> Self {
+        let at = |state: TaskState| transitions.iter().rev().find(|t| t.to == state).map(|t| t.at);
+        let queued_at = at(TaskState::Pending);
+        let dequeued_at = transitions.iter().rev().find(|t| t.from == Some(TaskState::Pending)).map(|t| t.at);
+        let running_at = at(TaskState::Running);
+        let execution_end = marks.reported_at.unwrap_or(finished_at);
+        let span = |from: Option<u64>, to: Option<u64>| Some(to?.saturating_sub(from?));
+        let validation_ms = span(marks.received_at, queued_at);
+        PhaseBreakdown {
+            validation_ms,
+            queue_wait_ms: span(queued_at, dequeued_at.or(Some(finished_at))),
+            assignment_ms: span(dequeued_at, marks.handed_off_at),
+            transport_ms: span(marks.handed_off_at, running_at),
+            execution_ms: span(running_at, Some(execution_end)),
+            result_processing_ms: span(marks.reported_at, Some(finished_at)),
+            total_ms: span(marks.received_at.or(queued_at), Some(finished_at)).unwrap_or(0),
+        }
+    }
+
+    // (phase name, duration) for every phase the attempt went through
+    pub fn phases(&self) -> Vec<(&'static str, u64)> {
+        let durations = [
+            self.validation_ms,
+            self.queue_wait_ms,
+            self.assignment_ms,
+            self.transport_ms,
+            self.execution_ms,
+            self.result_processing_ms,
+        ];
+        PHASES.iter().zip(durations).filter_map(|(name, ms)| Some((*name, ms?))).collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::ReasonCode;
+
+    fn transition(from: Option<TaskState>, to: TaskState, at: u64) -> Transition {
+        Transition { from, to, reason: ReasonCode::Dispatched, detail: String::new(), at }
+    }
+
+    #[test]
+    fn test_transport_attempt_breakdown() {
+        let marks = LatencyMarks { received_at: Some(100), handed_off_at: Some(160), reported_at: Some(700) };
+        let transitions = [
+            transition(None, TaskState::Pending, 105),
+            transition(Some(TaskState::Pending), TaskState::Assigned, 150),
+            transition(Some(TaskState::Assigned), TaskState::Running, 200),
+        ];
+        let breakdown = PhaseBreakdown::from_attempt(&marks, &transitions, 710);
+        assert_eq!(
+            breakdown.phases(),
+            vec![
+                ("validation", 5),
+                ("queue_wait", 45),
+                ("assignment", 10),
+                ("transport", 40),
+                ("execution", 500),
+                ("result_processing", 10),
+            ]
+        );
+        assert_eq!(breakdown.total_ms, 610);
+    }
+
+    #[test]
+    fn test_expired_attempt_only_waited() {
+        let transitions = [transition(None, TaskState::Pending, 0)];
+        let breakdown = PhaseBreakdown::from_attempt(&LatencyMarks::default(), &transitions, 300);
+        assert_eq!(breakdown.phases(), vec![("queue_wait", 300)]);
+        assert_eq!(breakdown.total_ms, 300);
+    }
+}
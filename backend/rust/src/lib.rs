@@ -1,3 +1,197 @@
 // Library root for MRTODP Rust scheduler
+// The scheduler can be embedded directly by Rust applications; the C FFI used by the
+// Python delegator is a thin optional layer behind the `ffi` feature.
+pub mod adapter;
+pub mod alerting;
+pub mod api_version;
+pub mod assignment;
+pub mod auction;
+pub mod audit;
+pub mod auth;
+pub mod backpressure;
+pub mod breakers;
+pub mod builder;
+pub mod capacity;
+pub mod charging;
+pub mod checkpoints;
+pub mod clock;
+pub mod coalitions;
+pub mod compatibility;
+pub mod dead_letters;
+pub mod deadline_miss;
+pub mod deadlines;
+pub mod decay;
+pub mod delayed;
+pub mod escalation;
+pub mod events;
+pub mod fair_queuing;
+pub mod fleet;
+pub mod frames;
+pub mod geofencing;
+pub mod geometry;
+pub mod handles;
+pub mod handoff;
+pub mod history;
+pub mod latency;
+pub mod load_shedding;
+pub mod location;
+pub mod metrics;
+pub mod missions;
+pub mod mutex_groups;
+pub mod payload_schemas;
+pub mod policy;
+pub mod preemption;
+pub mod priority_classes;
+pub mod profiles;
+pub mod quotas;
+pub mod readiness;
+pub mod reconcile;
+pub mod recovery;
+pub mod registry;
+pub mod retention;
+pub mod resource_locks;
+pub mod retries;
+pub mod robot_queues;
+pub mod rules;
 pub mod scheduler;
+pub mod shadow;
+pub mod shutdown;
+pub mod simulation;
+pub mod slo;
+pub mod store;
+pub mod submission_buffer;
+pub mod submitter_limits;
+pub mod task;
+pub mod task_list;
+pub mod templates;
+pub mod time_windows;
+pub mod trace_context;
+pub mod transport;
+pub mod uploads;
+pub mod validation;
+pub mod versioning;
+pub mod wal;
+pub mod watch;
+pub mod webhooks;
 
+#[cfg(feature = "http")]
+pub mod compression;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "ffi")]
+pub mod ffi_instance;
+
+#[cfg(feature = "ffi")]
+pub mod ffi_result;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+#[cfg(all(any(test, feature = "test-utils"), feature = "grpc"))]
+pub mod harness;
+
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "sled")]
+pub mod sled_store;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+#[cfg(feature = "tls")]
+pub mod tls;
+
+use std::future::Future;
+use std::pin::Pin;
+
+// Boxed future returned by object-safe async extension traits
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub use adapter::{AdapterMessage, AdapterTransport, ChannelAdapter, ResultReporter, RobotAdapter};
+pub use alerting::{Alert, AlertRoute, AlertSink, EmailSink, PagerDutySink, Severity, SlackSink};
+pub use api_version::{ApiVersion, API_VERSION_HEADER, DEPRECATION_HEADER};
+pub use assignment::{AssignmentConfig, AssignmentScorer, Candidate, DefaultScorer};
+pub use auction::{AuctionConfig, Bid};
+pub use audit::{AuditAction, AuditEntry, AuditQuery, DEFAULT_AUDIT_CAPACITY};
+pub use auth::{authorize, Action, ApiTokens, AuthError, Principal, Role, TokenVerifier};
+pub use backpressure::{BackpressurePolicy, QueueSaturation, QUEUE_FULL_ERROR};
+pub use breakers::{BreakerState, BreakerStatus, CircuitBreakerConfig};
+#[cfg(feature = "jwt")]
+pub use auth::JwtVerifier;
+pub use builder::{SchedulerBuilder, SchedulerWorkers, TransitionHook};
+pub use capacity::RobotCapacity;
+pub use charging::{AutoCharging, CHARGE_TASK_TYPE};
+pub use checkpoints::{Checkpoint, CheckpointInfo, RestoreReport};
+pub use clock::{Clock, SystemClock};
+pub use coalitions::Coalition;
+pub use compatibility::{CompatibilityEntry, CompatibilityMatrix, CompatibilityReport, RobotModel};
+#[cfg(feature = "http")]
+pub use compression::{StreamEncoder, StreamEncoding};
+pub use dead_letters::DeadLetter;
+pub use deadline_miss::{DeadlineMiss, DeadlineMissHook, DeadlinePolicy};
+pub use decay::ExpediteDecay;
+pub use escalation::{EscalationConfig, EscalationNotice};
+pub use events::{EventFilter, FilteredSubscription, SlowConsumerPolicy, StreamError, StreamOptions};
+pub use fair_queuing::FairShare;
+pub use fleet::{FleetStatus, RobotStatus, DEFAULT_OFFLINE_AFTER};
+pub use frames::{FrameRegistry, FrameSpec, StaticTransform};
+pub use geofencing::{Geofence, ZONE_VIOLATION_ERROR};
+pub use geometry::{GeoPoint, Location, Point, Pose, Waypoint, Zone};
+pub use handles::{AdminHandle, QueryHandle, SubmitHandle};
+pub use handoff::{Handoff, HandoffRequest};
+pub use history::{HistoryEntry, HistoryFilter, HistoryPage, DEFAULT_HISTORY_CAPACITY, MAX_HISTORY_PAGE};
+#[cfg(all(any(test, feature = "test-utils"), feature = "grpc"))]
+pub use harness::{TestCluster, TestClusterBuilder};
+pub use latency::{PhaseBreakdown, PHASES};
+pub use load_shedding::{LoadModeEvent, LoadSheddingConfig, OVERLOADED_ERROR};
+pub use location::{DistanceFunction, Euclidean, TravelTimeMatrix};
+pub use metrics::{Histogram, HistogramSnapshot, WindowStats};
+pub use missions::{Mission, MissionState, MissionStatus};
+#[cfg(feature = "mqtt")]
+pub use mqtt::{MqttBridge, MqttConfig, MqttTransport};
+pub use mutex_groups::MutexGroupStatus;
+pub use policy::{policy_by_name, EarliestDeadlineFirst, PriorityFirst, SchedulingPolicy};
+pub use preemption::PreemptionConfig;
+pub use priority_classes::{ClassQuotas, PriorityAging, PriorityClass};
+pub use profiles::{DurationStats, RobotProfile};
+pub use quotas::{QuotaCounter, QuotaUsage, QUOTA_EXCEEDED_ERROR};
+pub use readiness::{ReadyCheck, RobotReadiness, SELF_TEST_TASK_TYPE};
+pub use reconcile::ReconcileReport;
+pub use registry::{SchedulerRegistry, INSTANCE_METADATA_KEY};
+pub use resource_locks::ResourceStatus;
+pub use retention::DEFAULT_TASK_RETENTION;
+pub use retries::RetryPolicy;
+pub use robot_queues::StealPolicy;
+pub use rules::{AdmissionRuleSpec, Expression, RoutingRuleSpec, RuleEngine, RuleSetSpec};
+pub use scheduler::{Attempt, DuplicateRobotPolicy, ReasonCode, Scheduler, TaskEvent, TaskRecord, TaskState, Transition, CONFLICT_ERROR};
+pub use shadow::{DecisionKind, Divergence, ShadowReport};
+pub use shutdown::{ShutdownReport, SHUTTING_DOWN_ERROR};
+pub use simulation::{Simulation, SimulationConfig, VirtualRobot, VirtualRobotStats};
+pub use slo::{SloAlert, SloSpec, SloStatus};
+pub use store::{MemoryStore, TaskStore};
+#[cfg(feature = "sled")]
+pub use sled_store::SledStore;
+pub use submitter_limits::{Submitter, SubmitterLimits, SubmitterUsage};
+pub use task::Task;
+pub use task_list::{TaskListFilter, TaskSummary};
+pub use templates::{ParamSpec, ParamType, TaskTemplate};
+pub use time_windows::TimeWindow;
+#[cfg(feature = "tls")]
+pub use tls::{ClientTls, ServerTls};
+pub use trace_context::TraceContext;
+pub use transport::{ControlCommand, ControlDelivery, ControlEnvelope, DispatchSeq, ReplayGuard, RobotReport, RobotSequence, RobotTransport};
+pub use uploads::DEFAULT_UPLOAD_TTL;
+pub use validation::ValidationConfig;
+pub use versioning::VERSION_CONFLICT_ERROR;
+pub use wal::WalStore;
+pub use watch::{Watch, WatchEvent, WatchKind};
+pub use webhooks::{HttpWebhookTransport, WebhookConfig, WebhookTransport};
@@ -0,0 +1,141 @@
+// backend/rust/src/load_shedding.rs
+// Purpose: Overload protection for MRTODP. When the dispatch queue gets too deep or queue
+// wait (a smoothed average of submission-to-dispatch time) gets too long, the scheduler
+// enters load-shedding mode and rejects low-priority submissions with a retriable error
+// while still accepting high-priority work. Entering and leaving the mode is broadcast as
+// a `LoadModeEvent`; separate exit thresholds keep the mode from flapping.
+
+use serde::{Deserialize, Serialize};
+
+// Prefix of the error returned for shed submissions; clients should back off and retry
+pub const OVERLOADED_ERROR: &str = "Overloaded";
+
+// Weight of the newest sample in the smoothed queue wait
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+// Thresholds for entering and leaving load-shedding mode
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadSheddingConfig {
+    pub enter_queue_depth: usize, // Queued tasks at which shedding starts
+    pub exit_queue_depth: usize,  // Queued tasks at or below which shedding may stop
+    pub enter_latency_ms: u64,    // Smoothed queue wait at which shedding starts
+    pub exit_latency_ms: u64,     // Smoothed queue wait at or below which shedding may stop
+    pub min_priority: u32,        // Submissions below this priority are shed
+}
+
+impl Default for LoadSheddingConfig {
+    fn default() -> Self {
+        LoadSheddingConfig {
+            enter_queue_depth: 80,
+            exit_queue_depth: 40,
+            enter_latency_ms: 5_000,
+            exit_latency_ms: 1_000,
+            min_priority: 5,
+        }
+    }
+}
+
+impl LoadSheddingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.exit_queue_depth > self.enter_queue_depth || self.exit_latency_ms > self.enter_latency_ms {
+            return Err("Load shedding exit thresholds must not exceed enter thresholds".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Broadcast whenever the scheduler enters or leaves load-shedding mode
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LoadModeEvent {
+    pub shedding: bool,
+    pub queue_depth: usize,
+    pub latency_ms: u64, // Smoothed queue wait when the mode changed
+    pub reason: String,
+    pub at: u64, // Unix timestamp (milliseconds)
+}
+
+// Mode state machine fed by queue depth and dispatch latency samples
+pub(crate) struct LoadShedder {
+    config: LoadSheddingConfig,
+    shedding: bool,
+    latency_ms: f64,
+}
+
+impl LoadShedder {
+    pub(crate) fn new(config: LoadSheddingConfig) -> Self {
+        LoadShedder { config, shedding: false, latency_ms: 0.0 }
+    }
+
+    pub(crate) fn is_shedding(&self) -> bool {
+        self.shedding
+    }
+
+    pub(crate) fn record_latency(&mut self, wait_ms: u64) {
+        self.latency_ms += LATENCY_SMOOTHING * (wait_ms as f64 - self.latency_ms);
+    }
+
+    // Re-evaluate the mode; returns an event if it changed
+    pub(crate) fn update(&mut self, queue_depth: usize, now_ms: u64) -> Option<LoadModeEvent> {
+        let latency_ms = self.latency_ms.round() as u64;
+        let reason = if !self.shedding {
+            if queue_depth >= self.config.enter_queue_depth {
+                format!("Queue depth {} reached {}", queue_depth, self.config.enter_queue_depth)
+            } else if latency_ms >= self.config.enter_latency_ms {
+                format!("Queue wait {}ms reached {}ms", latency_ms, self.config.enter_latency_ms)
+            } else {
+                return None;
+            }
+        } else if queue_depth <= self.config.exit_queue_depth && latency_ms <= self.config.exit_latency_ms {
+            format!("Queue depth {} and wait {}ms back under exit thresholds", queue_depth, latency_ms)
+        } else {
+            return None;
+        };
+        self.shedding = !self.shedding;
+        Some(LoadModeEvent { shedding: self.shedding, queue_depth, latency_ms, reason, at: now_ms })
+    }
+
+    // Reject a submission that is shed in the current mode
+    pub(crate) fn admit(&self, priority: u32) -> Result<(), String> {
+        if self.shedding && priority < self.config.min_priority {
+            return Err(format!(
+                "{}: shedding submissions below priority {}; retry later",
+                OVERLOADED_ERROR, self.config.min_priority
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enter_and_exit_with_hysteresis() {
+        let config = LoadSheddingConfig { enter_queue_depth: 10, exit_queue_depth: 2, ..Default::default() };
+        let mut shedder = LoadShedder::new(config);
+        assert!(shedder.update(9, 0).is_none());
+        let entered = shedder.update(10, 1).unwrap();
+        assert!(entered.shedding);
+        assert!(shedder.admit(1).unwrap_err().starts_with(OVERLOADED_ERROR));
+        assert!(shedder.admit(5).is_ok());
+
+        // Dropping below the enter threshold is not enough to leave the mode
+        assert!(shedder.update(5, 2).is_none());
+        let exited = shedder.update(2, 3).unwrap();
+        assert!(!exited.shedding);
+        assert!(shedder.admit(1).is_ok());
+    }
+
+    #[test]
+    fn test_latency_triggers_shedding() {
+        let config = LoadSheddingConfig { enter_latency_ms: 100, exit_latency_ms: 10, ..Default::default() };
+        let mut shedder = LoadShedder::new(config);
+        for _ in 0..20 {
+            shedder.record_latency(500);
+        }
+        assert!(shedder.update(0, 0).unwrap().reason.contains("Queue wait"));
+        assert!(LoadSheddingConfig { exit_latency_ms: 200, ..config }.validate().is_err());
+    }
+}
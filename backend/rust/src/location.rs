@@ -0,0 +1,158 @@
+// backend/rust/src/location.rs
+// Purpose: Location-aware assignment for MRTODP. Robots (or their transports) report where
+// they are with `Scheduler::report_robot_location`; tasks may name where they are carried
+// out in their `location` field. Both are a pose in a frame or a lat/lon point (see
+// geometry.rs). When placing a task that has a location, the scheduler asks the builder's
+// `DistanceFunction` how far each candidate robot is from it, and robot selection (see
+// assignment.rs) can then prefer the nearest robot. Two distance functions ship built in:
+// `Euclidean` (the default) measures straight-line metres, re-expressing poses in
+// different frames through the frame registry and using great-circle distance between
+// lat/lon points; `TravelTimeMatrix` looks up precomputed travel times between areas,
+// taking a pose's frame as its area. Robots or tasks without a location, or locations a
+// function can't compare, have no distance.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::frames::FrameRegistry;
+use crate::geometry::{GeoPoint, Location, Pose};
+use crate::scheduler::Scheduler;
+
+// Mean Earth radius (metres) for great-circle distances
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+// Distance (or travel cost) from a robot's location to a task's; lower is nearer
+pub trait DistanceFunction: Send + Sync {
+    // None if the two locations can't be compared
+    fn distance(&self, from: &Location, to: &Location, frames: &FrameRegistry) -> Option<f64>;
+}
+
+// Straight-line distance in metres
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Euclidean;
+
+impl DistanceFunction for Euclidean {
+    fn distance(&self, from: &Location, to: &Location, frames: &FrameRegistry) -> Option<f64> {
+        match (from, to) {
+            (Location::Pose(from), Location::Pose(to)) => {
+                let from = if from.frame_id == to.frame_id { from.clone() } else { frames.transform_pose(from, &to.frame_id).ok()? };
+                Some(pose_distance(&from, to))
+            }
+            (Location::Geo(from), Location::Geo(to)) => Some(great_circle(from, to).hypot(to.alt - from.alt)),
+            _ => None,
+        }
+    }
+}
+
+fn pose_distance(a: &Pose, b: &Pose) -> f64 {
+    ((b.x - a.x).powi(2) + (b.y - a.y).powi(2) + (b.z - a.z).powi(2)).sqrt()
+}
+
+// Haversine distance along the Earth's surface
+fn great_circle(a: &GeoPoint, b: &GeoPoint) -> f64 {
+    let (lat_a, lat_b) = (a.lat.to_radians(), b.lat.to_radians());
+    let half_chord = ((lat_b - lat_a) / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * ((b.lon - a.lon).to_radians() / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * half_chord.sqrt().min(1.0).asin()
+}
+
+// Precomputed travel times (seconds) between areas, keyed by frame ID; moving within an
+// area is free. Lat/lon points and area pairs without a time have no distance.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TravelTimeMatrix {
+    times: HashMap<String, HashMap<String, f64>>, // from area -> to area -> seconds
+}
+
+impl TravelTimeMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Travel time between two areas, the same in both directions
+    pub fn time(self, a: &str, b: &str, seconds: f64) -> Self {
+        self.one_way(a, b, seconds).one_way(b, a, seconds)
+    }
+
+    // Travel time from one area to another only, e.g. along a one-way aisle
+    pub fn one_way(mut self, from: &str, to: &str, seconds: f64) -> Self {
+        self.times.entry(from.to_string()).or_default().insert(to.to_string(), seconds);
+        self
+    }
+}
+
+impl DistanceFunction for TravelTimeMatrix {
+    fn distance(&self, from: &Location, to: &Location, _frames: &FrameRegistry) -> Option<f64> {
+        let (Location::Pose(from), Location::Pose(to)) = (from, to) else {
+            return None;
+        };
+        if from.frame_id == to.frame_id {
+            return Some(0.0);
+        }
+        self.times.get(&from.frame_id)?.get(&to.frame_id).copied()
+    }
+}
+
+impl Scheduler {
+    // Record where a robot is; poses must be in a registered frame when frames are configured
+    pub async fn report_robot_location(&self, robot_id: &str, location: Location) -> Result<(), String> {
+        location.validate()?;
+        if let Location::Pose(pose) = &location {
+            if !self.core.frames.is_empty() && !self.core.frames.contains(&pose.frame_id) {
+                return Err(format!("Unknown frame: {}", pose.frame_id));
+            }
+        }
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.robot_heartbeat(robot_id);
+        self.core.robot_locations.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string(), location);
+        Ok(())
+    }
+
+    // Last location a robot reported
+    pub fn robot_location(&self, robot_id: &str) -> Option<Location> {
+        self.core.robot_locations.lock().unwrap_or_else(|e| e.into_inner()).get(robot_id).cloned()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assignment::AssignmentConfig;
+    use crate::scheduler::TaskState;
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_nearest_robot_gets_the_task() {
+        let pose = |frame_id: &str, x: f64, y: f64| Location::Pose(Pose { frame_id: frame_id.to_string(), x, y, ..Default::default() });
+        let frames = FrameRegistry::from_manifest_json(
+            r#"{"robots": [], "frames": [{"id": "site"}, {"id": "cell_3", "parent": "site", "transform": {"x": 10.0, "y": 0.0}}]}"#,
+        )
+        .unwrap();
+        let distance = |function: &dyn DistanceFunction, from: &Location, to: &Location| function.distance(from, to, &frames);
+        assert_eq!(distance(&Euclidean, &pose("site", 0.0, 0.0), &pose("site", 3.0, 4.0)), Some(5.0));
+        assert_eq!(distance(&Euclidean, &pose("cell_3", 0.0, 0.0), &pose("site", 7.0, 4.0)), Some(5.0));
+        let geo = |lat: f64, lon: f64| Location::Geo(GeoPoint { lat, lon, alt: 0.0 });
+        let one_degree = distance(&Euclidean, &geo(0.0, 0.0), &geo(1.0, 0.0)).unwrap();
+        assert!((one_degree - 111_195.0).abs() < 1.0);
+        assert_eq!(distance(&Euclidean, &geo(0.0, 0.0), &pose("site", 0.0, 0.0)), None);
+        let matrix = TravelTimeMatrix::new().time("dock", "aisle_4", 40.0).one_way("aisle_4", "pack", 15.0);
+        assert_eq!(distance(&matrix, &pose("aisle_4", 0.0, 0.0), &pose("dock", 9.0, 9.0)), Some(40.0));
+        assert_eq!(distance(&matrix, &pose("pack", 0.0, 0.0), &pose("aisle_4", 0.0, 0.0)), None);
+        assert_eq!(distance(&matrix, &pose("pack", 0.0, 0.0), &pose("pack", 5.0, 0.0)), Some(0.0));
+
+        let config = AssignmentConfig { nearest: true, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder().assignment(config).build().unwrap();
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        scheduler.report_robot_location("Ford", pose("site", 0.0, 0.0)).await.unwrap();
+        scheduler.report_robot_location("Scion", pose("site", 10.0, 0.0)).await.unwrap();
+        assert!(scheduler.report_robot_location("Hank", pose("site", 0.0, 0.0)).await.is_err());
+        scheduler.schedule_task(Task { id: 390, location: Some(pose("site", 8.0, 1.0)), ..Default::default() }).await.unwrap();
+        while scheduler.task_record(390).await.is_none_or(|r| r.state != TaskState::Completed) {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.task_record(390).await.unwrap().task.robot_id.as_deref(), Some("Scion"));
+    }
+}
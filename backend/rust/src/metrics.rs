@@ -0,0 +1,316 @@
+// backend/rust/src/metrics.rs
+// Purpose: In-process scheduler metrics for MRTODP. Tracks queue wait (time from submission
+// to dispatch) as fixed-bucket histograms broken down by required capability, so operators
+// can see which capabilities are bottlenecked (e.g. `precision_assembly` waiting 4x longer),
+// counts urgent-lane (expedite) submissions per source to spot lane abuse, and keeps a day
+// of timestamped dispatches and outcomes for the dashboard's sliding-window statistics,
+// along with each robot's busy intervals and late finishes for the fleet status (see
+// fleet.rs).
+// Finished tasks also feed one histogram per latency phase (see `latency`).
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::latency::PhaseBreakdown;
+use crate::scheduler::TaskState;
+
+// Upper bounds (milliseconds) of the histogram buckets; a final overflow bucket follows
+pub const WAIT_BUCKETS_MS: [u64; 11] = [10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000];
+
+// Label used for tasks that require no capability
+pub const NO_CAPABILITY: &str = "(none)";
+
+// Label used for submissions that name no source
+pub const UNKNOWN_SOURCE: &str = "(unknown)";
+
+// Longest window `stats` can report on; older samples are discarded
+pub const MAX_STATS_WINDOW_MS: u64 = 24 * 60 * 60 * 1000;
+
+// Cap on retained samples of each kind, bounding memory under very high throughput
+const MAX_SAMPLES: usize = 100_000;
+
+// Fixed-bucket histogram of millisecond durations
+#[derive(Clone, Debug, Default)]
+pub struct Histogram {
+    counts: [u64; WAIT_BUCKETS_MS.len() + 1],
+    count: u64,
+    sum_ms: u64,
+    max_ms: u64,
+}
+
+// Serializable view of a histogram with derived statistics
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HistogramSnapshot {
+    pub buckets_ms: Vec<u64>, // Upper bounds; the last count is the overflow bucket
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: u64,
+    pub mean_ms: f64,
+    pub max_ms: u64,
+    pub p50_ms: u64, // Percentiles are bucket upper bounds (max for the overflow bucket)
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl Histogram {
+    pub fn record(&mut self, value_ms: u64) {
+        let bucket = WAIT_BUCKETS_MS.iter().position(|&bound| value_ms <= bound).unwrap_or(WAIT_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += value_ms;
+        self.max_ms = self.max_ms.max(value_ms);
+    }
+
+    // Estimate a percentile (0-100) as the upper bound of the bucket containing it
+    pub fn percentile(&self, pct: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((pct / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, &c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= rank {
+                return WAIT_BUCKETS_MS.get(i).copied().unwrap_or(self.max_ms).min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot {
+            buckets_ms: WAIT_BUCKETS_MS.to_vec(),
+            counts: self.counts.to_vec(),
+            count: self.count,
+            sum_ms: self.sum_ms,
+            mean_ms: if self.count == 0 { 0.0 } else { self.sum_ms as f64 / self.count as f64 },
+            max_ms: self.max_ms,
+            p50_ms: self.percentile(50.0),
+            p95_ms: self.percentile(95.0),
+            p99_ms: self.percentile(99.0),
+        }
+    }
+}
+
+// Rolled-up fleet metrics over a sliding window, for the dashboard overview
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WindowStats {
+    pub window_ms: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub expired: u64,
+    #[serde(default)]
+    pub cancelled: u64,
+    pub throughput_per_min: f64, // Finished tasks (any outcome) per minute
+    pub success_rate: Option<f64>, // Completed / (completed + failed); None without outcomes
+    pub active_robots: usize, // Robots dispatched to or finishing work in the window
+    pub dispatched: u64,
+    pub p95_wait_ms: Option<u64>, // Exact, over dispatches in the window
+}
+
+// Timestamped samples backing windowed statistics
+#[derive(Default)]
+struct RecentActivity {
+    dispatches: VecDeque<(u64, u64, Option<String>)>, // (at, queue wait, robot)
+    outcomes: VecDeque<(u64, TaskState, Option<String>)>, // (at, terminal state, robot)
+    busy: VecDeque<(u64, u64, String)>, // (end, start, robot) of finished busy intervals
+    misses: VecDeque<(u64, String)>,    // (at, robot) of tasks finished past their deadline
+}
+
+// A robot's recent work, from the samples in a window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct RobotActivity {
+    pub(crate) busy_ms: u64, // Task time overlapping the window, summed over slots
+    pub(crate) deadline_misses: u64,
+}
+
+impl RecentActivity {
+    fn prune(&mut self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(MAX_STATS_WINDOW_MS);
+        while self.dispatches.front().is_some_and(|d| d.0 < cutoff) || self.dispatches.len() > MAX_SAMPLES {
+            self.dispatches.pop_front();
+        }
+        while self.outcomes.front().is_some_and(|o| o.0 < cutoff) || self.outcomes.len() > MAX_SAMPLES {
+            self.outcomes.pop_front();
+        }
+        while self.busy.front().is_some_and(|b| b.0 < cutoff) || self.busy.len() > MAX_SAMPLES {
+            self.busy.pop_front();
+        }
+        while self.misses.front().is_some_and(|m| m.0 < cutoff) || self.misses.len() > MAX_SAMPLES {
+            self.misses.pop_front();
+        }
+    }
+}
+
+// Scheduler-wide metrics registry
+#[derive(Default)]
+pub(crate) struct Metrics {
+    queue_wait: HashMap<String, Histogram>, // capability -> queue wait distribution
+    phases: HashMap<&'static str, Histogram>, // latency phase -> duration distribution
+    expedited: HashMap<String, u64>,        // source -> expedited submissions
+    recent: RecentActivity,
+}
+
+impl Metrics {
+    // Record a task's queue wait under each of its required capabilities
+    pub(crate) fn record_queue_wait(&mut self, capabilities: &[String], wait_ms: u64) {
+        if capabilities.is_empty() {
+            self.queue_wait.entry(NO_CAPABILITY.to_string()).or_default().record(wait_ms);
+        }
+        for capability in capabilities {
+            self.queue_wait.entry(capability.clone()).or_default().record(wait_ms);
+        }
+    }
+
+    pub(crate) fn queue_wait_by_capability(&self) -> HashMap<String, HistogramSnapshot> {
+        self.queue_wait.iter().map(|(k, h)| (k.clone(), h.snapshot())).collect()
+    }
+
+    pub(crate) fn record_phases(&mut self, breakdown: &PhaseBreakdown) {
+        for (phase, ms) in breakdown.phases() {
+            self.phases.entry(phase).or_default().record(ms);
+        }
+    }
+
+    pub(crate) fn phase_latency(&self) -> HashMap<String, HistogramSnapshot> {
+        self.phases.iter().map(|(k, h)| (k.to_string(), h.snapshot())).collect()
+    }
+
+    pub(crate) fn record_dispatch(&mut self, at_ms: u64, wait_ms: u64, robot_id: Option<&str>) {
+        self.recent.dispatches.push_back((at_ms, wait_ms, robot_id.map(str::to_string)));
+        self.recent.prune(at_ms);
+    }
+
+    pub(crate) fn record_finished(&mut self, at_ms: u64, state: TaskState, robot_id: Option<&str>) {
+        self.recent.outcomes.push_back((at_ms, state, robot_id.map(str::to_string)));
+        self.recent.prune(at_ms);
+    }
+
+    // A robot held a task from `start_ms` until `end_ms`
+    pub(crate) fn record_busy(&mut self, robot_id: &str, start_ms: u64, end_ms: u64) {
+        self.recent.busy.push_back((end_ms, start_ms, robot_id.to_string()));
+        self.recent.prune(end_ms);
+    }
+
+    // A robot finished a task after its deadline
+    pub(crate) fn record_deadline_miss(&mut self, at_ms: u64, robot_id: &str) {
+        self.recent.misses.push_back((at_ms, robot_id.to_string()));
+        self.recent.prune(at_ms);
+    }
+
+    // Busy time and deadline misses per robot over the last `window_ms` (capped at
+    // MAX_STATS_WINDOW_MS), from finished intervals only
+    pub(crate) fn robot_activity(&mut self, window_ms: u64, now_ms: u64) -> HashMap<String, RobotActivity> {
+        self.recent.prune(now_ms);
+        let cutoff = now_ms.saturating_sub(window_ms.clamp(1, MAX_STATS_WINDOW_MS));
+        let mut activity: HashMap<String, RobotActivity> = HashMap::new();
+        for (end, start, robot) in self.recent.busy.iter().filter(|b| b.0 > cutoff) {
+            activity.entry(robot.clone()).or_default().busy_ms += end.saturating_sub((*start).max(cutoff));
+        }
+        for (_, robot) in self.recent.misses.iter().filter(|m| m.0 >= cutoff) {
+            activity.entry(robot.clone()).or_default().deadline_misses += 1;
+        }
+        activity
+    }
+
+    // Roll up samples from the last `window_ms` (capped at MAX_STATS_WINDOW_MS)
+    pub(crate) fn window_stats(&mut self, window_ms: u64, now_ms: u64) -> WindowStats {
+        self.recent.prune(now_ms);
+        let window_ms = window_ms.clamp(1, MAX_STATS_WINDOW_MS);
+        let cutoff = now_ms.saturating_sub(window_ms);
+        let mut robots = HashSet::new();
+        let mut waits: Vec<u64> = Vec::new();
+        for (_, wait, robot) in self.recent.dispatches.iter().filter(|d| d.0 >= cutoff) {
+            waits.push(*wait);
+            robots.extend(robot.as_deref());
+        }
+        let (mut completed, mut failed, mut expired, mut cancelled) = (0, 0, 0, 0);
+        for (_, state, robot) in self.recent.outcomes.iter().filter(|o| o.0 >= cutoff) {
+            match state {
+                TaskState::Completed => completed += 1,
+                TaskState::Failed => failed += 1,
+                TaskState::Cancelled => cancelled += 1,
+                _ => expired += 1,
+            }
+            robots.extend(robot.as_deref());
+        }
+        waits.sort_unstable();
+        let p95_wait_ms = (!waits.is_empty()).then(|| waits[((waits.len() as f64 * 0.95).ceil() as usize).max(1) - 1]);
+        WindowStats {
+            window_ms,
+            completed,
+            failed,
+            expired,
+            cancelled,
+            throughput_per_min: (completed + failed + expired + cancelled) as f64 * 60_000.0 / window_ms as f64,
+            success_rate: (completed + failed > 0).then(|| completed as f64 / (completed + failed) as f64),
+            active_robots: robots.len(),
+            dispatched: waits.len() as u64,
+            p95_wait_ms,
+        }
+    }
+
+    pub(crate) fn record_expedite(&mut self, source: Option<&str>) {
+        *self.expedited.entry(source.unwrap_or(UNKNOWN_SOURCE).to_string()).or_default() += 1;
+    }
+
+    pub(crate) fn expedite_by_source(&self) -> HashMap<String, u64> {
+        self.expedited.clone()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = Histogram::default();
+        for value in [5, 8, 40, 90, 400, 700, 2_000, 2_200, 3_000, 120_000] {
+            histogram.record(value);
+        }
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 10);
+        assert_eq!(snapshot.counts[0], 2);
+        assert_eq!(*snapshot.counts.last().unwrap(), 1);
+        assert_eq!(snapshot.p50_ms, 500);
+        assert_eq!(snapshot.p99_ms, 120_000);
+    }
+
+    #[test]
+    fn test_queue_wait_split_by_capability() {
+        let mut metrics = Metrics::default();
+        metrics.record_queue_wait(&["precision_assembly".to_string(), "vision".to_string()], 4_000);
+        metrics.record_queue_wait(&["vision".to_string()], 1_000);
+        metrics.record_queue_wait(&[], 20);
+        let waits = metrics.queue_wait_by_capability();
+        assert_eq!(waits["precision_assembly"].count, 1);
+        assert_eq!(waits["vision"].count, 2);
+        assert_eq!(waits[NO_CAPABILITY].count, 1);
+    }
+
+    #[test]
+    fn test_window_stats_only_count_recent_samples() {
+        let mut metrics = Metrics::default();
+        let minute = 60_000;
+        metrics.record_dispatch(0, 9_000, Some("Ford"));
+        metrics.record_finished(0, TaskState::Failed, Some("Ford"));
+        for i in 0..20 {
+            metrics.record_dispatch(10 * minute + i, 100 + i, Some("Scion"));
+        }
+        metrics.record_finished(10 * minute, TaskState::Completed, Some("Scion"));
+        metrics.record_finished(10 * minute, TaskState::Completed, Some("Ryder"));
+        metrics.record_finished(10 * minute, TaskState::Expired, None);
+
+        let stats = metrics.window_stats(5 * minute, 11 * minute);
+        assert_eq!((stats.completed, stats.failed, stats.expired), (2, 0, 1));
+        assert_eq!(stats.success_rate, Some(1.0));
+        assert_eq!(stats.active_robots, 2);
+        assert_eq!(stats.p95_wait_ms, Some(118));
+        assert!((stats.throughput_per_min - 0.6).abs() < 1e-9);
+
+        let day = metrics.window_stats(MAX_STATS_WINDOW_MS, 11 * minute);
+        assert_eq!(day.failed, 1);
+        assert_eq!(day.p95_wait_ms, Some(119));
+    }
+}
@@ -0,0 +1,350 @@
+// backend/rust/src/missions.rs
+// Purpose: Missions for MRTODP. Tasks sharing a `mission_id` form a mission ("inspect
+// building floor 3" as 40 tasks), tracked as one unit: a mission may be registered up front
+// with a name and metadata, its status rolls up the states of its tasks into counts, a
+// percentage complete and a collective state, and cancelling it withdraws every unfinished
+// task at once. Cancellation closes the mission first, so none of its tasks is dispatched
+// or accepted afterwards. A configurable cap also bounds how many missions may be active
+// at once per namespace ("run at most 2 pilot missions at once"); tasks of missions beyond
+// the cap are parked at mission level and released in FIFO order as active missions finish.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{TaskRecord, TaskState};
+use crate::task::Task;
+
+// Namespace used for tasks that don't set one
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+// A registered mission and its shared metadata
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mission {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled_at: Option<u64>, // Set by cancel_mission (Unix milliseconds)
+}
+
+// Collective state of a mission's tasks
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissionState {
+    Pending,   // No task has started yet
+    Active,    // Some tasks have started and some are unfinished
+    Completed, // Every task completed
+    Failed,    // Every task finished, some failed or expired
+    Cancelled, // Every task finished after the mission was cancelled, or some were and none failed
+}
+
+// Progress of a mission, rolled up from its tasks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MissionStatus {
+    pub mission: Mission,
+    pub state: MissionState,
+    pub total: usize,
+    pub pending: usize,
+    pub active: usize, // Assigned, running, or suspended
+    pub completed: usize,
+    pub failed: usize, // Failed or expired
+    pub cancelled: usize,
+    pub percent_complete: f64, // Share of tasks that have finished, whatever the outcome
+}
+
+impl Mission {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("Mission ID must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    // Roll up the records of the mission's tasks
+    pub(crate) fn status<'a>(self, records: impl Iterator<Item = &'a TaskRecord>) -> MissionStatus {
+        let (mut pending, mut active, mut completed, mut failed, mut cancelled) = (0, 0, 0, 0, 0);
+        for record in records {
+            match record.state {
+                TaskState::Pending => pending += 1,
+                TaskState::Assigned | TaskState::Running | TaskState::Suspended => active += 1,
+                TaskState::Completed => completed += 1,
+                TaskState::Failed | TaskState::Expired => failed += 1,
+                TaskState::Cancelled => cancelled += 1,
+            }
+        }
+        let total = pending + active + completed + failed + cancelled;
+        let finished = completed + failed + cancelled;
+        let state = if finished < total && pending == total {
+            MissionState::Pending
+        } else if finished < total {
+            MissionState::Active
+        } else if total > 0 && completed == total {
+            MissionState::Completed
+        } else if self.cancelled_at.is_some() || (failed == 0 && cancelled > 0) {
+            MissionState::Cancelled
+        } else if failed > 0 {
+            MissionState::Failed
+        } else {
+            MissionState::Pending // Registered, no tasks submitted yet
+        };
+        let percent_complete = if total == 0 { 0.0 } else { finished as f64 * 100.0 / total as f64 };
+        MissionStatus { mission: self, state, total, pending, active, completed, failed, cancelled, percent_complete }
+    }
+}
+
+// Outcome of offering a task to the limiter
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Dispatch, // Mission is active (or task has no mission); send to execution
+    Parked,   // Mission is waiting for a slot; task held until released
+}
+
+// Per-namespace cap on concurrently active missions
+#[derive(Default)]
+pub(crate) struct MissionLimiter {
+    limits: HashMap<String, usize>, // namespace -> max active missions
+    default_limit: Option<usize>,   // Applies to namespaces without an explicit limit
+    active: HashMap<String, HashSet<String>>, // namespace -> active mission IDs
+    open_tasks: HashMap<String, usize>,       // mission ID -> non-terminal admitted tasks
+    waiting: HashMap<String, VecDeque<String>>, // namespace -> missions waiting for a slot
+    parked: HashMap<String, Vec<Task>>,         // mission ID -> tasks held for it
+}
+
+pub(crate) fn namespace_of(task: &Task) -> &str {
+    task.namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE)
+}
+
+impl MissionLimiter {
+    pub(crate) fn new(limits: HashMap<String, usize>, default_limit: Option<usize>) -> Self {
+        MissionLimiter { limits, default_limit, ..Default::default() }
+    }
+
+    fn limit(&self, namespace: &str) -> Option<usize> {
+        self.limits.get(namespace).copied().or(self.default_limit)
+    }
+
+    // Decide whether a newly accepted task may be dispatched now
+    pub(crate) fn admit(&mut self, task: &Task) -> Admission {
+        let Some(mission) = task.mission_id.clone() else {
+            return Admission::Dispatch;
+        };
+        let namespace = namespace_of(task).to_string();
+        if self.parked.contains_key(&mission) {
+            self.parked.get_mut(&mission).unwrap().push(task.clone());
+            return Admission::Parked;
+        }
+        let limit = self.limit(&namespace);
+        let active = self.active.entry(namespace.clone()).or_default();
+        let has_slot = limit.is_none_or(|limit| active.len() < limit);
+        if active.contains(&mission) || has_slot {
+            self.active.entry(namespace).or_default().insert(mission.clone());
+            *self.open_tasks.entry(mission).or_default() += 1;
+            Admission::Dispatch
+        } else {
+            self.waiting.entry(namespace).or_default().push_back(mission.clone());
+            self.parked.insert(mission, vec![task.clone()]);
+            Admission::Parked
+        }
+    }
+
+    // Record that an admitted task reached a terminal state; returns tasks released from
+    // missions that took over freed slots
+    pub(crate) fn task_finished(&mut self, task: &Task) -> Vec<Task> {
+        let Some(mission) = &task.mission_id else {
+            return Vec::new();
+        };
+        let namespace = namespace_of(task).to_string();
+        if let Some(open) = self.open_tasks.get_mut(mission) {
+            *open = open.saturating_sub(1);
+            if *open == 0 {
+                self.open_tasks.remove(mission);
+                if let Some(active) = self.active.get_mut(&namespace) {
+                    active.remove(mission);
+                }
+            }
+        }
+        self.release(&namespace)
+    }
+
+    // Admit waiting missions while the namespace has free slots
+    fn release(&mut self, namespace: &str) -> Vec<Task> {
+        let mut released = Vec::new();
+        loop {
+            let active_count = self.active.get(namespace).map_or(0, |a| a.len());
+            if self.limit(namespace).is_some_and(|limit| active_count >= limit) {
+                break;
+            }
+            let Some(mission) = self.waiting.get_mut(namespace).and_then(|w| w.pop_front()) else {
+                break;
+            };
+            let tasks = self.parked.remove(&mission).unwrap_or_default();
+            if tasks.is_empty() {
+                continue;
+            }
+            self.active.entry(namespace.to_string()).or_default().insert(mission.clone());
+            *self.open_tasks.entry(mission).or_default() += tasks.len();
+            released.extend(tasks);
+        }
+        released
+    }
+
+    // Remove a parked task so it is never released; missions left empty are skipped on release
+    pub(crate) fn withdraw(&mut self, task_id: u32) {
+        for tasks in self.parked.values_mut() {
+            tasks.retain(|t| t.id != task_id);
+        }
+    }
+
+    // Free reservations whose tasks are gone: active slots of missions without any live
+    // (non-terminal) task, and parked tasks that are no longer live. `live` counts live
+    // tasks per mission. Returns the freed missions, the dropped parked task IDs, and tasks
+    // released into the freed slots.
+    pub(crate) fn reconcile(&mut self, live: &HashMap<String, usize>, is_live: impl Fn(u32) -> bool) -> (Vec<String>, Vec<u32>, Vec<Task>) {
+        let mut orphaned = Vec::new();
+        let mut namespaces = Vec::new();
+        for (namespace, active) in self.active.iter_mut() {
+            active.retain(|mission| {
+                let open = live.get(mission).copied().unwrap_or(0);
+                if open == 0 {
+                    orphaned.push(mission.clone());
+                    return false;
+                }
+                // Only shrink: tasks counted here may not have reached the limiter yet
+                if let Some(count) = self.open_tasks.get_mut(mission) {
+                    *count = (*count).min(open);
+                }
+                true
+            });
+            namespaces.push(namespace.clone());
+        }
+        for mission in &orphaned {
+            self.open_tasks.remove(mission);
+        }
+        let mut dropped = Vec::new();
+        for tasks in self.parked.values_mut() {
+            tasks.retain(|t| is_live(t.id) || {
+                dropped.push(t.id);
+                false
+            });
+        }
+        let released = namespaces.iter().flat_map(|namespace| self.release(namespace)).collect();
+        (orphaned, dropped, released)
+    }
+
+    // Change the cap for a namespace (None = fall back to the default); returns released tasks
+    pub(crate) fn set_limit(&mut self, namespace: &str, limit: Option<usize>) -> Vec<Task> {
+        match limit {
+            Some(limit) => self.limits.insert(namespace.to_string(), limit),
+            None => self.limits.remove(namespace),
+        };
+        self.release(namespace)
+    }
+
+    // Missions currently holding a slot in a namespace
+    pub(crate) fn active_missions(&self, namespace: &str) -> Vec<String> {
+        let mut missions: Vec<String> = self.active.get(namespace).map(|a| a.iter().cloned().collect()).unwrap_or_default();
+        missions.sort();
+        missions
+    }
+
+    // Missions waiting for a slot in a namespace, in release order
+    pub(crate) fn queued_missions(&self, namespace: &str) -> Vec<String> {
+        self.waiting.get(namespace).map(|w| w.iter().cloned().collect()).unwrap_or_default()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::Scheduler;
+    use crate::store::{MemoryStore, TaskStore};
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    fn mission_task(id: u32, mission: &str) -> Task {
+        Task { id, mission_id: Some(mission.to_string()), namespace: Some("pilot".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_cap_parks_and_releases_missions() {
+        let mut limiter = MissionLimiter::new(HashMap::from([("pilot".to_string(), 1)]), None);
+        assert_eq!(limiter.admit(&mission_task(1, "m1")), Admission::Dispatch);
+        assert_eq!(limiter.admit(&mission_task(2, "m1")), Admission::Dispatch);
+        assert_eq!(limiter.admit(&mission_task(3, "m2")), Admission::Parked);
+        assert_eq!(limiter.admit(&mission_task(4, "m2")), Admission::Parked);
+        assert_eq!(limiter.queued_missions("pilot"), vec!["m2".to_string()]);
+
+        assert!(limiter.task_finished(&mission_task(1, "m1")).is_empty());
+        let released: Vec<u32> = limiter.task_finished(&mission_task(2, "m1")).iter().map(|t| t.id).collect();
+        assert_eq!(released, vec![3, 4]);
+        assert_eq!(limiter.active_missions("pilot"), vec!["m2".to_string()]);
+    }
+
+    #[test]
+    fn test_raising_limit_releases_waiting() {
+        let mut limiter = MissionLimiter::new(HashMap::new(), Some(1));
+        limiter.admit(&mission_task(1, "m1"));
+        assert_eq!(limiter.admit(&mission_task(2, "m2")), Admission::Parked);
+        let released = limiter.set_limit("pilot", Some(2));
+        assert_eq!(released.len(), 1);
+        assert_eq!(limiter.admit(&Task { id: 9, ..Default::default() }), Admission::Dispatch);
+    }
+
+    #[tokio::test]
+    async fn test_mission_status_and_atomic_cancel() {
+        let store = Arc::new(MemoryStore::new());
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().store(store.clone()).transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::ZERO), FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        let mission = Mission {
+            id: "floor-3".to_string(),
+            name: Some("Inspect building floor 3".to_string()),
+            metadata: BTreeMap::from([("building".to_string(), "B".to_string())]),
+            ..Default::default()
+        };
+        scheduler.create_mission(mission.clone()).unwrap();
+        assert!(scheduler.create_mission(mission.clone()).unwrap_err().contains("already exists"));
+        assert_eq!(scheduler.get_mission_status("floor-3").await.unwrap().state, MissionState::Pending);
+        assert!(scheduler.get_mission_status("floor-4").await.is_none());
+
+        let task = |id: u32| Task { id, mission_id: Some("floor-3".to_string()), robot_id: Some("Ford".to_string()), ..Default::default() };
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        scheduler.schedule_task(task(329)).await.unwrap();
+        wait_for_state(329, TaskState::Completed).await;
+        scheduler.schedule_task(task(330)).await.unwrap();
+        wait_for_state(330, TaskState::Running).await;
+        scheduler.schedule_task(task(331)).await.unwrap();
+        scheduler.schedule_task(task(332)).await.unwrap();
+        while scheduler.robot_queue_depths().get("Ford") != Some(&2) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let status = scheduler.get_mission_status("floor-3").await.unwrap();
+        assert_eq!((status.state, status.total, status.completed, status.active, status.pending), (MissionState::Active, 4, 1, 1, 2));
+        assert_eq!(status.percent_complete, 25.0);
+        assert_eq!(status.mission.metadata["building"], "B");
+
+        // Queued tasks are withdrawn at once, the running one stops when its robot reports
+        let status = scheduler.cancel_mission("floor-3").await.unwrap();
+        assert_eq!((status.state, status.cancelled, status.active), (MissionState::Active, 2, 1));
+        assert!(scheduler.schedule_task(task(333)).await.unwrap_err().contains("was cancelled"));
+        scheduler.report_result(330, Ok(())).await;
+        wait_for_state(330, TaskState::Cancelled).await;
+        let status = scheduler.get_mission_status("floor-3").await.unwrap();
+        assert_eq!((status.state, status.percent_complete), (MissionState::Cancelled, 100.0));
+        assert!(store.load_missions().unwrap()[0].cancelled_at.is_some());
+        assert!(scheduler.cancel_mission("floor-4").await.unwrap_err().contains("Unknown mission"));
+    }
+}
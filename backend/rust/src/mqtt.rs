@@ -0,0 +1,206 @@
+// backend/rust/src/mqtt.rs
+// Purpose: MQTT bridge between the MRTODP scheduler and robots in the field (`mqtt`
+// feature). `MqttTransport` is a `RobotTransport` that publishes JSON messages per robot:
+//
+//   mrtodp/robots/{id}/tasks     task assignments, stamped with their dispatch sequence
+//   mrtodp/robots/{id}/control   control envelopes (abort, hold, resume, calls for bids)
+//   mrtodp/robots/{id}/status    result reports from the robot (`RobotReport` JSON)
+//
+// `MqttBridge` drives the client connection: it subscribes to every robot's status topic
+// and feeds reports into the scheduler's state machine, ignoring reports for tasks the
+// sending robot doesn't hold. Publishing succeeds once the broker link has queued the
+// message; the robot's status report is what completes or fails the task. With the `tls`
+// feature, setting `MqttConfig::tls` connects to the broker over TLS, presenting a client
+// certificate if the broker requires mutual TLS (see tls.rs).
+
+use std::time::Duration;
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+#[cfg(feature = "tls")]
+use crate::tls::ClientTls;
+use crate::transport::{ControlEnvelope, DispatchSeq, RobotReport, RobotTransport};
+use crate::BoxFuture;
+
+// Requests buffered between the transport and the bridge's event loop
+const REQUEST_CAPACITY: usize = 256;
+
+// Broker connection and topic layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub topic_prefix: String,        // Robot topics live under "{topic_prefix}/{robot_id}/"
+    pub keep_alive: Duration,
+    pub reconnect_backoff: Duration, // Pause after a broker connection error
+    #[cfg(feature = "tls")]
+    pub tls: Option<ClientTls>,      // None = cleartext broker link
+}
+
+impl MqttConfig {
+    pub fn new(host: &str, port: u16) -> Self {
+        MqttConfig {
+            host: host.to_string(),
+            port,
+            client_id: "mrtodp-scheduler".to_string(),
+            topic_prefix: "mrtodp/robots".to_string(),
+            keep_alive: Duration::from_secs(30),
+            reconnect_backoff: Duration::from_secs(1),
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+// Payload published on a robot's tasks topic
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttAssignment {
+    pub seq: DispatchSeq,
+    pub task: Task,
+}
+
+// RobotTransport that publishes deliveries to per-robot MQTT topics
+pub struct MqttTransport {
+    client: AsyncClient,
+    topic_prefix: String,
+}
+
+impl MqttTransport {
+    // Create the transport and the bridge that must be run to connect it; pass the
+    // transport to `SchedulerBuilder::transport` and run the bridge with the built scheduler
+    pub fn new(config: MqttConfig) -> (std::sync::Arc<Self>, MqttBridge) {
+        let mut options = MqttOptions::new(config.client_id.clone(), config.host.clone(), config.port);
+        options.set_keep_alive(config.keep_alive);
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &config.tls {
+            options.set_transport(tls.mqtt_transport());
+        }
+        let (client, events) = AsyncClient::new(options, REQUEST_CAPACITY);
+        let transport = MqttTransport { client: client.clone(), topic_prefix: config.topic_prefix.clone() };
+        let bridge = MqttBridge { client, events, topic_prefix: config.topic_prefix, reconnect_backoff: config.reconnect_backoff };
+        (std::sync::Arc::new(transport), bridge)
+    }
+
+    async fn publish(&self, robot_id: &str, channel: &str, payload: Vec<u8>) -> Result<(), String> {
+        let topic = format!("{}/{}/{}", self.topic_prefix, robot_id, channel);
+        self.client
+            .publish(topic.clone(), QoS::AtLeastOnce, false, payload)
+            .await
+            .map_err(|e| format!("MQTT publish to {} failed: {}", topic, e))
+    }
+}
+
+impl RobotTransport for MqttTransport {
+    fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&MqttAssignment { seq, task: task.clone() })
+                .map_err(|e| format!("Failed to encode assignment: {}", e))?;
+            self.publish(robot_id, "tasks", payload).await
+        })
+    }
+
+    fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(envelope).map_err(|e| format!("Failed to encode control command: {}", e))?;
+            self.publish(&envelope.robot_id, "control", payload).await
+        })
+    }
+}
+
+// Event loop of the broker connection; feeds robot status reports into the scheduler
+pub struct MqttBridge {
+    client: AsyncClient,
+    events: EventLoop,
+    topic_prefix: String,
+    reconnect_backoff: Duration,
+}
+
+impl MqttBridge {
+    // Run the connection for the lifetime of the scheduler, reconnecting after errors
+    pub async fn run(mut self, scheduler: Scheduler) {
+        loop {
+            match self.events.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    // Subscriptions don't survive a reconnect with a clean session
+                    let topic = format!("{}/+/status", self.topic_prefix);
+                    if let Err(e) = self.client.try_subscribe(topic, QoS::AtLeastOnce) {
+                        tracing::warn!(error = %e, "MQTT status subscription failed");
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    if let Err(e) = self.ingest(&scheduler, &publish.topic, &publish.payload).await {
+                        tracing::warn!(topic = %publish.topic, error = %e, "rejected MQTT status report");
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "MQTT connection error; reconnecting");
+                    tokio::time::sleep(self.reconnect_backoff).await;
+                }
+            }
+        }
+    }
+
+    // Robot a status topic belongs to
+    fn status_robot<'t>(&self, topic: &'t str) -> Option<&'t str> {
+        let robot_id = topic.strip_prefix(&self.topic_prefix)?.strip_prefix('/')?.strip_suffix("/status")?;
+        (!robot_id.is_empty() && !robot_id.contains('/')).then_some(robot_id)
+    }
+
+    // Apply one status report, if it comes from the robot holding the task
+    async fn ingest(&self, scheduler: &Scheduler, topic: &str, payload: &[u8]) -> Result<(), String> {
+        let robot_id = self.status_robot(topic).ok_or_else(|| format!("Not a status topic: {}", topic))?;
+        let raw = std::str::from_utf8(payload).map_err(|_| "Status report is not UTF-8".to_string())?;
+        let report: RobotReport = serde_json::from_str(raw).map_err(|e| format!("Malformed robot report: {}", e))?;
+        let holder = scheduler.task_record(report.task_id).await.and_then(|r| r.attempts.last().and_then(|a| a.robot_id.clone()));
+        if holder.as_deref() != Some(robot_id) {
+            return Err(format!("Robot {} reported task {} it doesn't hold", robot_id, report.task_id));
+        }
+        scheduler.ingest_report(raw).await
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::TaskState;
+
+    #[tokio::test]
+    async fn test_status_reports_complete_tasks_of_their_robot() {
+        // Publishes are buffered for the event loop, so no broker is needed to dispatch
+        let (transport, bridge) = MqttTransport::new(MqttConfig::new("localhost", 1883));
+        let (scheduler, workers) = Scheduler::builder().transport(transport).build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 251, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while events.recv().await.unwrap().transition.to != TaskState::Assigned {}
+
+        assert_eq!(bridge.status_robot("mrtodp/robots/Ford/status"), Some("Ford"));
+        assert_eq!(bridge.status_robot("mrtodp/robots/Ford/tasks"), None);
+        assert_eq!(bridge.status_robot("mrtodp/robots/a/b/status"), None);
+
+        let report = br#"{"task_id": 251, "status": "completed"}"#;
+        let err = bridge.ingest(&scheduler, "mrtodp/robots/Hank/status", report).await.unwrap_err();
+        assert!(err.contains("doesn't hold"), "{}", err);
+        assert!(bridge.ingest(&scheduler, "mrtodp/robots/Ford/status", b"{").await.is_err());
+        bridge.ingest(&scheduler, "mrtodp/robots/Ford/status", report).await.unwrap();
+        while !events.recv().await.unwrap().transition.to.is_terminal() {}
+        assert_eq!(scheduler.task_record(251).await.unwrap().state, TaskState::Completed);
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_broker_link_uses_tls_when_configured() {
+        let pki = crate::tls::test_pki();
+        let (cert, key) = pki.client;
+        let config = MqttConfig { tls: Some(ClientTls::new(pki.ca).identity(cert, key)), ..MqttConfig::new("broker.plant.local", 8883) };
+        let (_transport, bridge) = MqttTransport::new(config);
+        assert!(matches!(bridge.events.mqtt_options.transport(), rumqttc::Transport::Tls(rumqttc::TlsConfiguration::Simple { client_auth: Some(_), .. })));
+        let (_transport, bridge) = MqttTransport::new(MqttConfig::new("localhost", 1883));
+        assert!(matches!(bridge.events.mqtt_options.transport(), rumqttc::Transport::Tcp));
+    }
+}
@@ -0,0 +1,128 @@
+// backend/rust/src/mutex_groups.rs
+// Purpose: Inter-task mutual exclusion for MRTODP. A task may join a named mutex group
+// (e.g. "crane_airspace"); at most one task per group is assigned or running fleet-wide at
+// any time. The executor acquires the group before dispatching a member task; tasks that
+// find it taken wait in a per-group FIFO, and when the holder finishes the group is handed
+// straight to the longest-waiting task, so later arrivals can't jump the queue.
+
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// Current holder and waiters of one mutex group
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MutexGroupStatus {
+    pub group: String,
+    pub holder: Option<u32>, // Task that may run in the group, if any
+    pub waiting: Vec<u32>,   // Tasks queued for the group, in hand-off order
+}
+
+// Holders and FIFO waiters of every mutex group in use
+#[derive(Default)]
+pub(crate) struct MutexGroups {
+    holders: HashMap<String, u32>,            // group -> task holding it
+    waiters: HashMap<String, VecDeque<Task>>, // group -> tasks waiting, oldest first
+}
+
+impl MutexGroups {
+    // Take the task's group, or queue the task behind the holder. Returns the task back if
+    // it may be dispatched: it has no group, the group was free, or it was handed the group.
+    pub(crate) fn acquire(&mut self, task: Task) -> Option<Task> {
+        let Some(group) = &task.mutex_group else {
+            return Some(task);
+        };
+        match self.holders.get(group) {
+            Some(&holder) if holder != task.id => {
+                self.waiters.entry(group.clone()).or_default().push_back(task);
+                None
+            }
+            Some(_) => Some(task),
+            None => {
+                self.holders.insert(group.clone(), task.id);
+                Some(task)
+            }
+        }
+    }
+
+    // Free a group if `task_id` holds it, handing it to the next waiter; returns that waiter
+    // for dispatch
+    pub(crate) fn release(&mut self, group: &str, task_id: u32) -> Option<Task> {
+        if self.holders.get(group) != Some(&task_id) {
+            return None;
+        }
+        self.holders.remove(group);
+        let queue = self.waiters.get_mut(group)?;
+        let next = queue.pop_front();
+        if queue.is_empty() {
+            self.waiters.remove(group);
+        }
+        let next = next?;
+        self.holders.insert(group.to_string(), next.id);
+        Some(next)
+    }
+
+    // Drop a waiting task, e.g. because it was cancelled
+    pub(crate) fn withdraw(&mut self, task_id: u32) {
+        for queue in self.waiters.values_mut() {
+            queue.retain(|t| t.id != task_id);
+        }
+        self.waiters.retain(|_, queue| !queue.is_empty());
+    }
+
+    // Drop waiters and free groups whose tasks are no longer live; returns the freed groups
+    // and dropped waiters, plus the tasks the freed groups were handed to
+    pub(crate) fn reconcile(&mut self, is_live: impl Fn(u32) -> bool) -> (Vec<String>, Vec<u32>, Vec<Task>) {
+        let mut dropped = Vec::new();
+        for queue in self.waiters.values_mut() {
+            queue.retain(|t| is_live(t.id) || {
+                dropped.push(t.id);
+                false
+            });
+        }
+        self.waiters.retain(|_, queue| !queue.is_empty());
+        let mut orphaned: Vec<(String, u32)> =
+            self.holders.iter().filter(|(_, &id)| !is_live(id)).map(|(g, &id)| (g.clone(), id)).collect();
+        orphaned.sort();
+        let released = orphaned.iter().filter_map(|(group, id)| self.release(group, *id)).collect();
+        (orphaned.into_iter().map(|(group, _)| group).collect(), dropped, released)
+    }
+
+    pub(crate) fn status(&self, group: &str) -> MutexGroupStatus {
+        MutexGroupStatus {
+            group: group.to_string(),
+            holder: self.holders.get(group).copied(),
+            waiting: self.waiters.get(group).map(|q| q.iter().map(|t| t.id).collect()).unwrap_or_default(),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(id: u32) -> Task {
+        Task { id, mutex_group: Some("crane_airspace".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn test_group_handed_to_waiters_in_arrival_order() {
+        let mut groups = MutexGroups::default();
+        assert!(groups.acquire(Task { id: 1, ..Default::default() }).is_some());
+        assert!(groups.acquire(member(2)).is_some());
+        assert!(groups.acquire(member(3)).is_none());
+        assert!(groups.acquire(member(4)).is_none());
+        assert_eq!(groups.status("crane_airspace").waiting, vec![3, 4]);
+
+        // Only the holder can release, and the group goes to the oldest waiter
+        assert!(groups.release("crane_airspace", 3).is_none());
+        assert_eq!(groups.release("crane_airspace", 2).unwrap().id, 3);
+        assert_eq!(groups.status("crane_airspace").holder, Some(3));
+        // The handed-off task passes when it comes back through the executor
+        assert!(groups.acquire(member(3)).is_some());
+
+        groups.withdraw(4);
+        assert!(groups.release("crane_airspace", 3).is_none());
+        assert_eq!(groups.status("crane_airspace"), MutexGroupStatus { group: "crane_airspace".to_string(), ..Default::default() });
+    }
+}
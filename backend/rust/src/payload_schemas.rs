@@ -0,0 +1,94 @@
+// backend/rust/src/payload_schemas.rs
+// Purpose: Typed task payloads for MRTODP. A task may carry a free-form JSON `payload` with
+// the parameters its robot needs (a weld's seam and torque, a pick's SKU); registering a
+// JSON Schema for a task type makes the scheduler check the payload of every submission of
+// that type against it. A submission whose payload doesn't match (or that has no payload,
+// which is checked as `null`) is rejected with every violation, each located by its JSON
+// pointer into the payload. Schemas are compiled when registered, so a malformed schema is
+// refused up front; task types without a schema accept any payload.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use jsonschema::Validator;
+use serde_json::Value;
+use crate::task::Task;
+
+// A registered schema and its compiled form
+struct PayloadSchema {
+    schema: Value,
+    validator: Arc<Validator>,
+}
+
+// Payload schemas by task type
+#[derive(Default)]
+pub(crate) struct PayloadSchemaRegistry {
+    schemas: RwLock<HashMap<String, PayloadSchema>>,
+}
+
+impl PayloadSchemaRegistry {
+    // Compile `schema` and register it for `task_type`, replacing any earlier one
+    pub(crate) fn register(&self, task_type: &str, schema: Value) -> Result<(), String> {
+        if task_type.is_empty() {
+            return Err("Payload schema needs a task type".to_string());
+        }
+        let validator = jsonschema::validator_for(&schema).map_err(|e| format!("Invalid payload schema for {}: {}", task_type, e))?;
+        let entry = PayloadSchema { schema, validator: Arc::new(validator) };
+        self.schemas.write().unwrap_or_else(|e| e.into_inner()).insert(task_type.to_string(), entry);
+        Ok(())
+    }
+
+    pub(crate) fn remove(&self, task_type: &str) -> bool {
+        self.schemas.write().unwrap_or_else(|e| e.into_inner()).remove(task_type).is_some()
+    }
+
+    pub(crate) fn get(&self, task_type: &str) -> Option<Value> {
+        self.schemas.read().unwrap_or_else(|e| e.into_inner()).get(task_type).map(|entry| entry.schema.clone())
+    }
+
+    // Check a task's payload against the schema for its type, listing every violation
+    pub(crate) fn check(&self, task: &Task) -> Result<(), String> {
+        let Some(validator) = self.schemas.read().unwrap_or_else(|e| e.into_inner()).get(&task.task_type).map(|entry| entry.validator.clone()) else {
+            return Ok(());
+        };
+        let payload = task.payload.as_ref().unwrap_or(&Value::Null);
+        let violations: Vec<String> = validator.iter_errors(payload).map(|e| format!("payload{}: {}", e.instance_path, e)).collect();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(format!("Task {} payload does not match the {} schema: {}", task.id, task.task_type, violations.join("; ")))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::scheduler::Scheduler;
+
+    #[tokio::test]
+    async fn test_payload_checked_against_task_type_schema() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        assert!(scheduler.register_payload_schema("weld", json!({"type": 7})).unwrap_err().contains("Invalid payload schema"));
+        let schema = json!({
+            "type": "object",
+            "properties": {"seam": {"type": "string"}, "torque": {"type": "number", "maximum": 50}},
+            "required": ["seam", "torque"],
+        });
+        scheduler.register_payload_schema("weld", schema.clone()).unwrap();
+        assert_eq!(scheduler.payload_schema("weld"), Some(schema));
+
+        let weld = |id, payload| Task { id, task_type: "weld".to_string(), payload, ..Default::default() };
+        let err = scheduler.schedule_task(weld(344, Some(json!({"seam": 3, "torque": 80})))).await.unwrap_err();
+        assert!(err.starts_with("Task 344 payload does not match the weld schema"), "{}", err);
+        assert!(err.contains("payload/seam: 3 is not of type \"string\""), "{}", err);
+        assert!(err.contains("payload/torque: 80 is greater than the maximum of 50"), "{}", err);
+        assert!(scheduler.schedule_task(weld(345, None)).await.unwrap_err().contains("payload: null is not of type \"object\""));
+        scheduler.schedule_task(weld(346, Some(json!({"seam": "A1", "torque": 40})))).await.unwrap();
+        scheduler.schedule_task(Task { id: 347, task_type: "inspect".to_string(), payload: Some(json!("anything")), ..Default::default() }).await.unwrap();
+
+        assert!(scheduler.remove_payload_schema("weld"));
+        scheduler.schedule_task(weld(348, None)).await.unwrap();
+    }
+}
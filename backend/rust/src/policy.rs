@@ -0,0 +1,218 @@
+// backend/rust/src/policy.rs
+// Purpose: Pluggable dispatch ordering for MRTODP. The execution loop drains its lanes
+// into ready queues and always dispatches the task the active `SchedulingPolicy` ranks
+// first, breaking ties by arrival. `PriorityFirst` (the default) and
+// `EarliestDeadlineFirst` ship built in; the policy is chosen on the builder and can be
+// swapped at runtime by name. Priority classes and aging (see priority_classes.rs) and
+// fair sharing between namespaces (see fair_queuing.rs) apply on top of whichever policy is
+// active.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+use crate::fair_queuing::FairClock;
+use crate::missions::namespace_of;
+use crate::priority_classes::{PriorityClass, QueueOrdering, QuotaRound};
+use crate::task::Task;
+
+// Ranks queued tasks for dispatch
+pub trait SchedulingPolicy: Send + Sync {
+    // Stable name used to select the policy at runtime
+    fn name(&self) -> &'static str;
+    // Less means `a` is dispatched before `b`; Equal falls back to arrival order
+    fn compare(&self, a: &Task, b: &Task) -> Ordering;
+}
+
+// Tasks without a deadline sort after every task that has one
+fn by_deadline(a: &Task, b: &Task) -> Ordering {
+    a.deadline.unwrap_or(u64::MAX).cmp(&b.deadline.unwrap_or(u64::MAX))
+}
+
+// Highest priority first; earliest deadline among equal priorities
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PriorityFirst;
+
+impl SchedulingPolicy for PriorityFirst {
+    fn name(&self) -> &'static str {
+        "priority_first"
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        b.priority.cmp(&a.priority).then_with(|| by_deadline(a, b))
+    }
+}
+
+// Earliest deadline first; highest priority among equal deadlines
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EarliestDeadlineFirst;
+
+impl SchedulingPolicy for EarliestDeadlineFirst {
+    fn name(&self) -> &'static str {
+        "earliest_deadline_first"
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        by_deadline(a, b).then_with(|| b.priority.cmp(&a.priority))
+    }
+}
+
+// Look up a built-in policy by its name
+pub fn policy_by_name(name: &str) -> Result<Arc<dyn SchedulingPolicy>, String> {
+    match name {
+        "priority_first" => Ok(Arc::new(PriorityFirst)),
+        "earliest_deadline_first" => Ok(Arc::new(EarliestDeadlineFirst)),
+        other => Err(format!("Unknown scheduling policy: {}", other)),
+    }
+}
+
+// Ranks tasks of different priority classes by class, then by the policy
+pub(crate) fn rank(policy: &dyn SchedulingPolicy, a: &Task, b: &Task) -> Ordering {
+    PriorityClass::of(a).cmp(&PriorityClass::of(b)).then_with(|| policy.compare(a, b))
+}
+
+// A queued task, its arrival, and its own priority while aging raises the queued copy's
+struct Queued {
+    seq: u64,
+    queued_at: u64, // Unix milliseconds
+    priority: u32,
+    task: Task,
+}
+
+// Tasks taken off a lane and waiting for the execution loop, in arrival order
+#[derive(Default)]
+pub(crate) struct ReadyQueue {
+    next_seq: u64,
+    tasks: Vec<Queued>,
+    ordering: QueueOrdering,
+    round: QuotaRound,
+    fair: FairClock,
+}
+
+impl ReadyQueue {
+    pub(crate) fn new(ordering: QueueOrdering) -> Self {
+        ReadyQueue { ordering, ..Self::default() }
+    }
+
+    pub(crate) fn push(&mut self, task: Task, now: u64) {
+        let priority = task.priority;
+        self.tasks.push(Queued { seq: self.next_seq, queued_at: now, priority, task });
+        self.next_seq += 1;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    // Raise each queued task's effective priority for the time it has waited
+    pub(crate) fn age(&mut self, now: u64) {
+        let Some(aging) = self.ordering.aging else {
+            return;
+        };
+        for queued in self.tasks.iter_mut() {
+            queued.task.priority = queued.priority.saturating_add(aging.boost(now.saturating_sub(queued.queued_at)));
+        }
+    }
+
+    // The policy's pick within the class, and then the namespace, whose turn it is
+    fn best(&self, policy: &dyn SchedulingPolicy) -> Option<usize> {
+        let turn = self.round.turn(self.ordering.quotas.as_ref(), self.tasks.iter().map(|q| PriorityClass::of(&q.task)))?;
+        let in_turn = |q: &&Queued| PriorityClass::of(&q.task) == turn;
+        let namespace = match &self.ordering.fair_share {
+            Some(_) => self.fair.turn(self.tasks.iter().filter(in_turn).map(|q| &q.task)),
+            None => None,
+        };
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| in_turn(q) && namespace.is_none_or(|ns| namespace_of(&q.task) == ns))
+            .min_by(|(_, a), (_, b)| policy.compare(&a.task, &b.task).then_with(|| a.seq.cmp(&b.seq)))
+            .map(|(index, _)| index)
+    }
+
+    // Take a task out with its own priority restored
+    fn take(&mut self, index: usize) -> Task {
+        let mut queued = self.tasks.swap_remove(index);
+        queued.task.priority = queued.priority;
+        queued.task
+    }
+
+    // ID of the task the policy would dispatch next, without removing it
+    pub(crate) fn peek(&mut self, policy: &dyn SchedulingPolicy, now: u64) -> Option<u32> {
+        self.age(now);
+        self.best(policy).map(|index| self.tasks[index].task.id)
+    }
+
+    // Remove the task the policy ranks first; ranking at pop time lets a policy change
+    // apply to tasks already queued
+    pub(crate) fn pop(&mut self, policy: &dyn SchedulingPolicy, now: u64) -> Option<Task> {
+        self.age(now);
+        let best = self.best(policy)?;
+        if let Some(quotas) = &self.ordering.quotas {
+            self.round.record(PriorityClass::of(&self.tasks[best].task), quotas);
+        }
+        if let Some(share) = &self.ordering.fair_share {
+            self.fair.record(namespace_of(&self.tasks[best].task), share);
+        }
+        Some(self.take(best))
+    }
+
+    // The task ranked first among those matching `filter`, by class and policy, as of the
+    // last aging
+    pub(crate) fn peek_where(&self, policy: &dyn SchedulingPolicy, filter: impl Fn(&Task) -> bool) -> Option<&Task> {
+        self.tasks
+            .iter()
+            .filter(|q| filter(&q.task))
+            .min_by(|a, b| rank(policy, &a.task, &b.task).then_with(|| a.seq.cmp(&b.seq)))
+            .map(|q| &q.task)
+    }
+
+    // Queued tasks in the order they would be dispatched, by class and policy
+    pub(crate) fn ordered(&self, policy: &dyn SchedulingPolicy) -> Vec<&Task> {
+        let mut tasks: Vec<&Queued> = self.tasks.iter().collect();
+        tasks.sort_by(|a, b| rank(policy, &a.task, &b.task).then_with(|| a.seq.cmp(&b.seq)));
+        tasks.into_iter().map(|q| &q.task).collect()
+    }
+
+    pub(crate) fn contains(&self, task_id: u32) -> bool {
+        self.tasks.iter().any(|q| q.task.id == task_id)
+    }
+
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&Task) -> bool) {
+        self.tasks.retain(|q| keep(&q.task));
+    }
+
+    pub(crate) fn remove(&mut self, task_id: u32) -> Option<Task> {
+        let index = self.tasks.iter().position(|q| q.task.id == task_id)?;
+        Some(self.take(index))
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue() -> ReadyQueue {
+        let mut queue = ReadyQueue::default();
+        queue.push(Task { id: 1, priority: 1, deadline: Some(500), ..Default::default() }, 0);
+        queue.push(Task { id: 2, priority: 5, deadline: None, ..Default::default() }, 0);
+        queue.push(Task { id: 3, priority: 1, deadline: Some(100), ..Default::default() }, 0);
+        queue.push(Task { id: 4, priority: 5, deadline: None, ..Default::default() }, 0);
+        queue
+    }
+
+    fn drain(queue: &mut ReadyQueue, policy: &dyn SchedulingPolicy) -> Vec<u32> {
+        std::iter::from_fn(|| queue.pop(policy, 0)).map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_priority_first_order() {
+        assert_eq!(drain(&mut queue(), &PriorityFirst), vec![2, 4, 3, 1]);
+    }
+
+    #[test]
+    fn test_earliest_deadline_first_order() {
+        assert_eq!(drain(&mut queue(), &EarliestDeadlineFirst), vec![3, 1, 2, 4]);
+        assert_eq!(policy_by_name("earliest_deadline_first").unwrap().name(), "earliest_deadline_first");
+        assert!(policy_by_name("random").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+// backend/rust/src/preemption.rs
+// Purpose: Task preemption for MRTODP. With preemption enabled on the builder, a task that
+// finds every slot of its robot taken may displace one of the robot's tasks that opted in
+// with `preemptible: true`, provided its priority exceeds that task's by at least the
+// configured threshold. The robot is sent a `Preempt` control command and the displaced task
+// moves to Suspended, handing its slot straight to the task that preempted it. When a slot
+// on that robot frees up again, its suspended tasks resume there, highest priority first and
+// ahead of queued work: the robot is sent `Resume` and the task is Running again. Tasks that
+// did not opt in are never preempted.
+
+use std::collections::HashMap;
+use crate::scheduler::TaskRecord;
+use crate::task::Task;
+
+// Preemption settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreemptionConfig {
+    pub threshold: u32, // Priority margin an arriving task needs over the task it displaces
+}
+
+impl Default for PreemptionConfig {
+    fn default() -> Self {
+        PreemptionConfig { threshold: 5 }
+    }
+}
+
+impl PreemptionConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold == 0 {
+            return Err("Preemption threshold must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+
+    // Task on the robot that `task` may displace: the lowest-priority preemptible one it
+    // outranks by the threshold, ties going to the lowest task ID
+    pub(crate) fn victim(&self, task: &Task, records: &HashMap<u32, TaskRecord>, robot_id: &str) -> Option<u32> {
+        records
+            .values()
+            .filter(|r| r.state.is_active() && r.task.preemptible)
+            .filter(|r| r.attempts.last().and_then(|a| a.robot_id.as_deref()) == Some(robot_id))
+            .filter(|r| task.priority >= r.task.priority.saturating_add(self.threshold))
+            .min_by_key(|r| (r.task.priority, r.task.id))
+            .map(|r| r.task.id)
+    }
+}
+
+// Suspended tasks per robot with their priorities, waiting to resume there
+#[derive(Default)]
+pub(crate) struct Suspensions {
+    by_robot: HashMap<String, Vec<(u32, u32)>>, // robot_id -> (task_id, priority)
+}
+
+impl Suspensions {
+    pub(crate) fn suspend(&mut self, robot_id: &str, task_id: u32, priority: u32) {
+        self.by_robot.entry(robot_id.to_string()).or_default().push((task_id, priority));
+    }
+
+    pub(crate) fn remove(&mut self, task_id: u32) {
+        for tasks in self.by_robot.values_mut() {
+            tasks.retain(|(id, _)| *id != task_id);
+        }
+        self.by_robot.retain(|_, tasks| !tasks.is_empty());
+    }
+
+    // Take the robot's next task to resume: highest priority, then earliest suspended
+    pub(crate) fn take_next(&mut self, robot_id: &str) -> Option<(u32, u32)> {
+        let tasks = self.by_robot.get_mut(robot_id)?;
+        let best = (0..tasks.len()).max_by(|&a, &b| tasks[a].1.cmp(&tasks[b].1).then(b.cmp(&a)))?;
+        let next = tasks.remove(best);
+        if tasks.is_empty() {
+            self.by_robot.remove(robot_id);
+        }
+        Some(next)
+    }
+
+    pub(crate) fn count(&self, robot_id: &str) -> usize {
+        self.by_robot.get(robot_id).map_or(0, Vec::len)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+    use crate::transport::ControlCommand;
+
+    #[tokio::test]
+    async fn test_urgent_task_preempts_and_suspended_task_resumes() {
+        assert!(PreemptionConfig { threshold: 0 }.validate().is_err());
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let config = PreemptionConfig { threshold: 3 };
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).preemption(config).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 3]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        let task = |id: u32, priority: u32, preemptible: bool| Task {
+            id,
+            priority,
+            preemptible,
+            robot_id: Some("Ford".to_string()),
+            ..Default::default()
+        };
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+
+        // 305 opted in; 306 doesn't outrank it by the threshold and waits in the queue
+        scheduler.schedule_task(task(305, 2, true)).await.unwrap();
+        wait_for_state(305, TaskState::Running).await;
+        scheduler.schedule_task(task(306, 4, false)).await.unwrap();
+        while scheduler.robot_queue_depths().is_empty() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        // 307 does, and takes 305's slot
+        scheduler.schedule_task(task(307, 9, false)).await.unwrap();
+        wait_for_state(307, TaskState::Running).await;
+        let suspended = scheduler.task_record(305).await.unwrap();
+        assert_eq!(suspended.state, TaskState::Suspended);
+        assert_eq!(suspended.attempts[0].transitions.last().unwrap().reason, ReasonCode::Preempted);
+        assert_eq!(fake.controls()[0].command, ControlCommand::Preempt { task_id: 305 });
+        // 307 isn't preemptible, so a later urgent task waits
+        scheduler.schedule_task(task(308, 20, false)).await.unwrap();
+        while scheduler.robot_queue_depths().get("Ford") != Some(&2) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // The freed slot resumes 305 ahead of the queued tasks
+        scheduler.report_result(307, Ok(())).await;
+        wait_for_state(305, TaskState::Running).await;
+        while fake.controls().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.controls()[1].command, ControlCommand::Resume { task_id: 305 });
+        assert_eq!(scheduler.task_record(305).await.unwrap().attempts.len(), 1);
+        assert_eq!(scheduler.task_record(306).await.unwrap().state, TaskState::Pending);
+        scheduler.report_result(305, Ok(())).await;
+        wait_for_state(308, TaskState::Running).await;
+        assert_eq!(scheduler.task_record(306).await.unwrap().state, TaskState::Pending);
+    }
+}
@@ -0,0 +1,176 @@
+// backend/rust/src/priority_classes.rs
+// Purpose: Starvation prevention for MRTODP dispatch. Pure priority ordering lets a steady
+// stream of higher-priority work hold a low-priority task back forever, so two mechanisms
+// bound how long queued work can wait. Aging raises a queued task's effective priority by
+// a step for every interval it has waited past a threshold, up to a cap, until it overtakes
+// newer work; the boost only affects ranking, and the task keeps its own priority. Priority
+// classes (critical, high, normal, background) sit above the scheduling policy: a queue
+// dispatches from its highest waiting class, and with per-class dispatch quotas a class
+// that has used its quota for the round yields to lower classes with work waiting, so every
+// waiting class is served each round. Tasks without a class are normal. Both apply to the
+// execution loop's queues and to each robot's backlog.
+
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::fair_queuing::FairShare;
+use crate::task::Task;
+
+// Named dispatch class, highest first
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityClass {
+    Critical,
+    High,
+    #[default]
+    Normal,
+    Background,
+}
+
+impl PriorityClass {
+    pub const ALL: [PriorityClass; 4] = [PriorityClass::Critical, PriorityClass::High, PriorityClass::Normal, PriorityClass::Background];
+
+    pub fn of(task: &Task) -> Self {
+        task.priority_class.unwrap_or_default()
+    }
+}
+
+// Dispatches each class may take per round while lower classes have work waiting
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClassQuotas {
+    pub critical: u32,
+    pub high: u32,
+    pub normal: u32,
+    pub background: u32,
+}
+
+impl Default for ClassQuotas {
+    fn default() -> Self {
+        ClassQuotas { critical: 8, high: 4, normal: 2, background: 1 }
+    }
+}
+
+impl ClassQuotas {
+    pub fn validate(&self) -> Result<(), String> {
+        if PriorityClass::ALL.into_iter().any(|class| self.quota(class) == 0) {
+            return Err("Every priority class needs a dispatch quota of at least one".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn quota(&self, class: PriorityClass) -> u32 {
+        match class {
+            PriorityClass::Critical => self.critical,
+            PriorityClass::High => self.high,
+            PriorityClass::Normal => self.normal,
+            PriorityClass::Background => self.background,
+        }
+    }
+}
+
+// How queued tasks gain effective priority while they wait
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriorityAging {
+    pub after_ms: u64,  // Wait before a task starts aging
+    pub every_ms: u64,  // Wait for each further step
+    pub step: u32,      // Priority gained per step
+    pub max_boost: u32, // Cap on the priority gained
+}
+
+impl PriorityAging {
+    pub fn new(after: Duration, every: Duration, step: u32, max_boost: u32) -> Self {
+        PriorityAging { after_ms: after.as_millis() as u64, every_ms: every.as_millis() as u64, step, max_boost }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.every_ms == 0 {
+            return Err("Priority aging interval must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+
+    // Priority gained by a task that has waited `waited_ms`
+    pub(crate) fn boost(&self, waited_ms: u64) -> u32 {
+        if waited_ms < self.after_ms {
+            return 0;
+        }
+        let steps = u32::try_from((waited_ms - self.after_ms) / self.every_ms + 1).unwrap_or(u32::MAX);
+        steps.saturating_mul(self.step).min(self.max_boost)
+    }
+}
+
+// How a queue ranks and shares dispatches on top of the scheduling policy
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct QueueOrdering {
+    pub(crate) aging: Option<PriorityAging>,       // None = tasks rank by their own priority
+    pub(crate) quotas: Option<ClassQuotas>,        // None = higher classes always go first
+    pub(crate) fair_share: Option<Arc<FairShare>>, // None = namespaces aren't balanced (see fair_queuing.rs)
+}
+
+// Dispatches each class has taken in a queue's current quota round
+#[derive(Default)]
+pub(crate) struct QuotaRound {
+    used: [u32; 4],
+}
+
+impl QuotaRound {
+    // Class to dispatch from next among those `waiting`: the highest with quota left, or
+    // the highest waiting once every waiting class has used its quota
+    pub(crate) fn turn(&self, quotas: Option<&ClassQuotas>, waiting: impl Iterator<Item = PriorityClass>) -> Option<PriorityClass> {
+        let mut present = [false; 4];
+        for class in waiting {
+            present[class as usize] = true;
+        }
+        let mut classes = PriorityClass::ALL.into_iter().filter(|class| present[*class as usize]);
+        let highest = classes.clone().next()?;
+        let Some(quotas) = quotas else {
+            return Some(highest);
+        };
+        Some(classes.find(|class| self.used[*class as usize] < quotas.quota(*class)).unwrap_or(highest))
+    }
+
+    // Count a dispatch from `class`, starting a new round if it was over its quota
+    pub(crate) fn record(&mut self, class: PriorityClass, quotas: &ClassQuotas) {
+        if self.used[class as usize] >= quotas.quota(class) {
+            self.used = [0; 4];
+        }
+        self.used[class as usize] += 1;
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{PriorityFirst, ReadyQueue};
+
+    #[test]
+    fn test_aging_and_class_quotas_prevent_starvation() {
+        let aging = PriorityAging::new(Duration::from_millis(100), Duration::from_millis(50), 2, 6);
+        assert_eq!((aging.boost(99), aging.boost(100), aging.boost(249), aging.boost(10_000)), (0, 2, 6, 6));
+        assert!(PriorityAging { every_ms: 0, ..aging }.validate().is_err());
+        assert!(ClassQuotas { background: 0, ..ClassQuotas::default() }.validate().is_err());
+
+        // A long-waiting low-priority task overtakes newer urgent work, keeping its priority
+        let mut queue = ReadyQueue::new(QueueOrdering { aging: Some(aging), ..Default::default() });
+        queue.push(Task { id: 1, priority: 1, ..Default::default() }, 0);
+        queue.push(Task { id: 2, priority: 5, ..Default::default() }, 1_000);
+        queue.push(Task { id: 3, priority: 8, ..Default::default() }, 1_000);
+        let popped = queue.pop(&PriorityFirst, 1_000).unwrap();
+        assert_eq!((popped.id, popped.priority), (3, 8));
+        let popped = queue.pop(&PriorityFirst, 1_000).unwrap();
+        assert_eq!((popped.id, popped.priority), (1, 1));
+
+        // Critical work gets two dispatches per round, then background gets its one
+        let quotas = ClassQuotas { critical: 2, high: 1, normal: 1, background: 1 };
+        let mut queue = ReadyQueue::new(QueueOrdering { quotas: Some(quotas), ..Default::default() });
+        for id in 10..16 {
+            queue.push(Task { id, priority: 9, priority_class: Some(PriorityClass::Critical), ..Default::default() }, 0);
+        }
+        queue.push(Task { id: 20, priority_class: Some(PriorityClass::Background), ..Default::default() }, 0);
+        queue.push(Task { id: 21, priority_class: Some(PriorityClass::Background), ..Default::default() }, 0);
+        let order: Vec<u32> = std::iter::from_fn(|| queue.pop(&PriorityFirst, 0)).map(|t| t.id).collect();
+        assert_eq!(order, vec![10, 11, 20, 12, 13, 21, 14, 15]);
+        assert_eq!(serde_json::to_value(PriorityClass::Background).unwrap(), "background");
+    }
+}
@@ -0,0 +1,88 @@
+// backend/rust/src/profiles.rs
+// Purpose: Per-robot performance profiles for MRTODP. Each finished task updates its
+// robot's success rate, execution duration per task type, and counts of failure reason
+// codes. Profiles are written through to the `TaskStore` and reloaded at startup, so the
+// cost model and operators keep their history across deployments.
+
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{ReasonCode, TaskState};
+
+// Execution duration statistics for one task type
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DurationStats {
+    pub count: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl DurationStats {
+    pub fn average_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.count).unwrap_or(0)
+    }
+}
+
+// Accumulated outcome history of one robot
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RobotProfile {
+    pub robot_id: String,
+    pub completed: u64,
+    pub failed: u64,
+    #[serde(default)]
+    pub durations: BTreeMap<String, DurationStats>, // task_type -> time from dispatch to finish
+    #[serde(default)]
+    pub errors: BTreeMap<ReasonCode, u64>, // Failure reason code -> occurrences
+}
+
+impl RobotProfile {
+    // Fraction of finished tasks that completed; None before the first outcome
+    pub fn success_rate(&self) -> Option<f64> {
+        let finished = self.completed + self.failed;
+        (finished > 0).then(|| self.completed as f64 / finished as f64)
+    }
+
+    // Fold in a finished task. Expirations and incompatibility failures never reached the
+    // robot, and deregistration failures are an operator's doing; none are counted.
+    pub(crate) fn record(&mut self, task_type: &str, state: TaskState, reason: ReasonCode, duration_ms: Option<u64>) {
+        match state {
+            TaskState::Completed => self.completed += 1,
+            TaskState::Failed if !matches!(reason, ReasonCode::FailedIncompatible | ReasonCode::FailedRobotDeregistered | ReasonCode::FailedZoneViolation) => {
+                self.failed += 1;
+                *self.errors.entry(reason).or_default() += 1;
+            }
+            _ => return,
+        }
+        if let Some(duration_ms) = duration_ms {
+            let stats = self.durations.entry(task_type.to_string()).or_default();
+            stats.count += 1;
+            stats.total_ms += duration_ms;
+            stats.max_ms = stats.max_ms.max(duration_ms);
+        }
+    }
+}
+
+// Profiles of every robot with at least one recorded outcome, keyed by robot ID
+pub(crate) type RobotProfiles = HashMap<String, RobotProfile>;
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_accumulates_outcomes() {
+        let mut profile = RobotProfile { robot_id: "Ford".to_string(), ..Default::default() };
+        assert_eq!(profile.success_rate(), None);
+        profile.record("navigation", TaskState::Completed, ReasonCode::CompletedOk, Some(300));
+        profile.record("navigation", TaskState::Completed, ReasonCode::CompletedOk, Some(500));
+        profile.record("navigation", TaskState::Failed, ReasonCode::FailedRobotError, Some(100));
+        profile.record("navigation", TaskState::Expired, ReasonCode::ExpiredDeadline, None);
+        assert_eq!(profile.success_rate(), Some(2.0 / 3.0));
+        assert_eq!(profile.durations["navigation"].average_ms(), 300);
+        assert_eq!(profile.errors[&ReasonCode::FailedRobotError], 1);
+
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["errors"]["FAILED_ROBOT_ERROR"], 1);
+        assert_eq!(serde_json::from_value::<RobotProfile>(json).unwrap(), profile);
+    }
+}
@@ -0,0 +1,232 @@
+// backend/rust/src/python.rs
+// Purpose: Native `mrtodp_scheduler` Python extension module (`python` feature), an
+// alternative to the C FFI for Python callers. Tasks go in as dicts and records come back
+// as dicts, failures raise exceptions instead of returning "Error: ..." strings, and no
+// returned string ever has to be freed. Each `Scheduler` object owns its own scheduler and
+// Tokio runtime, and releases the GIL while it waits on them. Build with maturin (see
+// pyproject.toml):
+//
+//   import mrtodp_scheduler
+//   scheduler = mrtodp_scheduler.Scheduler()
+//   scheduler.register_robot("Ford", ["lift"])
+//   handle = scheduler.schedule_task({"id": 1, "task_type": "lift", "priority": 5,
+//                                     "required_capabilities": ["lift"]})
+//   handle.wait(timeout=30)["state"]
+//
+// Errors raise `NotFoundError` for unknown tasks and robots, `RejectedError` for requests
+// the scheduler refuses, both subclasses of `SchedulerError`, and `ValueError` for task
+// dicts that don't describe a task.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Serialize;
+use tokio::runtime::Runtime;
+use crate::scheduler::{Scheduler, TaskRecord, TaskState};
+use crate::task::Task;
+
+create_exception!(mrtodp_scheduler, SchedulerError, PyException, "Base class of scheduler errors.");
+create_exception!(mrtodp_scheduler, NotFoundError, SchedulerError, "Unknown task or robot.");
+create_exception!(mrtodp_scheduler, RejectedError, SchedulerError, "Request refused by the scheduler.");
+
+// How often TaskHandle.wait checks the task's state
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn scheduler_error(message: String) -> PyErr {
+    if message.starts_with("Unknown task:") || message.starts_with("Unknown robot:") {
+        NotFoundError::new_err(message)
+    } else {
+        RejectedError::new_err(message)
+    }
+}
+
+// Convert between Python objects and the scheduler's JSON documents through the json module
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    value.py().import("json")?.call_method1("dumps", (value,))?.extract()
+}
+
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| SchedulerError::new_err(format!("JSON serialization failed: {}", e)))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn state_name(state: TaskState) -> String {
+    serde_json::to_value(state).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+// Scheduler and runtime shared by a Scheduler object and the handles it returns
+struct Core {
+    runtime: Runtime,
+    scheduler: Scheduler,
+}
+
+impl Core {
+    // Run a scheduler call to completion with the GIL released
+    fn block_on<F>(&self, py: Python<'_>, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        py.allow_threads(|| self.runtime.block_on(future))
+    }
+
+    fn record(&self, py: Python<'_>, task_id: u32) -> PyResult<TaskRecord> {
+        self.block_on(py, self.scheduler.task_record(task_id)).ok_or_else(|| NotFoundError::new_err(format!("Unknown task: {}", task_id)))
+    }
+}
+
+// A scheduler instance with its own runtime
+#[pyclass(name = "Scheduler", module = "mrtodp_scheduler")]
+struct PyScheduler {
+    core: Arc<Core>,
+}
+
+#[pymethods]
+impl PyScheduler {
+    #[new]
+    #[pyo3(signature = (worker_threads = 2))]
+    fn new(worker_threads: usize) -> PyResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(worker_threads.max(1))
+            .enable_all()
+            .build()
+            .map_err(|e| SchedulerError::new_err(format!("Failed to start runtime: {}", e)))?;
+        let _guard = runtime.enter();
+        let (scheduler, workers) = Scheduler::builder().build().map_err(SchedulerError::new_err)?;
+        workers.spawn();
+        drop(_guard);
+        Ok(PyScheduler { core: Arc::new(Core { runtime, scheduler }) })
+    }
+
+    #[pyo3(signature = (robot_id, capabilities = Vec::new()))]
+    fn register_robot(&self, py: Python<'_>, robot_id: String, capabilities: Vec<String>) -> PyResult<()> {
+        self.core.block_on(py, self.core.scheduler.register_robot(robot_id, capabilities)).map_err(scheduler_error)
+    }
+
+    // Submit a task given as a dict shaped like the FFI's task JSON
+    fn schedule_task(&self, py: Python<'_>, task: &Bound<'_, PyDict>) -> PyResult<TaskHandle> {
+        let task: Task = serde_json::from_str(&to_json(task.as_any())?).map_err(|e| PyValueError::new_err(format!("Invalid task: {}", e)))?;
+        let task_id = task.id;
+        self.core.block_on(py, self.core.scheduler.schedule_task(task)).map_err(scheduler_error)?;
+        Ok(TaskHandle { core: self.core.clone(), task_id })
+    }
+
+    // Task record (state, attempts, and transition reasons) as a dict
+    fn get_status(&self, py: Python<'_>, task_id: u32) -> PyResult<PyObject> {
+        to_python(py, &self.core.record(py, task_id)?)
+    }
+
+    // Handle to a task submitted earlier
+    fn task(&self, py: Python<'_>, task_id: u32) -> PyResult<TaskHandle> {
+        self.core.record(py, task_id)?;
+        Ok(TaskHandle { core: self.core.clone(), task_id })
+    }
+}
+
+// A submitted task
+#[pyclass(module = "mrtodp_scheduler")]
+struct TaskHandle {
+    core: Arc<Core>,
+    #[pyo3(get)]
+    task_id: u32,
+}
+
+#[pymethods]
+impl TaskHandle {
+    // Current task record as a dict
+    fn status(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.core.record(py, self.task_id)?)
+    }
+
+    // Current state name, e.g. "Running"
+    fn state(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(state_name(self.core.record(py, self.task_id)?.state))
+    }
+
+    // Withdraw the task; returns its state after the call ("Cancelled", or its active state
+    // while the robot stops it)
+    fn cancel(&self, py: Python<'_>) -> PyResult<String> {
+        let state = self.core.block_on(py, self.core.scheduler.cancel_task(self.task_id)).map_err(scheduler_error)?;
+        Ok(state_name(state))
+    }
+
+    // Block until the task finishes and return its final record; raises TimeoutError if
+    // `timeout` seconds pass first
+    #[pyo3(signature = (timeout = None))]
+    fn wait(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<PyObject> {
+        let deadline = timeout.map(|seconds| Instant::now() + Duration::from_secs_f64(seconds.max(0.0)));
+        let scheduler = &self.core.scheduler;
+        let task_id = self.task_id;
+        let record = self.core.block_on(py, async move {
+            loop {
+                match scheduler.task_record(task_id).await {
+                    Some(record) if record.state.is_terminal() => return Ok(record),
+                    None => return Err(NotFoundError::new_err(format!("Unknown task: {}", task_id))),
+                    Some(_) if deadline.is_some_and(|d| Instant::now() >= d) => {
+                        return Err(PyTimeoutError::new_err(format!("Task {} did not finish in time", task_id)))
+                    }
+                    Some(_) => tokio::time::sleep(WAIT_POLL_INTERVAL).await,
+                }
+            }
+        })?;
+        to_python(py, &record)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TaskHandle(task_id={})", self.task_id)
+    }
+}
+
+#[pymodule]
+fn mrtodp_scheduler(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PyScheduler>()?;
+    m.add_class::<TaskHandle>()?;
+    m.add("SchedulerError", py.get_type::<SchedulerError>())?;
+    m.add("NotFoundError", py.get_type::<NotFoundError>())?;
+    m.add("RejectedError", py.get_type::<RejectedError>())?;
+    Ok(())
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyModule;
+
+    #[test]
+    fn test_python_module_schedules_and_raises() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "mrtodp_scheduler").unwrap();
+            mrtodp_scheduler(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("m", module).unwrap();
+            let script = cr#"
+s = m.Scheduler()
+s.register_robot("Ford", ["lift"])
+handle = s.schedule_task({"id": 337, "task_type": "lift", "priority": 1, "required_capabilities": ["lift"]})
+assert handle.task_id == 337
+assert handle.wait(timeout=5)["state"] == "Completed"
+assert s.get_status(337)["task"]["task_type"] == "lift"
+for call, error in [
+    (lambda: s.get_status(999), m.NotFoundError),
+    (lambda: s.schedule_task({"id": 338, "robot_id": "Hank", "task_type": "lift", "priority": 1, "required_capabilities": []}), m.NotFoundError),
+    (lambda: s.schedule_task({"id": 339}), ValueError),
+    (lambda: handle.cancel(), m.RejectedError),
+]:
+    try:
+        call()
+        raise AssertionError("expected " + error.__name__)
+    except error:
+        pass
+assert issubclass(m.RejectedError, m.SchedulerError)
+"#;
+            py.run(script, Some(&globals), None).unwrap();
+        });
+    }
+}
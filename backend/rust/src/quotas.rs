@@ -0,0 +1,140 @@
+// backend/rust/src/quotas.rs
+// Purpose: Per-namespace (tenant) daily submission quotas for MRTODP. Each accepted
+// submission counts against its namespace's quota for the current UTC day; once the quota
+// is used up, further submissions are rejected until the day rolls over. Counters are
+// kept in memory and flushed to the `TaskStore` as one compact snapshot at a fixed
+// interval, so a restart loses at most one interval of counts instead of resetting quotas.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+// Prefix of the error returned once a namespace's daily quota is used up
+pub const QUOTA_EXCEEDED_ERROR: &str = "QuotaExceeded";
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+// Submissions counted for one namespace on one day
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuotaCounter {
+    pub day: u64, // Days since the Unix epoch (UTC)
+    pub used: u64,
+}
+
+// Current quota standing of a namespace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct QuotaUsage {
+    pub namespace: String,
+    pub day: u64,
+    pub used: u64,
+    pub limit: Option<u64>, // None = unlimited
+}
+
+// Daily limits and the counters charged against them
+pub(crate) struct QuotaLimiter {
+    limits: HashMap<String, u64>, // namespace -> submissions per day
+    default_limit: Option<u64>,   // Applies to namespaces without an explicit limit
+    counters: HashMap<String, QuotaCounter>,
+    dirty: bool, // Counters changed since the last flush
+}
+
+impl QuotaLimiter {
+    pub(crate) fn new(limits: HashMap<String, u64>, default_limit: Option<u64>, counters: HashMap<String, QuotaCounter>) -> Self {
+        QuotaLimiter { limits, default_limit, counters, dirty: false }
+    }
+
+    // Whether any quota is configured; without one nothing is counted or flushed
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.limits.is_empty() || self.default_limit.is_some()
+    }
+
+    fn limit(&self, namespace: &str) -> Option<u64> {
+        self.limits.get(namespace).copied().or(self.default_limit)
+    }
+
+    // Today's count for a namespace; counters from earlier days read as zero
+    fn used(&self, namespace: &str, day: u64) -> u64 {
+        self.counters.get(namespace).filter(|c| c.day == day).map_or(0, |c| c.used)
+    }
+
+    // Charge submissions (namespace -> count) all at once, or reject them all if any
+    // namespace would go over its quota
+    pub(crate) fn consume(&mut self, requests: &HashMap<&str, u64>, now_ms: u64) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let day = now_ms / DAY_MS;
+        for (namespace, count) in requests {
+            if let Some(limit) = self.limit(namespace) {
+                let used = self.used(namespace, day);
+                if used + count > limit {
+                    return Err(format!(
+                        "{}: namespace {} has used {} of {} daily submissions",
+                        QUOTA_EXCEEDED_ERROR, namespace, used, limit
+                    ));
+                }
+            }
+        }
+        for (namespace, count) in requests {
+            let used = self.used(namespace, day) + count;
+            self.counters.insert(namespace.to_string(), QuotaCounter { day, used });
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    // Give back submissions charged by `consume` that were not accepted after all
+    pub(crate) fn refund(&mut self, requests: &HashMap<&str, u64>, now_ms: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        let day = now_ms / DAY_MS;
+        for (namespace, count) in requests {
+            if let Some(counter) = self.counters.get_mut(*namespace).filter(|c| c.day == day) {
+                counter.used = counter.used.saturating_sub(*count);
+            }
+        }
+        self.dirty = true;
+    }
+
+    pub(crate) fn usage(&self, namespace: &str, now_ms: u64) -> QuotaUsage {
+        let day = now_ms / DAY_MS;
+        QuotaUsage { namespace: namespace.to_string(), day, used: self.used(namespace, day), limit: self.limit(namespace) }
+    }
+
+    // Counters to write if they changed since the last flush
+    pub(crate) fn take_dirty(&mut self) -> Option<HashMap<String, QuotaCounter>> {
+        std::mem::take(&mut self.dirty).then(|| self.counters.clone())
+    }
+
+    // Put back counters whose flush failed so the next flush retries them
+    pub(crate) fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_rejects_over_limit_and_resets_daily() {
+        let limits = HashMap::from([("cell-1".to_string(), 2)]);
+        let mut quotas = QuotaLimiter::new(limits, None, HashMap::new());
+        let one = HashMap::from([("cell-1", 1)]);
+        quotas.consume(&one, 0).unwrap();
+        assert!(quotas.consume(&HashMap::from([("cell-1", 2)]), 0).unwrap_err().starts_with(QUOTA_EXCEEDED_ERROR));
+        quotas.consume(&one, 0).unwrap();
+        assert!(quotas.consume(&one, 0).is_err());
+        assert!(quotas.consume(&HashMap::from([("cell-2", 100)]), 0).is_ok());
+        quotas.refund(&one, 0);
+        assert_eq!(quotas.usage("cell-1", 0).used, 1);
+        quotas.consume(&one, 0).unwrap();
+
+        // A new UTC day starts from zero
+        quotas.consume(&one, DAY_MS).unwrap();
+        assert_eq!(quotas.usage("cell-1", DAY_MS).used, 1);
+        assert!(quotas.take_dirty().is_some());
+        assert!(quotas.take_dirty().is_none());
+    }
+}
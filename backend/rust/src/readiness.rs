@@ -0,0 +1,154 @@
+// backend/rust/src/readiness.rs
+// Purpose: Ready-check protocol for MRTODP robots. When enabled on the builder, a newly
+// registered robot is not eligible for work straight away: the scheduler first delivers a
+// self-test task through the robot transport and only adds the robot to the fleet once
+// the robot reports success within the timeout. Robots that connect but fail or never
+// answer their self-test stay out of assignment and routing.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// Task type of the self-test delivered to newly registered robots
+pub const SELF_TEST_TASK_TYPE: &str = "self_test";
+
+// Ready-check settings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReadyCheck {
+    pub timeout: Duration, // Time a robot has to report its self-test result
+}
+
+impl Default for ReadyCheck {
+    fn default() -> Self {
+        ReadyCheck { timeout: Duration::from_secs(30) }
+    }
+}
+
+// Whether a registered robot may receive work
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RobotReadiness {
+    Verifying,                 // Self-test delivered, awaiting the result
+    Ready,                     // Eligible for assignment
+    Failed { reason: String }, // Self-test failed or timed out; re-register to retry
+}
+
+// Robot waiting on its self-test
+pub(crate) struct PendingCheck {
+    pub(crate) robot_id: String,
+    pub(crate) capabilities: Vec<String>,
+}
+
+// Self-tests in flight and the readiness of robots that took one
+pub(crate) struct ReadyChecks {
+    pub(crate) config: ReadyCheck,
+    next_task_id: u32, // Self-test IDs count down from u32::MAX, away from submitted tasks
+    pending: HashMap<u32, PendingCheck>, // self-test task ID -> robot under test
+    readiness: HashMap<String, RobotReadiness>,
+}
+
+impl ReadyChecks {
+    pub(crate) fn new(config: ReadyCheck) -> Self {
+        ReadyChecks { config, next_task_id: u32::MAX, pending: HashMap::new(), readiness: HashMap::new() }
+    }
+
+    // Register a robot as verifying and build the self-test to deliver to it; `taken`
+    // reports task IDs already used by submitted tasks
+    pub(crate) fn start(&mut self, robot_id: &str, capabilities: Vec<String>, taken: impl Fn(u32) -> bool) -> Task {
+        let mut id = self.next_task_id;
+        while taken(id) || self.pending.contains_key(&id) {
+            id = id.wrapping_sub(1);
+        }
+        self.next_task_id = id.wrapping_sub(1);
+        self.pending.insert(id, PendingCheck { robot_id: robot_id.to_string(), capabilities });
+        self.readiness.insert(robot_id.to_string(), RobotReadiness::Verifying);
+        Task {
+            id,
+            task_type: SELF_TEST_TASK_TYPE.to_string(),
+            robot_id: Some(robot_id.to_string()),
+            timeout_ms: Some(self.config.timeout.as_millis() as u64),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn is_self_test(&self, task_id: u32) -> bool {
+        self.pending.contains_key(&task_id)
+    }
+
+    pub(crate) fn is_verifying(&self, robot_id: &str) -> bool {
+        self.readiness.get(robot_id) == Some(&RobotReadiness::Verifying)
+    }
+
+    // Close a self-test; None if it already finished (e.g. answered before timing out)
+    pub(crate) fn finish(&mut self, task_id: u32, result: &Result<(), String>) -> Option<PendingCheck> {
+        let check = self.pending.remove(&task_id)?;
+        let readiness = match result {
+            Ok(()) => RobotReadiness::Ready,
+            Err(reason) => RobotReadiness::Failed { reason: reason.clone() },
+        };
+        self.readiness.insert(check.robot_id.clone(), readiness);
+        Some(check)
+    }
+
+    // Record a robot that passed its self-test but could not then be admitted
+    pub(crate) fn fail(&mut self, robot_id: &str, reason: String) {
+        self.readiness.insert(robot_id.to_string(), RobotReadiness::Failed { reason });
+    }
+
+    pub(crate) fn readiness(&self, robot_id: &str) -> Option<RobotReadiness> {
+        self.readiness.get(robot_id).cloned()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::scheduler::Scheduler;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    async fn settled(scheduler: &Scheduler, robot_id: &str) -> RobotReadiness {
+        for _ in 0..200 {
+            match scheduler.robot_readiness(robot_id).await {
+                Some(RobotReadiness::Verifying) => tokio::time::sleep(Duration::from_millis(5)).await,
+                Some(readiness) => return readiness,
+                None => panic!("robot {} unknown", robot_id),
+            }
+        }
+        panic!("robot {} still verifying", robot_id);
+    }
+
+    #[tokio::test]
+    async fn test_robots_join_fleet_only_after_passing_self_test() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let check = ReadyCheck { timeout: Duration::from_millis(50) };
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).ready_check(check).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_millis(10))]);
+        fake.script("Scion", vec![FakeBehavior::FailAfter(Duration::ZERO, "arm stalled".to_string())]);
+        fake.script("Hank", vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        for robot_id in ["Ford", "Scion", "Hank"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+
+        // Not eligible while the self-test is outstanding
+        let task = |id, robot: &str| Task { id, robot_id: Some(robot.to_string()), ..Default::default() };
+        let err = scheduler.schedule_task(task(171, "Ford")).await.unwrap_err();
+        assert!(err.contains("has not passed its ready check"), "{}", err);
+
+        assert_eq!(settled(&scheduler, "Ford").await, RobotReadiness::Ready);
+        assert_eq!(settled(&scheduler, "Scion").await, RobotReadiness::Failed { reason: "arm stalled".to_string() });
+        assert!(matches!(settled(&scheduler, "Hank").await, RobotReadiness::Failed { reason } if reason.contains("not answered")));
+        scheduler.schedule_task(task(172, "Ford")).await.unwrap();
+        assert!(scheduler.schedule_task(task(173, "Hank")).await.unwrap_err().contains("failed its ready check"));
+        assert!(fake.assignments().iter().all(|(robot, id)| robot == "Ford" || *id > 1_000_000));
+    }
+
+    #[test]
+    fn test_builder_requires_transport_for_ready_checks() {
+        assert!(Scheduler::builder().ready_check(ReadyCheck::default()).build().is_err());
+    }
+}
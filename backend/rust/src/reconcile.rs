@@ -0,0 +1,132 @@
+// backend/rust/src/reconcile.rs
+// Purpose: Orphan reservation cleanup for MRTODP. Mission slots, held tasks, parked
+// mission tasks, robot slot waiters, mutex groups, and exclusive resources and their
+// waiters are all reserved on behalf of a task and normally freed when that task
+// finishes. If a task disappears or finishes without its reservation being released
+// (e.g. a crash between persisting a cancellation and releasing the slot), the
+// reservation would block its resource forever. The reconciler compares every
+// reservation against the task records, releases the ones whose owner is gone, and logs
+// each cleanup; the workers run it periodically and operators can run it on demand.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::scheduler::Scheduler;
+
+// Reservations released by one reconciler pass
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    pub missions: Vec<String>,  // Mission slots freed because no live task held them
+    pub parked: Vec<u32>,       // Parked mission tasks dropped
+    pub held: Vec<u32>,         // Held tasks dropped
+    pub slot_waiters: Vec<u32>, // Tasks dropped from robot slot queues
+    #[serde(default)]
+    pub mutex_groups: Vec<String>, // Mutex groups freed because their holder was gone
+    #[serde(default)]
+    pub mutex_waiters: Vec<u32>, // Tasks dropped from mutex group queues
+    #[serde(default)]
+    pub resources: Vec<String>, // Exclusive resources freed because their holder was gone
+    #[serde(default)]
+    pub resource_waiters: Vec<u32>, // Tasks dropped from the resource queue
+}
+
+impl ReconcileReport {
+    pub fn is_empty(&self) -> bool {
+        self.missions.is_empty()
+            && self.parked.is_empty()
+            && self.held.is_empty()
+            && self.slot_waiters.is_empty()
+            && self.mutex_groups.is_empty()
+            && self.mutex_waiters.is_empty()
+            && self.resources.is_empty()
+            && self.resource_waiters.is_empty()
+    }
+}
+
+impl Scheduler {
+    // Release reservations whose owning tasks no longer exist or already finished
+    pub async fn reconcile_reservations(&self) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        // Hold the records lock throughout so no task can be submitted or finish mid-pass
+        let records = self.core.records.lock().await;
+        let is_live = |task_id: u32| records.get(&task_id).is_some_and(|r| !r.state.is_terminal());
+        let mut live_per_mission: HashMap<String, usize> = HashMap::new();
+        for record in records.values().filter(|r| !r.state.is_terminal()) {
+            if let Some(mission) = &record.task.mission_id {
+                *live_per_mission.entry(mission.clone()).or_default() += 1;
+            }
+        }
+        let (missions, parked, released) = self.core.missions.lock().await.reconcile(&live_per_mission, is_live);
+        report.missions = missions;
+        report.parked = parked;
+        self.core.held.lock().await.retain(|task_id, _| {
+            is_live(*task_id) || {
+                report.held.push(*task_id);
+                false
+            }
+        });
+        report.slot_waiters = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).retain(|task| is_live(task.id));
+        let (groups, waiters, handed_off) =
+            self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).reconcile(is_live);
+        report.mutex_groups = groups;
+        report.mutex_waiters = waiters;
+        let (resources, waiters, granted) =
+            self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).reconcile(is_live);
+        report.resources = resources;
+        report.resource_waiters = waiters;
+        drop(records);
+        for mission in &report.missions {
+            tracing::warn!(mission = %mission, "reconciler freed a mission slot no live task holds");
+        }
+        for group in &report.mutex_groups {
+            tracing::warn!(group = %group, "reconciler freed a mutex group whose holder is missing or finished");
+        }
+        for resource in &report.resources {
+            tracing::warn!(resource = %resource, "reconciler freed a resource whose holder is missing or finished");
+        }
+        let kinds = [
+            ("parked", &report.parked),
+            ("held", &report.held),
+            ("slot-waiting", &report.slot_waiters),
+            ("mutex-waiting", &report.mutex_waiters),
+            ("resource-waiting", &report.resource_waiters),
+        ];
+        for (kind, ids) in kinds {
+            for task_id in ids {
+                tracing::warn!(task_id, kind, "reconciler dropped a waiting task whose record is missing or finished");
+            }
+        }
+        self.dispatch_released(released);
+        self.dispatch_released(handed_off);
+        self.dispatch_released(granted);
+        report
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use crate::scheduler::{Scheduler, TaskState};
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_reconciler_frees_orphaned_mission_slot() {
+        let (scheduler, _workers) = Scheduler::builder().max_concurrent_missions("pilot", 1).build().unwrap();
+        let task = |id, mission: &str| Task {
+            id,
+            mission_id: Some(mission.to_string()),
+            namespace: Some("pilot".to_string()),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task(191, "m1")).await.unwrap();
+        scheduler.schedule_task(task(192, "m2")).await.unwrap();
+        assert_eq!(scheduler.queued_missions("pilot").await, vec!["m2".to_string()]);
+        assert!(scheduler.reconcile_reservations().await.is_empty());
+
+        // Simulate a crash that finished task 191 without releasing its mission slot
+        scheduler.core.records.lock().await.get_mut(&191).unwrap().state = TaskState::Cancelled;
+        let report = scheduler.reconcile_reservations().await;
+        assert_eq!(report.missions, vec!["m1".to_string()]);
+        assert_eq!(scheduler.active_missions("pilot").await, vec!["m2".to_string()]);
+        assert!(scheduler.queued_missions("pilot").await.is_empty());
+    }
+}
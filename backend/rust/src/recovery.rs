@@ -0,0 +1,76 @@
+// backend/rust/src/recovery.rs
+// Purpose: Startup recovery for MRTODP. The builder reloads every task record from the
+// `TaskStore`, so status queries keep answering after a restart; when the workers start,
+// unfinished tasks are put back in the queue so work accepted before a crash is not lost.
+// Pending tasks are re-admitted as they were. Tasks that were assigned, running, or
+// suspended when the process stopped lost their robot session with it and are re-queued as
+// a new attempt.
+
+use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+
+// Unfinished tasks put back in the queue on startup
+#[derive(Debug, Default)]
+pub(crate) struct RecoveryReport {
+    pub(crate) resumed: Vec<u32>,  // Pending when the scheduler stopped; queued again as they were
+    pub(crate) requeued: Vec<u32>, // Assigned, running, or suspended when it stopped; queued as a new attempt
+}
+
+impl Scheduler {
+    // Queue the unfinished tasks reloaded from the store, in the given order; tasks that
+    // finished since startup (e.g. cancelled by an operator) are skipped
+    pub(crate) async fn recover_tasks(&self, task_ids: Vec<u32>) -> RecoveryReport {
+        let mut report = RecoveryReport::default();
+        for task_id in task_ids {
+            let record = self.core.records.lock().await.get(&task_id).cloned();
+            let Some(record) = record.filter(|r| !r.state.is_terminal()) else {
+                continue;
+            };
+            let result = if record.state == TaskState::Pending {
+                report.resumed.push(task_id);
+                self.admit(record.task).await
+            } else {
+                report.requeued.push(task_id);
+                let detail = format!("Was {:?} when the scheduler stopped", record.state);
+                self.requeue(record, ReasonCode::RecoveredAfterRestart, detail).await
+            };
+            if let Err(e) = result {
+                tracing::warn!(task_id, error = %e, "could not recover task");
+            }
+        }
+        if !report.resumed.is_empty() || !report.requeued.is_empty() {
+            tracing::info!(pending = report.resumed.len(), interrupted = report.requeued.len(), "recovered tasks from the store");
+        }
+        report
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+    use crate::store::{MemoryStore, TaskStore};
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_unfinished_tasks_requeued_after_restart() {
+        let store: Arc<dyn TaskStore> = Arc::new(MemoryStore::new());
+        let (scheduler, _workers) = Scheduler::builder().store(store.clone()).build().unwrap();
+        for id in [201, 202, 203] {
+            scheduler.schedule_task(Task { id, ..Default::default() }).await.unwrap();
+        }
+        scheduler.transition_task(202, TaskState::Running, ReasonCode::Dispatched, String::new()).await.unwrap();
+        scheduler.cancel_task(203).await.unwrap();
+        // The process dies here without the workers ever draining the queue
+        drop(scheduler);
+
+        let (restarted, _workers) = Scheduler::builder().store(store).build().unwrap();
+        assert_eq!(restarted.task_record(203).await.unwrap().state, TaskState::Cancelled);
+        let report = restarted.recover_tasks(vec![201, 202, 203]).await;
+        assert_eq!((report.resumed, report.requeued), (vec![201], vec![202]));
+        let record = restarted.task_record(202).await.unwrap();
+        assert_eq!(record.state, TaskState::Pending);
+        assert_eq!(record.attempts.len(), 2);
+        assert_eq!(record.attempts[1].transitions[0].reason, ReasonCode::RecoveredAfterRestart);
+    }
+}
@@ -0,0 +1,82 @@
+// backend/rust/src/registry.rs
+// Purpose: Registry of independent scheduler instances for multi-tenant deployments and
+// tests that run several isolated schedulers in one process. Each instance is a `Scheduler`
+// built separately (with its own store, policy, and robots) and registered under a name;
+// the front-ends route requests to an instance by that name: the REST API under
+// /instances/{name}/ (see `registry_router` in http.rs) and the gRPC service by
+// `mrtodp-instance` request metadata (see `RegistryService` in grpc.rs). The C FFI keeps its
+// own registry of the instances it created, keyed by their opaque handles (see
+// ffi_instance.rs).
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use crate::scheduler::{Scheduler, CONFLICT_ERROR};
+
+// gRPC metadata key naming the instance a request is for
+pub const INSTANCE_METADATA_KEY: &str = "mrtodp-instance";
+
+// Named scheduler instances; share it behind an Arc
+#[derive(Default)]
+pub struct SchedulerRegistry {
+    instances: RwLock<BTreeMap<String, Scheduler>>,
+}
+
+impl SchedulerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Register `scheduler` under `name`; names are unique
+    pub fn insert(&self, name: &str, scheduler: Scheduler) -> Result<(), String> {
+        if name.is_empty() || name.contains('/') {
+            return Err(format!("Invalid instance name: {:?}", name));
+        }
+        let mut instances = self.instances.write().unwrap_or_else(|e| e.into_inner());
+        if instances.contains_key(name) {
+            return Err(format!("{}: instance {} already registered", CONFLICT_ERROR, name));
+        }
+        instances.insert(name.to_string(), scheduler);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Scheduler> {
+        self.instances.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+    }
+
+    // Stop routing to an instance; requests already running on it finish normally
+    pub fn remove(&self, name: &str) -> Option<Scheduler> {
+        self.instances.write().unwrap_or_else(|e| e.into_inner()).remove(name)
+    }
+
+    // Registered instance names, in order
+    pub fn names(&self) -> Vec<String> {
+        self.instances.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_registry_keeps_instances_isolated() {
+        let registry = SchedulerRegistry::new();
+        for name in ["north", "south"] {
+            let (scheduler, workers) = Scheduler::builder().build().unwrap();
+            workers.spawn();
+            registry.insert(name, scheduler).unwrap();
+        }
+        let (spare, _) = Scheduler::builder().build().unwrap();
+        assert!(registry.insert("north", spare.clone()).unwrap_err().contains("already registered"));
+        assert!(registry.insert("a/b", spare).unwrap_err().contains("Invalid instance name"));
+
+        let north = registry.get("north").unwrap();
+        north.schedule_task(Task { id: 341, ..Default::default() }).await.unwrap();
+        assert!(north.task_record(341).await.is_some());
+        assert!(registry.get("south").unwrap().task_record(341).await.is_none());
+        assert!(registry.remove("north").is_some());
+        assert_eq!(registry.names(), vec!["south".to_string()]);
+    }
+}
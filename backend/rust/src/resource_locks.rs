@@ -0,0 +1,191 @@
+// backend/rust/src/resource_locks.rs
+// Purpose: Exclusive shared resources for MRTODP. A task may declare the resources it needs
+// to itself (e.g. "cell_3_fixture", "corridor_B"); no two tasks holding a common resource
+// are assigned or running at the same time, so two robots are never sent into the same
+// workcell at once. The executor takes all of a task's resources together before
+// dispatching it, after its mutex group (see mutex_groups.rs), so a task never holds some
+// resources while waiting for others and contending tasks can't deadlock. Tasks that find
+// a resource taken wait in one FIFO; when resources are freed, waiters are granted theirs
+// in arrival order, and a waiter is never overtaken by a later task wanting one of the same
+// resources. Served by `Scheduler::resource_status` and the FFI.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// Current holder and waiters of one resource
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceStatus {
+    pub resource: String,
+    pub holder: Option<u32>, // Task that may use the resource, if any
+    pub waiting: Vec<u32>,   // Tasks waiting for it, in grant order
+}
+
+// Holders of every resource in use and the tasks waiting for theirs
+#[derive(Default)]
+pub(crate) struct ResourceLocks {
+    holders: HashMap<String, u32>, // resource -> task holding it
+    waiters: VecDeque<Task>,       // Tasks waiting for their resources, oldest first
+}
+
+impl ResourceLocks {
+    // Take all of the task's resources, or queue the task until they are free. Returns the
+    // task back if it may be dispatched: it needs no resources, they were free, or it was
+    // granted them while waiting.
+    pub(crate) fn acquire(&mut self, task: Task) -> Option<Task> {
+        if task.resources.iter().all(|r| self.holders.get(r) == Some(&task.id)) {
+            return Some(task);
+        }
+        // Resources a waiter wants are kept for it, so later tasks can't overtake it
+        let contended = |r: &String| self.holders.contains_key(r) || self.waiters.iter().any(|t| t.resources.contains(r));
+        if task.resources.iter().any(contended) {
+            if !self.waiters.iter().any(|t| t.id == task.id) {
+                self.waiters.push_back(task);
+            }
+            return None;
+        }
+        self.take(&task);
+        Some(task)
+    }
+
+    fn take(&mut self, task: &Task) {
+        for resource in &task.resources {
+            self.holders.insert(resource.clone(), task.id);
+        }
+    }
+
+    // Free every resource `task_id` holds and grant waiters theirs; returns the granted
+    // waiters for dispatch
+    pub(crate) fn release(&mut self, task_id: u32) -> Vec<Task> {
+        let before = self.holders.len();
+        self.holders.retain(|_, holder| *holder != task_id);
+        if self.holders.len() == before {
+            return Vec::new();
+        }
+        self.grant()
+    }
+
+    // Grant waiters whose resources are all free, oldest first; a waiter that can't go
+    // keeps its resources from later waiters
+    fn grant(&mut self) -> Vec<Task> {
+        let mut wanted = HashSet::new();
+        let mut granted = Vec::new();
+        let mut still_waiting = VecDeque::new();
+        for task in std::mem::take(&mut self.waiters) {
+            let free = task.resources.iter().all(|r| !self.holders.contains_key(r) && !wanted.contains(r));
+            if free {
+                self.take(&task);
+                granted.push(task);
+            } else {
+                wanted.extend(task.resources.iter().cloned());
+                still_waiting.push_back(task);
+            }
+        }
+        self.waiters = still_waiting;
+        granted
+    }
+
+    // Drop a waiting task, e.g. because it was cancelled; returns the waiters it no longer
+    // holds back
+    pub(crate) fn withdraw(&mut self, task_id: u32) -> Vec<Task> {
+        let before = self.waiters.len();
+        self.waiters.retain(|t| t.id != task_id);
+        if self.waiters.len() == before {
+            return Vec::new();
+        }
+        self.grant()
+    }
+
+    // Drop waiters and free resources whose tasks are no longer live; returns the freed
+    // resources and dropped waiters, plus the waiters granted the freed resources
+    pub(crate) fn reconcile(&mut self, is_live: impl Fn(u32) -> bool) -> (Vec<String>, Vec<u32>, Vec<Task>) {
+        let mut dropped = Vec::new();
+        self.waiters.retain(|t| {
+            is_live(t.id) || {
+                dropped.push(t.id);
+                false
+            }
+        });
+        let mut freed: Vec<String> = self.holders.iter().filter(|(_, &id)| !is_live(id)).map(|(r, _)| r.clone()).collect();
+        freed.sort();
+        self.holders.retain(|_, &mut id| is_live(id));
+        let granted = if freed.is_empty() && dropped.is_empty() { Vec::new() } else { self.grant() };
+        (freed, dropped, granted)
+    }
+
+    pub(crate) fn status(&self, resource: &str) -> ResourceStatus {
+        ResourceStatus {
+            resource: resource.to_string(),
+            holder: self.holders.get(resource).copied(),
+            waiting: self.waiters.iter().filter(|t| t.resources.iter().any(|r| r == resource)).map(|t| t.id).collect(),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn needing(id: u32, resources: &[&str]) -> Task {
+        Task { id, resources: resources.iter().map(|r| r.to_string()).collect(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_contending_tasks_are_serialized() {
+        let mut locks = ResourceLocks::default();
+        assert!(locks.acquire(Task { id: 1, ..Default::default() }).is_some());
+        assert!(locks.acquire(needing(2, &["cell_3_fixture", "corridor_B"])).is_some());
+        assert!(locks.acquire(needing(3, &["corridor_B"])).is_none());
+        assert!(locks.acquire(needing(4, &["cell_3_fixture", "cell_4_fixture"])).is_none());
+        // cell_4_fixture is free, but task 4 is waiting for it, so task 5 can't overtake
+        assert!(locks.acquire(needing(5, &["cell_4_fixture"])).is_none());
+        assert!(locks.acquire(needing(6, &["dock"])).is_some());
+        assert_eq!(locks.status("cell_3_fixture"), ResourceStatus { resource: "cell_3_fixture".to_string(), holder: Some(2), waiting: vec![4] });
+
+        // Freeing both resources lets every waiter go that no older waiter holds back
+        assert!(locks.release(3).is_empty());
+        let granted: Vec<u32> = locks.release(2).iter().map(|t| t.id).collect();
+        assert_eq!(granted, vec![3, 4]);
+        assert!(locks.acquire(needing(4, &["cell_3_fixture", "cell_4_fixture"])).is_some());
+        assert_eq!(locks.status("cell_4_fixture").waiting, vec![5]);
+
+        // A cancelled waiter leaves the queue; a missing holder's resources are freed
+        assert!(locks.acquire(needing(7, &["dock", "corridor_B"])).is_none());
+        assert!(locks.acquire(needing(8, &["corridor_B"])).is_none());
+        assert!(locks.withdraw(7).is_empty());
+        let (freed, dropped, granted) = locks.reconcile(|id| id != 3);
+        assert_eq!((freed, dropped, granted.iter().map(|t| t.id).collect::<Vec<_>>()), (vec!["corridor_B".to_string()], vec![], vec![8]));
+    }
+
+    #[tokio::test]
+    async fn test_robots_take_turns_in_a_shared_cell() {
+        use std::sync::Arc;
+        use std::time::Duration;
+        use crate::scheduler::Scheduler;
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let in_cell = |id: u32, robot_id: &str| Task { robot_id: Some(robot_id.to_string()), ..needing(id, &["cell_3_fixture"]) };
+        assert!(scheduler.schedule_task(needing(393, &[""])).await.is_err());
+        scheduler.schedule_task(in_cell(394, "Ford")).await.unwrap();
+        scheduler.schedule_task(in_cell(395, "Scion")).await.unwrap();
+        while scheduler.resource_status("cell_3_fixture").waiting.is_empty() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 394)]);
+
+        // Scion only enters the cell once Ford is done there
+        scheduler.report_result(394, Ok(())).await;
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.resource_status("cell_3_fixture"), ResourceStatus { resource: "cell_3_fixture".to_string(), holder: Some(395), waiting: vec![] });
+    }
+}
@@ -0,0 +1,100 @@
+// backend/rust/src/retention.rs
+// Purpose: Retention of finished task records for MRTODP. Every task record stays in
+// memory and in the store until something removes it, so without a limit a long-running
+// scheduler grows both without bound. The workers periodically drop the records of tasks
+// that finished more than the retention ago (default: 7 days, set with the builder's
+// `task_retention`) from memory and the store, then ask the store to compact, which
+// rewrites a `WalStore` log to one entry per live key. Dead-lettered tasks are kept until
+// purged from the dead-letter queue, and the task history keeps its own copies of
+// recently finished tasks.
+
+use std::time::Duration;
+use crate::scheduler::Scheduler;
+
+// Time a finished task's record is kept by default
+pub const DEFAULT_TASK_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+impl Scheduler {
+    // Drop the records of tasks finished longer than the retention ago, then compact the
+    // store; returns the IDs dropped. A record the store can't remove is kept for the next
+    // pass.
+    pub async fn compact_store(&self) -> Result<Vec<u32>, String> {
+        let now = self.core.clock.now_millis();
+        let retention = self.core.task_retention.as_millis() as u64;
+        let mut dropped = Vec::new();
+        {
+            let mut records = self.core.records.lock().await;
+            let mut expired: Vec<u32> = records
+                .values()
+                .filter(|r| r.state.is_terminal() && r.dead_lettered_at.is_none())
+                .filter(|r| {
+                    let finished = r.attempts.last().and_then(|a| a.transitions.last()).map_or(0, |t| t.at);
+                    now.saturating_sub(finished) >= retention
+                })
+                .map(|r| r.task.id)
+                .collect();
+            expired.sort();
+            for task_id in expired {
+                match self.core.store.remove_task(task_id) {
+                    Ok(()) => {
+                        records.remove(&task_id);
+                        dropped.push(task_id);
+                    }
+                    Err(e) => tracing::warn!(task_id, error = %e, "could not drop a finished task past its retention"),
+                }
+            }
+        }
+        self.core.store.compact()?;
+        Ok(dropped)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::Arc;
+    use crate::scheduler::TaskState;
+    use crate::store::TaskStore;
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+    use crate::wal::WalStore;
+
+    #[tokio::test]
+    async fn test_finished_tasks_dropped_past_retention() {
+        let path = std::env::temp_dir().join(format!("mrtodp-retention-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = Arc::new(WalStore::open(&path).unwrap());
+        let clock = Arc::new(MockClock::new(0));
+        let (scheduler, workers) = Scheduler::builder()
+            .store(store.clone())
+            .clock(clock.clone())
+            .task_retention(Duration::from_secs(60))
+            .compaction_interval(None)
+            .build()
+            .unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.schedule_task(Task { id: 415, ..Default::default() }).await.unwrap();
+        while events.recv().await.unwrap().transition.to != TaskState::Completed {}
+        // Waits for a start time an hour away
+        scheduler.schedule_task(Task { id: 416, not_before: Some(3_600_000), ..Default::default() }).await.unwrap();
+        let logged = fs::read_to_string(&path).unwrap().lines().count();
+
+        // Not yet past its retention
+        clock.advance(Duration::from_secs(59));
+        assert!(scheduler.compact_store().await.unwrap().is_empty());
+        assert!(scheduler.task_record(415).await.is_some());
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(scheduler.compact_store().await.unwrap(), vec![415]);
+        assert!(scheduler.task_record(415).await.is_none());
+        assert!(store.load_task(415).unwrap().is_none());
+        // An unfinished task is kept however old
+        assert_eq!(scheduler.task_record(416).await.unwrap().state, TaskState::Pending);
+        assert!(store.load_task(416).unwrap().is_some());
+        assert!(fs::read_to_string(&path).unwrap().lines().count() < logged);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+// backend/rust/src/retries.rs
+// Purpose: Automatic reassignment of failed work for MRTODP. With a `RetryPolicy` set on
+// the builder, a task that fails because of its robot (the robot reported failure or an
+// uninterpretable result, could not be reached, was deregistered or replaced, or went
+// offline) is not failed but queued again as a new attempt while it has attempts left.
+// That attempt opens with a ReasonCode::Reassigned transition naming the failure, so
+// event subscribers and webhooks see every reassignment, and it is recorded in the audit
+// log; the failure still counts against the robot's profile. Failures the robot didn't
+// cause (e.g. an incompatible robot or a zone violation) and tasks being cancelled fail
+// as before. A task pinned to a robot is retried there; any other goes back to the
+// assignment engine, which keeps it off the robot that failed it while another robot can
+// take it if `exclude_failed_robot` is set. With `reassign_offline`, the scheduler also
+// looks for robots that fell silent while holding tasks (see fleet.rs) every half
+// `offline_after` and fails those tasks with FailedRobotOffline, so they are reassigned
+// too. A task whose robot failures use up its attempts is moved to the dead-letter queue
+// (see dead_letters.rs). Without a policy, failures are final.
+
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{Attempt, ReasonCode, Requeue, Scheduler, TaskRecord, TaskState};
+
+// When failed tasks are queued again
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,          // Attempts per task in all, counting the first
+    pub exclude_failed_robot: bool, // Place a retry on another robot while one can take it
+    pub reassign_offline: bool,     // Fail and retry tasks held by robots gone offline
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { max_attempts: 3, exclude_failed_robot: true, reassign_offline: true }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("Retry policy needs at least 1 attempt".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Failures caused by the robot rather than the task or the site's configuration
+pub(crate) fn robot_failure(reason: ReasonCode) -> bool {
+    matches!(
+        reason,
+        ReasonCode::FailedRobotError
+            | ReasonCode::FailedCorruptResult
+            | ReasonCode::FailedRobotDeregistered
+            | ReasonCode::FailedRobotReplaced
+            | ReasonCode::FailedRobotOffline
+    )
+}
+
+// Attempts since the task last entered the queue other than by reassignment, e.g. when it
+// was submitted or re-driven from the dead-letter queue
+pub(crate) fn attempts_in_run(record: &TaskRecord) -> u32 {
+    let reassigned = |a: &&Attempt| a.transitions.first().is_some_and(|t| t.reason == ReasonCode::Reassigned);
+    1 + record.attempts.iter().rev().take_while(reassigned).count() as u32
+}
+
+impl Scheduler {
+    // How a failing task goes on as a new attempt, if the retry policy gives it one
+    pub(crate) fn retry_of(&self, record: &TaskRecord, to: TaskState, reason: ReasonCode) -> Option<Requeue> {
+        let policy = self.core.retry_policy?;
+        let robot_id = record.attempts.last()?.robot_id.clone()?;
+        let retry = to == TaskState::Failed
+            && robot_failure(reason)
+            && (record.state.is_active() || record.state == TaskState::Suspended)
+            && !record.cancel_requested
+            && attempts_in_run(record) < policy.max_attempts
+            && !self.is_shutting_down();
+        if !retry {
+            return None;
+        }
+        Some(Requeue {
+            to_robot: record.pinned.then(|| robot_id.clone()),
+            exclude: (policy.exclude_failed_robot && !record.pinned).then_some(robot_id),
+            handoff: None,
+        })
+    }
+
+    // Fail the tasks held by robots not heard from within `offline_after`, so the retry
+    // policy reassigns them; returns their IDs. Without a transport every robot is online.
+    pub async fn reassign_offline_tasks(&self) -> Vec<u32> {
+        if self.core.dispatcher.is_none() {
+            return Vec::new();
+        }
+        let now = self.core.clock.now_millis();
+        let offline_after = self.core.offline_after.as_millis() as u64;
+        let mut stranded: Vec<(u32, String)> = {
+            let seen = self.core.robot_seen.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            let records = self.core.records.lock().await;
+            records
+                .values()
+                .filter(|r| r.state.is_active())
+                .filter_map(|r| Some((r.task.id, r.attempts.last()?.robot_id.clone()?)))
+                .filter(|(_, robot_id)| seen.get(robot_id).is_none_or(|at| now.saturating_sub(*at) >= offline_after))
+                .collect()
+        };
+        stranded.sort();
+        for (task_id, robot_id) in &stranded {
+            let detail = format!("Robot {} went offline while holding the task", robot_id);
+            self.transition(*task_id, TaskState::Failed, ReasonCode::FailedRobotOffline, detail).await;
+        }
+        stranded.into_iter().map(|(task_id, _)| task_id).collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter, MockClock};
+
+    #[tokio::test]
+    async fn test_failed_tasks_are_reassigned_to_other_robots() {
+        let clock = Arc::new(MockClock::new(1_000_000));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let policy = RetryPolicy { max_attempts: 3, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).transport(fake.clone()).retry_policy(policy).offline_after(Duration::from_secs(30)).build().unwrap();
+        fake.attach(&scheduler);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            fake.script(robot_id, vec![FakeBehavior::AckAfter(Duration::from_secs(60)); 2]);
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let mut events = scheduler.subscribe();
+        let running_on = |robot_id: &'static str| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(402).await.is_none_or(|r| r.state != TaskState::Running || r.task.robot_id.as_deref() != Some(robot_id)) {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        };
+        scheduler.schedule_task(Task { id: 402, ..Default::default() }).await.unwrap();
+        while scheduler.task_record(402).await.is_none_or(|r| r.state != TaskState::Running) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        let first = scheduler.task_record(402).await.unwrap().task.robot_id.unwrap();
+        let second = if first == "Ford" { "Scion" } else { "Ford" };
+
+        // A reported failure moves the task to the other robot
+        scheduler.report_result(402, Err("Gripper jammed".to_string())).await;
+        running_on(second).await;
+        let reassigned = loop {
+            let event = events.recv().await.unwrap();
+            if event.transition.reason == ReasonCode::Reassigned {
+                break event;
+            }
+        };
+        assert_eq!((reassigned.attempt, reassigned.transition.detail.as_str()), (2, "Gripper jammed; reassigned as attempt 2 of 3"));
+
+        // So does its robot falling silent; the last attempt's failure is final
+        clock.advance(Duration::from_secs(31));
+        scheduler.robot_heartbeat(&first);
+        assert_eq!(scheduler.reassign_offline_tasks().await, vec![402]);
+        // With both robots excluded, either may take the last attempt
+        while scheduler.task_record(402).await.is_none_or(|r| r.state != TaskState::Running || r.attempts.len() < 3) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        scheduler.report_result(402, Err("Gripper jammed".to_string())).await;
+        let record = scheduler.task_record(402).await.unwrap();
+        assert_eq!((record.state, record.attempts.len()), (TaskState::Failed, 3));
+        assert_eq!(record.task.excluded_robots.len(), 2);
+
+        // Only the scheduler excludes robots
+        let steered = Task { id: 403, excluded_robots: vec!["Ford".to_string()], ..Default::default() };
+        assert!(scheduler.schedule_task(steered).await.unwrap_err().contains("with excluded robots"));
+    }
+}
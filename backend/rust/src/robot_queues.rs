@@ -0,0 +1,192 @@
+// backend/rust/src/robot_queues.rs
+// Purpose: Per-robot task queues for MRTODP. The execution loop takes tasks off the shared
+// lanes in policy order, but a task whose robot has no free slot is parked in that robot's
+// own queue instead of holding anything up, so one overloaded robot's backlog never delays
+// assignments to idle robots. When a slot frees, the robot's best queued task by the active
+// scheduling policy goes next. A robot that frees a slot with nothing of its own queued may
+// take over the best task it can run from a busier robot's backlog; which queued tasks may
+// move is set by the work-stealing policy on the builder:
+//
+//   - off: queued tasks stay with the robot they were queued for
+//   - conservative (default): only tasks the assignment engine placed (rather than a
+//     submitter or a pin) move
+//   - aggressive: tasks a submitter bound to a robot move too, operator pins never do, and a
+//     robot joining the fleet takes over work straight away instead of on its first freed slot
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use crate::policy::{rank, ReadyQueue, SchedulingPolicy};
+use crate::priority_classes::QueueOrdering;
+use crate::task::Task;
+
+// Which queued tasks idle robots may take over from busier ones
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StealPolicy {
+    Off,
+    #[default]
+    Conservative,
+    Aggressive,
+}
+
+impl StealPolicy {
+    // Whether a task queued for its robot may later move to another one
+    pub(crate) fn may_move(&self, engine_placed: bool, pinned: bool) -> bool {
+        match self {
+            StealPolicy::Off => false,
+            StealPolicy::Conservative => engine_placed,
+            StealPolicy::Aggressive => !pinned,
+        }
+    }
+}
+
+// Waiting tasks per robot, and which of them another robot may take over
+#[derive(Default)]
+pub(crate) struct RobotQueues {
+    queues: HashMap<String, ReadyQueue>,
+    movable: HashSet<u32>, // Task IDs the steal policy lets other robots take over
+    ordering: QueueOrdering, // Aging and class quotas of every robot's queue
+}
+
+impl RobotQueues {
+    pub(crate) fn new(ordering: QueueOrdering) -> Self {
+        RobotQueues { ordering, ..Self::default() }
+    }
+
+    // Queue a task until its robot has a free slot
+    pub(crate) fn park(&mut self, robot_id: String, task: Task, movable: bool, now: u64) {
+        if movable {
+            self.movable.insert(task.id);
+        }
+        let ordering = &self.ordering;
+        self.queues.entry(robot_id).or_insert_with(|| ReadyQueue::new(ordering.clone())).push(task, now);
+    }
+
+    // Take a robot's best queued task
+    pub(crate) fn pop(&mut self, robot_id: &str, policy: &dyn SchedulingPolicy, now: u64) -> Option<Task> {
+        let queue = self.queues.get_mut(robot_id)?;
+        let task = queue.pop(policy, now)?;
+        if queue.len() == 0 {
+            self.queues.remove(robot_id);
+        }
+        self.movable.remove(&task.id);
+        Some(task)
+    }
+
+    // Take the best movable task queued on another robot that `can_run` accepts
+    pub(crate) fn steal(&mut self, robot_id: &str, policy: &dyn SchedulingPolicy, now: u64, can_run: impl Fn(&Task) -> bool) -> Option<Task> {
+        for queue in self.queues.values_mut() {
+            queue.age(now);
+        }
+        let movable = &self.movable;
+        let (owner, task_id) = self
+            .queues
+            .iter()
+            .filter(|(owner, _)| owner.as_str() != robot_id)
+            .filter_map(|(owner, queue)| queue.peek_where(policy, |t| movable.contains(&t.id) && can_run(t)).map(|t| (owner, t)))
+            .min_by(|(a_owner, a), (b_owner, b)| rank(policy, a, b).then_with(|| a_owner.cmp(b_owner)))
+            .map(|(owner, task)| (owner.clone(), task.id))?;
+        let queue = self.queues.get_mut(&owner)?;
+        let task = queue.remove(task_id)?;
+        if queue.len() == 0 {
+            self.queues.remove(&owner);
+        }
+        self.movable.remove(&task_id);
+        Some(task)
+    }
+
+    // Drop a task from whichever queue holds it
+    pub(crate) fn withdraw(&mut self, task_id: u32) -> Option<Task> {
+        self.movable.remove(&task_id);
+        let (robot_id, queue) = self.queues.iter_mut().find(|(_, q)| q.contains(task_id))?;
+        let robot_id = robot_id.clone();
+        let task = queue.remove(task_id);
+        if queue.len() == 0 {
+            self.queues.remove(&robot_id);
+        }
+        task
+    }
+
+    // Take the tasks queued on a robot that `take` accepts, in policy order, e.g. all of them
+    // when the robot leaves service
+    pub(crate) fn release(&mut self, robot_id: &str, policy: &dyn SchedulingPolicy, take: impl Fn(&Task) -> bool) -> Vec<Task> {
+        let Some(queue) = self.queues.get_mut(robot_id) else {
+            return Vec::new();
+        };
+        let mut tasks = Vec::new();
+        while let Some(task_id) = queue.peek_where(policy, &take).map(|t| t.id) {
+            tasks.extend(queue.remove(task_id));
+        }
+        if queue.len() == 0 {
+            self.queues.remove(robot_id);
+        }
+        for task in &tasks {
+            self.movable.remove(&task.id);
+        }
+        tasks
+    }
+
+    // Drop every queued task `keep` rejects, returning their IDs
+    pub(crate) fn retain(&mut self, keep: impl Fn(&Task) -> bool) -> Vec<u32> {
+        let mut dropped = Vec::new();
+        for queue in self.queues.values_mut() {
+            queue.retain(|task| keep(task) || {
+                dropped.push(task.id);
+                false
+            });
+        }
+        self.queues.retain(|_, queue| queue.len() > 0);
+        for task_id in &dropped {
+            self.movable.remove(task_id);
+        }
+        dropped
+    }
+
+    // Tasks queued on a robot, in the order they would go to it
+    pub(crate) fn queued(&self, robot_id: &str, policy: &dyn SchedulingPolicy) -> Vec<&Task> {
+        self.queues.get(robot_id).map_or_else(Vec::new, |queue| queue.ordered(policy))
+    }
+
+    // Tasks queued on a robot
+    pub(crate) fn depth(&self, robot_id: &str) -> usize {
+        self.queues.get(robot_id).map_or(0, ReadyQueue::len)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queues.is_empty()
+    }
+
+    // Queue depth of every robot with a backlog
+    pub(crate) fn depths(&self) -> BTreeMap<String, usize> {
+        self.queues.iter().map(|(robot_id, queue)| (robot_id.clone(), queue.len())).collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::PriorityFirst;
+
+    #[test]
+    fn test_queues_pop_by_policy_and_share_movable_work() {
+        let task = |id: u32, priority: u32| Task { id, priority, ..Default::default() };
+        let mut queues = RobotQueues::default();
+        queues.park("Ford".to_string(), task(1, 1), false, 0);
+        queues.park("Ford".to_string(), task(2, 5), false, 0);
+        queues.park("Ford".to_string(), task(3, 3), true, 0);
+        queues.park("Ford".to_string(), task(4, 4), true, 0);
+        queues.park("Hank".to_string(), task(5, 9), true, 0);
+        assert_eq!(queues.depths(), BTreeMap::from([("Ford".to_string(), 4), ("Hank".to_string(), 1)]));
+
+        // Scion has no queue of its own; it takes the best movable task it can run
+        assert_eq!(queues.steal("Scion", &PriorityFirst, 0, |t| t.id != 5).map(|t| t.id), Some(4));
+        assert_eq!(queues.pop("Ford", &PriorityFirst, 0).map(|t| t.id), Some(2));
+        assert_eq!(queues.withdraw(1).map(|t| t.id), Some(1));
+        assert_eq!(queues.steal("Ford", &PriorityFirst, 0, |_| true).map(|t| t.id), Some(5));
+        assert_eq!(queues.depth("Hank"), 0);
+        assert_eq!(queues.pop("Ford", &PriorityFirst, 0).map(|t| t.id), Some(3));
+        assert!(queues.steal("Scion", &PriorityFirst, 0, |_| true).is_none());
+        assert!(queues.depths().is_empty());
+    }
+}
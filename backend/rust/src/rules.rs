@@ -0,0 +1,587 @@
+// backend/rust/src/rules.rs
+// Purpose: Sandboxed expression language for admission and routing rules. Expressions are
+// parsed once when a rule set is loaded and evaluated against a JSON context holding the
+// task (`task`), the robot under consideration (`robot`), the time of day (`now`), and
+// site variables (`site`). The language has no loops, assignments, or I/O: only literals,
+// field access, comparison, boolean and arithmetic operators, `in`, and a few pure
+// functions, so a rule can't hang or escape the submission path.
+//
+// Example: task.priority < 5 && (now.hour >= 22 || now.hour < 6) && 'night_shift' in site.modes
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use crate::task::Task;
+
+const MAX_EXPRESSION_LEN: usize = 4096;
+const MAX_NESTING: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    const OPS: [&str; 19] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "(", ")", "[", "]", "."];
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(text.parse().map_err(|_| format!("Invalid number: {}", text))?));
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err("Unterminated string".to_string()),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        text.push(*chars.get(i + 1).ok_or("Unterminated string")?);
+                        i += 2;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            i += 1;
+            tokens.push(Token::Str(text));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c == ',' {
+            tokens.push(Token::Op(","));
+            i += 1;
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPS.iter().find(|op| rest.starts_with(**op)).ok_or_else(|| format!("Unexpected character: {:?}", c))?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Literal(Value),
+    Var(String),
+    Field(Box<Node>, String),
+    Index(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Neg(Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    List(Vec<Node>),
+    Call(String, Vec<Node>),
+}
+
+// Recursive-descent parser over the token stream
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(op),
+            Some(Token::Ident(word)) if word == "in" => Some("in"),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if self.peek_op() == Some(op) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.eat(op) {
+            return Ok(());
+        }
+        Err(format!("Expected '{}'", op))
+    }
+
+    fn expression(&mut self) -> Result<Node, String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return Err("Expression nested too deeply".to_string());
+        }
+        let node = self.binary(0);
+        self.depth -= 1;
+        node
+    }
+
+    // Precedence climbing: || < && < comparisons and `in` < + - < * / %
+    fn binary(&mut self, level: usize) -> Result<Node, String> {
+        const LEVELS: [&[(&str, BinOp)]; 5] = [
+            &[("||", BinOp::Or)],
+            &[("&&", BinOp::And)],
+            &[("==", BinOp::Eq), ("!=", BinOp::Ne), ("<=", BinOp::Le), (">=", BinOp::Ge), ("<", BinOp::Lt), (">", BinOp::Gt), ("in", BinOp::In)],
+            &[("+", BinOp::Add), ("-", BinOp::Sub)],
+            &[("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Rem)],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(&(_, op)) = LEVELS[level].iter().find(|(text, _)| self.peek_op() == Some(*text)) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Node::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.eat("!") {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        let mut node = self.primary()?;
+        loop {
+            if self.eat(".") {
+                match self.tokens.get(self.pos).cloned() {
+                    Some(Token::Ident(name)) => {
+                        self.pos += 1;
+                        node = Node::Field(Box::new(node), name);
+                    }
+                    _ => return Err("Expected field name after '.'".to_string()),
+                }
+            } else if self.eat("[") {
+                let index = self.expression()?;
+                self.expect("]")?;
+                node = Node::Index(Box::new(node), Box::new(index));
+            } else {
+                return Ok(node);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of expression")?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Node::Literal(json!(n))),
+            Token::Str(s) => Ok(Node::Literal(Value::String(s))),
+            Token::Ident(word) => match word.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "null" => Ok(Node::Literal(Value::Null)),
+                _ if self.eat("(") => {
+                    if !["size", "lower", "upper", "starts_with", "ends_with"].contains(&word.as_str()) {
+                        return Err(format!("Unknown function: {}", word));
+                    }
+                    let args = self.list(")")?;
+                    Ok(Node::Call(word, args))
+                }
+                _ => Ok(Node::Var(word)),
+            },
+            Token::Op("(") => {
+                let node = self.expression()?;
+                self.expect(")")?;
+                Ok(node)
+            }
+            Token::Op("[") => Ok(Node::List(self.list("]")?)),
+            Token::Op(op) => Err(format!("Unexpected '{}'", op)),
+        }
+    }
+
+    // Comma-separated expressions up to a closing delimiter
+    fn list(&mut self, close: &str) -> Result<Vec<Node>, String> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expression()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+fn number(value: &Value) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| format!("Expected a number, got {}", value))
+}
+
+fn truthy(value: &Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| format!("Expected a boolean, got {}", value))
+}
+
+// Equality that treats 1 and 1.0 as the same number
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+fn string(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| format!("Expected a string, got {}", value))
+}
+
+// A parsed, reusable expression
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        if source.len() > MAX_EXPRESSION_LEN {
+            return Err(format!("Expression longer than {} bytes", MAX_EXPRESSION_LEN));
+        }
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, depth: 0 };
+        let root = parser.expression().map_err(|e| format!("{} in {:?}", e, source))?;
+        if parser.pos != parser.tokens.len() {
+            return Err(format!("Unexpected trailing input in {:?}", source));
+        }
+        Ok(Expression { source: source.to_string(), root })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    // Evaluate against a context object; unknown variables and fields are null
+    pub fn evaluate(&self, context: &Value) -> Result<Value, String> {
+        eval(&self.root, context)
+    }
+
+    // Evaluate and require a boolean result
+    pub fn test(&self, context: &Value) -> Result<bool, String> {
+        self.evaluate(context).and_then(|v| truthy(&v)).map_err(|e| format!("{} in {:?}", e, self.source))
+    }
+}
+
+fn eval(node: &Node, context: &Value) -> Result<Value, String> {
+    Ok(match node {
+        Node::Literal(value) => value.clone(),
+        Node::Var(name) => context.get(name).cloned().unwrap_or(Value::Null),
+        Node::Field(target, name) => eval(target, context)?.get(name).cloned().unwrap_or(Value::Null),
+        Node::Index(target, index) => {
+            let target = eval(target, context)?;
+            match eval(index, context)? {
+                Value::String(key) => target.get(&key).cloned().unwrap_or(Value::Null),
+                index => target.get(number(&index)? as usize).cloned().unwrap_or(Value::Null),
+            }
+        }
+        Node::Not(inner) => Value::Bool(!truthy(&eval(inner, context)?)?),
+        Node::Neg(inner) => json!(-number(&eval(inner, context)?)?),
+        Node::List(items) => Value::Array(items.iter().map(|item| eval(item, context)).collect::<Result<_, _>>()?),
+        Node::Call(name, args) => {
+            let args: Vec<Value> = args.iter().map(|arg| eval(arg, context)).collect::<Result<_, _>>()?;
+            call(name, &args)?
+        }
+        Node::Binary(BinOp::And, left, right) => Value::Bool(truthy(&eval(left, context)?)? && truthy(&eval(right, context)?)?),
+        Node::Binary(BinOp::Or, left, right) => Value::Bool(truthy(&eval(left, context)?)? || truthy(&eval(right, context)?)?),
+        Node::Binary(op, left, right) => {
+            let (a, b) = (eval(left, context)?, eval(right, context)?);
+            match op {
+                BinOp::Eq => Value::Bool(equal(&a, &b)),
+                BinOp::Ne => Value::Bool(!equal(&a, &b)),
+                BinOp::In => Value::Bool(match &b {
+                    Value::Array(items) => items.iter().any(|item| equal(item, &a)),
+                    Value::String(text) => text.contains(string(&a)?),
+                    Value::Object(map) => map.contains_key(string(&a)?),
+                    Value::Null => false,
+                    other => return Err(format!("Cannot test membership in {}", other)),
+                }),
+                BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => {
+                    let ordering = match (&a, &b) {
+                        (Value::String(x), Value::String(y)) => x.cmp(y),
+                        _ => number(&a)?.partial_cmp(&number(&b)?).ok_or("Cannot compare NaN")?,
+                    };
+                    Value::Bool(match op {
+                        BinOp::Lt => ordering.is_lt(),
+                        BinOp::Le => ordering.is_le(),
+                        BinOp::Gt => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    })
+                }
+                BinOp::Add => match (&a, &b) {
+                    (Value::String(x), Value::String(y)) => Value::String(format!("{}{}", x, y)),
+                    _ => json!(number(&a)? + number(&b)?),
+                },
+                BinOp::Sub => json!(number(&a)? - number(&b)?),
+                BinOp::Mul => json!(number(&a)? * number(&b)?),
+                BinOp::Div | BinOp::Rem => {
+                    let divisor = number(&b)?;
+                    if divisor == 0.0 {
+                        return Err("Division by zero".to_string());
+                    }
+                    if *op == BinOp::Div { json!(number(&a)? / divisor) } else { json!(number(&a)? % divisor) }
+                }
+                BinOp::And | BinOp::Or => unreachable!("short-circuited above"),
+            }
+        }
+    })
+}
+
+fn call(name: &str, args: &[Value]) -> Result<Value, String> {
+    let arity = if matches!(name, "starts_with" | "ends_with") { 2 } else { 1 };
+    if args.len() != arity {
+        return Err(format!("{}() takes {} argument(s)", name, arity));
+    }
+    Ok(match name {
+        "size" => json!(match &args[0] {
+            Value::Array(items) => items.len(),
+            Value::Object(map) => map.len(),
+            Value::String(text) => text.chars().count(),
+            Value::Null => 0,
+            other => return Err(format!("size() of {}", other)),
+        }),
+        "lower" => Value::String(string(&args[0])?.to_lowercase()),
+        "upper" => Value::String(string(&args[0])?.to_uppercase()),
+        "starts_with" => Value::Bool(string(&args[0])?.starts_with(string(&args[1])?)),
+        _ => Value::Bool(string(&args[0])?.ends_with(string(&args[1])?)),
+    })
+}
+
+// Admission rule: submissions matching `deny_when` are rejected with `message`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AdmissionRuleSpec {
+    pub name: String,
+    pub deny_when: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+// Routing rule: unassigned tasks matching `when` go to the first robot (by ID) for which
+// `robot` holds and that has the task's required capabilities
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoutingRuleSpec {
+    pub name: String,
+    pub when: String,
+    pub robot: String,
+}
+
+// Rule set as loaded from configuration
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RuleSetSpec {
+    #[serde(default)]
+    pub site: Map<String, Value>, // Site variables, available as `site.<name>`
+    #[serde(default)]
+    pub admission: Vec<AdmissionRuleSpec>,
+    #[serde(default)]
+    pub routing: Vec<RoutingRuleSpec>,
+}
+
+struct AdmissionRule {
+    name: String,
+    deny_when: Expression,
+    message: String,
+}
+
+struct RoutingRule {
+    name: String,
+    when: Expression,
+    robot: Expression,
+}
+
+// Compiled rule set; swapped wholesale on reload
+#[derive(Default)]
+pub struct RuleEngine {
+    site: Value,
+    admission: Vec<AdmissionRule>,
+    routing: Vec<RoutingRule>,
+}
+
+// Time-of-day variables derived from a Unix timestamp (UTC)
+fn now_context(now_ms: u64) -> Value {
+    let minutes = now_ms / 60_000;
+    let days = now_ms / 86_400_000;
+    json!({
+        "millis": now_ms,
+        "hour": (minutes / 60) % 24,
+        "minute": minutes % 60,
+        "weekday": (days + 4) % 7, // 0 = Sunday
+    })
+}
+
+fn robot_context(robot_id: &str, capabilities: &[String]) -> Value {
+    json!({ "id": robot_id, "capabilities": capabilities })
+}
+
+impl RuleEngine {
+    pub fn compile(spec: RuleSetSpec) -> Result<Self, String> {
+        let admission = spec
+            .admission
+            .into_iter()
+            .map(|rule| {
+                let deny_when = Expression::parse(&rule.deny_when).map_err(|e| format!("Rule {}: {}", rule.name, e))?;
+                let message = if rule.message.is_empty() { format!("Denied by rule {}", rule.name) } else { rule.message };
+                Ok(AdmissionRule { name: rule.name, deny_when, message })
+            })
+            .collect::<Result<_, String>>()?;
+        let routing = spec
+            .routing
+            .into_iter()
+            .map(|rule| {
+                let when = Expression::parse(&rule.when).map_err(|e| format!("Rule {}: {}", rule.name, e))?;
+                let robot = Expression::parse(&rule.robot).map_err(|e| format!("Rule {}: {}", rule.name, e))?;
+                Ok(RoutingRule { name: rule.name, when, robot })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(RuleEngine { site: Value::Object(spec.site), admission, routing })
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, String> {
+        let spec: RuleSetSpec = serde_json::from_str(raw).map_err(|e| format!("Invalid rule set: {}", e))?;
+        Self::compile(spec)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.admission.is_empty() && self.routing.is_empty()
+    }
+
+    // Route an unassigned task, then run admission rules. Evaluation errors reject the
+    // submission so a broken rule fails closed.
+    pub fn apply(&self, task: &mut Task, robots: &HashMap<String, Vec<String>>, now_ms: u64) -> Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let mut context = json!({
+            "task": serde_json::to_value(&*task).map_err(|e| e.to_string())?,
+            "robot": null,
+            "now": now_context(now_ms),
+            "site": self.site,
+        });
+        if task.robot_id.is_none() {
+            if let Some(rule) = self.matching_route(&context)? {
+                let mut ids: Vec<&String> = robots.keys().collect();
+                ids.sort();
+                let mut chosen = None;
+                for id in ids {
+                    let capabilities = &robots[id];
+                    if !task.required_capabilities.iter().all(|c| capabilities.contains(c)) {
+                        continue;
+                    }
+                    context["robot"] = robot_context(id, capabilities);
+                    if rule.robot.test(&context).map_err(|e| format!("Rule {}: {}", rule.name, e))? {
+                        chosen = Some(id.clone());
+                        break;
+                    }
+                }
+                let robot_id = chosen.ok_or_else(|| format!("No robot satisfies routing rule {}", rule.name))?;
+                context["task"]["robot_id"] = json!(robot_id);
+                task.robot_id = Some(robot_id);
+            }
+        }
+        context["robot"] = match &task.robot_id {
+            Some(id) => robot_context(id, robots.get(id).map(Vec::as_slice).unwrap_or(&[])),
+            None => Value::Null,
+        };
+        for rule in &self.admission {
+            if rule.deny_when.test(&context).map_err(|e| format!("Rule {}: {}", rule.name, e))? {
+                return Err(rule.message.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn matching_route(&self, context: &Value) -> Result<Option<&RoutingRule>, String> {
+        for rule in &self.routing {
+            if rule.when.test(context).map_err(|e| format!("Rule {}: {}", rule.name, e))? {
+                return Ok(Some(rule));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_str(source: &str, context: &Value) -> Value {
+        Expression::parse(source).unwrap().evaluate(context).unwrap()
+    }
+
+    #[test]
+    fn test_expression_operators() {
+        let context = json!({"task": {"priority": 3, "tags": ["inspection"], "task_type": "Navigation"}});
+        assert_eq!(eval_str("task.priority * 2 + 1 >= 7", &context), json!(true));
+        assert_eq!(eval_str("'inspection' in task.tags && !(task.priority == 4)", &context), json!(true));
+        assert_eq!(eval_str("lower(task.task_type) == \"navigation\"", &context), json!(true));
+        assert_eq!(eval_str("size(task.tags) + size(task.missing)", &context), json!(1.0));
+        assert_eq!(eval_str("task['tags'][0]", &context), json!("inspection"));
+        assert_eq!(eval_str("task.deadline == null", &context), json!(true));
+    }
+
+    #[test]
+    fn test_parse_errors_and_sandbox_limits() {
+        assert!(Expression::parse("task.priority >").is_err());
+        assert!(Expression::parse("exec('rm')").unwrap_err().contains("Unknown function"));
+        assert!(Expression::parse(&format!("{}1{}", "(".repeat(40), ")".repeat(40))).unwrap_err().contains("nested"));
+        assert!(Expression::parse("1 / 0").unwrap().evaluate(&Value::Null).is_err());
+        assert!(Expression::parse("task.priority").unwrap().test(&json!({"task": {"priority": 1}})).is_err());
+    }
+
+    #[test]
+    fn test_routing_and_admission() {
+        let engine = RuleEngine::from_json(
+            r#"{"site": {"quiet_hours": [22, 23, 0, 1, 2, 3, 4, 5]},
+                "admission": [{"name": "quiet", "deny_when": "task.task_type == 'drilling' && now.hour in site.quiet_hours", "message": "No drilling during quiet hours"}],
+                "routing": [{"name": "cameras", "when": "'inspection' in task.tags", "robot": "'camera' in robot.capabilities"}]}"#,
+        )
+        .unwrap();
+        let robots = HashMap::from([
+            ("Ford".to_string(), vec!["navigation".to_string()]),
+            ("Scion".to_string(), vec!["camera".to_string()]),
+        ]);
+        let mut inspection = Task { id: 1, tags: vec!["inspection".to_string()], ..Default::default() };
+        engine.apply(&mut inspection, &robots, 0).unwrap();
+        assert_eq!(inspection.robot_id.as_deref(), Some("Scion"));
+
+        let mut drilling = Task { id: 2, task_type: "drilling".to_string(), ..Default::default() };
+        let midnight = 86_400_000 * 10;
+        assert_eq!(engine.apply(&mut drilling, &robots, midnight).unwrap_err(), "No drilling during quiet hours");
+        assert!(engine.apply(&mut drilling, &robots, midnight + 12 * 3_600_000).is_ok());
+    }
+}
@@ -1,200 +1,2776 @@
 // backend/rust/src/scheduler.rs
 // Purpose: Implements a concurrent task scheduler for MRTODP using Rust and Tokio.
 // Prioritizes tasks based on robot capabilities and deadlines, interfacing with
-// backend/python/ai_engine/delegator.py via FFI for task submission and status queries.
-// Uses Tokio for low-latency, thread-safe concurrency and includes unit tests.
+// backend/python/ai_engine/delegator.py through the optional FFI layer in ffi.rs, and
+// embeddable directly by Rust applications as a library. Uses Tokio for low-latency, thread-safe concurrency and includes unit tests.
 // Includes robust error handling for invalid inputs and scheduling failures, optimized
 // for production use by advanced users (e.g., robotics engineers).
+// Every task state transition carries a machine-readable reason code, is stored on the
+// task's attempt record, and is broadcast as an event for downstream automation.
+// The executor loop logs through `tracing`: each dispatched task runs in a span carrying its
+// task and robot IDs.
 
-use std::collections::{BinaryHeap, HashMap};
-use std::ffi::{c_char, CStr, CString};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::{Mutex, mpsc};
-use tokio::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex, mpsc, oneshot};
 use serde::{Deserialize, Serialize};
-use serde_json;
-
-// Task struct with priority and deadline
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
-struct Task {
-    id: u32,
-    task_type: String,
-    priority: u32, // Higher value = higher priority
-    deadline: Option<u64>, // Unix timestamp (milliseconds) for deadline
-    robot_id: Option<String>,
-    required_capabilities: Vec<String>,
+use tracing::Instrument;
+use crate::alerting::{Alert, Severity};
+use crate::assignment::{best_scored, AssignmentConfig, AssignmentScorer, Candidate};
+use crate::audit::{AuditAction, AuditEntry, AuditLog, AuditQuery};
+use crate::auction::{Auctions, Bid};
+use crate::backpressure::DispatchGate;
+use crate::breakers::Breakers;
+use crate::capacity::{self, RobotCapacity};
+use crate::builder::{SchedulerBuilder, TransitionHook};
+use crate::charging::{AutoCharging, Batteries};
+use crate::checkpoints::{Checkpoint, CheckpointInfo, RestoreReport};
+use crate::clock::Clock;
+use crate::coalitions::Coalitions;
+use crate::compatibility::{CompatibilityMatrix, CompatibilityReport, RobotModel};
+use crate::deadline_miss::{DeadlineMiss, DeadlineMissHook, DeadlinePolicy};
+use crate::decay::ExpediteDecay;
+use crate::delayed::TimerWheel;
+use crate::escalation;
+use crate::events::{EventFilter, EventLog, FilteredSubscription, StreamOptions};
+use crate::fleet::held_since;
+use crate::frames::FrameRegistry;
+use crate::geofencing::Geofence;
+use crate::handoff::Handoff;
+use crate::history::{HistoryFilter, HistoryPage, TaskHistory};
+use crate::geometry::Location;
+use crate::latency::{LatencyMarks, PhaseBreakdown};
+use crate::load_shedding::{LoadModeEvent, LoadShedder};
+use crate::location::DistanceFunction;
+use crate::metrics::{HistogramSnapshot, Metrics, WindowStats};
+use crate::missions::{namespace_of, Admission, Mission, MissionLimiter, MissionStatus};
+use crate::mutex_groups::{MutexGroupStatus, MutexGroups};
+use crate::payload_schemas::PayloadSchemaRegistry;
+use crate::policy::{policy_by_name, ReadyQueue, SchedulingPolicy};
+use crate::preemption::{PreemptionConfig, Suspensions};
+use crate::priority_classes::QueueOrdering;
+use crate::profiles::{RobotProfile, RobotProfiles};
+use crate::quotas::{QuotaLimiter, QuotaUsage};
+use crate::readiness::{ReadyChecks, RobotReadiness};
+use crate::resource_locks::{ResourceLocks, ResourceStatus};
+use crate::retries::{self, RetryPolicy};
+use crate::robot_queues::{RobotQueues, StealPolicy};
+use crate::rules::RuleEngine;
+use crate::shadow::{DecisionKind, ShadowCandidate, ShadowReport, ShadowTrial};
+use crate::slo::{SloAlert, SloStatus, SloTracker};
+use crate::store::TaskStore;
+use crate::submitter_limits::{Submitter, SubmitterLimiter, SubmitterLimits, SubmitterUsage};
+use crate::task_list::{sort_summaries, TaskListFilter, TaskSummary};
+use crate::templates::TaskTemplate;
+use crate::transport::{ControlCommand, Dispatcher, RobotReport, RobotSequence};
+use crate::uploads::UploadRegistry;
+use crate::validation::ValidationJob;
+use crate::versioning::VERSION_CONFLICT_ERROR;
+use crate::webhooks::WebhookTransport;
+
+// The task model moved to task.rs; re-exported so `scheduler::Task` paths keep working
+pub use crate::task::Task;
+
+// Prefix of the error returned when a request clashes with the scheduler's state: what it
+// would create already exists, or what it would change can't be changed that way any more
+pub const CONFLICT_ERROR: &str = "Conflict";
+
+// Lifecycle state of a task as observed by the scheduler, kept on its TaskRecord
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskState {
+    Pending,   // Accepted and queued for execution
+    Assigned,  // Handed to a robot's outbox; waiting for the robot to accept it
+    Running,   // Executing: accepted by the robot, or picked up by simulated execution
+    Completed, // Execution finished successfully
+    Failed,    // Execution or delivery to the robot failed
+    Expired,   // Deadline passed before execution started
+    Cancelled, // Withdrawn through cancel_task
+    Suspended, // Preempted on its robot by a higher-priority task; resumes there later
+}
+
+impl TaskState {
+    // Whether the task has left the scheduler for good
+    pub fn is_terminal(self) -> bool {
+        matches!(self, TaskState::Completed | TaskState::Failed | TaskState::Expired | TaskState::Cancelled)
+    }
+
+    // Whether the lifecycle allows moving from this state to `to`. Assigned and Running may
+    // re-enter themselves when a robot session migrates; pending tasks fail without reaching
+    // a robot when theirs can't run them; suspended tasks resume into Running, or finish if
+    // the robot completed them before the preemption reached it; failed and expired tasks
+    // only come back to Pending through a checkpoint restore.
+    pub fn can_transition_to(self, to: TaskState) -> bool {
+        use TaskState::*;
+        match self {
+            Pending => matches!(to, Assigned | Running | Failed | Expired | Cancelled),
+            Assigned => matches!(to, Assigned | Running | Completed | Failed | Cancelled | Suspended),
+            Running => matches!(to, Running | Completed | Failed | Cancelled | Suspended),
+            Suspended => matches!(to, Running | Completed | Failed | Cancelled),
+            Failed | Expired => to == Pending,
+            Completed | Cancelled => false,
+        }
+    }
+
+    // Whether a robot holds the task (assigned or executing)
+    pub fn is_active(self) -> bool {
+        matches!(self, TaskState::Assigned | TaskState::Running)
+    }
+}
+
+// Machine-readable reason attached to every state transition
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReasonCode {
+    Submitted,       // Task accepted by schedule_task
+    Dispatched,      // Task handed to the execution loop
+    Accepted,        // Robot accepted a delivered assignment
+    CompletedOk,     // Execution reported success
+    FailedRobotError, // Robot reported failure or could not be reached
+    FailedCorruptResult, // Robot sent a result report that could not be interpreted
+    ExpiredDeadline, // Deadline elapsed before dispatch
+    FailedRobotReplaced, // Robot re-registered and its old session's work was aborted
+    MigratedToNewSession, // Robot re-registered and the assignment was re-sent to the new session
+    RestoredFromCheckpoint, // Operator rolled the task back to a pending checkpoint
+    RecoveredAfterRestart, // Assigned or running when the scheduler stopped; re-queued on startup
+    Cancelled,       // Withdrawn through cancel_task, or stopped by the robot after a request
+    FailedIncompatible, // Robot's model or firmware isn't certified for the task type at dispatch
+    FailedRobotDeregistered, // Robot was taken out of service while the task was in flight
+    DroppedQueueFull, // Dropped from a full dispatch queue for a higher-priority submission
+    SuspendedForShutdown, // Still on its robot when the scheduler shut down; re-queued on startup
+    Preempted,       // Suspended to free its robot's slot for a higher-priority task
+    Resumed,         // Suspended task continued on its robot once a slot freed
+    ExpiredWindow,   // Task could no longer finish inside its time window at dispatch
+    FailedZoneViolation, // Robot is no longer permitted in the task's zone at dispatch
+    HandedOff,       // Taken from its robot with its progress, to be continued by another
+    FailedRobotOffline, // Robot stopped reporting while holding the task
+    Reassigned,      // Failed on its robot and queued again under the retry policy
+    Redriven,        // Taken out of the dead-letter queue and queued again by an operator
+}
+
+// How register_robot handles a robot ID that already has a session, e.g. after a reboot
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateRobotPolicy {
+    #[default]
+    Reject,              // Refuse the new registration
+    ReplaceAndAbortOld,  // Accept it and fail tasks running on the old session
+    ReplaceAndMigrate,   // Accept it and re-send running tasks to the new session
+}
+
+// A single state transition with its reason code and free-text detail
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub from: Option<TaskState>, // None for the initial submission
+    pub to: TaskState,
+    pub reason: ReasonCode,
+    pub detail: String,
+    pub at: u64, // Unix timestamp (milliseconds)
+}
+
+// One execution attempt of a task and the transitions recorded during it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub number: u32, // 1-based attempt counter
+    pub robot_id: Option<String>,
+    pub transitions: Vec<Transition>,
+}
+
+// Scheduler-side record of a task: current state plus attempt history
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TaskRecord {
+    pub task: Task,
+    pub state: TaskState,
+    pub attempts: Vec<Attempt>,
+    #[serde(default)]
+    pub held: bool, // Frozen by an operator; skipped by dispatch until released
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub held_since: Option<u64>, // When the current hold began (Unix milliseconds)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool, // Robot forced by an operator; dispatched there regardless of routing
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancel_requested: bool, // Running task asked to stop; its robot's next report cancels it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coalition_members: Vec<String>, // Robots holding a coalition task while it is assigned or running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_lettered_at: Option<u64>, // When the task entered the dead-letter queue (see dead_letters.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phases: Option<PhaseBreakdown>, // Latency breakdown of the latest attempt, once finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_miss: Option<DeadlineMiss>, // How the latest missed deadline was handled
+    #[serde(default)]
+    pub version: u64, // Bumped on every change; checked by compare-and-swap updates (see versioning.rs)
+    #[serde(skip)]
+    pub(crate) marks: LatencyMarks,
+}
+
+// How apply_transition takes a task from its robot back to Pending as a new attempt: a
+// hand-off (see handoff.rs) or a reassignment after a robot failure (see retries.rs)
+pub(crate) struct Requeue {
+    pub(crate) to_robot: Option<String>, // Robot the new attempt is pinned to, if any
+    pub(crate) exclude: Option<String>,  // Robot the new attempt avoids while another can take it
+    pub(crate) handoff: Option<Handoff>, // Progress carried to the next robot
+}
+
+// Event broadcast to subscribers whenever a task changes state
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TaskEvent {
+    #[serde(default)]
+    pub seq: u64, // Position in the scheduler's event stream, assigned on publish
+    pub task_id: u32,
+    pub attempt: u32,
+    pub transition: Transition,
+    // Task attributes carried for server-side subscription filtering
+    #[serde(default)]
+    pub task_type: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub robot_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phases: Option<PhaseBreakdown>, // Set on terminal events
+}
+
+impl TaskEvent {
+    fn new(task: &Task, attempt: &Attempt, transition: Transition) -> Self {
+        TaskEvent {
+            seq: 0,
+            task_id: task.id,
+            attempt: attempt.number,
+            transition,
+            task_type: task.task_type.clone(),
+            namespace: task.namespace.clone(),
+            robot_id: attempt.robot_id.clone().or_else(|| task.robot_id.clone()),
+            tags: task.tags.clone(),
+            phases: None,
+        }
+    }
+}
+
+// Holds a submitted task's ID from its duplicate check until its record is inserted, or
+// the submission fails or is dropped
+struct IdReservation<'a> {
+    ids: &'a std::sync::Mutex<HashSet<u32>>,
+    task_id: u32,
+}
+
+impl Drop for IdReservation<'_> {
+    fn drop(&mut self) {
+        self.ids.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.task_id);
+    }
+}
+
+// Shared state behind every Scheduler clone and handle
+pub(crate) struct SchedulerCore {
+    pub(crate) policy: std::sync::RwLock<Arc<dyn SchedulingPolicy>>, // Dispatch order, swappable at runtime
+    pub(crate) queue_ordering: QueueOrdering, // Priority aging, class quotas, and fair sharing applied on top of the policy
+    pub(crate) ready_depth: std::sync::atomic::AtomicUsize, // Tasks drained from the lanes, not yet dispatched
+    pub(crate) capabilities: Mutex<HashMap<String, Vec<String>>>, // robot_id -> capabilities
+    pub(crate) records: Mutex<HashMap<u32, TaskRecord>>, // task_id -> state and attempt history
+    pub(crate) reserved_ids: std::sync::Mutex<HashSet<u32>>, // IDs of submissions checked but not yet recorded
+    pub(crate) events: broadcast::Sender<TaskEvent>, // Transition events for subscribers
+    pub(crate) event_log: Arc<std::sync::Mutex<EventLog>>, // Numbers events; retains recent ones for resume
+    pub(crate) tx: mpsc::Sender<Task>, // Channel for task execution
+    pub(crate) urgent_tx: mpsc::Sender<Task>, // Urgent lane for expedited tasks, drained first
+    pub(crate) store: Arc<dyn TaskStore>, // Write-through persistence backend
+    pub(crate) clock: Arc<dyn Clock>, // Time source for deadlines and timestamps
+    pub(crate) deadline_policy: DeadlinePolicy, // Handling of missed deadlines for tasks without their own
+    pub(crate) deadline_hook: Option<DeadlineMissHook>, // Decides for tasks whose policy is notify_callback
+    pub(crate) hooks: Vec<TransitionHook>, // Callbacks run on every transition
+    pub(crate) missions: Mutex<MissionLimiter>, // Per-namespace active mission caps
+    pub(crate) mission_registry: std::sync::Mutex<HashMap<String, Mission>>, // Registered and cancelled missions
+    pub(crate) dispatcher: Option<Dispatcher>, // Robot transport; None = simulated execution
+    pub(crate) metrics: std::sync::Mutex<Metrics>, // Queue wait histograms and other counters
+    pub(crate) held: Mutex<HashMap<u32, Task>>, // Held tasks skipped by dispatch, awaiting release
+    pub(crate) expedite_decay: Option<ExpediteDecay>, // None = expedited tasks stay urgent however long held
+    pub(crate) webhook_transport: Arc<dyn WebhookTransport>, // Delivery for webhooks and escalations
+    pub(crate) duplicate_robot_policy: DuplicateRobotPolicy,
+    pub(crate) frames: FrameRegistry, // Static transforms used to localize task geometry
+    pub(crate) uploads: Mutex<UploadRegistry>, // Open chunked mission uploads
+    pub(crate) upload_ttl: std::time::Duration, // Idle time after which an open upload is discarded
+    pub(crate) task_retention: std::time::Duration, // Time a finished task's record is kept
+    pub(crate) templates: std::sync::RwLock<HashMap<String, TaskTemplate>>, // Registered task templates
+    pub(crate) payload_schemas: PayloadSchemaRegistry, // JSON Schemas for task payloads, by task type
+    pub(crate) rules: std::sync::RwLock<Arc<RuleEngine>>, // Admission and routing rules, hot-swappable
+    pub(crate) load_shedder: Option<std::sync::Mutex<LoadShedder>>, // None = never shed
+    pub(crate) load_events: broadcast::Sender<LoadModeEvent>,
+    pub(crate) slos: std::sync::Mutex<SloTracker>, // Assignment latency objectives
+    pub(crate) slo_events: broadcast::Sender<SloAlert>,
+    pub(crate) alerts: Option<mpsc::UnboundedSender<Alert>>, // Raised alerts for the router; None without sinks
+    pub(crate) profiles: std::sync::Mutex<RobotProfiles>,
+    pub(crate) checkpoints: Mutex<HashMap<String, Checkpoint>>, // Named save points
+    pub(crate) audit: std::sync::Mutex<AuditLog>,        // Most recent audit entries, oldest first
+    pub(crate) history: std::sync::Mutex<TaskHistory>, // Most recently finished tasks, oldest first
+    pub(crate) robot_seen: std::sync::Mutex<HashMap<String, u64>>, // robot_id -> last contact (Unix ms)
+    pub(crate) offline_after: std::time::Duration, // Silence after which a robot is reported offline
+    pub(crate) auto_charging: Option<AutoCharging>, // Charge tasks for robots low on battery; None = off
+    pub(crate) retry_policy: Option<RetryPolicy>, // Requeueing of tasks whose robot failed them; None = off
+    pub(crate) breakers: Option<std::sync::Mutex<Breakers>>, // Per-robot circuit breakers; None = off
+    pub(crate) batteries: std::sync::Mutex<Batteries>, // Reported battery levels and robots charging
+    pub(crate) robot_slots: std::sync::Mutex<HashMap<String, u32>>, // Declared parallel slots; 1 if absent
+    pub(crate) robot_models: std::sync::Mutex<HashMap<String, RobotModel>>, // Declared model and firmware
+    pub(crate) compatibility: std::sync::RwLock<Arc<CompatibilityMatrix>>, // Certified models per task type, hot-swappable
+    pub(crate) geofence: std::sync::RwLock<Arc<Geofence>>, // Named zones and the robots permitted in them
+    pub(crate) robot_queues: std::sync::Mutex<RobotQueues>, // Tasks waiting for a free slot on their robot
+    pub(crate) delayed: std::sync::Mutex<TimerWheel>, // Tasks waiting for their not_before time
+    pub(crate) steal_policy: StealPolicy, // Which queued tasks idle robots may take over
+    pub(crate) draining: std::sync::Mutex<HashSet<String>>, // Robots finishing in-flight tasks, taking no new ones
+    pub(crate) preemption: Option<PreemptionConfig>, // None = tasks are never preempted
+    pub(crate) suspensions: std::sync::Mutex<Suspensions>, // Preempted tasks waiting to resume on their robot
+    pub(crate) mutex_groups: std::sync::Mutex<MutexGroups>, // Holders and waiters of task mutex groups
+    pub(crate) resource_locks: std::sync::Mutex<ResourceLocks>, // Holders and waiters of exclusive resources
+    pub(crate) coalitions: std::sync::Mutex<Coalitions>, // Coalition tasks waiting for robots or acceptances
+    pub(crate) quotas: std::sync::Mutex<QuotaLimiter>, // Per-namespace daily submission quotas
+    pub(crate) submitters: std::sync::Mutex<SubmitterLimiter>, // Queued-task and per-minute limits per namespace or source
+    pub(crate) shadow: std::sync::Mutex<Option<ShadowTrial>>, // Candidate configuration under evaluation
+    pub(crate) ready_checks: Option<std::sync::Mutex<ReadyChecks>>, // None = robots are eligible on registration
+    pub(crate) auctions: Option<std::sync::Mutex<Auctions>>, // None = unassigned tasks run without bidding
+    pub(crate) assignment: AssignmentConfig, // Robot selection for tasks submitted without one
+    pub(crate) assignment_scorer: Option<Arc<dyn AssignmentScorer>>, // Replaces the selection above when set
+    pub(crate) distance: Arc<dyn DistanceFunction>, // Distance from a robot to a task's location
+    pub(crate) robot_locations: std::sync::Mutex<HashMap<String, Location>>, // robot_id -> last reported location
+    pub(crate) validation: Option<mpsc::Sender<ValidationJob>>, // None = submissions validated inline
+    pub(crate) dispatch_gate: DispatchGate, // Bounds submissions per lane under the backpressure policy
+    pub(crate) shutdown: tokio::sync::watch::Sender<bool>, // Set once shutdown begins; stops the execution loop
 }
 
-// Implement Ord for BinaryHeap (max-heap based on priority and deadline)
-impl Ord for Task {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let self_score = self.priority as u64 * 1_000_000_000
-            + self.deadline.unwrap_or(u64::MAX);
-        let other_score = other.priority as u64 * 1_000_000_000
-            + other.deadline.unwrap_or(u64::MAX);
-        other_score.cmp(&self_score) // Reverse for max-heap
+// Tasks each robot currently holds (assigned or running)
+pub(crate) fn active_per_robot(records: &HashMap<u32, TaskRecord>) -> HashMap<&str, u32> {
+    let mut active = HashMap::new();
+    for record in records.values() {
+        if !record.coalition_members.is_empty() {
+            // Every member of a coalition holds a slot from selection until the task ends
+            for robot_id in &record.coalition_members {
+                *active.entry(robot_id.as_str()).or_insert(0) += 1;
+            }
+        } else if let Some(robot_id) = record.attempts.last().and_then(|a| a.robot_id.as_deref()).filter(|_| record.state.is_active()) {
+            *active.entry(robot_id).or_insert(0) += 1;
+        }
     }
+    active
 }
 
-impl PartialOrd for Task {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+// Submissions per namespace in a batch, as daily quotas count them
+fn quota_requests<'a>(tasks: &[&'a Task]) -> HashMap<&'a str, u64> {
+    let mut requests = HashMap::new();
+    for task in tasks {
+        *requests.entry(namespace_of(task)).or_insert(0) += 1;
     }
+    requests
 }
 
-// Scheduler struct for managing tasks
-struct Scheduler {
-    tasks: Arc<Mutex<BinaryHeap<Task>>>,
-    capabilities: Arc<Mutex<HashMap<String, Vec<String>>>>, // robot_id -> capabilities
-    tx: mpsc::Sender<Task>, // Channel for task execution
+// Scheduler struct for managing tasks; constructed through SchedulerBuilder.
+// Clones are cheap and share the same core.
+#[derive(Clone)]
+pub struct Scheduler {
+    pub(crate) core: Arc<SchedulerCore>,
+    pub(crate) actor: Option<Arc<str>>, // Recorded in audit entries; None = the scheduler itself
 }
 
 impl Scheduler {
-    // Initialize scheduler with a channel for task execution
-    fn new() -> (Self, mpsc::Receiver<Task>) {
-        let (tx, rx) = mpsc::channel(100);
-        let scheduler = Scheduler {
-            tasks: Arc::new(Mutex::new(BinaryHeap::new())),
-            capabilities: Arc::new(Mutex::new(HashMap::new())),
-            tx,
+    // Start configuring a scheduler
+    pub fn builder() -> SchedulerBuilder {
+        SchedulerBuilder::new()
+    }
+
+    // Handle on the same scheduler whose actions are recorded in the audit log as taken by
+    // `actor`, e.g. the authenticated caller of a front-end request
+    pub fn acting_as(&self, actor: &str) -> Scheduler {
+        Scheduler { core: self.core.clone(), actor: Some(Arc::from(actor)) }
+    }
+
+    // Create a scheduler with default options and spawn its execution loop on the current
+    // Tokio runtime, for applications embedding the scheduler in-process
+    pub fn spawn() -> Self {
+        let (scheduler, workers) = Scheduler::builder()
+            .build()
+            .expect("default scheduler configuration is valid");
+        workers.spawn();
+        scheduler
+    }
+
+    // Subscribe to task transition events
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.core.events.subscribe()
+    }
+
+    // Subscribe to the transition events matching a filter, with default flow control
+    pub fn subscribe_filtered(&self, filter: EventFilter) -> FilteredSubscription {
+        self.subscribe_stream(filter, StreamOptions::default()).expect("a live subscription needs no replay")
+    }
+
+    // Subscribe to matching events with explicit slow-consumer handling, optionally
+    // replaying retained events first; fails if the resume point was already evicted
+    pub fn subscribe_stream(&self, filter: EventFilter, options: StreamOptions) -> Result<FilteredSubscription, String> {
+        FilteredSubscription::open(&self.core.events, self.core.event_log.clone(), filter, options)
+    }
+
+    // Look up the current record (state and attempts) of a task
+    pub async fn task_record(&self, task_id: u32) -> Option<TaskRecord> {
+        self.core.records.lock().await.get(&task_id).cloned()
+    }
+
+    // Throughput, success rate, active robots, and p95 queue wait over the trailing window
+    pub fn get_stats(&self, window: std::time::Duration) -> WindowStats {
+        let now = self.core.clock.now_millis();
+        self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).window_stats(window.as_millis() as u64, now)
+    }
+
+    // Latency distribution of each lifecycle phase over finished tasks, keyed by phase name
+    pub fn phase_latency(&self) -> HashMap<String, HistogramSnapshot> {
+        self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).phase_latency()
+    }
+
+    // Expedited submissions counted per source
+    pub fn expedite_by_source(&self) -> HashMap<String, u64> {
+        self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).expedite_by_source()
+    }
+
+    // Queue wait distributions keyed by required capability
+    pub fn queue_wait_by_capability(&self) -> HashMap<String, HistogramSnapshot> {
+        self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).queue_wait_by_capability()
+    }
+
+    // Find every task recorded under a distributed trace ID
+    pub async fn tasks_by_trace(&self, trace_id: &str) -> Vec<TaskRecord> {
+        let records = self.core.records.lock().await;
+        let mut matches: Vec<TaskRecord> = records
+            .values()
+            .filter(|r| r.task.trace_context.as_ref().is_some_and(|c| c.trace_id == trace_id))
+            .cloned()
+            .collect();
+        matches.sort_by_key(|r| r.task.id);
+        matches
+    }
+
+    // Unfinished tasks matching `filter`, soonest deadline first (see task_list.rs)
+    pub async fn list_tasks(&self, filter: &TaskListFilter) -> Vec<TaskSummary> {
+        let now = self.core.clock.now_millis();
+        let records = self.core.records.lock().await;
+        let mut tasks: Vec<TaskSummary> = records.values().filter(|r| filter.matches(r, now)).map(|r| TaskSummary::of(r, now)).collect();
+        drop(records);
+        sort_summaries(&mut tasks);
+        tasks
+    }
+
+    // Apply an internal transition; illegal ones (e.g. a dispatch racing with a
+    // cancellation) are logged and dropped
+    pub(crate) async fn transition(&self, task_id: u32, to: TaskState, reason: ReasonCode, detail: String) {
+        if let Err(e) = self.transition_task(task_id, to, reason, detail).await {
+            tracing::warn!(task_id, ?to, error = %e, "dropped a transition");
+        }
+    }
+
+    // Move a task to a new state, recording the transition and broadcasting its event.
+    // Rejects unknown tasks and transitions the lifecycle doesn't allow (e.g. Completed to
+    // Running).
+    pub async fn transition_task(&self, task_id: u32, to: TaskState, reason: ReasonCode, detail: String) -> Result<(), String> {
+        self.transition_versioned(task_id, None, to, reason, detail).await.map(|_| ())
+    }
+
+    // Move a task to a new state, first checking that its record is still at
+    // `expected_version` when one is given; returns the record's new version
+    pub(crate) async fn transition_versioned(
+        &self,
+        task_id: u32,
+        expected_version: Option<u64>,
+        to: TaskState,
+        reason: ReasonCode,
+        detail: String,
+    ) -> Result<u64, String> {
+        self.apply_transition(task_id, expected_version, to, reason, detail, None).await
+    }
+
+    // transition_versioned, or with a requeue take the task from its robot back to Pending as
+    // a new attempt and dispatch it. A failure the retry policy covers is turned into such a
+    // requeue (see retries.rs).
+    pub(crate) async fn apply_transition(
+        &self,
+        task_id: u32,
+        expected_version: Option<u64>,
+        to: TaskState,
+        reason: ReasonCode,
+        detail: String,
+        requeue: Option<Requeue>,
+    ) -> Result<u64, String> {
+        let mut records = self.core.records.lock().await;
+        let record = records.get_mut(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+        if let Some(expected) = expected_version.filter(|expected| *expected != record.version) {
+            return Err(format!("{}: task {} is at version {}, not {}", VERSION_CONFLICT_ERROR, task_id, record.version, expected));
+        }
+        let (mut to, mut reason, mut detail, mut requeue) = (to, reason, detail, requeue);
+        // Robot failure the task is reassigned after, which still counts against the robot
+        let mut failure = None;
+        if requeue.is_none() {
+            if let Some(retry) = self.retry_of(record, to, reason) {
+                let number = retries::attempts_in_run(record) + 1;
+                let max_attempts = self.core.retry_policy.map_or(number, |p| p.max_attempts);
+                detail = format!("{}; reassigned as attempt {} of {}", detail, number, max_attempts);
+                failure = Some(reason);
+                (to, reason, requeue) = (TaskState::Pending, ReasonCode::Reassigned, Some(retry));
+            }
+        }
+        let requeuing = requeue.is_some() && to == TaskState::Pending && (record.state.is_active() || record.state == TaskState::Suspended);
+        if !requeuing && !record.state.can_transition_to(to) {
+            return Err(format!("Illegal transition for task {}: {:?} -> {:?}", task_id, record.state, to));
+        }
+        let dead_lettered = self.is_dead_letter(record, to, reason);
+        // Robot that held the task, whose slot a finish frees
+        let holder = record.attempts.last().and_then(|a| a.robot_id.clone());
+        // A preempted task's slot goes straight to the task that preempted it
+        let freed_slot = record.state.is_active() && !to.is_active() && to != TaskState::Suspended;
+        let Some(attempt) = record.attempts.last() else {
+            return Err(format!("Task {} has no attempt to record the transition in", task_id));
+        };
+        let transition = Transition {
+            from: Some(record.state),
+            to,
+            reason,
+            detail,
+            at: self.core.clock.now_millis(),
+        };
+        let phases = to.is_terminal().then(|| PhaseBreakdown::from_attempt(&record.marks, &attempt.transitions, transition.at));
+        // The changed record is logged before it replaces the one in memory, so a failed
+        // write rejects the transition and leaves the task as it was
+        let mut next = record.clone();
+        next.state = to;
+        if let Some(requeue) = requeue.filter(|_| requeuing) {
+            // The task goes on as a new attempt, opened by this transition
+            let number = attempt.number + 1;
+            next.task.robot_id = requeue.to_robot;
+            next.pinned = next.task.robot_id.is_some();
+            if let Some(handoff) = requeue.handoff {
+                next.task.handoff = Some(handoff);
+            }
+            if let Some(robot_id) = requeue.exclude.filter(|r| !next.task.avoids(r)) {
+                next.task.excluded_robots.push(robot_id);
+            }
+            next.marks = LatencyMarks::default();
+            next.attempts.push(Attempt { number, robot_id: next.task.robot_id.clone(), transitions: Vec::new() });
+        }
+        next.attempts.last_mut().unwrap().transitions.push(transition.clone());
+        if phases.is_some() {
+            next.phases = phases.clone();
+        }
+        if dead_lettered {
+            next.dead_lettered_at = Some(transition.at);
+        }
+        self.persist(&mut next)?;
+        let previous = std::mem::replace(record, next);
+        {
+            let mut suspensions = self.core.suspensions.lock().unwrap_or_else(|e| e.into_inner());
+            if previous.state == TaskState::Suspended {
+                suspensions.remove(task_id);
+            }
+            if let (TaskState::Suspended, Some(robot_id)) = (to, previous.attempts.last().and_then(|a| a.robot_id.as_deref())) {
+                suspensions.suspend(robot_id, task_id, previous.task.priority);
+            }
+        }
+        self.core.submitters.lock().unwrap_or_else(|e| e.into_inner()).transitioned(&previous.task, transition.from, to);
+        // The attempt as it was before this transition
+        let attempt = previous.attempts.last().unwrap();
+        if let Some(robot_id) = attempt.robot_id.as_deref().filter(|_| transition.from.is_some_and(TaskState::is_active) && !to.is_active()) {
+            // Time the robot held the task, for the fleet's utilization
+            let mut metrics = self.core.metrics.lock().unwrap_or_else(|e| e.into_inner());
+            metrics.record_busy(robot_id, held_since(attempt).unwrap_or(transition.at), transition.at);
+            if matches!(to, TaskState::Completed | TaskState::Failed) && record.task.deadline.is_some_and(|deadline| transition.at > deadline) {
+                metrics.record_deadline_miss(transition.at, robot_id);
+            }
+        }
+        if transition.from == Some(TaskState::Pending) && to.is_active() {
+            if let Some(robot_id) = attempt.robot_id.clone() {
+                self.breaker_assigned(&robot_id, task_id);
+                let detail = format!("Task {} assigned to robot {}", task_id, robot_id);
+                self.audit(AuditAction::RobotAssigned, Some(task_id), Some(robot_id), None, detail);
+            }
+            // Queue wait runs from the most recent entry into Pending
+            let queued_at = attempt.transitions.iter().rev().find(|t| t.to == TaskState::Pending).map(|t| t.at);
+            if let Some(queued_at) = queued_at {
+                let wait_ms = transition.at.saturating_sub(queued_at);
+                let mut metrics = self.core.metrics.lock().unwrap_or_else(|e| e.into_inner());
+                metrics.record_queue_wait(&record.task.required_capabilities, wait_ms);
+                metrics.record_dispatch(transition.at, wait_ms, attempt.robot_id.as_deref());
+                drop(metrics);
+                let alerts = self.core.slos.lock().unwrap_or_else(|e| e.into_inner()).record(record.task.priority, wait_ms, transition.at);
+                for alert in alerts {
+                    self.publish_slo_alert(alert);
+                }
+                if let Some(shedder) = &self.core.load_shedder {
+                    let mut shedder = shedder.lock().unwrap_or_else(|e| e.into_inner());
+                    shedder.record_latency(wait_ms);
+                    if let Some(event) = shedder.update(self.queue_depth(), transition.at) {
+                        drop(shedder);
+                        self.publish_load_mode(event);
+                    }
+                }
+            }
+        }
+        if let Some(breakdown) = &phases {
+            let mut metrics = self.core.metrics.lock().unwrap_or_else(|e| e.into_inner());
+            metrics.record_finished(transition.at, to, attempt.robot_id.as_deref());
+            metrics.record_phases(breakdown);
+        }
+        let outcome = if to.is_terminal() { Some((to, reason)) } else { failure.map(|reason| (TaskState::Failed, reason)) };
+        if let (Some((state, reason)), Some(robot_id)) = (outcome, attempt.robot_id.clone()) {
+            let started = attempt.transitions.iter().rev().find(|t| t.to.is_active()).map(|t| t.at);
+            let duration = started.map(|at| transition.at.saturating_sub(at));
+            self.record_outcome(&robot_id, &record.task.task_type, state, reason, duration);
+            let failed = match state {
+                TaskState::Completed => Some(false),
+                TaskState::Failed if retries::robot_failure(reason) => Some(true),
+                _ => None,
+            };
+            self.breaker_finished(&robot_id, task_id, failed);
+        }
+        let mut event = TaskEvent::new(&record.task, record.attempts.last().unwrap(), transition);
+        event.phases = phases;
+        // A migration re-enters Running within the same attempt; its watch is already armed
+        if to == TaskState::Running
+            && !matches!(event.transition.from, Some(TaskState::Running | TaskState::Suspended))
+            && record.task.escalation.is_some()
+        {
+            escalation::watch(self.clone(), task_id, event.attempt);
+        }
+        let version = record.version;
+        self.publish(event);
+        if let Some(robot_id) = holder.clone().filter(|_| dead_lettered) {
+            let detail = format!("Task {} moved to the dead-letter queue after failing on robot {}", task_id, robot_id);
+            self.audit(AuditAction::TaskDeadLettered, Some(task_id), Some(robot_id), None, detail);
+        }
+        if to.is_terminal() {
+            self.core.history.lock().unwrap_or_else(|e| e.into_inner()).record(record);
+            self.charge_finished(&record.task);
+            let released = self.core.missions.lock().await.task_finished(&record.task);
+            self.dispatch_released(released);
+        }
+        if let Some(group) = record.task.mutex_group.as_deref().filter(|_| !to.is_active()) {
+            // Pass the group on once its holder stops running, or never starts
+            let next = self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).release(group, task_id);
+            self.dispatch_released(next.into_iter().collect());
+        }
+        if !record.task.resources.is_empty() && !to.is_active() {
+            let granted = self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).release(task_id);
+            self.dispatch_released(granted);
+        }
+        if !record.coalition_members.is_empty() && !to.is_active() {
+            self.coalition_finished(record, to, holder.as_deref().filter(|_| freed_slot));
+        } else if freed_slot && !self.is_shutting_down() {
+            self.retry_coalitions();
+        }
+        if requeuing {
+            if let Some(failure) = failure {
+                let detail = format!("Task {} reassigned after {:?} on robot {}", task_id, failure, holder.as_deref().unwrap_or("?"));
+                self.audit(AuditAction::TaskReassigned, Some(task_id), None, holder.clone(), detail);
+            }
+            self.dispatch_released(vec![record.task.clone()]);
+        }
+        if freed_slot && !self.is_shutting_down() {
+            // Still under the records lock, so the executor can't park a task for this robot
+            // between the count that made it wait and this release
+            let robot_id = holder.unwrap_or_default();
+            // Tasks preempted on the robot resume before its queued work
+            let suspended = self.core.suspensions.lock().unwrap_or_else(|e| e.into_inner()).take_next(&robot_id);
+            if let Some(suspended) = suspended.and_then(|(id, _)| records.get_mut(&id)) {
+                self.resume_suspended(suspended, robot_id);
+                return Ok(version);
+            }
+            let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let mut queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+            match queues.pop(&robot_id, policy.as_ref(), self.core.clock.now_millis()) {
+                Some(task) => self.dispatch_released(vec![task]),
+                None if !queues.is_empty() && self.core.steal_policy != StealPolicy::Off => self.share_backlog(robot_id),
+                None => {}
+            }
+        }
+        Ok(version)
+    }
+
+    // Mark a delivered assignment as accepted by its robot. Tasks that already moved on
+    // (reported, cancelled, or migrated while Running) are left alone.
+    pub(crate) async fn assignment_accepted(&self, task_id: u32, robot_id: &str) {
+        self.robot_heartbeat(robot_id);
+        let members = match self.core.records.lock().await.get(&task_id) {
+            Some(record) if record.state == TaskState::Assigned => record.coalition_members.clone(),
+            _ => return,
         };
-        (scheduler, rx)
+        if !members.is_empty() {
+            self.coalition_member_accepted(task_id, robot_id, members).await;
+            return;
+        }
+        let detail = format!("Robot {} accepted assignment", robot_id);
+        self.transition(task_id, TaskState::Running, ReasonCode::Accepted, detail).await;
     }
 
-    // Register robot capabilities
-    async fn register_robot(&self, robot_id: String, capabilities: Vec<String>) -> Result<(), String> {
-        let mut caps = self.capabilities.lock().await;
-        if caps.contains_key(&robot_id) {
-            return Err(format!("Robot {} already registered", robot_id));
+    // Continue a suspended task in the slot its robot just freed and tell the robot to resume
+    // it. The caller holds the records lock, so the task takes the slot before any waiting
+    // task can; only the control command is sent detached.
+    fn resume_suspended(&self, record: &mut TaskRecord, robot_id: String) {
+        let task_id = record.task.id;
+        let transition = Transition {
+            from: Some(record.state),
+            to: TaskState::Running,
+            reason: ReasonCode::Resumed,
+            detail: format!("Resumed on robot {}", robot_id),
+            at: self.core.clock.now_millis(),
+        };
+        if record.attempts.is_empty() {
+            return;
+        }
+        let resumed = self.update(record, |next| {
+            next.state = TaskState::Running;
+            let attempt = next.attempts.last_mut().unwrap();
+            attempt.transitions.push(transition.clone());
+            TaskEvent::new(&next.task, attempt, transition)
+        });
+        let Ok(event) = resumed else {
+            // Still suspended; it waits for the robot's next free slot
+            self.core.suspensions.lock().unwrap_or_else(|e| e.into_inner()).suspend(&robot_id, task_id, record.task.priority);
+            return;
+        };
+        self.publish(event);
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = scheduler.send_control(&robot_id, ControlCommand::Resume { task_id }).await {
+                tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send resume");
+            }
+        });
+    }
+
+    // Suspend `victim` on its robot to free a slot for `task`, and tell the robot to stop it
+    async fn preempt(&self, victim: u32, task: &Task, robot_id: &str) -> Result<(), String> {
+        let detail = format!("Preempted by task {} (priority {})", task.id, task.priority);
+        self.transition_task(victim, TaskState::Suspended, ReasonCode::Preempted, detail).await?;
+        tracing::info!(victim, robot_id = %robot_id, "preempted a lower-priority task");
+        if let Err(e) = self.send_control(robot_id, ControlCommand::Preempt { task_id: victim }).await {
+            tracing::warn!(victim, robot_id = %robot_id, error = %e, "could not send preemption");
         }
-        caps.insert(robot_id, capabilities);
         Ok(())
     }
 
-    // Schedule a task with capability-based prioritization
-    async fn schedule_task(&self, task: Task) -> Result<(), String> {
-        let caps = self.capabilities.lock().await;
-        if let Some(robot_id) = &task.robot_id {
-            if !caps.contains_key(robot_id) {
-                return Err(format!("Unknown robot: {}", robot_id));
+    // Let a robot that freed a slot with nothing of its own queued take over the best task
+    // the steal policy lets move from a busier robot; the engine then places it again. Runs
+    // detached because the caller holds the records lock.
+    fn share_backlog(&self, robot_id: String) {
+        if self.is_draining(&robot_id) {
+            return;
+        }
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            let Some(caps) = scheduler.core.capabilities.lock().await.get(&robot_id).cloned() else {
+                return;
+            };
+            let policy = scheduler.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let can_run = |task: &Task| {
+                task.required_capabilities.iter().all(|c| caps.contains(c))
+                    && scheduler.check_compatibility(&task.task_type, &robot_id).is_ok()
+                    && scheduler.check_zone(task, &robot_id).is_ok()
+            };
+            let now = scheduler.core.clock.now_millis();
+            let moved = scheduler.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).steal(&robot_id, policy.as_ref(), now, can_run);
+            if let Some(task) = moved {
+                tracing::debug!(task_id = task.id, robot_id = %robot_id, "moving queued task to a robot with a free slot");
+                scheduler.dispatch_released(vec![Task { robot_id: None, ..task }]);
             }
-            let robot_caps = caps.get(robot_id).unwrap();
-            if !task.required_capabilities.iter().all(|c| robot_caps.contains(c)) {
-                return Err(format!("Robot {} lacks required capabilities: {:?}", robot_id, task.required_capabilities));
+        });
+    }
+
+    // Send tasks released from mission-level queueing to the execution loop. Runs detached
+    // because it may be called from the execution loop itself.
+    pub(crate) fn dispatch_released(&self, released: Vec<Task>) {
+        if released.is_empty() {
+            return;
+        }
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            for task in released {
+                let task_id = task.id;
+                if let Err(e) = scheduler.dispatch(task).await {
+                    tracing::warn!(task_id, error = %e, "could not dispatch a released task");
+                }
+            }
+        });
+    }
+
+    // Queue an admitted task for execution; once shutdown begins it stays pending in the
+    // store for the next start instead
+    pub(crate) async fn dispatch(&self, task: Task) -> Result<(), String> {
+        if self.is_shutting_down() {
+            return Ok(());
+        }
+        let lane = if task.expedite { &self.core.urgent_tx } else { &self.core.tx };
+        lane.send(task).await.map_err(|e| format!("Failed to send task: {}", e))
+    }
+
+    // Change the active mission cap for a namespace (None = use the default cap)
+    pub async fn set_mission_limit(&self, namespace: &str, limit: Option<usize>) {
+        let released = self.core.missions.lock().await.set_limit(namespace, limit);
+        self.dispatch_released(released);
+    }
+
+    // Missions holding an active slot in a namespace
+    pub async fn active_missions(&self, namespace: &str) -> Vec<String> {
+        self.core.missions.lock().await.active_missions(namespace)
+    }
+
+    // Missions waiting for an active slot in a namespace, in release order
+    pub async fn queued_missions(&self, namespace: &str) -> Vec<String> {
+        self.core.missions.lock().await.queued_missions(namespace)
+    }
+
+    // Register a mission with its name and metadata ahead of (or alongside) its tasks
+    pub fn create_mission(&self, mission: Mission) -> Result<(), String> {
+        mission.validate()?;
+        let mut registry = self.core.mission_registry.lock().unwrap_or_else(|e| e.into_inner());
+        if registry.contains_key(&mission.id) {
+            return Err(format!("{}: mission {} already exists", CONFLICT_ERROR, mission.id));
+        }
+        let mission = Mission { cancelled_at: None, ..mission };
+        self.core.store.save_mission(&mission)?;
+        registry.insert(mission.id.clone(), mission);
+        Ok(())
+    }
+
+    // Progress of a mission across its tasks; None if it was neither registered nor has tasks
+    pub async fn get_mission_status(&self, mission_id: &str) -> Option<MissionStatus> {
+        let mission = self.core.mission_registry.lock().unwrap_or_else(|e| e.into_inner()).get(mission_id).cloned();
+        let records = self.core.records.lock().await;
+        let mut tasks = records.values().filter(|r| r.task.mission_id.as_deref() == Some(mission_id)).peekable();
+        if mission.is_none() && tasks.peek().is_none() {
+            return None;
+        }
+        let mission = mission.unwrap_or_else(|| Mission { id: mission_id.to_string(), ..Default::default() });
+        Some(mission.status(tasks))
+    }
+
+    // Cancel a mission as a unit. The mission is closed first, so from then on none of its
+    // tasks is dispatched and no new ones are accepted; then every unfinished task is
+    // cancelled as by cancel_task, running ones stopping cooperatively. Returns the
+    // mission's status after the call.
+    pub async fn cancel_mission(&self, mission_id: &str) -> Result<MissionStatus, String> {
+        let task_ids: Vec<u32> = {
+            let records = self.core.records.lock().await;
+            let mut registry = self.core.mission_registry.lock().unwrap_or_else(|e| e.into_inner());
+            let tasks: Vec<&TaskRecord> = records.values().filter(|r| r.task.mission_id.as_deref() == Some(mission_id)).collect();
+            if tasks.is_empty() && !registry.contains_key(mission_id) {
+                return Err(format!("Unknown mission: {}", mission_id));
+            }
+            let task_ids = tasks.iter().filter(|r| !r.state.is_terminal()).map(|r| r.task.id).collect();
+            let mission = registry.entry(mission_id.to_string()).or_insert_with(|| Mission { id: mission_id.to_string(), ..Default::default() });
+            if mission.cancelled_at.is_none() {
+                mission.cancelled_at = Some(self.core.clock.now_millis());
+                self.core.store.save_mission(mission)?;
+            }
+            task_ids
+        };
+        for task_id in task_ids {
+            // A task may finish, or be cancelled by the execution loop, in the meantime
+            if let Err(e) = self.cancel_task(task_id).await {
+                tracing::debug!(task_id, "mission task not cancelled: {}", e);
             }
         }
-        let mut tasks = self.tasks.lock().await;
-        tasks.push(task.clone());
-        self.tx.send(task).await.map_err(|e| format!("Failed to send task: {}", e))?;
+        self.get_mission_status(mission_id).await.ok_or_else(|| format!("Unknown mission: {}", mission_id))
+    }
+
+    // Whether a mission was cancelled and takes no more tasks
+    fn mission_cancelled(&self, mission_id: Option<&str>) -> bool {
+        let Some(mission_id) = mission_id else {
+            return false;
+        };
+        let registry = self.core.mission_registry.lock().unwrap_or_else(|e| e.into_inner());
+        registry.get(mission_id).is_some_and(|m| m.cancelled_at.is_some())
+    }
+
+    // Update a robot's performance profile with a finished task and write it through
+    fn record_outcome(&self, robot_id: &str, task_type: &str, state: TaskState, reason: ReasonCode, duration_ms: Option<u64>) {
+        let mut profiles = self.core.profiles.lock().unwrap_or_else(|e| e.into_inner());
+        let profile = profiles
+            .entry(robot_id.to_string())
+            .or_insert_with(|| RobotProfile { robot_id: robot_id.to_string(), ..Default::default() });
+        profile.record(task_type, state, reason, duration_ms);
+        if let Err(e) = self.core.store.save_profile(profile) {
+            tracing::warn!(robot_id = %robot_id, error = %e, "could not persist robot profile");
+        }
+    }
+
+    // Performance profile of a robot, if it has finished any task
+    pub fn robot_profile(&self, robot_id: &str) -> Option<RobotProfile> {
+        self.core.profiles.lock().unwrap_or_else(|e| e.into_inner()).get(robot_id).cloned()
+    }
+
+    // Performance profiles of all robots, sorted by robot ID
+    pub fn robot_profiles(&self) -> Vec<RobotProfile> {
+        let mut profiles: Vec<RobotProfile> = self.core.profiles.lock().unwrap_or_else(|e| e.into_inner()).values().cloned().collect();
+        profiles.sort_by(|a, b| a.robot_id.cmp(&b.robot_id));
+        profiles
+    }
+
+    // Count a change to a task record and write it through to the store. A failed write
+    // leaves the version as it was, raises a critical alert, and is returned so the caller
+    // rejects the change.
+    pub(crate) fn persist(&self, record: &mut TaskRecord) -> Result<(), String> {
+        record.version += 1;
+        if let Err(e) = self.core.store.save_task(record) {
+            record.version -= 1;
+            tracing::error!(task_id = record.task.id, error = %e, "could not persist task");
+            let summary = format!("Failed to persist task {}: {}", record.task.id, e);
+            self.raise_alert(Severity::Critical, "store", summary.clone(), record.task.namespace.clone(), None);
+            return Err(summary);
+        }
+        Ok(())
+    }
+
+    // Make a change to a copy of a task record and write the copy to the store before it
+    // replaces the record, so a failed write leaves the task as it was
+    pub(crate) fn update<T>(&self, record: &mut TaskRecord, change: impl FnOnce(&mut TaskRecord) -> T) -> Result<T, String> {
+        let mut next = record.clone();
+        let changed = change(&mut next);
+        self.persist(&mut next)?;
+        *record = next;
+        Ok(changed)
+    }
+
+    // Hand an alert to the router, which delivers it to every sink with a matching route
+    pub fn raise_alert(&self, severity: Severity, source: &str, summary: String, namespace: Option<String>, robot_id: Option<String>) {
+        let Some(alerts) = &self.core.alerts else {
+            return;
+        };
+        let at = self.core.clock.now_millis();
+        // Send errors only mean the workers aren't running
+        let _ = alerts.send(Alert { severity, source: source.to_string(), summary, namespace, robot_id, at });
+    }
+
+    // Bid on behalf of a robot for a task under auction; the robot must have been sent the
+    // call for bids, and a later bid from the same robot replaces its earlier one
+    pub fn submit_bid(&self, task_id: u32, bid: Bid) -> Result<(), String> {
+        let Some(auctions) = &self.core.auctions else {
+            return Err("Auction allocation is not enabled".to_string());
+        };
+        bid.validate()?;
+        auctions.lock().unwrap_or_else(|e| e.into_inner()).bid(task_id, bid)
+    }
+
+    // Record that a robot's emergency stop was triggered and page its on-call. The robot's
+    // tasks are left to its own reports; the stop itself is handled on the robot.
+    pub async fn report_emergency_stop(&self, robot_id: &str, detail: &str) -> Result<(), String> {
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.robot_heartbeat(robot_id);
+        tracing::error!(robot_id = %robot_id, detail = %detail, "robot emergency stop");
+        let summary = format!("Emergency stop on robot {}: {}", robot_id, detail);
+        self.raise_alert(Severity::Critical, "e_stop", summary, None, Some(robot_id.to_string()));
         Ok(())
     }
 
-    // Process tasks in priority order
-    async fn process_tasks(mut rx: mpsc::Receiver<Task>) {
-        while let Some(task) = rx.recv().await {
-            if let Some(deadline) = task.deadline {
-                let now = Instant::now().duration_since(Instant::UNIX_EPOCH).as_millis() as u64;
-                if now > deadline {
-                    eprintln!("Task {} missed deadline: {}ms", task.id, deadline);
-                    continue;
+    // Number the event, run transition hooks, and broadcast it to subscribers
+    pub(crate) fn publish(&self, mut event: TaskEvent) {
+        let mut log = self.core.event_log.lock().unwrap_or_else(|e| e.into_inner());
+        log.append(&mut event);
+        for hook in self.core.hooks.iter() {
+            hook(&event);
+        }
+        // Send errors only mean there are no subscribers
+        let _ = self.core.events.send(event);
+    }
+
+    // Record the outcome a robot reported for a running task
+    pub async fn report_result(&self, task_id: u32, result: Result<(), String>) {
+        if self.is_self_test(task_id) {
+            self.finish_ready_check(task_id, result).await;
+            return;
+        }
+        let reported_at = self.core.clock.now_millis();
+        let (state, cancel_requested, robot_id) = match self.core.records.lock().await.get_mut(&task_id) {
+            Some(record) => {
+                if record.state.is_active() {
+                    record.marks.reported_at = Some(reported_at);
                 }
+                (Some(record.state), record.cancel_requested, record.attempts.last().and_then(|a| a.robot_id.clone()))
             }
-            // Simulate task execution (replace with actual call to Python delegator)
-            println!("Processing task {} (type: {}, robot: {:?})", task.id, task.task_type, task.robot_id);
+            None => (None, false, None),
+        };
+        if let Some(robot_id) = &robot_id {
+            self.robot_heartbeat(robot_id);
+        }
+        // A result may overtake the acceptance of its own assignment, or a robot may finish a
+        // task before acting on its preemption
+        if !state.is_some_and(|s| s.is_active() || s == TaskState::Suspended) {
+            tracing::debug!(task_id, ?state, "ignoring result for a task that isn't running");
+            return;
+        }
+        if cancel_requested {
+            let outcome = match result {
+                Ok(()) => "success".to_string(),
+                Err(e) => e,
+            };
+            let detail = format!("Robot stopped after cancellation request; reported {}", outcome);
+            self.transition(task_id, TaskState::Cancelled, ReasonCode::Cancelled, detail).await;
+            return;
+        }
+        match result {
+            Ok(()) => self.transition(task_id, TaskState::Completed, ReasonCode::CompletedOk, "Robot reported success".to_string()).await,
+            Err(e) => self.transition(task_id, TaskState::Failed, ReasonCode::FailedRobotError, e).await,
         }
     }
-}
 
-// Global scheduler instance for FFI
-lazy_static::lazy_static! {
-    static ref SCHEDULER: Arc<Scheduler> = {
-        let (scheduler, rx) = Scheduler::new();
-        tokio::spawn(Scheduler::process_tasks(rx));
-        Arc::new(scheduler)
-    };
-}
+    // Ingest a raw JSON result report from a robot. Reports for a known task with an
+    // unrecognized status fail the task as corrupt; unparseable reports are rejected.
+    pub async fn ingest_report(&self, raw: &str) -> Result<(), String> {
+        let report: RobotReport = serde_json::from_str(raw).map_err(|e| format!("Malformed robot report: {}", e))?;
+        match report.status.as_str() {
+            "completed" => self.report_result(report.task_id, Ok(())).await,
+            "failed" => {
+                let detail = report.detail.unwrap_or_else(|| "Robot reported failure".to_string());
+                self.report_result(report.task_id, Err(detail)).await
+            }
+            other => {
+                let active = self.core.records.lock().await.get(&report.task_id).is_some_and(|r| r.state.is_active());
+                if active {
+                    let detail = format!("Unrecognized result status: {:?}", other);
+                    self.transition(report.task_id, TaskState::Failed, ReasonCode::FailedCorruptResult, detail).await;
+                }
+                return Err(format!("Corrupt result for task {}: status {:?}", report.task_id, other));
+            }
+        }
+        Ok(())
+    }
+
+    // Send a control command on the robot's priority lane, ahead of queued assignments;
+    // returns the command ID robots use to discard duplicates
+    pub async fn send_control(&self, robot_id: &str, command: ControlCommand) -> Result<u64, String> {
+        let Some(dispatcher) = &self.core.dispatcher else {
+            return Err("No robot transport configured".to_string());
+        };
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        dispatcher.control(self, robot_id, command).await
+    }
+
+    // Last sequence number sent to a robot and the highest it acknowledged; None without a
+    // transport or before the robot's first delivery
+    pub fn robot_sequence(&self, robot_id: &str) -> Option<RobotSequence> {
+        self.core.dispatcher.as_ref()?.robot_sequence(robot_id)
+    }
 
-// FFI function to register robot capabilities
-#[no_mangle]
-pub extern "C" fn register_robot_ffi(robot_id: *const c_char, capabilities_json: *const c_char) -> *mut c_char {
-    let robot_id = unsafe {
-        if robot_id.is_null() {
-            return CString::new("Error: Null robot ID").unwrap().into_raw();
+    // Freeze a pending task so dispatch skips it until released
+    pub async fn hold_task(&self, task_id: u32) -> Result<(), String> {
+        let mut records = self.core.records.lock().await;
+        let record = records.get_mut(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+        if record.state != TaskState::Pending {
+            return Err(format!("Task {} is {:?}; only pending tasks can be held", task_id, record.state));
+        }
+        if record.held {
+            return Err(format!("{}: task {} is already held", CONFLICT_ERROR, task_id));
         }
-        match CStr::from_ptr(robot_id).to_str() {
-            Ok(s) => s.to_string(),
-            Err(_) => return CString::new("Error: Invalid robot ID").unwrap().into_raw(),
+        let now = self.core.clock.now_millis();
+        self.update(record, |next| {
+            next.held = true;
+            next.held_since = Some(now);
+        })?;
+        self.audit(AuditAction::TaskHeld, Some(task_id), None, None, format!("Task {} held", task_id));
+        Ok(())
+    }
+
+    // Unfreeze a held task; if dispatch already skipped it, queue it again. An expedited
+    // task held past the decay window is released as an ordinary, lower-priority task.
+    pub async fn release_task(&self, task_id: u32) -> Result<(), String> {
+        let (priority, expedite) = {
+            let mut records = self.core.records.lock().await;
+            let record = records.get_mut(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+            if !record.held {
+                return Err(format!("Task {} is not held", task_id));
+            }
+            let now = self.core.clock.now_millis();
+            let held_ms = self.update(record, |next| {
+                next.held = false;
+                let held_ms = next.held_since.take().map_or(0, |since| now.saturating_sub(since));
+                if self.core.expedite_decay.is_some_and(|decay| decay.apply(&mut next.task, held_ms)) {
+                    tracing::info!(task_id, held_ms, priority = next.task.priority, "expedited task held too long; released as routine");
+                }
+                held_ms
+            })?;
+            self.audit(AuditAction::TaskReleased, Some(task_id), None, None, format!("Task {} released after {}ms", task_id, held_ms));
+            (record.task.priority, record.task.expedite)
+        };
+        let skipped = self.core.held.lock().await.remove(&task_id);
+        match skipped {
+            Some(task) => self.dispatch(Task { priority, expedite, ..task }).await,
+            None => Ok(()),
         }
-    };
+    }
+
+    // Withdraw a task. A pending task (queued, held, or parked behind its mission) is
+    // cancelled at once; an assigned or running task is marked for cooperative cancellation,
+    // its robot is sent an abort when a transport is configured, and it becomes Cancelled
+    // when the robot next reports. Returns the task's state after the call.
+    pub async fn cancel_task(&self, task_id: u32) -> Result<TaskState, String> {
+        let state = self.withdraw(task_id).await?;
+        let detail = match state {
+            TaskState::Cancelled => format!("Task {} cancelled", task_id),
+            state => format!("Task {} marked for cancellation while {:?}", task_id, state),
+        };
+        self.audit(AuditAction::TaskCancelled, Some(task_id), None, None, detail);
+        Ok(state)
+    }
 
-    let capabilities: Vec<String> = unsafe {
-        if capabilities_json.is_null() {
-            return CString::new("Error: Null capabilities JSON").unwrap().into_raw();
+    async fn withdraw(&self, task_id: u32) -> Result<TaskState, String> {
+        let (robots, state) = {
+            let mut records = self.core.records.lock().await;
+            let record = records.get_mut(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+            match record.state {
+                TaskState::Pending => (Vec::new(), TaskState::Pending),
+                TaskState::Suspended => {
+                    let robot_id = record.attempts.last().and_then(|a| a.robot_id.clone());
+                    drop(records);
+                    self.transition(task_id, TaskState::Cancelled, ReasonCode::Cancelled, "Cancelled while suspended".to_string()).await;
+                    if let Some(robot_id) = robot_id.filter(|_| self.core.dispatcher.is_some()) {
+                        if let Err(e) = self.send_control(&robot_id, ControlCommand::Abort { task_id }).await {
+                            tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send abort");
+                        }
+                    }
+                    return Ok(TaskState::Cancelled);
+                }
+                state if state.is_active() && record.cancel_requested => return Ok(state),
+                TaskState::Assigned | TaskState::Running => {
+                    self.update(record, |next| next.cancel_requested = true)?;
+                    // Every member of a coalition stops, not just its leader
+                    let robots = if record.coalition_members.is_empty() {
+                        record.attempts.last().and_then(|a| a.robot_id.clone()).into_iter().collect()
+                    } else {
+                        record.coalition_members.clone()
+                    };
+                    (robots, record.state)
+                }
+                state => return Err(format!("{}: task {} is {:?} and can no longer be cancelled", CONFLICT_ERROR, task_id, state)),
+            }
+        };
+        if robots.is_empty() {
+            self.core.held.lock().await.remove(&task_id);
+            self.core.missions.lock().await.withdraw(task_id);
+            self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).withdraw(task_id);
+            self.core.delayed.lock().unwrap_or_else(|e| e.into_inner()).withdraw(task_id);
+            self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).withdraw(task_id);
+            let unblocked = self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).withdraw(task_id);
+            self.dispatch_released(unblocked);
+            self.core.coalitions.lock().unwrap_or_else(|e| e.into_inner()).withdraw(task_id);
+            self.transition(task_id, TaskState::Cancelled, ReasonCode::Cancelled, "Cancelled while pending".to_string()).await;
+            return Ok(TaskState::Cancelled);
         }
-        match CStr::from_ptr(capabilities_json).to_str() {
-            Ok(s) => match serde_json::from_str(s) {
-                Ok(caps) => caps,
-                Err(e) => return CString::new(format!("Error: JSON parsing failed: {}", e)).unwrap().into_raw(),
-            },
-            Err(_) => return CString::new("Error: Invalid capabilities JSON").unwrap().into_raw(),
+        if self.core.dispatcher.is_some() {
+            for robot_id in robots {
+                if let Err(e) = self.send_control(&robot_id, ControlCommand::Abort { task_id }).await {
+                    tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send abort");
+                }
+            }
         }
-    };
+        Ok(state)
+    }
 
-    let runtime = match tokio::runtime::Runtime::new() {
-        Ok(rt) => rt,
-        Err(e) => return CString::new(format!("Error: Tokio runtime creation failed: {}", e)).unwrap().into_raw(),
-    };
+    // Force a pending task onto a specific robot, bypassing routing and the cost model. The
+    // robot must still have the task's required capabilities and be certified for its type.
+    // Recorded in the audit log.
+    pub async fn pin_task(&self, task_id: u32, robot_id: &str) -> Result<(), String> {
+        let caps = self.core.capabilities.lock().await;
+        let robot_caps = caps.get(robot_id).ok_or_else(|| format!("Unknown robot: {}", robot_id))?;
+        if self.is_draining(robot_id) {
+            return Err(format!("Robot {} is draining and takes no new tasks", robot_id));
+        }
+        let mut records = self.core.records.lock().await;
+        let record = records.get_mut(&task_id).ok_or_else(|| format!("Unknown task: {}", task_id))?;
+        if record.state != TaskState::Pending {
+            return Err(format!("Task {} is {:?}; only pending tasks can be pinned", task_id, record.state));
+        }
+        if !record.task.required_capabilities.iter().all(|c| robot_caps.contains(c)) {
+            return Err(format!("Robot {} lacks required capabilities: {:?}", robot_id, record.task.required_capabilities));
+        }
+        self.check_compatibility(&record.task.task_type, robot_id)?;
+        self.check_zone(&record.task, robot_id)?;
+        let previous_robot_id = self.update(record, |next| {
+            if let Some(attempt) = next.attempts.last_mut() {
+                attempt.robot_id = Some(robot_id.to_string());
+            }
+            next.pinned = true;
+            next.task.robot_id.replace(robot_id.to_string())
+        })?;
+        let detail = format!("Task {} pinned to robot {}", task_id, robot_id);
+        self.audit(AuditAction::ManualOverride, Some(task_id), Some(robot_id.to_string()), previous_robot_id, detail);
+        Ok(())
+    }
 
-    let result = runtime.block_on(async {
-        SCHEDULER.register_robot(robot_id, capabilities).await
-    });
+    // Append an entry to the audit log as this handle's actor, writing it through to the store
+    pub(crate) fn audit(&self, action: AuditAction, task_id: Option<u32>, robot_id: Option<String>, previous_robot_id: Option<String>, detail: String) {
+        let entry = AuditEntry {
+            at: self.core.clock.now_millis(),
+            action,
+            actor: self.actor.as_deref().map(str::to_string),
+            task_id,
+            robot_id,
+            previous_robot_id,
+            detail,
+        };
+        if let Err(e) = self.core.store.append_audit(&entry) {
+            tracing::warn!(action = ?entry.action, detail = %entry.detail, error = %e, "could not persist audit entry");
+        }
+        self.core.audit.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+    }
 
-    match result {
-        Ok(()) => CString::new("Success").unwrap().into_raw(),
-        Err(e) => CString::new(format!("Error: {}", e)).unwrap().into_raw(),
+    // Recorded actions, oldest first; all of them or those for one task
+    pub fn audit_log(&self, task_id: Option<u32>) -> Vec<AuditEntry> {
+        self.query_audit(&AuditQuery { task_id, ..Default::default() })
+    }
+
+    // Recorded actions matching `query`, oldest first, from those kept in memory
+    pub fn query_audit(&self, query: &AuditQuery) -> Vec<AuditEntry> {
+        self.core.audit.lock().unwrap_or_else(|e| e.into_inner()).query(query)
+    }
+
+    // One page of finished tasks matching `filter`, most recently finished first: up to
+    // `limit` of them (at most MAX_HISTORY_PAGE), continuing from a previous page's
+    // `next_cursor` or from the newest when None
+    pub fn list_task_history(&self, filter: &HistoryFilter, limit: usize, cursor: Option<u64>) -> HistoryPage {
+        self.core.history.lock().unwrap_or_else(|e| e.into_inner()).list(filter, limit, cursor)
+    }
+
+    // Snapshot the robot registry and pending queue under a new name
+    pub async fn create_checkpoint(&self, name: &str) -> Result<CheckpointInfo, String> {
+        if name.is_empty() {
+            return Err("Checkpoint name must not be empty".to_string());
+        }
+        if self.core.checkpoints.lock().await.contains_key(name) {
+            return Err(format!("{}: checkpoint {} already exists", CONFLICT_ERROR, name));
+        }
+        let robots = self.core.capabilities.lock().await.clone();
+        let mut pending: Vec<TaskRecord> =
+            self.core.records.lock().await.values().filter(|r| r.state == TaskState::Pending).cloned().collect();
+        pending.sort_by_key(|r| r.task.id);
+        let checkpoint = Checkpoint { name: name.to_string(), created_at: self.core.clock.now_millis(), robots, pending };
+        let info = checkpoint.info();
+        let mut checkpoints = self.core.checkpoints.lock().await;
+        if checkpoints.contains_key(name) {
+            return Err(format!("{}: checkpoint {} already exists", CONFLICT_ERROR, name));
+        }
+        checkpoints.insert(name.to_string(), checkpoint);
+        Ok(info)
+    }
+
+    // Roll the registry and pending queue back to a checkpoint. Tasks submitted since stay
+    // queued; tasks that ran or completed since can't be rolled back and are skipped.
+    pub async fn restore_checkpoint(&self, name: &str) -> Result<RestoreReport, String> {
+        let checkpoint = self
+            .core
+            .checkpoints
+            .lock()
+            .await
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown checkpoint: {}", name))?;
+        let mut report = RestoreReport { robots: checkpoint.robots.len(), ..Default::default() };
+        {
+            let mut caps = self.core.capabilities.lock().await;
+            for (robot_id, capabilities) in &checkpoint.robots {
+                self.core.store.save_robot(robot_id, capabilities)?;
+            }
+            *caps = checkpoint.robots.clone();
+        }
+        for saved in checkpoint.pending {
+            let task_id = saved.task.id;
+            let state = self.core.records.lock().await.get(&task_id).map(|r| (r.state, r.held));
+            match state {
+                Some((TaskState::Pending, held)) if held != saved.held => {
+                    if saved.held {
+                        self.hold_task(task_id).await?;
+                    } else {
+                        self.release_task(task_id).await?;
+                    }
+                    report.hold_reset.push(task_id);
+                }
+                Some((TaskState::Pending, _)) => {}
+                Some((TaskState::Failed | TaskState::Expired, _)) | None => {
+                    let detail = format!("Restored from checkpoint {}", name);
+                    self.requeue(saved, ReasonCode::RestoredFromCheckpoint, detail).await?;
+                    report.requeued.push(task_id);
+                }
+                Some(_) => report.skipped.push(task_id),
+            }
+        }
+        Ok(report)
     }
-}
 
-// FFI function to schedule a task
-#[no_mangle]
-pub extern "C" fn schedule_task_ffi(task_json: *const c_char) -> *mut c_char {
-    let task_json = unsafe {
-        if task_json.is_null() {
-            return CString::new("Error: Null task JSON").unwrap().into_raw();
+    // Checkpoints in creation order
+    pub async fn list_checkpoints(&self) -> Vec<CheckpointInfo> {
+        let mut infos: Vec<CheckpointInfo> = self.core.checkpoints.lock().await.values().map(Checkpoint::info).collect();
+        infos.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.name.cmp(&b.name)));
+        infos
+    }
+
+    pub async fn delete_checkpoint(&self, name: &str) -> Result<(), String> {
+        self.core.checkpoints.lock().await.remove(name).map(|_| ()).ok_or_else(|| format!("Unknown checkpoint: {}", name))
+    }
+
+    // Put a task back in the queue as a new attempt, with its saved definition and hold flag
+    pub(crate) async fn requeue(&self, saved: TaskRecord, reason: ReasonCode, detail: String) -> Result<(), String> {
+        let task = saved.task.clone();
+        let event = {
+            let mut records = self.core.records.lock().await;
+            // Logged before it replaces the current record, if any
+            let mut record = match records.get(&task.id) {
+                Some(record) => record.clone(),
+                None => TaskRecord { attempts: Vec::new(), ..saved.clone() },
+            };
+            let transition = Transition {
+                from: (!record.attempts.is_empty()).then_some(record.state),
+                to: TaskState::Pending,
+                reason,
+                detail,
+                at: self.core.clock.now_millis(),
+            };
+            record.task = task.clone();
+            record.state = TaskState::Pending;
+            record.held = saved.held;
+            record.held_since = saved.held_since;
+            record.marks = LatencyMarks::default();
+            record.phases = None;
+            record.attempts.push(Attempt {
+                number: record.attempts.len() as u32 + 1,
+                robot_id: task.robot_id.clone(),
+                transitions: vec![transition.clone()],
+            });
+            self.persist(&mut record)?;
+            self.core.submitters.lock().unwrap_or_else(|e| e.into_inner()).transitioned(&task, transition.from, TaskState::Pending);
+            let event = TaskEvent::new(&task, record.attempts.last().unwrap(), transition);
+            records.insert(task.id, record);
+            event
+        };
+        self.publish(event);
+        self.admit(task).await
+    }
+
+    // Register robot capabilities
+    pub async fn register_robot(&self, robot_id: String, capabilities: Vec<String>) -> Result<(), String> {
+        if self.core.ready_checks.is_some() {
+            return self.start_ready_check(robot_id, capabilities).await;
         }
-        match CStr::from_ptr(task_json).to_str() {
-            Ok(s) => s,
-            Err(_) => return CString::new("Error: Invalid task JSON").unwrap().into_raw(),
+        self.admit_robot(robot_id, capabilities).await
+    }
+
+    // Add a robot to the fleet, making it eligible for assignment
+    async fn admit_robot(&self, robot_id: String, capabilities: Vec<String>) -> Result<(), String> {
+        let replaced = {
+            let mut caps = self.core.capabilities.lock().await;
+            let replaced = caps.contains_key(&robot_id);
+            if replaced && self.core.duplicate_robot_policy == DuplicateRobotPolicy::Reject {
+                return Err(format!("{}: robot {} already registered", CONFLICT_ERROR, robot_id));
+            }
+            self.core.store.save_robot(&robot_id, &capabilities)?;
+            caps.insert(robot_id.clone(), capabilities.clone());
+            self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).remove(&robot_id);
+            replaced
+        };
+        self.robot_heartbeat(&robot_id);
+        if replaced {
+            self.replace_robot_session(&robot_id, &capabilities).await;
+        }
+        let detail = format!("Robot {} {} with capabilities {:?}", robot_id, if replaced { "re-registered" } else { "registered" }, capabilities);
+        self.audit(AuditAction::RobotRegistered, None, Some(robot_id.clone()), None, detail);
+        if self.core.steal_policy == StealPolicy::Aggressive && !self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).is_empty() {
+            self.share_backlog(robot_id);
+        }
+        self.retry_coalitions();
+        Ok(())
+    }
+
+    // Add and remove capabilities of a registered robot, e.g. when it picks up a tool or a
+    // sensor fails, returning the updated set. Tasks queued for its slots that now need a
+    // capability it lacks go back to the assignment engine; running tasks are left alone. A
+    // robot that gained capabilities may take over queued work from busier robots.
+    pub async fn update_robot_capabilities(&self, robot_id: &str, add: Vec<String>, remove: Vec<String>) -> Result<Vec<String>, String> {
+        if let Some(both) = add.iter().find(|c| remove.contains(c)) {
+            return Err(format!("Capability {} is both added and removed", both));
+        }
+        if add.iter().any(String::is_empty) {
+            return Err("Capability names must not be empty".to_string());
+        }
+        let capabilities = {
+            let mut caps = self.core.capabilities.lock().await;
+            let current = caps.get(robot_id).ok_or_else(|| format!("Unknown robot: {}", robot_id))?;
+            let mut updated: Vec<String> = current.iter().filter(|c| !remove.contains(c)).cloned().collect();
+            for capability in &add {
+                if !updated.contains(capability) {
+                    updated.push(capability.clone());
+                }
+            }
+            self.core.store.save_robot(robot_id, &updated)?;
+            caps.insert(robot_id.to_string(), updated.clone());
+            updated
+        };
+        tracing::info!(robot_id = %robot_id, ?capabilities, "robot capabilities updated");
+        let lost = |task: &Task| !task.required_capabilities.iter().all(|c| capabilities.contains(c));
+        self.requeue_bound(robot_id, lost).await;
+        let idle = {
+            let records = self.core.records.lock().await;
+            active_per_robot(&records).get(robot_id).copied().unwrap_or(0) < self.robot_slots(robot_id)
+        };
+        let queued = !self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).is_empty();
+        if !add.is_empty() && idle && queued && self.core.steal_policy != StealPolicy::Off {
+            self.share_backlog(robot_id.to_string());
+        }
+        Ok(capabilities)
+    }
+
+    // Stop giving a robot new work while its in-flight tasks finish. Tasks queued for its
+    // slots, or bound to it and not yet dispatched, go back to the assignment engine.
+    // Registering the robot again returns it to service.
+    pub async fn drain_robot(&self, robot_id: &str) -> Result<(), String> {
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string());
+        self.audit(AuditAction::RobotDrained, None, Some(robot_id.to_string()), None, format!("Robot {} draining", robot_id));
+        self.requeue_bound(robot_id, |_| true).await;
+        Ok(())
+    }
+
+    // Whether a draining robot has no tasks left in flight or suspended
+    pub async fn is_drained(&self, robot_id: &str) -> bool {
+        if !self.is_draining(robot_id) {
+            return false;
+        }
+        let records = self.core.records.lock().await;
+        !active_per_robot(&records).contains_key(robot_id) && self.core.suspensions.lock().unwrap_or_else(|e| e.into_inner()).count(robot_id) == 0
+    }
+
+    // Take a robot out of service at once: its registration is removed, tasks it has in
+    // flight or suspended fail, and tasks bound to it that haven't started go back to the assignment
+    // engine. Drain the robot first to let in-flight tasks finish.
+    pub async fn deregister_robot(&self, robot_id: &str) -> Result<(), String> {
+        {
+            let mut caps = self.core.capabilities.lock().await;
+            if !caps.contains_key(robot_id) {
+                return Err(format!("Unknown robot: {}", robot_id));
+            }
+            self.core.store.remove_robot(robot_id)?;
+            caps.remove(robot_id);
+        }
+        self.audit(AuditAction::RobotDeregistered, None, Some(robot_id.to_string()), None, format!("Robot {} deregistered", robot_id));
+        // Kept draining until its queue is emptied, so nothing parks there in between
+        self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string());
+        self.requeue_bound(robot_id, |_| true).await;
+        let in_flight: Vec<u32> = {
+            let records = self.core.records.lock().await;
+            let mut in_flight: Vec<u32> = records
+                .values()
+                .filter(|r| r.state.is_active() || r.state == TaskState::Suspended)
+                .filter(|r| r.attempts.last().and_then(|a| a.robot_id.as_deref()) == Some(robot_id))
+                .map(|r| r.task.id)
+                .collect();
+            in_flight.sort();
+            in_flight
+        };
+        for task_id in in_flight {
+            let detail = format!("Robot {} deregistered", robot_id);
+            self.transition(task_id, TaskState::Failed, ReasonCode::FailedRobotDeregistered, detail).await;
+        }
+        self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).remove(robot_id);
+        Ok(())
+    }
+
+    pub(crate) fn is_draining(&self, robot_id: &str) -> bool {
+        self.core.draining.lock().unwrap_or_else(|e| e.into_inner()).contains(robot_id)
+    }
+
+    // Hand the tasks queued for a robot's slots that `take` accepts back to the assignment
+    // engine, clearing the binding (and any pin) on their records
+    async fn requeue_bound(&self, robot_id: &str, take: impl Fn(&Task) -> bool) {
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut records = self.core.records.lock().await;
+        let released = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).release(robot_id, policy.as_ref(), take);
+        let released: Vec<Task> = released
+            .into_iter()
+            .map(|task| {
+                if let Some(record) = records.get_mut(&task.id) {
+                    self.unbind(record);
+                }
+                Task { robot_id: None, ..task }
+            })
+            .collect();
+        drop(records);
+        if !released.is_empty() {
+            tracing::debug!(robot_id = %robot_id, tasks = released.len(), "requeueing tasks the robot can no longer take");
+        }
+        self.dispatch_released(released);
+    }
+
+    // Clear a pending task's robot so the assignment engine places it again. A failed write
+    // keeps the old binding on the record until the engine places the task.
+    fn unbind(&self, record: &mut TaskRecord) {
+        let _ = self.update(record, |next| {
+            next.task.robot_id = None;
+            next.pinned = false;
+            if let Some(attempt) = next.attempts.last_mut() {
+                attempt.robot_id = None;
+            }
+        });
+    }
+
+    // Deliver a self-test to a newly registered robot; the robot joins the fleet once it
+    // reports success within the ready-check timeout
+    async fn start_ready_check(&self, robot_id: String, capabilities: Vec<String>) -> Result<(), String> {
+        let (Some(checks), Some(dispatcher)) = (&self.core.ready_checks, &self.core.dispatcher) else {
+            return Err("Ready checks need a robot transport".to_string());
+        };
+        if self.core.capabilities.lock().await.contains_key(&robot_id)
+            && self.core.duplicate_robot_policy == DuplicateRobotPolicy::Reject
+        {
+            return Err(format!("{}: robot {} already registered", CONFLICT_ERROR, robot_id));
+        }
+        let (self_test, timeout) = {
+            let records = self.core.records.lock().await;
+            let mut checks = checks.lock().unwrap_or_else(|e| e.into_inner());
+            if checks.is_verifying(&robot_id) {
+                return Err(format!("{}: robot {} is already running its ready check", CONFLICT_ERROR, robot_id));
+            }
+            (checks.start(&robot_id, capabilities, |id| records.contains_key(&id)), checks.config.timeout)
+        };
+        let task_id = self_test.id;
+        if let Err(e) = dispatcher.assign(self, &robot_id, self_test).await {
+            self.finish_ready_check(task_id, Err(e.clone())).await;
+            return Err(e);
+        }
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            let reason = format!("Self-test not answered within {} ms", timeout.as_millis());
+            scheduler.finish_ready_check(task_id, Err(reason)).await;
+        });
+        Ok(())
+    }
+
+    fn is_self_test(&self, task_id: u32) -> bool {
+        self.core.ready_checks.as_ref().is_some_and(|c| c.lock().unwrap_or_else(|e| e.into_inner()).is_self_test(task_id))
+    }
+
+    // Resolve a self-test: admit the robot on success, keep it out of the fleet otherwise
+    async fn finish_ready_check(&self, task_id: u32, result: Result<(), String>) {
+        let Some(checks) = &self.core.ready_checks else {
+            return;
+        };
+        let Some(check) = checks.lock().unwrap_or_else(|e| e.into_inner()).finish(task_id, &result) else {
+            return;
+        };
+        if let Err(reason) = result {
+            tracing::warn!(robot_id = %check.robot_id, reason = %reason, "robot failed its ready check");
+            return;
+        }
+        if let Err(e) = self.admit_robot(check.robot_id.clone(), check.capabilities).await {
+            tracing::warn!(robot_id = %check.robot_id, error = %e, "robot passed its ready check but was not admitted");
+            checks.lock().unwrap_or_else(|e| e.into_inner()).fail(&check.robot_id, e);
+        }
+    }
+
+    // Whether a robot is eligible for work, still verifying, or failed its ready check;
+    // None for robots never registered
+    pub async fn robot_readiness(&self, robot_id: &str) -> Option<RobotReadiness> {
+        let checked = self
+            .core
+            .ready_checks
+            .as_ref()
+            .and_then(|c| c.lock().unwrap_or_else(|e| e.into_inner()).readiness(robot_id));
+        match checked {
+            Some(readiness) => Some(readiness),
+            None => self.core.capabilities.lock().await.contains_key(robot_id).then_some(RobotReadiness::Ready),
+        }
+    }
+
+    // Declare how many tasks a registered robot can execute in parallel (e.g. 2 for a
+    // dual-arm robot); robots that never declare have one slot
+    pub async fn set_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String> {
+        if slots == 0 {
+            return Err("A robot needs at least one task slot".to_string());
+        }
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.core.store.save_robot_slots(robot_id, slots)?;
+        let previous = self.core.robot_slots.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string(), slots);
+        // Hand newly opened slots to the robot's best queued tasks
+        let opened = slots.saturating_sub(previous.unwrap_or(1)) as usize;
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+        let now = self.core.clock.now_millis();
+        let released: Vec<Task> = std::iter::from_fn(|| queues.pop(robot_id, policy.as_ref(), now)).take(opened).collect();
+        drop(queues);
+        self.dispatch_released(released);
+        Ok(())
+    }
+
+    // Holder and waiters of a task mutex group
+    pub fn mutex_group_status(&self, group: &str) -> MutexGroupStatus {
+        self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).status(group)
+    }
+
+    // Current holder and waiters of an exclusive resource
+    pub fn resource_status(&self, resource: &str) -> ResourceStatus {
+        self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).status(resource)
+    }
+
+    // Tasks waiting for their not_before time
+    pub fn delayed_count(&self) -> usize {
+        self.core.delayed.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    // Send delayed tasks whose start time has come to the dispatch queue; called every timer
+    // tick by the workers
+    pub(crate) fn release_delayed(&self) {
+        let now = self.core.clock.now_millis();
+        let due = self.core.delayed.lock().unwrap_or_else(|e| e.into_inner()).advance(now);
+        self.dispatch_released(due);
+    }
+
+    // Projected slot availability of every registered robot from the estimated durations of
+    // its running and queued tasks, by robot ID
+    pub async fn capacity_plan(&self) -> Vec<RobotCapacity> {
+        let robot_ids: Vec<String> = self.core.capabilities.lock().await.keys().cloned().collect();
+        let records = self.core.records.lock().await;
+        let queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+        let profiles = self.core.profiles.lock().unwrap_or_else(|e| e.into_inner());
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let now = self.core.clock.now_millis();
+        let ends = capacity::running_ends(&records, &profiles, now);
+        let mut plan: Vec<RobotCapacity> = robot_ids
+            .into_iter()
+            .map(|robot_id| {
+                let queued: Vec<u64> = queues.queued(&robot_id, policy.as_ref()).iter().map(|t| capacity::estimate(t, Some(&robot_id), &profiles)).collect();
+                let running = ends.get(robot_id.as_str()).map_or(&[][..], Vec::as_slice);
+                let slots = self.robot_slots(&robot_id);
+                let free = capacity::project(now, slots, running, &queued);
+                RobotCapacity {
+                    slots,
+                    running: running.len(),
+                    queued: queued.len(),
+                    next_free_at: free.first().copied().unwrap_or(now),
+                    idle_at: free.last().copied().unwrap_or(now),
+                    robot_id,
+                }
+            })
+            .collect();
+        plan.sort_by(|a, b| a.robot_id.cmp(&b.robot_id));
+        plan
+    }
+
+    // Tasks waiting for a free slot, per robot with a backlog
+    pub fn robot_queue_depths(&self) -> BTreeMap<String, usize> {
+        self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).depths()
+    }
+
+    // Parallel task slots of a robot
+    pub fn robot_slots(&self, robot_id: &str) -> u32 {
+        self.core.robot_slots.lock().unwrap_or_else(|e| e.into_inner()).get(robot_id).copied().unwrap_or(1)
+    }
+
+    // Declare a registered robot's hardware model and firmware version, e.g. after a
+    // firmware update; restricted task types only go to certified models and firmware
+    pub async fn set_robot_model(&self, robot_id: &str, model: RobotModel) -> Result<(), String> {
+        model.validate()?;
+        if !self.core.capabilities.lock().await.contains_key(robot_id) {
+            return Err(format!("Unknown robot: {}", robot_id));
+        }
+        self.core.store.save_robot_model(robot_id, &model)?;
+        self.core.robot_models.lock().unwrap_or_else(|e| e.into_inner()).insert(robot_id.to_string(), model);
+        Ok(())
+    }
+
+    // Declared model and firmware of a robot
+    pub fn robot_model(&self, robot_id: &str) -> Option<RobotModel> {
+        self.core.robot_models.lock().unwrap_or_else(|e| e.into_inner()).get(robot_id).cloned()
+    }
+
+    // Replace the compatibility matrix without a restart; the old matrix stays in force if
+    // the new one is invalid. Queued tasks are checked against the new matrix at dispatch.
+    pub fn reload_compatibility(&self, raw: &str) -> Result<(), String> {
+        let matrix = CompatibilityMatrix::from_json(raw)?;
+        *self.core.compatibility.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(matrix);
+        Ok(())
+    }
+
+    // Which registered robots can run a task type under the current matrix and declared
+    // firmware, and why the others can't
+    pub async fn compatible_robots(&self, task_type: &str) -> CompatibilityReport {
+        let mut robots: Vec<String> = self.core.capabilities.lock().await.keys().cloned().collect();
+        robots.sort();
+        let matrix = self.core.compatibility.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let models = self.core.robot_models.lock().unwrap_or_else(|e| e.into_inner());
+        let mut report = CompatibilityReport { task_type: task_type.to_string(), ..Default::default() };
+        for robot_id in robots {
+            match matrix.check(task_type, models.get(&robot_id)) {
+                Ok(()) => report.compatible.push(robot_id),
+                Err(reason) => {
+                    report.incompatible.insert(robot_id, reason);
+                }
+            }
+        }
+        report
+    }
+
+    // Whether a robot is certified for a task type under the compatibility matrix
+    pub(crate) fn check_compatibility(&self, task_type: &str, robot_id: &str) -> Result<(), String> {
+        let matrix = self.core.compatibility.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let models = self.core.robot_models.lock().unwrap_or_else(|e| e.into_inner());
+        matrix.check(task_type, models.get(robot_id)).map_err(|e| format!("Robot {} is incompatible: {}", robot_id, e))
+    }
+
+    // Robots eligible for work and their capabilities
+    pub async fn registered_robots(&self) -> HashMap<String, Vec<String>> {
+        self.core.capabilities.lock().await.clone()
+    }
+
+    // Resolve tasks still running on a re-registered robot's old session per the policy
+    async fn replace_robot_session(&self, robot_id: &str, capabilities: &[String]) {
+        let active: Vec<(Task, TaskState)> = {
+            let records = self.core.records.lock().await;
+            let mut active: Vec<(Task, TaskState)> = records
+                .values()
+                .filter(|r| r.state.is_active())
+                .filter(|r| r.attempts.last().and_then(|a| a.robot_id.as_deref()) == Some(robot_id))
+                .map(|r| (r.task.clone(), r.state))
+                .collect();
+            active.sort_by_key(|(t, _)| t.id);
+            active
+        };
+        for (task, state) in active {
+            let migrate = self.core.duplicate_robot_policy == DuplicateRobotPolicy::ReplaceAndMigrate;
+            if !migrate {
+                let detail = format!("Robot {} re-registered; old session aborted", robot_id);
+                self.transition(task.id, TaskState::Failed, ReasonCode::FailedRobotReplaced, detail).await;
+                continue;
+            }
+            if !task.required_capabilities.iter().all(|c| capabilities.contains(c)) {
+                let detail = format!("Robot {} re-registered without required capabilities: {:?}", robot_id, task.required_capabilities);
+                self.transition(task.id, TaskState::Failed, ReasonCode::FailedRobotReplaced, detail).await;
+                continue;
+            }
+            let detail = format!("Robot {} re-registered; assignment re-sent", robot_id);
+            self.transition(task.id, state, ReasonCode::MigratedToNewSession, detail).await;
+            if let Some(dispatcher) = &self.core.dispatcher {
+                let task_id = task.id;
+                if let Err(e) = dispatcher.assign(self, robot_id, task).await {
+                    self.transition(task_id, TaskState::Failed, ReasonCode::FailedRobotError, e).await;
+                }
+            }
+        }
+    }
+
+    // Schedule a task with capability-based prioritization
+    pub async fn schedule_task(&self, task: Task) -> Result<(), String> {
+        let received_at = self.core.clock.now_millis();
+        self.check_accepting()?;
+        let _reservation = self.reserve_id(task.id).await?;
+        self.shed_load(&task)?;
+        let task = self.validate(task).await?;
+        self.enter_queue(&task).await?;
+        let now = self.core.clock.now_millis();
+        if let Err(e) = self.consume_quota(&[&task], now) {
+            self.core.dispatch_gate.leave(task.id);
+            return Err(e);
+        }
+        let (mut record, event) = self.submitted_record(&task);
+        record.marks.received_at = Some(received_at);
+        if let Err(e) = self.persist(&mut record) {
+            self.refund_quota(&[&task], now);
+            self.core.dispatch_gate.leave(task.id);
+            return Err(e);
+        }
+        self.core.records.lock().await.insert(task.id, record);
+        self.publish(event);
+        self.audit(AuditAction::TaskScheduled, Some(task.id), task.robot_id.clone(), None, format!("Task {} ({}) submitted", task.id, task.task_type));
+        self.admit(task).await
+    }
+
+    // Whether a task ID belongs to a record or to a submission still being checked; the
+    // caller holds the records lock
+    pub(crate) fn id_taken(&self, records: &HashMap<u32, TaskRecord>, task_id: u32) -> bool {
+        records.contains_key(&task_id) || self.core.reserved_ids.lock().unwrap_or_else(|e| e.into_inner()).contains(&task_id)
+    }
+
+    // Claim a task ID for a submission, so a concurrent submission of the same ID is
+    // rejected while this one is validated
+    async fn reserve_id(&self, task_id: u32) -> Result<IdReservation<'_>, String> {
+        let records = self.core.records.lock().await;
+        if records.contains_key(&task_id) || !self.core.reserved_ids.lock().unwrap_or_else(|e| e.into_inner()).insert(task_id) {
+            return Err(format!("{}: task {} already exists", CONFLICT_ERROR, task_id));
+        }
+        Ok(IdReservation { ids: &self.core.reserved_ids, task_id })
+    }
+
+    // Apply rules to a submission and validate it, on the validation stage if the builder
+    // enabled one; returns the task as routed
+    async fn validate(&self, mut task: Task) -> Result<Task, String> {
+        let Some(stage) = &self.core.validation else {
+            self.check_submission(&mut task).await?;
+            return Ok(task);
+        };
+        let (reply, verdict) = oneshot::channel();
+        stage.send(ValidationJob { task, reply }).await.map_err(|_| "Validation stage stopped".to_string())?;
+        verdict.await.map_err(|_| "Validation stage stopped".to_string())?
+    }
+
+    // Routing and admission rules, then field and robot checks
+    pub(crate) async fn check_submission(&self, task: &mut Task) -> Result<(), String> {
+        self.apply_rules(task).await?;
+        self.validate_submission(task).await
+    }
+
+    // Re-evaluate the load mode and reject the submission if it is being shed
+    fn shed_load(&self, task: &Task) -> Result<(), String> {
+        let Some(shedder) = &self.core.load_shedder else {
+            return Ok(());
+        };
+        let mut shedder = shedder.lock().unwrap_or_else(|e| e.into_inner());
+        let event = shedder.update(self.queue_depth(), self.core.clock.now_millis());
+        let admitted = shedder.admit(task.priority);
+        drop(shedder);
+        if let Some(event) = event {
+            self.publish_load_mode(event);
+        }
+        admitted
+    }
+
+    // Charge submissions against their submitters' limits and their namespaces' daily
+    // quotas, all or none
+    fn consume_quota(&self, tasks: &[&Task], now: u64) -> Result<(), String> {
+        let mut submitters = self.core.submitters.lock().unwrap_or_else(|e| e.into_inner());
+        submitters.check(tasks, now)?;
+        self.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).consume(&quota_requests(tasks), now)?;
+        submitters.charge(tasks, now);
+        Ok(())
+    }
+
+    // Give back what consume_quota charged at `now` for submissions the store refused
+    fn refund_quota(&self, tasks: &[&Task], now: u64) {
+        let mut submitters = self.core.submitters.lock().unwrap_or_else(|e| e.into_inner());
+        self.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).refund(&quota_requests(tasks), now);
+        submitters.refund(tasks, now);
+    }
+
+    // Replace the queued-task and per-minute limits of a namespace or source; all-None
+    // limits remove them
+    pub fn set_submitter_limits(&self, submitter: Submitter, limits: SubmitterLimits) {
+        let detail = format!("Limits of {} set to {}", submitter, serde_json::to_string(&limits).unwrap_or_default());
+        self.core.submitters.lock().unwrap_or_else(|e| e.into_inner()).set(submitter, limits);
+        self.audit(AuditAction::LimitsChanged, None, None, None, detail);
+    }
+
+    // Limits and current standing of every submitter that has limits
+    pub fn submitter_usage(&self) -> Vec<SubmitterUsage> {
+        let now = self.core.clock.now_millis();
+        self.core.submitters.lock().unwrap_or_else(|e| e.into_inner()).all_usage(now)
+    }
+
+    // Today's submissions and quota for a namespace
+    pub fn quota_usage(&self, namespace: &str) -> QuotaUsage {
+        let now = self.core.clock.now_millis();
+        self.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).usage(namespace, now)
+    }
+
+    // Write quota counters to the store if they changed since the last flush; the
+    // workers call this periodically
+    pub fn flush_quotas(&self) -> Result<(), String> {
+        let Some(counters) = self.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).take_dirty() else {
+            return Ok(());
+        };
+        self.core.store.save_quota_counters(&counters).inspect_err(|_| {
+            self.core.quotas.lock().unwrap_or_else(|e| e.into_inner()).mark_dirty();
+        })
+    }
+
+    // Tasks waiting in the dispatch queue for the execution loop
+    fn queue_depth(&self) -> usize {
+        let ready = self.core.ready_depth.load(std::sync::atomic::Ordering::Relaxed);
+        self.core.tx.max_capacity() - self.core.tx.capacity() + ready
+    }
+
+    // Replace the dispatch ordering; applies to tasks already queued
+    pub fn set_policy(&self, policy: Arc<dyn SchedulingPolicy>) {
+        let detail = format!("Scheduling policy set to {}", policy.name());
+        *self.core.policy.write().unwrap_or_else(|e| e.into_inner()) = policy;
+        self.audit(AuditAction::PolicyChanged, None, None, None, detail);
+    }
+
+    // Switch to a built-in policy by name ("priority_first" or "earliest_deadline_first")
+    pub fn set_policy_by_name(&self, name: &str) -> Result<(), String> {
+        self.set_policy(policy_by_name(name)?);
+        Ok(())
+    }
+
+    pub fn policy_name(&self) -> &'static str {
+        self.core.policy.read().unwrap_or_else(|e| e.into_inner()).name()
+    }
+
+    fn publish_load_mode(&self, event: LoadModeEvent) {
+        tracing::warn!(shedding = event.shedding, reason = %event.reason, "load shedding changed");
+        // Send errors only mean there are no subscribers
+        let _ = self.core.load_events.send(event);
+    }
+
+    fn publish_slo_alert(&self, alert: SloAlert) {
+        let state = if alert.at_risk { "at risk" } else { "recovered" };
+        tracing::warn!(slo = %alert.name, state, burn_rate = alert.burn_rate, "SLO burn rate alert");
+        let severity = if alert.at_risk { Severity::Critical } else { Severity::Info };
+        let summary = format!("SLO {} {}: burn rate {:.2}", alert.name, state, alert.burn_rate);
+        self.raise_alert(severity, "slo", summary, None, None);
+        // Send errors only mean there are no subscribers
+        let _ = self.core.slo_events.send(alert);
+    }
+
+    // Subscribe to SLO at-risk and recovery alerts
+    pub fn subscribe_slo_alerts(&self) -> broadcast::Receiver<SloAlert> {
+        self.core.slo_events.subscribe()
+    }
+
+    // Attainment and burn rate of every configured SLO over its window
+    pub fn slo_status(&self) -> Vec<SloStatus> {
+        let now = self.core.clock.now_millis();
+        self.core.slos.lock().unwrap_or_else(|e| e.into_inner()).status(now)
+    }
+
+    // Subscribe to load-shedding mode changes
+    pub fn subscribe_load_mode(&self) -> broadcast::Receiver<LoadModeEvent> {
+        self.core.load_events.subscribe()
+    }
+
+    // Whether low-priority submissions are currently being shed
+    pub fn is_shedding_load(&self) -> bool {
+        self.core
+            .load_shedder
+            .as_ref()
+            .is_some_and(|s| s.lock().unwrap_or_else(|e| e.into_inner()).is_shedding())
+    }
+
+    // Run routing and admission rules against a submission
+    async fn apply_rules(&self, task: &mut Task) -> Result<(), String> {
+        let rules = self.core.rules.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let shadow = match self.shadow_candidate() {
+            Some(ShadowCandidate::Rules(shadow)) => Some(shadow),
+            _ => None,
+        };
+        if rules.is_empty() && shadow.is_none() {
+            return Ok(());
+        }
+        // Routing only considers robots certified for the task type
+        let robots: HashMap<String, Vec<String>> = self
+            .core
+            .capabilities
+            .lock()
+            .await
+            .iter()
+            .filter(|(id, _)| self.check_compatibility(&task.task_type, id).is_ok() && self.check_zone(task, id).is_ok())
+            .map(|(id, caps)| (id.clone(), caps.clone()))
+            .collect();
+        let now = self.core.clock.now_millis();
+        let mut candidate = task.clone();
+        let result = rules.apply(task, &robots, now);
+        if let Some(shadow) = shadow {
+            let shadow_result = shadow.apply(&mut candidate, &robots, now);
+            let kind = if result.is_ok() == shadow_result.is_ok() { DecisionKind::Routing } else { DecisionKind::Admission };
+            let describe = |task: &Task, result: &Result<(), String>| match result {
+                Ok(()) => format!("admit to {}", task.robot_id.as_deref().unwrap_or("any robot")),
+                Err(e) => format!("reject: {}", e),
+            };
+            self.record_shadow(kind, task.id, describe(task, &result), describe(&candidate, &shadow_result));
+        }
+        result
+    }
+
+    // Evaluate a scheduling policy in shadow for a period: its dispatch choices are
+    // computed against the live queue and compared, but the active policy still decides.
+    // Replaces any running trial.
+    pub fn shadow_policy(&self, policy: Arc<dyn SchedulingPolicy>, duration: std::time::Duration) {
+        self.start_shadow(ShadowCandidate::Policy(policy), duration);
+    }
+
+    pub fn shadow_policy_by_name(&self, name: &str, duration: std::time::Duration) -> Result<(), String> {
+        self.shadow_policy(policy_by_name(name)?, duration);
+        Ok(())
+    }
+
+    // Evaluate a rule set in shadow for a period alongside the live rules
+    pub fn shadow_rules(&self, raw: &str, duration: std::time::Duration) -> Result<(), String> {
+        let rules = RuleEngine::from_json(raw)?;
+        self.start_shadow(ShadowCandidate::Rules(Arc::new(rules)), duration);
+        Ok(())
+    }
+
+    fn start_shadow(&self, candidate: ShadowCandidate, duration: std::time::Duration) {
+        let trial = ShadowTrial::new(candidate, self.core.clock.now_millis(), duration.as_millis() as u64);
+        *self.core.shadow.lock().unwrap_or_else(|e| e.into_inner()) = Some(trial);
+    }
+
+    // Decisions and divergences of the current or last shadow trial
+    pub fn shadow_report(&self) -> Option<ShadowReport> {
+        let now = self.core.clock.now_millis();
+        self.core.shadow.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map(|t| t.report(now))
+    }
+
+    // Stop the shadow trial and return its final report
+    pub fn end_shadow(&self) -> Option<ShadowReport> {
+        let now = self.core.clock.now_millis();
+        let trial = self.core.shadow.lock().unwrap_or_else(|e| e.into_inner()).take();
+        trial.map(|t| t.report(now))
+    }
+
+    // Candidate of the shadow trial, while its period lasts
+    fn shadow_candidate(&self) -> Option<ShadowCandidate> {
+        let now = self.core.clock.now_millis();
+        let shadow = self.core.shadow.lock().unwrap_or_else(|e| e.into_inner());
+        shadow.as_ref().filter(|t| t.is_active(now)).map(|t| t.candidate.clone())
+    }
+
+    fn record_shadow(&self, kind: DecisionKind, task_id: u32, live: String, shadow: String) {
+        let now = self.core.clock.now_millis();
+        let mut trial = self.core.shadow.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(trial) = trial.as_mut().filter(|t| t.is_active(now)) {
+            trial.record(now, kind, task_id, live, shadow);
+        }
+    }
+
+    // Replace the admission and routing rules without a restart; the old rules stay in
+    // force if the new set fails to compile
+    pub fn reload_rules(&self, raw: &str) -> Result<(), String> {
+        let rules = RuleEngine::from_json(raw)?;
+        *self.core.rules.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(rules);
+        self.audit(AuditAction::RulesReloaded, None, None, None, "Admission and routing rules reloaded".to_string());
+        Ok(())
+    }
+
+    // Check a task's own fields and its robot assignment before accepting it
+    async fn validate_submission(&self, task: &Task) -> Result<(), String> {
+        // Set by the scheduler when a robot hands the task off; a submitted one would be forged
+        if task.handoff.is_some() {
+            return Err(format!("Task {} can't be submitted with a hand-off", task.id));
+        }
+        // Likewise the robots that failed it, which a client could pre-seed to steer assignment
+        if !task.excluded_robots.is_empty() {
+            return Err(format!("Task {} can't be submitted with excluded robots", task.id));
+        }
+        if let Some(context) = &task.trace_context {
+            context.validate()?;
+        }
+        if let Some(escalation) = &task.escalation {
+            escalation.validate(task.timeout_ms)?;
+        }
+        if let Some(policy) = &task.deadline_policy {
+            policy.validate()?;
+        }
+        if let (Some(not_before), Some(deadline)) = (task.not_before, task.deadline) {
+            if not_before > deadline {
+                return Err(format!("Task {} may not start until after its deadline", task.id));
+            }
+        }
+        if let Some(window) = &task.window {
+            let estimate = task.estimated_duration_ms.unwrap_or(0);
+            window.validate(estimate)?;
+            if !window.fits(self.core.clock.now_millis(), estimate) {
+                return Err(format!("Task {} can no longer finish inside its time window", task.id));
+            }
+        }
+        task.validate_geometry()?;
+        self.core.frames.validate_task(task)?;
+        self.core.payload_schemas.check(task)?;
+        self.admit_zones(task)?;
+        if self.is_self_test(task.id) {
+            return Err(format!("Task ID {} is in use by a robot self-test", task.id));
+        }
+        if task.mutex_group.as_deref() == Some("") {
+            return Err("Mutex group name must not be empty".to_string());
+        }
+        if task.resources.iter().any(String::is_empty) {
+            return Err("Resource names must not be empty".to_string());
+        }
+        if let Some(coalition) = &task.coalition {
+            coalition.validate()?;
+            if task.preemptible {
+                return Err("Coalition tasks can't be preemptible".to_string());
+            }
+        }
+        if self.mission_cancelled(task.mission_id.as_deref()) {
+            return Err(format!("Mission {} was cancelled", task.mission_id.as_deref().unwrap_or_default()));
+        }
+        if let Some(robot_id) = &task.robot_id {
+            // Hold the capability lock only for the lookup
+            let robot_caps = self.core.capabilities.lock().await.get(robot_id).cloned();
+            let Some(robot_caps) = robot_caps else {
+                if let Some(checks) = &self.core.ready_checks {
+                    match checks.lock().unwrap_or_else(|e| e.into_inner()).readiness(robot_id) {
+                        Some(RobotReadiness::Verifying) => return Err(format!("Robot {} has not passed its ready check", robot_id)),
+                        Some(RobotReadiness::Failed { reason }) => return Err(format!("Robot {} failed its ready check: {}", robot_id, reason)),
+                        _ => {}
+                    }
+                }
+                return Err(format!("Unknown robot: {}", robot_id));
+            };
+            if self.is_draining(robot_id) {
+                return Err(format!("Robot {} is draining and takes no new tasks", robot_id));
+            }
+            if !task.required_capabilities.iter().all(|c| robot_caps.contains(c)) {
+                return Err(format!("Robot {} lacks required capabilities: {:?}", robot_id, task.required_capabilities));
+            }
+            self.check_compatibility(&task.task_type, robot_id)?;
+            self.check_zone(task, robot_id)?;
+        }
+        Ok(())
+    }
+
+    // Fresh Pending record for an accepted task, with its submission event
+    pub(crate) fn submitted_record(&self, task: &Task) -> (TaskRecord, TaskEvent) {
+        let submitted = Transition {
+            from: None,
+            to: TaskState::Pending,
+            reason: ReasonCode::Submitted,
+            detail: "Accepted by scheduler".to_string(),
+            at: self.core.clock.now_millis(),
+        };
+        let record = TaskRecord {
+            task: task.clone(),
+            state: TaskState::Pending,
+            attempts: vec![Attempt {
+                number: 1,
+                robot_id: task.robot_id.clone(),
+                transitions: vec![submitted.clone()],
+            }],
+            held: false,
+            held_since: None,
+            pinned: false,
+            cancel_requested: false,
+            coalition_members: Vec::new(),
+            dead_lettered_at: None,
+            phases: None,
+            deadline_miss: None,
+            version: 0,
+            marks: LatencyMarks::default(),
+        };
+        let event = TaskEvent::new(task, &record.attempts[0], submitted);
+        (record, event)
+    }
+
+    // Dispatch a recorded task now or park it behind its mission's concurrency cap
+    pub(crate) async fn admit(&self, task: Task) -> Result<(), String> {
+        if task.expedite {
+            self.core.metrics.lock().unwrap_or_else(|e| e.into_inner()).record_expedite(task.source.as_deref());
+        }
+        let admission = self.core.missions.lock().await.admit(&task);
+        match admission {
+            Admission::Dispatch => self.dispatch(task).await,
+            Admission::Parked => {
+                // Released when its mission gets an active slot, bypassing the gate
+                self.core.dispatch_gate.leave(task.id);
+                Ok(())
+            }
+        }
+    }
+
+    // Register a task template after checking it, replacing any with the same ID
+    pub fn register_template(&self, template: TaskTemplate) -> Result<(), String> {
+        template.validate()?;
+        self.core.templates.write().unwrap_or_else(|e| e.into_inner()).insert(template.id.clone(), template);
+        Ok(())
+    }
+
+    // Register the JSON Schema that payloads of `task_type` must match, replacing any
+    // earlier one; tasks already accepted are not rechecked
+    pub fn register_payload_schema(&self, task_type: &str, schema: serde_json::Value) -> Result<(), String> {
+        self.core.payload_schemas.register(task_type, schema)
+    }
+
+    // Stop checking payloads of `task_type`; false if it had no schema
+    pub fn remove_payload_schema(&self, task_type: &str) -> bool {
+        self.core.payload_schemas.remove(task_type)
+    }
+
+    pub fn payload_schema(&self, task_type: &str) -> Option<serde_json::Value> {
+        self.core.payload_schemas.get(task_type)
+    }
+
+    // Build a task from a registered template and submit it. `params_json` is an object
+    // with the task's `id` and the template's parameter values. Returns the submitted task.
+    pub async fn instantiate_template(&self, template_id: &str, params_json: &str) -> Result<Task, String> {
+        let params: serde_json::Value = serde_json::from_str(params_json).map_err(|e| format!("Invalid template parameters: {}", e))?;
+        let task = {
+            let templates = self.core.templates.read().unwrap_or_else(|e| e.into_inner());
+            let template = templates.get(template_id).ok_or_else(|| format!("Unknown template: {}", template_id))?;
+            template.instantiate(&params)?
+        };
+        self.schedule_task(task.clone()).await?;
+        Ok(task)
+    }
+
+    // Open a chunked upload for a large mission; tasks without a mission ID join `mission_id`
+    pub async fn begin_upload(&self, mission_id: Option<String>) -> String {
+        let now = self.core.clock.now_millis();
+        self.core.uploads.lock().await.begin(mission_id, now)
+    }
+
+    // Validate and stage one chunk of an upload. A bad chunk is rejected whole and can be
+    // resent; returns the number of tasks staged so far. Tasks are staged as sent and
+    // validated again when the upload is committed.
+    pub async fn upload_chunk(&self, upload_id: &str, mut tasks: Vec<Task>) -> Result<usize, String> {
+        self.check_accepting()?;
+        self.core.uploads.lock().await.prepare_chunk(upload_id, &mut tasks)?;
+        for task in &tasks {
+            self.shed_load(task).map_err(|e| format!("Task {}: {}", task.id, e))?;
+            self.check_submission(&mut task.clone()).await.map_err(|e| format!("Task {}: {}", task.id, e))?;
+        }
+        let records = self.core.records.lock().await;
+        if let Some(task) = tasks.iter().find(|t| self.id_taken(&records, t.id)) {
+            return Err(format!("{}: task {} already exists", CONFLICT_ERROR, task.id));
+        }
+        drop(records);
+        let now = self.core.clock.now_millis();
+        self.core.uploads.lock().await.append(upload_id, tasks, now)
+    }
+
+    // Submit every staged task of an upload at once, through the same checks and queue
+    // admission as schedule_task; a task that fails them discards the whole upload.
+    // Returns the number of tasks submitted.
+    pub async fn commit_upload(&self, upload_id: &str) -> Result<usize, String> {
+        self.check_accepting()?;
+        let staged = self.core.uploads.lock().await.take(upload_id)?;
+        let discarded = |e: String| format!("{}; upload {} discarded", e, upload_id);
+        let mut reservations = Vec::with_capacity(staged.len());
+        for task in &staged {
+            reservations.push(self.reserve_id(task.id).await.map_err(discarded)?);
+        }
+        // Robots, rules and limits may have changed since the chunks were staged
+        let mut tasks = Vec::with_capacity(staged.len());
+        for task in staged {
+            let task_id = task.id;
+            self.shed_load(&task).map_err(|e| discarded(format!("Task {}: {}", task_id, e)))?;
+            tasks.push(self.validate(task).await.map_err(|e| discarded(format!("Task {}: {}", task_id, e)))?);
+        }
+        let leave_all = |tasks: &[Task]| {
+            for task in tasks {
+                self.core.dispatch_gate.leave(task.id);
+            }
+        };
+        for (queued, task) in tasks.iter().enumerate() {
+            if let Err(e) = self.enter_queue(task).await {
+                leave_all(&tasks[..queued]);
+                return Err(discarded(e));
+            }
+        }
+        let batch: Vec<&Task> = tasks.iter().collect();
+        let now = self.core.clock.now_millis();
+        if let Err(e) = self.consume_quota(&batch, now) {
+            leave_all(&tasks);
+            return Err(discarded(e));
+        }
+        // Every record is logged before any is accepted; a failed write takes back the
+        // ones already written and rejects the whole upload
+        let mut submitted: Vec<(TaskRecord, TaskEvent)> = Vec::with_capacity(tasks.len());
+        for task in &tasks {
+            let (mut record, event) = self.submitted_record(task);
+            if let Err(e) = self.persist(&mut record) {
+                for (record, _) in &submitted {
+                    if let Err(e) = self.core.store.remove_task(record.task.id) {
+                        tracing::error!(task_id = record.task.id, error = %e, "could not remove task of a rejected upload");
+                    }
+                }
+                self.refund_quota(&batch, now);
+                leave_all(&tasks);
+                return Err(discarded(e));
+            }
+            submitted.push((record, event));
+        }
+        let mut records = self.core.records.lock().await;
+        let mut events = Vec::with_capacity(tasks.len());
+        for (task, (record, event)) in tasks.iter().zip(submitted) {
+            records.insert(task.id, record);
+            events.push(event);
+            let detail = format!("Task {} ({}) submitted in upload {}", task.id, task.task_type, upload_id);
+            self.audit(AuditAction::TaskScheduled, Some(task.id), task.robot_id.clone(), None, detail);
+        }
+        drop(records);
+        drop(reservations);
+        for event in events {
+            self.publish(event);
+        }
+        // Every task is offered to the queue even if an earlier one could not be; one left
+        // out stays pending in the store, as with schedule_task
+        let count = tasks.len();
+        let mut failed = None;
+        for task in tasks {
+            let task_id = task.id;
+            if let Err(e) = self.admit(task).await {
+                tracing::warn!(task_id, upload_id, error = %e, "could not queue a committed upload task");
+                failed.get_or_insert(e);
+            }
+        }
+        failed.map_or(Ok(count), Err)
+    }
+
+    // Discard an upload and everything staged in it
+    pub async fn abort_upload(&self, upload_id: &str) -> Result<(), String> {
+        self.core.uploads.lock().await.take(upload_id).map(|_| ())
+    }
+
+    // Discard uploads that received no chunk within the upload time-to-live; returns their
+    // IDs. The workers call this periodically.
+    pub async fn expire_uploads(&self) -> Vec<String> {
+        let now = self.core.clock.now_millis();
+        let expired = self.core.uploads.lock().await.expire(now, self.core.upload_ttl);
+        for upload_id in &expired {
+            tracing::info!(upload_id = %upload_id, "discarded an idle upload");
+        }
+        expired
+    }
+
+    // Pick a robot for a task submitted without one (see assignment.rs) and record it on the
+    // task; the task stays unassigned if no registered robot can run it
+    async fn assign_robot(&self, task: &mut Task) {
+        let caps = self.core.capabilities.lock().await;
+        let mut records = self.core.records.lock().await;
+        let active = active_per_robot(&records);
+        let queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+        let profiles = self.core.profiles.lock().unwrap_or_else(|e| e.into_inner());
+        let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let now = self.core.clock.now_millis();
+        // Scorers may weigh deadline slack, so they get projected finishes too
+        let projecting = self.core.assignment.earliest_finish || self.core.assignment_scorer.is_some();
+        let ends = projecting.then(|| capacity::running_ends(&records, &profiles, now));
+        let projected_finish = |robot_id: &str| {
+            let Some(ends) = &ends else {
+                return 0;
+            };
+            let queued: Vec<u64> = queues.queued(robot_id, policy.as_ref()).iter().map(|t| capacity::estimate(t, Some(robot_id), &profiles)).collect();
+            let running = ends.get(robot_id).map_or(&[][..], Vec::as_slice);
+            let free_at = capacity::project(now, self.robot_slots(robot_id), running, &queued)[0];
+            free_at.saturating_add(capacity::estimate(task, Some(robot_id), &profiles))
+        };
+        let locations = self.core.robot_locations.lock().unwrap_or_else(|e| e.into_inner());
+        let distance = |robot_id: &str| {
+            let (from, to) = (locations.get(robot_id)?, task.location.as_ref()?);
+            self.core.distance.distance(from, to, &self.core.frames)
+        };
+        let mut candidates: Vec<Candidate> = caps
+            .iter()
+            .filter(|(id, robot_caps)| {
+                task.required_capabilities.iter().all(|c| robot_caps.contains(c))
+                    && self.check_compatibility(&task.task_type, id).is_ok()
+                    && self.check_zone(task, id).is_ok()
+                    && !self.is_draining(id)
+                    && !task.handed_off_by(id)
+                    && self.breaker_allows(id)
+            })
+            .map(|(id, robot_caps)| Candidate {
+                robot_id: id.clone(),
+                capabilities: robot_caps.clone(),
+                active: active.get(id.as_str()).copied().unwrap_or(0) + queues.depth(id) as u32,
+                slots: self.robot_slots(id),
+                success_rate: profiles.get(id).and_then(|p| p.success_rate()).unwrap_or(1.0),
+                projected_finish: projected_finish(id),
+                distance: distance(id),
+            })
+            .collect();
+        // Robots that failed the task only get it again when no other robot can take it
+        if candidates.iter().any(|c| !task.avoids(&c.robot_id)) {
+            candidates.retain(|c| !task.avoids(&c.robot_id));
+        }
+        drop((queues, profiles, locations));
+        let selected = match &self.core.assignment_scorer {
+            Some(scorer) => best_scored(scorer.as_ref(), task, &candidates),
+            None => self.core.assignment.select(task, &candidates),
+        };
+        let Some(robot_id) = selected else {
+            return;
+        };
+        tracing::debug!(robot_id = %robot_id, candidates = candidates.len(), "selected robot");
+        if let Some(record) = records.get_mut(&task.id) {
+            let bound = self.update(record, |next| {
+                next.task.robot_id = Some(robot_id.clone());
+                if let Some(attempt) = next.attempts.last_mut() {
+                    attempt.robot_id = Some(robot_id.clone());
+                }
+            });
+            if bound.is_err() {
+                return;
+            }
+        }
+        task.robot_id = Some(robot_id);
+    }
+
+    // Send a call for bids to every robot able to run the task and award it when the
+    // bidding window closes
+    async fn open_auction(&self, task: Task) {
+        let Some(auctions) = &self.core.auctions else {
+            return;
+        };
+        let mut invited: Vec<String> = self
+            .core
+            .capabilities
+            .lock()
+            .await
+            .iter()
+            .filter(|(id, robot_caps)| {
+                task.required_capabilities.iter().all(|c| robot_caps.contains(c))
+                    && self.check_compatibility(&task.task_type, id).is_ok()
+                    && self.check_zone(&task, id).is_ok()
+                    && !self.is_draining(id)
+                    && !task.handed_off_by(id)
+                    && self.breaker_allows(id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        // Robots that failed the task are only invited when no other robot can take it
+        if invited.iter().any(|id| !task.avoids(id)) {
+            invited.retain(|id| !task.avoids(id));
+        }
+        invited.sort();
+        let (task_id, task_type) = (task.id, task.task_type.clone());
+        let window = {
+            let mut auctions = auctions.lock().unwrap_or_else(|e| e.into_inner());
+            auctions.open(task, invited.clone());
+            auctions.config.bidding_window
+        };
+        tracing::debug!(invited = invited.len(), "task up for auction");
+        if let Some(dispatcher) = &self.core.dispatcher {
+            for robot_id in &invited {
+                let command = ControlCommand::CallForBids {
+                    task_id,
+                    task_type: task_type.clone(),
+                    bidding_window_ms: window.as_millis() as u64,
+                };
+                if let Err(e) = dispatcher.control(self, robot_id, command).await {
+                    tracing::warn!(robot_id = %robot_id, error = %e, "could not send call for bids");
+                }
+            }
+        }
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            scheduler.close_auction(task_id).await;
+        });
+    }
+
+    // Bind an auctioned task to its winning bidder and queue it again; a task without bids
+    // is queued as it was and announced again
+    async fn close_auction(&self, task_id: u32) {
+        let Some(auctions) = &self.core.auctions else {
+            return;
+        };
+        let Some((mut task, winner)) = auctions.lock().unwrap_or_else(|e| e.into_inner()).close(task_id) else {
+            return;
+        };
+        match winner {
+            Some(bid) => {
+                tracing::info!(task_id, robot_id = %bid.robot_id, cost = bid.cost, eta_ms = bid.eta_ms, "auction awarded");
+                // A pin made during bidding wins over the auction, and a win the store couldn't
+                // record goes back to auction
+                let awarded = match self.core.records.lock().await.get_mut(&task_id) {
+                    Some(record) if !record.pinned => self
+                        .update(record, |next| {
+                            next.task.robot_id = Some(bid.robot_id.clone());
+                            if let Some(attempt) = next.attempts.last_mut() {
+                                attempt.robot_id = Some(bid.robot_id.clone());
+                            }
+                        })
+                        .is_ok(),
+                    _ => true,
+                };
+                if awarded {
+                    task.robot_id = Some(bid.robot_id);
+                }
+            }
+            None => tracing::debug!(task_id, "auction drew no bids; announcing again"),
+        }
+        self.dispatch_released(vec![task]);
+    }
+
+    // Expected execution time of a task: its own estimate, else its robot's average for the
+    // task type, else nothing
+    fn estimated_duration(&self, task: &Task) -> u64 {
+        if let Some(estimate) = task.estimated_duration_ms {
+            return estimate;
+        }
+        let profiles = self.core.profiles.lock().unwrap_or_else(|e| e.into_inner());
+        capacity::estimate(task, task.robot_id.as_deref(), &profiles)
+    }
+
+    // Apply the deadline policy to a task popped after its deadline. Returns whether the task
+    // should still be dispatched now; otherwise it has expired or been queued again.
+    async fn handle_deadline_miss(&self, task: &mut Task, deadline: u64, now: u64) -> bool {
+        let policy = task.deadline_policy.unwrap_or(self.core.deadline_policy);
+        let outcome = policy.resolve(self.core.deadline_hook.as_ref(), task, now);
+        tracing::warn!(deadline, now, ?outcome, "task missed its deadline");
+        match outcome {
+            DeadlinePolicy::Reschedule { extend_ms } => task.deadline = Some(now.saturating_add(extend_ms)),
+            DeadlinePolicy::EscalatePriority { boost } => task.priority = task.priority.saturating_add(boost),
+            _ => {}
+        }
+        if let Some(record) = self.core.records.lock().await.get_mut(&task.id) {
+            // The policy still applies when the store can't record the miss
+            let _ = self.update(record, |next| {
+                next.task.deadline = task.deadline;
+                next.task.priority = task.priority;
+                next.deadline_miss = Some(DeadlineMiss { deadline, missed_at: now, outcome });
+            });
+        }
+        match outcome {
+            DeadlinePolicy::ExecuteAnyway => true,
+            DeadlinePolicy::Reschedule { .. } | DeadlinePolicy::EscalatePriority { .. } => {
+                self.dispatch_released(vec![task.clone()]);
+                false
+            }
+            DeadlinePolicy::Drop | DeadlinePolicy::NotifyCallback => {
+                let detail = format!("Deadline {}ms passed at {}ms", deadline, now);
+                self.transition(task.id, TaskState::Expired, ReasonCode::ExpiredDeadline, detail).await;
+                false
+            }
+        }
+    }
+
+    // Dispatch queued tasks in the order the scheduling policy ranks them, urgent lane first,
+    // until shutdown
+    pub(crate) async fn process_tasks(self, mut rx: mpsc::Receiver<Task>, mut urgent_rx: mpsc::Receiver<Task>) {
+        let ordering = &self.core.queue_ordering;
+        let (mut urgent, mut ready) = (ReadyQueue::new(ordering.clone()), ReadyQueue::new(ordering.clone()));
+        let clock = self.core.clock.clone();
+        let mut shutdown = self.core.shutdown.subscribe();
+        // Queued tasks are left behind on shutdown; their records stay pending in the store
+        while !*shutdown.borrow_and_update() {
+            if urgent.len() + ready.len() == 0 {
+                tokio::select! {
+                    biased;
+                    _ = shutdown.changed() => continue,
+                    Some(task) = urgent_rx.recv() => urgent.push(task, clock.now_millis()),
+                    Some(task) = rx.recv() => ready.push(task, clock.now_millis()),
+                    else => break,
+                }
+            }
+            // Take whatever else is waiting so the policy ranks it too; the lane capacity
+            // still bounds how much is buffered here
+            let now = clock.now_millis();
+            while urgent.len() < urgent_rx.max_capacity() {
+                let Ok(task) = urgent_rx.try_recv() else { break };
+                urgent.push(task, now);
+            }
+            while ready.len() < rx.max_capacity() {
+                let Ok(task) = rx.try_recv() else { break };
+                ready.push(task, now);
+            }
+            let policy = self.core.policy.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let shadow_pick = match self.shadow_candidate() {
+                Some(ShadowCandidate::Policy(shadow)) => urgent.peek(shadow.as_ref(), now).or_else(|| ready.peek(shadow.as_ref(), now)),
+                _ => None,
+            };
+            let Some(task) = urgent.pop(policy.as_ref(), now).or_else(|| ready.pop(policy.as_ref(), now)) else {
+                continue;
+            };
+            self.core.dispatch_gate.leave(task.id);
+            if let Some(pick) = shadow_pick {
+                self.record_shadow(DecisionKind::Dispatch, task.id, format!("task {}", task.id), format!("task {}", pick));
+            }
+            self.core.ready_depth.store(urgent.len() + ready.len(), std::sync::atomic::Ordering::Relaxed);
+            let span = tracing::info_span!("task", task_id = task.id, task_type = %task.task_type, robot_id = tracing::field::Empty);
+            self.execute(task).instrument(span).await;
+        }
+    }
+
+    // Check a popped task against holds, start times, deadlines, mutex groups, and robot
+    // slots, then hand it to its robot (or simulate it without a transport). Runs inside the
+    // task's span.
+    async fn execute(&self, mut task: Task) {
+        let mut deadline_handled = false;
+        {
+            // Check and park under the records lock so a concurrent release can't miss it
+            let records = self.core.records.lock().await;
+            // Cancelled, or dropped from a full queue
+            if records.get(&task.id).is_some_and(|r| r.state.is_terminal()) {
+                return;
+            }
+            if self.mission_cancelled(task.mission_id.as_deref()) {
+                drop(records);
+                self.transition(task.id, TaskState::Cancelled, ReasonCode::Cancelled, "Mission cancelled".to_string()).await;
+                return;
+            }
+            if records.get(&task.id).is_some_and(|r| r.held) {
+                // A held task doesn't keep a mutex group or resources it was handed from others
+                if let Some(group) = &task.mutex_group {
+                    let next = self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).release(group, task.id);
+                    self.dispatch_released(next.into_iter().collect());
+                }
+                let granted = self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).release(task.id);
+                self.dispatch_released(granted);
+                tracing::debug!("task is held; parked until released");
+                self.core.held.lock().await.insert(task.id, task);
+                return;
+            }
+            // A pin or decay made after queueing overrides the queued copy
+            if let Some(record) = records.get(&task.id) {
+                if record.pinned {
+                    task.robot_id = record.task.robot_id.clone();
+                }
+                task.priority = record.task.priority;
+                task.expedite = record.task.expedite;
+                task.deadline = record.task.deadline;
+                // A miss already handled doesn't count again for the same deadline
+                deadline_handled = record.deadline_miss.is_some_and(|m| Some(m.deadline) == task.deadline);
+            }
+        }
+        if task.start_after().is_some_and(|t| t > self.core.clock.now_millis()) {
+            tracing::debug!(start_after = task.start_after(), "task is delayed; held until its start time");
+            self.core.delayed.lock().unwrap_or_else(|e| e.into_inner()).insert(task);
+            return;
+        }
+        if let Some(deadline) = task.deadline.filter(|_| !deadline_handled) {
+            let now = self.core.clock.now_millis();
+            if now > deadline && !self.handle_deadline_miss(&mut task, deadline, now).await {
+                return;
+            }
+        }
+        if let Some(window) = task.window {
+            let (now, estimate) = (self.core.clock.now_millis(), self.estimated_duration(&task));
+            if !window.fits(now, estimate) {
+                tracing::warn!(latest_finish = window.latest_finish, estimate, "task can no longer finish inside its window");
+                let detail = format!("Estimated {}ms from {}ms overruns the window ending at {}ms", estimate, now, window.latest_finish);
+                self.transition(task.id, TaskState::Expired, ReasonCode::ExpiredWindow, detail).await;
+                return;
+            }
+        }
+        // A robot drained, deregistered, or stripped of a required capability since the task
+        // was bound to it takes no new work
+        if let Some(robot_id) = task.robot_id.clone() {
+            let can_run = self
+                .core
+                .capabilities
+                .lock()
+                .await
+                .get(&robot_id)
+                .is_some_and(|caps| task.required_capabilities.iter().all(|c| caps.contains(c)));
+            if !can_run || self.is_draining(&robot_id) {
+                tracing::debug!(robot_id = %robot_id, "robot can't take the task; placing it again");
+                if let Some(record) = self.core.records.lock().await.get_mut(&task.id) {
+                    self.unbind(record);
+                }
+                task.robot_id = None;
+            }
+        }
+        // The robot's firmware or zones may have changed, or the matrix been reloaded, since
+        // submission
+        if let Some(robot_id) = &task.robot_id {
+            if let Err(e) = self.check_compatibility(&task.task_type, robot_id) {
+                tracing::warn!(error = %e, "robot can't run the task");
+                self.transition(task.id, TaskState::Failed, ReasonCode::FailedIncompatible, e).await;
+                return;
+            }
+            if let Err(e) = self.check_zone(&task, robot_id) {
+                tracing::warn!(error = %e, "robot may not enter the task's zone");
+                self.transition(task.id, TaskState::Failed, ReasonCode::FailedZoneViolation, e).await;
+                return;
+            }
+        }
+        // Without a robot, bidding picks one; the winner's copy comes back through the queue
+        if self.core.auctions.is_some() && task.robot_id.is_none() && !task.expedite && task.coalition.is_none() {
+            self.open_auction(task).await;
+            return;
+        }
+        // Wait in line for the task's mutex group; its holder hands it over on finishing
+        let Some(task) = self.core.mutex_groups.lock().unwrap_or_else(|e| e.into_inner()).acquire(task) else {
+            tracing::debug!("waiting for its mutex group");
+            return;
+        };
+        // Then for all of its resources at once; they are granted as their holders finish
+        let Some(mut task) = self.core.resource_locks.lock().unwrap_or_else(|e| e.into_inner()).acquire(task) else {
+            tracing::debug!("waiting for its resources");
+            return;
+        };
+        // A coalition needs a full set of free robots at once
+        if task.coalition.is_some() {
+            self.dispatch_coalition(task).await;
+            return;
+        }
+        let engine_placed = task.robot_id.is_none();
+        if engine_placed {
+            self.assign_robot(&mut task).await;
+        }
+        if let Some(robot_id) = &task.robot_id {
+            tracing::Span::current().record("robot_id", robot_id.as_str());
+        }
+        if let (Some(dispatcher), Some(robot_id)) = (&self.core.dispatcher, task.robot_id.clone()) {
+            {
+                // Wait for a free slot; the next task on the robot to finish releases it
+                let records = self.core.records.lock().await;
+                let active = active_per_robot(&records).get(robot_id.as_str()).copied().unwrap_or(0);
+                if self.is_draining(&robot_id) {
+                    // Drained while this task was being placed
+                    drop(records);
+                    self.dispatch_released(vec![Task { robot_id: None, ..task }]);
+                    return;
+                }
+                if let Some(charge) = self.charging_task(&robot_id).filter(|charge| *charge != task.id) {
+                    // Deferred until the robot finishes charging, which dispatches it again
+                    tracing::debug!(charge, "waiting for its robot to finish charging");
+                    let pinned = records.get(&task.id).is_some_and(|r| r.pinned);
+                    let movable = self.core.steal_policy.may_move(engine_placed, pinned);
+                    let free = active < self.robot_slots(&robot_id);
+                    let mut queues = self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner());
+                    queues.park(robot_id, task, movable, self.core.clock.now_millis());
+                    // A free slot goes to the charge task if it is still queued
+                    let charge = free.then(|| queues.withdraw(charge)).flatten();
+                    self.dispatch_released(charge.into_iter().collect());
+                    return;
+                }
+                if active >= self.robot_slots(&robot_id) {
+                    let victim = self.core.preemption.and_then(|config| config.victim(&task, &records, &robot_id));
+                    let Some(victim) = victim else {
+                        tracing::debug!(active, "waiting for a free slot on its robot");
+                        let pinned = records.get(&task.id).is_some_and(|r| r.pinned);
+                        let movable = self.core.steal_policy.may_move(engine_placed, pinned);
+                        self.core.robot_queues.lock().unwrap_or_else(|e| e.into_inner()).park(robot_id, task, movable, self.core.clock.now_millis());
+                        return;
+                    };
+                    drop(records);
+                    if let Err(e) = self.preempt(victim, &task, &robot_id).await {
+                        // The victim finished first; take the task through dispatch again
+                        tracing::debug!(error = %e, "preemption not needed");
+                        self.dispatch_released(vec![task]);
+                        return;
+                    }
+                }
+            }
+            // The robot accepts through the delivery loop and reports through report_result
+            let task_id = task.id;
+            let detail = format!("Assigned to robot {}", robot_id);
+            if let Err(e) = self.transition_task(task_id, TaskState::Assigned, ReasonCode::Dispatched, detail).await {
+                tracing::warn!(error = %e, "could not assign task");
+                return;
+            }
+            let assigned = match self.core.frames.localize(&task, &robot_id) {
+                Ok(local) => dispatcher.assign(self, &robot_id, local).await,
+                Err(e) => Err(format!("Cannot localize task for robot {}: {}", robot_id, e)),
+            };
+            match assigned {
+                Ok(()) => {
+                    tracing::info!("handed off to robot");
+                    let handed_off_at = self.core.clock.now_millis();
+                    if let Some(record) = self.core.records.lock().await.get_mut(&task_id) {
+                        record.marks.handed_off_at.get_or_insert(handed_off_at);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "hand-off to robot failed");
+                    self.transition(task_id, TaskState::Failed, ReasonCode::FailedRobotError, e).await;
+                }
+            }
+            return;
         }
-    };
-
-    let task: Task = match serde_json::from_str(task_json) {
-        Ok(task) => task,
-        Err(e) => return CString::new(format!("Error: JSON parsing failed: {}", e)).unwrap().into_raw(),
-    };
-
-    let runtime = match tokio::runtime::Runtime::new() {
-        Ok(rt) => rt,
-        Err(e) => return CString::new(format!("Error: Tokio runtime creation failed: {}", e)).unwrap().into_raw(),
-    };
-
-    let result = runtime.block_on(async {
-        SCHEDULER.schedule_task(task).await
-    });
-
-    match result {
-        Ok(()) => CString::new("Success").unwrap().into_raw(),
-        Err(e) => CString::new(format!("Error: {}", e)).unwrap().into_raw(),
-    }
-}
-
-// FFI function to free C string memory
-#[no_mangle]
-pub extern "C" fn free_string_ffi(s: *mut c_char) {
-    if !s.is_null() {
-        unsafe {
-            let _ = CString::from_raw(s);
+        let picked_up = "Picked up by executor".to_string();
+        if let Err(e) = self.transition_task(task.id, TaskState::Running, ReasonCode::Dispatched, picked_up).await {
+            tracing::warn!(error = %e, "could not start task");
+            return;
         }
+        // Simulate task execution (replace with actual call to Python delegator)
+        tracing::info!("processing task");
+        self.transition(task.id, TaskState::Completed, ReasonCode::CompletedOk, "Execution finished".to_string()).await;
     }
 }
 
@@ -202,12 +2778,14 @@ pub extern "C" fn free_string_ffi(s: *mut c_char) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tokio::runtime::Runtime;
+    use crate::backpressure::{BackpressurePolicy, QUEUE_FULL_ERROR};
+    use crate::trace_context::TraceContext;
+    use crate::validation::ValidationConfig;
 
     #[tokio::test]
     async fn test_schedule_task() {
-        let (scheduler, rx) = Scheduler::new();
-        tokio::spawn(Scheduler::process_tasks(rx));
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
 
         let robot_id = "Ford".to_string();
         scheduler.register_robot(robot_id.clone(), vec!["heavy_lifting".to_string()]).await.unwrap();
@@ -219,6 +2797,7 @@ mod tests {
             deadline: None,
             robot_id: Some(robot_id),
             required_capabilities: vec!["heavy_lifting".to_string()],
+            ..Default::default()
         };
 
         let result = scheduler.schedule_task(task.clone()).await;
@@ -227,8 +2806,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_missing_capability() {
-        let (scheduler, rx) = Scheduler::new();
-        tokio::spawn(Scheduler::process_tasks(rx));
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
 
         let robot_id = "Ford".to_string();
         scheduler.register_robot(robot_id.clone(), vec!["navigation".to_string()]).await.unwrap();
@@ -240,6 +2819,7 @@ mod tests {
             deadline: None,
             robot_id: Some(robot_id),
             required_capabilities: vec!["heavy_lifting".to_string()],
+            ..Default::default()
         };
 
         let result = scheduler.schedule_task(task).await;
@@ -249,7 +2829,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_deadline_miss() {
-        let (scheduler, mut rx) = Scheduler::new();
+        let (scheduler, mut workers) = Scheduler::builder().build().unwrap();
+        let rx = &mut workers.rx;
         let task = Task {
             id: 1,
             task_type: "heavy_lifting".to_string(),
@@ -257,6 +2838,7 @@ mod tests {
             deadline: Some(0), // Already missed
             robot_id: None,
             required_capabilities: vec![],
+            ..Default::default()
         };
 
         scheduler.schedule_task(task.clone()).await.unwrap();
@@ -264,5 +2846,983 @@ mod tests {
         assert_eq!(received.id, task.id);
         // Note: Deadline miss is logged, not propagated as error
     }
-}
 
+    #[tokio::test]
+    async fn test_transitions_carry_reason_codes() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+
+        let task = Task {
+            id: 7,
+            task_type: "navigation".to_string(),
+            priority: 1,
+            deadline: None,
+            robot_id: None,
+            required_capabilities: vec![],
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+
+        let mut reasons = Vec::new();
+        for _ in 0..3 {
+            let event = events.recv().await.unwrap();
+            assert_eq!(event.task_id, 7);
+            assert_eq!(event.attempt, 1);
+            reasons.push(event.transition.reason);
+        }
+        assert_eq!(reasons, vec![ReasonCode::Submitted, ReasonCode::Dispatched, ReasonCode::CompletedOk]);
+
+        let record = scheduler.task_record(7).await.unwrap();
+        assert_eq!(record.state, TaskState::Completed);
+        assert_eq!(record.attempts[0].transitions.len(), 3);
+        assert_eq!(record.attempts[0].transitions[1].from, Some(TaskState::Pending));
+    }
+
+    #[tokio::test]
+    async fn test_expired_deadline_reason() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+
+        let task = Task {
+            id: 8,
+            task_type: "navigation".to_string(),
+            priority: 1,
+            deadline: Some(1),
+            robot_id: None,
+            required_capabilities: vec![],
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+
+        events.recv().await.unwrap(); // Submitted
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.transition.to, TaskState::Expired);
+        assert_eq!(event.transition.reason, ReasonCode::ExpiredDeadline);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["transition"]["reason"], "EXPIRED_DEADLINE");
+    }
+
+    #[tokio::test]
+    async fn test_embedded_spawn() {
+        let scheduler = Scheduler::spawn();
+        let mut events = scheduler.subscribe();
+        scheduler.register_robot("Scion".to_string(), vec!["navigation".to_string()]).await.unwrap();
+
+        let task = Task {
+            id: 3,
+            task_type: "navigation".to_string(),
+            priority: 2,
+            deadline: None,
+            robot_id: Some("Scion".to_string()),
+            required_capabilities: vec!["navigation".to_string()],
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+
+        while let Ok(event) = events.recv().await {
+            if event.transition.to == TaskState::Completed {
+                break;
+            }
+        }
+        assert_eq!(scheduler.task_record(3).await.unwrap().state, TaskState::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_trace_context_stored_and_queryable() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let context = TraceContext::from_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        let task = Task {
+            id: 31,
+            task_type: "navigation".to_string(),
+            trace_context: Some(context.clone()),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task).await.unwrap();
+
+        let record = scheduler.task_record(31).await.unwrap();
+        assert_eq!(record.task.trace_context, Some(context));
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["task"]["trace_context"]["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(scheduler.tasks_by_trace("4bf92f3577b34da6a3ce929d0e0e4736").await.len(), 1);
+
+        let bad = Task {
+            id: 32,
+            trace_context: Some(TraceContext { trace_id: "xyz".to_string(), span_id: "00f067aa0ba902b7".to_string(), trace_flags: 0 }),
+            ..Default::default()
+        };
+        assert!(scheduler.schedule_task(bad).await.unwrap_err().contains("Invalid trace ID"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_waypoint_rejected() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let task: Task = serde_json::from_str(
+            r#"{"id": 33, "task_type": "navigation", "priority": 1, "deadline": null, "robot_id": null,
+                "required_capabilities": [], "waypoints": [{"pose": {"frame_id": "map", "x": 1.0, "y": 2.0}},
+                {"pose": {"frame_id": "", "x": 3.0, "y": 4.0}}]}"#,
+        )
+        .unwrap();
+        let err = scheduler.schedule_task(task).await.unwrap_err();
+        assert!(err.starts_with("Waypoint 1: Invalid frame ID"), "{}", err);
+        assert!(scheduler.task_record(33).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mission_cap_holds_second_mission() {
+        let (scheduler, _workers) = Scheduler::builder().max_concurrent_missions("pilot", 1).build().unwrap();
+        for (id, mission) in [(51, "m1"), (52, "m2")] {
+            let task = Task {
+                id,
+                namespace: Some("pilot".to_string()),
+                mission_id: Some(mission.to_string()),
+                ..Default::default()
+            };
+            scheduler.schedule_task(task).await.unwrap();
+        }
+        assert_eq!(scheduler.active_missions("pilot").await, vec!["m1".to_string()]);
+        assert_eq!(scheduler.queued_missions("pilot").await, vec!["m2".to_string()]);
+
+        scheduler.transition(51, TaskState::Running, ReasonCode::Dispatched, String::new()).await;
+        scheduler.transition(51, TaskState::Completed, ReasonCode::CompletedOk, String::new()).await;
+        assert_eq!(scheduler.active_missions("pilot").await, vec!["m2".to_string()]);
+        assert!(scheduler.queued_missions("pilot").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_illegal_transitions_rejected() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        scheduler.schedule_task(Task { id: 171, ..Default::default() }).await.unwrap();
+        let err = scheduler.transition_task(171, TaskState::Completed, ReasonCode::CompletedOk, String::new()).await.unwrap_err();
+        assert!(err.contains("Pending -> Completed"), "{}", err);
+        scheduler.transition_task(171, TaskState::Running, ReasonCode::Dispatched, String::new()).await.unwrap();
+        scheduler.transition_task(171, TaskState::Completed, ReasonCode::CompletedOk, String::new()).await.unwrap();
+        assert!(scheduler.transition_task(171, TaskState::Running, ReasonCode::Dispatched, String::new()).await.is_err());
+        assert!(scheduler.transition_task(172, TaskState::Running, ReasonCode::Dispatched, String::new()).await.is_err());
+        assert_eq!(scheduler.task_record(171).await.unwrap().attempts[0].transitions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_transport_task_passes_through_assigned() {
+        use crate::test_utils::FakeRobotAdapter;
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 173, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        let mut states = Vec::new();
+        while let Ok(event) = events.recv().await {
+            states.push(event.transition.to);
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        // The result may overtake the acceptance, in which case Running is skipped
+        assert_eq!(states[..2], [TaskState::Pending, TaskState::Assigned]);
+        assert_eq!(states.last(), Some(&TaskState::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_robot_slots_limit_parallel_assignments() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let long = FakeBehavior::AckAfter(std::time::Duration::from_secs(60));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![long.clone(), long.clone(), long]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        assert!(scheduler.set_robot_slots("Ford", 0).await.is_err());
+        scheduler.set_robot_slots("Ford", 2).await.unwrap();
+        for id in 181..184 {
+            scheduler.schedule_task(Task { id, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        }
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(fake.assignments().len(), 2);
+        assert_eq!(scheduler.task_record(183).await.unwrap().state, TaskState::Pending);
+
+        // Finishing one task frees a slot for the waiting one
+        scheduler.report_result(181, Ok(())).await;
+        while fake.assignments().len() < 3 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments()[2], ("Ford".to_string(), 183));
+    }
+
+    #[tokio::test]
+    async fn test_per_robot_queues_keep_backlogs_apart() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let long = FakeBehavior::AckAfter(std::time::Duration::from_secs(60));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![long.clone(); 4]);
+        fake.script("Scion", vec![long]);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let wait_for_assignments = |count: usize| {
+            let fake = fake.clone();
+            async move {
+                while fake.assignments().len() < count {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+            }
+        };
+        // Ford's backlog doesn't hold up Scion
+        let ford = Some("Ford".to_string());
+        scheduler.schedule_task(Task { id: 284, robot_id: ford.clone(), ..Default::default() }).await.unwrap();
+        wait_for_assignments(1).await;
+        scheduler.schedule_task(Task { id: 285, priority: 1, robot_id: ford.clone(), ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 286, priority: 5, robot_id: ford, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 287, ..Default::default() }).await.unwrap();
+        wait_for_assignments(2).await;
+        assert_eq!(fake.assignments()[1], ("Scion".to_string(), 287));
+        // With both robots busy, the engine queues on the shorter backlog
+        scheduler.schedule_task(Task { id: 288, ..Default::default() }).await.unwrap();
+        while scheduler.robot_queue_depths().values().sum::<usize>() < 3 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(scheduler.robot_queue_depths(), BTreeMap::from([("Ford".to_string(), 2), ("Scion".to_string(), 1)]));
+
+        // A freed slot goes to the robot's best queued task by policy
+        scheduler.report_result(284, Ok(())).await;
+        wait_for_assignments(3).await;
+        assert_eq!(fake.assignments()[2], ("Ford".to_string(), 286));
+        scheduler.report_result(286, Ok(())).await;
+        wait_for_assignments(4).await;
+        assert_eq!(fake.assignments()[3], ("Ford".to_string(), 285));
+        // Once Ford's own queue is empty it takes over the task the engine parked on Scion
+        scheduler.report_result(285, Ok(())).await;
+        wait_for_assignments(5).await;
+        assert_eq!(fake.assignments()[4], ("Ford".to_string(), 288));
+        assert!(scheduler.robot_queue_depths().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_steal_policy_decides_which_queued_tasks_move() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let long = FakeBehavior::AckAfter(std::time::Duration::from_secs(60));
+        let start = |policy: StealPolicy| {
+            let fake = Arc::new(FakeRobotAdapter::new());
+            let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).work_stealing(policy).build().unwrap();
+            fake.attach(&scheduler);
+            fake.script("Ford", vec![long.clone(); 3]);
+            fake.script("Scion", vec![long.clone()]);
+            workers.spawn();
+            (scheduler, fake)
+        };
+        let wait_for_queued = |scheduler: Scheduler, count: usize| async move {
+            while scheduler.robot_queue_depths().values().sum::<usize>() < count {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            }
+        };
+        let ford = Some("Ford".to_string());
+
+        // Aggressive: a robot joining the fleet takes over a task a submitter bound to Ford
+        let (scheduler, fake) = start(StealPolicy::Aggressive);
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        for id in 289..=291 {
+            scheduler.schedule_task(Task { id, robot_id: ford.clone(), ..Default::default() }).await.unwrap();
+        }
+        wait_for_queued(scheduler.clone(), 2).await;
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments()[1].0, "Scion");
+        assert_eq!(scheduler.robot_queue_depths(), BTreeMap::from([("Ford".to_string(), 1)]));
+
+        // Off: even the engine's own placements stay with the robot they were queued for
+        let (scheduler, fake) = start(StealPolicy::Off);
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 292, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 293, ..Default::default() }).await.unwrap();
+        wait_for_queued(scheduler.clone(), 1).await;
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 294, robot_id: Some("Scion".to_string()), ..Default::default() }).await.unwrap();
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        // Scion frees its slot with Ford's backlog still queued
+        scheduler.report_result(294, Ok(())).await;
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 292), ("Scion".to_string(), 294)]);
+        assert_eq!(scheduler.robot_queue_depths(), BTreeMap::from([("Ford".to_string(), 1)]));
+    }
+
+    #[tokio::test]
+    async fn test_drain_and_deregister_take_robots_out_of_service() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let long = FakeBehavior::AckAfter(std::time::Duration::from_secs(60));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![long.clone()]);
+        fake.script("Hank", vec![long]);
+        workers.spawn();
+        for robot_id in ["Ford", "Scion"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let bound = |id: u32, robot_id: &str| Task { id, robot_id: Some(robot_id.to_string()), ..Default::default() };
+        let wait_for_state = |task_id: u32, state: TaskState| {
+            let scheduler = scheduler.clone();
+            async move {
+                while scheduler.task_record(task_id).await.is_none_or(|r| r.state != state) {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+            }
+        };
+        assert_eq!(scheduler.drain_robot("Ghost").await, Err("Unknown robot: Ghost".to_string()));
+
+        // Draining: Ford's queued task moves to another robot, its running task finishes
+        scheduler.schedule_task(bound(295, "Ford")).await.unwrap();
+        scheduler.schedule_task(bound(296, "Ford")).await.unwrap();
+        while scheduler.robot_queue_depths().is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        scheduler.drain_robot("Ford").await.unwrap();
+        assert!(scheduler.schedule_task(bound(299, "Ford")).await.unwrap_err().contains("draining"));
+        wait_for_state(296, TaskState::Completed).await;
+        assert_ne!(scheduler.task_record(296).await.unwrap().task.robot_id.as_deref(), Some("Ford"));
+        assert!(!scheduler.is_drained("Ford").await);
+        scheduler.report_result(295, Ok(())).await;
+        assert_eq!(scheduler.task_record(295).await.unwrap().state, TaskState::Completed);
+        assert!(scheduler.is_drained("Ford").await);
+
+        // Deregistering: Hank's in-flight task fails, its queued task is placed again
+        scheduler.register_robot("Hank".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(bound(297, "Hank")).await.unwrap();
+        scheduler.schedule_task(bound(298, "Hank")).await.unwrap();
+        while scheduler.robot_queue_depths().is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        scheduler.deregister_robot("Hank").await.unwrap();
+        let record = scheduler.task_record(297).await.unwrap();
+        assert_eq!(record.state, TaskState::Failed);
+        assert_eq!(record.attempts[0].transitions.last().unwrap().reason, ReasonCode::FailedRobotDeregistered);
+        wait_for_state(298, TaskState::Completed).await;
+        assert!(scheduler.schedule_task(bound(299, "Hank")).await.unwrap_err().contains("Unknown robot"));
+        assert!(scheduler.deregister_robot("Hank").await.is_err());
+        assert_eq!(fake.assignments().iter().filter(|(robot_id, _)| robot_id == "Scion").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_capability_update_revalidates_queued_tasks() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(std::time::Duration::from_secs(60))]);
+        workers.spawn();
+        let caps = |names: &[&str]| names.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+        scheduler.register_robot("Ford".to_string(), caps(&["lift", "camera"])).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), caps(&["lift"])).await.unwrap();
+        assert!(scheduler.update_robot_capabilities("Ghost", vec![], vec![]).await.unwrap_err().contains("Unknown robot"));
+        assert!(scheduler.update_robot_capabilities("Ford", caps(&["lift"]), caps(&["lift"])).await.is_err());
+
+        // Ford is busy with 300; 301 and 302 wait for its slot
+        let task = |id: u32, required: &[&str]| Task { id, robot_id: Some("Ford".to_string()), required_capabilities: caps(required), ..Default::default() };
+        scheduler.schedule_task(task(300, &["lift"])).await.unwrap();
+        scheduler.schedule_task(task(301, &["camera"])).await.unwrap();
+        scheduler.schedule_task(task(302, &["lift"])).await.unwrap();
+        while scheduler.robot_queue_depths().values().sum::<usize>() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+
+        // Scion picks up a camera; bound tasks stay with Ford while it can run them
+        assert_eq!(scheduler.update_robot_capabilities("Scion", caps(&["camera"]), vec![]).await.unwrap(), caps(&["lift", "camera"]));
+        assert_eq!(scheduler.robot_queue_depths(), BTreeMap::from([("Ford".to_string(), 2)]));
+        // Ford's camera fails: 301 can't wait for it and is placed on Scion instead
+        assert_eq!(scheduler.update_robot_capabilities("Ford", vec![], caps(&["camera"])).await.unwrap(), caps(&["lift"]));
+        assert!(scheduler.schedule_task(task(303, &["camera"])).await.unwrap_err().contains("lacks required capabilities"));
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments()[1], ("Scion".to_string(), 301));
+        assert_eq!(scheduler.robot_queue_depths(), BTreeMap::from([("Ford".to_string(), 1)]));
+        assert_eq!(scheduler.task_record(302).await.unwrap().task.robot_id.as_deref(), Some("Ford"));
+    }
+
+    #[tokio::test]
+    async fn test_compatibility_matrix_gates_restricted_task_types() {
+        let matrix = CompatibilityMatrix::from_json(r#"{"entries": [{"task_type": "welding", "model": "UR10", "min_firmware": "5.2"}]}"#).unwrap();
+        let (scheduler, workers) = Scheduler::builder().compatibility(matrix).build().unwrap();
+        let mut events = scheduler.subscribe();
+        for robot_id in ["Ford", "Scion"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let ur10 = |firmware: &str| RobotModel { model: "UR10".to_string(), firmware: firmware.to_string() };
+        assert!(scheduler.set_robot_model("Hank", ur10("5.3")).await.is_err());
+        assert!(scheduler.set_robot_model("Ford", ur10("five")).await.is_err());
+        scheduler.set_robot_model("Ford", ur10("5.3")).await.unwrap();
+
+        let report = scheduler.compatible_robots("welding").await;
+        assert_eq!(report.compatible, vec!["Ford".to_string()]);
+        assert!(report.incompatible["Scion"].contains("hasn't declared"));
+        assert_eq!(scheduler.compatible_robots("navigation").await.compatible.len(), 2);
+
+        let welding = |id: u32, robot_id: &str| Task { id, task_type: "welding".to_string(), robot_id: Some(robot_id.to_string()), ..Default::default() };
+        let err = scheduler.schedule_task(welding(271, "Scion")).await.unwrap_err();
+        assert!(err.contains("Robot Scion is incompatible"), "{}", err);
+        scheduler.schedule_task(welding(272, "Ford")).await.unwrap();
+
+        // A downgrade while the task is queued fails it at dispatch
+        scheduler.set_robot_model("Ford", ur10("5.1.9")).await.unwrap();
+        workers.spawn();
+        while !events.recv().await.unwrap().transition.to.is_terminal() {}
+        let record = scheduler.task_record(272).await.unwrap();
+        assert_eq!(record.state, TaskState::Failed);
+        assert_eq!(record.attempts[0].transitions.last().unwrap().reason, ReasonCode::FailedIncompatible);
+        assert_eq!(scheduler.robot_profile("Ford").map_or(0, |p| p.failed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mutex_group_runs_one_task_fleet_wide() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let long = FakeBehavior::AckAfter(std::time::Duration::from_secs(60));
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        for robot_id in ["Ford", "Scion", "Hank"] {
+            fake.script(robot_id, vec![long.clone(), long.clone()]);
+        }
+        workers.spawn();
+        for robot_id in ["Ford", "Scion", "Hank"] {
+            scheduler.register_robot(robot_id.to_string(), vec![]).await.unwrap();
+        }
+        let task = |id, robot: &str, priority, group: Option<&str>| Task {
+            id,
+            robot_id: Some(robot.to_string()),
+            priority,
+            mutex_group: group.map(str::to_string),
+            ..Default::default()
+        };
+        let crane = Some("crane_airspace");
+        assert!(scheduler.schedule_task(task(220, "Ford", 1, Some(""))).await.is_err());
+        scheduler.schedule_task(task(221, "Ford", 1, crane)).await.unwrap();
+        while fake.assignments().is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        scheduler.schedule_task(task(222, "Scion", 5, crane)).await.unwrap();
+        scheduler.schedule_task(task(223, "Hank", 1, crane)).await.unwrap();
+        scheduler.schedule_task(task(224, "Hank", 1, None)).await.unwrap();
+        while fake.assignments().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        // Robots are free, but only one group member may be out at a time
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 221), ("Hank".to_string(), 224)]);
+        let status = scheduler.mutex_group_status("crane_airspace");
+        assert_eq!((status.holder, status.waiting), (Some(221), vec![222, 223]));
+
+        // The group goes to the first waiter once the holder finishes
+        scheduler.report_result(221, Ok(())).await;
+        while fake.assignments().len() < 3 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments()[2], ("Scion".to_string(), 222));
+        scheduler.cancel_task(223).await.unwrap();
+        assert_eq!(scheduler.mutex_group_status("crane_airspace").waiting, Vec::<u32>::new());
+    }
+
+    #[tokio::test]
+    async fn test_completion_event_carries_phase_breakdown() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(std::time::Duration::from_millis(20))]);
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 191, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        let finished = loop {
+            let event = events.recv().await.unwrap();
+            if event.transition.to.is_terminal() {
+                break event;
+            }
+            assert!(event.phases.is_none());
+        };
+        let phases = finished.phases.unwrap();
+        assert_eq!(phases.phases().len(), 6);
+        assert!(phases.execution_ms.unwrap() >= 20);
+        assert_eq!(scheduler.task_record(191).await.unwrap().phases, Some(phases));
+        assert_eq!(scheduler.phase_latency()["transport"].count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_policy_switch_reorders_queue() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.schedule_task(Task { id: 201, priority: 9, deadline: Some(u64::MAX - 1), ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 202, priority: 1, deadline: Some(u64::MAX - 2), ..Default::default() }).await.unwrap();
+        assert_eq!(scheduler.policy_name(), "priority_first");
+        assert!(scheduler.set_policy_by_name("shortest_job").is_err());
+        scheduler.set_policy_by_name("earliest_deadline_first").unwrap();
+
+        workers.spawn();
+        let first_dispatch = loop {
+            let event = events.recv().await.unwrap();
+            if event.transition.to == TaskState::Running {
+                break event;
+            }
+        };
+        assert_eq!(first_dispatch.task_id, 202);
+    }
+
+    #[tokio::test]
+    async fn test_control_requires_transport() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let result = scheduler.send_control("Ford", ControlCommand::Hold { task_id: 1 }).await;
+        assert!(result.unwrap_err().contains("No robot transport"));
+    }
+
+    #[tokio::test]
+    async fn test_queue_wait_recorded_on_dispatch() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        let task = Task { id: 61, required_capabilities: vec![], ..Default::default() };
+        scheduler.schedule_task(task).await.unwrap();
+        while let Ok(event) = events.recv().await {
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        let waits = scheduler.queue_wait_by_capability();
+        assert_eq!(waits[crate::metrics::NO_CAPABILITY].count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_held_task_skipped_until_released() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.schedule_task(Task { id: 71, ..Default::default() }).await.unwrap();
+        scheduler.hold_task(71).await.unwrap();
+        assert!(scheduler.task_record(71).await.unwrap().held);
+        assert!(scheduler.hold_task(71).await.is_err());
+
+        // The executor picks the queued task up but parks it instead of running it
+        workers.spawn();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(scheduler.task_record(71).await.unwrap().state, TaskState::Pending);
+
+        scheduler.release_task(71).await.unwrap();
+        while let Ok(event) = events.recv().await {
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        let record = scheduler.task_record(71).await.unwrap();
+        assert!(!record.held);
+        assert_eq!(record.state, TaskState::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_stale_expedited_task_released_as_routine() {
+        let decay = crate::decay::ExpediteDecay::new(std::time::Duration::from_millis(5), 1);
+        let (scheduler, workers) = Scheduler::builder().expedite_decay(decay).build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.schedule_task(Task { id: 241, priority: 100, expedite: true, ..Default::default() }).await.unwrap();
+        scheduler.hold_task(241).await.unwrap();
+        assert!(scheduler.task_record(241).await.unwrap().held_since.is_some());
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // Released while still queued in the urgent lane; dispatch picks up the decayed copy
+        scheduler.release_task(241).await.unwrap();
+        workers.spawn();
+        while !events.recv().await.unwrap().transition.to.is_terminal() {}
+        let record = scheduler.task_record(241).await.unwrap();
+        assert_eq!(record.state, TaskState::Completed);
+        assert!(!record.task.expedite && record.task.priority <= 96, "{:?}", record.task);
+        assert_eq!(record.held_since, None);
+    }
+
+    // Leave task 81 running on robot "Ford" after its link drops, then re-register it
+    async fn reregister_after_disconnect(policy: DuplicateRobotPolicy) -> (Arc<crate::test_utils::FakeRobotAdapter>, Result<(), String>, TaskRecord) {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).duplicate_robot_policy(policy).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::DisconnectMidTask(std::time::Duration::ZERO)]);
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 81, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while fake.is_connected("Ford") {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+
+        let mut events = scheduler.subscribe();
+        fake.reconnect("Ford");
+        let result = scheduler.register_robot("Ford".to_string(), vec![]).await;
+        if result.is_ok() {
+            while let Ok(event) = events.recv().await {
+                if event.transition.to.is_terminal() {
+                    break;
+                }
+            }
+        }
+        let record = scheduler.task_record(81).await.unwrap();
+        (fake, result, record)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_submissions_of_one_id_accept_one() {
+        let (scheduler, workers) = Scheduler::builder().validation(ValidationConfig { workers: 4, ..Default::default() }).build().unwrap();
+        workers.spawn();
+        let submissions: Vec<_> = (0..8)
+            .map(|priority| {
+                let scheduler = scheduler.clone();
+                tokio::spawn(async move { scheduler.schedule_task(Task { id: 412, priority, ..Default::default() }).await })
+            })
+            .collect();
+        let mut accepted = Vec::new();
+        for (priority, submission) in submissions.into_iter().enumerate() {
+            match submission.await.unwrap() {
+                Ok(()) => accepted.push(priority as u32),
+                Err(e) => assert_eq!(e, "Conflict: task 412 already exists"),
+            }
+        }
+        assert_eq!(accepted.len(), 1);
+        // The rejected submissions neither overwrote the accepted one nor kept the ID reserved
+        assert_eq!(scheduler.task_record(412).await.unwrap().task.priority, accepted[0]);
+        assert!(scheduler.core.reserved_ids.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_robot_rejected_by_default() {
+        let (_, result, record) = reregister_after_disconnect(DuplicateRobotPolicy::default()).await;
+        assert_eq!(result.unwrap_err(), "Conflict: robot Ford already registered");
+        assert_eq!(record.state, TaskState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_robot_replace_aborts_old_session() {
+        let (fake, result, record) = reregister_after_disconnect(DuplicateRobotPolicy::ReplaceAndAbortOld).await;
+        assert!(result.is_ok());
+        assert_eq!(record.state, TaskState::Failed);
+        assert_eq!(record.attempts[0].transitions.last().unwrap().reason, ReasonCode::FailedRobotReplaced);
+        assert_eq!(fake.assignments().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_robot_replace_migrates_assignment() {
+        let (fake, result, record) = reregister_after_disconnect(DuplicateRobotPolicy::ReplaceAndMigrate).await;
+        assert!(result.is_ok());
+        assert_eq!(record.state, TaskState::Completed);
+        let reasons: Vec<ReasonCode> = record.attempts[0].transitions.iter().map(|t| t.reason).collect();
+        assert!(reasons.contains(&ReasonCode::MigratedToNewSession));
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 81), ("Ford".to_string(), 81)]);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_commits_atomically() {
+        let (scheduler, _workers) = Scheduler::builder().task_channel_size(1000).build().unwrap();
+        let upload_id = scheduler.begin_upload(Some("survey".to_string())).await;
+        for chunk in 0..4u32 {
+            let tasks = (0..100).map(|i| Task { id: 1000 + chunk * 100 + i, ..Default::default() }).collect();
+            scheduler.upload_chunk(&upload_id, tasks).await.unwrap();
+        }
+        let bad = vec![Task { id: 2000, ..Default::default() }, Task { id: 2001, robot_id: Some("Ghost".to_string()), ..Default::default() }];
+        assert!(scheduler.upload_chunk(&upload_id, bad).await.unwrap_err().starts_with("Task 2001: Unknown robot"));
+
+        // Nothing is visible until the commit
+        assert!(scheduler.task_record(1000).await.is_none());
+        assert_eq!(scheduler.commit_upload(&upload_id).await.unwrap(), 400);
+        let record = scheduler.task_record(1399).await.unwrap();
+        assert_eq!(record.task.mission_id.as_deref(), Some("survey"));
+        assert!(scheduler.task_record(2000).await.is_none());
+        assert!(scheduler.commit_upload(&upload_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_upload_commit_checks_tasks_again() {
+        let (scheduler, _workers) = Scheduler::builder().task_channel_size(2).backpressure(BackpressurePolicy::Reject).build().unwrap();
+        scheduler.register_robot("Ford".to_string(), Vec::new()).await.unwrap();
+        let staged = |ids: &[u32]| ids.iter().map(|&id| Task { id, robot_id: Some("Ford".to_string()), ..Default::default() }).collect::<Vec<_>>();
+
+        // The robot left after its task was staged
+        let upload_id = scheduler.begin_upload(None).await;
+        scheduler.upload_chunk(&upload_id, staged(&[419, 420])).await.unwrap();
+        scheduler.deregister_robot("Ford").await.unwrap();
+        assert!(scheduler.commit_upload(&upload_id).await.unwrap_err().starts_with("Task 419: Unknown robot"));
+        assert!(scheduler.task_record(420).await.is_none());
+
+        // An ID taken after staging discards the upload
+        scheduler.register_robot("Ford".to_string(), Vec::new()).await.unwrap();
+        let upload_id = scheduler.begin_upload(None).await;
+        scheduler.upload_chunk(&upload_id, staged(&[421, 422])).await.unwrap();
+        scheduler.schedule_task(Task { id: 422, ..Default::default() }).await.unwrap();
+        assert!(scheduler.commit_upload(&upload_id).await.unwrap_err().starts_with("Conflict: task 422 already exists"));
+        assert!(scheduler.task_record(421).await.is_none());
+
+        // Only one lane slot is left, so the backpressure policy refuses the upload whole
+        let upload_id = scheduler.begin_upload(None).await;
+        scheduler.upload_chunk(&upload_id, staged(&[423, 424])).await.unwrap();
+        assert!(scheduler.commit_upload(&upload_id).await.unwrap_err().starts_with(QUEUE_FULL_ERROR));
+        assert!(scheduler.task_record(423).await.is_none());
+        scheduler.schedule_task(Task { id: 425, ..Default::default() }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rules_route_and_hot_reload() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        scheduler.register_robot("Ford".to_string(), vec!["camera".to_string()]).await.unwrap();
+        scheduler
+            .reload_rules(r#"{"routing": [{"name": "cam", "when": "task.task_type == 'inspect'", "robot": "'camera' in robot.capabilities"}]}"#)
+            .unwrap();
+        scheduler.schedule_task(Task { id: 95, task_type: "inspect".to_string(), ..Default::default() }).await.unwrap();
+        assert_eq!(scheduler.task_record(95).await.unwrap().task.robot_id.as_deref(), Some("Ford"));
+
+        assert!(scheduler.reload_rules(r#"{"admission": [{"name": "broken", "deny_when": "task.priority >"}]}"#).is_err());
+        scheduler.reload_rules(r#"{"admission": [{"name": "low", "deny_when": "task.priority < 2"}]}"#).unwrap();
+        let err = scheduler.schedule_task(Task { id: 96, priority: 1, ..Default::default() }).await.unwrap_err();
+        assert_eq!(err, "Denied by rule low");
+    }
+
+    #[tokio::test]
+    async fn test_load_shedding_rejects_low_priority() {
+        let shedding = crate::load_shedding::LoadSheddingConfig { enter_queue_depth: 2, exit_queue_depth: 0, min_priority: 5, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder().load_shedding(shedding).build().unwrap();
+        let mut modes = scheduler.subscribe_load_mode();
+        for id in 101..103 {
+            scheduler.schedule_task(Task { id, priority: 1, ..Default::default() }).await.unwrap();
+        }
+        let err = scheduler.schedule_task(Task { id: 103, priority: 1, ..Default::default() }).await.unwrap_err();
+        assert!(err.starts_with(crate::load_shedding::OVERLOADED_ERROR));
+        assert!(modes.recv().await.unwrap().shedding);
+        scheduler.schedule_task(Task { id: 104, priority: 9, ..Default::default() }).await.unwrap();
+
+        // Draining the queue ends the mode
+        workers.spawn();
+        assert!(!modes.recv().await.unwrap().shedding);
+        assert!(!scheduler.is_shedding_load());
+    }
+
+    #[tokio::test]
+    async fn test_robot_profile_survives_restart() {
+        let store: Arc<dyn TaskStore> = Arc::new(crate::store::MemoryStore::new());
+        let (scheduler, workers) = Scheduler::builder().store(store.clone()).build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 111, task_type: "navigation".to_string(), robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while let Ok(event) = events.recv().await {
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        assert_eq!(scheduler.robot_profile("Ford").unwrap().completed, 1);
+
+        let (restarted, _workers) = Scheduler::builder().store(store).build().unwrap();
+        let profile = restarted.robot_profile("Ford").unwrap();
+        assert_eq!(profile.success_rate(), Some(1.0));
+        assert_eq!(profile.durations["navigation"].count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_expedite_bypasses_queue_to_idle_robot() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec!["lift".to_string(), "weld".to_string()]).await.unwrap();
+        for id in 121..124 {
+            scheduler.schedule_task(Task { id, ..Default::default() }).await.unwrap();
+        }
+        let urgent = Task { id: 124, expedite: true, source: Some("line-4".to_string()), required_capabilities: vec!["weld".to_string()], ..Default::default() };
+        scheduler.schedule_task(urgent).await.unwrap();
+
+        workers.spawn();
+        let first_dispatch = loop {
+            let event = events.recv().await.unwrap();
+            if event.transition.to == TaskState::Running {
+                break event;
+            }
+        };
+        assert_eq!(first_dispatch.task_id, 124);
+        assert_eq!(first_dispatch.robot_id.as_deref(), Some("Scion"));
+        assert_eq!(scheduler.task_record(124).await.unwrap().task.robot_id.as_deref(), Some("Scion"));
+        assert_eq!(scheduler.expedite_by_source()["line-4"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_unassigned_task_placed_on_capable_robot() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec!["lift".to_string(), "weld".to_string()]).await.unwrap();
+        scheduler.schedule_task(Task { id: 282, required_capabilities: vec!["weld".to_string()], ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 283, required_capabilities: vec!["drill".to_string()], ..Default::default() }).await.unwrap();
+        let mut finished = 0;
+        while finished < 2 {
+            if events.recv().await.unwrap().transition.to.is_terminal() {
+                finished += 1;
+            }
+        }
+        let record = scheduler.task_record(282).await.unwrap();
+        assert_eq!(record.task.robot_id.as_deref(), Some("Scion"));
+        assert_eq!(record.attempts[0].robot_id.as_deref(), Some("Scion"));
+        // No robot can drill, so the task runs unassigned as before
+        assert_eq!(scheduler.task_record(283).await.unwrap().task.robot_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_pending_task_skips_dispatch() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.schedule_task(Task { id: 161, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 162, ..Default::default() }).await.unwrap();
+        assert_eq!(scheduler.cancel_task(161).await.unwrap(), TaskState::Cancelled);
+        assert!(scheduler.cancel_task(161).await.is_err());
+
+        workers.spawn();
+        while let Ok(event) = events.recv().await {
+            if event.task_id == 162 && event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        let record = scheduler.task_record(161).await.unwrap();
+        assert_eq!(record.state, TaskState::Cancelled);
+        assert_eq!(record.attempts[0].transitions.len(), 2);
+        assert_eq!(record.attempts[0].transitions[1].reason, ReasonCode::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_task_is_cooperative() {
+        use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(std::time::Duration::from_secs(60))]);
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 163, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        while events.recv().await.unwrap().transition.to != TaskState::Running {}
+
+        // The task keeps running until the robot acknowledges by reporting
+        assert_eq!(scheduler.cancel_task(163).await.unwrap(), TaskState::Running);
+        assert!(scheduler.task_record(163).await.unwrap().cancel_requested);
+        while fake.controls().is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.controls()[0].command, ControlCommand::Abort { task_id: 163 });
+        scheduler.report_result(163, Err("Stopped".to_string())).await;
+        let record = scheduler.task_record(163).await.unwrap();
+        assert_eq!(record.state, TaskState::Cancelled);
+        assert!(record.attempts[0].transitions.last().unwrap().detail.contains("Stopped"));
+    }
+
+    #[tokio::test]
+    async fn test_pin_task_overrides_assignment_and_is_audited() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        let mut events = scheduler.subscribe();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        let task = Task { id: 151, expedite: true, required_capabilities: vec!["lift".to_string()], ..Default::default() };
+        scheduler.schedule_task(task).await.unwrap();
+
+        assert!(scheduler.pin_task(151, "Scion").await.unwrap_err().contains("lacks required capabilities"));
+        assert!(scheduler.pin_task(151, "Ghost").await.is_err());
+        scheduler.pin_task(151, "Ford").await.unwrap();
+        assert!(scheduler.task_record(151).await.unwrap().pinned);
+
+        workers.spawn();
+        let dispatched = loop {
+            let event = events.recv().await.unwrap();
+            if event.transition.to == TaskState::Running {
+                break event;
+            }
+        };
+        assert_eq!(dispatched.robot_id.as_deref(), Some("Ford"));
+        let overrides = AuditQuery { task_id: Some(151), actions: vec![AuditAction::ManualOverride], ..Default::default() };
+        let audit = scheduler.query_audit(&overrides);
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].previous_robot_id, None);
+        assert_eq!(scheduler.core.store.load_audit().unwrap(), scheduler.audit_log(None));
+    }
+
+    #[tokio::test]
+    async fn test_shadow_trials_log_divergences_without_applying_them() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        scheduler.shadow_policy_by_name("earliest_deadline_first", std::time::Duration::from_secs(60)).unwrap();
+        scheduler.schedule_task(Task { id: 161, priority: 5, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 162, priority: 1, deadline: Some(100), ..Default::default() }).await.unwrap();
+        workers.spawn();
+        for _ in 0..200 {
+            if scheduler.shadow_report().unwrap().decisions == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        // Live priority order dispatched 161 first; the deadline-first candidate would not have
+        let report = scheduler.end_shadow().unwrap();
+        assert_eq!((report.decisions, report.divergences), (2, 1));
+        assert_eq!((report.recent[0].live.as_str(), report.recent[0].shadow.as_str()), ("task 161", "task 162"));
+        assert_eq!(scheduler.policy_name(), "priority_first");
+        assert!(scheduler.shadow_report().is_none());
+
+        // A candidate rule set that would reject a submission doesn't stop it being admitted
+        let rules = r#"{"admission": [{"name": "no_drills", "deny_when": "task.task_type == 'drilling'", "message": "No drilling"}]}"#;
+        scheduler.shadow_rules(rules, std::time::Duration::from_secs(60)).unwrap();
+        scheduler.schedule_task(Task { id: 163, task_type: "drilling".to_string(), ..Default::default() }).await.unwrap();
+        let report = scheduler.shadow_report().unwrap();
+        assert_eq!(report.recent[0].kind, DecisionKind::Admission);
+        assert_eq!(report.recent[0].shadow, "reject: No drilling");
+    }
+
+    #[tokio::test]
+    async fn test_restore_checkpoint_rolls_back_registry_and_holds() {
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        scheduler.schedule_task(Task { id: 131, ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 132, ..Default::default() }).await.unwrap();
+        scheduler.hold_task(131).await.unwrap();
+        scheduler.hold_task(132).await.unwrap();
+        let info = scheduler.create_checkpoint("before-policy").await.unwrap();
+        assert_eq!((info.robots, info.pending), (1, 2));
+        assert!(scheduler.create_checkpoint("before-policy").await.is_err());
+
+        // A bad bulk change: a robot registered by mistake and the held tasks released
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        scheduler.release_task(132).await.unwrap();
+        let report = scheduler.restore_checkpoint("before-policy").await.unwrap();
+        assert_eq!(report.hold_reset, vec![132]);
+        assert!(scheduler.task_record(131).await.unwrap().held);
+        assert!(scheduler.task_record(132).await.unwrap().held);
+        let err = scheduler.schedule_task(Task { id: 133, robot_id: Some("Scion".to_string()), ..Default::default() }).await;
+        assert_eq!(err.unwrap_err(), "Unknown robot: Scion");
+
+        assert_eq!(scheduler.list_checkpoints().await.len(), 1);
+        scheduler.delete_checkpoint("before-policy").await.unwrap();
+        assert!(scheduler.restore_checkpoint("before-policy").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_quota_counters_survive_restart() {
+        let store = Arc::new(crate::store::MemoryStore::new());
+        let build = || Scheduler::builder().store(store.clone()).daily_quota("cell-1", 2).build().unwrap();
+        let task = |id| Task { id, namespace: Some("cell-1".to_string()), ..Default::default() };
+        let (scheduler, _workers) = build();
+        scheduler.schedule_task(task(1)).await.unwrap();
+        scheduler.schedule_task(task(2)).await.unwrap();
+        let err = scheduler.schedule_task(task(3)).await.unwrap_err();
+        assert!(err.starts_with(crate::quotas::QUOTA_EXCEEDED_ERROR), "{}", err);
+        scheduler.flush_quotas().unwrap();
+
+        // A restarted scheduler picks up today's count instead of starting over
+        let (restarted, _workers) = build();
+        assert_eq!(restarted.quota_usage("cell-1").used, 2);
+        assert!(restarted.schedule_task(task(4)).await.is_err());
+        assert!(restarted.schedule_task(Task { id: 5, ..Default::default() }).await.is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+// backend/rust/src/shadow.rs
+// Purpose: Shadow evaluation of a proposed scheduling policy or rule set for MRTODP. While
+// a trial runs, the candidate makes the same decisions as the live configuration on live
+// traffic (which task to dispatch next, whether to admit a submission and where to route
+// it), but only the live decision is applied. Decisions where the two disagree are logged
+// and kept for the trial report, so a new policy can be checked before switching it on.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::policy::SchedulingPolicy;
+use crate::rules::RuleEngine;
+
+// Divergences retained per trial; older ones are only counted
+const MAX_DIVERGENCES: usize = 256;
+
+// Configuration evaluated in shadow
+#[derive(Clone)]
+pub enum ShadowCandidate {
+    Policy(Arc<dyn SchedulingPolicy>),
+    Rules(Arc<RuleEngine>),
+}
+
+impl ShadowCandidate {
+    fn describe(&self) -> String {
+        match self {
+            ShadowCandidate::Policy(policy) => format!("policy {}", policy.name()),
+            ShadowCandidate::Rules(_) => "rule set".to_string(),
+        }
+    }
+}
+
+// Kind of decision on which the candidate disagreed with the live configuration
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionKind {
+    Dispatch,  // Next task taken off the ready queues
+    Admission, // Submission admitted by one and rejected by the other
+    Routing,   // Submission routed to a different robot
+}
+
+// One decision the candidate would have made differently
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Divergence {
+    pub at: u64,
+    pub kind: DecisionKind,
+    pub task_id: u32,
+    pub live: String,   // Decision applied
+    pub shadow: String, // Decision the candidate would have made
+}
+
+// Summary of a shadow trial
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShadowReport {
+    pub candidate: String,
+    pub started_at: u64,
+    pub ends_at: u64,
+    pub active: bool, // False once the trial period has elapsed
+    pub decisions: u64,
+    pub divergences: u64,
+    pub recent: Vec<Divergence>, // Latest divergences, oldest first
+}
+
+// A running (or finished) shadow trial
+pub(crate) struct ShadowTrial {
+    pub(crate) candidate: ShadowCandidate,
+    started_at: u64,
+    ends_at: u64,
+    decisions: u64,
+    divergences: u64,
+    recent: VecDeque<Divergence>,
+}
+
+impl ShadowTrial {
+    pub(crate) fn new(candidate: ShadowCandidate, started_at: u64, duration_ms: u64) -> Self {
+        ShadowTrial {
+            candidate,
+            started_at,
+            ends_at: started_at.saturating_add(duration_ms),
+            decisions: 0,
+            divergences: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn is_active(&self, now_ms: u64) -> bool {
+        now_ms < self.ends_at
+    }
+
+    // Count a decision both configurations made; a disagreement is logged and retained
+    pub(crate) fn record(&mut self, at: u64, kind: DecisionKind, task_id: u32, live: String, shadow: String) {
+        self.decisions += 1;
+        if live == shadow {
+            return;
+        }
+        tracing::info!(candidate = %self.candidate.describe(), task_id, ?kind, live = %live, shadow = %shadow, "shadow policy diverged");
+        self.divergences += 1;
+        if self.recent.len() == MAX_DIVERGENCES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(Divergence { at, kind, task_id, live, shadow });
+    }
+
+    pub(crate) fn report(&self, now_ms: u64) -> ShadowReport {
+        ShadowReport {
+            candidate: self.candidate.describe(),
+            started_at: self.started_at,
+            ends_at: self.ends_at,
+            active: self.is_active(now_ms),
+            decisions: self.decisions,
+            divergences: self.divergences,
+            recent: self.recent.iter().cloned().collect(),
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::EarliestDeadlineFirst;
+
+    #[test]
+    fn test_trial_counts_decisions_and_keeps_divergences() {
+        let mut trial = ShadowTrial::new(ShadowCandidate::Policy(Arc::new(EarliestDeadlineFirst)), 1_000, 500);
+        trial.record(1_100, DecisionKind::Dispatch, 1, "task 1".to_string(), "task 1".to_string());
+        trial.record(1_200, DecisionKind::Dispatch, 2, "task 2".to_string(), "task 3".to_string());
+        let report = trial.report(1_300);
+        assert!(report.active);
+        assert_eq!(report.candidate, "policy earliest_deadline_first");
+        assert_eq!((report.decisions, report.divergences), (2, 1));
+        assert_eq!(report.recent[0].shadow, "task 3");
+        assert!(!trial.report(1_500).active);
+    }
+}
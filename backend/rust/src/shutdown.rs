@@ -0,0 +1,158 @@
+// backend/rust/src/shutdown.rs
+// Purpose: Graceful shutdown for MRTODP, so an embedding process (e.g. the Python delegator
+// through shutdown_scheduler_ffi) can stop without losing state. `Scheduler::shutdown`
+// refuses new submissions from then on and stops the execution loop, closing its channels,
+// so no queued task is dispatched; tasks already on a robot get the grace period to finish.
+// Tasks a robot still holds after it are checkpointed: the robot is sent `Preempt`, which
+// stops the task but keeps its progress, and the task moves to Suspended. Quota counters
+// and every unfinished task record are then written to the store, and startup recovery
+// (see recovery.rs) queues the pending and suspended tasks again on the next start.
+
+use std::collections::HashSet;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+use crate::transport::ControlCommand;
+
+// Error prefix for submissions refused once shutdown has begun
+pub const SHUTTING_DOWN_ERROR: &str = "Shutting down";
+
+// Outcome of a graceful shutdown
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    pub finished: Vec<u32>,  // On a robot when shutdown began; finished within the grace period
+    pub suspended: Vec<u32>, // Still on a robot after it; checkpointed as Suspended
+    pub unfinished: usize,   // Left in the store for recovery, suspended tasks included
+}
+
+impl Scheduler {
+    // Whether shutdown has begun; submissions are refused from then on
+    pub fn is_shutting_down(&self) -> bool {
+        *self.core.shutdown.borrow()
+    }
+
+    pub(crate) fn check_accepting(&self) -> Result<(), String> {
+        if self.is_shutting_down() {
+            return Err(format!("{}: the scheduler no longer accepts tasks", SHUTTING_DOWN_ERROR));
+        }
+        Ok(())
+    }
+
+    // Stop the scheduler without losing state: refuse new tasks, stop dispatching, wait up to
+    // `grace_period` for tasks on robots to finish, suspend those still running, and flush
+    // the store. Fails if shutdown already began or the final writes fail; the scheduler
+    // stays shut down either way. Status queries keep answering afterwards.
+    pub async fn shutdown(&self, grace_period: Duration) -> Result<ShutdownReport, String> {
+        let mut events = self.subscribe();
+        if self.core.shutdown.send_replace(true) {
+            return Err(format!("{}: shutdown already began", SHUTTING_DOWN_ERROR));
+        }
+        // Submissions waiting for room in the queue give up
+        self.core.dispatch_gate.wake_all();
+        let in_flight = self.held_by_robots().await;
+        tracing::info!(?grace_period, in_flight = in_flight.len(), "shutting down; waiting for tasks in flight");
+        let deadline = tokio::time::Instant::now() + grace_period;
+        while !self.held_by_robots().await.is_empty() {
+            match tokio::time::timeout_at(deadline, events.recv()).await {
+                Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => {}
+                Ok(Err(RecvError::Closed)) | Err(_) => break,
+            }
+        }
+
+        let mut report = ShutdownReport::default();
+        for (task_id, robot_id) in self.held_by_robots().await {
+            let detail = "Still running when the scheduler shut down".to_string();
+            if let Err(e) = self.transition_task(task_id, TaskState::Suspended, ReasonCode::SuspendedForShutdown, detail).await {
+                tracing::warn!(task_id, error = %e, "could not checkpoint task");
+                continue;
+            }
+            report.suspended.push(task_id);
+            if let (Some(robot_id), Some(_)) = (robot_id, &self.core.dispatcher) {
+                if let Err(e) = self.send_control(&robot_id, ControlCommand::Preempt { task_id }).await {
+                    tracing::warn!(task_id, robot_id = %robot_id, error = %e, "could not send preemption");
+                }
+            }
+        }
+
+        let mut first_error = self.flush_quotas().err();
+        let records = self.core.records.lock().await;
+        let suspended: HashSet<u32> = report.suspended.iter().copied().collect();
+        report.finished = in_flight
+            .iter()
+            .map(|(task_id, _)| *task_id)
+            .filter(|task_id| !suspended.contains(task_id) && records.get(task_id).is_some_and(|r| r.state.is_terminal()))
+            .collect();
+        for record in records.values().filter(|r| !r.state.is_terminal()) {
+            report.unfinished += 1;
+            if let Err(e) = self.core.store.save_task(record) {
+                tracing::warn!(task_id = record.task.id, error = %e, "could not persist task");
+                first_error.get_or_insert(e);
+            }
+        }
+        drop(records);
+        tracing::info!(finished = report.finished.len(), suspended = report.suspended.len(), unfinished = report.unfinished, "shut down");
+        match first_error {
+            Some(e) => Err(format!("Shutdown could not flush the store: {}", e)),
+            None => Ok(report),
+        }
+    }
+
+    // Tasks a robot holds (assigned or running), by task ID, with the robot
+    async fn held_by_robots(&self) -> Vec<(u32, Option<String>)> {
+        let records = self.core.records.lock().await;
+        let mut held: Vec<(u32, Option<String>)> = records
+            .values()
+            .filter(|r| r.state.is_active())
+            .map(|r| (r.task.id, r.attempts.last().and_then(|a| a.robot_id.clone())))
+            .collect();
+        held.sort();
+        held
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::store::{MemoryStore, TaskStore};
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[tokio::test]
+    async fn test_shutdown_drains_checkpoints_and_refuses_tasks() {
+        let store = Arc::new(MemoryStore::new());
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().store(store.clone()).transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        fake.script("Scion", vec![FakeBehavior::AckAfter(Duration::from_millis(50))]);
+        let worker = workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.register_robot("Scion".to_string(), vec![]).await.unwrap();
+        let pinned = |id: u32, robot: &str| Task { id, robot_id: Some(robot.to_string()), ..Default::default() };
+        for task in [pinned(375, "Ford"), pinned(376, "Scion"), pinned(377, "Ford")] {
+            scheduler.schedule_task(task).await.unwrap();
+        }
+        while scheduler.task_record(376).await.is_none_or(|r| !r.state.is_active()) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Scion finishes within the grace period; Ford's task is stopped and its queued one
+        // is never dispatched
+        let report = scheduler.shutdown(Duration::from_millis(500)).await.unwrap();
+        assert_eq!(report, ShutdownReport { finished: vec![376], suspended: vec![375], unfinished: 2 });
+        while !fake.controls().iter().any(|c| c.command == ControlCommand::Preempt { task_id: 375 }) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(fake.assignments().len(), 2);
+        tokio::time::timeout(Duration::from_secs(1), worker).await.unwrap().unwrap();
+
+        let refused = scheduler.schedule_task(pinned(378, "Scion")).await.unwrap_err();
+        assert!(refused.starts_with(SHUTTING_DOWN_ERROR));
+        assert!(scheduler.shutdown(Duration::ZERO).await.is_err());
+        let stored = |id: u32| store.load_task(id).unwrap().unwrap().state;
+        assert_eq!((stored(375), stored(377)), (TaskState::Suspended, TaskState::Pending));
+    }
+}
@@ -0,0 +1,382 @@
+// backend/rust/src/simulation.rs
+// Purpose: Simulation mode for MRTODP. `Simulation` implements `RobotTransport` with a
+// fleet of virtual robots, each configured with capabilities, a speed (multiplier on
+// task durations), a failure probability, and a battery drain per second of work, so
+// scheduling policies, retry policies, and charging settings can be evaluated end to end
+// against the real scheduler without hardware. Pass the simulation to the builder as its
+// transport, then call `Simulation::start`, which registers the robots, reports their
+// starting battery, and keeps them heartbeating. A virtual robot works on each accepted
+// task for its `estimated_duration_ms` (or the configured default) divided by its speed,
+// then reports success, or fails part-way through with its failure probability; the
+// battery it used is reported just before the result, and a completed charge task (see
+// charging.rs) refills it. A robot with an empty battery refuses everything but charge
+// tasks. Hold and Preempt pause a task's work, Resume continues it, Abort drops it,
+// coalition tasks wait for Start, and calls for bids are answered with the robot's
+// expected duration as its cost. `time_scale` runs simulated time faster than real time,
+// and `seed` makes failures reproducible. `Simulation::stats` reports what each robot
+// did.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+use crate::auction::Bid;
+use crate::charging::CHARGE_TASK_TYPE;
+use crate::scheduler::{Scheduler, CONFLICT_ERROR};
+use crate::task::Task;
+use crate::transport::{ControlCommand, ControlEnvelope, DispatchSeq, RobotTransport};
+use crate::BoxFuture;
+
+// A simulated robot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VirtualRobot {
+    pub robot_id: String,
+    pub capabilities: Vec<String>,
+    pub speed: f64,               // Multiplier on task durations; 2.0 finishes in half the time
+    pub failure_probability: f64, // Chance that a task fails, from 0 to 1
+    pub battery_drain: f64,       // Battery percentage used per simulated second of work
+    pub battery: f64,             // Starting battery percentage
+}
+
+impl VirtualRobot {
+    // A reliable robot with a full battery that never drains
+    pub fn new(robot_id: &str, capabilities: Vec<String>) -> Self {
+        VirtualRobot { robot_id: robot_id.to_string(), capabilities, speed: 1.0, failure_probability: 0.0, battery_drain: 0.0, battery: 100.0 }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.speed.is_finite() || self.speed <= 0.0 {
+            return Err(format!("Virtual robot {} needs a positive speed", self.robot_id));
+        }
+        if !(0.0..=1.0).contains(&self.failure_probability) {
+            return Err(format!("Failure probability of virtual robot {} must be between 0 and 1", self.robot_id));
+        }
+        if !self.battery_drain.is_finite() || self.battery_drain < 0.0 {
+            return Err(format!("Battery drain of virtual robot {} can't be negative", self.robot_id));
+        }
+        if !(0.0..=100.0).contains(&self.battery) {
+            return Err(format!("Battery of virtual robot {} must be between 0 and 100 percent", self.robot_id));
+        }
+        Ok(())
+    }
+}
+
+// Simulation settings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SimulationConfig {
+    pub robots: Vec<VirtualRobot>,
+    pub default_duration: Duration,   // Work for tasks without an estimated duration
+    pub time_scale: f64,              // Simulated seconds per real second
+    pub seed: u64,                    // Seed of the failure draws
+    pub heartbeat_interval: Duration, // Real time between robot heartbeats
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig { robots: Vec::new(), default_duration: Duration::from_secs(10), time_scale: 1.0, seed: 1, heartbeat_interval: Duration::from_secs(5) }
+    }
+}
+
+impl SimulationConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !self.time_scale.is_finite() || self.time_scale <= 0.0 {
+            return Err("Simulation time scale must be positive".to_string());
+        }
+        if self.heartbeat_interval.is_zero() {
+            return Err("Simulation heartbeat interval must be positive".to_string());
+        }
+        for (index, robot) in self.robots.iter().enumerate() {
+            robot.validate()?;
+            if self.robots[..index].iter().any(|r| r.robot_id == robot.robot_id) {
+                return Err(format!("Duplicate virtual robot: {}", robot.robot_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+// What a virtual robot did so far
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct VirtualRobotStats {
+    pub robot_id: String,
+    pub assigned: u64,  // Assignments accepted
+    pub completed: u64, // Tasks reported completed
+    pub failed: u64,    // Tasks reported failed
+    pub refused: u64,   // Assignments refused for an empty battery
+    pub busy_ms: u64,   // Simulated time spent working
+    pub battery: f64,   // Current battery percentage
+}
+
+// Work a virtual robot holds for one task
+struct Job {
+    robot_id: String,
+    remaining: Duration, // Simulated work left
+    fails: bool,         // Reported failed once the work is done
+    charge: bool,        // Refills the battery once done
+    running: Option<(Instant, AbortHandle)>, // Real start of the current stretch of work
+}
+
+struct SimState {
+    rng: u64,
+    robots: HashMap<String, (VirtualRobot, VirtualRobotStats)>,
+    jobs: HashMap<u32, Job>,
+}
+
+impl SimState {
+    // Next draw in [0, 1) from a xorshift64* generator
+    fn draw(&mut self) -> f64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        (self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Stop a job's current stretch of work, charging the robot for the work done; returns
+    // the robot's battery level
+    fn settle(&mut self, task_id: u32, time_scale: f64, finished: bool) -> Option<f64> {
+        let job = self.jobs.get_mut(&task_id)?;
+        let worked = match job.running.take() {
+            Some(_) if finished => job.remaining,
+            Some((started, handle)) => {
+                handle.abort();
+                started.elapsed().mul_f64(time_scale).min(job.remaining)
+            }
+            None => Duration::ZERO,
+        };
+        job.remaining -= worked;
+        let (robot, stats) = self.robots.get_mut(&job.robot_id)?;
+        stats.busy_ms += worked.as_millis() as u64;
+        stats.battery = (stats.battery - robot.battery_drain * worked.as_secs_f64()).max(0.0);
+        Some(stats.battery)
+    }
+}
+
+// RobotTransport backed by virtual robots
+pub struct Simulation {
+    config: SimulationConfig,
+    scheduler: OnceLock<Scheduler>,
+    state: Arc<Mutex<SimState>>,
+}
+
+impl Simulation {
+    pub fn new(config: SimulationConfig) -> Result<Self, String> {
+        config.validate()?;
+        let robots = config
+            .robots
+            .iter()
+            .map(|robot| {
+                let stats = VirtualRobotStats { robot_id: robot.robot_id.clone(), battery: robot.battery, ..Default::default() };
+                (robot.robot_id.clone(), (robot.clone(), stats))
+            })
+            .collect();
+        let rng = (config.seed ^ 0x9E37_79B9_7F4A_7C15).max(1);
+        Ok(Simulation { config, scheduler: OnceLock::new(), state: Arc::new(Mutex::new(SimState { rng, robots, jobs: HashMap::new() })) })
+    }
+
+    // Connect the simulation to the scheduler using it as its transport, register the
+    // virtual robots with their starting battery, and heartbeat them until shutdown
+    pub async fn start(&self, scheduler: &Scheduler) -> Result<(), String> {
+        if self.scheduler.set(scheduler.clone()).is_err() {
+            return Err(format!("{}: simulation already started", CONFLICT_ERROR));
+        }
+        for robot in &self.config.robots {
+            scheduler.register_robot(robot.robot_id.clone(), robot.capabilities.clone()).await?;
+            scheduler.report_battery(&robot.robot_id, robot.battery).await?;
+        }
+        let scheduler = scheduler.clone();
+        let robot_ids: Vec<String> = self.config.robots.iter().map(|r| r.robot_id.clone()).collect();
+        let mut ticker = tokio::time::interval(self.config.heartbeat_interval);
+        tokio::spawn(async move {
+            while !scheduler.is_shutting_down() {
+                ticker.tick().await;
+                for robot_id in &robot_ids {
+                    scheduler.robot_heartbeat(robot_id);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // What each virtual robot did so far, by robot ID
+    pub fn stats(&self) -> Vec<VirtualRobotStats> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<VirtualRobotStats> = state.robots.values().map(|(_, stats)| stats.clone()).collect();
+        stats.sort_by(|a, b| a.robot_id.cmp(&b.robot_id));
+        stats
+    }
+
+    // Start or continue the work of a job
+    fn run(&self, task_id: u32) {
+        let Some(scheduler) = self.scheduler.get().cloned() else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(job) = state.jobs.get_mut(&task_id).filter(|job| job.running.is_none()) else {
+            return;
+        };
+        let delay = job.remaining.div_f64(self.config.time_scale);
+        let shared = self.state.clone();
+        let time_scale = self.config.time_scale;
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let finished = {
+                let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                let level = state.settle(task_id, time_scale, true);
+                state.jobs.remove(&task_id).zip(level)
+            };
+            let Some((job, mut level)) = finished else {
+                return;
+            };
+            {
+                let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some((_, stats)) = state.robots.get_mut(&job.robot_id) {
+                    if job.fails {
+                        stats.failed += 1;
+                    } else {
+                        stats.completed += 1;
+                    }
+                    if job.charge && !job.fails {
+                        stats.battery = 100.0;
+                        level = 100.0;
+                    }
+                }
+            }
+            if let Err(e) = scheduler.report_battery(&job.robot_id, level).await {
+                tracing::warn!(task_id, robot_id = %job.robot_id, error = %e, "simulation could not report battery");
+            }
+            let result = if job.fails { Err(format!("Virtual robot {} failed the task", job.robot_id)) } else { Ok(()) };
+            scheduler.report_result(task_id, result).await;
+        });
+        job.running = Some((Instant::now(), handle.abort_handle()));
+    }
+
+    // Stop the work of a job, keeping it for a later Resume unless it is dropped
+    async fn stop(&self, task_id: u32, drop_job: bool) {
+        let stopped = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let level = state.settle(task_id, self.config.time_scale, false);
+            let robot_id = if drop_job { state.jobs.remove(&task_id).map(|job| job.robot_id) } else { state.jobs.get(&task_id).map(|job| job.robot_id.clone()) };
+            robot_id.zip(level)
+        };
+        if let (Some(scheduler), Some((robot_id, level))) = (self.scheduler.get(), stopped) {
+            if let Err(e) = scheduler.report_battery(&robot_id, level).await {
+                tracing::warn!(task_id, robot_id = %robot_id, error = %e, "simulation could not report battery");
+            }
+        }
+    }
+
+    // Answer a call for bids with the robot's expected duration for a task of default
+    // length, and the real time until its current work is done
+    fn bid(&self, robot_id: &str, task_id: u32) -> Result<(), String> {
+        let Some(scheduler) = self.scheduler.get() else {
+            return Ok(());
+        };
+        let bid = {
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let (robot, _) = state.robots.get(robot_id).ok_or_else(|| format!("Unknown virtual robot: {}", robot_id))?;
+            let busy = state.jobs.values().filter(|job| job.robot_id == robot_id).map(|job| job.remaining).sum::<Duration>();
+            Bid {
+                robot_id: robot_id.to_string(),
+                cost: self.config.default_duration.div_f64(robot.speed).as_millis() as f64,
+                eta_ms: busy.div_f64(self.config.time_scale).as_millis() as u64,
+            }
+        };
+        scheduler.submit_bid(task_id, bid)
+    }
+}
+
+impl RobotTransport for Simulation {
+    fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, _seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let (robot, stats) = state.robots.get_mut(robot_id).ok_or_else(|| format!("Unknown virtual robot: {}", robot_id))?;
+                let charge = task.task_type == CHARGE_TASK_TYPE;
+                if stats.battery <= 0.0 && !charge {
+                    stats.refused += 1;
+                    return Err(format!("Virtual robot {} has an empty battery", robot_id));
+                }
+                stats.assigned += 1;
+                let work = task.estimated_duration_ms.map(Duration::from_millis).unwrap_or(self.config.default_duration).div_f64(robot.speed);
+                let failure_probability = robot.failure_probability;
+                let fails = state.draw() < failure_probability;
+                // A failing task gives up part-way through its work
+                let remaining = if fails { work.mul_f64(state.draw()) } else { work };
+                // A task reassigned to the same robot starts over
+                if let Some(mut job) = state.jobs.remove(&task.id) {
+                    if let Some((_, handle)) = job.running.take() {
+                        handle.abort();
+                    }
+                }
+                state.jobs.insert(task.id, Job { robot_id: robot_id.to_string(), remaining, fails, charge, running: None });
+            }
+            if task.coalition.is_none() {
+                self.run(task.id);
+            }
+            Ok(())
+        })
+    }
+
+    fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            match &envelope.command {
+                ControlCommand::Abort { task_id } => self.stop(*task_id, true).await,
+                ControlCommand::Hold { task_id } | ControlCommand::Preempt { task_id } => self.stop(*task_id, false).await,
+                ControlCommand::Resume { task_id } | ControlCommand::Start { task_id } => self.run(*task_id),
+                ControlCommand::CallForBids { task_id, .. } => self.bid(&envelope.robot_id, *task_id)?,
+            }
+            Ok(())
+        })
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retries::RetryPolicy;
+    use crate::scheduler::TaskState;
+
+    #[tokio::test]
+    async fn test_virtual_robots_work_through_tasks() {
+        let mut fast = VirtualRobot::new("Ford", vec!["lift".to_string()]);
+        fast.speed = 2.0;
+        fast.battery_drain = 1.0;
+        let mut flaky = VirtualRobot::new("Scion", vec!["lift".to_string()]);
+        flaky.failure_probability = 1.0;
+        let config = SimulationConfig { robots: vec![fast, flaky], time_scale: 100.0, ..Default::default() };
+        let simulation = Arc::new(Simulation::new(config).unwrap());
+        let policy = RetryPolicy { max_attempts: 3, ..Default::default() };
+        let (scheduler, workers) = Scheduler::builder().transport(simulation.clone()).retry_policy(policy).build().unwrap();
+        workers.spawn();
+        simulation.start(&scheduler).await.unwrap();
+        for id in 408..=411 {
+            let task = Task { id, required_capabilities: vec!["lift".to_string()], estimated_duration_ms: Some(1_000), ..Default::default() };
+            scheduler.schedule_task(task).await.unwrap();
+        }
+        for id in 408..=411 {
+            while scheduler.task_record(id).await.is_none_or(|r| r.state != TaskState::Completed) {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+
+        // Every task ends on the reliable robot, which used half a second of work on each
+        let stats = simulation.stats();
+        assert_eq!((stats[0].robot_id.as_str(), stats[0].completed, stats[0].busy_ms), ("Ford", 4, 2_000));
+        assert_eq!(stats[0].battery, 98.0);
+        assert_eq!(scheduler.battery_level("Ford"), Some(98.0));
+        assert_eq!((stats[1].completed, stats[1].failed), (0, stats[1].assigned));
+    }
+
+    #[test]
+    fn test_invalid_configs_are_rejected() {
+        let mut robot = VirtualRobot::new("Ford", vec![]);
+        robot.failure_probability = 1.5;
+        assert!(Simulation::new(SimulationConfig { robots: vec![robot], ..Default::default() }).is_err());
+        let twins = vec![VirtualRobot::new("Ford", vec![]), VirtualRobot::new("Ford", vec![])];
+        let error = Simulation::new(SimulationConfig { robots: twins, ..Default::default() }).err().unwrap();
+        assert_eq!(error, "Duplicate virtual robot: Ford");
+    }
+}
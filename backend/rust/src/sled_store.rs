@@ -0,0 +1,209 @@
+// backend/rust/src/sled_store.rs
+// Purpose: On-disk `TaskStore` for MRTODP backed by the sled embedded database, enabled
+// by the `sled` feature. Task records, robot registrations, slots and models, profiles,
+// the audit log, quota counters, and missions are kept as JSON in one tree each. Task,
+// robot, and mission writes are flushed before returning, so a scheduler restarted after
+// a crash reloads every task it accepted and re-queues the unfinished ones.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::audit::AuditEntry;
+use crate::compatibility::RobotModel;
+use crate::missions::Mission;
+use crate::profiles::RobotProfile;
+use crate::quotas::QuotaCounter;
+use crate::scheduler::TaskRecord;
+use crate::store::TaskStore;
+
+// Key of the single quota counter snapshot in its tree
+const QUOTA_SNAPSHOT_KEY: &[u8] = b"snapshot";
+
+// Store persisting scheduler state in a sled database directory
+pub struct SledStore {
+    db: sled::Db,
+    tasks: sled::Tree,        // task ID (big-endian) -> TaskRecord
+    robots: sled::Tree,       // robot ID -> capabilities
+    robot_slots: sled::Tree,  // robot ID -> slot count
+    robot_models: sled::Tree, // robot ID -> RobotModel
+    profiles: sled::Tree,     // robot ID -> RobotProfile
+    audit: sled::Tree,        // monotonic ID (big-endian) -> AuditEntry
+    quotas: sled::Tree,       // QUOTA_SNAPSHOT_KEY -> namespace counters
+    missions: sled::Tree,     // mission ID -> Mission
+}
+
+impl SledStore {
+    // Open the database at `path`, creating it if it does not exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let db = sled::open(path).map_err(|e| format!("Failed to open store at {}: {}", path.display(), e))?;
+        let tree = |name: &str| db.open_tree(name).map_err(|e| format!("Failed to open store tree {}: {}", name, e));
+        Ok(SledStore {
+            tasks: tree("tasks")?,
+            robots: tree("robots")?,
+            robot_slots: tree("robot_slots")?,
+            robot_models: tree("robot_models")?,
+            profiles: tree("profiles")?,
+            audit: tree("audit")?,
+            quotas: tree("quota_counters")?,
+            missions: tree("missions")?,
+            db,
+        })
+    }
+
+    fn put<T: Serialize + ?Sized>(tree: &sled::Tree, key: &[u8], value: &T) -> Result<(), String> {
+        let bytes = serde_json::to_vec(value).map_err(|e| format!("Failed to encode store entry: {}", e))?;
+        tree.insert(key, bytes).map_err(|e| format!("Store write failed: {}", e))?;
+        Ok(())
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+        serde_json::from_slice(bytes).map_err(|e| format!("Corrupt store entry: {}", e))
+    }
+
+    // Every entry of a tree as (UTF-8 key, value)
+    fn entries<T: DeserializeOwned>(tree: &sled::Tree) -> Result<Vec<(String, T)>, String> {
+        tree.iter()
+            .map(|entry| {
+                let (key, value) = entry.map_err(|e| format!("Store read failed: {}", e))?;
+                Ok((String::from_utf8_lossy(&key).into_owned(), Self::decode(&value)?))
+            })
+            .collect()
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        self.db.flush().map(|_| ()).map_err(|e| format!("Store flush failed: {}", e))
+    }
+}
+
+impl TaskStore for SledStore {
+    fn save_task(&self, record: &TaskRecord) -> Result<(), String> {
+        Self::put(&self.tasks, &record.task.id.to_be_bytes(), record)?;
+        self.flush()
+    }
+
+    fn load_task(&self, task_id: u32) -> Result<Option<TaskRecord>, String> {
+        let value = self.tasks.get(task_id.to_be_bytes()).map_err(|e| format!("Store read failed: {}", e))?;
+        value.map(|bytes| Self::decode(&bytes)).transpose()
+    }
+
+    fn load_tasks(&self) -> Result<Vec<TaskRecord>, String> {
+        Ok(Self::entries(&self.tasks)?.into_iter().map(|(_, record)| record).collect())
+    }
+
+    fn remove_task(&self, task_id: u32) -> Result<(), String> {
+        self.tasks.remove(task_id.to_be_bytes()).map_err(|e| format!("Store write failed: {}", e))?;
+        self.flush()
+    }
+
+    fn save_robot(&self, robot_id: &str, capabilities: &[String]) -> Result<(), String> {
+        Self::put(&self.robots, robot_id.as_bytes(), capabilities)?;
+        self.flush()
+    }
+
+    fn load_robots(&self) -> Result<HashMap<String, Vec<String>>, String> {
+        Ok(Self::entries(&self.robots)?.into_iter().collect())
+    }
+
+    fn remove_robot(&self, robot_id: &str) -> Result<(), String> {
+        self.robots.remove(robot_id.as_bytes()).map_err(|e| format!("Store write failed: {}", e))?;
+        self.flush()
+    }
+
+    fn save_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String> {
+        Self::put(&self.robot_slots, robot_id.as_bytes(), &slots)
+    }
+
+    fn load_robot_slots(&self) -> Result<HashMap<String, u32>, String> {
+        Ok(Self::entries(&self.robot_slots)?.into_iter().collect())
+    }
+
+    fn save_robot_model(&self, robot_id: &str, model: &RobotModel) -> Result<(), String> {
+        Self::put(&self.robot_models, robot_id.as_bytes(), model)
+    }
+
+    fn load_robot_models(&self) -> Result<HashMap<String, RobotModel>, String> {
+        Ok(Self::entries(&self.robot_models)?.into_iter().collect())
+    }
+
+    fn save_profile(&self, profile: &RobotProfile) -> Result<(), String> {
+        Self::put(&self.profiles, profile.robot_id.as_bytes(), profile)
+    }
+
+    fn load_profiles(&self) -> Result<Vec<RobotProfile>, String> {
+        Ok(Self::entries(&self.profiles)?.into_iter().map(|(_, profile)| profile).collect())
+    }
+
+    fn append_audit(&self, entry: &AuditEntry) -> Result<(), String> {
+        let id = self.db.generate_id().map_err(|e| format!("Store write failed: {}", e))?;
+        Self::put(&self.audit, &id.to_be_bytes(), entry)
+    }
+
+    fn load_audit(&self) -> Result<Vec<AuditEntry>, String> {
+        Ok(Self::entries(&self.audit)?.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    fn save_quota_counters(&self, counters: &HashMap<String, QuotaCounter>) -> Result<(), String> {
+        Self::put(&self.quotas, QUOTA_SNAPSHOT_KEY, counters)
+    }
+
+    fn load_quota_counters(&self) -> Result<HashMap<String, QuotaCounter>, String> {
+        let value = self.quotas.get(QUOTA_SNAPSHOT_KEY).map_err(|e| format!("Store read failed: {}", e))?;
+        Ok(value.map(|bytes| Self::decode(&bytes)).transpose()?.unwrap_or_default())
+    }
+
+    fn save_mission(&self, mission: &Mission) -> Result<(), String> {
+        Self::put(&self.missions, mission.id.as_bytes(), mission)?;
+        self.flush()
+    }
+
+    fn load_missions(&self) -> Result<Vec<Mission>, String> {
+        Ok(Self::entries(&self.missions)?.into_iter().map(|(_, mission)| mission).collect())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::scheduler::{Scheduler, TaskState};
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_queue_and_robots_survive_reopen() {
+        let path = std::env::temp_dir().join(format!("mrtodp-sled-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        {
+            let store = Arc::new(SledStore::open(&path).unwrap());
+            let (scheduler, _workers) = Scheduler::builder().store(store).build().unwrap();
+            scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+            scheduler.schedule_task(Task { id: 211, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+            scheduler.schedule_task(Task { id: 212, ..Default::default() }).await.unwrap();
+        }
+
+        // sled's flusher thread lets go of the database lock shortly after the store is dropped
+        let store = loop {
+            match SledStore::open(&path) {
+                Ok(store) => break Arc::new(store),
+                Err(e) if e.contains("could not acquire lock") => tokio::time::sleep(std::time::Duration::from_millis(5)).await,
+                Err(e) => panic!("{}", e),
+            }
+        };
+        assert_eq!(store.load_robots().unwrap()["Ford"], vec!["lift".to_string()]);
+        let (restarted, workers) = Scheduler::builder().store(store).build().unwrap();
+        let mut events = restarted.subscribe();
+        workers.spawn();
+        let mut finished = Vec::new();
+        while finished.len() < 2 {
+            let event = events.recv().await.unwrap();
+            if event.transition.to == TaskState::Completed {
+                finished.push(event.task_id);
+            }
+        }
+        finished.sort();
+        assert_eq!(finished, vec![211, 212]);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+// backend/rust/src/slo.rs
+// Purpose: Assignment latency SLOs for MRTODP. Operators declare objectives such as "95% of
+// priority >= 8 tasks assigned within 2 s"; every dispatch of a matching task counts as met
+// or missed, attainment and error-budget burn rate are tracked over a sliding window, and
+// an `SloAlert` is broadcast when the burn rate crosses the alert threshold (the SLO is at
+// risk of being breached) and again when it recovers.
+
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+// Cap on retained samples per SLO, bounding memory under very high throughput
+const MAX_SAMPLES: usize = 100_000;
+
+// One assignment latency objective
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SloSpec {
+    pub name: String,
+    #[serde(default)]
+    pub min_priority: u32, // Tasks below this priority are not counted
+    pub target_ms: u64,    // Submission-to-assignment latency a task must meet
+    pub objective: f64,    // Fraction of tasks that must meet the target, e.g. 0.95
+    #[serde(default = "default_window_ms")]
+    pub window_ms: u64, // Sliding window attainment is measured over
+    #[serde(default = "default_alert_burn_rate")]
+    pub alert_burn_rate: f64, // Burn rate at which the SLO is reported at risk
+    #[serde(default = "default_min_samples")]
+    pub min_samples: u64, // Samples needed in the window before alerting
+}
+
+fn default_window_ms() -> u64 {
+    60 * 60 * 1000
+}
+
+fn default_alert_burn_rate() -> f64 {
+    2.0
+}
+
+fn default_min_samples() -> u64 {
+    20
+}
+
+impl SloSpec {
+    // Objective with the default window (1 h), alert burn rate (2x), and minimum samples (20)
+    pub fn new(name: &str, min_priority: u32, target_ms: u64, objective: f64) -> Self {
+        SloSpec {
+            name: name.to_string(),
+            min_priority,
+            target_ms,
+            objective,
+            window_ms: default_window_ms(),
+            alert_burn_rate: default_alert_burn_rate(),
+            min_samples: default_min_samples(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("SLO name must not be empty".to_string());
+        }
+        if !(self.objective > 0.0 && self.objective < 1.0) {
+            return Err(format!("SLO {}: objective must be between 0 and 1 exclusive", self.name));
+        }
+        if self.window_ms == 0 || self.alert_burn_rate <= 0.0 {
+            return Err(format!("SLO {}: window and alert burn rate must be positive", self.name));
+        }
+        Ok(())
+    }
+}
+
+// Current attainment of an SLO
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SloStatus {
+    pub name: String,
+    pub objective: f64,
+    pub target_ms: u64,
+    pub total: u64,              // Matching tasks assigned within the window
+    pub met: u64,                // Of those, assigned within the target
+    pub attainment: Option<f64>, // met / total; None without samples
+    pub burn_rate: f64,          // Miss rate relative to the error budget; 1.0 spends it exactly
+    pub at_risk: bool,
+}
+
+// Broadcast when an SLO becomes at risk or recovers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SloAlert {
+    pub name: String,
+    pub at_risk: bool,
+    pub burn_rate: f64,
+    pub attainment: Option<f64>,
+    pub at: u64, // Unix timestamp (milliseconds)
+}
+
+struct TrackedSlo {
+    spec: SloSpec,
+    samples: VecDeque<(u64, bool)>, // (assigned at, met target)
+    at_risk: bool,
+}
+
+impl TrackedSlo {
+    fn prune(&mut self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(self.spec.window_ms);
+        while self.samples.front().is_some_and(|&(at, _)| at < cutoff) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn status(&self) -> SloStatus {
+        let total = self.samples.len() as u64;
+        let met = self.samples.iter().filter(|(_, met)| *met).count() as u64;
+        let attainment = (total > 0).then(|| met as f64 / total as f64);
+        let burn_rate = attainment.map_or(0.0, |a| (1.0 - a) / (1.0 - self.spec.objective));
+        SloStatus {
+            name: self.spec.name.clone(),
+            objective: self.spec.objective,
+            target_ms: self.spec.target_ms,
+            total,
+            met,
+            attainment,
+            burn_rate,
+            at_risk: self.at_risk,
+        }
+    }
+}
+
+// Attainment tracking for every configured SLO
+#[derive(Default)]
+pub(crate) struct SloTracker {
+    slos: Vec<TrackedSlo>,
+}
+
+impl SloTracker {
+    pub(crate) fn new(specs: Vec<SloSpec>) -> Self {
+        SloTracker { slos: specs.into_iter().map(|spec| TrackedSlo { spec, samples: VecDeque::new(), at_risk: false }).collect() }
+    }
+
+    // Count an assignment against every matching SLO; returns alerts for SLOs whose risk
+    // state changed
+    pub(crate) fn record(&mut self, priority: u32, latency_ms: u64, now_ms: u64) -> Vec<SloAlert> {
+        let mut alerts = Vec::new();
+        for slo in self.slos.iter_mut().filter(|s| priority >= s.spec.min_priority) {
+            if slo.samples.len() == MAX_SAMPLES {
+                slo.samples.pop_front();
+            }
+            slo.samples.push_back((now_ms, latency_ms <= slo.spec.target_ms));
+            slo.prune(now_ms);
+            let status = slo.status();
+            let at_risk = status.total >= slo.spec.min_samples && status.burn_rate >= slo.spec.alert_burn_rate;
+            if at_risk != slo.at_risk {
+                slo.at_risk = at_risk;
+                alerts.push(SloAlert {
+                    name: status.name,
+                    at_risk,
+                    burn_rate: status.burn_rate,
+                    attainment: status.attainment,
+                    at: now_ms,
+                });
+            }
+        }
+        alerts
+    }
+
+    pub(crate) fn status(&mut self, now_ms: u64) -> Vec<SloStatus> {
+        self.slos
+            .iter_mut()
+            .map(|slo| {
+                slo.prune(now_ms);
+                slo.status()
+            })
+            .collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_rate_alerts_and_recovers() {
+        let spec = SloSpec { min_samples: 4, ..SloSpec::new("urgent_assign", 8, 2_000, 0.9) };
+        spec.validate().unwrap();
+        let mut tracker = SloTracker::new(vec![spec]);
+        // Low-priority tasks don't count
+        assert!(tracker.record(3, 10_000, 0).is_empty());
+        for at in 1..=3 {
+            assert!(tracker.record(9, 100, at).is_empty());
+        }
+        // One miss in four is 25% against a 10% budget: burn rate 2.5
+        let alerts = tracker.record(9, 5_000, 4);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].at_risk);
+        assert!((alerts[0].burn_rate - 2.5).abs() < 1e-9);
+
+        // Once the miss leaves the window, the SLO recovers
+        let later = default_window_ms() + 10;
+        let alerts = tracker.record(9, 100, later);
+        assert!(!alerts[0].at_risk);
+        let status = &tracker.status(later)[0];
+        assert_eq!((status.total, status.met), (1, 1));
+        assert!(SloSpec::new("bad", 0, 1_000, 1.5).validate().is_err());
+    }
+}
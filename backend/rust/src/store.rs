@@ -0,0 +1,227 @@
+// backend/rust/src/store.rs
+// Purpose: Storage backend abstraction for the MRTODP scheduler. The scheduler keeps its
+// working state in memory and writes task records, robot registrations, slot counts and
+// models, robot performance profiles, the operator audit log, daily quota counters, and
+// registered missions through to a `TaskStore`, selected at construction via the builder,
+// and reloads them on startup.
+// `MemoryStore` is the default and, like the scheduler's audit log, keeps only the most
+// recent audit entries; `WalStore` and `SledStore` (behind the `sled` feature) persist to
+// disk.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use crate::audit::{AuditEntry, DEFAULT_AUDIT_CAPACITY};
+use crate::compatibility::RobotModel;
+use crate::missions::Mission;
+use crate::profiles::RobotProfile;
+use crate::quotas::QuotaCounter;
+use crate::scheduler::TaskRecord;
+
+// Write-through persistence for task records, robot registrations, slots and models,
+// robot profiles, audit entries, quota counters, and missions
+pub trait TaskStore: Send + Sync {
+    fn save_task(&self, record: &TaskRecord) -> Result<(), String>;
+    fn load_task(&self, task_id: u32) -> Result<Option<TaskRecord>, String>;
+    fn load_tasks(&self) -> Result<Vec<TaskRecord>, String>;
+    // Drop a task record for good, e.g. when purged from the dead-letter queue
+    fn remove_task(&self, task_id: u32) -> Result<(), String>;
+    fn save_robot(&self, robot_id: &str, capabilities: &[String]) -> Result<(), String>;
+    fn load_robots(&self) -> Result<HashMap<String, Vec<String>>, String>;
+    // Drop a registration; slots, model and profile stay for a later re-registration
+    fn remove_robot(&self, robot_id: &str) -> Result<(), String>;
+    fn save_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String>;
+    fn load_robot_slots(&self) -> Result<HashMap<String, u32>, String>;
+    fn save_robot_model(&self, robot_id: &str, model: &RobotModel) -> Result<(), String>;
+    fn load_robot_models(&self) -> Result<HashMap<String, RobotModel>, String>;
+    fn save_profile(&self, profile: &RobotProfile) -> Result<(), String>;
+    fn load_profiles(&self) -> Result<Vec<RobotProfile>, String>;
+    fn append_audit(&self, entry: &AuditEntry) -> Result<(), String>;
+    fn load_audit(&self) -> Result<Vec<AuditEntry>, String>;
+    // Quota counters are written as one snapshot, replacing the previous one
+    fn save_quota_counters(&self, counters: &HashMap<String, QuotaCounter>) -> Result<(), String>;
+    fn load_quota_counters(&self) -> Result<HashMap<String, QuotaCounter>, String>;
+    fn save_mission(&self, mission: &Mission) -> Result<(), String>;
+    fn load_missions(&self) -> Result<Vec<Mission>, String>;
+    // Reclaim space left by overwritten and removed entries; the workers call this
+    // periodically. Stores without such space have nothing to do.
+    fn compact(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// In-memory store; state does not survive a process restart. Like the scheduler's own
+// audit log, it keeps only the most recent audit entries.
+pub struct MemoryStore {
+    tasks: Mutex<HashMap<u32, TaskRecord>>,
+    robots: Mutex<HashMap<String, Vec<String>>>,
+    robot_slots: Mutex<HashMap<String, u32>>,
+    robot_models: Mutex<HashMap<String, RobotModel>>,
+    profiles: Mutex<HashMap<String, RobotProfile>>,
+    audit: Mutex<VecDeque<AuditEntry>>,
+    audit_capacity: usize, // Oldest entries are dropped past this
+    quota_counters: Mutex<HashMap<String, QuotaCounter>>,
+    missions: Mutex<HashMap<String, Mission>>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::with_audit_capacity(DEFAULT_AUDIT_CAPACITY)
+    }
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Store keeping at most `entries` audit entries (at least one)
+    pub fn with_audit_capacity(entries: usize) -> Self {
+        MemoryStore {
+            tasks: Mutex::default(),
+            robots: Mutex::default(),
+            robot_slots: Mutex::default(),
+            robot_models: Mutex::default(),
+            profiles: Mutex::default(),
+            audit: Mutex::default(),
+            audit_capacity: entries.max(1),
+            quota_counters: Mutex::default(),
+            missions: Mutex::default(),
+        }
+    }
+}
+
+impl TaskStore for MemoryStore {
+    fn save_task(&self, record: &TaskRecord) -> Result<(), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        tasks.insert(record.task.id, record.clone());
+        Ok(())
+    }
+
+    fn load_task(&self, task_id: u32) -> Result<Option<TaskRecord>, String> {
+        let tasks = self.tasks.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(tasks.get(&task_id).cloned())
+    }
+
+    fn load_tasks(&self) -> Result<Vec<TaskRecord>, String> {
+        let tasks = self.tasks.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(tasks.values().cloned().collect())
+    }
+
+    fn remove_task(&self, task_id: u32) -> Result<(), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        tasks.remove(&task_id);
+        Ok(())
+    }
+
+    fn save_robot(&self, robot_id: &str, capabilities: &[String]) -> Result<(), String> {
+        let mut robots = self.robots.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        robots.insert(robot_id.to_string(), capabilities.to_vec());
+        Ok(())
+    }
+
+    fn load_robots(&self) -> Result<HashMap<String, Vec<String>>, String> {
+        let robots = self.robots.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(robots.clone())
+    }
+
+    fn remove_robot(&self, robot_id: &str) -> Result<(), String> {
+        let mut robots = self.robots.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        robots.remove(robot_id);
+        Ok(())
+    }
+
+    fn save_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String> {
+        let mut robot_slots = self.robot_slots.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        robot_slots.insert(robot_id.to_string(), slots);
+        Ok(())
+    }
+
+    fn load_robot_slots(&self) -> Result<HashMap<String, u32>, String> {
+        let robot_slots = self.robot_slots.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(robot_slots.clone())
+    }
+
+    fn save_robot_model(&self, robot_id: &str, model: &RobotModel) -> Result<(), String> {
+        let mut robot_models = self.robot_models.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        robot_models.insert(robot_id.to_string(), model.clone());
+        Ok(())
+    }
+
+    fn load_robot_models(&self) -> Result<HashMap<String, RobotModel>, String> {
+        let robot_models = self.robot_models.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(robot_models.clone())
+    }
+
+    fn save_profile(&self, profile: &RobotProfile) -> Result<(), String> {
+        let mut profiles = self.profiles.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        profiles.insert(profile.robot_id.clone(), profile.clone());
+        Ok(())
+    }
+
+    fn load_profiles(&self) -> Result<Vec<RobotProfile>, String> {
+        let profiles = self.profiles.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(profiles.values().cloned().collect())
+    }
+
+    fn append_audit(&self, entry: &AuditEntry) -> Result<(), String> {
+        let mut audit = self.audit.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        if audit.len() == self.audit_capacity {
+            audit.pop_front();
+        }
+        audit.push_back(entry.clone());
+        Ok(())
+    }
+
+    fn load_audit(&self) -> Result<Vec<AuditEntry>, String> {
+        let audit = self.audit.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(audit.iter().cloned().collect())
+    }
+
+    fn save_quota_counters(&self, counters: &HashMap<String, QuotaCounter>) -> Result<(), String> {
+        let mut quota_counters = self.quota_counters.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        *quota_counters = counters.clone();
+        Ok(())
+    }
+
+    fn load_quota_counters(&self) -> Result<HashMap<String, QuotaCounter>, String> {
+        let quota_counters = self.quota_counters.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(quota_counters.clone())
+    }
+
+    fn save_mission(&self, mission: &Mission) -> Result<(), String> {
+        let mut missions = self.missions.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        missions.insert(mission.id.clone(), mission.clone());
+        Ok(())
+    }
+
+    fn load_missions(&self) -> Result<Vec<Mission>, String> {
+        let missions = self.missions.lock().map_err(|e| format!("Store lock poisoned: {}", e))?;
+        Ok(missions.values().cloned().collect())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditAction;
+
+    #[test]
+    fn test_memory_store_keeps_recent_audit_entries() {
+        let store = MemoryStore::with_audit_capacity(2);
+        for at in 0..5 {
+            let entry = AuditEntry {
+                at,
+                action: AuditAction::TaskScheduled,
+                actor: None,
+                task_id: None,
+                robot_id: None,
+                previous_robot_id: None,
+                detail: String::new(),
+            };
+            store.append_audit(&entry).unwrap();
+        }
+        let kept: Vec<u64> = store.load_audit().unwrap().iter().map(|e| e.at).collect();
+        assert_eq!(kept, vec![3, 4]);
+    }
+}
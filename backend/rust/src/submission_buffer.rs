@@ -0,0 +1,133 @@
+// backend/rust/src/submission_buffer.rs
+// Purpose: Bounded buffer for task submissions that arrive while the scheduler core is not
+// ready (e.g. still initializing). Holds submissions in memory up to a capacity, spills
+// further submissions to a JSON-lines file up to a second bound, and hands them back in
+// submission order for flushing. Each submission gets a provisional token. A spill file
+// left behind by an earlier process is cleared when the buffer is created: its tokens
+// were never answered and would collide with the new ones.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::task::Task;
+
+// A buffered submission and the provisional token returned to the caller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BufferedSubmission {
+    pub token: String,
+    pub task: Task,
+}
+
+// Memory-first submission buffer with optional spill-to-disk
+pub struct SubmissionBuffer {
+    memory_capacity: usize,
+    spill_capacity: usize, // Maximum submissions spilled to disk (0 without a spill path)
+    spill_path: Option<PathBuf>,
+    memory: VecDeque<BufferedSubmission>,
+    spilled: usize,
+    next_token: u64,
+}
+
+impl SubmissionBuffer {
+    pub fn new(memory_capacity: usize, spill_path: Option<PathBuf>, spill_capacity: usize) -> Result<Self, String> {
+        if let Some(path) = &spill_path {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Stale spill file {} could not be removed: {}", path.display(), e));
+                }
+                _ => {}
+            }
+        }
+        Ok(SubmissionBuffer {
+            memory_capacity,
+            spill_capacity: if spill_path.is_some() { spill_capacity } else { 0 },
+            spill_path,
+            memory: VecDeque::new(),
+            spilled: 0,
+            next_token: 1,
+        })
+    }
+
+    // Number of buffered submissions (memory and disk)
+    pub fn len(&self) -> usize {
+        self.memory.len() + self.spilled
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Buffer a task, returning its provisional token; errors when both tiers are full
+    pub fn push(&mut self, task: Task) -> Result<String, String> {
+        let token = format!("buf-{}", self.next_token);
+        let submission = BufferedSubmission { token: token.clone(), task };
+        // Once anything has spilled, keep spilling so disk order stays behind memory order
+        if self.memory.len() < self.memory_capacity && self.spilled == 0 {
+            self.memory.push_back(submission);
+        } else if self.spilled < self.spill_capacity {
+            let path = self.spill_path.as_ref().expect("spill capacity implies a spill path");
+            let line = serde_json::to_string(&submission).map_err(|e| format!("Spill serialization failed: {}", e))?;
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Spill file {} unavailable: {}", path.display(), e))?;
+            writeln!(file, "{}", line).map_err(|e| format!("Spill write failed: {}", e))?;
+            self.spilled += 1;
+        } else {
+            return Err(format!("Submission buffer full ({} buffered)", self.len()));
+        }
+        self.next_token += 1;
+        Ok(token)
+    }
+
+    // Remove and return every buffered submission in submission order
+    pub fn drain(&mut self) -> Result<Vec<BufferedSubmission>, String> {
+        let mut submissions: Vec<BufferedSubmission> = self.memory.drain(..).collect();
+        if self.spilled > 0 {
+            let path = self.spill_path.as_ref().expect("spilled entries imply a spill path");
+            let file = File::open(path).map_err(|e| format!("Spill file {} unreadable: {}", path.display(), e))?;
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(|e| format!("Spill read failed: {}", e))?;
+                let submission = serde_json::from_str(&line).map_err(|e| format!("Corrupt spill entry: {}", e))?;
+                submissions.push(submission);
+            }
+            fs::remove_file(path).map_err(|e| format!("Spill cleanup failed: {}", e))?;
+            self.spilled = 0;
+        }
+        Ok(submissions)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spills_and_drains_in_order() {
+        let path = std::env::temp_dir().join(format!("mrtodp-spill-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        // Left over from a crashed process
+        fs::write(&path, "{\"token\":\"buf-1\",\"task\":{\"id\":99}}\n").unwrap();
+        let mut buffer = SubmissionBuffer::new(2, Some(path.clone()), 2).unwrap();
+        let tokens: Vec<String> = (1..=4).map(|id| buffer.push(Task { id, ..Default::default() }).unwrap()).collect();
+        assert_eq!(tokens[3], "buf-4");
+        assert!(path.exists());
+        assert!(buffer.push(Task { id: 5, ..Default::default() }).unwrap_err().contains("full"));
+
+        let ids: Vec<u32> = buffer.drain().unwrap().iter().map(|s| s.task.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert!(buffer.is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_memory_only_bound() {
+        let mut buffer = SubmissionBuffer::new(1, None, 10).unwrap();
+        buffer.push(Task::default()).unwrap();
+        assert!(buffer.push(Task::default()).is_err());
+    }
+}
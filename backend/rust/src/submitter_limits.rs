@@ -0,0 +1,245 @@
+// backend/rust/src/submitter_limits.rs
+// Purpose: Per-submitter limits on MRTODP submissions, complementing the daily namespace
+// quotas in quotas.rs. A limit applies to a namespace (tenant) or to a task source, the
+// submitting system or API key a front-end records in `Task::source`, and caps how many of
+// its tasks may be unfinished at once and how many it may submit in any 60-second window.
+// `schedule_task` and upload commits reject a submission that would go over a limit with a
+// `QuotaExceeded` error naming the limit. Limits are set on the builder and can be changed
+// at runtime through the admin API (`Scheduler::set_submitter_limits`, PUT
+// /admin/limits/{namespace|source}/{name} over REST, or the FFI); unfinished tasks are
+// counted from the start, so a limit added later applies to work already queued.
+
+use std::collections::{HashMap, VecDeque};
+use serde::{Deserialize, Serialize};
+use crate::missions::namespace_of;
+use crate::quotas::QUOTA_EXCEEDED_ERROR;
+use crate::scheduler::TaskState;
+use crate::task::Task;
+
+const WINDOW_MS: u64 = 60_000;
+
+// Whose submissions a limit applies to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Submitter {
+    Namespace(String),
+    Source(String), // Task::source, e.g. an API key's owner
+}
+
+impl Submitter {
+    // Submitter from its kind ("namespace" or "source") and name, as the front-ends take it
+    pub fn parse(kind: &str, name: &str) -> Result<Self, String> {
+        match kind {
+            "namespace" => Ok(Submitter::Namespace(name.to_string())),
+            "source" => Ok(Submitter::Source(name.to_string())),
+            other => Err(format!("Unknown submitter kind: {}", other)),
+        }
+    }
+
+    // The submitters a task counts against: its namespace, and its source if it has one
+    fn of(task: &Task) -> impl Iterator<Item = Submitter> {
+        let namespace = Submitter::Namespace(namespace_of(task).to_string());
+        std::iter::once(namespace).chain(task.source.clone().map(Submitter::Source))
+    }
+}
+
+impl std::fmt::Display for Submitter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Submitter::Namespace(name) => write!(f, "namespace {}", name),
+            Submitter::Source(name) => write!(f, "source {}", name),
+        }
+    }
+}
+
+// Limits on one submitter; None = unlimited
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubmitterLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queued: Option<u64>, // Unfinished tasks at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_minute: Option<u32>, // Submissions in any 60-second window
+}
+
+impl SubmitterLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_queued.is_none() && self.max_per_minute.is_none()
+    }
+}
+
+// A submitter's limits and current standing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SubmitterUsage {
+    pub submitter: Submitter,
+    pub queued: u64,
+    pub last_minute: u32,
+    pub limits: SubmitterLimits,
+}
+
+// Limits, unfinished task counts, and recent submission times per submitter
+#[derive(Default)]
+pub(crate) struct SubmitterLimiter {
+    limits: HashMap<Submitter, SubmitterLimits>,
+    queued: HashMap<Submitter, u64>,
+    recent: HashMap<Submitter, VecDeque<u64>>, // Submission times in the window, for rate-limited submitters
+}
+
+impl SubmitterLimiter {
+    // Start from the unfinished tasks recovered from the store
+    pub(crate) fn new<'a>(limits: HashMap<Submitter, SubmitterLimits>, unfinished: impl Iterator<Item = &'a Task>) -> Self {
+        let mut limiter = SubmitterLimiter { limits, ..Self::default() };
+        for task in unfinished {
+            limiter.adjust(task, 1);
+        }
+        limiter
+    }
+
+    fn adjust(&mut self, task: &Task, delta: i64) {
+        for submitter in Submitter::of(task) {
+            let queued = self.queued.entry(submitter).or_insert(0);
+            *queued = queued.saturating_add_signed(delta);
+        }
+    }
+
+    fn last_minute(&mut self, submitter: &Submitter, now: u64) -> u32 {
+        let Some(times) = self.recent.get_mut(submitter) else {
+            return 0;
+        };
+        while times.front().is_some_and(|t| now.saturating_sub(*t) >= WINDOW_MS) {
+            times.pop_front();
+        }
+        times.len() as u32
+    }
+
+    // Reject the batch if any of its submitters would go over a limit
+    pub(crate) fn check(&mut self, tasks: &[&Task], now: u64) -> Result<(), String> {
+        let mut requests: HashMap<Submitter, u64> = HashMap::new();
+        for submitter in tasks.iter().flat_map(|task| Submitter::of(task)) {
+            *requests.entry(submitter).or_insert(0) += 1;
+        }
+        for (submitter, count) in requests {
+            let Some(limits) = self.limits.get(&submitter).copied() else {
+                continue;
+            };
+            let queued = self.queued.get(&submitter).copied().unwrap_or(0);
+            if let Some(max) = limits.max_queued.filter(|max| queued + count > *max) {
+                return Err(format!("{}: {} has {} of {} queued tasks", QUOTA_EXCEEDED_ERROR, submitter, queued, max));
+            }
+            let recent = self.last_minute(&submitter, now);
+            if let Some(max) = limits.max_per_minute.filter(|max| u64::from(recent) + count > u64::from(*max)) {
+                return Err(format!("{}: {} has made {} of {} submissions in the last minute", QUOTA_EXCEEDED_ERROR, submitter, recent, max));
+            }
+        }
+        Ok(())
+    }
+
+    // Count accepted submissions
+    pub(crate) fn charge(&mut self, tasks: &[&Task], now: u64) {
+        for task in tasks {
+            self.adjust(task, 1);
+            for submitter in Submitter::of(task) {
+                if self.limits.get(&submitter).is_some_and(|l| l.max_per_minute.is_some()) {
+                    self.recent.entry(submitter).or_default().push_back(now);
+                }
+            }
+        }
+    }
+
+    // Take back the charge of submissions that were not accepted after all
+    pub(crate) fn refund(&mut self, tasks: &[&Task], now: u64) {
+        for task in tasks {
+            self.adjust(task, -1);
+            for submitter in Submitter::of(task) {
+                if let Some(times) = self.recent.get_mut(&submitter) {
+                    if let Some(index) = times.iter().rposition(|t| *t == now) {
+                        times.remove(index);
+                    }
+                }
+            }
+        }
+    }
+
+    // Follow a task into or out of the finished states
+    pub(crate) fn transitioned(&mut self, task: &Task, from: Option<TaskState>, to: TaskState) {
+        match (from.is_some_and(|s| !s.is_terminal()), !to.is_terminal()) {
+            (true, false) => self.adjust(task, -1),
+            (false, true) => self.adjust(task, 1),
+            _ => {}
+        }
+    }
+
+    // Replace a submitter's limits; unlimited ones are removed
+    pub(crate) fn set(&mut self, submitter: Submitter, limits: SubmitterLimits) {
+        if limits.max_per_minute.is_none() {
+            self.recent.remove(&submitter);
+        }
+        if limits.is_unlimited() {
+            self.limits.remove(&submitter);
+        } else {
+            self.limits.insert(submitter, limits);
+        }
+    }
+
+    pub(crate) fn usage(&mut self, submitter: &Submitter, now: u64) -> SubmitterUsage {
+        SubmitterUsage {
+            submitter: submitter.clone(),
+            queued: self.queued.get(submitter).copied().unwrap_or(0),
+            last_minute: self.last_minute(submitter, now),
+            limits: self.limits.get(submitter).copied().unwrap_or_default(),
+        }
+    }
+
+    // Standing of every submitter with limits, in order
+    pub(crate) fn all_usage(&mut self, now: u64) -> Vec<SubmitterUsage> {
+        let mut submitters: Vec<Submitter> = self.limits.keys().cloned().collect();
+        submitters.sort();
+        submitters.iter().map(|submitter| self.usage(submitter, now)).collect()
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::Scheduler;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_submitter_limits_enforced_and_adjustable() {
+        let clock = Arc::new(MockClock::new(0));
+        let (scheduler, _workers) = Scheduler::builder()
+            .clock(clock.clone())
+            .submitter_limits(Submitter::Namespace("cell-1".to_string()), SubmitterLimits { max_queued: Some(2), max_per_minute: None })
+            .build()
+            .unwrap();
+        let task = |id, namespace: &str, source: Option<&str>| Task {
+            id,
+            namespace: Some(namespace.to_string()),
+            source: source.map(str::to_string),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task(349, "cell-1", None)).await.unwrap();
+        scheduler.schedule_task(task(350, "cell-1", None)).await.unwrap();
+        let err = scheduler.schedule_task(task(351, "cell-1", None)).await.unwrap_err();
+        assert_eq!(err, "QuotaExceeded: namespace cell-1 has 2 of 2 queued tasks");
+        // A finished task frees its place
+        scheduler.cancel_task(349).await.unwrap();
+        scheduler.schedule_task(task(351, "cell-1", None)).await.unwrap();
+
+        // Raised at runtime, and a per-minute cap on a source across namespaces
+        let planner = Submitter::Source("planner".to_string());
+        scheduler.set_submitter_limits(Submitter::Namespace("cell-1".to_string()), SubmitterLimits::default());
+        scheduler.set_submitter_limits(planner.clone(), SubmitterLimits { max_queued: None, max_per_minute: Some(1) });
+        scheduler.schedule_task(task(352, "cell-2", Some("planner"))).await.unwrap();
+        let err = scheduler.schedule_task(task(353, "cell-1", Some("planner"))).await.unwrap_err();
+        assert!(err.contains("source planner has made 1 of 1 submissions in the last minute"), "{}", err);
+        clock.advance(Duration::from_millis(WINDOW_MS));
+        scheduler.schedule_task(task(353, "cell-1", Some("planner"))).await.unwrap();
+
+        let usage = scheduler.submitter_usage();
+        assert_eq!(usage.len(), 1);
+        assert_eq!((usage[0].submitter.clone(), usage[0].queued, usage[0].last_minute), (planner, 2, 1));
+    }
+}
@@ -0,0 +1,112 @@
+// backend/rust/src/task.rs
+// Purpose: The task model shared by every MRTODP front-end (FFI, Python module, REST,
+// gRPC, MQTT) and the scheduler core: one `Task` type with its JSON shape, so a task
+// submitted through any front-end is the same document. Besides identity, type,
+// priority, deadline, and required capabilities, a task carries optional timing
+// (not-before, time window, duration estimate, timeout), routing (robot, namespace,
+// mission, mutex group, exclusive resources, coalition), geometry (waypoints, zone,
+// named zone, location), and tracing fields, plus a free-form JSON payload for the robot
+// (see payload_schemas.rs), any progress handed over by another robot, and the robots
+// that failed it; every optional field may be omitted from JSON.
+
+use serde::{Deserialize, Serialize};
+use crate::coalitions::Coalition;
+use crate::deadline_miss::DeadlinePolicy;
+use crate::escalation::EscalationConfig;
+use crate::geometry::{Location, Waypoint, Zone};
+use crate::handoff::Handoff;
+use crate::priority_classes::PriorityClass;
+use crate::time_windows::TimeWindow;
+use crate::trace_context::TraceContext;
+
+// Task struct with priority and deadline
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Task {
+    pub id: u32,
+    pub task_type: String,
+    pub priority: u32, // Higher value = higher priority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_class: Option<PriorityClass>, // Dispatch class above priority; None = normal
+    #[serde(default, deserialize_with = "crate::deadlines::deserialize")]
+    pub deadline: Option<u64>, // Unix timestamp (milliseconds); submitted as millis or RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "crate::deadlines::deserialize")]
+    pub not_before: Option<u64>, // Held out of dispatch until this time; same format as deadline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<TimeWindow>, // Interval the task must start and finish within
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_duration_ms: Option<u64>, // Expected execution time, checked against the window
+    pub robot_id: Option<String>,
+    pub required_capabilities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>, // Distributed trace the task belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>, // Owning team/tenant; None = "default"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mission_id: Option<String>, // Mission this task belongs to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>, // Execution budget measured from dispatch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<EscalationConfig>, // Endpoint called as the budget runs out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>, // Free-form labels for filtering and grouping
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<Waypoint>, // Route the robot should follow, in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<Zone>, // Area the task is confined to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone_name: Option<String>, // Named zone it runs in; only robots permitted there take it (see geofencing.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>, // Where the task is carried out, to place it on a nearby robot
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub expedite: bool, // Dispatch through the urgent lane to the best idle robot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>, // Submitting system or operator, for usage metrics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutex_group: Option<String>, // At most one task per group is assigned or running at a time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<String>, // Held exclusively while assigned or running (see resource_locks.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coalition: Option<Coalition>, // Robots that must carry the task out together (see coalitions.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff: Option<Handoff>, // Progress handed over by the robot that last held the task (see handoff.rs); never submitted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_robots: Vec<String>, // Robots that failed the task, avoided by retries (see retries.rs); never submitted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preemptible: bool, // May be suspended for a much higher-priority task (see preemption.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_policy: Option<DeadlinePolicy>, // Handling of a missed deadline; None = scheduler default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>, // Parameters for the robot, checked against the task type's schema
+}
+
+impl Task {
+    // Earliest time the task may be dispatched: its not_before or window start, whichever
+    // is later
+    pub fn start_after(&self) -> Option<u64> {
+        self.not_before.max(self.window.map(|w| w.earliest_start))
+    }
+
+    // Whether the robot gave the task up through a hand-off; it isn't placed there again
+    pub fn handed_off_by(&self, robot_id: &str) -> bool {
+        self.handoff.as_ref().is_some_and(|h| h.from_robot == robot_id)
+    }
+
+    // Whether a retry should go to another robot than this one, if another can take it
+    pub fn avoids(&self, robot_id: &str) -> bool {
+        self.excluded_robots.iter().any(|r| r == robot_id)
+    }
+
+    // Reject malformed geometry before the task reaches a robot
+    pub fn validate_geometry(&self) -> Result<(), String> {
+        for (i, waypoint) in self.waypoints.iter().enumerate() {
+            waypoint.validate().map_err(|e| format!("Waypoint {}: {}", i, e))?;
+        }
+        if let Some(zone) = &self.zone {
+            zone.validate().map_err(|e| format!("Zone: {}", e))?;
+        }
+        if let Some(location) = &self.location {
+            location.validate().map_err(|e| format!("Location: {}", e))?;
+        }
+        Ok(())
+    }
+}
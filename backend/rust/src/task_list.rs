@@ -0,0 +1,130 @@
+// backend/rust/src/task_list.rs
+// Purpose: Listing of the MRTODP live queue, so operators can see every unfinished task
+// (pending, assigned, running, or suspended) instead of probing task IDs one at a time.
+// `Scheduler::list_tasks`, GET /tasks, and the FFI return a summary per task, filtered by
+// state, robot, priority range, required capability, and deadline proximity (tasks due
+// within a given time, overdue ones included), soonest deadline first.
+
+use serde::{Deserialize, Serialize};
+use crate::scheduler::{TaskRecord, TaskState};
+
+// Which unfinished tasks a listing returns; unset fields match every task
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskListFilter {
+    #[serde(default)]
+    pub states: Vec<TaskState>, // Empty = every unfinished state
+    #[serde(default)]
+    pub robot_id: Option<String>, // Robot the task is assigned or pinned to
+    #[serde(default)]
+    pub min_priority: Option<u32>,
+    #[serde(default)]
+    pub max_priority: Option<u32>,
+    #[serde(default)]
+    pub capability: Option<String>, // Among the task's required capabilities
+    #[serde(default)]
+    pub due_within_ms: Option<u64>, // Deadline at most this far away; tasks without one never match
+}
+
+impl TaskListFilter {
+    pub(crate) fn matches(&self, record: &TaskRecord, now: u64) -> bool {
+        let task = &record.task;
+        !record.state.is_terminal()
+            && (self.states.is_empty() || self.states.contains(&record.state))
+            && self.robot_id.as_ref().is_none_or(|robot_id| robot_of(record) == Some(robot_id))
+            && self.min_priority.is_none_or(|min| task.priority >= min)
+            && self.max_priority.is_none_or(|max| task.priority <= max)
+            && self.capability.as_ref().is_none_or(|capability| task.required_capabilities.contains(capability))
+            && self.due_within_ms.is_none_or(|within| task.deadline.is_some_and(|deadline| deadline <= now.saturating_add(within)))
+    }
+}
+
+// Robot an unfinished task is on, or is pinned to if it hasn't reached one
+fn robot_of(record: &TaskRecord) -> Option<&String> {
+    record.attempts.last().and_then(|a| a.robot_id.as_ref()).or(record.task.robot_id.as_ref())
+}
+
+// Live view of one unfinished task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TaskSummary {
+    pub task_id: u32,
+    pub task_type: String,
+    pub namespace: Option<String>,
+    pub state: TaskState,
+    pub held: bool,
+    pub priority: u32,
+    pub robot_id: Option<String>,
+    pub required_capabilities: Vec<String>,
+    pub deadline: Option<u64>,       // Unix timestamp (milliseconds)
+    pub deadline_in_ms: Option<i64>, // Time left until the deadline; negative once overdue
+    pub submitted_at: u64,           // Unix timestamp (milliseconds)
+    pub version: u64,                // Record version, for compare-and-swap updates
+}
+
+impl TaskSummary {
+    pub(crate) fn of(record: &TaskRecord, now: u64) -> Self {
+        let task = &record.task;
+        TaskSummary {
+            task_id: task.id,
+            task_type: task.task_type.clone(),
+            namespace: task.namespace.clone(),
+            state: record.state,
+            held: record.held,
+            priority: task.priority,
+            robot_id: robot_of(record).cloned(),
+            required_capabilities: task.required_capabilities.clone(),
+            deadline: task.deadline,
+            deadline_in_ms: task.deadline.map(|deadline| deadline as i64 - now as i64),
+            submitted_at: record.attempts.first().and_then(|a| a.transitions.first()).map_or(0, |t| t.at),
+            version: record.version,
+        }
+    }
+}
+
+// Soonest deadline first, tasks without one last; then higher priority, then older tasks
+pub(crate) fn sort_summaries(tasks: &mut [TaskSummary]) {
+    tasks.sort_by_key(|t| (t.deadline.is_none(), t.deadline, std::cmp::Reverse(t.priority), t.submitted_at, t.task_id));
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::scheduler::Scheduler;
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_live_queue_listed_by_filter() {
+        let clock = Arc::new(MockClock::new(10_000));
+        let (scheduler, _workers) = Scheduler::builder().clock(clock).build().unwrap();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+        let task = |id: u32, priority: u32, deadline: Option<u64>, capabilities: &[&str]| Task {
+            id,
+            priority,
+            deadline,
+            required_capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        scheduler.schedule_task(task(368, 1, Some(70_000), &["lift"])).await.unwrap();
+        scheduler.schedule_task(task(369, 5, Some(20_000), &["weld"])).await.unwrap();
+        scheduler.schedule_task(task(370, 9, None, &["lift"])).await.unwrap();
+        scheduler.schedule_task(Task { robot_id: Some("Ford".to_string()), ..task(371, 3, Some(20_000), &[]) }).await.unwrap();
+        scheduler.schedule_task(task(372, 2, None, &[])).await.unwrap();
+        scheduler.cancel_task(372).await.unwrap();
+
+        let ids = |filter: TaskListFilter| {
+            let scheduler = scheduler.clone();
+            async move { scheduler.list_tasks(&filter).await.into_iter().map(|t| t.task_id).collect::<Vec<_>>() }
+        };
+        assert_eq!(ids(TaskListFilter::default()).await, vec![369, 371, 368, 370]);
+        assert_eq!(ids(TaskListFilter { due_within_ms: Some(10_000), ..Default::default() }).await, vec![369, 371]);
+        assert_eq!(ids(TaskListFilter { min_priority: Some(2), max_priority: Some(5), ..Default::default() }).await, vec![369, 371]);
+        assert_eq!(ids(TaskListFilter { capability: Some("lift".to_string()), ..Default::default() }).await, vec![368, 370]);
+        assert_eq!(ids(TaskListFilter { robot_id: Some("Ford".to_string()), ..Default::default() }).await, vec![371]);
+        assert!(ids(TaskListFilter { states: vec![TaskState::Running], ..Default::default() }).await.is_empty());
+
+        let listed = scheduler.list_tasks(&TaskListFilter::default()).await;
+        assert_eq!((listed[0].state, listed[0].deadline_in_ms), (TaskState::Pending, Some(10_000)));
+    }
+}
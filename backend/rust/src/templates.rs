@@ -0,0 +1,250 @@
+// backend/rust/src/templates.rs
+// Purpose: Task templates for MRTODP. A template defines a parameterized task shape once
+// (`weld_component` taking a `component` string and a `torque` number) as task JSON whose
+// strings may hold `{{param}}` placeholders, plus a schema of typed parameters with
+// optional defaults. A string that is exactly one placeholder takes the parameter's value
+// with its JSON type, so `"priority": "{{urgency}}"` becomes a number; placeholders inside
+// longer strings are spliced in as text. Templates are checked when registered: every
+// placeholder must name a declared parameter, every parameter must be used, defaults must
+// match their types, and the shape must yield a valid task when filled with each
+// parameter's default or a sample value of its type (so a string parameter placed in a
+// timestamp field needs a default). Instantiating takes a JSON object with the new task's
+// `id` and the parameter values; unknown, missing, or mistyped parameters are rejected
+// before anything is submitted.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::task::Task;
+
+// Type of a template parameter
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    StringList,
+}
+
+// One parameter of a template's schema
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParamSpec {
+    #[serde(rename = "type")]
+    pub kind: ParamType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>, // None = the parameter is required
+}
+
+// Parameterized task shape
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskTemplate {
+    pub id: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, ParamSpec>,
+    pub task: Value, // Task JSON without an `id`; strings may hold {{param}} placeholders
+}
+
+impl ParamType {
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            ParamType::String => value.is_string(),
+            ParamType::Integer => value.is_i64() || value.is_u64(),
+            ParamType::Number => value.is_number(),
+            ParamType::Boolean => value.is_boolean(),
+            ParamType::StringList => value.as_array().is_some_and(|items| items.iter().all(Value::is_string)),
+        }
+    }
+
+    // Stand-in value used to check a template's shape at registration
+    fn sample(self) -> Value {
+        match self {
+            ParamType::String => Value::from("sample"),
+            ParamType::Integer => Value::from(0),
+            ParamType::Number => Value::from(0.0),
+            ParamType::Boolean => Value::from(false),
+            ParamType::StringList => Value::Array(Vec::new()),
+        }
+    }
+}
+
+impl TaskTemplate {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("Template ID must not be empty".to_string());
+        }
+        let Some(shape) = self.task.as_object() else {
+            return Err(format!("Template {}: task must be a JSON object", self.id));
+        };
+        if shape.contains_key("id") {
+            return Err(format!("Template {}: task ID is given when instantiating, not in the template", self.id));
+        }
+        for (name, spec) in &self.params {
+            if spec.default.as_ref().is_some_and(|d| !spec.kind.accepts(d)) {
+                return Err(format!("Template {}: default of parameter {} is not a {:?}", self.id, name, spec.kind));
+            }
+        }
+        let mut used = Vec::new();
+        placeholders(&self.task, &mut used)?;
+        if let Some(name) = used.iter().find(|name| !self.params.contains_key(*name)) {
+            return Err(format!("Template {} uses undeclared parameter {}", self.id, name));
+        }
+        if let Some(name) = self.params.keys().find(|name| !used.contains(name)) {
+            return Err(format!("Template {} declares unused parameter {}", self.id, name));
+        }
+        let samples = self.params.iter().map(|(name, spec)| (name.clone(), spec.default.clone().unwrap_or_else(|| spec.kind.sample()))).collect();
+        self.fill(0, &samples).map_err(|e| format!("Template {} does not produce a valid task: {}", self.id, e))?;
+        Ok(())
+    }
+
+    // Build a task from a JSON object holding its `id` and the parameter values
+    pub fn instantiate(&self, params: &Value) -> Result<Task, String> {
+        let Some(given) = params.as_object() else {
+            return Err("Template parameters must be a JSON object".to_string());
+        };
+        let task_id = given
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| format!("Template {}: parameters need the task's numeric id", self.id))?;
+        if let Some(name) = given.keys().find(|name| *name != "id" && !self.params.contains_key(*name)) {
+            return Err(format!("Template {} has no parameter {}", self.id, name));
+        }
+        let mut values = BTreeMap::new();
+        for (name, spec) in &self.params {
+            let value = match (given.get(name), &spec.default) {
+                (Some(value), _) if spec.kind.accepts(value) => value.clone(),
+                (Some(_), _) => return Err(format!("Template {}: parameter {} must be a {:?}", self.id, name, spec.kind)),
+                (None, Some(default)) => default.clone(),
+                (None, None) => return Err(format!("Template {}: missing parameter {}", self.id, name)),
+            };
+            values.insert(name.clone(), value);
+        }
+        self.fill(task_id, &values).map_err(|e| format!("Template {}: {}", self.id, e))
+    }
+
+    // Substitute parameter values into the shape and parse the result as a task
+    fn fill(&self, task_id: u32, values: &BTreeMap<String, Value>) -> Result<Task, String> {
+        let mut task = substitute(&self.task, values)?;
+        if let Value::Object(fields) = &mut task {
+            fields.insert("id".to_string(), Value::from(task_id));
+        }
+        serde_json::from_value(task).map_err(|e| format!("Invalid task: {}", e))
+    }
+}
+
+// Split a string into literal text and placeholder names
+fn segments(text: &str) -> Result<Vec<(bool, &str)>, String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..].find("}}").ok_or_else(|| format!("Unclosed placeholder in {:?}", text))? + start;
+        let name = rest[start + 2..end].trim();
+        if name.is_empty() {
+            return Err(format!("Empty placeholder in {:?}", text));
+        }
+        if start > 0 {
+            segments.push((false, &rest[..start]));
+        }
+        segments.push((true, name));
+        rest = &rest[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push((false, rest));
+    }
+    Ok(segments)
+}
+
+// Collect every placeholder name used in a shape
+fn placeholders(shape: &Value, used: &mut Vec<String>) -> Result<(), String> {
+    match shape {
+        Value::String(text) => {
+            for (_, name) in segments(text)?.into_iter().filter(|(is_param, _)| *is_param) {
+                if !used.iter().any(|u| u == name) {
+                    used.push(name.to_string());
+                }
+            }
+        }
+        Value::Array(items) => items.iter().try_for_each(|item| placeholders(item, used))?,
+        Value::Object(fields) => fields.values().try_for_each(|field| placeholders(field, used))?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn substitute(shape: &Value, values: &BTreeMap<String, Value>) -> Result<Value, String> {
+    match shape {
+        Value::String(text) => {
+            let segments = segments(text)?;
+            if let [(true, name)] = segments.as_slice() {
+                return Ok(values[*name].clone());
+            }
+            let mut out = String::new();
+            for (is_param, part) in segments {
+                match (is_param, &values.get(part)) {
+                    (false, _) => out.push_str(part),
+                    (true, Some(Value::String(value))) => out.push_str(value),
+                    (true, Some(value @ (Value::Number(_) | Value::Bool(_)))) => out.push_str(&value.to_string()),
+                    (true, _) => return Err(format!("Parameter {} can't be spliced into text", part)),
+                }
+            }
+            Ok(Value::String(out))
+        }
+        Value::Array(items) => items.iter().map(|item| substitute(item, values)).collect::<Result<_, _>>().map(Value::Array),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, field)| Ok((key.clone(), substitute(field, values)?)))
+            .collect::<Result<Map<_, _>, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::scheduler::{Scheduler, TaskState};
+
+    #[tokio::test]
+    async fn test_templates_validate_and_instantiate() {
+        let template: TaskTemplate = serde_json::from_value(json!({
+            "id": "weld_component",
+            "params": {
+                "component": {"type": "string"},
+                "urgency": {"type": "integer", "default": 5},
+                "capabilities": {"type": "string_list", "default": ["welding"]}
+            },
+            "task": {
+                "task_type": "weld",
+                "priority": "{{urgency}}",
+                "robot_id": null,
+                "required_capabilities": "{{ capabilities }}",
+                "tags": ["component:{{component}}"]
+            }
+        }))
+        .unwrap();
+        template.validate().unwrap();
+        let broken = |task: Value| TaskTemplate { task, ..template.clone() }.validate().unwrap_err();
+        assert!(broken(json!({"priority": "{{urgency}}", "tags": ["{{component}}"], "robot_id": "{{missing}}"})).contains("undeclared parameter missing"));
+        assert!(broken(json!({"task_type": "{{component}}"})).contains("unused parameter"));
+        assert!(broken(json!({"task_type": "weld", "priority": "{{component}}", "required_capabilities": "{{capabilities}}", "tags": ["{{urgency}}"]})).contains("does not produce a valid task"));
+
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        scheduler.register_template(template).unwrap();
+        assert!(scheduler.instantiate_template("weld_component", r#"{"id": 329}"#).await.unwrap_err().contains("missing parameter component"));
+        assert!(scheduler.instantiate_template("weld_component", r#"{"id": 329, "component": 7}"#).await.unwrap_err().contains("must be a String"));
+        assert!(scheduler.instantiate_template("weld_component", r#"{"id": 329, "component": "x", "torque": 3}"#).await.unwrap_err().contains("no parameter torque"));
+        assert!(scheduler.instantiate_template("drill", r#"{"id": 329}"#).await.unwrap_err().contains("Unknown template"));
+
+        let task = scheduler.instantiate_template("weld_component", r#"{"id": 329, "component": "bracket-7", "urgency": 9}"#).await.unwrap();
+        assert_eq!((task.id, task.priority, task.tags), (329, 9, vec!["component:bracket-7".to_string()]));
+        assert_eq!(task.required_capabilities, vec!["welding".to_string()]);
+        while scheduler.task_record(329).await.is_none_or(|r| r.state != TaskState::Completed) {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+    }
+}
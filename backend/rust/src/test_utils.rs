@@ -0,0 +1,222 @@
+// backend/rust/src/test_utils.rs
+// Purpose: Test doubles for integration tests against the MRTODP scheduler. Compiled with
+// the `test-utils` feature. `FakeRobotAdapter` implements `RobotTransport` with scriptable
+// per-robot behaviors (delay then ack, nack, disconnect mid-task, corrupt result) so
+// downstream users can exercise realistic robot failure modes without hardware.
+// `MockClock` is a settable wall clock for driving deadlines and timestamps, including
+// clock steps backward and forward.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use crate::clock::Clock;
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+use crate::transport::{ControlEnvelope, DispatchSeq, RobotReport, RobotTransport};
+use crate::BoxFuture;
+
+// Scripted reaction of a fake robot to one assignment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FakeBehavior {
+    AckAfter(Duration),            // Accept, then report success after the delay
+    FailAfter(Duration, String),   // Accept, then report failure after the delay
+    Nack(String),                  // Refuse the assignment outright
+    DisconnectMidTask(Duration),   // Accept, then drop the link after the delay
+    CorruptResultAfter(Duration),  // Accept, then send an uninterpretable result
+}
+
+// Scriptable robot double implementing the transport trait
+#[derive(Default)]
+pub struct FakeRobotAdapter {
+    scheduler: OnceLock<Scheduler>,
+    scripts: Mutex<HashMap<String, VecDeque<FakeBehavior>>>,
+    disconnected: Mutex<HashSet<String>>,
+    assignments: Mutex<Vec<(String, u32)>>,
+    controls: Mutex<Vec<ControlEnvelope>>,
+}
+
+impl FakeRobotAdapter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Connect the fake to the scheduler it reports results to
+    pub fn attach(&self, scheduler: &Scheduler) {
+        let _ = self.scheduler.set(scheduler.clone());
+    }
+
+    // Queue behaviors for a robot's next assignments; unscripted assignments ack immediately
+    pub fn script(&self, robot_id: &str, behaviors: Vec<FakeBehavior>) {
+        self.scripts.lock().unwrap().entry(robot_id.to_string()).or_default().extend(behaviors);
+    }
+
+    // Restore the link of a robot that disconnected
+    pub fn reconnect(&self, robot_id: &str) {
+        self.disconnected.lock().unwrap().remove(robot_id);
+    }
+
+    pub fn is_connected(&self, robot_id: &str) -> bool {
+        !self.disconnected.lock().unwrap().contains(robot_id)
+    }
+
+    // Assignments received so far as (robot_id, task_id)
+    pub fn assignments(&self) -> Vec<(String, u32)> {
+        self.assignments.lock().unwrap().clone()
+    }
+
+    // Control commands received so far
+    pub fn controls(&self) -> Vec<ControlEnvelope> {
+        self.controls.lock().unwrap().clone()
+    }
+
+    fn next_behavior(&self, robot_id: &str) -> FakeBehavior {
+        self.scripts
+            .lock()
+            .unwrap()
+            .get_mut(robot_id)
+            .and_then(|script| script.pop_front())
+            .unwrap_or(FakeBehavior::AckAfter(Duration::ZERO))
+    }
+
+    // Send a raw report back to the scheduler after a delay
+    fn report_later(&self, delay: Duration, raw: String) {
+        let Some(scheduler) = self.scheduler.get().cloned() else {
+            return;
+        };
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = scheduler.ingest_report(&raw).await;
+        });
+    }
+}
+
+fn report_json(task_id: u32, status: &str, detail: Option<String>) -> String {
+    serde_json::to_string(&RobotReport { task_id, status: status.to_string(), detail }).unwrap()
+}
+
+impl RobotTransport for FakeRobotAdapter {
+    fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, _seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            if !self.is_connected(robot_id) {
+                return Err(format!("Robot {} disconnected", robot_id));
+            }
+            let behavior = self.next_behavior(robot_id);
+            if let FakeBehavior::Nack(reason) = &behavior {
+                return Err(reason.clone());
+            }
+            self.assignments.lock().unwrap().push((robot_id.to_string(), task.id));
+            match behavior {
+                FakeBehavior::AckAfter(delay) => self.report_later(delay, report_json(task.id, "completed", None)),
+                FakeBehavior::FailAfter(delay, detail) => self.report_later(delay, report_json(task.id, "failed", Some(detail))),
+                FakeBehavior::CorruptResultAfter(delay) => self.report_later(delay, report_json(task.id, "\u{fffd}garbled", None)),
+                FakeBehavior::DisconnectMidTask(delay) => {
+                    tokio::time::sleep(delay).await;
+                    self.disconnected.lock().unwrap().insert(robot_id.to_string());
+                }
+                FakeBehavior::Nack(_) => unreachable!("handled above"),
+            }
+            Ok(())
+        })
+    }
+
+    fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            if !self.is_connected(&envelope.robot_id) {
+                return Err(format!("Robot {} disconnected", envelope.robot_id));
+            }
+            self.controls.lock().unwrap().push(envelope.clone());
+            Ok(())
+        })
+    }
+}
+
+// Wall clock that only moves when told to
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64, // Unix milliseconds
+}
+
+impl MockClock {
+    pub fn new(now_millis: u64) -> Self {
+        MockClock { now: AtomicU64::new(now_millis) }
+    }
+
+    // Jump to a time, earlier or later than the current one
+    pub fn set(&self, now_millis: u64) {
+        self.now.store(now_millis, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.fetch_add(by.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    // Step backward, as when NTP corrects a fast clock
+    pub fn rewind(&self, by: Duration) {
+        let by = by.as_millis() as u64;
+        let _ = self.now.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| Some(now.saturating_sub(by)));
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::scheduler::{ReasonCode, TaskRecord, TaskState};
+
+    async fn run_one(behavior: FakeBehavior) -> (Arc<FakeRobotAdapter>, TaskRecord) {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![behavior]);
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 1, robot_id: Some("Ford".to_string()), ..Default::default() }).await.unwrap();
+        let _ = tokio::time::timeout(Duration::from_millis(200), async {
+            while let Ok(event) = events.recv().await {
+                if event.transition.to.is_terminal() {
+                    break;
+                }
+            }
+        })
+        .await;
+        let record = scheduler.task_record(1).await.unwrap();
+        (fake, record)
+    }
+
+    #[tokio::test]
+    async fn test_delay_then_ack_completes() {
+        let (fake, record) = run_one(FakeBehavior::AckAfter(Duration::from_millis(5))).await;
+        assert_eq!(record.state, TaskState::Completed);
+        assert_eq!(fake.assignments(), vec![("Ford".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_nack_fails_task() {
+        let (_, record) = run_one(FakeBehavior::Nack("busy".to_string())).await;
+        assert_eq!(record.state, TaskState::Failed);
+        assert!(record.attempts[0].transitions.last().unwrap().detail.contains("busy"));
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_result_flagged() {
+        let (_, record) = run_one(FakeBehavior::CorruptResultAfter(Duration::ZERO)).await;
+        assert_eq!(record.state, TaskState::Failed);
+        assert_eq!(record.attempts[0].transitions.last().unwrap().reason, ReasonCode::FailedCorruptResult);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_leaves_task_running() {
+        let (fake, record) = run_one(FakeBehavior::DisconnectMidTask(Duration::ZERO)).await;
+        assert_eq!(record.state, TaskState::Running);
+        assert!(!fake.is_connected("Ford"));
+    }
+}
@@ -0,0 +1,98 @@
+// backend/rust/src/time_windows.rs
+// Purpose: Time windows for MRTODP tasks. A task may carry a `window` with an
+// `earliest_start` and a `latest_finish` (Unix milliseconds, or RFC 3339 like deadlines)
+// and an `estimated_duration_ms`; without an estimate of its own, a task is expected to take
+// as long as its robot's average for the task type, or no time at all if the robot has no
+// history. A submission whose window can't hold the estimate, or can no longer be met, is
+// rejected. At dispatch, a task before its earliest start waits on the timer wheel like a
+// `not_before` task, and one that could no longer finish by its latest finish expires with
+// reason EXPIRED_WINDOW instead of reaching a robot.
+
+use serde::{Deserialize, Serialize};
+
+// Interval a task must run within
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeWindow {
+    #[serde(deserialize_with = "crate::deadlines::deserialize_required")]
+    pub earliest_start: u64,
+    #[serde(deserialize_with = "crate::deadlines::deserialize_required")]
+    pub latest_finish: u64,
+}
+
+impl TimeWindow {
+    // Reject windows too short for the task's own estimate
+    pub fn validate(&self, estimate_ms: u64) -> Result<(), String> {
+        if self.earliest_start.saturating_add(estimate_ms) > self.latest_finish {
+            return Err(format!(
+                "Time window {}..{} can't hold an estimated duration of {}ms",
+                self.earliest_start, self.latest_finish, estimate_ms
+            ));
+        }
+        Ok(())
+    }
+
+    // Whether a task taking `estimate_ms`, started now or at its earliest start if that is
+    // later, finishes inside the window
+    pub fn fits(&self, now: u64, estimate_ms: u64) -> bool {
+        now.max(self.earliest_start).saturating_add(estimate_ms) <= self.latest_finish
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+    use crate::task::Task;
+    use crate::test_utils::MockClock;
+
+    #[tokio::test]
+    async fn test_tasks_dispatch_only_when_they_fit_their_window() {
+        let start = 1_767_225_600_000;
+        let window: TimeWindow = serde_json::from_str(r#"{"earliest_start": "2026-01-01T00:00:00Z", "latest_finish": 1767225605000}"#).unwrap();
+        assert_eq!(window, TimeWindow { earliest_start: start, latest_finish: start + 5_000 });
+        assert!(window.fits(start - 1_000, 5_000) && !window.fits(start + 1, 5_000));
+
+        let clock = Arc::new(MockClock::new(start));
+        let (scheduler, workers) = Scheduler::builder().clock(clock.clone()).timer_tick(Duration::from_millis(1)).build().unwrap();
+        let task = |id: u32, earliest_start: u64, latest_finish: u64| Task {
+            id,
+            window: Some(TimeWindow { earliest_start, latest_finish }),
+            estimated_duration_ms: Some(5_000),
+            ..Default::default()
+        };
+        let too_short = scheduler.schedule_task(task(322, start, start + 4_999)).await.unwrap_err();
+        assert!(too_short.contains("can't hold"), "{}", too_short);
+        let missed = scheduler.schedule_task(task(323, start - 60_000, start + 4_000)).await.unwrap_err();
+        assert!(missed.contains("can no longer finish"), "{}", missed);
+
+        // By the time the workers pick them up, 325 can no longer finish in time
+        scheduler.schedule_task(task(324, start + 40_000, start + 60_000)).await.unwrap();
+        scheduler.schedule_task(task(325, start, start + 30_000)).await.unwrap();
+        clock.set(start + 26_000);
+        workers.spawn();
+        let finished = |task_id: u32| {
+            let scheduler = scheduler.clone();
+            async move {
+                loop {
+                    match scheduler.task_record(task_id).await {
+                        Some(record) if record.state.is_terminal() => return record,
+                        _ => tokio::time::sleep(Duration::from_millis(1)).await,
+                    }
+                }
+            }
+        };
+        let expired = finished(325).await;
+        assert_eq!(expired.state, TaskState::Expired);
+        assert_eq!(expired.attempts[0].transitions.last().unwrap().reason, ReasonCode::ExpiredWindow);
+
+        // 324 waits for its earliest start
+        while scheduler.delayed_count() < 1 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        clock.set(start + 40_000);
+        assert_eq!(finished(324).await.state, TaskState::Completed);
+    }
+}
@@ -0,0 +1,172 @@
+// backend/rust/src/tls.rs
+// Purpose: TLS for the MRTODP network links (`tls` feature), so robot links and operator
+// dashboards don't cross factory networks in cleartext. Certificates and keys are given
+// as PEM. `ServerTls` configures the listening side: `Scheduler::serve_https` and
+// `http::serve_tls` (see http.rs) and `Scheduler::serve_grpc_tls` (see grpc.rs) serve over
+// it, and with `client_ca` set the server requires mutual TLS, accepting only clients
+// whose certificate chains to one of the given CAs. `ClientTls` configures the connecting
+// side: `SchedulerClient::connect_tls` and the MQTT bridge's broker link (`MqttConfig::tls`)
+// trust the given CAs and, with `identity` set, present a client certificate. TLS is
+// provided by rustls with the ring crypto backend.
+
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+// Read a PEM file, e.g. a certificate chain, key, or CA bundle
+pub fn read_pem(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+fn certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = rustls_pemfile::certs(&mut &pem[..]).collect::<Result<Vec<_>, _>>().map_err(|e| format!("Invalid PEM certificate: {}", e))?;
+    if certs.is_empty() {
+        return Err("No certificates in PEM".to_string());
+    }
+    Ok(certs)
+}
+
+fn private_key(pem: &[u8]) -> Result<PrivateKeyDer<'static>, String> {
+    rustls_pemfile::private_key(&mut &pem[..])
+        .map_err(|e| format!("Invalid PEM private key: {}", e))?
+        .ok_or_else(|| "No private key in PEM".to_string())
+}
+
+// Listening side of a TLS link
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerTls {
+    pub cert_chain_pem: Vec<u8>,        // Server certificate, then any intermediates
+    pub key_pem: Vec<u8>,               // Server private key
+    pub client_ca_pem: Option<Vec<u8>>, // Some = mutual TLS; clients must chain to these CAs
+}
+
+impl ServerTls {
+    pub fn new(cert_chain_pem: Vec<u8>, key_pem: Vec<u8>) -> Self {
+        ServerTls { cert_chain_pem, key_pem, client_ca_pem: None }
+    }
+
+    // Require clients to present a certificate issued by one of the CAs in `ca_pem`
+    pub fn client_ca(mut self, ca_pem: Vec<u8>) -> Self {
+        self.client_ca_pem = Some(ca_pem);
+        self
+    }
+
+    // rustls configuration offering the `alpn` protocols, e.g. for a listener of your own
+    pub fn rustls_config(&self, alpn: &[&[u8]]) -> Result<Arc<rustls::ServerConfig>, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Unsupported TLS versions: {}", e))?;
+        let builder = match &self.client_ca_pem {
+            Some(ca_pem) => {
+                let mut roots = rustls::RootCertStore::empty();
+                for cert in certificates(ca_pem)? {
+                    roots.add(cert).map_err(|e| format!("Invalid client CA certificate: {}", e))?;
+                }
+                let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                    .build()
+                    .map_err(|e| format!("Invalid client CA: {}", e))?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder
+            .with_single_cert(certificates(&self.cert_chain_pem)?, private_key(&self.key_pem)?)
+            .map_err(|e| format!("Invalid server certificate or key: {}", e))?;
+        config.alpn_protocols = alpn.iter().map(|protocol| protocol.to_vec()).collect();
+        Ok(Arc::new(config))
+    }
+
+    #[cfg(feature = "grpc")]
+    pub(crate) fn grpc_config(&self) -> tonic::transport::ServerTlsConfig {
+        let identity = tonic::transport::Identity::from_pem(&self.cert_chain_pem, &self.key_pem);
+        let config = tonic::transport::ServerTlsConfig::new().identity(identity);
+        match &self.client_ca_pem {
+            Some(ca_pem) => config.client_ca_root(tonic::transport::Certificate::from_pem(ca_pem)),
+            None => config,
+        }
+    }
+}
+
+// Connecting side of a TLS link
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientTls {
+    pub ca_pem: Vec<u8>,                          // CAs trusted to issue the server's certificate
+    pub identity_pem: Option<(Vec<u8>, Vec<u8>)>, // Client certificate chain and key, for mutual TLS
+}
+
+impl ClientTls {
+    pub fn new(ca_pem: Vec<u8>) -> Self {
+        ClientTls { ca_pem, identity_pem: None }
+    }
+
+    // Present this certificate chain and key to servers that require mutual TLS
+    pub fn identity(mut self, cert_chain_pem: Vec<u8>, key_pem: Vec<u8>) -> Self {
+        self.identity_pem = Some((cert_chain_pem, key_pem));
+        self
+    }
+
+    #[cfg(feature = "grpc")]
+    pub(crate) fn grpc_config(&self) -> tonic::transport::ClientTlsConfig {
+        let config = tonic::transport::ClientTlsConfig::new().ca_certificate(tonic::transport::Certificate::from_pem(&self.ca_pem));
+        match &self.identity_pem {
+            Some((cert, key)) => config.identity(tonic::transport::Identity::from_pem(cert, key)),
+            None => config,
+        }
+    }
+
+    #[cfg(feature = "mqtt")]
+    pub(crate) fn mqtt_transport(&self) -> rumqttc::Transport {
+        rumqttc::Transport::tls_with_config(rumqttc::TlsConfiguration::Simple {
+            ca: self.ca_pem.clone(),
+            alpn: None,
+            client_auth: self.identity_pem.clone(),
+        })
+    }
+}
+
+// Throwaway PKI for tests: a CA, a server certificate for "localhost", and a client
+// certificate, all as PEM
+#[cfg(test)]
+pub(crate) struct TestPki {
+    pub(crate) ca: Vec<u8>,
+    pub(crate) server: ServerTls,
+    pub(crate) client: (Vec<u8>, Vec<u8>),
+}
+
+#[cfg(test)]
+pub(crate) fn test_pki() -> TestPki {
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+    let ca_key = KeyPair::generate().unwrap();
+    let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca = ca_params.self_signed(&ca_key).unwrap();
+    let issue = |name: &str| {
+        let key = KeyPair::generate().unwrap();
+        let cert = CertificateParams::new(vec![name.to_string()]).unwrap().signed_by(&key, &ca, &ca_key).unwrap();
+        (cert.pem().into_bytes(), key.serialize_pem().into_bytes())
+    };
+    let (server_cert, server_key) = issue("localhost");
+    TestPki { ca: ca.pem().into_bytes(), server: ServerTls::new(server_cert, server_key), client: issue("robot-7") }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config_from_pem() {
+        let pki = test_pki();
+        let config = pki.server.rustls_config(&[b"h2"]).unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"h2".to_vec()]);
+        assert!(pki.server.clone().client_ca(pki.ca.clone()).rustls_config(&[]).is_ok());
+        assert!(certificates(&pki.client.0).is_ok() && private_key(&pki.client.1).is_ok());
+
+        let swapped = ServerTls::new(pki.server.key_pem.clone(), pki.server.cert_chain_pem.clone());
+        assert_eq!(swapped.rustls_config(&[]).unwrap_err(), "No certificates in PEM");
+        assert!(pki.server.clone().client_ca(b"not a pem".to_vec()).rustls_config(&[]).is_err());
+        assert!(read_pem(Path::new("/nonexistent/server.pem")).unwrap_err().starts_with("Failed to read /nonexistent/server.pem"));
+    }
+}
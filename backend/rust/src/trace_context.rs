@@ -0,0 +1,79 @@
+// backend/rust/src/trace_context.rs
+// Purpose: Distributed tracing context carried by MRTODP tasks. Stores the W3C trace
+// context (trace ID, parent span ID, flags) supplied at submission so status and API
+// responses can link an operator straight to the trace of a problematic mission.
+
+use serde::{Deserialize, Serialize};
+
+// W3C trace context attached to a task at submission
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String, // 32 lowercase hex characters, not all zero
+    pub span_id: String,  // 16 lowercase hex characters, not all zero
+    #[serde(default)]
+    pub trace_flags: u8, // Bit 0 = sampled
+}
+
+// Check that a field is lowercase hex of the expected length and not all zeros
+fn valid_hex_id(value: &str, len: usize) -> bool {
+    value.len() == len
+        && value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        && value.bytes().any(|b| b != b'0')
+}
+
+impl TraceContext {
+    // Parse a `traceparent` header value (version 00)
+    pub fn from_traceparent(header: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = header.trim().split('-').collect();
+        if parts.len() != 4 || parts[0] != "00" {
+            return Err(format!("Invalid traceparent: {}", header));
+        }
+        let trace_flags = u8::from_str_radix(parts[3], 16)
+            .map_err(|_| format!("Invalid traceparent flags: {}", parts[3]))?;
+        let context = TraceContext {
+            trace_id: parts[1].to_string(),
+            span_id: parts[2].to_string(),
+            trace_flags,
+        };
+        context.validate()?;
+        Ok(context)
+    }
+
+    // Render as a `traceparent` header value
+    pub fn to_traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, self.trace_flags)
+    }
+
+    // Reject malformed identifiers so stored contexts are always linkable
+    pub fn validate(&self) -> Result<(), String> {
+        if !valid_hex_id(&self.trace_id, 32) {
+            return Err(format!("Invalid trace ID: {}", self.trace_id));
+        }
+        if !valid_hex_id(&self.span_id, 16) {
+            return Err(format!("Invalid span ID: {}", self.span_id));
+        }
+        Ok(())
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context = TraceContext::from_traceparent(header).unwrap();
+        assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.trace_flags, 1);
+        assert_eq!(context.to_traceparent(), header);
+    }
+
+    #[test]
+    fn test_rejects_invalid_ids() {
+        assert!(TraceContext::from_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_err());
+        assert!(TraceContext::from_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").is_err());
+        assert!(TraceContext::from_traceparent("garbage").is_err());
+    }
+}
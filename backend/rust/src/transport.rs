@@ -0,0 +1,358 @@
+// backend/rust/src/transport.rs
+// Purpose: Outbound robot transport for MRTODP. The scheduler delivers task assignments and
+// control commands (abort, hold, resume, calls for bids) to robots through the
+// `RobotTransport` trait. Each robot gets an outbox with two lanes: a priority control lane
+// that is always drained before queued assignments and retried until acknowledged, and a
+// bounded assignment lane delivered once, with failures reported back to the scheduler. Every delivery carries a
+// per-robot sequence number and replay window so adapters can drop commands replayed
+// after a reconnect; `ReplayGuard` implements the robot side of that check.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+use crate::scheduler::Scheduler;
+use crate::BoxFuture;
+use crate::task::Task;
+
+// High-priority command delivered ahead of queued assignments
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    Abort { task_id: u32 },  // Stop executing a task immediately
+    Hold { task_id: u32 },   // Pause a task in place
+    Resume { task_id: u32 }, // Continue a held or preempted task
+    Preempt { task_id: u32 }, // Stop a task but keep its progress; a later Resume continues it
+    Start { task_id: u32 },   // Begin an accepted coalition task, sent to every member at once
+    // Task up for auction; answer through Scheduler::submit_bid within the window
+    CallForBids { task_id: u32, task_type: String, bidding_window_ms: u64 },
+}
+
+// Control command with a per-scheduler unique ID so robots can drop redelivered duplicates
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ControlEnvelope {
+    pub command_id: u64,
+    pub robot_id: String,
+    pub command: ControlCommand,
+    #[serde(default)]
+    pub seq: DispatchSeq, // Stamped when the command is first delivered; kept across retries
+}
+
+// Replay protection stamp carried by every assignment and control command
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispatchSeq {
+    pub epoch: u64,  // Scheduler start (Unix ms); a newer epoch restarts the sequence
+    pub seq: u64,    // Strictly increasing per robot, starting at 1
+    pub window: u64, // Sequences more than this far behind the newest seen are rejected
+}
+
+// Scheduler-side sequence state of one robot
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RobotSequence {
+    pub last_sent: u64,  // Sequence of the latest delivery attempt
+    pub last_acked: u64, // Highest sequence the robot acknowledged
+}
+
+// Robot-side replay filter: accepts each sequence number once, within the sender's window
+#[derive(Clone, Debug, Default)]
+pub struct ReplayGuard {
+    epoch: u64,
+    highest: u64,
+    seen: BTreeSet<u64>, // Accepted sequences inside the window
+}
+
+impl ReplayGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Whether a delivery is new and should be executed. Replays should still be
+    // acknowledged, so the scheduler stops retrying them, but not executed again.
+    pub fn accept(&mut self, stamp: &DispatchSeq) -> bool {
+        if stamp.epoch < self.epoch {
+            return false; // From a scheduler run that has since restarted
+        }
+        if stamp.epoch > self.epoch {
+            *self = ReplayGuard { epoch: stamp.epoch, ..Default::default() };
+        }
+        if stamp.seq.saturating_add(stamp.window) <= self.highest || !self.seen.insert(stamp.seq) {
+            return false;
+        }
+        self.highest = self.highest.max(stamp.seq);
+        let floor = self.highest.saturating_sub(stamp.window);
+        self.seen = self.seen.split_off(&floor);
+        true
+    }
+}
+
+// Result report sent by a robot for an assigned task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RobotReport {
+    pub task_id: u32,
+    pub status: String, // "completed" or "failed"
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+// Delivery mechanism between the scheduler and robots
+pub trait RobotTransport: Send + Sync {
+    // Deliver a task assignment; Ok means the robot accepted it
+    fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>>;
+    // Deliver a control command; Ok means the robot acknowledged it
+    fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>>;
+}
+
+// Delivery settings for the control lane
+#[derive(Clone, Copy, Debug)]
+pub struct ControlDelivery {
+    pub max_attempts: u32,
+    pub retry_backoff: Duration, // Doubled after each failed attempt
+}
+
+impl Default for ControlDelivery {
+    fn default() -> Self {
+        ControlDelivery { max_attempts: 5, retry_backoff: Duration::from_millis(100) }
+    }
+}
+
+// Per-robot sequence numbering shared by a dispatcher and its delivery loops
+pub(crate) struct Sequencer {
+    epoch: u64,
+    window: u64,
+    robots: std::sync::Mutex<HashMap<String, RobotSequence>>,
+}
+
+impl Sequencer {
+    // Stamp the robot's next delivery
+    fn next(&self, robot_id: &str) -> DispatchSeq {
+        let mut robots = self.robots.lock().unwrap_or_else(|e| e.into_inner());
+        let state = robots.entry(robot_id.to_string()).or_default();
+        state.last_sent += 1;
+        DispatchSeq { epoch: self.epoch, seq: state.last_sent, window: self.window }
+    }
+
+    fn acked(&self, robot_id: &str, stamp: &DispatchSeq) {
+        let mut robots = self.robots.lock().unwrap_or_else(|e| e.into_inner());
+        let state = robots.entry(robot_id.to_string()).or_default();
+        state.last_acked = state.last_acked.max(stamp.seq);
+    }
+}
+
+// Sending side of one robot's outbox
+struct Outbox {
+    control: mpsc::UnboundedSender<ControlEnvelope>,
+    assignments: mpsc::Sender<Task>,
+}
+
+// Per-robot outboxes over a shared transport
+pub(crate) struct Dispatcher {
+    transport: Arc<dyn RobotTransport>,
+    delivery: ControlDelivery,
+    assignment_lane_size: usize,
+    next_command_id: AtomicU64,
+    outboxes: Mutex<HashMap<String, Outbox>>,
+    sequencer: Arc<Sequencer>,
+}
+
+impl Dispatcher {
+    // `epoch` distinguishes this scheduler run's sequence numbers from earlier runs'
+    pub(crate) fn new(
+        transport: Arc<dyn RobotTransport>,
+        delivery: ControlDelivery,
+        assignment_lane_size: usize,
+        epoch: u64,
+        replay_window: u64,
+    ) -> Self {
+        Dispatcher {
+            transport,
+            delivery,
+            assignment_lane_size,
+            next_command_id: AtomicU64::new(1),
+            outboxes: Mutex::new(HashMap::new()),
+            sequencer: Arc::new(Sequencer { epoch, window: replay_window, robots: std::sync::Mutex::new(HashMap::new()) }),
+        }
+    }
+
+    // Sequence state of a robot that has been sent anything
+    pub(crate) fn robot_sequence(&self, robot_id: &str) -> Option<RobotSequence> {
+        self.sequencer.robots.lock().unwrap_or_else(|e| e.into_inner()).get(robot_id).copied()
+    }
+
+    // Queue an assignment behind any pending control commands for the robot
+    pub(crate) async fn assign(&self, scheduler: &Scheduler, robot_id: &str, task: Task) -> Result<(), String> {
+        let sender = {
+            let mut outboxes = self.outboxes.lock().await;
+            self.outbox(&mut outboxes, scheduler, robot_id).assignments.clone()
+        };
+        sender.send(task).await.map_err(|_| format!("Outbox for robot {} closed", robot_id))
+    }
+
+    // Queue a control command on the robot's priority lane; returns its command ID
+    pub(crate) async fn control(&self, scheduler: &Scheduler, robot_id: &str, command: ControlCommand) -> Result<u64, String> {
+        let command_id = self.next_command_id.fetch_add(1, Ordering::Relaxed);
+        let envelope = ControlEnvelope { command_id, robot_id: robot_id.to_string(), command, seq: DispatchSeq::default() };
+        let mut outboxes = self.outboxes.lock().await;
+        self.outbox(&mut outboxes, scheduler, robot_id)
+            .control
+            .send(envelope)
+            .map_err(|_| format!("Outbox for robot {} closed", robot_id))?;
+        Ok(command_id)
+    }
+
+    // Get or lazily create a robot's outbox and its delivery worker
+    fn outbox<'a>(&self, outboxes: &'a mut HashMap<String, Outbox>, scheduler: &Scheduler, robot_id: &str) -> &'a Outbox {
+        outboxes.entry(robot_id.to_string()).or_insert_with(|| {
+            let (control_tx, control_rx) = mpsc::unbounded_channel();
+            let (assign_tx, assign_rx) = mpsc::channel(self.assignment_lane_size);
+            tokio::spawn(deliver(
+                scheduler.clone(),
+                self.transport.clone(),
+                self.sequencer.clone(),
+                self.delivery,
+                control_rx,
+                assign_rx,
+            ));
+            Outbox { control: control_tx, assignments: assign_tx }
+        })
+    }
+}
+
+// Deliver one control command, retrying with exponential backoff under one sequence number
+async fn deliver_control(transport: &dyn RobotTransport, sequencer: &Sequencer, delivery: ControlDelivery, mut envelope: ControlEnvelope) {
+    envelope.seq = sequencer.next(&envelope.robot_id);
+    let mut backoff = delivery.retry_backoff;
+    for attempt in 1..=delivery.max_attempts.max(1) {
+        match transport.send_control(&envelope).await {
+            Ok(()) => {
+                sequencer.acked(&envelope.robot_id, &envelope.seq);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(command_id = envelope.command_id, robot_id = %envelope.robot_id, attempt, error = %e, "control command delivery failed");
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+    }
+    tracing::error!(command_id = envelope.command_id, robot_id = %envelope.robot_id, "control command undeliverable");
+}
+
+// Per-robot delivery loop; control commands always win over queued assignments
+async fn deliver(
+    scheduler: Scheduler,
+    transport: Arc<dyn RobotTransport>,
+    sequencer: Arc<Sequencer>,
+    delivery: ControlDelivery,
+    mut control_rx: mpsc::UnboundedReceiver<ControlEnvelope>,
+    mut assign_rx: mpsc::Receiver<Task>,
+) {
+    loop {
+        tokio::select! {
+            biased;
+            Some(envelope) = control_rx.recv() => {
+                deliver_control(transport.as_ref(), &sequencer, delivery, envelope).await;
+            }
+            Some(task) = assign_rx.recv() => {
+                let robot_id = task.robot_id.clone().unwrap_or_default();
+                let seq = sequencer.next(&robot_id);
+                match transport.send_assignment(&robot_id, &task, seq).await {
+                    Ok(()) => {
+                        sequencer.acked(&robot_id, &seq);
+                        scheduler.assignment_accepted(task.id, &robot_id).await;
+                    }
+                    Err(e) => scheduler.report_result(task.id, Err(format!("Assignment delivery failed: {}", e))).await,
+                }
+            }
+            else => break,
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Transport that records deliveries and can be gated to let commands pile up
+    #[derive(Default)]
+    struct RecordingTransport {
+        log: std::sync::Mutex<Vec<String>>,
+        gate: Mutex<()>,
+        control_failures: AtomicU64,
+    }
+
+    impl RobotTransport for RecordingTransport {
+        fn send_assignment<'a>(&'a self, robot_id: &'a str, task: &'a Task, seq: DispatchSeq) -> BoxFuture<'a, Result<(), String>> {
+            Box::pin(async move {
+                let _open = self.gate.lock().await;
+                self.log.lock().unwrap().push(format!("assign {} {} #{}", robot_id, task.id, seq.seq));
+                Ok(())
+            })
+        }
+
+        fn send_control<'a>(&'a self, envelope: &'a ControlEnvelope) -> BoxFuture<'a, Result<(), String>> {
+            Box::pin(async move {
+                if self.control_failures.load(Ordering::SeqCst) > 0 {
+                    self.control_failures.fetch_sub(1, Ordering::SeqCst);
+                    return Err("link down".to_string());
+                }
+                self.log.lock().unwrap().push(format!("control {} #{}", envelope.command_id, envelope.seq.seq));
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_control_bypasses_queued_assignments() {
+        let transport = Arc::new(RecordingTransport::default());
+        let (scheduler, _workers) = Scheduler::builder().build().unwrap();
+        let delivery = ControlDelivery { max_attempts: 3, retry_backoff: Duration::from_millis(1) };
+        let dispatcher = Dispatcher::new(transport.clone(), delivery, 8, 1, 64);
+
+        // Block the first assignment in flight so the rest queue up behind it
+        let gate = transport.gate.lock().await;
+        for id in 1..=3 {
+            let task = Task { id, robot_id: Some("Ford".to_string()), ..Default::default() };
+            dispatcher.assign(&scheduler, "Ford", task).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        transport.control_failures.store(1, Ordering::SeqCst);
+        let command_id = dispatcher.control(&scheduler, "Ford", ControlCommand::Abort { task_id: 3 }).await.unwrap();
+        drop(gate);
+
+        for _ in 0..100 {
+            if transport.log.lock().unwrap().len() == 4 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        let log = transport.log.lock().unwrap().clone();
+        // The failed control attempt is retried under the sequence number it was given
+        assert_eq!(log, vec![
+            "assign Ford 1 #1".to_string(),
+            format!("control {} #2", command_id),
+            "assign Ford 2 #3".to_string(),
+            "assign Ford 3 #4".to_string(),
+        ]);
+        assert_eq!(dispatcher.robot_sequence("Ford"), Some(RobotSequence { last_sent: 4, last_acked: 4 }));
+    }
+
+    #[test]
+    fn test_replay_guard_rejects_duplicates_and_stale_epochs() {
+        let stamp = |epoch, seq| DispatchSeq { epoch, seq, window: 4 };
+        let mut guard = ReplayGuard::new();
+        assert!(guard.accept(&stamp(10, 1)));
+        assert!(guard.accept(&stamp(10, 3)));
+        // Out of order but inside the window is fine once; a redelivery is not
+        assert!(guard.accept(&stamp(10, 2)));
+        assert!(!guard.accept(&stamp(10, 2)));
+        assert!(guard.accept(&stamp(10, 9)));
+        // Too far behind the newest sequence to tell apart from a replay
+        assert!(!guard.accept(&stamp(10, 4)));
+        // A restarted scheduler starts over; the old run's commands are stale
+        assert!(guard.accept(&stamp(11, 1)));
+        assert!(!guard.accept(&stamp(10, 10)));
+    }
+}
@@ -0,0 +1,127 @@
+// backend/rust/src/uploads.rs
+// Purpose: Staging area for chunked submission of very large missions. Callers open an
+// upload, append tasks chunk by chunk, and commit at the end, through the `Scheduler`
+// methods or the HTTP API (POST /uploads, POST /uploads/{id}/chunks with a JSON array of
+// tasks per request, POST /uploads/{id}/commit, DELETE /uploads/{id}); there is no gRPC
+// streaming or multipart form upload. Each chunk is validated as it arrives and rejected
+// whole on error; nothing becomes visible to the scheduler until the commit inserts every
+// task at once. An upload left without a chunk for longer than its time-to-live (see
+// `SchedulerBuilder::upload_ttl`) is discarded by the workers.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use crate::task::Task;
+
+// Idle time after which an open upload is discarded, unless the builder sets another
+pub const DEFAULT_UPLOAD_TTL: Duration = Duration::from_secs(3600);
+
+// Tasks accepted so far for one open upload
+struct UploadSession {
+    mission_id: Option<String>, // Applied to tasks that name no mission
+    tasks: Vec<Task>,
+    ids: HashSet<u32>,
+    touched_at: u64, // Unix timestamp (milliseconds) of the last chunk, or of the opening
+}
+
+// Open uploads keyed by upload ID ("upload-N")
+#[derive(Default)]
+pub(crate) struct UploadRegistry {
+    next_id: u64,
+    sessions: HashMap<String, UploadSession>,
+}
+
+impl UploadRegistry {
+    pub(crate) fn begin(&mut self, mission_id: Option<String>, now: u64) -> String {
+        self.next_id += 1;
+        let upload_id = format!("upload-{}", self.next_id);
+        let session = UploadSession { mission_id, tasks: Vec::new(), ids: HashSet::new(), touched_at: now };
+        self.sessions.insert(upload_id.clone(), session);
+        upload_id
+    }
+
+    // Check a chunk against the upload's own tasks and stamp the upload's mission on it
+    pub(crate) fn prepare_chunk(&self, upload_id: &str, tasks: &mut [Task]) -> Result<(), String> {
+        let session = self.sessions.get(upload_id).ok_or_else(|| format!("Unknown upload: {}", upload_id))?;
+        let mut chunk_ids = HashSet::new();
+        for task in tasks.iter_mut() {
+            if session.ids.contains(&task.id) || !chunk_ids.insert(task.id) {
+                return Err(format!("Duplicate task {} in upload {}", task.id, upload_id));
+            }
+            match (&session.mission_id, &task.mission_id) {
+                (Some(mission), Some(own)) if mission != own => {
+                    return Err(format!("Task {} belongs to mission {}, not {}", task.id, own, mission));
+                }
+                (Some(mission), None) => task.mission_id = Some(mission.clone()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Stage a chunk that passed validation, checking it again so a chunk staged since it
+    // was prepared can't share its tasks; returns the number of tasks staged so far
+    pub(crate) fn append(&mut self, upload_id: &str, mut tasks: Vec<Task>, now: u64) -> Result<usize, String> {
+        self.prepare_chunk(upload_id, &mut tasks)?;
+        let session = self.sessions.get_mut(upload_id).ok_or_else(|| format!("Unknown upload: {}", upload_id))?;
+        session.touched_at = now;
+        session.ids.extend(tasks.iter().map(|t| t.id));
+        session.tasks.extend(tasks);
+        Ok(session.tasks.len())
+    }
+
+    // Close an upload and hand back its staged tasks
+    pub(crate) fn take(&mut self, upload_id: &str) -> Result<Vec<Task>, String> {
+        self.sessions
+            .remove(upload_id)
+            .map(|session| session.tasks)
+            .ok_or_else(|| format!("Unknown upload: {}", upload_id))
+    }
+
+    // Discard uploads idle for at least `ttl`; returns their IDs
+    pub(crate) fn expire(&mut self, now: u64, ttl: Duration) -> Vec<String> {
+        let ttl = ttl.as_millis() as u64;
+        let mut expired: Vec<String> = self.sessions.iter().filter(|(_, s)| now.saturating_sub(s.touched_at) >= ttl).map(|(id, _)| id.clone()).collect();
+        expired.sort();
+        for upload_id in &expired {
+            self.sessions.remove(upload_id);
+        }
+        expired
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_rejects_duplicates_and_foreign_missions() {
+        let mut uploads = UploadRegistry::default();
+        let upload_id = uploads.begin(Some("survey".to_string()), 0);
+        let mut first = vec![Task { id: 1, ..Default::default() }, Task { id: 2, ..Default::default() }];
+        uploads.prepare_chunk(&upload_id, &mut first).unwrap();
+        assert_eq!(first[0].mission_id.as_deref(), Some("survey"));
+        assert_eq!(uploads.append(&upload_id, first.clone(), 0).unwrap(), 2);
+        // A chunk prepared before another staged the same tasks is refused when appended
+        assert!(uploads.append(&upload_id, first, 0).unwrap_err().contains("Duplicate task 1"));
+
+        let mut repeat = vec![Task { id: 2, ..Default::default() }];
+        assert!(uploads.prepare_chunk(&upload_id, &mut repeat).unwrap_err().contains("Duplicate task 2"));
+        let mut foreign = vec![Task { id: 3, mission_id: Some("patrol".to_string()), ..Default::default() }];
+        assert!(uploads.prepare_chunk(&upload_id, &mut foreign).is_err());
+
+        assert_eq!(uploads.take(&upload_id).unwrap().len(), 2);
+        assert!(uploads.take(&upload_id).is_err());
+    }
+
+    #[test]
+    fn test_idle_uploads_expire() {
+        let mut uploads = UploadRegistry::default();
+        let idle = uploads.begin(None, 0);
+        let busy = uploads.begin(None, 0);
+        uploads.append(&busy, vec![Task { id: 1, ..Default::default() }], 50_000).unwrap();
+        assert_eq!(uploads.expire(60_000, Duration::from_secs(60)), vec![idle.clone()]);
+        assert!(uploads.take(&idle).is_err());
+        assert_eq!(uploads.take(&busy).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,105 @@
+// backend/rust/src/validation.rs
+// Purpose: Parallel validation stage for MRTODP submissions. At high submission rates
+// (thousands per second) checking every task inline in `schedule_task` — routing and
+// admission rules, field and geometry validation, robot and capability lookups — makes
+// submitters queue up behind each other. With the stage enabled on the builder, a submission
+// is handed to a bounded channel drained by a pool of validation workers and the submitter
+// awaits the verdict; accepted tasks are then recorded and queued as before. Only the short
+// robot lookup takes the capability lock, so workers validate side by side. A full channel
+// applies backpressure to submitters. The workers run with `SchedulerWorkers`, so
+// submissions wait until the workers are started.
+
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use crate::scheduler::Scheduler;
+use crate::task::Task;
+
+// Validation stage settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationConfig {
+    pub workers: usize,        // Submissions validated in parallel
+    pub queue_capacity: usize, // Submissions waiting for a worker before submitters block
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        ValidationConfig { workers, queue_capacity: 1024 }
+    }
+}
+
+impl ValidationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.workers == 0 {
+            return Err("Validation stage needs at least one worker".to_string());
+        }
+        if self.queue_capacity == 0 {
+            return Err("Validation queue capacity must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+// A submission waiting for validation and where to send the verdict: the task as routed by
+// the rules, or why it was rejected
+pub(crate) struct ValidationJob {
+    pub(crate) task: Task,
+    pub(crate) reply: oneshot::Sender<Result<Task, String>>,
+}
+
+// Run the validation workers; they share the channel and stop when it closes
+pub(crate) async fn run_validators(scheduler: Scheduler, rx: mpsc::Receiver<ValidationJob>, workers: usize) {
+    let rx = Arc::new(Mutex::new(rx));
+    let mut pool = tokio::task::JoinSet::new();
+    for _ in 0..workers {
+        let (scheduler, rx) = (scheduler.clone(), rx.clone());
+        pool.spawn(async move {
+            loop {
+                // Hold the receiver only while taking a job, not while validating it
+                let Some(job) = rx.lock().await.recv().await else {
+                    return;
+                };
+                let mut task = job.task;
+                let verdict = scheduler.check_submission(&mut task).await.map(|()| task);
+                // A send error means the submitter gave up waiting
+                let _ = job.reply.send(verdict);
+            }
+        });
+    }
+    while pool.join_next().await.is_some() {}
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::TaskState;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_submissions_validated_in_parallel_stage() {
+        assert!(ValidationConfig { workers: 0, queue_capacity: 8 }.validate().is_err());
+        let config = ValidationConfig { workers: 4, queue_capacity: 8 };
+        let (scheduler, workers) = Scheduler::builder().validation(config).build().unwrap();
+        workers.spawn();
+        scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+
+        // More concurrent submitters than queue slots; every third names an unknown robot
+        let mut submitters = tokio::task::JoinSet::new();
+        for id in 1000..1060 {
+            let scheduler = scheduler.clone();
+            let robot_id = if id % 3 == 0 { "Ghost" } else { "Ford" };
+            let task = Task { id, robot_id: Some(robot_id.to_string()), required_capabilities: vec!["lift".to_string()], ..Default::default() };
+            submitters.spawn(async move { (id, scheduler.schedule_task(task).await) });
+        }
+        while let Some(joined) = submitters.join_next().await {
+            let (id, result) = joined.unwrap();
+            if id % 3 == 0 {
+                assert_eq!(result, Err("Unknown robot: Ghost".to_string()));
+                assert!(scheduler.task_record(id).await.is_none());
+            } else {
+                assert_eq!(result, Ok(()));
+                assert!(scheduler.task_record(id).await.is_some_and(|r| r.state != TaskState::Failed));
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// backend/rust/src/versioning.rs
+// Purpose: Optimistic concurrency on MRTODP task records. Every record carries a `version`
+// that starts at 1 on submission and goes up by one with each change the scheduler writes
+// (transitions, holds, pins, re-routing). A controller reads a record, decides what to do,
+// and applies its update with the version it read: `Scheduler::transition_task_if` and
+// `complete_task` refuse with VERSION_CONFLICT_ERROR if the record changed in between, so
+// two controllers racing on the same task can't silently overwrite each other's decision.
+// The loser re-reads the record and decides again. Served by the Rust API, POST
+// /tasks/{id}/complete (409 on a conflict), and the FFI.
+
+use crate::scheduler::{ReasonCode, Scheduler, TaskState};
+
+// Prefix of the error returned when a task changed since the version a caller expected
+pub const VERSION_CONFLICT_ERROR: &str = "Version conflict";
+
+impl Scheduler {
+    // Move a task to a new state only if its record is still at `expected_version`;
+    // returns the record's new version
+    pub async fn transition_task_if(&self, task_id: u32, expected_version: u64, to: TaskState, reason: ReasonCode, detail: String) -> Result<u64, String> {
+        self.transition_versioned(task_id, Some(expected_version), to, reason, detail).await
+    }
+
+    // Mark an assigned or running task completed on behalf of a controller, only if its
+    // record is still at `expected_version`; returns the record's new version
+    pub async fn complete_task(&self, task_id: u32, expected_version: u64) -> Result<u64, String> {
+        let detail = format!("Completed by controller at version {}", expected_version);
+        self.transition_task_if(task_id, expected_version, TaskState::Completed, ReasonCode::CompletedOk, detail).await
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::task::Task;
+    use crate::test_utils::{FakeBehavior, FakeRobotAdapter};
+
+    #[tokio::test]
+    async fn test_racing_completions_detect_conflicts() {
+        let fake = Arc::new(FakeRobotAdapter::new());
+        let (scheduler, workers) = Scheduler::builder().transport(fake.clone()).build().unwrap();
+        fake.attach(&scheduler);
+        fake.script("Ford", vec![FakeBehavior::AckAfter(Duration::from_secs(60))]);
+        scheduler.register_robot("Ford".to_string(), vec![]).await.unwrap();
+        scheduler.schedule_task(Task { id: 386, ..Default::default() }).await.unwrap();
+        assert_eq!(scheduler.task_record(386).await.unwrap().version, 1);
+        scheduler.hold_task(386).await.unwrap();
+        let stale = scheduler.task_record(386).await.unwrap().version;
+        scheduler.release_task(386).await.unwrap();
+        workers.spawn();
+        while scheduler.task_record(386).await.is_none_or(|r| r.state != TaskState::Running) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Two controllers read the same version; only the first update lands
+        let seen = scheduler.task_record(386).await.unwrap().version;
+        assert!(scheduler.complete_task(386, stale).await.unwrap_err().starts_with(VERSION_CONFLICT_ERROR));
+        let version = scheduler.complete_task(386, seen).await.unwrap();
+        assert_eq!(version, seen + 1);
+        let lost = scheduler.transition_task_if(386, seen, TaskState::Failed, ReasonCode::FailedRobotError, "Timed out".to_string()).await;
+        assert!(lost.unwrap_err().starts_with(VERSION_CONFLICT_ERROR));
+        let record = scheduler.task_record(386).await.unwrap();
+        assert_eq!((record.state, record.version), (TaskState::Completed, version));
+    }
+}
@@ -0,0 +1,335 @@
+// backend/rust/src/wal.rs
+// Purpose: Write-ahead log `TaskStore` for MRTODP. Every store write (task submissions,
+// assignments, and completions as they update task records, plus robot
+// (de)registrations, slots, models, profiles, audit entries, quota counters, and
+// missions) is appended to a log file and synced before the call returns, and the
+// scheduler writes under the records lock before it publishes the change, so no caller
+// observes a decision that isn't durable; a task change the log refuses is rejected and
+// leaves the task as it was. On open the log is replayed to rebuild state, then
+// compacted to one entry per live key; the builder's startup recovery re-queues whatever
+// was unfinished. The workers compact it again periodically, after dropping finished
+// tasks past their retention (see retention.rs); compaction also keeps only the most
+// recent audit entries (default: the scheduler's `DEFAULT_AUDIT_CAPACITY`), so a
+// long-running process doesn't grow the log without bound. No database is needed.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::audit::{AuditEntry, DEFAULT_AUDIT_CAPACITY};
+use crate::compatibility::RobotModel;
+use crate::missions::Mission;
+use crate::profiles::RobotProfile;
+use crate::quotas::QuotaCounter;
+use crate::scheduler::TaskRecord;
+use crate::store::{MemoryStore, TaskStore};
+
+// One logged store write
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum WalEntry {
+    SaveTask { record: Box<TaskRecord> },
+    RemoveTask { task_id: u32 },
+    SaveRobot { robot_id: String, capabilities: Vec<String> },
+    RemoveRobot { robot_id: String },
+    SaveRobotSlots { robot_id: String, slots: u32 },
+    SaveRobotModel { robot_id: String, model: RobotModel },
+    SaveProfile { profile: RobotProfile },
+    AppendAudit { entry: AuditEntry },
+    SaveQuotaCounters { counters: HashMap<String, QuotaCounter> },
+    SaveMission { mission: Mission },
+}
+
+impl WalEntry {
+    fn apply(self, state: &MemoryStore) -> Result<(), String> {
+        match self {
+            WalEntry::SaveTask { record } => state.save_task(&record),
+            WalEntry::RemoveTask { task_id } => state.remove_task(task_id),
+            WalEntry::SaveRobot { robot_id, capabilities } => state.save_robot(&robot_id, &capabilities),
+            WalEntry::RemoveRobot { robot_id } => state.remove_robot(&robot_id),
+            WalEntry::SaveRobotSlots { robot_id, slots } => state.save_robot_slots(&robot_id, slots),
+            WalEntry::SaveRobotModel { robot_id, model } => state.save_robot_model(&robot_id, &model),
+            WalEntry::SaveProfile { profile } => state.save_profile(&profile),
+            WalEntry::AppendAudit { entry } => state.append_audit(&entry),
+            WalEntry::SaveQuotaCounters { counters } => state.save_quota_counters(&counters),
+            WalEntry::SaveMission { mission } => state.save_mission(&mission),
+        }
+    }
+}
+
+// Store that logs each write to an append-only file before applying it in memory
+pub struct WalStore {
+    path: PathBuf,
+    log: Mutex<File>,   // Held across append and apply so the log and state agree on order
+    state: MemoryStore, // Replayed view served to reads
+}
+
+impl WalStore {
+    // Open the log at `path`, creating it if missing, replaying and compacting existing
+    // entries. A torn final line left by a crash mid-append is discarded; corruption
+    // anywhere else is an error rather than silently lost history.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::open_with_audit_capacity(path, DEFAULT_AUDIT_CAPACITY)
+    }
+
+    // `open`, keeping at most `entries` audit entries; older ones are dropped from the log
+    // when it is next compacted
+    pub fn open_with_audit_capacity(path: impl AsRef<Path>, entries: usize) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let state = MemoryStore::with_audit_capacity(entries);
+        if path.exists() {
+            let file = File::open(&path).map_err(|e| format!("Failed to open WAL {}: {}", path.display(), e))?;
+            let lines: Vec<String> = BufReader::new(file)
+                .lines()
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to read WAL {}: {}", path.display(), e))?;
+            for (i, line) in lines.iter().enumerate() {
+                match serde_json::from_str::<WalEntry>(line) {
+                    Ok(entry) => entry.apply(&state)?,
+                    Err(_) if i + 1 == lines.len() => tracing::warn!(path = %path.display(), "discarding torn final WAL entry"),
+                    Err(e) => return Err(format!("Corrupt WAL entry {} in {}: {}", i + 1, path.display(), e)),
+                }
+            }
+        }
+        let log = Self::write_snapshot(&path, &state)?;
+        Ok(WalStore { path, log: Mutex::new(log), state })
+    }
+
+    // Rewrite the log as the minimal set of entries that rebuilds the current state
+    pub fn compact(&self) -> Result<(), String> {
+        let mut log = self.log.lock().map_err(|e| format!("WAL lock poisoned: {}", e))?;
+        *log = Self::write_snapshot(&self.path, &self.state)?;
+        Ok(())
+    }
+
+    // Write a snapshot beside the log, atomically swap it in, and return it open for appends
+    fn write_snapshot(path: &Path, state: &MemoryStore) -> Result<File, String> {
+        let mut entries: Vec<WalEntry> = Vec::new();
+        let mut robots: Vec<(String, Vec<String>)> = state.load_robots()?.into_iter().collect();
+        robots.sort();
+        entries.extend(robots.into_iter().map(|(robot_id, capabilities)| WalEntry::SaveRobot { robot_id, capabilities }));
+        let mut slots: Vec<(String, u32)> = state.load_robot_slots()?.into_iter().collect();
+        slots.sort();
+        entries.extend(slots.into_iter().map(|(robot_id, slots)| WalEntry::SaveRobotSlots { robot_id, slots }));
+        let mut models: Vec<(String, RobotModel)> = state.load_robot_models()?.into_iter().collect();
+        models.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.extend(models.into_iter().map(|(robot_id, model)| WalEntry::SaveRobotModel { robot_id, model }));
+        entries.extend(state.load_profiles()?.into_iter().map(|profile| WalEntry::SaveProfile { profile }));
+        entries.extend(state.load_audit()?.into_iter().map(|entry| WalEntry::AppendAudit { entry }));
+        let counters = state.load_quota_counters()?;
+        if !counters.is_empty() {
+            entries.push(WalEntry::SaveQuotaCounters { counters });
+        }
+        let mut missions = state.load_missions()?;
+        missions.sort_by(|a, b| a.id.cmp(&b.id));
+        entries.extend(missions.into_iter().map(|mission| WalEntry::SaveMission { mission }));
+        let mut tasks = state.load_tasks()?;
+        tasks.sort_by_key(|r| r.task.id);
+        entries.extend(tasks.into_iter().map(|record| WalEntry::SaveTask { record: Box::new(record) }));
+
+        let tmp = path.with_extension("compact");
+        let write = || -> std::io::Result<()> {
+            let mut file = File::create(&tmp)?;
+            for entry in &entries {
+                let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+                writeln!(file, "{}", line)?;
+            }
+            file.sync_all()?;
+            fs::rename(&tmp, path)
+        };
+        write().map_err(|e| format!("Failed to compact WAL {}: {}", path.display(), e))?;
+        OpenOptions::new().append(true).open(path).map_err(|e| format!("Failed to open WAL {}: {}", path.display(), e))
+    }
+
+    // Durably log an entry, then apply it to the in-memory state
+    fn append(&self, entry: WalEntry) -> Result<(), String> {
+        let mut line = serde_json::to_string(&entry).map_err(|e| format!("Failed to encode WAL entry: {}", e))?;
+        line.push('\n');
+        let mut log = self.log.lock().map_err(|e| format!("WAL lock poisoned: {}", e))?;
+        log.write_all(line.as_bytes())
+            .and_then(|_| log.sync_data())
+            .map_err(|e| format!("WAL append to {} failed: {}", self.path.display(), e))?;
+        entry.apply(&self.state)
+    }
+}
+
+impl TaskStore for WalStore {
+    fn save_task(&self, record: &TaskRecord) -> Result<(), String> {
+        self.append(WalEntry::SaveTask { record: Box::new(record.clone()) })
+    }
+
+    fn load_task(&self, task_id: u32) -> Result<Option<TaskRecord>, String> {
+        self.state.load_task(task_id)
+    }
+
+    fn load_tasks(&self) -> Result<Vec<TaskRecord>, String> {
+        self.state.load_tasks()
+    }
+
+    fn remove_task(&self, task_id: u32) -> Result<(), String> {
+        self.append(WalEntry::RemoveTask { task_id })
+    }
+
+    fn save_robot(&self, robot_id: &str, capabilities: &[String]) -> Result<(), String> {
+        self.append(WalEntry::SaveRobot { robot_id: robot_id.to_string(), capabilities: capabilities.to_vec() })
+    }
+
+    fn load_robots(&self) -> Result<HashMap<String, Vec<String>>, String> {
+        self.state.load_robots()
+    }
+
+    fn remove_robot(&self, robot_id: &str) -> Result<(), String> {
+        self.append(WalEntry::RemoveRobot { robot_id: robot_id.to_string() })
+    }
+
+    fn save_robot_slots(&self, robot_id: &str, slots: u32) -> Result<(), String> {
+        self.append(WalEntry::SaveRobotSlots { robot_id: robot_id.to_string(), slots })
+    }
+
+    fn load_robot_slots(&self) -> Result<HashMap<String, u32>, String> {
+        self.state.load_robot_slots()
+    }
+
+    fn save_robot_model(&self, robot_id: &str, model: &RobotModel) -> Result<(), String> {
+        self.append(WalEntry::SaveRobotModel { robot_id: robot_id.to_string(), model: model.clone() })
+    }
+
+    fn load_robot_models(&self) -> Result<HashMap<String, RobotModel>, String> {
+        self.state.load_robot_models()
+    }
+
+    fn save_profile(&self, profile: &RobotProfile) -> Result<(), String> {
+        self.append(WalEntry::SaveProfile { profile: profile.clone() })
+    }
+
+    fn load_profiles(&self) -> Result<Vec<RobotProfile>, String> {
+        self.state.load_profiles()
+    }
+
+    fn append_audit(&self, entry: &AuditEntry) -> Result<(), String> {
+        self.append(WalEntry::AppendAudit { entry: entry.clone() })
+    }
+
+    fn load_audit(&self) -> Result<Vec<AuditEntry>, String> {
+        self.state.load_audit()
+    }
+
+    fn save_quota_counters(&self, counters: &HashMap<String, QuotaCounter>) -> Result<(), String> {
+        self.append(WalEntry::SaveQuotaCounters { counters: counters.clone() })
+    }
+
+    fn load_quota_counters(&self) -> Result<HashMap<String, QuotaCounter>, String> {
+        self.state.load_quota_counters()
+    }
+
+    fn save_mission(&self, mission: &Mission) -> Result<(), String> {
+        self.append(WalEntry::SaveMission { mission: mission.clone() })
+    }
+
+    fn load_missions(&self) -> Result<Vec<Mission>, String> {
+        self.state.load_missions()
+    }
+
+    fn compact(&self) -> Result<(), String> {
+        WalStore::compact(self)
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::audit::AuditAction;
+    use crate::missions::DEFAULT_NAMESPACE;
+    use crate::scheduler::{Scheduler, TaskState};
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_replay_restores_state_and_tolerates_torn_tail() {
+        let path = std::env::temp_dir().join(format!("mrtodp-wal-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        {
+            let (scheduler, workers) = Scheduler::builder().store(Arc::new(WalStore::open(&path).unwrap())).build().unwrap();
+            let mut events = scheduler.subscribe();
+            workers.spawn();
+            scheduler.register_robot("Ford".to_string(), vec!["lift".to_string()]).await.unwrap();
+            scheduler.schedule_task(Task { id: 231, ..Default::default() }).await.unwrap();
+            while events.recv().await.unwrap().transition.to != TaskState::Completed {}
+        }
+        // A crash mid-append leaves half an entry behind
+        let mut log = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(log, "{{\"op\":\"save_task\",\"rec").unwrap();
+        drop(log);
+
+        let store = WalStore::open(&path).unwrap();
+        let record = store.load_task(231).unwrap().unwrap();
+        assert_eq!(record.state, TaskState::Completed);
+        assert_eq!(record.task.robot_id.as_deref(), Some("Ford"));
+        assert_eq!(store.load_robots().unwrap()["Ford"], vec!["lift".to_string()]);
+        // Replay compacted the log to one entry per robot, robot profile, and task, plus the
+        // audit entries for the registration, the submission, and the assignment
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 6);
+        assert_eq!(store.load_audit().unwrap().len(), 3);
+
+        fs::write(&path, "garbage\n{}\n").unwrap();
+        let err = WalStore::open(&path).err().unwrap();
+        assert!(err.contains("Corrupt WAL entry 1"), "{}", err);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compaction_keeps_recent_audit_entries() {
+        let path = std::env::temp_dir().join(format!("mrtodp-wal-audit-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = WalStore::open_with_audit_capacity(&path, 10).unwrap();
+        for at in 0..100 {
+            let entry = AuditEntry {
+                at,
+                action: AuditAction::TaskScheduled,
+                actor: None,
+                task_id: None,
+                robot_id: None,
+                previous_robot_id: None,
+                detail: String::new(),
+            };
+            store.append_audit(&entry).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 100);
+        TaskStore::compact(&store).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 10);
+        drop(store);
+        let store = WalStore::open_with_audit_capacity(&path, 10).unwrap();
+        assert_eq!(store.load_audit().unwrap().first().map(|e| e.at), Some(90));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refused_writes_reject_the_change() {
+        let path = std::env::temp_dir().join(format!("mrtodp-wal-refused-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = Arc::new(WalStore::open(&path).unwrap());
+        let (scheduler, _workers) = Scheduler::builder().store(store.clone()).default_daily_quota(10).build().unwrap();
+        scheduler.schedule_task(Task { id: 417, ..Default::default() }).await.unwrap();
+
+        // A log that takes no more writes, as on a failed disk
+        *store.log.lock().unwrap() = File::open(&path).unwrap();
+        let err = scheduler.schedule_task(Task { id: 418, ..Default::default() }).await.unwrap_err();
+        assert!(err.starts_with("Failed to persist task 418"), "{}", err);
+        assert!(scheduler.task_record(418).await.is_none());
+        assert_eq!(scheduler.quota_usage(DEFAULT_NAMESPACE).used, 1);
+        assert!(scheduler.hold_task(417).await.is_err());
+        let record = scheduler.task_record(417).await.unwrap();
+        assert_eq!((record.held, record.version), (false, 1));
+
+        *store.log.lock().unwrap() = OpenOptions::new().append(true).open(&path).unwrap();
+        scheduler.hold_task(417).await.unwrap();
+        scheduler.schedule_task(Task { id: 418, ..Default::default() }).await.unwrap();
+        let store = WalStore::open(&path).unwrap();
+        assert!(store.load_task(417).unwrap().unwrap().held);
+        assert!(store.load_task(418).unwrap().is_some());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+// backend/rust/src/watch.rs
+// Purpose: Live task updates for MRTODP user interfaces. A watch follows the scheduler's
+// transition events through an `EventFilter` and reports each one as a compact
+// `WatchEvent` naming the lifecycle step (scheduled, assigned, started, completed, failed,
+// expired, cancelled, suspended), so a UI can keep fleet state current without polling
+// `get_task_status` in a loop. A watch that falls behind skips to the latest update of each
+// task rather than being disconnected. Watches are served over a WebSocket at GET /watch
+// (see http.rs) and through the FFI as open/poll/close calls.
+
+use serde::{Deserialize, Serialize};
+use crate::events::{EventFilter, FilteredSubscription, SlowConsumerPolicy, StreamError, StreamOptions};
+use crate::scheduler::{ReasonCode, Scheduler, TaskEvent, TaskState};
+
+// Lifecycle step a watch event reports
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchKind {
+    Scheduled, // Accepted, or queued again for another attempt
+    Assigned,
+    Started, // Running on its robot, or resumed there after a preemption
+    Completed,
+    Failed,
+    Expired,
+    Cancelled,
+    Suspended,
+}
+
+// One task update as shown to a watcher
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub seq: u64,
+    pub task_id: u32,
+    pub kind: WatchKind,
+    pub at: u64, // Unix milliseconds
+    pub task_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robot_id: Option<String>,
+    pub reason: ReasonCode,
+    pub detail: String,
+}
+
+impl From<&TaskEvent> for WatchEvent {
+    fn from(event: &TaskEvent) -> Self {
+        let kind = match event.transition.to {
+            TaskState::Pending => WatchKind::Scheduled,
+            TaskState::Assigned => WatchKind::Assigned,
+            TaskState::Running => WatchKind::Started,
+            TaskState::Completed => WatchKind::Completed,
+            TaskState::Failed => WatchKind::Failed,
+            TaskState::Expired => WatchKind::Expired,
+            TaskState::Cancelled => WatchKind::Cancelled,
+            TaskState::Suspended => WatchKind::Suspended,
+        };
+        WatchEvent {
+            seq: event.seq,
+            task_id: event.task_id,
+            kind,
+            at: event.transition.at,
+            task_type: event.task_type.clone(),
+            robot_id: event.robot_id.clone(),
+            reason: event.transition.reason,
+            detail: event.transition.detail.clone(),
+        }
+    }
+}
+
+// Stream of task updates matching a filter
+pub struct Watch {
+    subscription: FilteredSubscription,
+}
+
+impl Watch {
+    // Next update, waiting for one; ends only when the scheduler shuts down
+    pub async fn next(&mut self) -> Result<WatchEvent, StreamError> {
+        self.subscription.recv().await.map(|event| WatchEvent::from(&event))
+    }
+
+    // Up to `max` updates that have already arrived, without waiting
+    pub fn poll(&mut self, max: usize) -> Result<Vec<WatchEvent>, StreamError> {
+        let mut events = Vec::new();
+        while events.len() < max {
+            match self.subscription.try_recv()? {
+                Some(event) => events.push(WatchEvent::from(&event)),
+                None => break,
+            }
+        }
+        Ok(events)
+    }
+}
+
+impl Scheduler {
+    // Watch task updates matching `filter` from now on
+    pub fn watch(&self, filter: EventFilter) -> Watch {
+        let options = StreamOptions { policy: SlowConsumerPolicy::Coalesce, ..StreamOptions::default() };
+        let subscription = self.subscribe_stream(filter, options).expect("a live subscription needs no replay");
+        Watch { subscription }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::task::Task;
+
+    #[tokio::test]
+    async fn test_watch_reports_lifecycle_steps() {
+        let (scheduler, workers) = Scheduler::builder().build().unwrap();
+        workers.spawn();
+        let mut watch = scheduler.watch(EventFilter { task_types: vec!["weld".to_string()], ..Default::default() });
+        assert!(watch.poll(10).unwrap().is_empty());
+        scheduler.schedule_task(Task { id: 334, task_type: "inspect".to_string(), ..Default::default() }).await.unwrap();
+        scheduler.schedule_task(Task { id: 335, task_type: "weld".to_string(), ..Default::default() }).await.unwrap();
+
+        let mut kinds = Vec::new();
+        while kinds.last() != Some(&WatchKind::Completed) {
+            kinds.extend(watch.poll(1).unwrap().into_iter().inspect(|e| assert_eq!(e.task_id, 335)).map(|e| e.kind));
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(kinds, vec![WatchKind::Scheduled, WatchKind::Started, WatchKind::Completed]);
+        assert_eq!(serde_json::to_value(WatchKind::Started).unwrap(), "started");
+    }
+}
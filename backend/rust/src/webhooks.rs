@@ -0,0 +1,304 @@
+// backend/rust/src/webhooks.rs
+// Purpose: Completion webhooks for MRTODP tasks. Each webhook selects the task states it
+// fires on and may carry a handlebars-style payload template (`{{task.id}}`) rendered
+// against the event, task, and record, so receivers such as an MES get exactly the JSON
+// shape they expect. Delivery goes through the pluggable `WebhookTransport` trait.
+
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+use crate::scheduler::{Scheduler, TaskEvent, TaskState};
+use crate::BoxFuture;
+
+// Webhook endpoint with optional payload template
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub states: Vec<TaskState>, // States that trigger delivery; empty = terminal states
+    #[serde(default)]
+    pub template: Option<String>, // Payload template; None = default JSON payload
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+impl WebhookConfig {
+    pub fn new(url: &str) -> Self {
+        WebhookConfig {
+            url: url.to_string(),
+            states: Vec::new(),
+            template: None,
+            max_attempts: default_max_attempts(),
+        }
+    }
+
+    pub fn template(mut self, template: &str) -> Self {
+        self.template = Some(template.to_string());
+        self
+    }
+
+    pub fn states(mut self, states: Vec<TaskState>) -> Self {
+        self.states = states;
+        self
+    }
+
+    // Validate the template by rendering it against a sample context
+    pub fn validate(&self) -> Result<(), String> {
+        if self.url.is_empty() {
+            return Err("Webhook URL must not be empty".to_string());
+        }
+        if let Some(template) = &self.template {
+            let sample = json!({
+                "event": {"task_id": 0, "attempt": 1, "transition": {"from": null, "to": "Completed", "reason": "COMPLETED_OK", "detail": "", "at": 0}},
+                "task": {"id": 0, "task_type": "", "priority": 0, "deadline": null, "robot_id": null, "required_capabilities": []},
+                "record": {"state": "Completed", "attempts": []},
+            });
+            let rendered = render_template(template, &sample)?;
+            serde_json::from_str::<Value>(&rendered)
+                .map_err(|e| format!("Webhook template does not render valid JSON: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn fires_on(&self, state: TaskState) -> bool {
+        if self.states.is_empty() {
+            state.is_terminal()
+        } else {
+            self.states.contains(&state)
+        }
+    }
+}
+
+// Look up a dotted path (`task.robot_id`, `record.attempts.0.number`) in a JSON value
+fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(context, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+// Render a template. `{{path}}` inserts strings JSON-escaped without quotes (for use inside
+// a quoted template string) and other values as JSON; `{{json path}}` always inserts JSON.
+// Unknown paths render as `null`.
+pub fn render_template(template: &str, context: &Value) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("Unclosed placeholder at offset {}", template.len() - rest.len() + start))?;
+        let expr = after[..end].trim();
+        let (raw_json, path) = match expr.strip_prefix("json ") {
+            Some(path) => (true, path.trim()),
+            None => (false, expr),
+        };
+        if path.is_empty() {
+            return Err("Empty placeholder in template".to_string());
+        }
+        let value = lookup(context, path).unwrap_or(&Value::Null);
+        match value {
+            Value::String(s) if !raw_json => {
+                let quoted = serde_json::to_string(s).map_err(|e| e.to_string())?;
+                output.push_str(&quoted[1..quoted.len() - 1]);
+            }
+            other => output.push_str(&other.to_string()),
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+// Delivery mechanism for rendered webhook payloads
+pub trait WebhookTransport: Send + Sync {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> BoxFuture<'a, Result<(), String>>;
+}
+
+// Minimal HTTP/1.1 POST over plain TCP for `http://` endpoints on the plant network
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpWebhookTransport;
+
+impl HttpWebhookTransport {
+    async fn send(url: &str, body: String) -> Result<(), String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Unsupported webhook URL (only http:// is supported): {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+        let mut stream = tokio::time::timeout(Duration::from_secs(5), TcpStream::connect(&address))
+            .await
+            .map_err(|_| format!("Webhook connect to {} timed out", address))?
+            .map_err(|e| format!("Webhook connect to {} failed: {}", address, e))?;
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            authority,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.map_err(|e| format!("Webhook write failed: {}", e))?;
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(10), stream.read_to_end(&mut response))
+            .await
+            .map_err(|_| "Webhook response timed out".to_string())?
+            .map_err(|e| format!("Webhook read failed: {}", e))?;
+        let status_line = String::from_utf8_lossy(&response);
+        let status = status_line.split_whitespace().nth(1).unwrap_or("");
+        if status.starts_with('2') {
+            Ok(())
+        } else {
+            Err(format!("Webhook {} returned status {}", url, status))
+        }
+    }
+}
+
+impl WebhookTransport for HttpWebhookTransport {
+    fn post<'a>(&'a self, url: &'a str, body: String) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(Self::send(url, body))
+    }
+}
+
+// Background worker delivering webhooks for transition events
+pub(crate) struct WebhookDispatcher {
+    pub(crate) scheduler: Scheduler,
+    pub(crate) webhooks: Vec<WebhookConfig>,
+    pub(crate) transport: Arc<dyn WebhookTransport>,
+    pub(crate) events: broadcast::Receiver<TaskEvent>,
+}
+
+impl WebhookDispatcher {
+    // Build the payload for one webhook and event
+    async fn payload(&self, webhook: &WebhookConfig, event: &TaskEvent) -> Result<String, String> {
+        let record = self.scheduler.task_record(event.task_id).await;
+        let context = json!({
+            "event": event,
+            "task": record.as_ref().map(|r| &r.task),
+            "record": record,
+        });
+        match &webhook.template {
+            Some(template) => render_template(template, &context),
+            None => serde_json::to_string(&context).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub(crate) async fn run(mut self) {
+        loop {
+            let event = match self.events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "webhook dispatcher lagged; events not delivered");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            for webhook in self.webhooks.iter().filter(|w| w.fires_on(event.transition.to)) {
+                let body = match self.payload(webhook, &event).await {
+                    Ok(body) => body,
+                    Err(e) => {
+                        tracing::warn!(task_id = event.task_id, error = %e, "could not build webhook payload");
+                        continue;
+                    }
+                };
+                let transport = self.transport.clone();
+                let webhook = webhook.clone();
+                let task_id = event.task_id;
+                // Deliver off the event loop so a slow receiver doesn't stall other webhooks
+                tokio::spawn(async move {
+                    for attempt in 1..=webhook.max_attempts.max(1) {
+                        match transport.post(&webhook.url, body.clone()).await {
+                            Ok(()) => return,
+                            Err(e) => {
+                                tracing::warn!(task_id, attempt, error = %e, "webhook delivery failed");
+                                tokio::time::sleep(Duration::from_millis(200 * 2u64.pow(attempt - 1))).await;
+                            }
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::task::Task;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        posts: Mutex<Vec<(String, String)>>,
+    }
+
+    impl WebhookTransport for RecordingTransport {
+        fn post<'a>(&'a self, url: &'a str, body: String) -> BoxFuture<'a, Result<(), String>> {
+            self.posts.lock().unwrap().push((url.to_string(), body));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_render_template_escapes_strings() {
+        let context = json!({"task": {"id": 4, "task_type": "say \"hi\"", "required_capabilities": ["a"]}});
+        let rendered = render_template(
+            r#"{"order": {{task.id}}, "op": "{{task.task_type}}", "caps": {{task.required_capabilities}}, "missing": {{task.nope}}}"#,
+            &context,
+        )
+        .unwrap();
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["order"], 4);
+        assert_eq!(value["op"], "say \"hi\"");
+        assert_eq!(value["caps"][0], "a");
+        assert!(value["missing"].is_null());
+        assert!(render_template("{{task.id", &context).is_err());
+    }
+
+    #[test]
+    fn test_invalid_template_rejected() {
+        assert!(WebhookConfig::new("http://mes/hook").template(r#"{"id": {{task.id}}"#).validate().is_err());
+        assert!(WebhookConfig::new("http://mes/hook").template(r#"{"id": {{task.id}}}"#).validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_completion_webhook_delivered() {
+        let transport = Arc::new(RecordingTransport::default());
+        let (scheduler, workers) = Scheduler::builder()
+            .webhook(WebhookConfig::new("http://mes/hook").template(r#"{"order": {{task.id}}, "status": "{{event.transition.reason}}"}"#))
+            .webhook_transport(transport.clone())
+            .build()
+            .unwrap();
+        let mut events = scheduler.subscribe();
+        workers.spawn();
+
+        scheduler.schedule_task(Task { id: 41, task_type: "navigation".to_string(), ..Default::default() }).await.unwrap();
+        while let Ok(event) = events.recv().await {
+            if event.transition.to.is_terminal() {
+                break;
+            }
+        }
+        for _ in 0..50 {
+            if !transport.posts.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let posts = transport.posts.lock().unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].1, r#"{"order": 41, "status": "COMPLETED_OK"}"#);
+    }
+}
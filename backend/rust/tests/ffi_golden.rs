@@ -0,0 +1,254 @@
+// backend/rust/tests/ffi_golden.rs
+// Purpose: Golden-file conformance tests for the FFI JSON contract consumed by
+// backend/python/ai_engine/delegator.py. Each fixture in tests/golden/ffi/ lists calls
+// to the exported extern "C" functions with their exact expected responses, so renamed
+// fields or reworded error strings that would break Python callers fail here first. The
+// calls go through the cdylib cargo builds alongside the test, loaded with dlopen like
+// the delegator's ctypes loader does, and each function must also be declared in the
+// generated include/mrtodp_scheduler.h, so a symbol missing from either fails too. Unix
+// only. Fixtures share the library's global FFI scheduler and run sequentially in
+// file-name order, so each fixture uses its own robot and task IDs. Steps marked
+// `"eventually": true` are retried for a short while, for state that settles
+// asynchronously after a submission. Steps calling a *_with_status_ffi function may also
+// pin the status code with `"status"`; steps calling an mrtodp_* function pin their
+// MrtodpErrorCode the same way.
+
+#![cfg(all(feature = "ffi", unix))]
+
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use serde_json::Value;
+
+// Return type of the mrtodp_* functions, as declared in the header
+#[repr(C)]
+struct MrtodpResult {
+    code: i32,
+    payload: *mut c_char,
+}
+
+// The cdylib cargo built next to this test, loaded the way the Python delegator loads it,
+// and the generated header its callers compile against
+struct Library {
+    handle: *mut c_void,
+    header: String,
+}
+
+impl Library {
+    fn open() -> Self {
+        let exe = std::env::current_exe().expect("test binary path");
+        let path = exe.parent().unwrap().join(format!("{}mrtodp_scheduler{}", DLL_PREFIX, DLL_SUFFIX));
+        let c_path = CString::new(path.to_str().expect("UTF-8 library path")).unwrap();
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        if handle.is_null() {
+            let error = unsafe { CStr::from_ptr(libc::dlerror()) }.to_string_lossy();
+            panic!("could not load {}: {}", path.display(), error);
+        }
+        let header = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("include/mrtodp_scheduler.h"))
+            .expect("generated header exists");
+        Library { handle, header }
+    }
+
+    // Look up a function the header declares and the library exports. F must be the
+    // function pointer type matching the declaration.
+    unsafe fn symbol<F: Copy>(&self, name: &str) -> F {
+        let declared = [format!(" {}(", name), format!("*{}(", name)];
+        assert!(declared.iter().any(|d| self.header.contains(d.as_str())), "{} is not declared in include/mrtodp_scheduler.h", name);
+        let c_name = CString::new(name).unwrap();
+        let symbol = libc::dlsym(self.handle, c_name.as_ptr());
+        assert!(!symbol.is_null(), "{} is not exported by the cdylib", name);
+        std::mem::transmute_copy::<*mut c_void, F>(&symbol)
+    }
+
+    // Take ownership of a returned C string and release it through the library
+    fn take(&self, raw: *mut c_char) -> String {
+        assert!(!raw.is_null(), "FFI returned a null pointer");
+        let response = unsafe { CStr::from_ptr(raw) }.to_str().expect("FFI returned invalid UTF-8").to_string();
+        unsafe {
+            let free: unsafe extern "C" fn(*mut c_char) = self.symbol("free_string_ffi");
+            free(raw);
+        }
+        response
+    }
+
+    // Invoke a *_with_status_ffi function, returning its response and status code
+    fn call_with_status(&self, call: impl FnOnce(*mut i32) -> *mut c_char) -> (String, Option<i32>) {
+        let mut status = i32::MIN;
+        let response = self.take(call(&mut status));
+        assert_ne!(status, i32::MIN, "FFI did not write a status code");
+        (response, Some(status))
+    }
+
+    // Invoke an mrtodp_* function, returning its payload and error code
+    fn call_structured(&self, result: MrtodpResult) -> (String, Option<i32>) {
+        (self.take(result.payload), Some(result.code))
+    }
+}
+
+type StrFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type StrStrFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type IdFn = unsafe extern "C" fn(u32) -> *mut c_char;
+
+// Convert an optional JSON string argument into a C string (None = null pointer)
+fn c_arg(value: &Value) -> Option<CString> {
+    value.as_str().map(|s| CString::new(s).expect("fixture strings contain no NUL"))
+}
+
+fn ptr(arg: &Option<CString>) -> *const c_char {
+    arg.as_ref().map_or(std::ptr::null(), |s| s.as_ptr())
+}
+
+// Invoke one exported function by name with fixture arguments; the status code is None for
+// functions without a status out-parameter
+fn call(lib: &Library, name: &str, args: &[Value]) -> (String, Option<i32>) {
+    let task_id = || args[0].as_u64().expect("task ID") as u32;
+    let response = unsafe {
+        match name {
+            "schedule_task_with_status_ffi" => {
+                let f: unsafe extern "C" fn(*const c_char, *mut i32) -> *mut c_char = lib.symbol(name);
+                let task = c_arg(&args[0]);
+                return lib.call_with_status(|status| f(ptr(&task), status));
+            }
+            "get_task_status_with_status_ffi" => {
+                let f: unsafe extern "C" fn(u32, *mut i32) -> *mut c_char = lib.symbol(name);
+                return lib.call_with_status(|status| f(task_id(), status));
+            }
+            "submit_bid_with_status_ffi" => {
+                let f: unsafe extern "C" fn(u32, *const c_char, f64, u64, *mut i32) -> *mut c_char = lib.symbol(name);
+                let robot_id = c_arg(&args[1]);
+                let (cost, eta_ms) = (args[2].as_f64().expect("cost"), args[3].as_u64().expect("ETA"));
+                return lib.call_with_status(|status| f(task_id(), ptr(&robot_id), cost, eta_ms, status));
+            }
+            "mrtodp_schedule_task" => {
+                let f: unsafe extern "C" fn(*const c_char) -> MrtodpResult = lib.symbol(name);
+                let task = c_arg(&args[0]);
+                return lib.call_structured(f(ptr(&task)));
+            }
+            "mrtodp_register_robot" => {
+                let f: unsafe extern "C" fn(*const c_char, *const c_char) -> MrtodpResult = lib.symbol(name);
+                let (robot_id, capabilities) = (c_arg(&args[0]), c_arg(&args[1]));
+                return lib.call_structured(f(ptr(&robot_id), ptr(&capabilities)));
+            }
+            "mrtodp_get_task_status" => {
+                let f: unsafe extern "C" fn(u32) -> MrtodpResult = lib.symbol(name);
+                return lib.call_structured(f(task_id()));
+            }
+            "register_robot_ffi" => {
+                let f: StrStrFn = lib.symbol(name);
+                let (robot_id, capabilities) = (c_arg(&args[0]), c_arg(&args[1]));
+                lib.take(f(ptr(&robot_id), ptr(&capabilities)))
+            }
+            "schedule_task_ffi" | "get_buffered_status_ffi" => {
+                let f: StrFn = lib.symbol(name);
+                let arg = c_arg(&args[0]);
+                lib.take(f(ptr(&arg)))
+            }
+            "get_task_status_ffi" => {
+                let f: IdFn = lib.symbol(name);
+                lib.take(f(task_id()))
+            }
+            "init_tracing_ffi" => {
+                let f: unsafe extern "C" fn(*const c_char, bool) -> *mut c_char = lib.symbol(name);
+                let level = c_arg(&args[0]);
+                lib.take(f(ptr(&level), args[1].as_bool().expect("json_output flag")))
+            }
+            "set_robot_model_ffi" => {
+                let f: unsafe extern "C" fn(*const c_char, *const c_char, *const c_char) -> *mut c_char = lib.symbol(name);
+                let (robot_id, model, firmware) = (c_arg(&args[0]), c_arg(&args[1]), c_arg(&args[2]));
+                lib.take(f(ptr(&robot_id), ptr(&model), ptr(&firmware)))
+            }
+            "submit_bid_ffi" => {
+                let f: unsafe extern "C" fn(u32, *const c_char, f64, u64) -> *mut c_char = lib.symbol(name);
+                let robot_id = c_arg(&args[1]);
+                lib.take(f(task_id(), ptr(&robot_id), args[2].as_f64().expect("cost"), args[3].as_u64().expect("ETA")))
+            }
+            other => panic!("Fixture calls unknown FFI function {}", other),
+        }
+    };
+    (response, None)
+}
+
+// Replace wall-clock timestamps and measured phase durations with placeholders so JSON
+// responses compare stably; phases that didn't apply stay null
+fn mask_timestamps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if key == "at" && field.is_u64() {
+                    *field = Value::String("<timestamp>".to_string());
+                } else if key == "phases" && field.is_object() {
+                    for duration in field.as_object_mut().unwrap().values_mut().filter(|d| d.is_u64()) {
+                        *duration = Value::String("<duration>".to_string());
+                    }
+                } else {
+                    mask_timestamps(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_timestamps),
+        _ => {}
+    }
+}
+
+// Compare one response with the step's expectation (exact, prefix, or JSON, plus the status
+// code if the step names one)
+fn mismatch(step: &Value, response: &str, status: Option<i32>) -> Option<String> {
+    if let Some(expected) = step.get("status").and_then(Value::as_i64) {
+        if status.map(i64::from) != Some(expected) {
+            return Some(format!("status {:?} != {}", status, expected));
+        }
+    }
+    if let Some(expected) = step.get("response").and_then(Value::as_str) {
+        (response != expected).then(|| format!("{:?} != {:?}", response, expected))
+    } else if let Some(prefix) = step.get("response_prefix").and_then(Value::as_str) {
+        (!response.starts_with(prefix)).then(|| format!("{:?} does not start with {:?}", response, prefix))
+    } else if let Some(expected) = step.get("response_json") {
+        let mut actual: Value = match serde_json::from_str(response) {
+            Ok(actual) => actual,
+            Err(e) => return Some(format!("response {:?} is not JSON: {}", response, e)),
+        };
+        mask_timestamps(&mut actual);
+        (&actual != expected).then(|| format!("{} != {}", actual, expected))
+    } else {
+        Some("step has no expected response".to_string())
+    }
+}
+
+// Run one step, retrying `eventually` steps until they match or time out
+fn run_step(lib: &Library, fixture: &str, index: usize, step: &Value) {
+    let args = step["args"].as_array().cloned().unwrap_or_default();
+    let eventually = step.get("eventually").and_then(Value::as_bool).unwrap_or(false);
+    let deadline = Instant::now() + Duration::from_secs(2);
+    loop {
+        let (response, status) = call(lib, step["call"].as_str().expect("step names a call"), &args);
+        match mismatch(step, &response, status) {
+            None => return,
+            Some(_) if eventually && Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Some(problem) => panic!("{} step {} ({}): {}", fixture, index, step["call"], problem),
+        }
+    }
+}
+
+#[test]
+fn ffi_golden_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/ffi");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .expect("golden fixture directory exists")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no golden fixtures found in {}", dir.display());
+    let lib = Library::open();
+
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let fixture: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("{} is not valid JSON: {}", name, e));
+        for (index, step) in fixture["steps"].as_array().expect("fixture has steps").iter().enumerate() {
+            run_step(&lib, &name, index, step);
+        }
+    }
+}